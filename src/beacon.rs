@@ -0,0 +1,137 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use log::{debug, warn};
+use tokio::net::UdpSocket;
+
+use crate::dom::communication::SharedStateMutex;
+use crate::dom::DeviceId;
+
+/// Answer to a query for a device which is online.
+pub const ONLINE: u8 = b'1';
+/// Answer to a query for a device which is offline.
+pub const OFFLINE: u8 = b'0';
+/// Answer to a query for an unknown device.
+pub const UNKNOWN: u8 = b'?';
+
+const MAX_QUERY_SIZE: usize = 256;
+
+/// Tiny UDP responder answering "is <device> online?" queries (a datagram containing only the
+/// ID of the device) with a single byte so that microcontrollers can query the presence of a
+/// device without the overhead of TCP, HTTP and JSON.
+pub struct Beacon {
+    socket: UdpSocket,
+    shared_state: Arc<SharedStateMutex>,
+}
+
+impl Beacon {
+    pub async fn bind(
+        address: SocketAddr,
+        shared_state: Arc<SharedStateMutex>,
+    ) -> std::io::Result<Self> {
+        let socket = UdpSocket::bind(address).await?;
+        Ok(Self {
+            socket,
+            shared_state,
+        })
+    }
+
+    #[cfg(test)]
+    pub fn local_addr(&self) -> std::io::Result<SocketAddr> {
+        self.socket.local_addr()
+    }
+
+    pub async fn serve(&self) {
+        let mut query = [0; MAX_QUERY_SIZE];
+        loop {
+            let (size, peer) = match self.socket.recv_from(&mut query).await {
+                Ok(received) => received,
+                Err(e) => {
+                    warn!("failed to receive beacon query: {}", e);
+                    continue;
+                }
+            };
+
+            let answer = self.answer(&query[..size]);
+            if let Err(e) = self.socket.send_to(&[answer], peer).await {
+                warn!("failed to answer beacon query from {}: {}", peer, e);
+            }
+        }
+    }
+
+    fn answer(&self, query: &[u8]) -> u8 {
+        let device_id = match std::str::from_utf8(query) {
+            Ok(device_id) => device_id.trim(),
+            Err(_) => return UNKNOWN,
+        };
+        let device_id: DeviceId = match device_id.parse() {
+            Ok(device_id) => device_id,
+            Err(_) => return UNKNOWN,
+        };
+
+        let shared_state = self.shared_state.lock().unwrap();
+        let answer = match shared_state
+            .get_devices()
+            .iter()
+            .find(|device| *device.id() == device_id)
+        {
+            Some(device) if device.is_online() => ONLINE,
+            Some(_) => OFFLINE,
+            None => UNKNOWN,
+        };
+        debug!(
+            "answering beacon query for {} with {}",
+            device_id, answer as char
+        );
+
+        answer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use rstest::*;
+
+    use super::*;
+    use crate::dom::communication::SharedState;
+    use crate::dom::device::test::*;
+    use crate::dom::{Device, Machine, Server};
+
+    #[fixture]
+    fn shared_state(server: Server, mut machine: Machine) -> Arc<SharedStateMutex> {
+        machine.set_online(true);
+        Arc::new(Mutex::new(SharedState::new(vec![
+            Device::Server(server),
+            Device::Machine(machine),
+        ])))
+    }
+
+    #[rstest]
+    #[case(MACHINE_ID.as_bytes(), ONLINE)]
+    #[case(b"testmachine\n", ONLINE)]
+    #[case(SERVER_ID.as_bytes(), OFFLINE)]
+    #[case(b"unknowndevice", UNKNOWN)]
+    #[case(b"", UNKNOWN)]
+    #[case(&[0xff, 0xfe], UNKNOWN)]
+    #[tokio::test]
+    async fn test_beacon_answers_queries(
+        shared_state: Arc<SharedStateMutex>,
+        #[case] query: &[u8],
+        #[case] expected: u8,
+    ) {
+        let beacon = Beacon::bind("127.0.0.1:0".parse().unwrap(), shared_state)
+            .await
+            .unwrap();
+        let address = beacon.local_addr().unwrap();
+        tokio::spawn(async move { beacon.serve().await });
+
+        let client = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        client.send_to(query, address).await.unwrap();
+
+        let mut answer = [0; 2];
+        let size = client.recv(&mut answer).await.unwrap();
+        assert_eq!(&answer[..size], &[expected]);
+    }
+}
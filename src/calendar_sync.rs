@@ -0,0 +1,69 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use log::{debug, info, warn};
+
+use crate::configuration;
+use crate::dom::{parse_ical, Calendar};
+use crate::env::PKG_NAME;
+use crate::networking::agent_builder;
+
+/// Periodically downloads an iCal calendar and updates the events of the shared calendar
+/// evaluated by the schedules.
+pub struct CalendarSync {
+    agent: ureq::Agent,
+    name: String,
+    url: String,
+    interval: Duration,
+    calendar: Calendar,
+}
+
+impl CalendarSync {
+    pub fn new(
+        name: &str,
+        config: &configuration::Calendar,
+        calendar: Calendar,
+        proxy: Option<&str>,
+    ) -> Self {
+        Self {
+            agent: agent_builder(proxy).build(),
+            name: name.to_string(),
+            url: config.url.clone(),
+            interval: Duration::from_secs(config.interval),
+            calendar,
+        }
+    }
+
+    pub fn sync_once(&self) -> anyhow::Result<()> {
+        debug!("downloading calendar {} from {}", self.name, self.url);
+        let ical = self
+            .agent
+            .get(&self.url)
+            .set("User-Agent", PKG_NAME)
+            .call()
+            .map_err(anyhow::Error::from)?
+            .into_string()?;
+
+        let events = parse_ical(&ical)?;
+        info!("calendar {} contains {} events", self.name, events.len());
+        self.calendar.set_events(events);
+
+        Ok(())
+    }
+
+    /// Keeps the calendar up to date. The previous events are kept if a download fails.
+    pub async fn sync(self) {
+        let sync = Arc::new(self);
+        let mut interval = tokio::time::interval(sync.interval);
+        loop {
+            interval.tick().await;
+
+            let calendar_sync = sync.clone();
+            match tokio::task::spawn_blocking(move || calendar_sync.sync_once()).await {
+                Ok(Ok(())) => {}
+                Ok(Err(e)) => warn!("failed to update calendar {}: {}", sync.name, e),
+                Err(e) => warn!("failed to update calendar {}: {}", sync.name, e),
+            }
+        }
+    }
+}
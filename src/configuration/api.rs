@@ -1,14 +1,21 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use super::{Files, Web};
+use super::{Beacon, Dns, Files, WakeOnLan, Web};
 
 #[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Api {
-    pub files: Files,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub files: Option<Files>,
     #[serde(default)]
     pub web: Web,
+    #[serde(default)]
+    pub beacon: Beacon,
+    #[serde(default)]
+    pub dns: Dns,
+    #[serde(default)]
+    pub wake_on_lan: WakeOnLan,
 }
 
 impl Api {
@@ -0,0 +1,28 @@
+use std::net::IpAddr;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Beacon {
+    #[serde(default = "Beacon::default_ip")]
+    pub ip: IpAddr,
+    #[serde(default)]
+    pub port: u16,
+}
+
+impl Beacon {
+    pub fn default_ip() -> IpAddr {
+        "0.0.0.0".parse().unwrap()
+    }
+}
+
+impl Default for Beacon {
+    fn default() -> Self {
+        Self {
+            ip: Beacon::default_ip(),
+            port: 0,
+        }
+    }
+}
@@ -0,0 +1,56 @@
+use std::collections::HashMap;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::{duration, Machine};
+
+#[derive(
+    Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize, JsonSchema,
+)]
+#[serde(rename_all = "camelCase")]
+pub enum DeviceClass {
+    Phone,
+    Laptop,
+    Iot,
+    Infrastructure,
+}
+
+/// Defaults for all devices of a class which can be overridden by every device.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Class {
+    /// TCP port which is checked instead of pinging the devices.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
+    /// Number of seconds between two checks of the devices.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "duration::deserialize_option"
+    )]
+    #[schemars(with = "Option<duration::Seconds>")]
+    pub interval: Option<u64>,
+    /// Number of seconds without a response after which the devices are considered offline.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "duration::deserialize_option"
+    )]
+    #[schemars(with = "Option<duration::Seconds>")]
+    pub timeout: Option<u64>,
+}
+
+pub type Classes = HashMap<DeviceClass, Class>;
+
+/// Fills the properties the machine doesn't configure itself with the defaults of its class.
+pub fn apply_class(machine: &mut Machine, classes: &Classes) {
+    let class = match machine.class.and_then(|class| classes.get(&class)) {
+        Some(class) => class,
+        None => return,
+    };
+
+    machine.port = machine.port.or(class.port);
+    machine.interval = machine.interval.or(class.interval);
+    machine.last_seen_timeout = machine.last_seen_timeout.or(class.timeout);
+}
@@ -4,11 +4,13 @@ use std::fmt;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use super::DeviceId;
+use super::{serialize_sorted, DeviceId};
 
 #[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize, JsonSchema)]
 #[serde(transparent)]
-pub struct Dependencies(pub HashMap<DeviceId, Vec<DeviceId>>);
+pub struct Dependencies(
+    #[serde(serialize_with = "serialize_sorted")] pub HashMap<DeviceId, Vec<DeviceId>>,
+);
 
 #[derive(Debug, Clone)]
 pub struct DependencyError(String);
@@ -5,7 +5,8 @@ use std::str::FromStr;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use super::super::utils::MacAddr;
+use super::super::utils::{MacAddr, Secret, REDACTED};
+use super::{duration, DeviceClass};
 
 #[derive(
     Clone, Debug, Default, Hash, Eq, PartialEq, Ord, PartialOrd, Deserialize, Serialize, JsonSchema,
@@ -26,6 +27,34 @@ impl fmt::Display for DeviceId {
     }
 }
 
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct HttpCheck {
+    pub url: String,
+    #[serde(default = "HttpCheck::default_expected_status")]
+    pub expected_status: u16,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected_body: Option<String>,
+    #[serde(
+        default = "HttpCheck::default_timeout",
+        deserialize_with = "duration::deserialize"
+    )]
+    #[schemars(with = "duration::Seconds")]
+    pub timeout: u64,
+    #[serde(default)]
+    pub insecure: bool,
+}
+
+impl HttpCheck {
+    pub fn default_expected_status() -> u16 {
+        200
+    }
+
+    pub fn default_timeout() -> u64 {
+        5
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Machine {
@@ -34,8 +63,53 @@ pub struct Machine {
     pub name: String,
     pub ip: IpAddr,
 
-    #[serde(rename = "timeout")]
-    pub last_seen_timeout: u64,
+    /// Defaults to the timeout of the class of the machine.
+    #[serde(
+        rename = "timeout",
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "duration::deserialize_option"
+    )]
+    #[schemars(with = "Option<duration::Seconds>")]
+    pub last_seen_timeout: Option<u64>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub room: Option<DeviceId>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub class: Option<DeviceClass>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub http: Option<HttpCheck>,
+
+    /// TCP port which is checked instead of pinging the machine.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
+
+    /// Number of seconds between two checks of the machine (defaults to the ping interval).
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "duration::deserialize_option"
+    )]
+    #[schemars(with = "Option<duration::Seconds>")]
+    pub interval: Option<u64>,
+
+    /// Schedules (e.g. a weekly reboot) during which the machine being offline is ignored.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ignore_windows: Vec<DeviceId>,
+
+    /// Local IP address from which the machine is pinged / its TCP ports are checked (e.g. on
+    /// hosts with multiple network interfaces and policy routing).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<IpAddr>,
+
+    /// MAC address of the machine which its ARP entry is checked against (servers use their
+    /// `mac` instead).
+    // not part of the schema because it would replace the mandatory `mac` of servers
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(skip)]
+    pub mac: Option<MacAddr>,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
@@ -53,24 +127,46 @@ impl From<SshPort> for u16 {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[derive(Clone, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct SshPrivateKeyAuthentication {
     pub file: String,
     #[serde(default)]
-    pub passphrase: String,
+    pub passphrase: Secret,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+// credentials must never show up in (verbose) logs
+impl fmt::Debug for SshPrivateKeyAuthentication {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SshPrivateKeyAuthentication")
+            .field("file", &self.file)
+            .field("passphrase", &REDACTED)
+            .finish()
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub enum SshAuthentication {
-    Password(String),
+    Password(Secret),
     PrivateKey(SshPrivateKeyAuthentication),
 }
 
+impl fmt::Debug for SshAuthentication {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Password(_) => f.debug_tuple("Password").field(&REDACTED).finish(),
+            Self::PrivateKey(pk) => f.debug_tuple("PrivateKey").field(pk).finish(),
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Ssh {
+    /// DNS name to connect to instead of the IP address.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub host: Option<String>,
     #[serde(default)]
     pub port: SshPort,
 
@@ -79,6 +175,183 @@ pub struct Ssh {
     pub authentication: SshAuthentication,
 }
 
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiRelay {
+    pub url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub server: Option<DeviceId>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token: Option<Secret>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SshRelay {
+    pub ip: IpAddr,
+    #[serde(flatten)]
+    pub ssh: Ssh,
+    #[serde(default = "SshRelay::default_command")]
+    pub command: String,
+}
+
+impl SshRelay {
+    pub fn default_command() -> String {
+        "wakeonlan {mac}".to_string()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum WakeupRelay {
+    Api(ApiRelay),
+    Ssh(Box<SshRelay>),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(untagged)]
+pub enum ServiceCheck {
+    Tcp { port: u16 },
+    Http { http: HttpCheck },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Service {
+    pub name: String,
+    #[serde(flatten)]
+    pub check: ServiceCheck,
+}
+
+/// Traffic counters of a switch port queried through SNMPv2c.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SnmpCheck {
+    /// Host name or IP address of the switch.
+    pub host: String,
+    #[serde(default = "SnmpCheck::default_port")]
+    pub port: u16,
+    #[serde(default = "SnmpCheck::default_community")]
+    pub community: Secret,
+    /// Index of the switch port in the interface table (`ifIndex`).
+    pub interface: u32,
+    /// Number of bytes per second (in both directions) from which on the port is in use.
+    pub threshold: u64,
+    #[serde(
+        default = "SnmpCheck::default_timeout",
+        deserialize_with = "duration::deserialize"
+    )]
+    #[schemars(with = "duration::Seconds")]
+    pub timeout: u64,
+}
+
+impl SnmpCheck {
+    pub fn default_port() -> u16 {
+        161
+    }
+
+    pub fn default_community() -> Secret {
+        Secret::from("public")
+    }
+
+    pub fn default_timeout() -> u64 {
+        5
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum FileSharingProtocol {
+    Smb,
+    Nfs,
+}
+
+/// Active file sharing sessions on the server counted through SSH.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionsCheck {
+    pub protocol: FileSharingProtocol,
+    /// Number of sessions from which on the server is busy.
+    #[serde(default = "SessionsCheck::default_threshold")]
+    pub threshold: u32,
+    /// Command printing the number of sessions instead of counting the established connections
+    /// to the port of the protocol.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+}
+
+impl SessionsCheck {
+    pub fn default_threshold() -> u32 {
+        1
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(untagged)]
+pub enum ExternalCheck {
+    Http { http: HttpCheck },
+    Snmp { snmp: SnmpCheck },
+    Sessions { sessions: SessionsCheck },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ExternalDependency {
+    pub name: String,
+    #[serde(flatten)]
+    pub check: ExternalCheck,
+    /// Number of seconds the result of the check is cached.
+    #[serde(
+        default = "ExternalDependency::default_cache",
+        deserialize_with = "duration::deserialize"
+    )]
+    #[schemars(with = "duration::Seconds")]
+    pub cache: u64,
+}
+
+impl ExternalDependency {
+    pub fn default_cache() -> u64 {
+        60
+    }
+}
+
+/// Operating system of a server which determines how it is shut down.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum ServerOs {
+    LinuxSystemd,
+    LinuxSysv,
+    Freebsd,
+    WindowsSsh,
+    Macos,
+}
+
+/// Number of seconds a wakeup or shutdown of a server may take before it is aborted.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionTimeouts {
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "duration::deserialize_option"
+    )]
+    #[schemars(with = "Option<duration::Seconds>")]
+    pub wakeup: Option<u64>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "duration::deserialize_option"
+    )]
+    #[schemars(with = "Option<duration::Seconds>")]
+    pub shutdown: Option<u64>,
+}
+
+impl ActionTimeouts {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Server {
@@ -87,10 +360,91 @@ pub struct Server {
 
     pub mac: MacAddr,
     pub ssh: Ssh,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub relay: Option<WakeupRelay>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub services: Vec<Service>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub external_dependencies: Vec<ExternalDependency>,
+
+    /// DNS names which wake up the server when they are resolved through the DNS trigger.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hostnames: Vec<String>,
+
+    /// Operating system of the server selecting the command used to shut it down.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub os: Option<ServerOs>,
+
+    /// Number of seconds within which a shutdown requested through the web API has to be confirmed.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "duration::deserialize_option"
+    )]
+    #[schemars(with = "Option<duration::Seconds>")]
+    pub shutdown_confirmation: Option<u64>,
+
+    #[serde(default, skip_serializing_if = "ActionTimeouts::is_default")]
+    pub action_timeouts: ActionTimeouts,
+
+    /// Number of seconds within which the server has to stop answering pings after it has been
+    /// shut down for the shutdown to be considered successful.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "duration::deserialize_option"
+    )]
+    #[schemars(with = "Option<duration::Seconds>")]
+    pub shutdown_verification: Option<u64>,
+
+    /// Number of consecutive failed shutdowns after which the server is no longer woken up or
+    /// shut down automatically until its control is re-enabled through the web API.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_failed_shutdowns: Option<u32>,
+
+    /// Number of seconds per day after which the server is no longer woken up automatically.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "duration::deserialize_option"
+    )]
+    #[schemars(with = "Option<duration::Seconds>")]
+    pub max_daily_on_time: Option<u64>,
+
+    /// Minimum number of seconds the server stays online before it is shut down automatically.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "duration::deserialize_option"
+    )]
+    #[schemars(with = "Option<duration::Seconds>")]
+    pub min_on_duration: Option<u64>,
+
+    /// Minimum number of seconds the server stays offline before it is woken up automatically.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "duration::deserialize_option"
+    )]
+    #[schemars(with = "Option<duration::Seconds>")]
+    pub min_off_duration: Option<u64>,
+
+    /// Schedules (e.g. calendars) during which the server is never shut down automatically.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub blackouts: Vec<DeviceId>,
+
+    /// Keeps the server online while the away mode is active (all other servers are shut down).
+    #[serde(default)]
+    pub keep_on_when_away: bool,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
 #[serde(untagged)]
+// servers are inherently a lot larger than machines
+#[allow(clippy::large_enum_variant)]
 pub enum Device {
     Server(Server),
     Machine(Machine),
@@ -0,0 +1,36 @@
+use std::net::IpAddr;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Dns {
+    #[serde(default = "Dns::default_ip")]
+    pub ip: IpAddr,
+    #[serde(default)]
+    pub port: u16,
+    /// Number of seconds clients may cache the answers.
+    #[serde(default = "Dns::default_ttl")]
+    pub ttl: u32,
+}
+
+impl Dns {
+    pub fn default_ip() -> IpAddr {
+        "0.0.0.0".parse().unwrap()
+    }
+
+    pub fn default_ttl() -> u32 {
+        60
+    }
+}
+
+impl Default for Dns {
+    fn default() -> Self {
+        Self {
+            ip: Dns::default_ip(),
+            port: 0,
+            ttl: Dns::default_ttl(),
+        }
+    }
+}
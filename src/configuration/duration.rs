@@ -0,0 +1,130 @@
+use schemars::JsonSchema;
+use serde::{de, Deserialize, Deserializer};
+
+/// A duration in the configuration, either a plain number of seconds (e.g. `300`) or a
+/// human-readable string (e.g. `"90s"`, `"5m"`, `"2h"` or `"1h 30m"`).
+#[derive(Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum Seconds {
+    Number(u64),
+    Text(String),
+}
+
+impl Seconds {
+    fn into_seconds<E: de::Error>(self) -> Result<u64, E> {
+        match self {
+            Self::Number(seconds) => Ok(seconds),
+            Self::Text(text) => parse(&text).map_err(E::custom),
+        }
+    }
+}
+
+/// Number of seconds of the supported (English and German) units.
+fn unit_seconds(unit: &str) -> Option<u64> {
+    match unit {
+        "" | "s" | "sec" | "secs" | "second" | "seconds" | "sek" | "sekunde" | "sekunden" => {
+            Some(1)
+        }
+        "m" | "min" | "mins" | "minute" | "minutes" | "minuten" => Some(60),
+        "h" | "hr" | "hrs" | "hour" | "hours" | "std" | "stunde" | "stunden" => Some(60 * 60),
+        "d" | "day" | "days" | "tag" | "tage" => Some(24 * 60 * 60),
+        _ => None,
+    }
+}
+
+/// Parses a human-readable duration consisting of one or more numbers each followed by an
+/// optional unit (seconds if missing) into a number of seconds.
+pub fn parse(duration: &str) -> Result<u64, String> {
+    let invalid = || format!("invalid duration \"{duration}\" (e.g. \"90s\", \"5m\" or \"2h\")");
+
+    let mut chars = duration.trim().chars().peekable();
+    if chars.peek().is_none() {
+        return Err(invalid());
+    }
+
+    let mut seconds: u64 = 0;
+    while chars.peek().is_some() {
+        let mut number = String::new();
+        while let Some(c) = chars.next_if(char::is_ascii_digit) {
+            number.push(c);
+        }
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        let mut unit = String::new();
+        while let Some(c) = chars.next_if(|c| c.is_alphabetic()) {
+            unit.extend(c.to_lowercase());
+        }
+        while chars.next_if(|c| c.is_whitespace() || *c == ',').is_some() {}
+
+        let number: u64 = number.parse().map_err(|_| invalid())?;
+        let unit = unit_seconds(&unit).ok_or_else(invalid)?;
+        seconds = number
+            .checked_mul(unit)
+            .and_then(|component| seconds.checked_add(component))
+            .ok_or_else(invalid)?;
+    }
+
+    Ok(seconds)
+}
+
+pub fn deserialize<'de, D>(deserializer: D) -> Result<u64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Seconds::deserialize(deserializer)?.into_seconds()
+}
+
+pub fn deserialize_option<'de, D>(deserializer: D) -> Result<Option<u64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Option::<Seconds>::deserialize(deserializer)?
+        .map(Seconds::into_seconds)
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::*;
+
+    use super::*;
+
+    #[rstest]
+    #[case("300", Some(300))]
+    #[case("90s", Some(90))]
+    #[case("5m", Some(5 * 60))]
+    #[case("2h", Some(2 * 60 * 60))]
+    #[case("1d", Some(24 * 60 * 60))]
+    #[case("1h30m", Some(90 * 60))]
+    #[case("1 hour, 30 minutes", Some(90 * 60))]
+    #[case(" 2 Stunden 15 Sekunden ", Some(2 * 60 * 60 + 15))]
+    #[case("", None)]
+    #[case("h", None)]
+    #[case("5 fortnights", None)]
+    #[case("-5s", None)]
+    #[case("99999999999999999999d", None)]
+    fn test_parse_duration(#[case] duration: &str, #[case] expected: Option<u64>) {
+        assert_eq!(parse(duration).ok(), expected);
+    }
+
+    #[rstest]
+    fn test_deserialize_duration_from_number_or_string() {
+        #[derive(Deserialize)]
+        struct Durations {
+            #[serde(deserialize_with = "deserialize")]
+            required: u64,
+            #[serde(default, deserialize_with = "deserialize_option")]
+            optional: Option<u64>,
+        }
+
+        let durations: Durations =
+            serde_json::from_str(r#"{ "required": 300, "optional": "5m" }"#).unwrap();
+        assert_eq!(durations.required, 300);
+        assert_eq!(durations.optional, Some(300));
+
+        let durations: Durations = serde_json::from_str(r#"{ "required": "2h" }"#).unwrap();
+        assert_eq!(durations.required, 2 * 60 * 60);
+        assert_eq!(durations.optional, None);
+
+        assert!(serde_json::from_str::<Durations>(r#"{ "required": "soon" }"#).is_err());
+    }
+}
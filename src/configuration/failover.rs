@@ -0,0 +1,42 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::utils::Secret;
+
+use super::duration;
+
+/// Active instance of `home-monitor-rs` which this (standby) instance takes over from once it
+/// stops heartbeating.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Failover {
+    /// URL of the web API of the active instance (e.g. `http://192.168.1.2:8000`).
+    pub peer: String,
+    /// Bearer token checked by an optional reverse proxy in front of the active instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token: Option<Secret>,
+    /// Number of seconds between two heartbeats of the active instance.
+    #[serde(
+        default = "Failover::default_interval",
+        deserialize_with = "duration::deserialize"
+    )]
+    #[schemars(with = "duration::Seconds")]
+    pub interval: u64,
+    /// Number of seconds without a heartbeat after which the standby takes over.
+    #[serde(
+        default = "Failover::default_timeout",
+        deserialize_with = "duration::deserialize"
+    )]
+    #[schemars(with = "duration::Seconds")]
+    pub timeout: u64,
+}
+
+impl Failover {
+    pub fn default_interval() -> u64 {
+        5
+    }
+
+    pub fn default_timeout() -> u64 {
+        30
+    }
+}
@@ -0,0 +1,41 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::duration;
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Fingerprinting {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(
+        default = "Fingerprinting::default_interval",
+        deserialize_with = "duration::deserialize"
+    )]
+    #[schemars(with = "duration::Seconds")]
+    pub interval: u64,
+    #[serde(default = "Fingerprinting::default_ports")]
+    pub ports: Vec<u16>,
+}
+
+impl Fingerprinting {
+    pub fn default_interval() -> u64 {
+        60 * 60
+    }
+
+    pub fn default_ports() -> Vec<u16> {
+        vec![
+            22, 80, 135, 443, 445, 548, 631, 3389, 5000, 8009, 8123, 9100, 32400, 62078,
+        ]
+    }
+}
+
+impl Default for Fingerprinting {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval: Fingerprinting::default_interval(),
+            ports: Fingerprinting::default_ports(),
+        }
+    }
+}
@@ -0,0 +1,18 @@
+use std::collections::HashMap;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::DeviceId;
+
+// NOTE: groups share their IDs with devices so that their files don't collide with the ones of servers
+pub type GroupId = DeviceId;
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Group {
+    pub name: String,
+    pub servers: Vec<DeviceId>,
+}
+
+pub type Groups = HashMap<GroupId, Group>;
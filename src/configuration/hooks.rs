@@ -0,0 +1,46 @@
+use std::fmt;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Point in time of a wakeup or shutdown at which a hook is run.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum HookEvent {
+    PreWakeup,
+    PostWakeup,
+    PreShutdown,
+    PostShutdown,
+}
+
+impl fmt::Display for HookEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::PreWakeup => "preWakeup",
+            Self::PostWakeup => "postWakeup",
+            Self::PreShutdown => "preShutdown",
+            Self::PostShutdown => "postShutdown",
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(untagged)]
+pub enum HookTarget {
+    /// Program (and its arguments) run with the context of the action in its environment.
+    Command { command: Vec<String> },
+    /// URL the context of the action is posted to as JSON.
+    Webhook { url: String },
+}
+
+/// Command or webhook run before / after the wakeups and shutdowns of all servers.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Hook {
+    pub events: Vec<HookEvent>,
+    #[serde(flatten)]
+    pub target: HookTarget,
+    /// Fails the action if the hook fails instead of only reporting the failure.
+    #[serde(default)]
+    pub fatal: bool,
+}
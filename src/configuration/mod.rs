@@ -3,24 +3,80 @@ use std::fs::File;
 use std::io::BufReader;
 use std::path::Path;
 
+use chrono::NaiveTime;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 mod api;
+mod beacon;
+mod class;
 mod dependencies;
 mod device;
+mod dns;
+mod duration;
+mod failover;
 mod files;
+mod fingerprinting;
+mod group;
+mod hooks;
 mod network;
+mod notifications;
+mod person;
+mod presence;
+mod profile;
+mod proxy;
+mod recovery;
+mod room;
+mod schedule;
+mod secrets;
+mod sorted;
+mod store;
+mod updates;
+mod wake_on_lan;
 mod web;
 
 pub use api::Api;
+pub use beacon::Beacon;
+pub use class::{apply_class, Classes, DeviceClass};
 pub use dependencies::{Dependencies, DependencyError};
 pub use device::{
-    Device, DeviceId, Machine, Server, Ssh, SshAuthentication, SshPort, SshPrivateKeyAuthentication,
+    ActionTimeouts, ApiRelay, Device, DeviceId, ExternalCheck, ExternalDependency,
+    FileSharingProtocol, HttpCheck, Machine, Server, ServerOs, Service, ServiceCheck,
+    SessionsCheck, SnmpCheck, Ssh, SshAuthentication, SshPort, SshPrivateKeyAuthentication,
+    SshRelay, WakeupRelay,
 };
+pub use dns::Dns;
+pub use failover::Failover;
 pub use files::Files;
-pub use network::{Network, Ping};
-pub use web::Web;
+pub use fingerprinting::Fingerprinting;
+pub use group::{Group, GroupId, Groups};
+pub use hooks::{Hook, HookEvent, HookTarget};
+pub use network::{Limits, Network, Ping, PingMethod};
+pub use notifications::{
+    Locale, NotificationEvent, NotificationRetry, NotificationTemplate, Notifications, Webhook,
+};
+pub use person::Persons;
+#[cfg(test)]
+pub use person::{Person, PersonId};
+pub use presence::{PresenceProvider, Router};
+#[cfg(test)]
+pub use profile::Profile;
+pub use profile::Profiles;
+pub use recovery::Recovery;
+#[cfg(test)]
+pub use room::Room;
+pub use room::{RoomId, Rooms};
+pub use schedule::{
+    Calendar, Schedules, Weekday, CALENDAR_PREFIX, SUN_DAYLIGHT, SUN_NIGHT, TIME_PREFIX,
+};
+#[cfg(test)]
+pub use schedule::{Location, TimeWindow};
+pub use secrets::Secrets;
+pub use sorted::serialize_sorted;
+pub use store::Store;
+pub use updates::Updates;
+pub use wake_on_lan::WakeOnLan;
+pub use web::{BasicAuth, Docs, RequestLog, Web};
 
 pub const LOCATION: &str = "/etc/home-monitor-rs/home-monitor-rs.json";
 
@@ -31,8 +87,71 @@ pub type DeviceMap = HashMap<DeviceId, Device>;
 pub struct Configuration {
     pub api: api::Api,
     pub network: network::Network,
+    #[serde(serialize_with = "serialize_sorted")]
     pub devices: DeviceMap,
+    #[serde(default, serialize_with = "serialize_sorted")]
+    pub rooms: Rooms,
+    #[serde(default, serialize_with = "serialize_sorted")]
+    pub persons: Persons,
+    #[serde(default, serialize_with = "serialize_sorted")]
+    pub groups: Groups,
+    #[serde(
+        default,
+        skip_serializing_if = "HashMap::is_empty",
+        serialize_with = "serialize_sorted"
+    )]
+    pub classes: Classes,
+    #[serde(default, skip_serializing_if = "Schedules::is_empty")]
+    pub schedules: Schedules,
     pub dependencies: Dependencies,
+    /// Named sets of dependencies replacing the configured ones while they are active.
+    #[serde(
+        default,
+        skip_serializing_if = "HashMap::is_empty",
+        serialize_with = "serialize_sorted"
+    )]
+    pub profiles: Profiles,
+    /// Profile which is active on startup (defaults to the configured dependencies).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+    /// Whether the away mode (keeping only servers with `keepOnWhenAway` online) is active on
+    /// startup.
+    #[serde(default)]
+    pub away: bool,
+    /// Proxy through which all outbound connections (e.g. webhooks, the update check and the
+    /// router APIs) are made.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "proxy::deserialize_option"
+    )]
+    pub proxy: Option<String>,
+    #[serde(default)]
+    pub notifications: Notifications,
+    /// Commands / webhooks run before and after every wakeup and shutdown.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hooks: Vec<Hook>,
+    #[serde(default)]
+    pub updates: Updates,
+    #[serde(default)]
+    pub fingerprinting: Fingerprinting,
+    /// Routers whose clients are considered online (in addition to pinging them).
+    #[serde(
+        default,
+        skip_serializing_if = "HashMap::is_empty",
+        serialize_with = "serialize_sorted"
+    )]
+    pub presence: HashMap<String, PresenceProvider>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub store: Option<Store>,
+    /// Active instance which this instance is the standby of.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failover: Option<Failover>,
+    /// Policy applied after a probable power outage.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recovery: Option<Recovery>,
+    #[serde(default)]
+    pub secrets: Secrets,
 }
 
 #[allow(dead_code)]
@@ -44,7 +163,7 @@ pub fn parse_from_file<P: AsRef<Path>>(path: P) -> anyhow::Result<Configuration>
     // Read the JSON contents of the file as an instance of `Configuration`.
     let mut config: Configuration = serde_json::from_reader(reader)?;
 
-    check_dependencies(&config.devices, &config.dependencies)?;
+    check(&config)?;
     fill_ids(&mut config.devices);
 
     // Return the `Configuration`.
@@ -56,12 +175,190 @@ pub fn parse_from_str(s: &str) -> serde_json::Result<Configuration> {
     // Read the JSON contents of the string as an instance of `Configuration`.
     let mut config: Configuration = serde_json::from_str(s)?;
 
-    check_dependencies(&config.devices, &config.dependencies).unwrap();
+    check(&config).unwrap();
     fill_ids(&mut config.devices);
 
     Ok(config)
 }
 
+/// Makes sure the devices, rooms, persons, groups, schedules and dependencies are consistent.
+pub fn check(config: &Configuration) -> Result<(), DependencyError> {
+    check_rooms(&config.devices, &config.rooms)?;
+    check_persons(&config.devices, &config.rooms, &config.persons)?;
+    check_groups(
+        &config.devices,
+        &config.rooms,
+        &config.persons,
+        &config.groups,
+    )?;
+    check_schedules(
+        &config.devices,
+        &config.rooms,
+        &config.persons,
+        &config.groups,
+        &config.schedules,
+    )?;
+    check_dependencies(
+        &config.devices,
+        &config.rooms,
+        &config.persons,
+        &config.schedules,
+        &config.dependencies,
+    )?;
+    check_profiles(
+        &config.devices,
+        &config.rooms,
+        &config.persons,
+        &config.schedules,
+        &config.profiles,
+        &config.profile,
+    )?;
+    check_classes(&config.devices, &config.classes)?;
+    check_recovery(&config.devices, &config.recovery)
+}
+
+/// Describes suspicious but valid parts of the configuration which most likely don't behave as
+/// intended.
+pub fn lint(config: &Configuration) -> Vec<String> {
+    let mut warnings = Vec::new();
+    warnings.extend(lint_timeouts(
+        &config.devices,
+        &config.classes,
+        &config.network.ping,
+    ));
+    warnings.extend(lint_names(&config.devices));
+    let dependencies = resolve_dependencies(
+        &config.devices,
+        &config.rooms,
+        &config.persons,
+        &config.dependencies,
+    );
+    warnings.extend(lint_dependencies(&config.devices, &dependencies));
+    // devices only used by profiles aren't unused
+    let mut all_dependencies = dependencies;
+    for profile in config.profiles.values() {
+        let profile_dependencies = resolve_dependencies(
+            &config.devices,
+            &config.rooms,
+            &config.persons,
+            &profile.dependencies,
+        );
+        for (server_id, deps) in profile_dependencies.0 {
+            all_dependencies
+                .0
+                .entry(server_id)
+                .or_default()
+                .extend(deps);
+        }
+    }
+    warnings.extend(lint_unused_devices(&config.devices, &all_dependencies));
+    warnings.extend(
+        config
+            .network
+            .limits
+            .check(config.devices.len(), get_servers(&config.devices).len()),
+    );
+
+    warnings
+}
+
+fn lint_timeouts(devices: &DeviceMap, classes: &Classes, ping: &Ping) -> Vec<String> {
+    let mut warnings: Vec<String> = devices
+        .iter()
+        .filter_map(|(device_id, device)| {
+            let mut machine = match device {
+                Device::Server(server) => server.machine.clone(),
+                Device::Machine(machine) => machine.clone(),
+            };
+            apply_class(&mut machine, classes);
+
+            // the device would be considered offline between two checks
+            let interval = machine.interval.unwrap_or(ping.interval);
+            match machine.last_seen_timeout {
+                Some(timeout) if timeout < interval => Some(format!(
+                    "{device_id} has a timeout of {timeout}s which is shorter than its check interval of {interval}s"
+                )),
+                _ => None,
+            }
+        })
+        .collect();
+    warnings.sort();
+
+    warnings
+}
+
+fn lint_names(devices: &DeviceMap) -> Vec<String> {
+    let mut device_names: HashMap<&str, Vec<&DeviceId>> = HashMap::new();
+    for (device_id, device) in devices.iter() {
+        let name = match device {
+            Device::Server(server) => &server.machine.name,
+            Device::Machine(machine) => &machine.name,
+        };
+        device_names.entry(name).or_default().push(device_id);
+    }
+
+    let mut warnings: Vec<String> = device_names
+        .into_iter()
+        .filter(|(_, device_ids)| device_ids.len() > 1)
+        .map(|(name, mut device_ids)| {
+            device_ids.sort();
+            format!(
+                "{} are all named \"{name}\"",
+                device_ids
+                    .iter()
+                    .map(|device_id| device_id.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            )
+        })
+        .collect();
+    warnings.sort();
+
+    warnings
+}
+
+fn lint_dependencies(devices: &DeviceMap, dependencies: &Dependencies) -> Vec<String> {
+    let mut warnings: Vec<String> = dependencies
+        .0
+        .iter()
+        .filter_map(|(server_id, deps)| {
+            // schedules aren't devices and don't tell anything about the dependencies
+            let mut device_deps = deps
+                .iter()
+                .filter_map(|device_id| devices.get(device_id))
+                .peekable();
+            if device_deps.peek().is_some()
+                && device_deps.all(|device| matches!(device, Device::Server(_)))
+            {
+                Some(format!(
+                    "{server_id} only depends on other servers and is never woken up because of a machine"
+                ))
+            } else {
+                None
+            }
+        })
+        .collect();
+    warnings.sort();
+
+    warnings
+}
+
+fn lint_unused_devices(devices: &DeviceMap, dependencies: &Dependencies) -> Vec<String> {
+    let mut warnings: Vec<String> = get_machines(devices)
+        .into_keys()
+        .filter(|machine_id| {
+            !dependencies
+                .0
+                .values()
+                .any(|deps| deps.contains(machine_id))
+        })
+        .map(|machine_id| format!("{machine_id} is not a dependency of any server"))
+        .collect();
+    warnings.sort();
+
+    warnings
+}
+
 pub fn fill_ids(devices: &mut DeviceMap) {
     for device in devices.iter_mut() {
         let device_id = device.0.clone();
@@ -92,8 +389,166 @@ pub fn get_machines(devices: &DeviceMap) -> HashMap<DeviceId, Machine> {
         .collect()
 }
 
+pub fn get_room_devices(devices: &DeviceMap, room_id: &RoomId) -> Vec<DeviceId> {
+    let mut room_devices: Vec<DeviceId> = devices
+        .iter()
+        .filter_map(|(device_id, device)| {
+            let machine = match device {
+                Device::Server(server) => &server.machine,
+                Device::Machine(machine) => machine,
+            };
+            match &machine.room {
+                Some(room) if room == room_id => Some(device_id.clone()),
+                _ => None,
+            }
+        })
+        .collect();
+    room_devices.sort();
+
+    room_devices
+}
+
+pub fn resolve_dependencies(
+    devices: &DeviceMap,
+    rooms: &Rooms,
+    persons: &Persons,
+    dependencies: &Dependencies,
+) -> Dependencies {
+    Dependencies(
+        dependencies
+            .0
+            .iter()
+            .map(|(server_id, deps)| {
+                let mut resolved_deps: Vec<DeviceId> = Vec::new();
+                for device_id in deps.iter() {
+                    // replace rooms and persons by their devices (except for the server itself)
+                    let group_devices = if rooms.contains_key(device_id) {
+                        get_room_devices(devices, device_id)
+                    } else if let Some(person) = persons.get(device_id) {
+                        person.devices.clone()
+                    } else {
+                        vec![device_id.clone()]
+                    };
+
+                    for group_device_id in group_devices {
+                        if group_device_id != *server_id
+                            && !resolved_deps.contains(&group_device_id)
+                        {
+                            resolved_deps.push(group_device_id);
+                        }
+                    }
+                }
+
+                (server_id.clone(), resolved_deps)
+            })
+            .collect(),
+    )
+}
+
+fn check_rooms(devices: &DeviceMap, rooms: &Rooms) -> Result<(), DependencyError> {
+    for (room_id, _) in rooms.iter() {
+        // make sure rooms and devices don't share the same ID
+        if devices.contains_key(room_id) {
+            return Err(DependencyError::new(format!(
+                "{room_id} is used as a device and a room"
+            )));
+        }
+    }
+
+    for (device_id, device) in devices.iter() {
+        let room = match device {
+            Device::Server(server) => &server.machine.room,
+            Device::Machine(machine) => &machine.room,
+        };
+
+        // make sure the room of the device exists
+        if let Some(room_id) = room {
+            if !rooms.contains_key(room_id) {
+                return Err(DependencyError::new(format!(
+                    "{device_id} belongs to {room_id} which is not a configured room"
+                )));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn check_persons(
+    devices: &DeviceMap,
+    rooms: &Rooms,
+    persons: &Persons,
+) -> Result<(), DependencyError> {
+    for (person_id, person) in persons.iter() {
+        // make sure persons don't share the same ID with devices or rooms
+        if devices.contains_key(person_id) || rooms.contains_key(person_id) {
+            return Err(DependencyError::new(format!(
+                "{person_id} is used as a person and a device or room"
+            )));
+        }
+
+        // each person needs at least one device
+        if person.devices.is_empty() {
+            return Err(DependencyError::new(format!(
+                "{person_id} has no devices configured"
+            )));
+        }
+
+        // make sure all devices of the person exist
+        for device_id in person.devices.iter() {
+            if !devices.contains_key(device_id) {
+                return Err(DependencyError::new(format!(
+                    "{device_id} of {person_id} is not a configured device"
+                )));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn check_groups(
+    devices: &DeviceMap,
+    rooms: &Rooms,
+    persons: &Persons,
+    groups: &Groups,
+) -> Result<(), DependencyError> {
+    for (group_id, group) in groups.iter() {
+        // make sure groups don't share the same ID with devices, rooms or persons
+        if devices.contains_key(group_id)
+            || rooms.contains_key(group_id)
+            || persons.contains_key(group_id)
+        {
+            return Err(DependencyError::new(format!(
+                "{group_id} is used as a group and a device, room or person"
+            )));
+        }
+
+        // each group needs at least one server
+        if group.servers.is_empty() {
+            return Err(DependencyError::new(format!(
+                "{group_id} has no servers configured"
+            )));
+        }
+
+        // make sure all servers of the group exist
+        for server_id in group.servers.iter() {
+            if !matches!(devices.get(server_id), Some(Device::Server(_))) {
+                return Err(DependencyError::new(format!(
+                    "{server_id} of {group_id} is not a configured server"
+                )));
+            }
+        }
+    }
+
+    Ok(())
+}
+
 fn check_dependencies(
     devices: &DeviceMap,
+    rooms: &Rooms,
+    persons: &Persons,
+    schedules: &Schedules,
     dependencies: &Dependencies,
 ) -> Result<(), DependencyError> {
     if dependencies.0.is_empty() {
@@ -134,9 +589,91 @@ fn check_dependencies(
 
         // make sure all values of the dependency exist
         for device_id in dependencies.iter() {
-            if !devices.contains_key(device_id) {
+            if !devices.contains_key(device_id)
+                && !rooms.contains_key(device_id)
+                && !persons.contains_key(device_id)
+                && !schedules.contains(device_id)
+            {
+                return Err(DependencyError::new(format!(
+                    "{device_id} is not a configured device, room, person or schedule"
+                )));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn check_schedules(
+    devices: &DeviceMap,
+    rooms: &Rooms,
+    persons: &Persons,
+    groups: &Groups,
+    schedules: &Schedules,
+) -> Result<(), DependencyError> {
+    for schedule_id in schedules.ids() {
+        // make sure schedules don't share the same ID with devices, rooms, persons or groups
+        if devices.contains_key(&schedule_id)
+            || rooms.contains_key(&schedule_id)
+            || persons.contains_key(&schedule_id)
+            || groups.contains_key(&schedule_id)
+        {
+            return Err(DependencyError::new(format!(
+                "{schedule_id} is used as a schedule and a device, room, person or group"
+            )));
+        }
+    }
+
+    for (name, time) in schedules.times.iter() {
+        for value in [&time.from, &time.to] {
+            if NaiveTime::parse_from_str(value, "%H:%M").is_err() {
+                return Err(DependencyError::new(format!(
+                    "{TIME_PREFIX}{name} has an invalid time {value} (expected HH:MM)"
+                )));
+            }
+        }
+    }
+
+    if let Some(location) = &schedules.location {
+        if !(-90.0..=90.0).contains(&location.latitude)
+            || !(-180.0..=180.0).contains(&location.longitude)
+        {
+            return Err(DependencyError::new(format!(
+                "invalid location {}, {}",
+                location.latitude, location.longitude
+            )));
+        }
+    }
+
+    for (name, calendar) in schedules.calendars.iter() {
+        if calendar.interval == 0 {
+            return Err(DependencyError::new(format!(
+                "{CALENDAR_PREFIX}{name} has no interval configured"
+            )));
+        }
+    }
+
+    // make sure the blackouts of all servers are configured schedules
+    for (server_id, server) in get_servers(devices).iter() {
+        for blackout in server.blackouts.iter() {
+            if !schedules.contains(blackout) {
+                return Err(DependencyError::new(format!(
+                    "blackout {blackout} of {server_id} is not a configured schedule"
+                )));
+            }
+        }
+    }
+
+    // make sure the ignore windows of all devices are configured schedules
+    for (device_id, device) in devices.iter() {
+        let machine = match device {
+            Device::Server(server) => &server.machine,
+            Device::Machine(machine) => machine,
+        };
+        for ignore_window in machine.ignore_windows.iter() {
+            if !schedules.contains(ignore_window) {
                 return Err(DependencyError::new(format!(
-                    "{server_id} is not a configured device"
+                    "ignore window {ignore_window} of {device_id} is not a configured schedule"
                 )));
             }
         }
@@ -145,12 +682,80 @@ fn check_dependencies(
     Ok(())
 }
 
+fn check_profiles(
+    devices: &DeviceMap,
+    rooms: &Rooms,
+    persons: &Persons,
+    schedules: &Schedules,
+    profiles: &Profiles,
+    profile: &Option<String>,
+) -> Result<(), DependencyError> {
+    // the dependencies of every profile have to be as consistent as the configured ones
+    for (_, profile) in profiles.iter() {
+        check_dependencies(devices, rooms, persons, schedules, &profile.dependencies)?;
+    }
+
+    // make sure the profile active on startup exists
+    match profile {
+        Some(profile) if !profiles.contains_key(profile) => Err(DependencyError::new(format!(
+            "{profile} is not a configured profile"
+        ))),
+        _ => Ok(()),
+    }
+}
+
+fn check_classes(devices: &DeviceMap, classes: &Classes) -> Result<(), DependencyError> {
+    for (device_id, device) in devices.iter() {
+        let mut machine = match device {
+            Device::Server(server) => server.machine.clone(),
+            Device::Machine(machine) => machine.clone(),
+        };
+
+        // make sure the timeout is configured either by the device or by its class
+        apply_class(&mut machine, classes);
+        if machine.last_seen_timeout.is_none() {
+            return Err(DependencyError::new(format!(
+                "{device_id} has neither a timeout nor a class with a timeout configured"
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+fn check_recovery(devices: &DeviceMap, recovery: &Option<Recovery>) -> Result<(), DependencyError> {
+    let recovery = match recovery {
+        Some(recovery) => recovery,
+        None => return Ok(()),
+    };
+
+    if recovery.min_reappeared > 100 {
+        return Err(DependencyError::new(format!(
+            "{}% of the devices can't reappear after a power outage",
+            recovery.min_reappeared
+        )));
+    }
+
+    // make sure all core servers exist
+    for server_id in recovery.servers.iter() {
+        if !matches!(devices.get(server_id), Some(Device::Server(_))) {
+            return Err(DependencyError::new(format!(
+                "{server_id} of the recovery is not a configured server"
+            )));
+        }
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use rstest::*;
     use serde_json::json;
 
+    use super::class::Class;
     use super::*;
+    use crate::dom;
     use crate::utils::MacAddr;
 
     static SERVER_ID: &str = "testserver";
@@ -179,14 +784,37 @@ mod tests {
                 id: server_id(),
                 name: SERVER_NAME.to_string(),
                 ip: SERVER_IP.parse().unwrap(),
-                last_seen_timeout: SERVER_LAST_SEEN_TIMEOUT,
+                last_seen_timeout: Some(SERVER_LAST_SEEN_TIMEOUT),
+                room: None,
+                class: None,
+                http: None,
+                port: None,
+                interval: None,
+                ignore_windows: Vec::new(),
+                source: None,
+                mac: None,
             },
             mac: MacAddr::V6(SERVER_MAC.parse().unwrap()),
             ssh: Ssh {
+                host: None,
                 port: SshPort(SERVER_SSH_PORT),
                 username: SERVER_SSH_USERNAME.to_string(),
-                authentication: SshAuthentication::Password(SERVER_SSH_PASSWORD.to_string()),
+                authentication: SshAuthentication::Password(SERVER_SSH_PASSWORD.into()),
             },
+            relay: None,
+            services: Vec::new(),
+            external_dependencies: Vec::new(),
+            hostnames: Vec::new(),
+            os: None,
+            shutdown_confirmation: None,
+            action_timeouts: ActionTimeouts::default(),
+            shutdown_verification: None,
+            max_failed_shutdowns: None,
+            max_daily_on_time: None,
+            min_on_duration: None,
+            min_off_duration: None,
+            blackouts: Vec::new(),
+            keep_on_when_away: false,
         }
     }
 
@@ -201,7 +829,15 @@ mod tests {
             id: machine_id(),
             name: MACHINE_NAME.to_string(),
             ip: MACHINE_IP.parse().unwrap(),
-            last_seen_timeout: MACHINE_LAST_SEEN_TIMEOUT,
+            last_seen_timeout: Some(MACHINE_LAST_SEEN_TIMEOUT),
+            room: None,
+            class: None,
+            http: None,
+            port: None,
+            interval: None,
+            ignore_windows: Vec::new(),
+            source: None,
+            mac: None,
         }
     }
 
@@ -286,82 +922,457 @@ mod tests {
     }
 
     #[rstest]
-    fn test_get_servers_is_empty_if_no_servers_configured(machine: Machine) {
-        let mut devices = DeviceMap::new();
-        devices.insert(machine.id.clone(), Device::Machine(machine));
+    fn test_parse_api_without_files() {
+        let api_json = json!({
+            "web": {
+                "ip": "127.0.0.1",
+                "port": 8000
+            }
+        });
 
-        assert!(get_servers(&devices).is_empty());
+        let api: Api = serde_json::from_value(api_json).unwrap();
+        assert_eq!(api.files, None);
     }
 
     #[rstest]
-    fn test_get_servers_returns_configured_servers(server: Server, machine: Machine) {
-        let server_id = server.machine.id.clone();
-
-        let mut devices = DeviceMap::new();
-        devices.insert(server_id.clone(), Device::Server(server.clone()));
-        devices.insert(machine.id.clone(), Device::Machine(machine));
+    fn test_parse_network_limits() {
+        let network_json = json!({
+            "interface": "eth0",
+            "ping": {
+                "interval": 5,
+                "timeout": 2
+            },
+            "limits": {
+                "maxPingsPerSecond": 0,
+                "maxParallelWakeups": 2
+            }
+        });
 
-        let servers = get_servers(&devices);
-        assert_eq!(1, servers.len());
-        assert!(servers.contains_key(&server_id));
-        assert_eq!(server, *servers.get(&server_id).unwrap());
+        let network: Network = serde_json::from_value(network_json).unwrap();
+        assert_eq!(
+            network.limits,
+            Limits {
+                max_pings_per_second: 0,
+                max_wakeups_per_minute: Limits::default_max_wakeups_per_minute(),
+                max_parallel_wakeups: 2,
+                wakeup_stagger_delay: Limits::default_wakeup_stagger_delay(),
+            }
+        );
     }
 
     #[rstest]
-    fn test_get_machines_is_empty_if_no_machines_configured(server: Server) {
-        let mut devices = DeviceMap::new();
-        devices.insert(server.machine.id.clone(), Device::Server(server));
-
-        assert!(get_machines(&devices).is_empty());
+    fn test_parse_hooks() {
+        let hooks_json = json!([
+            {
+                "events": ["preWakeup", "postShutdown"],
+                "command": ["/usr/local/bin/silence-alerts", "--server"]
+            },
+            {
+                "events": ["postWakeup"],
+                "url": "http://localhost:8080/hook",
+                "fatal": true
+            }
+        ]);
+
+        let hooks: Vec<Hook> = serde_json::from_value(hooks_json).unwrap();
+        assert_eq!(
+            hooks,
+            vec![
+                Hook {
+                    events: vec![HookEvent::PreWakeup, HookEvent::PostShutdown],
+                    target: HookTarget::Command {
+                        command: vec![
+                            "/usr/local/bin/silence-alerts".to_string(),
+                            "--server".to_string()
+                        ]
+                    },
+                    fatal: false,
+                },
+                Hook {
+                    events: vec![HookEvent::PostWakeup],
+                    target: HookTarget::Webhook {
+                        url: "http://localhost:8080/hook".to_string()
+                    },
+                    fatal: true,
+                },
+            ]
+        );
     }
 
     #[rstest]
-    fn test_get_machines_returns_configured_machines(server: Server, machine: Machine) {
-        let machine_id = machine.id.clone();
+    #[case(100, 10, 0)]
+    #[case(101, 10, 1)]
+    #[case(5000, 11, 2)]
+    fn test_network_limits_warn_about_exceeding_configuration(
+        #[case] devices: usize,
+        #[case] servers: usize,
+        #[case] expected_warnings: usize,
+    ) {
+        let limits = Limits::default();
+
+        assert_eq!(limits.check(devices, servers).len(), expected_warnings);
+        // without limits nothing can be exceeded
+        let unlimited = Limits {
+            max_pings_per_second: 0,
+            max_wakeups_per_minute: 0,
+            ..Limits::default()
+        };
+        assert!(unlimited.check(devices, servers).is_empty());
+    }
 
-        let mut devices = DeviceMap::new();
-        devices.insert(server.machine.id.clone(), Device::Server(server.clone()));
-        devices.insert(machine_id.clone(), Device::Machine(machine.clone()));
+    #[rstest]
+    fn test_parse_server_with_api_relay() {
+        let server_json = json!({
+            "name": "Server 1",
+            "mac": "aa:bb:cc:dd:ee:ff",
+            "ip": "192.168.2.1",
+            "timeout": 60,
+            "ssh": {
+                "username": "foo",
+                "password": "bar"
+            },
+            "relay": {
+                "api": {
+                    "url": "http://192.168.2.254:8000"
+                }
+            }
+        });
 
-        let machines = get_machines(&devices);
-        assert_eq!(1, machines.len());
-        assert!(machines.contains_key(&machine_id));
-        assert_eq!(machine, *machines.get(&machine_id).unwrap());
+        let server: Server = serde_json::from_value(server_json).unwrap();
+        assert_eq!(
+            server.relay,
+            Some(WakeupRelay::Api(ApiRelay {
+                url: "http://192.168.2.254:8000".to_string(),
+                server: None,
+                token: None,
+            }))
+        );
     }
 
     #[rstest]
-    fn test_check_dependencies_succeeds_if_no_dependencies_configured(
-        server: Server,
-        machine: Machine,
-    ) {
-        let mut devices = DeviceMap::new();
-        devices.insert(server.machine.id.clone(), Device::Server(server.clone()));
-        devices.insert(machine.id.clone(), Device::Machine(machine.clone()));
-
-        let dependencies = Dependencies(HashMap::<DeviceId, Vec<DeviceId>>::new());
+    fn test_parse_server_with_ssh_relay() {
+        let server_json = json!({
+            "name": "Server 1",
+            "mac": "aa:bb:cc:dd:ee:ff",
+            "ip": "192.168.2.1",
+            "timeout": 60,
+            "ssh": {
+                "username": "foo",
+                "password": "bar"
+            },
+            "relay": {
+                "ssh": {
+                    "ip": "192.168.2.254",
+                    "username": "relay",
+                    "password": "secret"
+                }
+            }
+        });
 
-        assert!(check_dependencies(&devices, &dependencies).is_ok());
+        let server: Server = serde_json::from_value(server_json).unwrap();
+        assert_eq!(
+            server.relay,
+            Some(WakeupRelay::Ssh(Box::new(SshRelay {
+                ip: "192.168.2.254".parse().unwrap(),
+                ssh: Ssh {
+                    host: None,
+                    port: SshPort::default(),
+                    username: "relay".to_string(),
+                    authentication: SshAuthentication::Password("secret".into()),
+                },
+                command: SshRelay::default_command(),
+            })))
+        );
     }
 
     #[rstest]
-    fn test_check_dependencies_fails_if_no_servers_configured(
-        server_id: DeviceId,
-        machine: Machine,
-    ) {
-        let machine_id = machine.id.clone();
+    #[case(json!("linux-systemd"), ServerOs::LinuxSystemd)]
+    #[case(json!("linux-sysv"), ServerOs::LinuxSysv)]
+    #[case(json!("freebsd"), ServerOs::Freebsd)]
+    #[case(json!("windows-ssh"), ServerOs::WindowsSsh)]
+    #[case(json!("macos"), ServerOs::Macos)]
+    fn test_parse_server_with_os(#[case] os_json: serde_json::Value, #[case] expected: ServerOs) {
+        let server_json = json!({
+            "name": "Server 1",
+            "mac": "aa:bb:cc:dd:ee:ff",
+            "ip": "192.168.2.1",
+            "timeout": 60,
+            "ssh": {
+                "username": "foo",
+                "password": "bar"
+            },
+            "os": os_json
+        });
 
-        let mut devices = DeviceMap::new();
-        devices.insert(machine_id.clone(), Device::Machine(machine));
+        let server: Server = serde_json::from_value(server_json).unwrap();
+        assert_eq!(server.os, Some(expected));
+    }
 
-        let mut dependencies = Dependencies(HashMap::<DeviceId, Vec<DeviceId>>::new());
-        dependencies.0.insert(server_id, vec![machine_id.clone()]);
+    #[rstest]
+    #[case(json!({ "username": "foo", "password": "s3cr3t" }))]
+    #[case(json!({ "username": "foo", "privateKey": { "file": "~/.ssh/id_rsa", "passphrase": "s3cr3t" } }))]
+    fn test_debug_doesnt_leak_ssh_credentials(#[case] ssh_json: serde_json::Value) {
+        let ssh: Ssh = serde_json::from_value(ssh_json).unwrap();
+        let debug = format!("{:?}", ssh);
+        assert!(debug.contains("foo"));
+        assert!(!debug.contains("s3cr3t"));
+
+        let ssh = dom::device::Ssh::from(&ssh);
+        assert!(!format!("{:?}", ssh).contains("s3cr3t"));
+        assert!(!format!("{:#?}", ssh).contains("s3cr3t"));
+    }
 
-        assert!(check_dependencies(&devices, &dependencies).is_err());
+    #[rstest]
+    fn test_debug_doesnt_leak_basic_auth_password() {
+        let auth: BasicAuth =
+            serde_json::from_value(json!({ "username": "foo", "password": "s3cr3t" })).unwrap();
+
+        assert!(!format!("{:?}", auth).contains("s3cr3t"));
     }
 
     #[rstest]
-    fn test_check_dependencies_fails_if_no_machines_configured(
-        server: Server,
+    fn test_server_with_services() {
+        let server_json = json!({
+            "name": SERVER_NAME,
+            "ip": SERVER_IP,
+            "timeout": SERVER_LAST_SEEN_TIMEOUT,
+            "mac": SERVER_MAC,
+            "ssh": {
+                "username": SERVER_SSH_USERNAME,
+                "password": SERVER_SSH_PASSWORD
+            },
+            "services": [
+                { "name": "ssh", "port": 22 },
+                {
+                    "name": "jellyfin",
+                    "http": {
+                        "url": "https://192.168.1.1:8096/health",
+                        "expectedBody": "Healthy",
+                        "insecure": true
+                    }
+                }
+            ]
+        });
+
+        let server: Server = serde_json::from_value(server_json).unwrap();
+        assert_eq!(
+            server.services,
+            vec![
+                Service {
+                    name: "ssh".to_string(),
+                    check: ServiceCheck::Tcp { port: 22 },
+                },
+                Service {
+                    name: "jellyfin".to_string(),
+                    check: ServiceCheck::Http {
+                        http: HttpCheck {
+                            url: "https://192.168.1.1:8096/health".to_string(),
+                            expected_status: HttpCheck::default_expected_status(),
+                            expected_body: Some("Healthy".to_string()),
+                            timeout: HttpCheck::default_timeout(),
+                            insecure: true,
+                        }
+                    },
+                },
+            ]
+        );
+    }
+
+    #[rstest]
+    fn test_server_with_external_dependencies() {
+        let server_json = json!({
+            "name": SERVER_NAME,
+            "ip": SERVER_IP,
+            "timeout": SERVER_LAST_SEEN_TIMEOUT,
+            "mac": SERVER_MAC,
+            "ssh": {
+                "username": SERVER_SSH_USERNAME,
+                "password": SERVER_SSH_PASSWORD
+            },
+            "externalDependencies": [
+                {
+                    "name": "backup",
+                    "http": { "url": "http://192.168.1.2:8080/backup/status" }
+                },
+                {
+                    "name": "switch port",
+                    "snmp": { "host": "192.168.1.254", "interface": 3, "threshold": 100000 },
+                    "cache": 120
+                },
+                { "name": "shares", "sessions": { "protocol": "smb" } }
+            ]
+        });
+
+        let server: Server = serde_json::from_value(server_json).unwrap();
+        assert_eq!(server.external_dependencies.len(), 3);
+        assert!(matches!(
+            server.external_dependencies[0].check,
+            ExternalCheck::Http { .. }
+        ));
+        assert_eq!(
+            server.external_dependencies[1],
+            ExternalDependency {
+                name: "switch port".to_string(),
+                check: ExternalCheck::Snmp {
+                    snmp: SnmpCheck {
+                        host: "192.168.1.254".to_string(),
+                        port: SnmpCheck::default_port(),
+                        community: SnmpCheck::default_community(),
+                        interface: 3,
+                        threshold: 100000,
+                        timeout: SnmpCheck::default_timeout(),
+                    }
+                },
+                cache: 120,
+            }
+        );
+        assert_eq!(
+            server.external_dependencies[2].check,
+            ExternalCheck::Sessions {
+                sessions: SessionsCheck {
+                    protocol: FileSharingProtocol::Smb,
+                    threshold: SessionsCheck::default_threshold(),
+                    command: None,
+                }
+            }
+        );
+    }
+
+    #[rstest]
+    fn test_machine_with_http_check() {
+        let machine_json = json!({
+            "name": MACHINE_NAME,
+            "ip": MACHINE_IP,
+            "timeout": MACHINE_LAST_SEEN_TIMEOUT,
+            "http": {
+                "url": "http://192.168.1.2/status",
+                "expectedStatus": 204,
+                "timeout": 2
+            }
+        });
+
+        let machine: Machine = serde_json::from_value(machine_json).unwrap();
+        assert_eq!(
+            machine.http,
+            Some(HttpCheck {
+                url: "http://192.168.1.2/status".to_string(),
+                expected_status: 204,
+                expected_body: None,
+                timeout: 2,
+                insecure: false,
+            })
+        );
+    }
+
+    #[rstest]
+    fn test_machine_with_mac() {
+        let machine_json = json!({
+            "name": MACHINE_NAME,
+            "ip": MACHINE_IP,
+            "mac": "11:22:33:44:55:66"
+        });
+
+        // a machine with a MAC address isn't mistaken for a server
+        let device: Device = serde_json::from_value(machine_json).unwrap();
+        match device {
+            Device::Machine(machine) => {
+                assert_eq!(machine.mac, Some("11:22:33:44:55:66".parse().unwrap()))
+            }
+            Device::Server(_) => panic!("machine parsed as server"),
+        }
+    }
+
+    #[rstest]
+    fn test_get_servers_is_empty_if_no_servers_configured(machine: Machine) {
+        let mut devices = DeviceMap::new();
+        devices.insert(machine.id.clone(), Device::Machine(machine));
+
+        assert!(get_servers(&devices).is_empty());
+    }
+
+    #[rstest]
+    fn test_get_servers_returns_configured_servers(server: Server, machine: Machine) {
+        let server_id = server.machine.id.clone();
+
+        let mut devices = DeviceMap::new();
+        devices.insert(server_id.clone(), Device::Server(server.clone()));
+        devices.insert(machine.id.clone(), Device::Machine(machine));
+
+        let servers = get_servers(&devices);
+        assert_eq!(1, servers.len());
+        assert!(servers.contains_key(&server_id));
+        assert_eq!(server, *servers.get(&server_id).unwrap());
+    }
+
+    #[rstest]
+    fn test_get_machines_is_empty_if_no_machines_configured(server: Server) {
+        let mut devices = DeviceMap::new();
+        devices.insert(server.machine.id.clone(), Device::Server(server));
+
+        assert!(get_machines(&devices).is_empty());
+    }
+
+    #[rstest]
+    fn test_get_machines_returns_configured_machines(server: Server, machine: Machine) {
+        let machine_id = machine.id.clone();
+
+        let mut devices = DeviceMap::new();
+        devices.insert(server.machine.id.clone(), Device::Server(server.clone()));
+        devices.insert(machine_id.clone(), Device::Machine(machine.clone()));
+
+        let machines = get_machines(&devices);
+        assert_eq!(1, machines.len());
+        assert!(machines.contains_key(&machine_id));
+        assert_eq!(machine, *machines.get(&machine_id).unwrap());
+    }
+
+    #[rstest]
+    fn test_check_dependencies_succeeds_if_no_dependencies_configured(
+        server: Server,
+        machine: Machine,
+    ) {
+        let mut devices = DeviceMap::new();
+        devices.insert(server.machine.id.clone(), Device::Server(server.clone()));
+        devices.insert(machine.id.clone(), Device::Machine(machine.clone()));
+
+        let dependencies = Dependencies(HashMap::<DeviceId, Vec<DeviceId>>::new());
+
+        assert!(check_dependencies(
+            &devices,
+            &Rooms::new(),
+            &Persons::new(),
+            &Schedules::default(),
+            &dependencies
+        )
+        .is_ok());
+    }
+
+    #[rstest]
+    fn test_check_dependencies_fails_if_no_servers_configured(
+        server_id: DeviceId,
+        machine: Machine,
+    ) {
+        let machine_id = machine.id.clone();
+
+        let mut devices = DeviceMap::new();
+        devices.insert(machine_id.clone(), Device::Machine(machine));
+
+        let mut dependencies = Dependencies(HashMap::<DeviceId, Vec<DeviceId>>::new());
+        dependencies.0.insert(server_id, vec![machine_id.clone()]);
+
+        assert!(check_dependencies(
+            &devices,
+            &Rooms::new(),
+            &Persons::new(),
+            &Schedules::default(),
+            &dependencies
+        )
+        .is_err());
+    }
+
+    #[rstest]
+    fn test_check_dependencies_fails_if_no_machines_configured(
+        server: Server,
         machine_id: DeviceId,
     ) {
         let server_id = server.machine.id.clone();
@@ -372,7 +1383,14 @@ mod tests {
         let mut dependencies = Dependencies(HashMap::<DeviceId, Vec<DeviceId>>::new());
         dependencies.0.insert(server_id.clone(), vec![machine_id]);
 
-        assert!(check_dependencies(&devices, &dependencies).is_err());
+        assert!(check_dependencies(
+            &devices,
+            &Rooms::new(),
+            &Persons::new(),
+            &Schedules::default(),
+            &dependencies
+        )
+        .is_err());
     }
 
     #[rstest]
@@ -392,7 +1410,32 @@ mod tests {
             .0
             .insert(machine_id.clone(), vec![server_id.clone()]);
 
-        assert!(check_dependencies(&devices, &dependencies).is_err());
+        assert!(check_dependencies(
+            &devices,
+            &Rooms::new(),
+            &Persons::new(),
+            &Schedules::default(),
+            &dependencies
+        )
+        .is_err());
+    }
+
+    #[rstest]
+    fn test_check_recovery_fails_if_core_server_is_not_a_server(server: Server, machine: Machine) {
+        let server_id = server.machine.id.clone();
+        let machine_id = machine.id.clone();
+
+        let mut devices = DeviceMap::new();
+        devices.insert(server_id.clone(), Device::Server(server));
+        devices.insert(machine_id.clone(), Device::Machine(machine));
+
+        let recovery_json = json!({ "servers": [server_id.to_string()] });
+        let mut recovery: Recovery = serde_json::from_value(recovery_json).unwrap();
+        assert_eq!(recovery.min_offline, Recovery::default_min_offline());
+        assert!(check_recovery(&devices, &Some(recovery.clone())).is_ok());
+
+        recovery.servers.push(machine_id);
+        assert!(check_recovery(&devices, &Some(recovery)).is_err());
     }
 
     #[rstest]
@@ -410,7 +1453,14 @@ mod tests {
         let mut dependencies = Dependencies(HashMap::<DeviceId, Vec<DeviceId>>::new());
         dependencies.0.insert(server_id.clone(), vec![]);
 
-        assert!(check_dependencies(&devices, &dependencies).is_err());
+        assert!(check_dependencies(
+            &devices,
+            &Rooms::new(),
+            &Persons::new(),
+            &Schedules::default(),
+            &dependencies
+        )
+        .is_err());
     }
 
     #[rstest]
@@ -431,7 +1481,14 @@ mod tests {
             vec![machine_id.clone(), server_id.clone()],
         );
 
-        assert!(check_dependencies(&devices, &dependencies).is_err());
+        assert!(check_dependencies(
+            &devices,
+            &Rooms::new(),
+            &Persons::new(),
+            &Schedules::default(),
+            &dependencies
+        )
+        .is_err());
     }
 
     #[rstest]
@@ -449,7 +1506,14 @@ mod tests {
             vec![machine_id.clone(), "badid".parse().unwrap()],
         );
 
-        assert!(check_dependencies(&devices, &dependencies).is_err());
+        assert!(check_dependencies(
+            &devices,
+            &Rooms::new(),
+            &Persons::new(),
+            &Schedules::default(),
+            &dependencies
+        )
+        .is_err());
     }
 
     #[rstest]
@@ -466,6 +1530,630 @@ mod tests {
             .0
             .insert(server_id.clone(), vec![machine_id.clone()]);
 
-        assert!(check_dependencies(&devices, &dependencies).is_ok());
+        assert!(check_dependencies(
+            &devices,
+            &Rooms::new(),
+            &Persons::new(),
+            &Schedules::default(),
+            &dependencies
+        )
+        .is_ok());
+    }
+
+    #[fixture]
+    fn room_id() -> RoomId {
+        "livingroom".parse().unwrap()
+    }
+
+    #[fixture]
+    fn rooms(room_id: RoomId) -> Rooms {
+        let mut rooms = Rooms::new();
+        rooms.insert(
+            room_id,
+            Room {
+                name: "Living Room".to_string(),
+            },
+        );
+
+        rooms
+    }
+
+    #[rstest]
+    fn test_check_rooms_fails_if_room_doesnt_exist(
+        server: Server,
+        mut machine: Machine,
+        room_id: RoomId,
+    ) {
+        machine.room = Some(room_id);
+
+        let mut devices = DeviceMap::new();
+        devices.insert(server.machine.id.clone(), Device::Server(server));
+        devices.insert(machine.id.clone(), Device::Machine(machine));
+
+        assert!(check_rooms(&devices, &Rooms::new()).is_err());
+    }
+
+    #[rstest]
+    fn test_check_rooms_fails_if_room_is_also_a_device(server: Server, machine: Machine) {
+        let mut rooms = Rooms::new();
+        rooms.insert(
+            machine.id.clone(),
+            Room {
+                name: "Living Room".to_string(),
+            },
+        );
+
+        let mut devices = DeviceMap::new();
+        devices.insert(server.machine.id.clone(), Device::Server(server));
+        devices.insert(machine.id.clone(), Device::Machine(machine));
+
+        assert!(check_rooms(&devices, &rooms).is_err());
+    }
+
+    #[rstest]
+    fn test_check_rooms_succeeds(
+        server: Server,
+        mut machine: Machine,
+        room_id: RoomId,
+        rooms: Rooms,
+    ) {
+        machine.room = Some(room_id);
+
+        let mut devices = DeviceMap::new();
+        devices.insert(server.machine.id.clone(), Device::Server(server));
+        devices.insert(machine.id.clone(), Device::Machine(machine));
+
+        assert!(check_rooms(&devices, &rooms).is_ok());
+    }
+
+    #[rstest]
+    fn test_check_dependencies_succeeds_with_room(
+        server: Server,
+        mut machine: Machine,
+        room_id: RoomId,
+        rooms: Rooms,
+    ) {
+        let server_id = server.machine.id.clone();
+        machine.room = Some(room_id.clone());
+
+        let mut devices = DeviceMap::new();
+        devices.insert(server_id.clone(), Device::Server(server));
+        devices.insert(machine.id.clone(), Device::Machine(machine));
+
+        let mut dependencies = Dependencies(HashMap::<DeviceId, Vec<DeviceId>>::new());
+        dependencies.0.insert(server_id, vec![room_id]);
+
+        assert!(check_dependencies(
+            &devices,
+            &rooms,
+            &Persons::new(),
+            &Schedules::default(),
+            &dependencies
+        )
+        .is_ok());
+    }
+
+    #[rstest]
+    fn test_resolve_dependencies_replaces_rooms_with_devices(
+        mut server: Server,
+        mut machine: Machine,
+        room_id: RoomId,
+        rooms: Rooms,
+    ) {
+        let server_id = server.machine.id.clone();
+        let machine_id = machine.id.clone();
+        server.machine.room = Some(room_id.clone());
+        machine.room = Some(room_id.clone());
+
+        let mut devices = DeviceMap::new();
+        devices.insert(server_id.clone(), Device::Server(server));
+        devices.insert(machine_id.clone(), Device::Machine(machine));
+
+        let mut dependencies = Dependencies(HashMap::<DeviceId, Vec<DeviceId>>::new());
+        dependencies
+            .0
+            .insert(server_id.clone(), vec![room_id, machine_id.clone()]);
+
+        let resolved_dependencies =
+            resolve_dependencies(&devices, &rooms, &Persons::new(), &dependencies);
+        assert_eq!(
+            resolved_dependencies.0.get(&server_id),
+            Some(&vec![machine_id])
+        );
+    }
+
+    #[fixture]
+    fn person_id() -> PersonId {
+        "dad".parse().unwrap()
+    }
+
+    #[rstest]
+    fn test_check_persons_fails_if_device_doesnt_exist(
+        server: Server,
+        machine: Machine,
+        person_id: PersonId,
+    ) {
+        let mut devices = DeviceMap::new();
+        devices.insert(server.machine.id.clone(), Device::Server(server));
+        devices.insert(machine.id.clone(), Device::Machine(machine));
+
+        let mut persons = Persons::new();
+        persons.insert(
+            person_id,
+            Person {
+                name: "Dad".to_string(),
+                devices: vec!["badid".parse().unwrap()],
+            },
+        );
+
+        assert!(check_persons(&devices, &Rooms::new(), &persons).is_err());
+    }
+
+    #[rstest]
+    fn test_check_persons_fails_if_person_is_also_a_device(server: Server, machine: Machine) {
+        let machine_id = machine.id.clone();
+
+        let mut devices = DeviceMap::new();
+        devices.insert(server.machine.id.clone(), Device::Server(server));
+        devices.insert(machine_id.clone(), Device::Machine(machine));
+
+        let mut persons = Persons::new();
+        persons.insert(
+            machine_id.clone(),
+            Person {
+                name: "Dad".to_string(),
+                devices: vec![machine_id],
+            },
+        );
+
+        assert!(check_persons(&devices, &Rooms::new(), &persons).is_err());
+    }
+
+    #[rstest]
+    fn test_resolve_dependencies_replaces_persons_with_devices(
+        server: Server,
+        machine: Machine,
+        person_id: PersonId,
+    ) {
+        let server_id = server.machine.id.clone();
+        let machine_id = machine.id.clone();
+
+        let mut devices = DeviceMap::new();
+        devices.insert(server_id.clone(), Device::Server(server));
+        devices.insert(machine_id.clone(), Device::Machine(machine));
+
+        let mut persons = Persons::new();
+        persons.insert(
+            person_id.clone(),
+            Person {
+                name: "Dad".to_string(),
+                devices: vec![machine_id.clone()],
+            },
+        );
+
+        let mut dependencies = Dependencies(HashMap::<DeviceId, Vec<DeviceId>>::new());
+        dependencies.0.insert(server_id.clone(), vec![person_id]);
+
+        assert!(check_persons(&devices, &Rooms::new(), &persons).is_ok());
+        assert!(check_dependencies(
+            &devices,
+            &Rooms::new(),
+            &persons,
+            &Schedules::default(),
+            &dependencies
+        )
+        .is_ok());
+
+        let resolved_dependencies =
+            resolve_dependencies(&devices, &Rooms::new(), &persons, &dependencies);
+        assert_eq!(
+            resolved_dependencies.0.get(&server_id),
+            Some(&vec![machine_id])
+        );
+    }
+
+    #[fixture]
+    fn group_id() -> GroupId {
+        "lab".parse().unwrap()
+    }
+
+    #[rstest]
+    fn test_check_groups_fails_if_server_doesnt_exist(
+        server: Server,
+        machine: Machine,
+        group_id: GroupId,
+    ) {
+        let mut devices = DeviceMap::new();
+        devices.insert(server.machine.id.clone(), Device::Server(server));
+        devices.insert(machine.id.clone(), Device::Machine(machine));
+
+        let mut groups = Groups::new();
+        groups.insert(
+            group_id,
+            Group {
+                name: "Lab".to_string(),
+                servers: vec!["badid".parse().unwrap()],
+            },
+        );
+
+        assert!(check_groups(&devices, &Rooms::new(), &Persons::new(), &groups).is_err());
+    }
+
+    #[rstest]
+    fn test_check_groups_fails_if_group_contains_a_machine(
+        server: Server,
+        machine: Machine,
+        group_id: GroupId,
+    ) {
+        let machine_id = machine.id.clone();
+
+        let mut devices = DeviceMap::new();
+        devices.insert(server.machine.id.clone(), Device::Server(server));
+        devices.insert(machine_id.clone(), Device::Machine(machine));
+
+        let mut groups = Groups::new();
+        groups.insert(
+            group_id,
+            Group {
+                name: "Lab".to_string(),
+                servers: vec![machine_id],
+            },
+        );
+
+        assert!(check_groups(&devices, &Rooms::new(), &Persons::new(), &groups).is_err());
+    }
+
+    #[rstest]
+    fn test_check_groups_fails_if_group_is_also_a_device(server: Server, machine: Machine) {
+        let server_id = server.machine.id.clone();
+
+        let mut devices = DeviceMap::new();
+        devices.insert(server_id.clone(), Device::Server(server));
+        devices.insert(machine.id.clone(), Device::Machine(machine));
+
+        let mut groups = Groups::new();
+        groups.insert(
+            server_id.clone(),
+            Group {
+                name: "Lab".to_string(),
+                servers: vec![server_id],
+            },
+        );
+
+        assert!(check_groups(&devices, &Rooms::new(), &Persons::new(), &groups).is_err());
+    }
+
+    #[rstest]
+    fn test_check_groups_succeeds(server: Server, machine: Machine, group_id: GroupId) {
+        let server_id = server.machine.id.clone();
+
+        let mut devices = DeviceMap::new();
+        devices.insert(server_id.clone(), Device::Server(server));
+        devices.insert(machine.id.clone(), Device::Machine(machine));
+
+        let mut groups = Groups::new();
+        groups.insert(
+            group_id,
+            Group {
+                name: "Lab".to_string(),
+                servers: vec![server_id],
+            },
+        );
+
+        assert!(check_groups(&devices, &Rooms::new(), &Persons::new(), &groups).is_ok());
+    }
+
+    #[fixture]
+    fn schedules() -> Schedules {
+        Schedules {
+            times: [(
+                "workhours".to_string(),
+                TimeWindow {
+                    from: "08:00".to_string(),
+                    to: "18:00".to_string(),
+                    days: vec![Weekday::Mon, Weekday::Fri],
+                },
+            )]
+            .iter()
+            .cloned()
+            .collect(),
+            location: Some(Location {
+                latitude: 48.21,
+                longitude: 16.37,
+            }),
+            calendars: HashMap::new(),
+        }
+    }
+
+    #[rstest]
+    fn test_check_dependencies_succeeds_with_schedules(
+        server: Server,
+        machine: Machine,
+        schedules: Schedules,
+    ) {
+        let server_id = server.machine.id.clone();
+
+        let mut devices = DeviceMap::new();
+        devices.insert(server_id.clone(), Device::Server(server));
+        devices.insert(machine.id.clone(), Device::Machine(machine));
+
+        let mut dependencies = Dependencies::default();
+        dependencies.0.insert(
+            server_id.clone(),
+            vec![
+                "time:workhours".parse().unwrap(),
+                SUN_NIGHT.parse().unwrap(),
+            ],
+        );
+        assert!(check_dependencies(
+            &devices,
+            &Rooms::new(),
+            &Persons::new(),
+            &schedules,
+            &dependencies
+        )
+        .is_ok());
+
+        // unknown time windows can't be used as dependencies
+        dependencies
+            .0
+            .insert(server_id, vec!["time:weekend".parse().unwrap()]);
+        assert!(check_dependencies(
+            &devices,
+            &Rooms::new(),
+            &Persons::new(),
+            &schedules,
+            &dependencies
+        )
+        .is_err());
+    }
+
+    #[rstest]
+    fn test_check_schedules_fails_if_time_is_invalid(mut schedules: Schedules) {
+        assert!(check_schedules(
+            &DeviceMap::new(),
+            &Rooms::new(),
+            &Persons::new(),
+            &Groups::new(),
+            &schedules
+        )
+        .is_ok());
+
+        schedules.times.get_mut("workhours").unwrap().to = "25:00".to_string();
+        assert!(check_schedules(
+            &DeviceMap::new(),
+            &Rooms::new(),
+            &Persons::new(),
+            &Groups::new(),
+            &schedules
+        )
+        .is_err());
+    }
+
+    #[rstest]
+    fn test_check_schedules_fails_if_blackout_is_not_a_schedule(
+        mut server: Server,
+        schedules: Schedules,
+    ) {
+        let server_id = server.machine.id.clone();
+        server.blackouts = vec![SUN_NIGHT.parse().unwrap()];
+
+        let mut devices = DeviceMap::new();
+        devices.insert(server_id.clone(), Device::Server(server.clone()));
+        assert!(check_schedules(
+            &devices,
+            &Rooms::new(),
+            &Persons::new(),
+            &Groups::new(),
+            &schedules
+        )
+        .is_ok());
+
+        server.blackouts = vec!["calendar:backups".parse().unwrap()];
+        devices.insert(server_id, Device::Server(server));
+        assert!(check_schedules(
+            &devices,
+            &Rooms::new(),
+            &Persons::new(),
+            &Groups::new(),
+            &schedules
+        )
+        .is_err());
+    }
+
+    #[rstest]
+    fn test_check_schedules_fails_if_ignore_window_is_not_a_schedule(
+        mut machine: Machine,
+        schedules: Schedules,
+    ) {
+        let machine_id = machine.id.clone();
+        machine.ignore_windows = vec![SUN_NIGHT.parse().unwrap()];
+
+        let mut devices = DeviceMap::new();
+        devices.insert(machine_id.clone(), Device::Machine(machine.clone()));
+        assert!(check_schedules(
+            &devices,
+            &Rooms::new(),
+            &Persons::new(),
+            &Groups::new(),
+            &schedules
+        )
+        .is_ok());
+
+        machine.ignore_windows = vec!["calendar:backups".parse().unwrap()];
+        devices.insert(machine_id, Device::Machine(machine));
+        assert!(check_schedules(
+            &devices,
+            &Rooms::new(),
+            &Persons::new(),
+            &Groups::new(),
+            &schedules
+        )
+        .is_err());
+    }
+
+    #[rstest]
+    fn test_apply_class_only_fills_missing_properties(mut machine: Machine) {
+        let mut classes = Classes::new();
+        classes.insert(
+            DeviceClass::Phone,
+            Class {
+                port: Some(62078),
+                interval: Some(30),
+                timeout: Some(900),
+            },
+        );
+
+        // machines without a class are not changed
+        let mut unclassified = machine.clone();
+        apply_class(&mut unclassified, &classes);
+        assert_eq!(unclassified, machine);
+
+        machine.class = Some(DeviceClass::Phone);
+        apply_class(&mut machine, &classes);
+        assert_eq!(machine.port, Some(62078));
+        assert_eq!(machine.interval, Some(30));
+        assert_eq!(machine.last_seen_timeout, Some(MACHINE_LAST_SEEN_TIMEOUT));
+    }
+
+    #[rstest]
+    fn test_check_classes_fails_if_timeout_is_missing(mut machine: Machine) {
+        machine.last_seen_timeout = None;
+        machine.class = Some(DeviceClass::Iot);
+
+        let mut devices = DeviceMap::new();
+        devices.insert(machine.id.clone(), Device::Machine(machine));
+
+        let mut classes = Classes::new();
+        assert!(check_classes(&devices, &classes).is_err());
+
+        classes.insert(
+            DeviceClass::Iot,
+            Class {
+                timeout: Some(3600),
+                ..Default::default()
+            },
+        );
+        assert!(check_classes(&devices, &classes).is_ok());
+    }
+
+    #[rstest]
+    fn test_check_profiles_fails_if_profile_is_inconsistent(server: Server, machine: Machine) {
+        let server_id = server.machine.id.clone();
+
+        let mut devices = DeviceMap::new();
+        devices.insert(server_id.clone(), Device::Server(server));
+        devices.insert(machine.id.clone(), Device::Machine(machine));
+
+        let mut profiles = Profiles::new();
+        profiles.insert("vacation".to_string(), Profile::default());
+        let check = |profiles: &Profiles, profile: Option<&str>| {
+            check_profiles(
+                &devices,
+                &Rooms::new(),
+                &Persons::new(),
+                &Schedules::default(),
+                profiles,
+                &profile.map(str::to_string),
+            )
+        };
+        assert!(check(&profiles, Some("vacation")).is_ok());
+        assert!(check(&profiles, Some("party")).is_err());
+
+        profiles
+            .get_mut("vacation")
+            .unwrap()
+            .dependencies
+            .0
+            .insert(server_id, vec!["unknown".parse().unwrap()]);
+        assert!(check(&profiles, None).is_err());
+    }
+
+    #[rstest]
+    fn test_lint_timeouts_warns_about_timeout_shorter_than_interval(mut machine: Machine) {
+        let ping = Ping {
+            interval: 10,
+            timeout: 2,
+            ..Ping::new()
+        };
+
+        let mut devices = DeviceMap::new();
+        devices.insert(machine.id.clone(), Device::Machine(machine.clone()));
+        assert!(lint_timeouts(&devices, &Classes::new(), &ping).is_empty());
+
+        machine.last_seen_timeout = Some(5);
+        devices.insert(machine.id.clone(), Device::Machine(machine.clone()));
+        assert_eq!(lint_timeouts(&devices, &Classes::new(), &ping).len(), 1);
+
+        // the interval of the machine overrides the ping interval
+        machine.interval = Some(5);
+        devices.insert(machine.id.clone(), Device::Machine(machine));
+        assert!(lint_timeouts(&devices, &Classes::new(), &ping).is_empty());
+    }
+
+    #[rstest]
+    fn test_lint_names_warns_about_identical_names(server: Server, mut machine: Machine) {
+        let mut devices = DeviceMap::new();
+        devices.insert(server.machine.id.clone(), Device::Server(server.clone()));
+        devices.insert(machine.id.clone(), Device::Machine(machine.clone()));
+        assert!(lint_names(&devices).is_empty());
+
+        machine.name = server.machine.name.clone();
+        devices.insert(machine.id.clone(), Device::Machine(machine));
+        assert_eq!(
+            lint_names(&devices),
+            vec![format!(
+                "{MACHINE_ID}, {SERVER_ID} are all named \"{SERVER_NAME}\""
+            )]
+        );
+    }
+
+    #[rstest]
+    fn test_lint_dependencies_warns_about_servers_only_depending_on_servers(
+        server: Server,
+        machine: Machine,
+    ) {
+        let server_id = server.machine.id.clone();
+        let mut other_server = server.clone();
+        other_server.machine.id = "otherserver".parse().unwrap();
+        let other_server_id = other_server.machine.id.clone();
+
+        let mut devices = DeviceMap::new();
+        devices.insert(server_id.clone(), Device::Server(server));
+        devices.insert(other_server_id.clone(), Device::Server(other_server));
+        devices.insert(machine.id.clone(), Device::Machine(machine.clone()));
+
+        let mut dependencies = Dependencies(HashMap::<DeviceId, Vec<DeviceId>>::new());
+        dependencies
+            .0
+            .insert(server_id.clone(), vec![other_server_id.clone()]);
+        assert_eq!(lint_dependencies(&devices, &dependencies).len(), 1);
+
+        dependencies
+            .0
+            .insert(server_id, vec![other_server_id, machine.id]);
+        assert!(lint_dependencies(&devices, &dependencies).is_empty());
+    }
+
+    #[rstest]
+    fn test_lint_unused_devices_warns_about_machines_without_dependency(
+        server: Server,
+        machine: Machine,
+    ) {
+        let server_id = server.machine.id.clone();
+        let machine_id = machine.id.clone();
+
+        let mut devices = DeviceMap::new();
+        devices.insert(server_id.clone(), Device::Server(server));
+        devices.insert(machine_id.clone(), Device::Machine(machine));
+
+        let mut dependencies = Dependencies(HashMap::<DeviceId, Vec<DeviceId>>::new());
+        assert_eq!(
+            lint_unused_devices(&devices, &dependencies),
+            vec![format!("{MACHINE_ID} is not a dependency of any server")]
+        );
+
+        dependencies.0.insert(server_id, vec![machine_id]);
+        assert!(lint_unused_devices(&devices, &dependencies).is_empty());
     }
 }
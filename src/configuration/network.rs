@@ -1,11 +1,35 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use super::duration;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum PingMethod {
+    /// Sends ICMP echo requests through a raw socket and falls back to the `ping` command if
+    /// no raw socket can be opened.
+    #[default]
+    Icmp,
+    /// Runs the `ping` command of the operating system for every device.
+    Command,
+}
+
 #[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Ping {
+    #[serde(deserialize_with = "duration::deserialize")]
+    #[schemars(with = "duration::Seconds")]
     pub interval: u64,
+    #[serde(deserialize_with = "duration::deserialize")]
+    #[schemars(with = "duration::Seconds")]
     pub timeout: u64,
+    #[serde(default)]
+    pub method: PingMethod,
+    /// Number of consecutive pings a device on the local network has to miss before it's
+    /// probed with ARP requests instead (disabled by default).
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub arp_fallback: Option<u32>,
 }
 
 impl Ping {
@@ -15,11 +39,101 @@ impl Ping {
     }
 }
 
+/// Global limits of the probes sent into the network (`0` is unlimited).
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Limits {
+    #[serde(default = "Limits::default_max_pings_per_second")]
+    pub max_pings_per_second: u32,
+    #[serde(default = "Limits::default_max_wakeups_per_minute")]
+    pub max_wakeups_per_minute: u32,
+    /// Maximum number of servers woken up by the monitor within `wakeup_stagger_delay`, further
+    /// wakeups are deferred to the next batch.
+    #[serde(default)]
+    pub max_parallel_wakeups: u32,
+    /// Number of seconds between two batches of staggered wakeups.
+    #[serde(
+        default = "Limits::default_wakeup_stagger_delay",
+        deserialize_with = "duration::deserialize"
+    )]
+    #[schemars(with = "duration::Seconds")]
+    pub wakeup_stagger_delay: u64,
+}
+
+impl Limits {
+    pub fn default_max_pings_per_second() -> u32 {
+        100
+    }
+
+    pub fn default_max_wakeups_per_minute() -> u32 {
+        10
+    }
+
+    pub fn default_wakeup_stagger_delay() -> u64 {
+        30
+    }
+
+    /// Describes how monitoring the given number of devices and servers would exceed the limits.
+    pub fn check(&self, devices: usize, servers: usize) -> Vec<String> {
+        let mut warnings = Vec::new();
+        if self.max_pings_per_second > 0 && devices > self.max_pings_per_second as usize {
+            warnings.push(format!(
+                "pinging {} devices exceeds the limit of {} pings per second (pings are delayed)",
+                devices, self.max_pings_per_second
+            ));
+        }
+        if self.max_wakeups_per_minute > 0 && servers > self.max_wakeups_per_minute as usize {
+            warnings.push(format!(
+                "waking up {} servers at once exceeds the limit of {} wakeups per minute",
+                servers, self.max_wakeups_per_minute
+            ));
+        }
+
+        warnings
+    }
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            max_pings_per_second: Limits::default_max_pings_per_second(),
+            max_wakeups_per_minute: Limits::default_max_wakeups_per_minute(),
+            max_parallel_wakeups: 0,
+            wakeup_stagger_delay: Limits::default_wakeup_stagger_delay(),
+        }
+    }
+}
+
 #[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Network {
     pub interface: String,
     pub ping: Ping,
+    /// Number of seconds after startup during which no server is woken up or shut down.
+    #[serde(default, deserialize_with = "duration::deserialize")]
+    #[schemars(with = "duration::Seconds")]
+    pub startup_grace_period: u64,
+    /// Maximum number of simultaneous SSH sessions to shut down servers (`0` is unlimited).
+    #[serde(default = "Network::default_max_ssh_sessions")]
+    pub max_ssh_sessions: usize,
+    #[serde(default)]
+    pub limits: Limits,
+    /// Number of seconds after a successful wakeup / shutdown during which further requests of
+    /// the same action for the same server are ignored.
+    #[serde(
+        default = "Network::default_duplicate_action_window",
+        deserialize_with = "duration::deserialize"
+    )]
+    #[schemars(with = "duration::Seconds")]
+    pub duplicate_action_window: u64,
+    /// Number of seconds between two checks whether the ARP entries of the devices still match
+    /// their configured MAC addresses (`0` disables the check, only supported on Linux).
+    #[serde(
+        default = "Network::default_mac_check_interval",
+        deserialize_with = "duration::deserialize"
+    )]
+    #[schemars(with = "duration::Seconds")]
+    pub mac_check_interval: u64,
 }
 
 impl Network {
@@ -27,4 +141,21 @@ impl Network {
     pub fn new() -> Self {
         Self::default()
     }
+
+    pub fn default_max_ssh_sessions() -> usize {
+        4
+    }
+
+    pub fn default_duplicate_action_window() -> u64 {
+        10
+    }
+
+    pub fn default_mac_check_interval() -> u64 {
+        // the ARP table is only read from /proc on Linux
+        if cfg!(target_os = "linux") {
+            5 * 60
+        } else {
+            0
+        }
+    }
 }
@@ -0,0 +1,116 @@
+use std::collections::BTreeMap;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::duration;
+use crate::utils::Secret;
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Webhook {
+    pub url: String,
+    /// Secret with which the payloads sent to the webhook are signed (HMAC-SHA256).
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secret: Option<Secret>,
+}
+
+/// Events about which notifications are sent.
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize, JsonSchema,
+)]
+#[serde(rename_all = "camelCase")]
+pub enum NotificationEvent {
+    UnexpectedOnline,
+    ShutdownFailed,
+    Failover,
+    UpdateAvailable,
+    Crashed,
+    PowerChanged,
+    ControlDisabled,
+    AuthenticationFailed,
+    MacMismatch,
+}
+
+/// Language of the built-in notification messages.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum Locale {
+    #[default]
+    En,
+    De,
+}
+
+/// Replaces the built-in title and / or message of the notifications about an event. Variables
+/// of the event are referenced as `{variable}`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationTemplate {
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// Retries notifications which couldn't be delivered (e.g. due to a flaky uplink).
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationRetry {
+    /// Maximum number of queued notifications (the oldest ones are dropped).
+    #[serde(default = "NotificationRetry::default_max_queued")]
+    pub max_queued: usize,
+    /// Delay before the first retry which is doubled after every failed retry.
+    #[serde(
+        default = "NotificationRetry::default_backoff",
+        deserialize_with = "duration::deserialize"
+    )]
+    #[schemars(with = "duration::Seconds")]
+    pub backoff: u64,
+    #[serde(
+        default = "NotificationRetry::default_max_backoff",
+        deserialize_with = "duration::deserialize"
+    )]
+    #[schemars(with = "duration::Seconds")]
+    pub max_backoff: u64,
+}
+
+impl NotificationRetry {
+    pub fn default_max_queued() -> usize {
+        100
+    }
+
+    pub fn default_backoff() -> u64 {
+        30
+    }
+
+    pub fn default_max_backoff() -> u64 {
+        60 * 60
+    }
+}
+
+impl Default for NotificationRetry {
+    fn default() -> Self {
+        Self {
+            max_queued: NotificationRetry::default_max_queued(),
+            backoff: NotificationRetry::default_backoff(),
+            max_backoff: NotificationRetry::default_max_backoff(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Notifications {
+    #[serde(default)]
+    pub webhooks: Vec<Webhook>,
+    #[serde(default)]
+    pub locale: Locale,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub templates: BTreeMap<NotificationEvent, NotificationTemplate>,
+    #[serde(default)]
+    pub retry: NotificationRetry,
+}
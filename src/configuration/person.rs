@@ -0,0 +1,18 @@
+use std::collections::HashMap;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::DeviceId;
+
+// NOTE: persons share their IDs with devices so that they can be used as dependencies
+pub type PersonId = DeviceId;
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Person {
+    pub name: String,
+    pub devices: Vec<DeviceId>,
+}
+
+pub type Persons = HashMap<PersonId, Person>;
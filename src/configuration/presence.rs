@@ -0,0 +1,52 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::utils::Secret;
+
+use super::duration;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum Router {
+    /// UniFi controller (e.g. `https://unifi:8443`).
+    Unifi,
+    /// OpenWrt router with `uhttpd-mod-ubus` (e.g. `http://openwrt`).
+    Openwrt,
+    /// Fritz!Box through TR-064 (e.g. `http://fritz.box:49000`).
+    Fritzbox,
+}
+
+/// Router whose list of associated (Wi-Fi) clients is used as the online signal of devices.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PresenceProvider {
+    pub router: Router,
+    pub url: String,
+    #[serde(default)]
+    pub username: String,
+    #[serde(default)]
+    pub password: Secret,
+    /// Site of the UniFi controller.
+    #[serde(default = "PresenceProvider::default_site")]
+    pub site: String,
+    /// Number of seconds between two queries of the clients.
+    #[serde(
+        default = "PresenceProvider::default_interval",
+        deserialize_with = "duration::deserialize"
+    )]
+    #[schemars(with = "duration::Seconds")]
+    pub interval: u64,
+    /// Accepts any (e.g. self-signed) TLS certificate of the router.
+    #[serde(default)]
+    pub insecure: bool,
+}
+
+impl PresenceProvider {
+    pub fn default_site() -> String {
+        "default".to_string()
+    }
+
+    pub fn default_interval() -> u64 {
+        30
+    }
+}
@@ -0,0 +1,17 @@
+use std::collections::HashMap;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::Dependencies;
+
+/// Named set of dependencies (including schedules) which replaces the configured dependencies
+/// while it is active. Servers without dependencies in the profile are kept offline.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Profile {
+    #[serde(default)]
+    pub dependencies: Dependencies,
+}
+
+pub type Profiles = HashMap<String, Profile>;
@@ -0,0 +1,50 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::{duration, DeviceId};
+
+/// Policy applied once most devices reappear after a long offline period (e.g. after the power
+/// has been restored).
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Recovery {
+    /// Number of seconds all devices have to be offline before reappearing is considered a
+    /// recovery.
+    #[serde(
+        default = "Recovery::default_min_offline",
+        deserialize_with = "duration::deserialize"
+    )]
+    #[schemars(with = "duration::Seconds")]
+    pub min_offline: u64,
+    /// Percentage of the devices which have to reappear within five minutes.
+    #[serde(default = "Recovery::default_min_reappeared")]
+    pub min_reappeared: u8,
+    /// Core servers woken up one after another (regardless of their dependencies).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub servers: Vec<DeviceId>,
+    /// Number of seconds after the recovery started during which no server is shut down
+    /// automatically.
+    #[serde(
+        default = "Recovery::default_grace_period",
+        deserialize_with = "duration::deserialize"
+    )]
+    #[schemars(with = "duration::Seconds")]
+    pub grace_period: u64,
+    /// Whether the start of `home-monitor-rs` is considered the end of a long offline period.
+    #[serde(default)]
+    pub on_startup: bool,
+}
+
+impl Recovery {
+    pub fn default_min_offline() -> u64 {
+        600
+    }
+
+    pub fn default_min_reappeared() -> u8 {
+        50
+    }
+
+    pub fn default_grace_period() -> u64 {
+        1800
+    }
+}
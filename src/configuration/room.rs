@@ -0,0 +1,17 @@
+use std::collections::HashMap;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::DeviceId;
+
+// NOTE: rooms share their IDs with devices so that they can be used as dependencies
+pub type RoomId = DeviceId;
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Room {
+    pub name: String,
+}
+
+pub type Rooms = HashMap<RoomId, Room>;
@@ -0,0 +1,129 @@
+use std::collections::HashMap;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::{duration, serialize_sorted, DeviceId};
+
+pub static TIME_PREFIX: &str = "time:";
+pub static CALENDAR_PREFIX: &str = "calendar:";
+pub static SUN_DAYLIGHT: &str = "sun:daylight";
+pub static SUN_NIGHT: &str = "sun:night";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Weekday {
+    Mon,
+    Tue,
+    Wed,
+    Thu,
+    Fri,
+    Sat,
+    Sun,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TimeWindow {
+    /// Start of the time window in the local time zone (HH:MM).
+    pub from: String,
+    /// End of the time window in the local time zone (HH:MM). A time window ending before it
+    /// starts spans midnight and one ending when it starts spans the whole day.
+    pub to: String,
+    /// Days of the week on which the time window starts (defaults to every day).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub days: Vec<Weekday>,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Location {
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+// NOTE: the coordinates of a location are never NaN
+impl Eq for Location {}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Calendar {
+    /// URL of the iCal calendar.
+    pub url: String,
+    /// Number of seconds between two downloads of the calendar.
+    #[serde(
+        default = "Calendar::default_interval",
+        deserialize_with = "duration::deserialize"
+    )]
+    #[schemars(with = "duration::Seconds")]
+    pub interval: u64,
+    /// Only events with any of these categories are considered (defaults to all events).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub categories: Vec<String>,
+}
+
+impl Calendar {
+    pub fn default_interval() -> u64 {
+        900
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Schedules {
+    /// Time windows which can be used as `time:<name>` dependencies.
+    #[serde(
+        default,
+        skip_serializing_if = "HashMap::is_empty",
+        serialize_with = "serialize_sorted"
+    )]
+    pub times: HashMap<String, TimeWindow>,
+    /// Location enabling the `sun:daylight` and `sun:night` dependencies.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<Location>,
+    /// iCal calendars which can be used as `calendar:<name>` dependencies.
+    #[serde(
+        default,
+        skip_serializing_if = "HashMap::is_empty",
+        serialize_with = "serialize_sorted"
+    )]
+    pub calendars: HashMap<String, Calendar>,
+}
+
+impl Schedules {
+    pub fn is_empty(&self) -> bool {
+        self.times.is_empty() && self.location.is_none() && self.calendars.is_empty()
+    }
+
+    /// Returns the IDs of all pseudo devices which can be used as dependencies.
+    pub fn ids(&self) -> Vec<DeviceId> {
+        let mut ids: Vec<DeviceId> = self
+            .times
+            .keys()
+            .map(|name| DeviceId(format!("{TIME_PREFIX}{name}")))
+            .collect();
+        ids.extend(
+            self.calendars
+                .keys()
+                .map(|name| DeviceId(format!("{CALENDAR_PREFIX}{name}"))),
+        );
+        if self.location.is_some() {
+            ids.push(DeviceId(SUN_DAYLIGHT.to_string()));
+            ids.push(DeviceId(SUN_NIGHT.to_string()));
+        }
+        ids.sort();
+
+        ids
+    }
+
+    pub fn contains(&self, id: &DeviceId) -> bool {
+        if let Some(name) = id.0.strip_prefix(TIME_PREFIX) {
+            return self.times.contains_key(name);
+        }
+        if let Some(name) = id.0.strip_prefix(CALENDAR_PREFIX) {
+            return self.calendars.contains_key(name);
+        }
+
+        self.location.is_some() && (id.0 == SUN_DAYLIGHT || id.0 == SUN_NIGHT)
+    }
+}
@@ -0,0 +1,43 @@
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Serialize, Serializer};
+
+/// Serializes a map sorted by its keys so that the output (e.g. of the web API or of the device
+/// store) doesn't change with the random iteration order of the map.
+pub fn serialize_sorted<K, V, S>(map: &HashMap<K, V>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    K: Ord + Serialize,
+    V: Serialize,
+    S: Serializer,
+{
+    serializer.collect_map(map.iter().collect::<BTreeMap<&K, &V>>())
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::*;
+    use serde::Serialize;
+
+    use super::*;
+
+    #[rstest]
+    fn test_serialize_sorted_orders_keys() {
+        #[derive(Serialize)]
+        struct Map {
+            #[serde(serialize_with = "serialize_sorted")]
+            map: HashMap<String, u32>,
+        }
+
+        let map = Map {
+            map: ["c", "a", "d", "b"]
+                .iter()
+                .enumerate()
+                .map(|(index, key)| (key.to_string(), index as u32))
+                .collect(),
+        };
+        assert_eq!(
+            serde_json::to_string(&map).unwrap(),
+            r#"{"map":{"a":1,"b":3,"c":0,"d":2}}"#
+        );
+    }
+}
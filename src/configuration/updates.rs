@@ -0,0 +1,36 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::duration;
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Updates {
+    #[serde(default = "Updates::default_check")]
+    pub check: bool,
+    #[serde(
+        default = "Updates::default_interval",
+        deserialize_with = "duration::deserialize"
+    )]
+    #[schemars(with = "duration::Seconds")]
+    pub interval: u64,
+}
+
+impl Updates {
+    pub fn default_check() -> bool {
+        true
+    }
+
+    pub fn default_interval() -> u64 {
+        24 * 60 * 60
+    }
+}
+
+impl Default for Updates {
+    fn default() -> Self {
+        Self {
+            check: Updates::default_check(),
+            interval: Updates::default_interval(),
+        }
+    }
+}
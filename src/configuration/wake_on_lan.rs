@@ -0,0 +1,32 @@
+use std::net::IpAddr;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WakeOnLan {
+    #[serde(default = "WakeOnLan::default_ip")]
+    pub ip: IpAddr,
+    #[serde(default)]
+    pub port: u16,
+    /// Broadcast address of another network segment to which received magic packets are relayed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub relay: Option<IpAddr>,
+}
+
+impl WakeOnLan {
+    pub fn default_ip() -> IpAddr {
+        "0.0.0.0".parse().unwrap()
+    }
+}
+
+impl Default for WakeOnLan {
+    fn default() -> Self {
+        Self {
+            ip: WakeOnLan::default_ip(),
+            port: 0,
+            relay: None,
+        }
+    }
+}
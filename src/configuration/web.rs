@@ -1,8 +1,79 @@
+use std::fmt;
 use std::net::IpAddr;
 
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use crate::utils::{Secret, REDACTED};
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RequestLog {
+    #[serde(default = "RequestLog::default_enabled")]
+    pub enabled: bool,
+    #[serde(default = "RequestLog::default_sample_every")]
+    pub sample_every: u64,
+}
+
+impl RequestLog {
+    pub fn default_enabled() -> bool {
+        true
+    }
+
+    pub fn default_sample_every() -> u64 {
+        1
+    }
+}
+
+impl Default for RequestLog {
+    fn default() -> Self {
+        Self {
+            enabled: RequestLog::default_enabled(),
+            sample_every: RequestLog::default_sample_every(),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BasicAuth {
+    pub username: String,
+    pub password: Secret,
+}
+
+impl fmt::Debug for BasicAuth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BasicAuth")
+            .field("username", &self.username)
+            .field("password", &REDACTED)
+            .finish()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Docs {
+    #[serde(default = "Docs::default_enabled")]
+    pub enabled: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth: Option<BasicAuth>,
+}
+
+impl Docs {
+    pub fn default_enabled() -> bool {
+        true
+    }
+}
+
+impl Default for Docs {
+    fn default() -> Self {
+        Self {
+            enabled: Docs::default_enabled(),
+            auth: None,
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Web {
@@ -10,6 +81,12 @@ pub struct Web {
     pub ip: IpAddr,
     #[serde(default)]
     pub port: u16,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub base_path: String,
+    #[serde(default)]
+    pub request_log: RequestLog,
+    #[serde(default)]
+    pub docs: Docs,
 }
 
 impl Web {
@@ -21,6 +98,16 @@ impl Web {
     pub fn default_ip() -> IpAddr {
         "0.0.0.0".parse().unwrap()
     }
+
+    /// Returns the base path with a leading but without a trailing slash (or an empty string).
+    pub fn normalized_base_path(&self) -> String {
+        let base_path = self.base_path.trim_matches('/');
+        if base_path.is_empty() {
+            String::new()
+        } else {
+            format!("/{base_path}")
+        }
+    }
 }
 
 impl Default for Web {
@@ -28,6 +115,32 @@ impl Default for Web {
         Self {
             ip: Web::default_ip(),
             port: 0,
+            base_path: String::new(),
+            request_log: RequestLog::default(),
+            docs: Docs::default(),
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use rstest::*;
+
+    use super::*;
+
+    #[rstest]
+    #[case("", "")]
+    #[case("/", "")]
+    #[case("home-monitor", "/home-monitor")]
+    #[case("/home-monitor", "/home-monitor")]
+    #[case("/home-monitor/", "/home-monitor")]
+    #[case("/apps/home-monitor/", "/apps/home-monitor")]
+    fn test_normalized_base_path(#[case] base_path: &str, #[case] expected: &str) {
+        let web = Web {
+            base_path: base_path.to_string(),
+            ..Default::default()
+        };
+
+        assert_eq!(web.normalized_base_path(), expected);
+    }
+}
@@ -1,11 +1,35 @@
-use std::path::Path;
+use std::collections::HashMap;
+use std::net::IpAddr;
 use std::sync::Arc;
+use std::time::Duration;
 
-use crate::dom::Server;
+use log::warn;
+use tokio::sync::Semaphore;
+
+use crate::configuration;
+use crate::dom::communication::{ControlSwitch, ExpectedTransition, InvalidCredentials, Standby};
+use crate::dom::device::WakeupRelay;
+use crate::dom::{DeviceId, Server};
+use crate::events::{Events, PublishedShutdownServer, PublishedWakeupServer};
+use crate::metrics::{MeteredShutdownServer, MeteredWakeupServer, Registry};
 use crate::networking::{
-    FastPinger, Pinger, ShutdownServer, Ssh2ShutdownServer, WakeOnLanServer, WakeupServer,
+    get_network_interface, ActionHooks, ActionQueue, ApiRelayWakeupServer, ArpFallbackPinger,
+    ArpResponders, CommandPinger, CredentialsShutdownServer, FastPinger, FritzboxRouterClients,
+    HookedShutdownServer, HookedWakeupServer, HttpChecker, LimitedPinger, LimitedShutdownServer,
+    LimitedWakeupServer, NetworkArpProber, OpenwrtRouterClients, Pinger, PortChecker, Presence,
+    PresencePinger, QueuedShutdownServer, QueuedWakeupServer, RouterClients, ShutdownServer,
+    SourcePinger, Ssh2RelayWakeupServer, Ssh2ShutdownServer, StandbyShutdownServer,
+    StandbyWakeupServer, TcpPortChecker, TimedShutdownServer, TimedWakeupServer,
+    UnifiRouterClients, WakeOnLanServer, WakeupServer,
+};
+use crate::notifications::{Notifier, Notifiers, Outbox, Templates, WebhookNotifier};
+use crate::utils::{
+    AlwaysOff, AlwaysOffFile, AlwaysOffMemory, AlwaysOn, AlwaysOnFile, AlwaysOnMemory, RateLimiter,
+    WakeupStagger,
 };
-use crate::utils::{AlwaysOff, AlwaysOffFile, AlwaysOn, AlwaysOnFile};
+
+/// Maximum time a single wakeup or shutdown action may take.
+pub const ACTION_TIMEOUT: Duration = Duration::from_secs(60);
 
 #[derive(Clone)]
 pub struct ServerControl {
@@ -15,42 +39,367 @@ pub struct ServerControl {
 
     pub always_off: Arc<dyn AlwaysOff>,
     pub always_on: Arc<dyn AlwaysOn>,
+
+    /// Groups the server belongs to which can keep it always off / on as a whole.
+    pub groups: Vec<GroupControl>,
+
+    /// Cause of the last successful wakeup / shutdown to classify the following transition.
+    pub expected_transition: Arc<ExpectedTransition>,
+
+    /// Disables the automatic wakeups / shutdowns after repeatedly failed shutdowns.
+    pub control_switch: Arc<ControlSwitch>,
+
+    /// Whether the server rejected the configured credentials (which suppresses further shutdowns).
+    pub invalid_credentials: Arc<InvalidCredentials>,
+}
+
+#[derive(Clone)]
+pub struct GroupControl {
+    pub id: DeviceId,
+    pub name: String,
+    pub servers: Vec<DeviceId>,
+
+    pub always_off: Arc<dyn AlwaysOff>,
+    pub always_on: Arc<dyn AlwaysOn>,
 }
 
 pub struct Factory {}
 
 impl Factory {
-    pub fn create_pinger(max_rtt: Option<u64>) -> Box<dyn Pinger> {
-        Box::new(FastPinger::new(max_rtt))
+    pub fn create_pinger(
+        max_rtt: Option<u64>,
+        interface: &str,
+        ping: &configuration::Ping,
+        limits: &configuration::Limits,
+        arp_responders: &Arc<ArpResponders>,
+        presence: &Option<Arc<Presence>>,
+        sources: &HashMap<IpAddr, IpAddr>,
+    ) -> Box<dyn Pinger> {
+        let command_pinger = || CommandPinger::new(Duration::from_secs(ping.timeout));
+        let pinger: Box<dyn Pinger> = match ping.method {
+            configuration::PingMethod::Command => {
+                Box::new(command_pinger().with_sources(sources.clone()))
+            }
+            configuration::PingMethod::Icmp => match FastPinger::new(max_rtt) {
+                // raw ICMP sockets can't be bound to the source addresses of single devices
+                Ok(pinger) if !sources.is_empty() => Box::new(SourcePinger::new(
+                    Box::new(pinger),
+                    command_pinger(),
+                    sources.clone(),
+                )),
+                Ok(pinger) => Box::new(pinger),
+                Err(e) => {
+                    warn!("{}, falling back to the ping command", e);
+                    Box::new(command_pinger().with_sources(sources.clone()))
+                }
+            },
+        };
+
+        let pinger: Box<dyn Pinger> = match ping.arp_fallback {
+            None => pinger,
+            Some(missed_pings) => match get_network_interface(interface) {
+                Ok(interface) => Box::new(ArpFallbackPinger::new(
+                    pinger,
+                    Box::new(NetworkArpProber::new(
+                        interface.clone(),
+                        Duration::from_secs(ping.timeout),
+                    )),
+                    arp_responders.clone(),
+                    missed_pings,
+                    interface.ips,
+                )),
+                Err(e) => {
+                    warn!("{}, not falling back to ARP requests", e);
+                    pinger
+                }
+            },
+        };
+
+        let pinger: Box<dyn Pinger> = match limits.max_pings_per_second {
+            0 => pinger,
+            max_pings_per_second => Box::new(LimitedPinger::new(
+                pinger,
+                Arc::new(RateLimiter::new(
+                    max_pings_per_second,
+                    Duration::from_secs(1),
+                )),
+            )),
+        };
+
+        match presence {
+            Some(presence) => Box::new(PresencePinger::new(pinger, presence.clone())),
+            None => pinger,
+        }
+    }
+
+    pub fn create_router_clients(
+        provider: &configuration::PresenceProvider,
+        proxy: Option<&str>,
+    ) -> Box<dyn RouterClients> {
+        match provider.router {
+            configuration::Router::Unifi => Box::new(UnifiRouterClients::new(provider, proxy)),
+            configuration::Router::Openwrt => Box::new(OpenwrtRouterClients::new(provider, proxy)),
+            configuration::Router::Fritzbox => {
+                Box::new(FritzboxRouterClients::new(provider, proxy))
+            }
+        }
     }
 
     pub fn create_shutdown_server(server: &Server) -> Arc<dyn ShutdownServer> {
         Arc::new(Ssh2ShutdownServer::new(server))
     }
 
+    /// Creates the SSH sessions shared by the shutdown servers of all servers (if limited).
+    pub fn create_ssh_sessions(network: &configuration::Network) -> Option<Arc<Semaphore>> {
+        match network.max_ssh_sessions {
+            0 => None,
+            max_ssh_sessions => Some(Arc::new(Semaphore::new(max_ssh_sessions))),
+        }
+    }
+
+    /// Creates the rate limit of the wakeups shared by the wakeup servers of all servers (if
+    /// limited).
+    pub fn create_wakeup_limit(limits: &configuration::Limits) -> Option<Arc<RateLimiter>> {
+        match limits.max_wakeups_per_minute {
+            0 => None,
+            max_wakeups_per_minute => Some(Arc::new(RateLimiter::new(
+                max_wakeups_per_minute,
+                Duration::from_secs(60),
+            ))),
+        }
+    }
+
+    /// Creates the staggering of the wakeups of all servers by the monitor (if limited).
+    pub fn create_wakeup_stagger(limits: &configuration::Limits) -> Option<Arc<WakeupStagger>> {
+        match limits.max_parallel_wakeups {
+            0 => None,
+            max_parallel_wakeups => Some(Arc::new(WakeupStagger::new(
+                max_parallel_wakeups,
+                Duration::from_secs(limits.wakeup_stagger_delay),
+            ))),
+        }
+    }
+
+    fn create_limited_wakeup_server(
+        server: &Server,
+        wakeup_limit: &Option<Arc<RateLimiter>>,
+    ) -> Arc<dyn WakeupServer> {
+        let wakeup_server = Self::create_wakeup_server(server);
+        let wakeup_server: Arc<dyn WakeupServer> = match wakeup_limit {
+            Some(wakeup_limit) => Arc::new(LimitedWakeupServer::new(
+                wakeup_server,
+                wakeup_limit.clone(),
+            )),
+            None => wakeup_server,
+        };
+
+        // a stuck wakeup must not block further wakeups of the server forever
+        match server.action_timeouts.wakeup {
+            Some(timeout) => Arc::new(TimedWakeupServer::new(wakeup_server, timeout)),
+            None => wakeup_server,
+        }
+    }
+
+    fn create_limited_shutdown_server(
+        server: &Server,
+        ssh_sessions: &Option<Arc<Semaphore>>,
+    ) -> Arc<dyn ShutdownServer> {
+        let shutdown_server = Self::create_shutdown_server(server);
+        let shutdown_server: Arc<dyn ShutdownServer> = match ssh_sessions {
+            Some(ssh_sessions) => Arc::new(LimitedShutdownServer::new(
+                shutdown_server,
+                ssh_sessions.clone(),
+            )),
+            None => shutdown_server,
+        };
+
+        // the timeout includes waiting for a free SSH session
+        match server.action_timeouts.shutdown {
+            Some(timeout) => Arc::new(TimedShutdownServer::new(shutdown_server, timeout)),
+            None => shutdown_server,
+        }
+    }
+
+    /// Creates a checker whether the server is online using its HTTP check or its SSH port.
+    pub fn create_online_checker(server: &Server) -> Arc<dyn PortChecker> {
+        match &server.machine.http {
+            Some(http) => Arc::new(HttpChecker::new(http)),
+            None => Arc::new(
+                TcpPortChecker::new(
+                    server.machine.ip,
+                    server.ssh.port.into(),
+                    Duration::from_secs(1),
+                )
+                .with_source(server.machine.source),
+            ),
+        }
+    }
+
     pub fn create_wakeup_server(server: &Server) -> Arc<dyn WakeupServer> {
-        Arc::new(WakeOnLanServer::new(server))
+        match &server.relay {
+            None => Arc::new(WakeOnLanServer::new(server)),
+            Some(WakeupRelay::Api(relay)) => Arc::new(ApiRelayWakeupServer::new(server, relay)),
+            Some(WakeupRelay::Ssh(relay)) => Arc::new(Ssh2RelayWakeupServer::new(server, relay)),
+        }
+    }
+
+    /// Creates the hooks run before and after the wakeups and shutdowns of all servers (if any).
+    pub fn create_action_hooks(
+        hooks: &[configuration::Hook],
+        proxy: Option<&str>,
+    ) -> Option<Arc<ActionHooks>> {
+        if hooks.is_empty() {
+            None
+        } else {
+            Some(Arc::new(ActionHooks::new(hooks, proxy)))
+        }
     }
 
-    pub fn create_always_off(root_path: &Path, server: &Server) -> Arc<dyn AlwaysOff> {
-        let mut path = root_path.to_path_buf();
-        path.push(server.machine.id.to_string());
-        Arc::new(AlwaysOffFile::new(&path))
+    pub fn create_notifier(
+        notifications: &configuration::Notifications,
+        proxy: Option<&str>,
+        outbox: Outbox,
+        metrics: &Arc<Registry>,
+    ) -> Arc<Notifiers> {
+        // the URL identifies the webhook of a queued notification
+        let notifiers = notifications
+            .webhooks
+            .iter()
+            .map(|webhook| {
+                (
+                    webhook.url.clone(),
+                    Box::new(WebhookNotifier::new(webhook, proxy)) as Box<dyn Notifier>,
+                )
+            })
+            .collect();
+
+        Arc::new(Notifiers::new(
+            notifiers,
+            Templates::new(notifications),
+            outbox,
+            metrics.clone(),
+        ))
+    }
+
+    // without a files API the always off / on state is only kept in memory
+    pub fn create_always_off(
+        files: &Option<configuration::Files>,
+        id: &DeviceId,
+    ) -> Arc<dyn AlwaysOff> {
+        match files {
+            Some(files) => Arc::new(AlwaysOffFile::new(&files.root.join(id.to_string()))),
+            None => Arc::new(AlwaysOffMemory::new()),
+        }
     }
 
-    pub fn create_always_on(root_path: &Path, server: &Server) -> Arc<dyn AlwaysOn> {
-        let mut path = root_path.to_path_buf();
-        path.push(server.machine.id.to_string());
-        Arc::new(AlwaysOnFile::new(&path))
+    pub fn create_always_on(
+        files: &Option<configuration::Files>,
+        id: &DeviceId,
+    ) -> Arc<dyn AlwaysOn> {
+        match files {
+            Some(files) => Arc::new(AlwaysOnFile::new(&files.root.join(id.to_string()))),
+            None => Arc::new(AlwaysOnMemory::new()),
+        }
+    }
+
+    pub fn create_group_control(
+        group_id: &configuration::GroupId,
+        group: &configuration::Group,
+        files: &Option<configuration::Files>,
+    ) -> GroupControl {
+        let id = DeviceId::from(group_id);
+        GroupControl {
+            always_off: Self::create_always_off(files, &id),
+            always_on: Self::create_always_on(files, &id),
+            id,
+            name: group.name.clone(),
+            servers: group.servers.iter().map(DeviceId::from).collect(),
+        }
     }
 
-    pub fn create_control(server: &Server, files_api_root_path: &Path) -> ServerControl {
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_control(
+        server: &Server,
+        group_controls: &[GroupControl],
+        files: &Option<configuration::Files>,
+        ssh_sessions: &Option<Arc<Semaphore>>,
+        wakeup_limit: &Option<Arc<RateLimiter>>,
+        hooks: &Option<Arc<ActionHooks>>,
+        standby: &Option<Arc<Standby>>,
+        duplicate_action_window: Duration,
+        metrics: &Arc<Registry>,
+        events: &Events,
+    ) -> ServerControl {
+        let mut wakeup_server = Self::create_limited_wakeup_server(server, wakeup_limit);
+        let mut shutdown_server = Self::create_limited_shutdown_server(server, ssh_sessions);
+        if let Some(hooks) = hooks {
+            wakeup_server = Arc::new(HookedWakeupServer::new(
+                server,
+                wakeup_server,
+                hooks.clone(),
+            ));
+            shutdown_server = Arc::new(HookedShutdownServer::new(
+                server,
+                shutdown_server,
+                hooks.clone(),
+            ));
+        }
+        // a standby instance doesn't control the servers (e.g. through the DNS trigger or the web
+        // API) as long as the active instance does
+        if let Some(standby) = standby {
+            wakeup_server = Arc::new(StandbyWakeupServer::new(wakeup_server, standby.clone()));
+            shutdown_server =
+                Arc::new(StandbyShutdownServer::new(shutdown_server, standby.clone()));
+        }
+        // don't run any hooks or SSH sessions for credentials which have already been rejected
+        let invalid_credentials = Arc::new(InvalidCredentials::new());
+        let shutdown_server = Arc::new(CredentialsShutdownServer::new(
+            &server.machine.name,
+            shutdown_server,
+            invalid_credentials.clone(),
+        ));
+
+        // wakeups and shutdowns requested from multiple places at once are only run once
+        let queue = Arc::new(ActionQueue::new(
+            &server.machine.name,
+            duplicate_action_window,
+        ));
         ServerControl {
             server: server.clone(),
-            wakeup: Self::create_wakeup_server(server),
-            shutdown: Self::create_shutdown_server(server),
-            always_off: Self::create_always_off(files_api_root_path, server),
-            always_on: Self::create_always_on(files_api_root_path, server),
+            wakeup: Arc::new(QueuedWakeupServer::new(
+                Arc::new(PublishedWakeupServer::new(
+                    &server.machine.id,
+                    Arc::new(MeteredWakeupServer::new(
+                        &server.machine.id,
+                        wakeup_server,
+                        metrics.clone(),
+                    )),
+                    events.clone(),
+                )),
+                queue.clone(),
+            )),
+            shutdown: Arc::new(QueuedShutdownServer::new(
+                Arc::new(PublishedShutdownServer::new(
+                    &server.machine.id,
+                    Arc::new(MeteredShutdownServer::new(
+                        &server.machine.id,
+                        shutdown_server,
+                        metrics.clone(),
+                    )),
+                    events.clone(),
+                )),
+                queue,
+            )),
+            always_off: Self::create_always_off(files, &server.machine.id),
+            always_on: Self::create_always_on(files, &server.machine.id),
+            groups: group_controls
+                .iter()
+                .filter(|group| group.servers.contains(&server.machine.id))
+                .cloned()
+                .collect(),
+            expected_transition: Arc::new(ExpectedTransition::new()),
+            control_switch: Arc::new(ControlSwitch::new()),
+            invalid_credentials,
         }
     }
 }
@@ -69,6 +418,8 @@ pub mod test {
 
         pub always_off: crate::utils::MockAlwaysOff,
         pub always_on: crate::utils::MockAlwaysOn,
+
+        pub groups: Vec<GroupControl>,
     }
 
     impl From<MockServerControl> for ServerControl {
@@ -79,6 +430,10 @@ pub mod test {
                 shutdown: Arc::new(mock_server_control.shutdown),
                 always_off: Arc::new(mock_server_control.always_off),
                 always_on: Arc::new(mock_server_control.always_on),
+                groups: mock_server_control.groups,
+                expected_transition: Arc::new(ExpectedTransition::new()),
+                control_switch: Arc::new(ControlSwitch::new()),
+                invalid_credentials: Arc::new(InvalidCredentials::new()),
             }
         }
     }
@@ -91,6 +446,22 @@ pub mod test {
             shutdown: crate::networking::MockShutdownServer::new(),
             always_off: crate::utils::MockAlwaysOff::new(),
             always_on: crate::utils::MockAlwaysOn::new(),
+            groups: Vec::new(),
+        }
+    }
+
+    pub fn mocked_group_control(
+        id: &str,
+        servers: Vec<DeviceId>,
+        always_off: crate::utils::MockAlwaysOff,
+        always_on: crate::utils::MockAlwaysOn,
+    ) -> GroupControl {
+        GroupControl {
+            id: id.parse().unwrap(),
+            name: id.to_string(),
+            servers,
+            always_off: Arc::new(always_off),
+            always_on: Arc::new(always_on),
         }
     }
 }
@@ -0,0 +1,543 @@
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use log::{debug, error, info, warn};
+use tokio::net::UdpSocket;
+use tokio_util::sync::CancellationToken;
+
+use crate::control::{ServerControl, ACTION_TIMEOUT};
+use crate::dom::communication::{Away, SharedStateMutex};
+use crate::dom::{ActionContext, DeviceId, TraceContext, TransitionCause};
+use crate::networking::ActionInProgress;
+use crate::utils::Instant;
+
+// don't send another wakeup for every single query while the server is booting
+const WAKEUP_INTERVAL: Duration = Duration::from_secs(60);
+
+const HEADER_SIZE: usize = 12;
+const MAX_QUERY_SIZE: usize = 512;
+const MAX_NAME_SIZE: usize = 255;
+
+const FLAG_RESPONSE: u16 = 0x8000;
+const FLAG_AUTHORITATIVE: u16 = 0x0400;
+const FLAG_RECURSION_DESIRED: u16 = 0x0100;
+const OPCODE_MASK: u16 = 0x7800;
+
+const RCODE_NO_ERROR: u16 = 0;
+const RCODE_FORMAT_ERROR: u16 = 1;
+const RCODE_REFUSED: u16 = 5;
+
+const TYPE_A: u16 = 1;
+const TYPE_AAAA: u16 = 28;
+const TYPE_ANY: u16 = 255;
+const CLASS_IN: u16 = 1;
+
+// compressed name pointing to the name of the question right after the header
+const NAME_POINTER: u16 = 0xc000 | HEADER_SIZE as u16;
+
+struct Question {
+    id: u16,
+    flags: u16,
+    name: String,
+    qtype: u16,
+    end: usize,
+}
+
+/// Stub DNS responder answering queries for the configured hostnames of servers with the IP
+/// address of the server and waking up the server in the background if it is offline.
+pub struct DnsTrigger {
+    socket: UdpSocket,
+    ttl: u32,
+    servers: Vec<ServerControl>,
+    shared_state: Arc<SharedStateMutex>,
+    wakeups: Mutex<HashMap<DeviceId, Instant>>,
+
+    away: Option<Arc<Away>>,
+    started: Instant,
+    startup_grace_period: Duration,
+}
+
+impl DnsTrigger {
+    pub async fn bind(
+        address: SocketAddr,
+        ttl: u32,
+        servers: Vec<ServerControl>,
+        shared_state: Arc<SharedStateMutex>,
+    ) -> std::io::Result<Self> {
+        let socket = UdpSocket::bind(address).await?;
+        let servers = servers
+            .into_iter()
+            .filter(|control| !control.server.hostnames.is_empty())
+            .collect();
+
+        Ok(Self {
+            socket,
+            ttl,
+            servers,
+            shared_state,
+            wakeups: Mutex::new(HashMap::new()),
+            away: None,
+            started: Instant::now(),
+            startup_grace_period: Duration::ZERO,
+        })
+    }
+
+    /// Only wakes up the servers with `keep_on_when_away` while the away mode is active.
+    pub fn set_away(&mut self, away: Arc<Away>) {
+        self.away = Some(away);
+    }
+
+    /// Doesn't wake up any servers until the startup grace period has elapsed.
+    pub fn set_startup_grace_period(&mut self, startup_grace_period: Duration) {
+        self.startup_grace_period = startup_grace_period;
+    }
+
+    #[cfg(test)]
+    pub fn local_addr(&self) -> std::io::Result<SocketAddr> {
+        self.socket.local_addr()
+    }
+
+    pub async fn serve(&self) {
+        let mut query = [0; MAX_QUERY_SIZE];
+        loop {
+            let (size, peer) = match self.socket.recv_from(&mut query).await {
+                Ok(received) => received,
+                Err(e) => {
+                    warn!("failed to receive DNS query: {}", e);
+                    continue;
+                }
+            };
+
+            let (response, server) = match self.resolve(&query[..size]) {
+                Some(resolved) => resolved,
+                None => {
+                    debug!("ignoring invalid DNS query from {}", peer);
+                    continue;
+                }
+            };
+
+            // answer right away and wake up the server in the background
+            if let Err(e) = self.socket.send_to(&response, peer).await {
+                warn!("failed to answer DNS query from {}: {}", peer, e);
+            }
+            if let Some(control) = server {
+                if self.should_wakeup(control) {
+                    Self::wakeup(control.clone(), peer);
+                }
+            }
+        }
+    }
+
+    /// Builds the response to the given query and returns the server the query was for (if any).
+    fn resolve(&self, query: &[u8]) -> Option<(Vec<u8>, Option<&ServerControl>)> {
+        if query.len() < HEADER_SIZE {
+            return None;
+        }
+
+        let question = match parse_question(query) {
+            Some(question) => question,
+            None => {
+                let id = u16::from_be_bytes([query[0], query[1]]);
+                let flags = u16::from_be_bytes([query[2], query[3]]);
+                return Some((build_header(id, flags, RCODE_FORMAT_ERROR, 0, 0), None));
+            }
+        };
+
+        let server = self.servers.iter().find(|control| {
+            control.server.hostnames.iter().any(|hostname| {
+                hostname
+                    .trim_end_matches('.')
+                    .eq_ignore_ascii_case(&question.name)
+            })
+        });
+        let control = match server {
+            Some(control) => control,
+            None => {
+                // leave names which aren't configured to other resolvers
+                debug!("refusing DNS query for unknown name {}", question.name);
+                let mut response = build_header(question.id, question.flags, RCODE_REFUSED, 1, 0);
+                response.extend_from_slice(&query[HEADER_SIZE..question.end]);
+                return Some((response, None));
+            }
+        };
+
+        let ip = control.server.machine.ip;
+        let rdata = match (question.qtype, ip) {
+            (TYPE_A | TYPE_ANY, IpAddr::V4(ip)) => Some((TYPE_A, ip.octets().to_vec())),
+            (TYPE_AAAA | TYPE_ANY, IpAddr::V6(ip)) => Some((TYPE_AAAA, ip.octets().to_vec())),
+            _ => None,
+        };
+        debug!(
+            "answering DNS query for {} with {}",
+            question.name,
+            if rdata.is_some() {
+                ip.to_string()
+            } else {
+                "no record".to_string()
+            }
+        );
+
+        let mut response = build_header(
+            question.id,
+            question.flags,
+            RCODE_NO_ERROR,
+            1,
+            rdata.is_some() as u16,
+        );
+        response.extend_from_slice(&query[HEADER_SIZE..question.end]);
+        if let Some((rtype, rdata)) = rdata {
+            response.extend_from_slice(&NAME_POINTER.to_be_bytes());
+            response.extend_from_slice(&rtype.to_be_bytes());
+            response.extend_from_slice(&CLASS_IN.to_be_bytes());
+            response.extend_from_slice(&self.ttl.to_be_bytes());
+            response.extend_from_slice(&(rdata.len() as u16).to_be_bytes());
+            response.extend_from_slice(&rdata);
+        }
+
+        Some((response, Some(control)))
+    }
+
+    /// Only wakes up a server which is offline, not kept off by ALWAYS OFF or the away mode and
+    /// hasn't been woken up recently (like the monitor not before the startup grace period has
+    /// elapsed).
+    fn should_wakeup(&self, control: &ServerControl) -> bool {
+        if self.started.elapsed() < self.startup_grace_period {
+            debug!(
+                "not waking up {} during the startup grace period",
+                control.server
+            );
+            return false;
+        }
+
+        let server_id = &control.server.machine.id;
+        let is_online = self
+            .shared_state
+            .lock()
+            .unwrap()
+            .get_devices()
+            .iter()
+            .any(|device| device.id() == server_id && device.is_online());
+        if is_online {
+            return false;
+        }
+
+        let always_off = control.always_off.is_always_off()
+            || control
+                .groups
+                .iter()
+                .any(|group| group.always_off.is_always_off());
+        if always_off {
+            debug!(
+                "not waking up {} because ALWAYS OFF is enabled",
+                control.server
+            );
+            return false;
+        }
+
+        let away = self.away.as_ref().is_some_and(|away| away.is_active());
+        if away && !control.server.keep_on_when_away {
+            debug!(
+                "not waking up {} because the away mode is active",
+                control.server
+            );
+            return false;
+        }
+
+        let mut wakeups = self.wakeups.lock().unwrap();
+        match wakeups.get(server_id) {
+            Some(last_wakeup) if last_wakeup.elapsed() < WAKEUP_INTERVAL => false,
+            _ => {
+                wakeups.insert(server_id.clone(), Instant::now());
+                true
+            }
+        }
+    }
+
+    fn wakeup(control: ServerControl, peer: SocketAddr) {
+        tokio::spawn(async move {
+            let context = ActionContext::new(
+                TraceContext::new(),
+                CancellationToken::new(),
+                ACTION_TIMEOUT,
+            );
+            info!(
+                "[{}] waking up {} resolved by {}...",
+                context,
+                control.server,
+                peer.ip()
+            );
+            match control.wakeup.wakeup(&context).await {
+                Ok(report) => {
+                    info!("[{}] woke up {}: {}", context, control.server, report);
+                    control
+                        .expected_transition
+                        .expect(true, TransitionCause::Monitor);
+                }
+                Err(e) if e.downcast_ref::<ActionInProgress>().is_some() => {
+                    info!("[{}] not waking up {}: {}", context, control.server, e)
+                }
+                Err(e) => error!("[{}] failed to wake up {}: {}", context, control.server, e),
+            }
+        });
+    }
+}
+
+fn parse_question(query: &[u8]) -> Option<Question> {
+    let id = u16::from_be_bytes([query[0], query[1]]);
+    let flags = u16::from_be_bytes([query[2], query[3]]);
+    let question_count = u16::from_be_bytes([query[4], query[5]]);
+    if flags & FLAG_RESPONSE != 0 || flags & OPCODE_MASK != 0 || question_count != 1 {
+        return None;
+    }
+
+    let mut labels = Vec::new();
+    let mut position = HEADER_SIZE;
+    loop {
+        let length = *query.get(position)? as usize;
+        position += 1;
+        if length == 0 {
+            break;
+        }
+        // compressed names are not expected in queries
+        if length > 63 {
+            return None;
+        }
+
+        let label = std::str::from_utf8(query.get(position..position + length)?).ok()?;
+        labels.push(label.to_ascii_lowercase());
+        position += length;
+        if position - HEADER_SIZE > MAX_NAME_SIZE {
+            return None;
+        }
+    }
+
+    let qtype = u16::from_be_bytes([*query.get(position)?, *query.get(position + 1)?]);
+    // skip the type and the class of the question
+    let end = position + 4;
+    if query.len() < end {
+        return None;
+    }
+
+    Some(Question {
+        id,
+        flags,
+        name: labels.join("."),
+        qtype,
+        end,
+    })
+}
+
+fn build_header(id: u16, query_flags: u16, rcode: u16, questions: u16, answers: u16) -> Vec<u8> {
+    let flags = FLAG_RESPONSE | FLAG_AUTHORITATIVE | (query_flags & FLAG_RECURSION_DESIRED) | rcode;
+
+    let mut header = Vec::with_capacity(MAX_QUERY_SIZE);
+    header.extend_from_slice(&id.to_be_bytes());
+    header.extend_from_slice(&flags.to_be_bytes());
+    header.extend_from_slice(&questions.to_be_bytes());
+    header.extend_from_slice(&answers.to_be_bytes());
+    // no authority and additional records
+    header.extend_from_slice(&[0, 0, 0, 0]);
+    header
+}
+
+#[cfg(test)]
+mod tests {
+
+    use rstest::*;
+    use tokio::sync::mpsc;
+    use tokio::time::timeout;
+
+    use super::*;
+    use crate::control::test::*;
+    use crate::dom::communication::SharedState;
+    use crate::dom::device::test::*;
+    use crate::dom::{Device, Server};
+    use crate::networking::ActionReport;
+
+    static HOSTNAME: &str = "nas.home";
+    static TTL: u32 = 60;
+
+    fn query(name: &str, qtype: u16) -> Vec<u8> {
+        let mut query = vec![0x12, 0x34, 0x01, 0x00, 0, 1, 0, 0, 0, 0, 0, 0];
+        for label in name.split('.') {
+            query.push(label.len() as u8);
+            query.extend_from_slice(label.as_bytes());
+        }
+        query.push(0);
+        query.extend_from_slice(&qtype.to_be_bytes());
+        query.extend_from_slice(&CLASS_IN.to_be_bytes());
+        query
+    }
+
+    fn rcode(response: &[u8]) -> u16 {
+        u16::from_be_bytes([response[2], response[3]]) & 0x000f
+    }
+
+    fn answers(response: &[u8]) -> u16 {
+        u16::from_be_bytes([response[6], response[7]])
+    }
+
+    #[fixture]
+    fn control(mut mocked_server_control: MockServerControl) -> MockServerControl {
+        mocked_server_control.server.hostnames = vec![HOSTNAME.to_string()];
+        mocked_server_control
+            .always_off
+            .expect_is_always_off()
+            .returning(|| false);
+        mocked_server_control
+    }
+
+    #[fixture]
+    fn shared_state(server: Server) -> Arc<SharedStateMutex> {
+        Arc::new(Mutex::new(SharedState::new(vec![Device::Server(server)])))
+    }
+
+    async fn bind(control: MockServerControl, shared_state: Arc<SharedStateMutex>) -> DnsTrigger {
+        DnsTrigger::bind(
+            "127.0.0.1:0".parse().unwrap(),
+            TTL,
+            vec![ServerControl::from(control)],
+            shared_state,
+        )
+        .await
+        .unwrap()
+    }
+
+    #[rstest]
+    #[case(query(HOSTNAME, TYPE_A), RCODE_NO_ERROR, 1, true)]
+    #[case(query("NAS.Home", TYPE_A), RCODE_NO_ERROR, 1, true)]
+    #[case(query(HOSTNAME, TYPE_AAAA), RCODE_NO_ERROR, 0, true)]
+    #[case(query("printer.home", TYPE_A), RCODE_REFUSED, 0, false)]
+    #[case(query(HOSTNAME, TYPE_A)[..HEADER_SIZE + 4].to_vec(), RCODE_FORMAT_ERROR, 0, false)]
+    #[tokio::test]
+    async fn test_dns_trigger_resolves_queries(
+        control: MockServerControl,
+        shared_state: Arc<SharedStateMutex>,
+        #[case] query: Vec<u8>,
+        #[case] expected_rcode: u16,
+        #[case] expected_answers: u16,
+        #[case] expected_server: bool,
+    ) {
+        let dns_trigger = bind(control, shared_state).await;
+
+        let (response, server) = dns_trigger.resolve(&query).unwrap();
+
+        assert_eq!(&response[..2], &query[..2]);
+        assert_eq!(rcode(&response), expected_rcode);
+        assert_eq!(answers(&response), expected_answers);
+        assert_eq!(server.is_some(), expected_server);
+        if expected_answers > 0 {
+            // the answer ends with the TTL, the length and the address of the server
+            let answer = &response[response.len() - 10..];
+            assert_eq!(&answer[..4], &TTL.to_be_bytes());
+            assert_eq!(&answer[4..6], &[0, 4]);
+            assert_eq!(&answer[6..], &[10, 0, 0, 1]);
+        }
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_dns_trigger_ignores_truncated_queries(
+        control: MockServerControl,
+        shared_state: Arc<SharedStateMutex>,
+    ) {
+        let dns_trigger = bind(control, shared_state).await;
+
+        assert!(dns_trigger.resolve(&[0x12, 0x34]).is_none());
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_dns_trigger_wakes_up_server_once(
+        mut control: MockServerControl,
+        shared_state: Arc<SharedStateMutex>,
+    ) {
+        // SETUP
+        let (wakeup_tx, mut wakeup_rx) = mpsc::unbounded_channel();
+        control.wakeup.expect_wakeup().returning(move |_| {
+            wakeup_tx.send(()).unwrap();
+            Ok(ActionReport::default())
+        });
+
+        let dns_trigger = bind(control, shared_state).await;
+        let address = dns_trigger.local_addr().unwrap();
+        tokio::spawn(async move { dns_trigger.serve().await });
+
+        let client = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let mut response = [0; MAX_QUERY_SIZE];
+
+        // TESTING
+        // the first query is answered and wakes up the server
+        client
+            .send_to(&query(HOSTNAME, TYPE_A), address)
+            .await
+            .unwrap();
+        let size = client.recv(&mut response).await.unwrap();
+        assert_eq!(answers(&response[..size]), 1);
+        timeout(Duration::from_secs(1), wakeup_rx.recv())
+            .await
+            .unwrap();
+
+        // the second query is only answered
+        client
+            .send_to(&query(HOSTNAME, TYPE_A), address)
+            .await
+            .unwrap();
+        let size = client.recv(&mut response).await.unwrap();
+        assert_eq!(answers(&response[..size]), 1);
+        assert!(timeout(Duration::from_millis(100), wakeup_rx.recv())
+            .await
+            .is_err());
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_dns_trigger_doesnt_wake_up_server_during_startup_grace_period(
+        control: MockServerControl,
+        shared_state: Arc<SharedStateMutex>,
+    ) {
+        let startup_grace_period = Duration::from_secs(10);
+        let mut dns_trigger = bind(control, shared_state).await;
+        dns_trigger.set_startup_grace_period(startup_grace_period);
+        let control = dns_trigger.servers[0].clone();
+
+        assert!(!dns_trigger.should_wakeup(&control));
+
+        dns_trigger.started = Instant::now() - startup_grace_period;
+        assert!(dns_trigger.should_wakeup(&control));
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_dns_trigger_doesnt_wake_up_server_while_away(
+        control: MockServerControl,
+        shared_state: Arc<SharedStateMutex>,
+    ) {
+        let mut dns_trigger = bind(control, shared_state).await;
+        let away = Arc::new(Away::new(true));
+        dns_trigger.set_away(away.clone());
+        let control = dns_trigger.servers[0].clone();
+
+        assert!(!dns_trigger.should_wakeup(&control));
+
+        // the server is woken up as soon as the away mode ends
+        away.set(false);
+        assert!(dns_trigger.should_wakeup(&control));
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_dns_trigger_wakes_up_server_kept_on_while_away(
+        mut control: MockServerControl,
+        shared_state: Arc<SharedStateMutex>,
+    ) {
+        control.server.keep_on_when_away = true;
+        let mut dns_trigger = bind(control, shared_state).await;
+        dns_trigger.set_away(Arc::new(Away::new(true)));
+        let control = dns_trigger.servers[0].clone();
+
+        assert!(dns_trigger.should_wakeup(&control));
+    }
+}
@@ -0,0 +1,36 @@
+use std::fmt;
+use std::time::Duration;
+
+use tokio::time::Instant;
+use tokio_util::sync::CancellationToken;
+
+use super::TraceContext;
+
+#[derive(Clone, Debug)]
+pub struct ActionContext {
+    pub trace: TraceContext,
+    pub cancellation: CancellationToken,
+    pub deadline: Instant,
+}
+
+impl ActionContext {
+    pub fn new(trace: TraceContext, cancellation: CancellationToken, timeout: Duration) -> Self {
+        Self {
+            trace,
+            cancellation,
+            deadline: Instant::now() + timeout,
+        }
+    }
+
+    /// Returns a context for a part of the action which can be cancelled on its own and has
+    /// to finish within the given timeout.
+    pub fn with_timeout(&self, timeout: Duration) -> Self {
+        Self::new(self.trace.clone(), self.cancellation.child_token(), timeout)
+    }
+}
+
+impl fmt::Display for ActionContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.trace.fmt(f)
+    }
+}
@@ -0,0 +1,304 @@
+use std::sync::{Arc, RwLock};
+
+use anyhow::{anyhow, bail};
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CalendarEvent {
+    pub summary: String,
+    pub categories: Vec<String>,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+impl CalendarEvent {
+    pub fn is_ongoing(&self, now: &DateTime<Utc>) -> bool {
+        self.start <= *now && *now < self.end
+    }
+}
+
+/// Date or date-time of an iCal property (all-day events only have a date).
+enum IcalTime {
+    Date(DateTime<Utc>),
+    DateTime(DateTime<Utc>),
+}
+
+impl IcalTime {
+    fn time(&self) -> DateTime<Utc> {
+        match self {
+            Self::Date(time) => *time,
+            Self::DateTime(time) => *time,
+        }
+    }
+}
+
+fn local_to_utc(time: &NaiveDateTime) -> anyhow::Result<DateTime<Utc>> {
+    Local
+        .from_local_datetime(time)
+        .earliest()
+        .map(|time| time.with_timezone(&Utc))
+        .ok_or_else(|| anyhow!("{} doesn't exist in the local time zone", time))
+}
+
+// NOTE: times with a TZID are interpreted in the local time zone
+fn parse_time(value: &str) -> anyhow::Result<IcalTime> {
+    if let Some(utc) = value.strip_suffix('Z') {
+        let time = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S")?;
+        return Ok(IcalTime::DateTime(Utc.from_utc_datetime(&time)));
+    }
+    if value.contains('T') {
+        let time = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S")?;
+        return Ok(IcalTime::DateTime(local_to_utc(&time)?));
+    }
+
+    let date = NaiveDate::parse_from_str(value, "%Y%m%d")?;
+    Ok(IcalTime::Date(local_to_utc(
+        &date.and_hms_opt(0, 0, 0).unwrap(),
+    )?))
+}
+
+fn parse_duration(value: &str) -> anyhow::Result<Duration> {
+    let (negative, value) = match value.strip_prefix('-') {
+        Some(value) => (true, value),
+        None => (false, value.trim_start_matches('+')),
+    };
+    let value = match value.strip_prefix('P') {
+        Some(value) => value,
+        None => bail!("invalid duration {}", value),
+    };
+
+    let mut duration = Duration::zero();
+    let mut number = String::new();
+    for c in value.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+        if c == 'T' {
+            continue;
+        }
+
+        let amount: i64 = number.parse()?;
+        number.clear();
+        duration = duration
+            + match c {
+                'W' => Duration::weeks(amount),
+                'D' => Duration::days(amount),
+                'H' => Duration::hours(amount),
+                'M' => Duration::minutes(amount),
+                'S' => Duration::seconds(amount),
+                _ => bail!("invalid duration {}", value),
+            };
+    }
+
+    Ok(if negative { -duration } else { duration })
+}
+
+fn parse_event(properties: &[(String, String)]) -> anyhow::Result<Option<CalendarEvent>> {
+    let mut summary = String::new();
+    let mut categories = Vec::new();
+    let mut start = None;
+    let mut end = None;
+    let mut duration = None;
+    for (name, value) in properties.iter() {
+        match name.as_str() {
+            "SUMMARY" => summary = value.clone(),
+            "CATEGORIES" => categories.extend(value.split(',').map(|c| c.trim().to_string())),
+            "DTSTART" => start = Some(parse_time(value)?),
+            "DTEND" => end = Some(parse_time(value)?.time()),
+            "DURATION" => duration = Some(parse_duration(value)?),
+            // cancelled events are ignored
+            "STATUS" if value == "CANCELLED" => return Ok(None),
+            _ => {}
+        }
+    }
+
+    let start = match start {
+        Some(start) => start,
+        None => bail!("event {} without DTSTART", summary),
+    };
+    // events without an end last for their duration or a day (all-day events) or no time at all
+    let end = match (end, duration, &start) {
+        (Some(end), _, _) => end,
+        (None, Some(duration), start) => start.time() + duration,
+        (None, None, IcalTime::Date(start)) => *start + Duration::days(1),
+        (None, None, IcalTime::DateTime(start)) => *start,
+    };
+
+    Ok(Some(CalendarEvent {
+        summary,
+        categories,
+        start: start.time(),
+        end,
+    }))
+}
+
+/// Parses all (non-recurring) events of the given iCal calendar.
+pub fn parse_ical(ical: &str) -> anyhow::Result<Vec<CalendarEvent>> {
+    // unfold lines continued on the next line (starting with a space or a tab)
+    let mut lines: Vec<String> = Vec::new();
+    for line in ical.lines() {
+        match (
+            line.strip_prefix(' ').or_else(|| line.strip_prefix('\t')),
+            lines.last_mut(),
+        ) {
+            (Some(continuation), Some(last)) => last.push_str(continuation),
+            _ => lines.push(line.to_string()),
+        }
+    }
+
+    let mut events = Vec::new();
+    let mut event: Option<Vec<(String, String)>> = None;
+    for line in lines.iter() {
+        let (name, value) = match line.split_once(':') {
+            Some((name, value)) => (name, value),
+            None => continue,
+        };
+        // ignore the parameters of the property (e.g. TZID or VALUE=DATE)
+        let name = name.split(';').next().unwrap().to_ascii_uppercase();
+
+        match (name.as_str(), value, &mut event) {
+            ("BEGIN", "VEVENT", _) => event = Some(Vec::new()),
+            ("END", "VEVENT", Some(properties)) => {
+                if let Some(parsed) = parse_event(properties)? {
+                    events.push(parsed);
+                }
+                event = None;
+            }
+            (_, _, Some(properties)) => properties.push((name, value.to_string())),
+            _ => {}
+        }
+    }
+
+    Ok(events)
+}
+
+/// Events of a calendar which are updated periodically.
+#[derive(Clone, Debug, Default)]
+pub struct Calendar {
+    categories: Vec<String>,
+    events: Arc<RwLock<Vec<CalendarEvent>>>,
+}
+
+impl Calendar {
+    pub fn new(categories: &[String]) -> Self {
+        Self {
+            categories: categories.to_vec(),
+            events: Arc::new(RwLock::new(Vec::new())),
+        }
+    }
+
+    pub fn set_events(&self, events: Vec<CalendarEvent>) {
+        *self.events.write().unwrap() = events;
+    }
+
+    /// Checks whether any (matching) event of the calendar is ongoing.
+    pub fn is_active(&self, now: &DateTime<Utc>) -> bool {
+        self.events.read().unwrap().iter().any(|event| {
+            event.is_ongoing(now)
+                && (self.categories.is_empty()
+                    || event.categories.iter().any(|category| {
+                        self.categories
+                            .iter()
+                            .any(|expected| category.eq_ignore_ascii_case(expected))
+                    }))
+        })
+    }
+}
+
+// calendars are shared and therefore only equal to themselves
+impl PartialEq for Calendar {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.events, &other.events)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::*;
+
+    use super::*;
+
+    static ICAL: &str = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+BEGIN:VEVENT\r
+SUMMARY:Nightly\r
+  backup\r
+CATEGORIES:BACKUP,Maintenance\r
+DTSTART:20230621T220000Z\r
+DTEND:20230622T020000Z\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+SUMMARY:Movie night\r
+DTSTART:20230623T180000Z\r
+DURATION:PT2H30M\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+SUMMARY:Cancelled\r
+STATUS:CANCELLED\r
+DTSTART:20230624T180000Z\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+
+    fn utc(time: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(time)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    #[rstest]
+    fn test_parse_ical() {
+        let events = parse_ical(ICAL).unwrap();
+
+        assert_eq!(
+            events,
+            vec![
+                CalendarEvent {
+                    summary: "Nightly backup".to_string(),
+                    categories: vec!["BACKUP".to_string(), "Maintenance".to_string()],
+                    start: utc("2023-06-21T22:00:00Z"),
+                    end: utc("2023-06-22T02:00:00Z"),
+                },
+                CalendarEvent {
+                    summary: "Movie night".to_string(),
+                    categories: Vec::new(),
+                    start: utc("2023-06-23T18:00:00Z"),
+                    end: utc("2023-06-23T20:30:00Z"),
+                },
+            ]
+        );
+    }
+
+    #[rstest]
+    fn test_parse_ical_all_day_event_lasts_a_day() {
+        let events =
+            parse_ical("BEGIN:VEVENT\nSUMMARY:Holiday\nDTSTART;VALUE=DATE:20230621\nEND:VEVENT\n")
+                .unwrap();
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].end - events[0].start, Duration::days(1));
+    }
+
+    #[rstest]
+    fn test_parse_ical_fails_without_start() {
+        assert!(parse_ical("BEGIN:VEVENT\nSUMMARY:Broken\nEND:VEVENT\n").is_err());
+    }
+
+    #[rstest]
+    #[case(vec![], "2023-06-21T23:00:00Z", true)]
+    #[case(vec![], "2023-06-23T19:00:00Z", true)]
+    #[case(vec![], "2023-06-22T02:00:00Z", false)]
+    #[case(vec!["backup".to_string()], "2023-06-21T23:00:00Z", true)]
+    #[case(vec!["backup".to_string()], "2023-06-23T19:00:00Z", false)]
+    fn test_calendar_is_active_during_matching_events(
+        #[case] categories: Vec<String>,
+        #[case] now: &str,
+        #[case] expected: bool,
+    ) {
+        let calendar = Calendar::new(&categories);
+        calendar.set_events(parse_ical(ICAL).unwrap());
+
+        assert_eq!(calendar.is_active(&utc(now)), expected);
+    }
+}
@@ -0,0 +1,46 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Global away mode (e.g. during a vacation) which keeps only the designated servers online and
+/// shuts down all other servers independent of their dependencies.
+#[derive(Debug, Default)]
+pub struct Away {
+    active: AtomicBool,
+}
+
+impl Away {
+    pub fn new(active: bool) -> Self {
+        Self {
+            active: AtomicBool::new(active),
+        }
+    }
+
+    /// Activates / deactivates the away mode and returns whether it has changed.
+    pub fn set(&self, active: bool) -> bool {
+        self.active.swap(active, Ordering::SeqCst) != active
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.active.load(Ordering::SeqCst)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::*;
+
+    use super::*;
+
+    #[rstest]
+    fn test_away_activates_and_deactivates() {
+        let away = Away::new(false);
+        assert!(!away.is_active());
+        assert!(!away.set(false));
+
+        assert!(away.set(true));
+        assert!(away.is_active());
+        assert!(!away.set(true));
+
+        assert!(away.set(false));
+        assert!(!away.is_active());
+    }
+}
@@ -0,0 +1,47 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Disables the automatic wakeups / shutdowns of a server after repeatedly failed shutdowns until
+/// they are explicitly re-enabled through the web API.
+#[derive(Debug, Default)]
+pub struct ControlSwitch {
+    disabled: AtomicBool,
+}
+
+impl ControlSwitch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn disable(&self) {
+        self.disabled.store(true, Ordering::SeqCst);
+    }
+
+    /// Re-enables the control of the server and returns whether it has been disabled.
+    pub fn enable(&self) -> bool {
+        self.disabled.swap(false, Ordering::SeqCst)
+    }
+
+    pub fn is_disabled(&self) -> bool {
+        self.disabled.load(Ordering::SeqCst)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::*;
+
+    use super::*;
+
+    #[rstest]
+    fn test_control_switch_disables_and_enables_control() {
+        let switch = ControlSwitch::new();
+        assert!(!switch.is_disabled());
+        assert!(!switch.enable());
+
+        switch.disable();
+        assert!(switch.is_disabled());
+
+        assert!(switch.enable());
+        assert!(!switch.is_disabled());
+    }
+}
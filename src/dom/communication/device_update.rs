@@ -0,0 +1,19 @@
+use std::sync::Arc;
+
+use super::super::{Device, DeviceId};
+
+#[derive(Clone, Debug)]
+pub enum DeviceUpdate {
+    // the same snapshot of the device is shared by all subscribers
+    Changed(Arc<Device>),
+    Removed(DeviceId),
+}
+
+impl DeviceUpdate {
+    pub fn device_id(&self) -> &DeviceId {
+        match self {
+            DeviceUpdate::Changed(device) => device.id(),
+            DeviceUpdate::Removed(device_id) => device_id,
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use std::sync::Mutex;
+use std::time::Duration;
+
+use super::super::TransitionCause;
+use crate::utils::Instant;
+
+// a wakeup / shutdown which didn't take effect within this time didn't cause a later transition
+const EXPECTATION_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Remembers who successfully woke up / shut down a server so that the monitor can classify the
+/// following change of its online state.
+#[derive(Debug, Default)]
+pub struct ExpectedTransition {
+    expected: Mutex<Option<(bool, TransitionCause, Instant)>>,
+}
+
+impl ExpectedTransition {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Expects the server to come online / go offline because of the given cause.
+    pub fn expect(&self, is_online: bool, cause: TransitionCause) {
+        *self.expected.lock().unwrap() = Some((is_online, cause, Instant::now()));
+    }
+
+    /// Returns the probable cause of the server having come online / gone offline.
+    pub fn classify(&self, is_online: bool) -> TransitionCause {
+        match self.expected.lock().unwrap().take() {
+            Some((expected_online, cause, since))
+                if expected_online == is_online && since.elapsed() <= EXPECTATION_TIMEOUT =>
+            {
+                cause
+            }
+            _ => TransitionCause::External,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::*;
+
+    use super::*;
+
+    #[rstest]
+    fn test_expected_transition_classifies_transitions() {
+        let expected = ExpectedTransition::new();
+
+        // nobody woke up the server
+        assert_eq!(expected.classify(true), TransitionCause::External);
+
+        expected.expect(true, TransitionCause::Api);
+        assert_eq!(expected.classify(true), TransitionCause::Api);
+        // the expectation is only used once
+        assert_eq!(expected.classify(false), TransitionCause::External);
+
+        // the server went offline although it has been woken up
+        expected.expect(true, TransitionCause::Monitor);
+        assert_eq!(expected.classify(false), TransitionCause::External);
+        assert_eq!(expected.classify(true), TransitionCause::External);
+    }
+}
@@ -0,0 +1,115 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use chrono::{DateTime, TimeZone, Utc};
+
+use super::super::{DeviceId, Schedule};
+
+/// One-off window set through the web API during which a device being offline is ignored.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct IgnoreWindow {
+    pub from: DateTime<Utc>,
+    pub until: DateTime<Utc>,
+}
+
+impl IgnoreWindow {
+    pub fn is_active<Tz: TimeZone>(&self, now: &DateTime<Tz>) -> bool {
+        let now = now.with_timezone(&Utc);
+        now >= self.from && now < self.until
+    }
+}
+
+/// Keeps track of the configured (recurring) and the one-off windows during which devices being
+/// offline is neither reported nor held against the servers depending on them.
+pub struct IgnoreWindows {
+    schedules: HashMap<DeviceId, Vec<Schedule>>,
+    windows: Mutex<HashMap<DeviceId, IgnoreWindow>>,
+}
+
+impl IgnoreWindows {
+    pub fn new(schedules: HashMap<DeviceId, Vec<Schedule>>) -> Self {
+        Self {
+            schedules,
+            windows: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Sets (or replaces) the one-off window of the given device.
+    pub fn set(&self, device_id: &DeviceId, window: IgnoreWindow) {
+        self.windows
+            .lock()
+            .unwrap()
+            .insert(device_id.clone(), window);
+    }
+
+    /// Removes the one-off window of the given device (if there is one).
+    pub fn remove(&self, device_id: &DeviceId) -> Option<IgnoreWindow> {
+        self.windows.lock().unwrap().remove(device_id)
+    }
+
+    /// Whether any configured schedule or the one-off window of the given device is active.
+    pub fn is_active<Tz: TimeZone>(&self, device_id: &DeviceId, now: &DateTime<Tz>) -> bool {
+        let mut windows = self.windows.lock().unwrap();
+        // forget one-off windows once they are over
+        if windows
+            .get(device_id)
+            .is_some_and(|window| window.until <= now.with_timezone(&Utc))
+        {
+            windows.remove(device_id);
+        }
+
+        windows
+            .get(device_id)
+            .is_some_and(|window| window.is_active(now))
+            || self
+                .schedules
+                .get(device_id)
+                .is_some_and(|schedules| schedules.iter().any(|schedule| schedule.is_active(now)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, NaiveTime};
+    use rstest::*;
+
+    use super::*;
+    use crate::dom::device::test::*;
+
+    #[rstest]
+    fn test_ignore_windows_are_active_during_one_off_window(machine_id: DeviceId) {
+        let ignore_windows = IgnoreWindows::new(HashMap::new());
+        let now = Utc::now();
+        assert!(!ignore_windows.is_active(&machine_id, &now));
+
+        ignore_windows.set(
+            &machine_id,
+            IgnoreWindow {
+                from: now + Duration::minutes(10),
+                until: now + Duration::minutes(20),
+            },
+        );
+        assert!(!ignore_windows.is_active(&machine_id, &now));
+        assert!(ignore_windows.is_active(&machine_id, &(now + Duration::minutes(15))));
+
+        // the window is forgotten once it is over
+        assert!(!ignore_windows.is_active(&machine_id, &(now + Duration::minutes(20))));
+        assert_eq!(ignore_windows.remove(&machine_id), None);
+    }
+
+    #[rstest]
+    fn test_ignore_windows_are_active_during_schedule(machine_id: DeviceId) {
+        let schedule = Schedule::Time {
+            id: "time:reboot".parse().unwrap(),
+            from: NaiveTime::from_hms_opt(3, 0, 0).unwrap(),
+            to: NaiveTime::from_hms_opt(4, 0, 0).unwrap(),
+            days: Vec::new(),
+        };
+        let ignore_windows =
+            IgnoreWindows::new(HashMap::from([(machine_id.clone(), vec![schedule])]));
+
+        let at = |hour| Utc.with_ymd_and_hms(2024, 1, 1, hour, 30, 0).unwrap();
+        assert!(ignore_windows.is_active(&machine_id, &at(3)));
+        assert!(!ignore_windows.is_active(&machine_id, &at(4)));
+    }
+}
@@ -0,0 +1,42 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Remembers that a server rejected its configured credentials so that no further shutdowns are
+/// attempted with them until the configuration has been updated.
+#[derive(Debug, Default)]
+pub struct InvalidCredentials {
+    invalid: AtomicBool,
+}
+
+impl InvalidCredentials {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Marks the credentials as invalid and returns whether they have been considered valid.
+    pub fn invalidate(&self) -> bool {
+        !self.invalid.swap(true, Ordering::SeqCst)
+    }
+
+    pub fn are_invalid(&self) -> bool {
+        self.invalid.load(Ordering::SeqCst)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::*;
+
+    use super::*;
+
+    #[rstest]
+    fn test_invalid_credentials_are_only_invalidated_once() {
+        let credentials = InvalidCredentials::new();
+        assert!(!credentials.are_invalid());
+
+        assert!(credentials.invalidate());
+        assert!(credentials.are_invalid());
+
+        assert!(!credentials.invalidate());
+        assert!(credentials.are_invalid());
+    }
+}
@@ -1,21 +1,95 @@
+use std::sync::{Arc, Mutex};
+
+mod away;
+mod control_switch;
+mod device_update;
+mod expected_transition;
+mod ignore_windows;
+mod invalid_credentials;
+mod monitor_stats;
+mod mpsc_receiver;
 mod mpsc_sender;
 mod noop_sender;
+mod overflow;
+mod profiles;
+mod readiness;
+mod runtime_stats;
 mod sender;
 mod shared_state;
+mod standby;
+mod sync_stats;
+mod update_coalescer;
 
+pub use away::Away;
+pub use control_switch::ControlSwitch;
+pub use device_update::DeviceUpdate;
+pub use expected_transition::ExpectedTransition;
+pub use ignore_windows::{IgnoreWindow, IgnoreWindows};
+pub use invalid_credentials::InvalidCredentials;
+pub use monitor_stats::MonitorStats;
+pub use mpsc_receiver::MpscReceiver;
 pub use mpsc_sender::MpscSender;
 pub use noop_sender::NoopSender;
+pub use overflow::Overflow;
+pub use profiles::{Profile, Profiles};
+pub use readiness::Readiness;
+pub use runtime_stats::{RuntimeStats, MAX_BLOCKING_THREADS};
 #[cfg(test)]
 pub use sender::MockSender;
 pub use sender::Sender;
 pub use shared_state::{SharedState, SharedStateMutex};
+pub use standby::Standby;
+pub use sync_stats::SyncStats;
+pub use update_coalescer::UpdateCoalescer;
+
+// once full only the latest update of every device is kept in the overflow
+const DEVICE_UPDATES_CAPACITY: usize = 256;
+const DEVICE_EVENTS_CAPACITY: usize = 64;
+
+/// Broadcasts all device updates applied to the shared state to any interested subscriber.
+pub type DeviceEvents = tokio::sync::broadcast::Sender<DeviceUpdate>;
+
+pub type GuestSender = tokio::sync::mpsc::UnboundedSender<super::Guest>;
+pub type GuestReceiver = tokio::sync::mpsc::UnboundedReceiver<super::Guest>;
+
+pub type ExternalWakeupSender = tokio::sync::mpsc::UnboundedSender<super::DeviceId>;
+pub type ExternalWakeupReceiver = tokio::sync::mpsc::UnboundedReceiver<super::DeviceId>;
+
+pub type ChangeTimeoutResetSender = tokio::sync::mpsc::UnboundedSender<super::DeviceId>;
+pub type ChangeTimeoutResetReceiver = tokio::sync::mpsc::UnboundedReceiver<super::DeviceId>;
+
+pub fn mpsc_channel(stats: Arc<SyncStats>) -> (MpscSender, MpscReceiver) {
+    mpsc_channel_with_capacity(DEVICE_UPDATES_CAPACITY, stats)
+}
 
-pub type MpscReceiver = tokio::sync::mpsc::UnboundedReceiver<super::Device>;
+fn mpsc_channel_with_capacity(
+    capacity: usize,
+    stats: Arc<SyncStats>,
+) -> (MpscSender, MpscReceiver) {
+    let (tx, rx) = tokio::sync::mpsc::channel::<DeviceUpdate>(capacity);
+    let overflow = Arc::new(Mutex::new(Overflow::default()));
 
-pub fn mpsc_channel() -> (MpscSender, MpscReceiver) {
-    let (tx, rx) = tokio::sync::mpsc::unbounded_channel::<super::Device>();
+    (
+        MpscSender::new(tx, overflow.clone(), stats),
+        MpscReceiver::new(rx, overflow),
+    )
+}
+
+pub fn device_events() -> DeviceEvents {
+    let (tx, _) = tokio::sync::broadcast::channel::<DeviceUpdate>(DEVICE_EVENTS_CAPACITY);
+    tx
+}
+
+pub fn guest_channel() -> (GuestSender, GuestReceiver) {
+    tokio::sync::mpsc::unbounded_channel::<super::Guest>()
+}
+
+pub fn external_wakeup_channel() -> (ExternalWakeupSender, ExternalWakeupReceiver) {
+    tokio::sync::mpsc::unbounded_channel::<super::DeviceId>()
+}
 
-    (MpscSender::new(tx), rx)
+pub fn change_timeout_reset_channel() -> (ChangeTimeoutResetSender, ChangeTimeoutResetReceiver) {
+    tokio::sync::mpsc::unbounded_channel::<super::DeviceId>()
 }
 
 pub fn create_mpsc_sender(mpsc_sender: MpscSender) -> Box<dyn Sender> {
@@ -0,0 +1,102 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Timing statistics of the monitoring loop showing whether it keeps up with the configured ping
+/// interval or drifts (e.g. because pinging or processing the servers blocks it).
+#[derive(Debug, Default)]
+pub struct MonitorStats {
+    ping_cycles: AtomicU64,
+    last_drift: Mutex<Duration>,
+    max_drift: Mutex<Duration>,
+    last_duration: Mutex<Duration>,
+    max_duration: Mutex<Duration>,
+    overruns: AtomicU64,
+}
+
+impl MonitorStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Called whenever the devices are pinged with the time since they have been pinged last.
+    pub fn ping_cycle(&self, interval: Duration, elapsed: Duration) {
+        let drift = elapsed.saturating_sub(interval);
+        *self.last_drift.lock().unwrap() = drift;
+
+        let mut max_drift = self.max_drift.lock().unwrap();
+        if drift > *max_drift {
+            *max_drift = drift;
+        }
+
+        self.ping_cycles.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Called after every run of the monitoring loop and returns whether it took longer than the
+    /// ping interval.
+    pub fn run(&self, interval: Duration, duration: Duration) -> bool {
+        *self.last_duration.lock().unwrap() = duration;
+
+        let mut max_duration = self.max_duration.lock().unwrap();
+        if duration > *max_duration {
+            *max_duration = duration;
+        }
+
+        let overrun = duration > interval;
+        if overrun {
+            self.overruns.fetch_add(1, Ordering::Relaxed);
+        }
+
+        overrun
+    }
+
+    pub fn ping_cycles(&self) -> u64 {
+        self.ping_cycles.load(Ordering::Relaxed)
+    }
+
+    pub fn last_drift(&self) -> Duration {
+        *self.last_drift.lock().unwrap()
+    }
+
+    pub fn max_drift(&self) -> Duration {
+        *self.max_drift.lock().unwrap()
+    }
+
+    pub fn last_duration(&self) -> Duration {
+        *self.last_duration.lock().unwrap()
+    }
+
+    pub fn max_duration(&self) -> Duration {
+        *self.max_duration.lock().unwrap()
+    }
+
+    pub fn overruns(&self) -> u64 {
+        self.overruns.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::*;
+
+    use super::*;
+
+    #[rstest]
+    fn test_monitor_stats_track_drift_and_overruns() {
+        let stats = MonitorStats::new();
+        let interval = Duration::from_secs(5);
+
+        stats.ping_cycle(interval, Duration::from_millis(6500));
+        stats.ping_cycle(interval, Duration::from_millis(5200));
+        assert_eq!(stats.ping_cycles(), 2);
+        assert_eq!(stats.last_drift(), Duration::from_millis(200));
+        assert_eq!(stats.max_drift(), Duration::from_millis(1500));
+
+        assert!(!stats.run(interval, Duration::from_millis(300)));
+        assert!(stats.run(interval, Duration::from_secs(6)));
+        assert!(!stats.run(interval, Duration::from_millis(100)));
+        assert_eq!(stats.overruns(), 1);
+        assert_eq!(stats.last_duration(), Duration::from_millis(100));
+        assert_eq!(stats.max_duration(), Duration::from_secs(6));
+    }
+}
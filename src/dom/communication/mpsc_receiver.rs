@@ -0,0 +1,41 @@
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::mpsc;
+use tokio::sync::mpsc::error::TryRecvError;
+
+use super::{DeviceUpdate, Overflow};
+
+/// Receives the device updates from the bounded channel and afterwards from the overflow.
+#[derive(Debug)]
+pub struct MpscReceiver {
+    receiver: mpsc::Receiver<DeviceUpdate>,
+    overflow: Arc<Mutex<Overflow>>,
+}
+
+impl MpscReceiver {
+    pub fn new(receiver: mpsc::Receiver<DeviceUpdate>, overflow: Arc<Mutex<Overflow>>) -> Self {
+        Self { receiver, overflow }
+    }
+
+    /// Receives the next device update or `None` once all senders have been dropped and every
+    /// update has been received.
+    pub async fn recv(&mut self) -> Option<DeviceUpdate> {
+        {
+            // while the overflow is locked no sender can add updates so the channel only
+            // contains updates older than the ones in the overflow
+            let mut overflow = self.overflow.lock().unwrap();
+            match self.receiver.try_recv() {
+                Ok(update) => return Some(update),
+                Err(TryRecvError::Empty) => {
+                    if let Some(update) = overflow.pop() {
+                        return Some(update);
+                    }
+                }
+                Err(TryRecvError::Disconnected) => return overflow.pop(),
+            }
+        }
+
+        // the overflow is only used once the channel is full so waiting for the channel is enough
+        self.receiver.recv().await
+    }
+}
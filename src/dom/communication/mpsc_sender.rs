@@ -1,22 +1,143 @@
+use std::sync::{Arc, Mutex};
+
 use tokio::sync::mpsc;
+use tokio::sync::mpsc::error::TrySendError;
 
-use super::super::Device;
-use super::Sender;
+use super::super::{Device, DeviceId};
+use super::{DeviceUpdate, Overflow, Sender, SyncStats};
 
 #[derive(Clone, Debug)]
 pub struct MpscSender {
-    sender: mpsc::UnboundedSender<Device>,
+    sender: mpsc::Sender<DeviceUpdate>,
+    overflow: Arc<Mutex<Overflow>>,
+    stats: Arc<SyncStats>,
 }
 
 impl MpscSender {
-    pub fn new(sender: mpsc::UnboundedSender<Device>) -> Self {
-        Self { sender }
+    pub fn new(
+        sender: mpsc::Sender<DeviceUpdate>,
+        overflow: Arc<Mutex<Overflow>>,
+        stats: Arc<SyncStats>,
+    ) -> Self {
+        Self {
+            sender,
+            overflow,
+            stats,
+        }
+    }
+
+    fn send_update(&self, update: DeviceUpdate) -> anyhow::Result<()> {
+        self.stats.sent();
+        if self.sender.is_closed() {
+            self.stats.send_failed();
+            return Err(TrySendError::Closed(update).into());
+        }
+
+        let mut overflow = self.overflow.lock().unwrap();
+        // keep the order of the updates by not using the channel until the overflow is drained
+        let update = if overflow.is_empty() {
+            match self.sender.try_send(update) {
+                Ok(()) => return Ok(()),
+                Err(TrySendError::Full(update)) | Err(TrySendError::Closed(update)) => update,
+            }
+        } else {
+            update
+        };
+
+        if overflow.push(update) {
+            self.stats.coalesced();
+        }
+
+        Ok(())
     }
 }
 
 impl Sender for MpscSender {
-    fn send(&self, device: Device) -> anyhow::Result<()> {
-        self.sender.send(device)?;
-        Ok(())
+    fn send(&self, device: Arc<Device>) -> anyhow::Result<()> {
+        self.send_update(DeviceUpdate::Changed(device))
+    }
+
+    fn send_removal(&self, device_id: DeviceId) -> anyhow::Result<()> {
+        self.send_update(DeviceUpdate::Removed(device_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::*;
+
+    use super::super::mpsc_channel_with_capacity;
+    use super::*;
+    use crate::dom::device::test::*;
+    use crate::dom::Machine;
+
+    fn get_machine(machine: &Machine, is_online: bool) -> Arc<Device> {
+        let mut machine = machine.clone();
+        machine.is_online = is_online;
+        Arc::new(Device::Machine(machine))
+    }
+
+    fn assert_update(update: Option<DeviceUpdate>, expected_device: &Arc<Device>) {
+        match update {
+            Some(DeviceUpdate::Changed(device)) => assert_eq!(device, *expected_device),
+            update => panic!("unexpected update {:?}", update),
+        }
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_mpsc_sender_coalesces_updates_if_channel_is_full(machine: Machine) {
+        let stats = Arc::new(SyncStats::new());
+        let (sender, mut receiver) = mpsc_channel_with_capacity(1, stats.clone());
+
+        let first = get_machine(&machine, true);
+        let second = get_machine(&machine, false);
+        let third = get_machine(&machine, true);
+        sender.send(first.clone()).unwrap();
+        sender.send(second).unwrap();
+        sender.send(third.clone()).unwrap();
+
+        // the second update has been replaced by the third one
+        assert_eq!(stats.queue_depth(), 2);
+        assert_eq!(stats.coalesced_updates(), 1);
+        assert_update(receiver.recv().await, &first);
+        assert_update(receiver.recv().await, &third);
+
+        drop(sender);
+        assert!(receiver.recv().await.is_none());
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_mpsc_sender_keeps_order_while_overflow_is_not_drained(
+        machine: Machine,
+        server: crate::dom::Server,
+    ) {
+        let stats = Arc::new(SyncStats::new());
+        let (sender, mut receiver) = mpsc_channel_with_capacity(1, stats);
+
+        let machine_online = get_machine(&machine, true);
+        let machine_offline = get_machine(&machine, false);
+        let server = Arc::new(Device::Server(server));
+        sender.send(machine_online.clone()).unwrap();
+        sender.send(server.clone()).unwrap();
+
+        // the channel has space again but the newer update must not overtake the overflow
+        assert_update(receiver.recv().await, &machine_online);
+        sender.send(machine_offline.clone()).unwrap();
+        assert_update(receiver.recv().await, &server);
+        assert_update(receiver.recv().await, &machine_offline);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_mpsc_sender_fails_if_receiver_is_dropped(machine: Machine) {
+        let stats = Arc::new(SyncStats::new());
+        let (sender, receiver) = mpsc_channel_with_capacity(1, stats.clone());
+        drop(receiver);
+
+        assert!(sender.send(get_machine(&machine, true)).is_err());
+        assert_eq!(stats.failed_sends(), 1);
+        assert_eq!(stats.queue_depth(), 0);
     }
 }
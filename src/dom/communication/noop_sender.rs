@@ -1,4 +1,6 @@
-use super::super::Device;
+use std::sync::Arc;
+
+use super::super::{Device, DeviceId};
 use super::Sender;
 
 #[derive(Clone, Debug)]
@@ -11,7 +13,11 @@ impl NoopSender {
 }
 
 impl Sender for NoopSender {
-    fn send(&self, _: Device) -> anyhow::Result<()> {
+    fn send(&self, _: Arc<Device>) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    fn send_removal(&self, _: DeviceId) -> anyhow::Result<()> {
         Ok(())
     }
 }
@@ -0,0 +1,82 @@
+use std::collections::{HashMap, VecDeque};
+
+use super::super::DeviceId;
+use super::DeviceUpdate;
+
+/// Holds the device updates which didn't fit into the bounded channel anymore. Only the latest
+/// update of every device is kept so that a stalled consumer can't grow the memory without bound.
+#[derive(Debug, Default)]
+pub struct Overflow {
+    updates: HashMap<DeviceId, DeviceUpdate>,
+    // the order in which the devices have been queued
+    order: VecDeque<DeviceId>,
+}
+
+impl Overflow {
+    pub fn is_empty(&self) -> bool {
+        self.updates.is_empty()
+    }
+
+    /// Adds the given update and returns whether it replaced an older update of the same device.
+    pub fn push(&mut self, update: DeviceUpdate) -> bool {
+        let device_id = update.device_id().clone();
+        if self.updates.insert(device_id.clone(), update).is_some() {
+            return true;
+        }
+
+        self.order.push_back(device_id);
+        false
+    }
+
+    pub fn pop(&mut self) -> Option<DeviceUpdate> {
+        let device_id = self.order.pop_front()?;
+        self.updates.remove(&device_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use rstest::*;
+
+    use super::*;
+    use crate::dom::device::test::*;
+    use crate::dom::{Device, Machine, Server};
+
+    #[rstest]
+    fn test_overflow_coalesces_updates_per_device(machine: Machine, server: Server) {
+        let mut overflow = Overflow::default();
+        assert!(overflow.is_empty());
+
+        let mut offline_machine = machine.clone();
+        offline_machine.is_online = false;
+        let mut online_machine = machine;
+        online_machine.is_online = true;
+
+        assert!(
+            !overflow.push(DeviceUpdate::Changed(Arc::new(Device::Machine(
+                offline_machine
+            ))))
+        );
+        assert!(!overflow.push(DeviceUpdate::Changed(Arc::new(Device::Server(server)))));
+        assert!(
+            overflow.push(DeviceUpdate::Changed(Arc::new(Device::Machine(
+                online_machine.clone()
+            ))))
+        );
+
+        // the latest update of the machine keeps the position of the replaced update
+        match overflow.pop() {
+            Some(DeviceUpdate::Changed(device)) => {
+                assert_eq!(*device, Device::Machine(online_machine))
+            }
+            update => panic!("unexpected update {:?}", update),
+        }
+        assert!(matches!(
+            overflow.pop(),
+            Some(DeviceUpdate::Changed(device)) if matches!(*device, Device::Server(_))
+        ));
+        assert!(overflow.pop().is_none());
+    }
+}
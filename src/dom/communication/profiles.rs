@@ -0,0 +1,165 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+use super::super::{Dependencies, DeviceId, Schedule};
+
+/// Dependencies (devices and schedules) of the controlled servers within a profile.
+#[derive(Clone, Debug, Default)]
+pub struct Profile {
+    pub dependencies: Dependencies,
+    pub schedules: HashMap<DeviceId, Vec<Schedule>>,
+}
+
+/// Keeps track of the active profile which can be switched through the web API and is applied
+/// by the monitor.
+#[derive(Debug)]
+pub struct Profiles {
+    default: Profile,
+    profiles: HashMap<String, Profile>,
+    active: Mutex<Option<String>>,
+    changed: AtomicBool,
+}
+
+impl Profiles {
+    /// Creates the profiles with the configured dependencies being active.
+    pub fn new(default: Profile, profiles: HashMap<String, Profile>) -> Self {
+        Self {
+            default,
+            profiles,
+            active: Mutex::new(None),
+            changed: AtomicBool::new(false),
+        }
+    }
+
+    /// Names of all profiles (sorted).
+    pub fn names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.profiles.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    /// Name of the active profile (none if the configured dependencies are active).
+    pub fn active(&self) -> Option<String> {
+        self.active.lock().unwrap().clone()
+    }
+
+    /// Activates the given profile (or the configured dependencies) and returns whether the
+    /// profile exists.
+    pub fn activate(&self, name: Option<&str>) -> bool {
+        if name.is_some_and(|name| !self.profiles.contains_key(name)) {
+            return false;
+        }
+
+        let mut active = self.active.lock().unwrap();
+        if active.as_deref() != name {
+            *active = name.map(str::to_string);
+            self.changed.store(true, Ordering::SeqCst);
+        }
+
+        true
+    }
+
+    /// Returns the schedules the given server depends on within the active profile.
+    pub fn schedules(&self, server_id: &DeviceId) -> Vec<Schedule> {
+        let active = self.active.lock().unwrap();
+        active
+            .as_ref()
+            .and_then(|name| self.profiles.get(name))
+            .unwrap_or(&self.default)
+            .schedules
+            .get(server_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Returns the active profile if it has been switched since the last call.
+    pub fn take_change(&self) -> Option<Profile> {
+        if !self.changed.swap(false, Ordering::SeqCst) {
+            return None;
+        }
+
+        let active = self.active.lock().unwrap();
+        match active.as_ref().and_then(|name| self.profiles.get(name)) {
+            Some(profile) => Some(profile.clone()),
+            None => Some(self.default.clone()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::*;
+
+    use super::super::super::device::test::*;
+    use super::*;
+
+    #[rstest]
+    fn test_profiles_switch_between_profiles(server_id: DeviceId, machine_id: DeviceId) {
+        let default = Profile {
+            dependencies: [(server_id.clone(), vec![machine_id])]
+                .iter()
+                .cloned()
+                .collect(),
+            ..Default::default()
+        };
+        let profiles = Profiles::new(
+            default,
+            [("vacation".to_string(), Profile::default())]
+                .iter()
+                .cloned()
+                .collect(),
+        );
+        assert_eq!(profiles.names(), vec!["vacation".to_string()]);
+        assert_eq!(profiles.active(), None);
+        assert!(profiles.take_change().is_none());
+
+        assert!(!profiles.activate(Some("party")));
+        assert!(profiles.take_change().is_none());
+
+        assert!(profiles.activate(Some("vacation")));
+        assert_eq!(profiles.active(), Some("vacation".to_string()));
+        assert!(profiles.take_change().unwrap().dependencies.is_empty());
+        assert!(profiles.take_change().is_none());
+
+        // activating the active profile again doesn't change anything
+        assert!(profiles.activate(Some("vacation")));
+        assert!(profiles.take_change().is_none());
+
+        assert!(profiles.activate(None));
+        assert_eq!(profiles.active(), None);
+        assert!(profiles
+            .take_change()
+            .unwrap()
+            .dependencies
+            .contains_key(&server_id));
+    }
+
+    #[rstest]
+    fn test_profiles_return_schedules_of_active_profile(server_id: DeviceId) {
+        let schedule = Schedule::Time {
+            id: "time:workhours".parse().unwrap(),
+            from: chrono::NaiveTime::from_hms_opt(8, 0, 0).unwrap(),
+            to: chrono::NaiveTime::from_hms_opt(18, 0, 0).unwrap(),
+            days: Vec::new(),
+        };
+        let vacation = Profile {
+            schedules: [(server_id.clone(), vec![schedule.clone()])]
+                .iter()
+                .cloned()
+                .collect(),
+            ..Default::default()
+        };
+        let profiles = Profiles::new(
+            Profile::default(),
+            [("vacation".to_string(), vacation)]
+                .iter()
+                .cloned()
+                .collect(),
+        );
+        assert!(profiles.schedules(&server_id).is_empty());
+
+        assert!(profiles.activate(Some("vacation")));
+        assert_eq!(profiles.schedules(&server_id), vec![schedule]);
+    }
+}
@@ -0,0 +1,53 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::networking;
+
+/// Maximum time between two monitoring runs before the monitor is considered stalled.
+const HEARTBEAT_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug)]
+pub struct Readiness {
+    interface: String,
+    last_heartbeat: Mutex<Option<Instant>>,
+    pinger_running: Mutex<bool>,
+}
+
+impl Readiness {
+    pub fn new(interface: &str) -> Self {
+        Self {
+            interface: interface.to_string(),
+            last_heartbeat: Mutex::new(None),
+            pinger_running: Mutex::new(false),
+        }
+    }
+
+    /// Called by the monitor on every run to signal that it is still responsive.
+    pub fn heartbeat(&self) {
+        *self.last_heartbeat.lock().unwrap() = Some(Instant::now());
+    }
+
+    /// Called by the monitor once the pinger has successfully completed a ping run.
+    pub fn set_pinger_running(&self) {
+        *self.pinger_running.lock().unwrap() = true;
+    }
+
+    pub fn is_monitor_started(&self) -> bool {
+        self.last_heartbeat.lock().unwrap().is_some()
+    }
+
+    pub fn is_monitor_stalled(&self) -> bool {
+        match *self.last_heartbeat.lock().unwrap() {
+            Some(last_heartbeat) => last_heartbeat.elapsed() > HEARTBEAT_TIMEOUT,
+            None => false,
+        }
+    }
+
+    pub fn is_pinger_running(&self) -> bool {
+        *self.pinger_running.lock().unwrap()
+    }
+
+    pub fn is_interface_present(&self) -> bool {
+        networking::get_network_interface(&self.interface).is_ok()
+    }
+}
@@ -0,0 +1,131 @@
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// Maximum number of threads of the blocking pool of the tokio runtime (its default).
+pub const MAX_BLOCKING_THREADS: usize = 512;
+
+/// Statistics of the tokio runtime collected through the thread hooks of its builder and of the
+/// long-running tasks of the daemon (e.g. the monitoring loop or the web API) tracked through
+/// [`RuntimeStats::track`].
+#[derive(Debug, Default)]
+pub struct RuntimeStats {
+    workers: usize,
+    threads: AtomicUsize,
+    idle_workers: AtomicUsize,
+    tasks: AtomicUsize,
+}
+
+/// Counts a tracked task as running until it is dropped.
+struct TaskGuard(Arc<RuntimeStats>);
+
+impl Drop for TaskGuard {
+    fn drop(&mut self) {
+        self.0.tasks.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+impl RuntimeStats {
+    pub fn new(workers: usize) -> Self {
+        Self {
+            workers,
+            ..Default::default()
+        }
+    }
+
+    /// Called whenever a thread (worker or blocking) of the runtime has been started.
+    pub fn thread_started(&self) {
+        self.threads.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Called whenever a thread (worker or blocking) of the runtime is stopped.
+    pub fn thread_stopped(&self) {
+        self.threads.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    /// Called whenever a worker runs out of tasks and goes idle.
+    pub fn worker_parked(&self) {
+        self.idle_workers.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Called whenever an idle worker is woken up again.
+    pub fn worker_unparked(&self) {
+        self.idle_workers.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    /// Counts the given future as a running task until it has finished (or has been dropped).
+    pub fn track<F: Future>(self: &Arc<Self>, future: F) -> impl Future<Output = F::Output> {
+        self.tasks.fetch_add(1, Ordering::Relaxed);
+        let guard = TaskGuard(self.clone());
+        async move {
+            let _guard = guard;
+            future.await
+        }
+    }
+
+    pub fn workers(&self) -> usize {
+        self.workers
+    }
+
+    /// Number of workers which are currently running tasks.
+    pub fn busy_workers(&self) -> usize {
+        self.workers
+            .saturating_sub(self.idle_workers.load(Ordering::Relaxed))
+    }
+
+    /// Number of threads of the blocking pool (the workers are started through the blocking pool
+    /// as well).
+    pub fn blocking_threads(&self) -> usize {
+        self.threads
+            .load(Ordering::Relaxed)
+            .saturating_sub(self.workers)
+    }
+
+    pub fn tasks(&self) -> usize {
+        self.tasks.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::*;
+
+    use super::*;
+
+    #[rstest]
+    fn test_runtime_stats_count_threads_and_workers() {
+        let stats = RuntimeStats::new(2);
+        for _ in 0..5 {
+            stats.thread_started();
+        }
+        stats.thread_stopped();
+        stats.worker_parked();
+
+        assert_eq!(stats.workers(), 2);
+        assert_eq!(stats.busy_workers(), 1);
+        assert_eq!(stats.blocking_threads(), 2);
+
+        stats.worker_unparked();
+        assert_eq!(stats.busy_workers(), 2);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_runtime_stats_track_tasks_until_finished() {
+        let stats = Arc::new(RuntimeStats::new(1));
+
+        let (tx, rx) = tokio::sync::oneshot::channel::<()>();
+        let task = tokio::spawn(stats.track(async move {
+            let _ = rx.await;
+        }));
+        assert_eq!(stats.tasks(), 1);
+
+        tx.send(()).unwrap();
+        task.await.unwrap();
+        assert_eq!(stats.tasks(), 0);
+
+        // dropping a task which hasn't finished doesn't leak it
+        drop(stats.track(std::future::pending::<()>()));
+        assert_eq!(stats.tasks(), 0);
+    }
+}
@@ -1,9 +1,12 @@
 #[cfg(test)]
 use mockall::automock;
 
-use super::super::Device;
+use std::sync::Arc;
+
+use super::super::{Device, DeviceId};
 
 #[cfg_attr(test, automock)]
 pub trait Sender: Send {
-    fn send(&self, device: Device) -> anyhow::Result<()>;
+    fn send(&self, device: Arc<Device>) -> anyhow::Result<()>;
+    fn send_removal(&self, device_id: DeviceId) -> anyhow::Result<()>;
 }
@@ -1,19 +1,35 @@
-use super::super::{Device, Machine, Server};
+use std::collections::HashMap;
+
+use super::super::{Attributes, Device, DeviceId, Fingerprint, Machine, Note, Server};
+use crate::utils::MacAddr;
 
 pub struct SharedState {
+    /// Devices sorted by their ID to keep the output of the web API stable.
     devices: Vec<Device>,
+    fingerprints: HashMap<DeviceId, Fingerprint>,
+    attributes: HashMap<DeviceId, Attributes>,
+    notes: HashMap<DeviceId, Vec<Note>>,
+    /// MAC addresses found in the ARP table which differ from the configured ones.
+    mac_mismatches: HashMap<DeviceId, MacAddr>,
 }
 
 impl SharedState {
-    pub fn new(devices: Vec<Device>) -> Self {
-        Self { devices }
+    pub fn new(mut devices: Vec<Device>) -> Self {
+        devices.sort_by(|lhs, rhs| lhs.id().cmp(rhs.id()));
+        Self {
+            devices,
+            fingerprints: HashMap::new(),
+            attributes: HashMap::new(),
+            notes: HashMap::new(),
+            mac_mismatches: HashMap::new(),
+        }
     }
 
     pub fn get_devices(&self) -> &Vec<Device> {
         &self.devices
     }
 
-    pub fn update_device(&mut self, device: Device) {
+    pub fn update_device(&mut self, device: &Device) {
         // try to find a matching machine by IP and update the mutable fields
         for dev in self.devices.iter_mut() {
             if match device {
@@ -25,15 +41,98 @@ impl SharedState {
             }
         }
 
-        // otherwise add the machine to the shared state
-        self.devices.push(device);
+        // otherwise add the machine to the shared state (keeping the devices sorted)
+        let index = self.devices.partition_point(|dev| dev.id() < device.id());
+        self.devices.insert(index, device.clone());
+    }
+
+    pub fn remove_device(&mut self, device_id: &DeviceId) {
+        self.devices.retain(|device| device.id() != device_id);
+        self.fingerprints.remove(device_id);
+        self.attributes.remove(device_id);
+        self.notes.remove(device_id);
+        self.mac_mismatches.remove(device_id);
+    }
+
+    pub fn get_fingerprint(&self, device_id: &DeviceId) -> Option<&Fingerprint> {
+        self.fingerprints.get(device_id)
+    }
+
+    pub fn set_fingerprint(&mut self, device_id: &DeviceId, fingerprint: Fingerprint) {
+        self.fingerprints.insert(device_id.clone(), fingerprint);
+    }
+
+    pub fn get_attributes(&self, device_id: &DeviceId) -> Option<&Attributes> {
+        self.attributes.get(device_id)
+    }
+
+    pub fn get_all_attributes(&self) -> &HashMap<DeviceId, Attributes> {
+        &self.attributes
+    }
+
+    pub fn set_attributes(&mut self, device_id: &DeviceId, attributes: Attributes) {
+        if attributes.is_empty() {
+            self.attributes.remove(device_id);
+        } else {
+            self.attributes.insert(device_id.clone(), attributes);
+        }
+    }
+
+    /// Returns the notes of the given device (oldest first).
+    pub fn get_notes(&self, device_id: &DeviceId) -> Option<&Vec<Note>> {
+        self.notes.get(device_id)
+    }
+
+    pub fn get_all_notes(&self) -> &HashMap<DeviceId, Vec<Note>> {
+        &self.notes
+    }
+
+    pub fn set_notes(&mut self, device_id: &DeviceId, mut notes: Vec<Note>) {
+        if notes.is_empty() {
+            self.notes.remove(device_id);
+        } else {
+            notes.sort_by_key(|note| note.date);
+            self.notes.insert(device_id.clone(), notes);
+        }
+    }
+
+    /// Returns the MAC address found in the ARP table if it differs from the configured one.
+    pub fn get_mac_mismatch(&self, device_id: &DeviceId) -> Option<&MacAddr> {
+        self.mac_mismatches.get(device_id)
+    }
+
+    pub fn set_mac_mismatch(&mut self, device_id: &DeviceId, observed_mac: Option<MacAddr>) {
+        match observed_mac {
+            Some(mac) => self.mac_mismatches.insert(device_id.clone(), mac),
+            None => self.mac_mismatches.remove(device_id),
+        };
     }
 
     fn update_device_from_server(device: &mut Device, updated_server: &Server) -> bool {
         // only update a server device with a server
         match device {
             Device::Server(ref mut server) => {
-                Self::raw_update_machine_from_machine(&mut server.machine, &updated_server.machine)
+                let updated = Self::raw_update_machine_from_machine(
+                    &mut server.machine,
+                    &updated_server.machine,
+                );
+                if updated {
+                    server.consecutive_failed_actions = updated_server.consecutive_failed_actions;
+                    server.is_control_disabled = updated_server.is_control_disabled;
+                    server.has_invalid_credentials = updated_server.has_invalid_credentials;
+                    server.services = updated_server.services.clone();
+                    server.unexpected_onlines = updated_server.unexpected_onlines;
+                    server.last_unexpected_online = updated_server.last_unexpected_online;
+                    server.external_wakeups = updated_server.external_wakeups;
+                    server.last_external_wakeup = updated_server.last_external_wakeup;
+                    server.last_triggers = updated_server.last_triggers.clone();
+                    server.on_time_today = updated_server.on_time_today;
+                    server.change_timeout_until = updated_server.change_timeout_until;
+                    server.wakeup_deferred_until = updated_server.wakeup_deferred_until;
+                    server.transitions = updated_server.transitions.clone();
+                }
+
+                updated
             }
             _ => false,
         }
@@ -54,6 +153,9 @@ impl SharedState {
             machine.is_online = updated_machine.is_online;
             machine.last_seen = updated_machine.last_seen;
             machine.last_seen_date = updated_machine.last_seen_date;
+            machine.consecutive_missed_pings = updated_machine.consecutive_missed_pings;
+            machine.probe_method = updated_machine.probe_method;
+            machine.is_ignored = updated_machine.is_ignored;
 
             true
         } else {
@@ -66,14 +168,17 @@ pub type SharedStateMutex = std::sync::Mutex<SharedState>;
 
 #[cfg(test)]
 mod test {
+    use chrono::Utc;
     use rstest::*;
 
     use super::*;
     use crate::dom::device::test::*;
+    use crate::dom::{ProbeMethod, Transition, TransitionCause};
 
     #[fixture]
     fn devices(server: Server, machine: Machine) -> Vec<Device> {
-        vec![Device::Server(server), Device::Machine(machine)]
+        // sorted by their ID
+        vec![Device::Machine(machine), Device::Server(server)]
     }
 
     #[fixture]
@@ -86,6 +191,19 @@ mod test {
         assert_eq!(*shared_state.get_devices(), devices);
     }
 
+    #[rstest]
+    fn test_devices_are_sorted_by_id(server: Server, machine: Machine) {
+        let shared_state = SharedState::new(vec![
+            Device::Server(server.clone()),
+            Device::Machine(machine.clone()),
+        ]);
+
+        assert_eq!(
+            *shared_state.get_devices(),
+            vec![Device::Machine(machine), Device::Server(server)]
+        );
+    }
+
     #[rstest]
     fn test_update_devices_adds_device_if_not_existing(
         mut shared_state: SharedState,
@@ -101,8 +219,9 @@ mod test {
         // TESTING
         assert_eq!(*shared_state.get_devices(), devices);
 
-        shared_state.update_device(new_device.clone());
-        devices.push(new_device);
+        shared_state.update_device(&new_device);
+        // the new device is inserted in the order of the IDs
+        devices.insert(0, new_device);
 
         assert_eq!(*shared_state.get_devices(), devices);
     }
@@ -117,9 +236,65 @@ mod test {
             Device::Server(ref mut server) => server.machine.is_online = !server.machine.is_online,
             Device::Machine(ref mut machine) => machine.is_online = !machine.is_online,
         };
+        device.set_ignored(true);
 
-        shared_state.update_device(device.clone());
+        shared_state.update_device(device);
 
         assert_eq!(*shared_state.get_devices(), devices);
     }
+
+    #[rstest]
+    fn test_can_remove_existing_device(
+        mut shared_state: SharedState,
+        mut devices: Vec<Device>,
+        machine_id: DeviceId,
+    ) {
+        // TESTING
+        assert_eq!(*shared_state.get_devices(), devices);
+
+        shared_state.remove_device(&machine_id);
+        devices.retain(|device| *device.id() != machine_id);
+
+        assert_eq!(*shared_state.get_devices(), devices);
+    }
+
+    #[rstest]
+    fn test_update_device_copies_probe_method(mut shared_state: SharedState, mut machine: Machine) {
+        machine.is_online = true;
+        machine.probe_method = Some(ProbeMethod::Arp);
+
+        shared_state.update_device(&Device::Machine(machine.clone()));
+
+        assert_eq!(shared_state.get_devices()[0], Device::Machine(machine));
+    }
+
+    #[rstest]
+    fn test_update_device_copies_change_timeout(mut shared_state: SharedState, mut server: Server) {
+        server.change_timeout_until = Some(Utc::now());
+
+        shared_state.update_device(&Device::Server(server.clone()));
+
+        assert_eq!(shared_state.get_devices()[1], Device::Server(server));
+    }
+
+    #[rstest]
+    fn test_update_device_copies_transitions(mut shared_state: SharedState, mut server: Server) {
+        server.transitions = vec![Transition::new(true, TransitionCause::Api)];
+
+        shared_state.update_device(&Device::Server(server.clone()));
+
+        assert_eq!(shared_state.get_devices()[1], Device::Server(server));
+    }
+
+    #[rstest]
+    fn test_update_device_copies_deferred_wakeup(
+        mut shared_state: SharedState,
+        mut server: Server,
+    ) {
+        server.wakeup_deferred_until = Some(Utc::now());
+
+        shared_state.update_device(&Device::Server(server.clone()));
+
+        assert_eq!(shared_state.get_devices()[1], Device::Server(server));
+    }
 }
@@ -0,0 +1,89 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::utils::{Clock, Instant};
+
+#[derive(Debug)]
+struct StandbyState {
+    last_heartbeat: Instant,
+    active: bool,
+}
+
+/// Tracks the heartbeats of the active instance a standby instance takes over from once it
+/// hasn't been heard of for longer than the timeout.
+pub struct Standby {
+    timeout: Duration,
+    state: Mutex<StandbyState>,
+    clock: Arc<dyn Clock>,
+}
+
+impl Standby {
+    pub fn new(timeout: Duration, clock: Arc<dyn Clock>) -> Self {
+        Self {
+            timeout,
+            // give the active instance the full timeout to answer after startup
+            state: Mutex::new(StandbyState {
+                last_heartbeat: clock.now(),
+                active: false,
+            }),
+            clock,
+        }
+    }
+
+    /// Records whether the active instance answered and returns whether this instance took over
+    /// (`Some(true)`) or handed back (`Some(false)`) the control of the servers.
+    pub fn update(&self, peer_alive: bool) -> Option<bool> {
+        let mut state = self.state.lock().unwrap();
+        if peer_alive {
+            state.last_heartbeat = self.clock.now();
+        }
+
+        let active = !peer_alive && self.clock.elapsed(state.last_heartbeat) > self.timeout;
+        if active == state.active {
+            return None;
+        }
+
+        state.active = active;
+        Some(active)
+    }
+
+    /// Whether this instance currently controls the servers.
+    pub fn is_active(&self) -> bool {
+        self.state.lock().unwrap().active
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::*;
+
+    use super::*;
+    use crate::utils::ManualClock;
+
+    #[rstest]
+    fn test_standby_takes_over_and_hands_back() {
+        let timeout = Duration::from_secs(30);
+        let clock = Arc::new(ManualClock::new(Instant::now()));
+        let standby = Standby::new(timeout, clock.clone());
+        assert!(!standby.is_active());
+
+        // the active instance is only considered gone after the timeout
+        assert_eq!(standby.update(false), None);
+        clock.advance(timeout);
+        assert_eq!(standby.update(false), None);
+        assert!(!standby.is_active());
+
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(standby.update(false), Some(true));
+        assert_eq!(standby.update(false), None);
+        assert!(standby.is_active());
+
+        // the active instance takes over again as soon as it answers
+        assert_eq!(standby.update(true), Some(false));
+        assert_eq!(standby.update(true), None);
+        assert!(!standby.is_active());
+
+        clock.advance(timeout);
+        assert_eq!(standby.update(false), None);
+    }
+}
@@ -0,0 +1,121 @@
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Statistics of the channel synchronizing the device updates of the monitor with the shared
+/// state so that slow consumers don't silently delay status updates.
+#[derive(Debug, Default)]
+pub struct SyncStats {
+    // time at which every queued update has been sent (in the order of the channel)
+    pending: Mutex<VecDeque<Instant>>,
+    failed_sends: AtomicU64,
+    coalesced_updates: AtomicU64,
+    synced_updates: AtomicU64,
+    last_latency: Mutex<Duration>,
+    max_latency: Mutex<Duration>,
+    event_backlog: AtomicUsize,
+}
+
+impl SyncStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Called right before an update is sent to the channel.
+    pub fn sent(&self) {
+        self.pending.lock().unwrap().push_back(Instant::now());
+    }
+
+    /// Called if sending the update (announced through `sent()`) failed.
+    pub fn send_failed(&self) {
+        self.pending.lock().unwrap().pop_back();
+        self.failed_sends.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Called if the update (announced through `sent()`) replaced an older queued update of the
+    /// same device.
+    pub fn coalesced(&self) {
+        self.pending.lock().unwrap().pop_back();
+        self.coalesced_updates.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Called once an update has been applied to the shared state and broadcast to all
+    /// subscribers which haven't received `event_backlog` events yet.
+    pub fn synced(&self, event_backlog: usize) {
+        if let Some(sent) = self.pending.lock().unwrap().pop_front() {
+            let latency = sent.elapsed();
+            *self.last_latency.lock().unwrap() = latency;
+
+            let mut max_latency = self.max_latency.lock().unwrap();
+            if latency > *max_latency {
+                *max_latency = latency;
+            }
+        }
+
+        self.synced_updates.fetch_add(1, Ordering::Relaxed);
+        self.event_backlog.store(event_backlog, Ordering::Relaxed);
+    }
+
+    pub fn queue_depth(&self) -> usize {
+        self.pending.lock().unwrap().len()
+    }
+
+    pub fn failed_sends(&self) -> u64 {
+        self.failed_sends.load(Ordering::Relaxed)
+    }
+
+    pub fn coalesced_updates(&self) -> u64 {
+        self.coalesced_updates.load(Ordering::Relaxed)
+    }
+
+    pub fn synced_updates(&self) -> u64 {
+        self.synced_updates.load(Ordering::Relaxed)
+    }
+
+    pub fn last_latency(&self) -> Duration {
+        *self.last_latency.lock().unwrap()
+    }
+
+    pub fn max_latency(&self) -> Duration {
+        *self.max_latency.lock().unwrap()
+    }
+
+    pub fn event_backlog(&self) -> usize {
+        self.event_backlog.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::*;
+
+    use super::*;
+
+    #[rstest]
+    fn test_sync_stats_track_queue_depth_and_latency() {
+        let stats = SyncStats::new();
+
+        stats.sent();
+        stats.sent();
+        assert_eq!(stats.queue_depth(), 2);
+
+        std::thread::sleep(Duration::from_millis(10));
+        stats.synced(3);
+        assert_eq!(stats.queue_depth(), 1);
+        assert_eq!(stats.synced_updates(), 1);
+        assert_eq!(stats.event_backlog(), 3);
+        assert!(stats.last_latency() >= Duration::from_millis(10));
+        assert_eq!(stats.max_latency(), stats.last_latency());
+
+        stats.sent();
+        stats.send_failed();
+        assert_eq!(stats.queue_depth(), 1);
+        assert_eq!(stats.failed_sends(), 1);
+
+        stats.sent();
+        stats.coalesced();
+        assert_eq!(stats.queue_depth(), 1);
+        assert_eq!(stats.coalesced_updates(), 1);
+    }
+}
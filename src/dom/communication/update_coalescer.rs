@@ -0,0 +1,262 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use log::{debug, trace, warn};
+
+use super::super::{Device, DeviceId, Machine, Server};
+use super::Sender;
+
+/// Collects the device updates of one monitoring cycle and only publishes the latest state of
+/// every device if it differs from the state published last. Devices flapping within one cycle
+/// and repeated updates of the same device therefore don't cause any chatter.
+#[derive(Debug, Default)]
+pub struct UpdateCoalescer {
+    pending: HashMap<DeviceId, Arc<Device>>,
+    // the order in which the devices have been updated first within the current cycle
+    order: Vec<DeviceId>,
+    published: HashMap<DeviceId, Arc<Device>>,
+}
+
+impl UpdateCoalescer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, device: Device) {
+        let device_id = device.id().clone();
+        if self
+            .pending
+            .insert(device_id.clone(), Arc::new(device))
+            .is_none()
+        {
+            self.order.push(device_id);
+        }
+    }
+
+    /// Forgets everything about the given device so that it can be removed from the subscribers.
+    pub fn remove(&mut self, device_id: &DeviceId) {
+        if self.pending.remove(device_id).is_some() {
+            self.order.retain(|pending| pending != device_id);
+        }
+        self.published.remove(device_id);
+    }
+
+    /// Publishes all collected device updates which changed any observable field.
+    pub fn flush(&mut self, sender: &dyn Sender) {
+        // keep the allocated buffer for the next cycle
+        let mut order = std::mem::take(&mut self.order);
+        for device_id in order.drain(..) {
+            let device = match self.pending.remove(&device_id) {
+                Some(device) => device,
+                None => continue,
+            };
+            if let Some(published) = self.published.get(device.id()) {
+                if Self::is_observably_equal(published, &device) {
+                    trace!("not publishing unchanged {}", device);
+                    continue;
+                }
+            }
+
+            debug!("publishing update for {}", device);
+            match sender.send(device.clone()) {
+                Ok(()) => {
+                    self.published.insert(device.id().clone(), device);
+                }
+                Err(e) => warn!("failed to publish update for {}: {}", device, e),
+            }
+        }
+        self.order = order;
+    }
+
+    // the time a device has last been seen and the number of missed pings change with every ping
+    // and are only interesting together with any other change
+    fn is_machine_observably_equal(machine: &Machine, other: &Machine) -> bool {
+        let Machine {
+            id,
+            name,
+            ip,
+            last_seen_timeout,
+            is_online,
+            last_seen: _,
+            last_seen_date: _,
+            consecutive_missed_pings: _,
+            probe_method,
+            is_ignored,
+            http,
+            port,
+            interval,
+            source,
+            is_off_subnet,
+        } = machine;
+
+        *id == other.id
+            && *name == other.name
+            && *ip == other.ip
+            && *last_seen_timeout == other.last_seen_timeout
+            && *is_online == other.is_online
+            && *probe_method == other.probe_method
+            && *is_ignored == other.is_ignored
+            && *http == other.http
+            && *port == other.port
+            && *interval == other.interval
+            && *source == other.source
+            && *is_off_subnet == other.is_off_subnet
+    }
+
+    fn is_observably_equal(device: &Device, other: &Device) -> bool {
+        match (device, other) {
+            (Device::Server(server), Device::Server(other)) => {
+                let Server {
+                    machine,
+                    mac,
+                    ssh,
+                    relay,
+                    services,
+                    external_dependencies,
+                    hostnames,
+                    action_timeouts,
+                    shutdown_profile,
+                    shutdown_verification,
+                    max_failed_shutdowns,
+                    max_daily_on_time,
+                    on_time_today,
+                    min_on_duration,
+                    min_off_duration,
+                    keep_on_when_away,
+                    consecutive_failed_actions,
+                    is_control_disabled,
+                    has_invalid_credentials,
+                    unexpected_onlines,
+                    last_unexpected_online,
+                    external_wakeups,
+                    last_external_wakeup,
+                    change_timeout_until,
+                    wakeup_deferred_until,
+                    transitions,
+                    last_triggers,
+                } = server;
+
+                Self::is_machine_observably_equal(machine, &other.machine)
+                    && *mac == other.mac
+                    && *ssh == other.ssh
+                    && *relay == other.relay
+                    && *services == other.services
+                    && *external_dependencies == other.external_dependencies
+                    && *hostnames == other.hostnames
+                    && *action_timeouts == other.action_timeouts
+                    && *shutdown_profile == other.shutdown_profile
+                    && *shutdown_verification == other.shutdown_verification
+                    && *max_failed_shutdowns == other.max_failed_shutdowns
+                    && *max_daily_on_time == other.max_daily_on_time
+                    && *on_time_today == other.on_time_today
+                    && *min_on_duration == other.min_on_duration
+                    && *min_off_duration == other.min_off_duration
+                    && *keep_on_when_away == other.keep_on_when_away
+                    && *consecutive_failed_actions == other.consecutive_failed_actions
+                    && *is_control_disabled == other.is_control_disabled
+                    && *has_invalid_credentials == other.has_invalid_credentials
+                    && *unexpected_onlines == other.unexpected_onlines
+                    && *last_unexpected_online == other.last_unexpected_online
+                    && *external_wakeups == other.external_wakeups
+                    && *last_external_wakeup == other.last_external_wakeup
+                    && *change_timeout_until == other.change_timeout_until
+                    && *wakeup_deferred_until == other.wakeup_deferred_until
+                    && *transitions == other.transitions
+                    && *last_triggers == other.last_triggers
+            }
+            (Device::Machine(machine), Device::Machine(other)) => {
+                Self::is_machine_observably_equal(machine, other)
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mockall::predicate::*;
+    use rstest::*;
+
+    use super::super::MockSender;
+    use super::*;
+    use crate::dom::device::test::*;
+    use crate::dom::Machine;
+
+    fn get_machine(machine: &Machine, is_online: bool) -> Device {
+        let mut machine = machine.clone();
+        machine.set_online(is_online);
+        Device::Machine(machine)
+    }
+
+    #[rstest]
+    fn test_update_coalescer_only_publishes_latest_update(machine: Machine) {
+        let mut coalescer = UpdateCoalescer::new();
+        let mut sender = MockSender::new();
+        sender
+            .expect_send()
+            .with(eq(Arc::new(get_machine(&machine, false))))
+            .once()
+            .returning(|_| Ok(()));
+
+        coalescer.push(get_machine(&machine, true));
+        coalescer.push(get_machine(&machine, false));
+        coalescer.flush(&sender);
+    }
+
+    #[rstest]
+    fn test_update_coalescer_ignores_unchanged_devices(machine: Machine) {
+        let mut coalescer = UpdateCoalescer::new();
+        let mut sender = MockSender::new();
+        sender.expect_send().times(2).returning(|_| Ok(()));
+
+        coalescer.push(get_machine(&machine, true));
+        coalescer.flush(&sender);
+
+        // the device flapped but ended up in the published state
+        coalescer.push(get_machine(&machine, false));
+        coalescer.push(get_machine(&machine, true));
+        coalescer.flush(&sender);
+
+        // the device is published again after it has been removed
+        coalescer.remove(&machine.id);
+        coalescer.push(get_machine(&machine, true));
+        coalescer.flush(&sender);
+    }
+
+    #[rstest]
+    fn test_update_coalescer_ignores_missed_pings(machine: Machine) {
+        let mut coalescer = UpdateCoalescer::new();
+        let mut sender = MockSender::new();
+        sender.expect_send().once().returning(|_| Ok(()));
+
+        // the device stays offline for two cycles
+        let mut offline = machine;
+        for _ in 0..2 {
+            offline.add_missed_ping();
+            coalescer.push(Device::Machine(offline.clone()));
+            coalescer.flush(&sender);
+        }
+    }
+
+    #[rstest]
+    fn test_update_coalescer_retries_failed_updates(machine: Machine) {
+        let mut coalescer = UpdateCoalescer::new();
+        let mut sender = MockSender::new();
+        let mut seq = mockall::Sequence::new();
+        sender
+            .expect_send()
+            .once()
+            .in_sequence(&mut seq)
+            .returning(|_| Err(anyhow::anyhow!("failed")));
+        sender
+            .expect_send()
+            .once()
+            .in_sequence(&mut seq)
+            .returning(|_| Ok(()));
+
+        coalescer.push(get_machine(&machine, true));
+        coalescer.flush(&sender);
+        coalescer.push(get_machine(&machine, true));
+        coalescer.flush(&sender);
+    }
+}
@@ -1,28 +1,63 @@
+use std::collections::HashMap;
 use std::convert::From;
 use std::fmt;
+use std::hash::{Hash, Hasher};
 use std::net::IpAddr;
 use std::str::FromStr;
 use std::string::ToString;
+use std::sync::Arc;
+use std::time::Duration;
 
 use chrono::{offset, DateTime, Utc};
 
 use super::super::configuration;
-use super::super::utils::{Instant, MacAddr};
+use super::super::utils::{Instant, MacAddr, Secret, REDACTED};
+use super::Transition;
 
-#[derive(Clone, Debug, Default, Hash, Eq, PartialEq, Ord, PartialOrd)]
-pub struct DeviceId(pub String);
+// the last trigger of a dependency only has to be precise enough to spot stale dependencies
+const LAST_TRIGGER_RESOLUTION: i64 = 3600;
+const MAX_TRANSITIONS: usize = 10;
+
+/// Identifier of a device which is shared (instead of copied) by all its clones so that cloning
+/// and comparing the identifiers of the same device is cheap.
+#[derive(Clone, Debug, Eq, Ord, PartialOrd)]
+pub struct DeviceId(pub Arc<str>);
+
+impl DeviceId {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Default for DeviceId {
+    fn default() -> Self {
+        Self(Arc::from(""))
+    }
+}
+
+impl PartialEq for DeviceId {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0) || self.0 == other.0
+    }
+}
+
+impl Hash for DeviceId {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash(state);
+    }
+}
 
 impl FromStr for DeviceId {
     type Err = anyhow::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(Self(s.to_string()))
+        Ok(Self(Arc::from(s)))
     }
 }
 
 impl From<&configuration::DeviceId> for DeviceId {
     fn from(device_id: &configuration::DeviceId) -> Self {
-        DeviceId(device_id.0.clone())
+        DeviceId(Arc::from(device_id.0.as_str()))
     }
 }
 
@@ -32,6 +67,44 @@ impl fmt::Display for DeviceId {
     }
 }
 
+/// Probe which produced the last "online" verdict of a machine.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProbeMethod {
+    Ping,
+    /// ARP requests on the local network (for devices which don't answer pings).
+    Arp,
+}
+
+impl fmt::Display for ProbeMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Ping => "ping",
+            Self::Arp => "arp",
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HttpCheck {
+    pub url: String,
+    pub expected_status: u16,
+    pub expected_body: Option<String>,
+    pub timeout: Duration,
+    pub insecure: bool,
+}
+
+impl From<&configuration::HttpCheck> for HttpCheck {
+    fn from(http: &configuration::HttpCheck) -> Self {
+        Self {
+            url: http.url.clone(),
+            expected_status: http.expected_status,
+            expected_body: http.expected_body.clone(),
+            timeout: Duration::from_secs(http.timeout),
+            insecure: http.insecure,
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Machine {
     pub id: DeviceId,
@@ -42,6 +115,19 @@ pub struct Machine {
     pub is_online: bool,
     pub last_seen: Option<Instant>,
     pub last_seen_date: Option<DateTime<Utc>>,
+
+    pub consecutive_missed_pings: u64,
+    pub probe_method: Option<ProbeMethod>,
+    /// Whether the machine being offline is currently ignored (e.g. during a maintenance window).
+    pub is_ignored: bool,
+
+    pub http: Option<HttpCheck>,
+    pub port: Option<u16>,
+    pub interval: Option<Duration>,
+    pub source: Option<IpAddr>,
+    /// Whether the machine isn't on a subnet of the monitored interface (e.g. behind a router)
+    /// and can't be reached by Wake-on-LAN or ARP requests.
+    pub is_off_subnet: bool,
 }
 
 impl Machine {
@@ -55,26 +141,50 @@ impl Machine {
             is_online: false,
             last_seen: None,
             last_seen_date: None,
+            consecutive_missed_pings: 0,
+            probe_method: None,
+            is_ignored: false,
+            http: None,
+            port: None,
+            interval: None,
+            source: None,
+            is_off_subnet: false,
         }
     }
 
     pub fn set_online(&mut self, online: bool) {
+        self.set_online_at(online, Instant::now(), offset::Utc::now());
+    }
+
+    pub fn set_online_at(&mut self, online: bool, now: Instant, date: DateTime<Utc>) {
         self.is_online = online;
         if online {
-            self.last_seen = Some(Instant::now());
-            self.last_seen_date = Some(offset::Utc::now());
+            self.last_seen = Some(now);
+            self.last_seen_date = Some(date);
+            self.consecutive_missed_pings = 0;
         }
     }
+
+    pub fn add_missed_ping(&mut self) {
+        self.consecutive_missed_pings += 1;
+    }
 }
 
 impl From<&configuration::Machine> for Machine {
     fn from(machine: &configuration::Machine) -> Self {
-        Self::new(
+        let mut dom_machine = Self::new(
             &DeviceId::from(&machine.id),
             &machine.name,
             machine.ip,
-            machine.last_seen_timeout,
-        )
+            // the timeout is guaranteed by the configuration check (directly or by the class)
+            machine.last_seen_timeout.unwrap_or_default(),
+        );
+        dom_machine.http = machine.http.as_ref().map(HttpCheck::from);
+        dom_machine.port = machine.port;
+        dom_machine.interval = machine.interval.map(Duration::from_secs);
+        dom_machine.source = machine.source;
+
+        dom_machine
     }
 }
 
@@ -117,10 +227,20 @@ impl From<SshPort> for u16 {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, PartialEq, Eq)]
 pub struct SshPrivateKeyAuthentication {
     pub file: String,
-    pub passphrase: String,
+    pub passphrase: Secret,
+}
+
+// only the file of the private key is worth logging
+impl fmt::Debug for SshPrivateKeyAuthentication {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SshPrivateKeyAuthentication")
+            .field("file", &self.file)
+            .field("passphrase", &REDACTED)
+            .finish()
+    }
 }
 
 impl From<&configuration::SshPrivateKeyAuthentication> for SshPrivateKeyAuthentication {
@@ -132,12 +252,21 @@ impl From<&configuration::SshPrivateKeyAuthentication> for SshPrivateKeyAuthenti
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, PartialEq, Eq)]
 pub enum SshAuthentication {
-    Password(String),
+    Password(Secret),
     PrivateKey(SshPrivateKeyAuthentication),
 }
 
+impl fmt::Debug for SshAuthentication {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Password(_) => f.debug_tuple("Password").field(&REDACTED).finish(),
+            Self::PrivateKey(pk) => f.debug_tuple("PrivateKey").field(pk).finish(),
+        }
+    }
+}
+
 impl From<&configuration::SshAuthentication> for SshAuthentication {
     fn from(auth: &configuration::SshAuthentication) -> Self {
         match auth {
@@ -153,6 +282,7 @@ impl From<&configuration::SshAuthentication> for SshAuthentication {
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Ssh {
+    pub host: Option<String>,
     pub port: SshPort,
 
     pub username: String,
@@ -162,6 +292,7 @@ pub struct Ssh {
 impl From<&configuration::Ssh> for Ssh {
     fn from(ssh: &configuration::Ssh) -> Self {
         Self {
+            host: ssh.host.clone(),
             port: SshPort::from(&ssh.port),
             username: ssh.username.clone(),
             authentication: SshAuthentication::from(&ssh.authentication),
@@ -169,12 +300,315 @@ impl From<&configuration::Ssh> for Ssh {
     }
 }
 
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ApiRelay {
+    pub url: String,
+    pub server: Option<DeviceId>,
+    pub token: Option<Secret>,
+}
+
+impl From<&configuration::ApiRelay> for ApiRelay {
+    fn from(relay: &configuration::ApiRelay) -> Self {
+        Self {
+            url: relay.url.clone(),
+            server: relay.server.as_ref().map(DeviceId::from),
+            token: relay.token.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SshRelay {
+    pub ip: IpAddr,
+    pub ssh: Ssh,
+    pub command: String,
+}
+
+impl From<&configuration::SshRelay> for SshRelay {
+    fn from(relay: &configuration::SshRelay) -> Self {
+        Self {
+            ip: relay.ip,
+            ssh: Ssh::from(&relay.ssh),
+            command: relay.command.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum WakeupRelay {
+    Api(ApiRelay),
+    Ssh(Box<SshRelay>),
+}
+
+impl From<&configuration::WakeupRelay> for WakeupRelay {
+    fn from(relay: &configuration::WakeupRelay) -> Self {
+        match relay {
+            configuration::WakeupRelay::Api(api_relay) => {
+                WakeupRelay::Api(ApiRelay::from(api_relay))
+            }
+            configuration::WakeupRelay::Ssh(ssh_relay) => {
+                WakeupRelay::Ssh(Box::new(SshRelay::from(ssh_relay.as_ref())))
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ServiceCheck {
+    Tcp(u16),
+    Http(HttpCheck),
+}
+
+impl From<&configuration::ServiceCheck> for ServiceCheck {
+    fn from(check: &configuration::ServiceCheck) -> Self {
+        match check {
+            configuration::ServiceCheck::Tcp { port } => ServiceCheck::Tcp(*port),
+            configuration::ServiceCheck::Http { http } => ServiceCheck::Http(HttpCheck::from(http)),
+        }
+    }
+}
+
+impl fmt::Display for ServiceCheck {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ServiceCheck::Tcp(port) => write!(f, "TCP port {}", port),
+            ServiceCheck::Http(http) => write!(f, "{}", http.url),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Service {
+    pub name: String,
+    pub check: ServiceCheck,
+    pub is_online: bool,
+}
+
+impl From<&configuration::Service> for Service {
+    fn from(service: &configuration::Service) -> Self {
+        Self {
+            name: service.name.clone(),
+            check: ServiceCheck::from(&service.check),
+            is_online: false,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SnmpCheck {
+    pub host: String,
+    pub port: u16,
+    pub community: Secret,
+    pub interface: u32,
+    pub threshold: u64,
+    pub timeout: Duration,
+}
+
+impl From<&configuration::SnmpCheck> for SnmpCheck {
+    fn from(snmp: &configuration::SnmpCheck) -> Self {
+        Self {
+            host: snmp.host.clone(),
+            port: snmp.port,
+            community: snmp.community.clone(),
+            interface: snmp.interface,
+            threshold: snmp.threshold,
+            timeout: Duration::from_secs(snmp.timeout),
+        }
+    }
+}
+
+impl fmt::Display for SnmpCheck {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "snmp://{}:{}/{}", self.host, self.port, self.interface)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SessionsCheck {
+    /// Command printing the number of active file sharing sessions.
+    pub command: String,
+    pub threshold: u32,
+}
+
+impl From<&configuration::SessionsCheck> for SessionsCheck {
+    fn from(sessions: &configuration::SessionsCheck) -> Self {
+        let port = match sessions.protocol {
+            configuration::FileSharingProtocol::Smb => 445,
+            configuration::FileSharingProtocol::Nfs => 2049,
+        };
+
+        Self {
+            command: sessions.command.clone().unwrap_or_else(|| {
+                format!("ss -Htn state established '( sport = :{port} )' | wc -l")
+            }),
+            threshold: sessions.threshold,
+        }
+    }
+}
+
+impl fmt::Display for SessionsCheck {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ssh:{}", self.command)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ExternalCheck {
+    Http(HttpCheck),
+    Snmp(SnmpCheck),
+    Sessions(SessionsCheck),
+}
+
+impl From<&configuration::ExternalCheck> for ExternalCheck {
+    fn from(check: &configuration::ExternalCheck) -> Self {
+        match check {
+            configuration::ExternalCheck::Http { http } => {
+                ExternalCheck::Http(HttpCheck::from(http))
+            }
+            configuration::ExternalCheck::Snmp { snmp } => {
+                ExternalCheck::Snmp(SnmpCheck::from(snmp))
+            }
+            configuration::ExternalCheck::Sessions { sessions } => {
+                ExternalCheck::Sessions(SessionsCheck::from(sessions))
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExternalDependency {
+    pub name: String,
+    pub check: ExternalCheck,
+    pub cache: Duration,
+    pub is_active: bool,
+}
+
+impl From<&configuration::ExternalDependency> for ExternalDependency {
+    fn from(dependency: &configuration::ExternalDependency) -> Self {
+        Self {
+            name: dependency.name.clone(),
+            check: ExternalCheck::from(&dependency.check),
+            cache: Duration::from_secs(dependency.cache),
+            is_active: false,
+        }
+    }
+}
+
+/// Commands to shut down a server through SSH and to verify that the user is allowed to do so.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ShutdownProfile {
+    pub command: String,
+    pub privileges_command: String,
+}
+
+impl Default for ShutdownProfile {
+    fn default() -> Self {
+        Self {
+            command: "shutdown -h now".to_string(),
+            privileges_command: "sudo -n true".to_string(),
+        }
+    }
+}
+
+impl From<Option<configuration::ServerOs>> for ShutdownProfile {
+    fn from(os: Option<configuration::ServerOs>) -> Self {
+        let command = match os {
+            None => return Self::default(),
+            Some(configuration::ServerOs::LinuxSystemd) => "systemctl poweroff",
+            Some(configuration::ServerOs::LinuxSysv) => "shutdown -h now",
+            // FreeBSD only halts the system without powering it off with -h
+            Some(configuration::ServerOs::Freebsd) => "shutdown -p now",
+            Some(configuration::ServerOs::WindowsSsh) => "shutdown /s /t 0",
+            Some(configuration::ServerOs::Macos) => "shutdown -h now",
+        };
+        let privileges_command = match os {
+            // only succeeds with administrator privileges
+            Some(configuration::ServerOs::WindowsSsh) => "net session",
+            _ => "sudo -n true",
+        };
+
+        Self {
+            command: command.to_string(),
+            privileges_command: privileges_command.to_string(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ActionTimeouts {
+    pub wakeup: Option<Duration>,
+    pub shutdown: Option<Duration>,
+}
+
+impl From<&configuration::ActionTimeouts> for ActionTimeouts {
+    fn from(timeouts: &configuration::ActionTimeouts) -> Self {
+        Self {
+            wakeup: timeouts.wakeup.map(Duration::from_secs),
+            shutdown: timeouts.shutdown.map(Duration::from_secs),
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Server {
     pub machine: Machine,
 
     pub mac: MacAddr,
     pub ssh: Ssh,
+
+    pub relay: Option<WakeupRelay>,
+
+    pub services: Vec<Service>,
+
+    pub external_dependencies: Vec<ExternalDependency>,
+
+    pub hostnames: Vec<String>,
+
+    pub action_timeouts: ActionTimeouts,
+
+    pub shutdown_profile: ShutdownProfile,
+
+    /// Time within which the server has to stop answering pings after it has been shut down.
+    pub shutdown_verification: Option<Duration>,
+    /// Number of consecutive failed shutdowns after which the control of the server is disabled.
+    pub max_failed_shutdowns: Option<u32>,
+
+    pub max_daily_on_time: Option<Duration>,
+    /// Time the server has been online today (in whole minutes).
+    pub on_time_today: Duration,
+
+    pub min_on_duration: Option<Duration>,
+    pub min_off_duration: Option<Duration>,
+
+    /// Whether the server is kept online (instead of being shut down) while away.
+    pub keep_on_when_away: bool,
+
+    pub consecutive_failed_actions: u64,
+    /// Whether the server is only monitored (and no longer woken up / shut down automatically).
+    pub is_control_disabled: bool,
+    /// Whether the server rejected the configured credentials (and isn't shut down anymore).
+    pub has_invalid_credentials: bool,
+
+    pub unexpected_onlines: u64,
+    pub last_unexpected_online: Option<DateTime<Utc>>,
+
+    /// Wakeups of the server by other tools observed through their Wake-on-LAN magic packets.
+    pub external_wakeups: u64,
+    pub last_external_wakeup: Option<DateTime<Utc>>,
+
+    /// End of the timeout after the last wakeup / shutdown during which no further action is run.
+    pub change_timeout_until: Option<DateTime<Utc>>,
+
+    /// Time until which the wakeup of the server is deferred to stagger it with the wakeups of
+    /// other servers.
+    pub wakeup_deferred_until: Option<DateTime<Utc>>,
+
+    /// Most recent changes of the online state (oldest first).
+    pub transitions: Vec<Transition>,
+
+    /// Last time the online state of each dependency influenced whether the server is woken up
+    /// or kept online.
+    pub last_triggers: HashMap<DeviceId, DateTime<Utc>>,
 }
 
 impl Server {
@@ -191,6 +625,100 @@ impl Server {
             machine: Machine::new(id, name, ip, last_seen_timeout),
             mac,
             ssh,
+            relay: None,
+            services: Vec::new(),
+            external_dependencies: Vec::new(),
+            hostnames: Vec::new(),
+            action_timeouts: ActionTimeouts::default(),
+            shutdown_profile: ShutdownProfile::default(),
+            shutdown_verification: None,
+            max_failed_shutdowns: None,
+            max_daily_on_time: None,
+            on_time_today: Duration::ZERO,
+            min_on_duration: None,
+            min_off_duration: None,
+            keep_on_when_away: false,
+            consecutive_failed_actions: 0,
+            is_control_disabled: false,
+            has_invalid_credentials: false,
+            unexpected_onlines: 0,
+            last_unexpected_online: None,
+            external_wakeups: 0,
+            last_external_wakeup: None,
+            change_timeout_until: None,
+            wakeup_deferred_until: None,
+            transitions: Vec::new(),
+            last_triggers: HashMap::new(),
+        }
+    }
+
+    pub fn set_action_result(&mut self, succeeded: bool) {
+        if succeeded {
+            self.consecutive_failed_actions = 0;
+        } else {
+            self.consecutive_failed_actions += 1;
+        }
+    }
+
+    pub fn add_unexpected_online(&mut self) {
+        self.unexpected_onlines += 1;
+        self.last_unexpected_online = Some(offset::Utc::now());
+    }
+
+    pub fn add_external_wakeup(&mut self) {
+        self.external_wakeups += 1;
+        self.last_external_wakeup = Some(offset::Utc::now());
+    }
+
+    /// Records a change of the online state keeping only the most recent ones.
+    pub fn add_transition(&mut self, transition: Transition) {
+        if self.transitions.len() >= MAX_TRANSITIONS {
+            self.transitions.remove(0);
+        }
+        self.transitions.push(transition);
+    }
+
+    /// Returns the remaining time of the timeout after the last wakeup / shutdown.
+    pub fn remaining_change_timeout(&self, now: DateTime<Utc>) -> Duration {
+        self.change_timeout_until
+            .and_then(|until| (until - now).to_std().ok())
+            .unwrap_or(Duration::ZERO)
+    }
+
+    /// Returns the remaining time the wakeup of the server is deferred.
+    pub fn remaining_wakeup_deferral(&self, now: DateTime<Utc>) -> Duration {
+        self.wakeup_deferred_until
+            .and_then(|until| (until - now).to_std().ok())
+            .unwrap_or(Duration::ZERO)
+    }
+
+    /// Updates the time the server has been online today and returns whether it changed.
+    pub fn set_on_time_today(&mut self, on_time: Duration) -> bool {
+        let on_time = Duration::from_secs(on_time.as_secs() / 60 * 60);
+        if self.on_time_today == on_time {
+            return false;
+        }
+
+        self.on_time_today = on_time;
+        true
+    }
+
+    /// Whether the server has been online longer than its daily budget allows.
+    pub fn is_budget_exhausted(&self) -> bool {
+        matches!(self.max_daily_on_time, Some(max_daily_on_time) if self.on_time_today >= max_daily_on_time)
+    }
+
+    /// Records that the online state of the given dependency influenced the server at the given
+    /// time. To not change the server with every cycle the time is only updated once per hour.
+    pub fn record_trigger(&mut self, device_id: &DeviceId, now: DateTime<Utc>) -> bool {
+        match self.last_triggers.get(device_id) {
+            Some(last_trigger) if (now - *last_trigger).num_seconds() < LAST_TRIGGER_RESOLUTION => {
+                false
+            }
+            _ => {
+                self.last_triggers.insert(device_id.clone(), now);
+                true
+            }
         }
     }
 }
@@ -201,6 +729,34 @@ impl From<&configuration::Server> for Server {
             machine: Machine::from(&server.machine),
             mac: server.mac,
             ssh: Ssh::from(&server.ssh),
+            relay: server.relay.as_ref().map(WakeupRelay::from),
+            services: server.services.iter().map(Service::from).collect(),
+            external_dependencies: server
+                .external_dependencies
+                .iter()
+                .map(ExternalDependency::from)
+                .collect(),
+            hostnames: server.hostnames.clone(),
+            action_timeouts: ActionTimeouts::from(&server.action_timeouts),
+            shutdown_profile: ShutdownProfile::from(server.os),
+            shutdown_verification: server.shutdown_verification.map(Duration::from_secs),
+            max_failed_shutdowns: server.max_failed_shutdowns,
+            max_daily_on_time: server.max_daily_on_time.map(Duration::from_secs),
+            on_time_today: Duration::ZERO,
+            min_on_duration: server.min_on_duration.map(Duration::from_secs),
+            min_off_duration: server.min_off_duration.map(Duration::from_secs),
+            keep_on_when_away: server.keep_on_when_away,
+            consecutive_failed_actions: 0,
+            is_control_disabled: false,
+            has_invalid_credentials: false,
+            unexpected_onlines: 0,
+            last_unexpected_online: None,
+            external_wakeups: 0,
+            last_external_wakeup: None,
+            change_timeout_until: None,
+            wakeup_deferred_until: None,
+            transitions: Vec::new(),
+            last_triggers: HashMap::new(),
         }
     }
 }
@@ -212,13 +768,14 @@ impl fmt::Display for Server {
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
+// servers are inherently a lot larger than machines
+#[allow(clippy::large_enum_variant)]
 pub enum Device {
     Server(Server),
     Machine(Machine),
 }
 
 impl Device {
-    #[allow(dead_code)]
     pub fn id(&self) -> &DeviceId {
         match self {
             Device::Server(server) => &server.machine.id,
@@ -250,6 +807,13 @@ impl Device {
         }
     }
 
+    pub fn interval(&self) -> Option<Duration> {
+        match self {
+            Device::Server(server) => server.machine.interval,
+            Device::Machine(machine) => machine.interval,
+        }
+    }
+
     #[allow(dead_code)]
     pub fn last_seen(&self) -> Option<Instant> {
         match self {
@@ -273,6 +837,56 @@ impl Device {
             Device::Machine(machine) => machine.set_online(online),
         };
     }
+
+    pub fn set_online_at(&mut self, online: bool, now: Instant, date: DateTime<Utc>) {
+        match self {
+            Device::Server(server) => server.machine.set_online_at(online, now, date),
+            Device::Machine(machine) => machine.set_online_at(online, now, date),
+        };
+    }
+
+    #[allow(dead_code)]
+    pub fn consecutive_missed_pings(&self) -> u64 {
+        match self {
+            Device::Server(server) => server.machine.consecutive_missed_pings,
+            Device::Machine(machine) => machine.consecutive_missed_pings,
+        }
+    }
+
+    pub fn add_missed_ping(&mut self) {
+        match self {
+            Device::Server(server) => server.machine.add_missed_ping(),
+            Device::Machine(machine) => machine.add_missed_ping(),
+        };
+    }
+
+    pub fn probe_method(&self) -> Option<ProbeMethod> {
+        match self {
+            Device::Server(server) => server.machine.probe_method,
+            Device::Machine(machine) => machine.probe_method,
+        }
+    }
+
+    pub fn set_probe_method(&mut self, probe_method: ProbeMethod) {
+        match self {
+            Device::Server(server) => server.machine.probe_method = Some(probe_method),
+            Device::Machine(machine) => machine.probe_method = Some(probe_method),
+        };
+    }
+
+    pub fn is_ignored(&self) -> bool {
+        match self {
+            Device::Server(server) => server.machine.is_ignored,
+            Device::Machine(machine) => machine.is_ignored,
+        }
+    }
+
+    pub fn set_ignored(&mut self, is_ignored: bool) {
+        match self {
+            Device::Server(server) => server.machine.is_ignored = is_ignored,
+            Device::Machine(machine) => machine.is_ignored = is_ignored,
+        };
+    }
 }
 
 impl fmt::Display for Device {
@@ -328,9 +942,10 @@ pub mod test {
             SERVER_LAST_SEEN_TIMEOUT,
             server_mac(),
             Ssh {
+                host: None,
                 port: SERVER_SSH_PORT,
                 username: SERVER_SSH_USERNAME.to_string(),
-                authentication: SshAuthentication::Password(SERVER_SSH_PASSWORD.to_string()),
+                authentication: SshAuthentication::Password(SERVER_SSH_PASSWORD.into()),
             },
         )
     }
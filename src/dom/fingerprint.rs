@@ -0,0 +1,163 @@
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OperatingSystem {
+    Windows,
+    MacOs,
+    Ios,
+    Linux,
+    /// Any Unix-like operating system (Linux, macOS, iOS, Android, the BSDs).
+    Unix,
+    /// Operating system of routers, switches, printers and the like.
+    Embedded,
+}
+
+impl fmt::Display for OperatingSystem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Windows => "windows",
+            Self::MacOs => "macOs",
+            Self::Ios => "ios",
+            Self::Linux => "linux",
+            Self::Unix => "unix",
+            Self::Embedded => "embedded",
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DeviceType {
+    Computer,
+    Server,
+    Phone,
+    Printer,
+    MediaPlayer,
+    Nas,
+    SmartHome,
+    NetworkDevice,
+}
+
+impl fmt::Display for DeviceType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Computer => "computer",
+            Self::Server => "server",
+            Self::Phone => "phone",
+            Self::Printer => "printer",
+            Self::MediaPlayer => "mediaPlayer",
+            Self::Nas => "nas",
+            Self::SmartHome => "smartHome",
+            Self::NetworkDevice => "networkDevice",
+        })
+    }
+}
+
+/// Operating system and type of a device inferred from how it responds on the network.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Fingerprint {
+    pub os: Option<OperatingSystem>,
+    pub device_type: Option<DeviceType>,
+    /// TTL of the ping response.
+    pub ttl: Option<u8>,
+    pub open_ports: Vec<u16>,
+    /// Service types announced through mDNS (e.g. `_ipp._tcp`).
+    pub services: Vec<String>,
+}
+
+impl Fingerprint {
+    pub fn infer(ttl: Option<u8>, open_ports: Vec<u16>, services: Vec<String>) -> Self {
+        let has_port = |port: u16| open_ports.contains(&port);
+        let has_service = |service: &str| services.iter().any(|s| s == service);
+
+        // operating systems use different initial TTLs which are only decremented by a few hops
+        // within the local network
+        let mut os = ttl.map(|ttl| match ttl {
+            0..=64 => OperatingSystem::Unix,
+            65..=128 => OperatingSystem::Windows,
+            _ => OperatingSystem::Embedded,
+        });
+
+        let device_type = if has_port(62078) {
+            os = Some(OperatingSystem::Ios);
+            Some(DeviceType::Phone)
+        } else if has_port(631)
+            || has_port(9100)
+            || has_service("_ipp._tcp")
+            || has_service("_printer._tcp")
+            || has_service("_pdl-datastream._tcp")
+        {
+            Some(DeviceType::Printer)
+        } else if has_port(8009)
+            || has_port(32400)
+            || has_service("_googlecast._tcp")
+            || has_service("_airplay._tcp")
+            || has_service("_raop._tcp")
+        {
+            Some(DeviceType::MediaPlayer)
+        } else if has_port(5000) || has_port(5001) || has_service("_afpovertcp._tcp") {
+            Some(DeviceType::Nas)
+        } else if has_port(8123) || has_service("_hap._tcp") || has_service("_home-assistant._tcp")
+        {
+            Some(DeviceType::SmartHome)
+        } else if has_port(3389) || (has_port(135) && has_port(445)) {
+            os = Some(OperatingSystem::Windows);
+            Some(DeviceType::Computer)
+        } else if has_port(548) || has_service("_companion-link._tcp") {
+            os = Some(OperatingSystem::MacOs);
+            Some(DeviceType::Computer)
+        } else if has_service("_workstation._tcp") {
+            os = Some(OperatingSystem::Linux);
+            Some(DeviceType::Computer)
+        } else if has_port(22) && (has_port(80) || has_port(443) || has_port(445)) {
+            Some(DeviceType::Server)
+        } else if os == Some(OperatingSystem::Embedded) && (has_port(80) || has_port(443)) {
+            Some(DeviceType::NetworkDevice)
+        } else {
+            None
+        };
+
+        Self {
+            os,
+            device_type,
+            ttl,
+            open_ports,
+            services,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::*;
+
+    use super::*;
+
+    #[rstest]
+    #[case(Some(64), vec![], vec![], Some(OperatingSystem::Unix), None)]
+    #[case(Some(127), vec![], vec![], Some(OperatingSystem::Windows), None)]
+    #[case(Some(254), vec![80], vec![], Some(OperatingSystem::Embedded), Some(DeviceType::NetworkDevice))]
+    #[case(Some(128), vec![135, 445, 3389], vec![], Some(OperatingSystem::Windows), Some(DeviceType::Computer))]
+    #[case(Some(64), vec![62078], vec![], Some(OperatingSystem::Ios), Some(DeviceType::Phone))]
+    #[case(Some(64), vec![], vec!["_companion-link._tcp"], Some(OperatingSystem::MacOs), Some(DeviceType::Computer))]
+    #[case(Some(64), vec![], vec!["_workstation._tcp"], Some(OperatingSystem::Linux), Some(DeviceType::Computer))]
+    #[case(Some(64), vec![22, 443], vec![], Some(OperatingSystem::Unix), Some(DeviceType::Server))]
+    #[case(Some(255), vec![80, 9100], vec!["_ipp._tcp"], Some(OperatingSystem::Embedded), Some(DeviceType::Printer))]
+    #[case(Some(64), vec![8009], vec!["_googlecast._tcp"], Some(OperatingSystem::Unix), Some(DeviceType::MediaPlayer))]
+    #[case(Some(64), vec![22, 5000], vec![], Some(OperatingSystem::Unix), Some(DeviceType::Nas))]
+    #[case(Some(64), vec![8123], vec![], Some(OperatingSystem::Unix), Some(DeviceType::SmartHome))]
+    #[case(None, vec![], vec![], None, None)]
+    fn test_fingerprint_infers_os_and_type(
+        #[case] ttl: Option<u8>,
+        #[case] open_ports: Vec<u16>,
+        #[case] services: Vec<&str>,
+        #[case] expected_os: Option<OperatingSystem>,
+        #[case] expected_device_type: Option<DeviceType>,
+    ) {
+        let services = services.into_iter().map(str::to_string).collect();
+        let fingerprint = Fingerprint::infer(ttl, open_ports, services);
+
+        assert_eq!(fingerprint.os, expected_os);
+        assert_eq!(fingerprint.device_type, expected_device_type);
+        assert_eq!(fingerprint.ttl, ttl);
+    }
+}
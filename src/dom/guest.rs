@@ -0,0 +1,20 @@
+use std::time::Duration;
+
+use super::{DeviceId, Machine};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Guest {
+    pub machine: Machine,
+    pub servers: Vec<DeviceId>,
+    pub ttl: Duration,
+}
+
+impl Guest {
+    pub fn new(machine: Machine, servers: Vec<DeviceId>, ttl: Duration) -> Self {
+        Self {
+            machine,
+            servers,
+            ttl,
+        }
+    }
+}
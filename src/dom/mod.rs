@@ -1,12 +1,33 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
+mod action_context;
+pub mod calendar;
 pub mod communication;
 pub mod device;
+mod fingerprint;
+mod guest;
+mod note;
+mod schedule;
+mod trace_context;
+mod transition;
 
-pub use device::{Device, DeviceId, Machine, Server};
+pub use action_context::ActionContext;
+pub use calendar::{parse_ical, Calendar};
+pub use device::{Device, DeviceId, Machine, ProbeMethod, Server};
+pub use fingerprint::Fingerprint;
+#[cfg(test)]
+pub use fingerprint::{DeviceType, OperatingSystem};
+pub use guest::Guest;
+pub use note::Note;
+pub use schedule::{plan, PlannedAction, Schedule};
+pub use trace_context::TraceContext;
+pub use transition::{Transition, TransitionCause};
 
 pub type Dependencies = HashMap<DeviceId, Vec<DeviceId>>;
 
+/// Custom key / value attributes of a device set by external automations.
+pub type Attributes = BTreeMap<String, String>;
+
 #[cfg(test)]
 pub mod test {
     use rstest::*;
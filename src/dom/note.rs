@@ -0,0 +1,13 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Timestamped note attached to a device through the web API (e.g. "PSU replaced").
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Note {
+    pub date: DateTime<Utc>,
+    pub text: String,
+    /// Whether the note describes an incident (e.g. an outage) rather than a regular change.
+    #[serde(default)]
+    pub is_incident: bool,
+}
@@ -0,0 +1,439 @@
+use std::collections::HashMap;
+use std::f64::consts::PI;
+use std::fmt;
+
+use chrono::{DateTime, Datelike, Duration, NaiveTime, TimeZone, Utc, Weekday};
+
+use super::{Calendar, DeviceId};
+use crate::configuration;
+
+// Julian date of the UNIX epoch and of the J2000 epoch
+const JULIAN_UNIX_EPOCH: f64 = 2_440_587.5;
+const JULIAN_2000: f64 = 2_451_545.0;
+// apparent altitude of the center of the sun at sunrise and sunset (refraction and disc size)
+const SUNRISE_ALTITUDE: f64 = -0.833;
+const EARTH_AXIAL_TILT: f64 = 23.4397;
+// time windows are configured with a precision of one minute
+const PLAN_STEP: i64 = 60;
+
+/// Pseudo device which is active during a time window, depending on the position of the sun or
+/// during the events of a calendar.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Schedule {
+    Time {
+        id: DeviceId,
+        from: NaiveTime,
+        to: NaiveTime,
+        days: Vec<Weekday>,
+    },
+    Sun {
+        id: DeviceId,
+        latitude: f64,
+        longitude: f64,
+        daylight: bool,
+    },
+    Calendar {
+        id: DeviceId,
+        calendar: Calendar,
+    },
+}
+
+fn weekday(day: &configuration::Weekday) -> Weekday {
+    match day {
+        configuration::Weekday::Mon => Weekday::Mon,
+        configuration::Weekday::Tue => Weekday::Tue,
+        configuration::Weekday::Wed => Weekday::Wed,
+        configuration::Weekday::Thu => Weekday::Thu,
+        configuration::Weekday::Fri => Weekday::Fri,
+        configuration::Weekday::Sat => Weekday::Sat,
+        configuration::Weekday::Sun => Weekday::Sun,
+    }
+}
+
+fn to_julian(time: &DateTime<Utc>) -> f64 {
+    time.timestamp_millis() as f64 / 86_400_000.0 + JULIAN_UNIX_EPOCH
+}
+
+fn sin(degrees: f64) -> f64 {
+    (degrees * PI / 180.0).sin()
+}
+
+fn cos(degrees: f64) -> f64 {
+    (degrees * PI / 180.0).cos()
+}
+
+/// Determines whether the sun is above the horizon at the given location and time using the
+/// sunrise equation of the solar day closest to the given time.
+fn is_daylight(latitude: f64, longitude: f64, now: &DateTime<Utc>) -> bool {
+    // mean solar noon closest to the given time
+    let cycle = (to_julian(now) - JULIAN_2000 - 0.0009 + longitude / 360.0).round();
+    let mean_noon = cycle + 0.0009 - longitude / 360.0;
+
+    let anomaly = (357.5291 + 0.985_600_28 * mean_noon).rem_euclid(360.0);
+    let center = 1.9148 * sin(anomaly) + 0.02 * sin(2.0 * anomaly) + 0.0003 * sin(3.0 * anomaly);
+    let ecliptic_longitude = (anomaly + center + 180.0 + 102.9372).rem_euclid(360.0);
+    let transit =
+        JULIAN_2000 + mean_noon + 0.0053 * sin(anomaly) - 0.0069 * sin(2.0 * ecliptic_longitude);
+
+    let declination = (sin(ecliptic_longitude) * sin(EARTH_AXIAL_TILT)).asin() * 180.0 / PI;
+    let cos_hour_angle = (sin(SUNRISE_ALTITUDE) - sin(latitude) * sin(declination))
+        / (cos(latitude) * cos(declination));
+    if cos_hour_angle <= -1.0 {
+        // polar day
+        return true;
+    }
+    if cos_hour_angle >= 1.0 {
+        // polar night
+        return false;
+    }
+
+    let hour_angle = cos_hour_angle.acos() * 180.0 / PI;
+    let now = to_julian(now);
+    now >= transit - hour_angle / 360.0 && now < transit + hour_angle / 360.0
+}
+
+impl Schedule {
+    /// Creates the schedule matching the given pseudo device ID (if it is configured). Calendars
+    /// are shared with the task keeping their events up to date.
+    pub fn new(
+        id: &configuration::DeviceId,
+        schedules: &configuration::Schedules,
+        calendars: &HashMap<String, Calendar>,
+    ) -> Option<Self> {
+        if let Some(name) = id.0.strip_prefix(configuration::TIME_PREFIX) {
+            let time = schedules.times.get(name)?;
+            return Some(Self::Time {
+                id: DeviceId::from(id),
+                from: NaiveTime::parse_from_str(&time.from, "%H:%M").ok()?,
+                to: NaiveTime::parse_from_str(&time.to, "%H:%M").ok()?,
+                days: time.days.iter().map(weekday).collect(),
+            });
+        }
+        if let Some(name) = id.0.strip_prefix(configuration::CALENDAR_PREFIX) {
+            return Some(Self::Calendar {
+                id: DeviceId::from(id),
+                calendar: calendars.get(name)?.clone(),
+            });
+        }
+
+        let location = schedules.location.as_ref()?;
+        let daylight = match id.0.as_str() {
+            id if id == configuration::SUN_DAYLIGHT => true,
+            id if id == configuration::SUN_NIGHT => false,
+            _ => return None,
+        };
+        Some(Self::Sun {
+            id: DeviceId::from(id),
+            latitude: location.latitude,
+            longitude: location.longitude,
+            daylight,
+        })
+    }
+
+    pub fn id(&self) -> &DeviceId {
+        match self {
+            Self::Time { id, .. } => id,
+            Self::Sun { id, .. } => id,
+            Self::Calendar { id, .. } => id,
+        }
+    }
+
+    pub fn is_active<Tz: TimeZone>(&self, now: &DateTime<Tz>) -> bool {
+        match self {
+            Self::Time { from, to, days, .. } => {
+                let starts_on = |day: Weekday| days.is_empty() || days.contains(&day);
+                let time = now.time();
+                let today = now.weekday();
+                if from < to {
+                    starts_on(today) && time >= *from && time < *to
+                } else if from > to {
+                    // the time window spans midnight
+                    (starts_on(today) && time >= *from) || (starts_on(today.pred()) && time < *to)
+                } else {
+                    starts_on(today)
+                }
+            }
+            Self::Sun {
+                latitude,
+                longitude,
+                daylight,
+                ..
+            } => is_daylight(*latitude, *longitude, &now.with_timezone(&Utc)) == *daylight,
+            Self::Calendar { calendar, .. } => calendar.is_active(&now.with_timezone(&Utc)),
+        }
+    }
+}
+
+/// Change of a server caused by its schedules.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PlannedAction {
+    /// The first of the schedules the server depends on becomes active.
+    Wakeup,
+    /// The last of the schedules the server depends on ends (the server is only shut down if
+    /// none of its other dependencies is online).
+    Shutdown,
+    BlackoutStart,
+    BlackoutEnd,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PlannedChange<Tz: TimeZone> {
+    pub time: DateTime<Tz>,
+    pub action: PlannedAction,
+    /// Schedules which started (wakeup / blackout start) or ended (shutdown / blackout end).
+    pub schedules: Vec<DeviceId>,
+}
+
+/// Evaluates the given schedules and blackouts of a server between the given times and returns
+/// the planned changes (oldest first).
+pub fn plan<Tz: TimeZone>(
+    schedules: &[Schedule],
+    blackouts: &[Schedule],
+    from: &DateTime<Tz>,
+    until: &DateTime<Tz>,
+) -> Vec<PlannedChange<Tz>> {
+    let active = |schedules: &[Schedule], time: &DateTime<Tz>| -> Vec<DeviceId> {
+        schedules
+            .iter()
+            .filter(|schedule| schedule.is_active(time))
+            .map(|schedule| schedule.id().clone())
+            .collect()
+    };
+    let change = |before: &[DeviceId], after: &[DeviceId], start, end| match (
+        before.is_empty(),
+        after.is_empty(),
+    ) {
+        (true, false) => Some((start, after.to_vec())),
+        (false, true) => Some((end, before.to_vec())),
+        _ => None,
+    };
+
+    let mut changes = Vec::new();
+    let mut active_schedules = active(schedules, from);
+    let mut active_blackouts = active(blackouts, from);
+    let mut time = from.clone() + Duration::seconds(PLAN_STEP);
+    while time <= *until {
+        let schedules_now = active(schedules, &time);
+        let blackouts_now = active(blackouts, &time);
+        for (action, schedules) in [
+            change(
+                &active_schedules,
+                &schedules_now,
+                PlannedAction::Wakeup,
+                PlannedAction::Shutdown,
+            ),
+            change(
+                &active_blackouts,
+                &blackouts_now,
+                PlannedAction::BlackoutStart,
+                PlannedAction::BlackoutEnd,
+            ),
+        ]
+        .iter()
+        .flatten()
+        {
+            changes.push(PlannedChange {
+                time: time.clone(),
+                action: *action,
+                schedules: schedules.clone(),
+            });
+        }
+
+        active_schedules = schedules_now;
+        active_blackouts = blackouts_now;
+        time += Duration::seconds(PLAN_STEP);
+    }
+
+    changes
+}
+
+impl fmt::Display for Schedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.id(), f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::*;
+
+    use super::*;
+
+    fn utc(time: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(time)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn time_schedule(from: &str, to: &str, days: Vec<Weekday>) -> Schedule {
+        Schedule::Time {
+            id: "time:test".parse().unwrap(),
+            from: NaiveTime::parse_from_str(from, "%H:%M").unwrap(),
+            to: NaiveTime::parse_from_str(to, "%H:%M").unwrap(),
+            days,
+        }
+    }
+
+    fn sun_schedule(latitude: f64, longitude: f64) -> Schedule {
+        Schedule::Sun {
+            id: configuration::SUN_DAYLIGHT.parse().unwrap(),
+            latitude,
+            longitude,
+            daylight: true,
+        }
+    }
+
+    // 2023-06-19 is a monday
+    #[rstest]
+    #[case("08:00", "18:00", vec![], "2023-06-19T12:00:00Z", true)]
+    #[case("08:00", "18:00", vec![], "2023-06-19T18:00:00Z", false)]
+    #[case("08:00", "18:00", vec![Weekday::Tue], "2023-06-19T12:00:00Z", false)]
+    #[case("22:00", "06:00", vec![], "2023-06-19T23:00:00Z", true)]
+    #[case("22:00", "06:00", vec![], "2023-06-19T03:00:00Z", true)]
+    #[case("22:00", "06:00", vec![], "2023-06-19T12:00:00Z", false)]
+    #[case("22:00", "06:00", vec![Weekday::Sun], "2023-06-19T03:00:00Z", true)]
+    #[case("22:00", "06:00", vec![Weekday::Sun], "2023-06-19T23:00:00Z", false)]
+    #[case("00:00", "00:00", vec![Weekday::Mon], "2023-06-19T12:00:00Z", true)]
+    fn test_time_schedule_is_active(
+        #[case] from: &str,
+        #[case] to: &str,
+        #[case] days: Vec<Weekday>,
+        #[case] now: &str,
+        #[case] expected: bool,
+    ) {
+        assert_eq!(time_schedule(from, to, days).is_active(&utc(now)), expected);
+    }
+
+    // Vienna: sunrise at 02:53 UTC and sunset at 18:58 UTC on 2023-06-21
+    #[rstest]
+    #[case(48.21, 16.37, "2023-06-21T12:00:00Z", true)]
+    #[case(48.21, 16.37, "2023-06-21T02:30:00Z", false)]
+    #[case(48.21, 16.37, "2023-06-21T03:15:00Z", true)]
+    #[case(48.21, 16.37, "2023-06-21T19:15:00Z", false)]
+    #[case(48.21, 16.37, "2023-12-21T17:00:00Z", false)]
+    // Sydney: sunrise at 19:41 UTC (on the day before) and sunset at 06:53 UTC on 2023-06-21
+    #[case(-33.87, 151.21, "2023-06-21T03:00:00Z", true)]
+    #[case(-33.87, 151.21, "2023-06-21T12:00:00Z", false)]
+    // Tromsø: midnight sun in summer and polar night in winter
+    #[case(69.65, 18.96, "2023-06-21T23:00:00Z", true)]
+    #[case(69.65, 18.96, "2023-12-21T11:00:00Z", false)]
+    fn test_sun_schedule_is_active_during_daylight(
+        #[case] latitude: f64,
+        #[case] longitude: f64,
+        #[case] now: &str,
+        #[case] expected: bool,
+    ) {
+        assert_eq!(
+            sun_schedule(latitude, longitude).is_active(&utc(now)),
+            expected
+        );
+    }
+
+    #[rstest]
+    fn test_plan_returns_changes_of_schedules_and_blackouts() {
+        let mut night = time_schedule("22:00", "06:00", vec![]);
+        if let Schedule::Time { id, .. } = &mut night {
+            *id = "time:night".parse().unwrap();
+        }
+        let schedules = vec![time_schedule("08:00", "18:00", vec![]), night];
+        let blackouts = vec![Schedule::Time {
+            id: "time:backup".parse().unwrap(),
+            from: NaiveTime::from_hms_opt(17, 30, 0).unwrap(),
+            to: NaiveTime::from_hms_opt(23, 0, 0).unwrap(),
+            days: vec![],
+        }];
+
+        let changes: Vec<(String, PlannedAction, Vec<String>)> = plan(
+            &schedules,
+            &blackouts,
+            &utc("2023-06-19T07:00:00Z"),
+            &utc("2023-06-20T07:00:00Z"),
+        )
+        .into_iter()
+        .map(|change| {
+            (
+                change.time.to_rfc3339(),
+                change.action,
+                change.schedules.iter().map(ToString::to_string).collect(),
+            )
+        })
+        .collect();
+
+        let change = |time: &str, action, schedule: &str| {
+            (utc(time).to_rfc3339(), action, vec![schedule.to_string()])
+        };
+        assert_eq!(
+            changes,
+            vec![
+                change("2023-06-19T08:00:00Z", PlannedAction::Wakeup, "time:test"),
+                change(
+                    "2023-06-19T17:30:00Z",
+                    PlannedAction::BlackoutStart,
+                    "time:backup"
+                ),
+                change("2023-06-19T18:00:00Z", PlannedAction::Shutdown, "time:test"),
+                change("2023-06-19T22:00:00Z", PlannedAction::Wakeup, "time:night"),
+                change(
+                    "2023-06-19T23:00:00Z",
+                    PlannedAction::BlackoutEnd,
+                    "time:backup"
+                ),
+                change(
+                    "2023-06-20T06:00:00Z",
+                    PlannedAction::Shutdown,
+                    "time:night"
+                ),
+            ]
+        );
+    }
+
+    #[rstest]
+    fn test_schedule_is_created_from_configuration() {
+        let schedules = configuration::Schedules {
+            times: [(
+                "workhours".to_string(),
+                configuration::TimeWindow {
+                    from: "08:00".to_string(),
+                    to: "18:00".to_string(),
+                    days: vec![configuration::Weekday::Mon],
+                },
+            )]
+            .iter()
+            .cloned()
+            .collect(),
+            location: None,
+            calendars: HashMap::new(),
+        };
+
+        assert_eq!(
+            Schedule::new(
+                &"time:workhours".parse().unwrap(),
+                &schedules,
+                &HashMap::new()
+            ),
+            Some(Schedule::Time {
+                id: "time:workhours".parse().unwrap(),
+                from: NaiveTime::from_hms_opt(8, 0, 0).unwrap(),
+                to: NaiveTime::from_hms_opt(18, 0, 0).unwrap(),
+                days: vec![Weekday::Mon],
+            })
+        );
+        assert_eq!(
+            Schedule::new(
+                &"time:unknown".parse().unwrap(),
+                &schedules,
+                &HashMap::new()
+            ),
+            None
+        );
+        // the sun schedules require a location
+        assert_eq!(
+            Schedule::new(
+                &configuration::SUN_NIGHT.parse().unwrap(),
+                &schedules,
+                &HashMap::new()
+            ),
+            None
+        );
+    }
+}
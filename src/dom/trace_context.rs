@@ -0,0 +1,59 @@
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+static NEXT_TRACE_ID: AtomicU64 = AtomicU64::new(1);
+
+#[derive(Clone, Debug, Hash, Eq, PartialEq)]
+pub struct TraceContext {
+    id: String,
+}
+
+impl TraceContext {
+    pub fn new() -> Self {
+        // combine the current time with a process-wide counter to get unique IDs
+        let id = format!(
+            "{:x}-{:x}",
+            chrono::Utc::now().timestamp_millis(),
+            NEXT_TRACE_ID.fetch_add(1, Ordering::Relaxed)
+        );
+
+        Self { id }
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+impl Default for TraceContext {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<&str> for TraceContext {
+    fn from(id: &str) -> Self {
+        Self { id: id.to_string() }
+    }
+}
+
+impl fmt::Display for TraceContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trace_context_ids_are_unique() {
+        assert_ne!(TraceContext::new(), TraceContext::new());
+    }
+
+    #[test]
+    fn test_trace_context_can_be_created_from_id() {
+        assert_eq!(TraceContext::from("abc").id(), "abc");
+    }
+}
@@ -0,0 +1,52 @@
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+
+/// Probable cause of a server coming online or going offline.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TransitionCause {
+    /// Woken up / shut down by the monitor because of its dependencies or ALWAYS OFF / ON.
+    Monitor,
+    /// Woken up by the monitor because one of its schedules is active.
+    Schedule,
+    /// Woken up / shut down through the web API.
+    Api,
+    /// Turned on / off by someone or something else (e.g. manually).
+    External,
+}
+
+impl fmt::Display for TransitionCause {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Monitor => "monitor",
+            Self::Schedule => "schedule",
+            Self::Api => "api",
+            Self::External => "external",
+        })
+    }
+}
+
+/// Change of the online state of a server.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Transition {
+    pub is_online: bool,
+    pub cause: TransitionCause,
+    pub date: DateTime<Utc>,
+}
+
+impl Transition {
+    pub fn new(is_online: bool, cause: TransitionCause) -> Self {
+        Self {
+            is_online,
+            cause,
+            date: Utc::now(),
+        }
+    }
+}
+
+impl fmt::Display for Transition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = if self.is_online { "online" } else { "offline" };
+        write!(f, "{} ({})", state, self.cause)
+    }
+}
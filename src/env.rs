@@ -1,2 +1,7 @@
 pub const PKG_NAME: &str = env!("CARGO_PKG_NAME");
 pub const PKG_VERSION: &str = env!("CARGO_PKG_VERSION");
+pub const PKG_DESCRIPTION: &str = env!("CARGO_PKG_DESCRIPTION");
+
+pub const BUILD_GIT_COMMIT: &str = env!("BUILD_GIT_COMMIT");
+pub const BUILD_TIMESTAMP: &str = env!("BUILD_TIMESTAMP");
+pub const BUILD_FEATURES: &str = env!("BUILD_FEATURES");
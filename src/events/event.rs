@@ -0,0 +1,133 @@
+use chrono::Utc;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::dom::{self, ActionContext, DeviceId};
+
+/// Version of the event schema which is only increased for incompatible changes.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Action run on a server.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    Wakeup,
+    Shutdown,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceStateChanged {
+    pub device_id: String,
+    pub name: String,
+    pub is_online: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionStarted {
+    pub server_id: String,
+    pub action: Action,
+    pub trace_id: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionFinished {
+    pub server_id: String,
+    pub action: Action,
+    pub trace_id: String,
+    pub success: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigReloaded {}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(tag = "type", content = "data", rename_all = "snake_case")]
+pub enum EventData {
+    DeviceStateChanged(DeviceStateChanged),
+    ActionStarted(ActionStarted),
+    ActionFinished(ActionFinished),
+    ConfigReloaded(ConfigReloaded),
+}
+
+/// Machine-readable event shared by all integrations which only changes incompatibly together
+/// with its `version`.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Event {
+    pub version: u32,
+    /// Time of the event in RFC 3339 format.
+    pub timestamp: String,
+    #[serde(flatten)]
+    pub data: EventData,
+}
+
+impl Event {
+    pub fn new(data: EventData) -> Self {
+        Self {
+            version: SCHEMA_VERSION,
+            timestamp: Utc::now().to_rfc3339(),
+            data,
+        }
+    }
+
+    pub fn device_state_changed(device: &dom::Device) -> Self {
+        Self::new(EventData::DeviceStateChanged(DeviceStateChanged {
+            device_id: device.id().to_string(),
+            name: device.name().clone(),
+            is_online: device.is_online(),
+        }))
+    }
+
+    pub fn action_started(server_id: &DeviceId, action: Action, context: &ActionContext) -> Self {
+        Self::new(EventData::ActionStarted(ActionStarted {
+            server_id: server_id.to_string(),
+            action,
+            trace_id: context.trace.id().to_string(),
+        }))
+    }
+
+    pub fn action_finished(
+        server_id: &DeviceId,
+        action: Action,
+        context: &ActionContext,
+        error: Option<String>,
+    ) -> Self {
+        Self::new(EventData::ActionFinished(ActionFinished {
+            server_id: server_id.to_string(),
+            action,
+            trace_id: context.trace.id().to_string(),
+            success: error.is_none(),
+            error,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::*;
+    use serde_json::json;
+
+    use super::*;
+
+    #[rstest]
+    fn test_event_is_serialized_with_version_and_type() {
+        let mut event = Event::new(EventData::ConfigReloaded(ConfigReloaded::default()));
+        event.timestamp = "2024-01-01T00:00:00+00:00".to_string();
+
+        assert_eq!(
+            serde_json::to_value(&event).unwrap(),
+            json!({
+                "version": SCHEMA_VERSION,
+                "timestamp": "2024-01-01T00:00:00+00:00",
+                "type": "config_reloaded",
+                "data": {}
+            })
+        );
+    }
+}
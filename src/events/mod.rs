@@ -0,0 +1,26 @@
+use schemars::schema::RootSchema;
+
+mod event;
+mod published_shutdown_server;
+mod published_wakeup_server;
+
+#[cfg(test)]
+pub use event::EventData;
+pub use event::{Action, Event};
+pub use published_shutdown_server::PublishedShutdownServer;
+pub use published_wakeup_server::PublishedWakeupServer;
+
+const EVENTS_CAPACITY: usize = 64;
+
+/// Broadcasts the events of all servers to any interested integration.
+pub type Events = tokio::sync::broadcast::Sender<Event>;
+
+pub fn events() -> Events {
+    let (tx, _) = tokio::sync::broadcast::channel::<Event>(EVENTS_CAPACITY);
+    tx
+}
+
+/// Returns the JSON schema of all events.
+pub fn schema() -> RootSchema {
+    schemars::schema_for!(Event)
+}
@@ -0,0 +1,55 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use super::{Action, Event, Events};
+use crate::dom::{ActionContext, DeviceId};
+use crate::networking::{ActionReport, ConnectionTest, ShutdownError, ShutdownServer};
+
+/// Publishes the start and the result of every shutdown of a server as events.
+pub struct PublishedShutdownServer {
+    server_id: DeviceId,
+    shutdown_server: Arc<dyn ShutdownServer>,
+    events: Events,
+}
+
+impl PublishedShutdownServer {
+    pub fn new(
+        server_id: &DeviceId,
+        shutdown_server: Arc<dyn ShutdownServer>,
+        events: Events,
+    ) -> Self {
+        Self {
+            server_id: server_id.clone(),
+            shutdown_server,
+            events,
+        }
+    }
+}
+
+#[async_trait]
+impl ShutdownServer for PublishedShutdownServer {
+    async fn shutdown(&self, context: &ActionContext) -> Result<ActionReport, ShutdownError> {
+        // nobody listening for events isn't an error
+        let _ = self.events.send(Event::action_started(
+            &self.server_id,
+            Action::Shutdown,
+            context,
+        ));
+
+        let result = self.shutdown_server.shutdown(context).await;
+        let _ = self.events.send(Event::action_finished(
+            &self.server_id,
+            Action::Shutdown,
+            context,
+            result.as_ref().err().map(|e| e.to_string()),
+        ));
+
+        result
+    }
+
+    async fn test_connection(&self, context: &ActionContext) -> anyhow::Result<ConnectionTest> {
+        // testing the connection is not a shutdown
+        self.shutdown_server.test_connection(context).await
+    }
+}
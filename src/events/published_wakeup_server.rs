@@ -0,0 +1,100 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use super::{Action, Event, Events};
+use crate::dom::{ActionContext, DeviceId};
+use crate::networking::{ActionReport, WakeupServer};
+
+/// Publishes the start and the result of every wakeup of a server as events.
+pub struct PublishedWakeupServer {
+    server_id: DeviceId,
+    wakeup_server: Arc<dyn WakeupServer>,
+    events: Events,
+}
+
+impl PublishedWakeupServer {
+    pub fn new(server_id: &DeviceId, wakeup_server: Arc<dyn WakeupServer>, events: Events) -> Self {
+        Self {
+            server_id: server_id.clone(),
+            wakeup_server,
+            events,
+        }
+    }
+}
+
+#[async_trait]
+impl WakeupServer for PublishedWakeupServer {
+    async fn wakeup(&self, context: &ActionContext) -> anyhow::Result<ActionReport> {
+        // nobody listening for events isn't an error
+        let _ = self.events.send(Event::action_started(
+            &self.server_id,
+            Action::Wakeup,
+            context,
+        ));
+
+        let result = self.wakeup_server.wakeup(context).await;
+        let _ = self.events.send(Event::action_finished(
+            &self.server_id,
+            Action::Wakeup,
+            context,
+            result.as_ref().err().map(|e| e.to_string()),
+        ));
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use rstest::*;
+    use tokio_util::sync::CancellationToken;
+
+    use super::*;
+    use crate::dom::device::test::*;
+    use crate::dom::TraceContext;
+    use crate::events::EventData;
+    use crate::networking::MockWakeupServer;
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_published_wakeup_server_publishes_start_and_result(server_id: DeviceId) {
+        // SETUP
+        let events = crate::events::events();
+        let mut receiver = events.subscribe();
+        let mut wakeup_server = MockWakeupServer::new();
+
+        // EXPECTATIONS
+        wakeup_server
+            .expect_wakeup()
+            .once()
+            .returning(|_| Err(anyhow::anyhow!("failed")));
+
+        // TESTING
+        let published = PublishedWakeupServer::new(&server_id, Arc::new(wakeup_server), events);
+        let context = ActionContext::new(
+            TraceContext::from("trace"),
+            CancellationToken::new(),
+            Duration::from_secs(10),
+        );
+        assert!(published.wakeup(&context).await.is_err());
+
+        match receiver.try_recv().unwrap().data {
+            EventData::ActionStarted(started) => {
+                assert_eq!(started.server_id, server_id.to_string());
+                assert_eq!(started.action, Action::Wakeup);
+                assert_eq!(started.trace_id, "trace");
+            }
+            data => panic!("unexpected event {:?}", data),
+        }
+        match receiver.try_recv().unwrap().data {
+            EventData::ActionFinished(finished) => {
+                assert!(!finished.success);
+                assert_eq!(finished.error, Some("failed".to_string()));
+            }
+            data => panic!("unexpected event {:?}", data),
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use std::fmt;
+
+/// Outcome of running `home-monitor-rs` which determines its exit code. The exit codes follow
+/// `sysexits.h` where possible.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExitStatus {
+    /// Everything succeeded (`0`).
+    Success,
+    /// The command line arguments are invalid (`64`).
+    Usage,
+    /// The devices to import are invalid (`65`).
+    DataError,
+    /// An input (e.g. the file to import or the key of the secrets) can't be read (`66`).
+    NoInput,
+    /// The configured network interface isn't available (`68`).
+    Network,
+    /// None of the servers could be controlled or the remote daemon isn't reachable (`69`).
+    Unavailable,
+    /// A task of the daemon stopped unexpectedly (`70`).
+    Software,
+    /// Only some of the servers could be controlled (`75`).
+    PartialFailure,
+    /// The configuration (or device store) can't be loaded or is invalid (`78`).
+    Config,
+    /// The daemon has been stopped by SIGINT (`130`).
+    Signal,
+}
+
+impl ExitStatus {
+    /// Returns the status of an action on multiple servers / devices depending on how many of
+    /// them succeeded.
+    pub fn from_outcomes(succeeded: usize, failed: usize) -> Self {
+        match (succeeded, failed) {
+            (_, 0) => Self::Success,
+            (0, _) => Self::Unavailable,
+            _ => Self::PartialFailure,
+        }
+    }
+
+    pub fn code(self) -> exitcode::ExitCode {
+        match self {
+            Self::Success => exitcode::OK,
+            Self::Usage => exitcode::USAGE,
+            Self::DataError => exitcode::DATAERR,
+            Self::NoInput => exitcode::NOINPUT,
+            Self::Network => exitcode::NOHOST,
+            Self::Unavailable => exitcode::UNAVAILABLE,
+            Self::Software => exitcode::SOFTWARE,
+            Self::PartialFailure => exitcode::TEMPFAIL,
+            Self::Config => exitcode::CONFIG,
+            // shells report processes terminated by a signal as 128 + the signal number
+            Self::Signal => 128 + 2,
+        }
+    }
+}
+
+impl fmt::Display for ExitStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Success => "success",
+            Self::Usage => "usage error",
+            Self::DataError => "data error",
+            Self::NoInput => "missing input",
+            Self::Network => "network error",
+            Self::Unavailable => "unavailable",
+            Self::Software => "internal error",
+            Self::PartialFailure => "partial failure",
+            Self::Config => "configuration error",
+            Self::Signal => "stopped by signal",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use rstest::*;
+
+    use super::*;
+
+    #[rstest]
+    #[case(0, 0, ExitStatus::Success)]
+    #[case(2, 0, ExitStatus::Success)]
+    #[case(0, 2, ExitStatus::Unavailable)]
+    #[case(1, 1, ExitStatus::PartialFailure)]
+    fn test_exit_status_from_outcomes(
+        #[case] succeeded: usize,
+        #[case] failed: usize,
+        #[case] expected: ExitStatus,
+    ) {
+        assert_eq!(ExitStatus::from_outcomes(succeeded, failed), expected);
+    }
+
+    #[rstest]
+    fn test_exit_status_codes_are_distinct() {
+        let statuses = [
+            ExitStatus::Success,
+            ExitStatus::Usage,
+            ExitStatus::DataError,
+            ExitStatus::NoInput,
+            ExitStatus::Network,
+            ExitStatus::Unavailable,
+            ExitStatus::Software,
+            ExitStatus::PartialFailure,
+            ExitStatus::Config,
+            ExitStatus::Signal,
+        ];
+
+        let codes: HashSet<exitcode::ExitCode> = statuses.iter().map(|s| s.code()).collect();
+        assert_eq!(codes.len(), statuses.len());
+        assert_eq!(ExitStatus::Success.code(), 0);
+        assert!(statuses[1..].iter().all(|status| status.code() != 0));
+    }
+}
@@ -0,0 +1,106 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use log::{info, warn};
+
+use crate::configuration::NotificationEvent;
+use crate::dom::communication::Standby;
+use crate::notifications::{Notification, Notifier};
+use crate::remote::RemoteClient;
+
+/// Regularly checks whether the active instance is still alive and lets this standby instance
+/// take over the control of the servers once it isn't.
+pub struct FailoverPoller {
+    peer: RemoteClient,
+    interval: Duration,
+    standby: Arc<Standby>,
+    notifier: Arc<dyn Notifier>,
+}
+
+impl FailoverPoller {
+    pub fn new(
+        peer: RemoteClient,
+        interval: Duration,
+        standby: Arc<Standby>,
+        notifier: Arc<dyn Notifier>,
+    ) -> Self {
+        Self {
+            peer,
+            interval,
+            standby,
+            notifier,
+        }
+    }
+
+    pub fn poll_once(&self) {
+        match self.standby.update(self.peer.is_alive(self.interval)) {
+            Some(true) => {
+                warn!("the active instance stopped responding, taking over the control of the servers");
+                let notification = Notification::new(NotificationEvent::Failover, &[]);
+                if let Err(e) = self.notifier.notify(&notification) {
+                    warn!("failed to notify about the failover: {}", e);
+                }
+            }
+            Some(false) => {
+                info!("the active instance is responding again, handing back the control of the servers")
+            }
+            None => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::TcpListener;
+
+    use mockall::predicate::*;
+    use rstest::*;
+
+    use super::*;
+    use crate::notifications::MockNotifier;
+    use crate::utils::{Instant, ManualClock};
+
+    #[rstest]
+    fn test_failover_poller_takes_over_from_unresponsive_peer() {
+        // SETUP
+        let closed_port = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let peer = RemoteClient::new(&format!("http://127.0.0.1:{closed_port}"), None);
+
+        let timeout = Duration::from_secs(30);
+        let clock = Arc::new(ManualClock::new(Instant::now()));
+        let standby = Arc::new(Standby::new(timeout, clock.clone()));
+
+        // EXPECTATIONS
+        let mut notifier = MockNotifier::new();
+        notifier
+            .expect_notify()
+            .with(function(|notification: &Notification| {
+                notification.event == NotificationEvent::Failover
+            }))
+            .once()
+            .returning(|_| Ok(()));
+
+        // TESTING
+        let poller = FailoverPoller::new(
+            peer,
+            Duration::from_secs(5),
+            standby.clone(),
+            Arc::new(notifier),
+        );
+
+        poller.poll_once();
+        assert!(!standby.is_active());
+
+        clock.advance(timeout + Duration::from_secs(1));
+        poller.poll_once();
+        assert!(standby.is_active());
+
+        // the takeover is only notified once
+        poller.poll_once();
+        assert!(standby.is_active());
+    }
+}
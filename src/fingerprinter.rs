@@ -0,0 +1,123 @@
+use std::net::IpAddr;
+use std::sync::Arc;
+
+use log::debug;
+
+use crate::dom::communication::SharedStateMutex;
+use crate::dom::{DeviceId, Fingerprint};
+use crate::networking::FingerprintProber;
+
+/// Regularly infers the operating system and the type of all online devices from how they respond
+/// on the network and annotates the devices in the shared state with the result.
+pub struct Fingerprinter {
+    prober: Box<dyn FingerprintProber>,
+    shared_state: Arc<SharedStateMutex>,
+    ports: Vec<u16>,
+}
+
+impl Fingerprinter {
+    pub fn new(
+        prober: Box<dyn FingerprintProber>,
+        shared_state: Arc<SharedStateMutex>,
+        ports: Vec<u16>,
+    ) -> Self {
+        Self {
+            prober,
+            shared_state,
+            ports,
+        }
+    }
+
+    pub fn fingerprint_once(&self) {
+        // don't keep the shared state locked while probing the devices
+        let devices: Vec<(DeviceId, IpAddr)> = self
+            .shared_state
+            .lock()
+            .unwrap()
+            .get_devices()
+            .iter()
+            .filter(|device| device.is_online())
+            .map(|device| (device.id().clone(), *device.ip()))
+            .collect();
+
+        for (device_id, ip) in devices {
+            let fingerprint = Fingerprint::infer(
+                self.prober.ttl(ip),
+                self.prober.open_ports(ip, &self.ports),
+                self.prober.services(ip),
+            );
+            debug!(
+                "fingerprinted {} as {} / {}",
+                device_id,
+                fingerprint
+                    .os
+                    .map_or("unknown OS".to_string(), |os| os.to_string()),
+                fingerprint
+                    .device_type
+                    .map_or("unknown type".to_string(), |device_type| device_type
+                        .to_string())
+            );
+
+            self.shared_state
+                .lock()
+                .unwrap()
+                .set_fingerprint(&device_id, fingerprint);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use mockall::predicate::*;
+    use rstest::*;
+
+    use super::*;
+    use crate::dom::communication::SharedState;
+    use crate::dom::device::test::*;
+    use crate::dom::{Device, DeviceType, Machine, OperatingSystem, Server};
+    use crate::networking::MockFingerprintProber;
+
+    #[rstest]
+    fn test_fingerprint_once_only_fingerprints_online_devices(
+        mut server: Server,
+        machine: Machine,
+    ) {
+        // SETUP
+        server.machine.is_online = true;
+        let shared_state = Arc::new(Mutex::new(SharedState::new(vec![
+            Device::Server(server),
+            Device::Machine(machine),
+        ])));
+
+        let mut prober = MockFingerprintProber::new();
+        prober
+            .expect_ttl()
+            .with(eq(server_ip()))
+            .once()
+            .return_const(Some(128));
+        prober
+            .expect_open_ports()
+            .withf(|ip, ports| *ip == server_ip() && ports == [135, 445, 3389])
+            .once()
+            .returning(|_, ports| ports.to_vec());
+        prober
+            .expect_services()
+            .with(eq(server_ip()))
+            .once()
+            .returning(|_| Vec::new());
+
+        let fingerprinter =
+            Fingerprinter::new(Box::new(prober), shared_state.clone(), vec![135, 445, 3389]);
+
+        // TESTING
+        fingerprinter.fingerprint_once();
+
+        let shared_state = shared_state.lock().unwrap();
+        let fingerprint = shared_state.get_fingerprint(&server_id()).unwrap();
+        assert_eq!(fingerprint.os, Some(OperatingSystem::Windows));
+        assert_eq!(fingerprint.device_type, Some(DeviceType::Computer));
+        assert!(shared_state.get_fingerprint(&machine_id()).is_none());
+    }
+}
@@ -0,0 +1,292 @@
+use std::fmt::Write;
+
+use serde_json::{json, Value};
+
+use crate::configuration::{Configuration, Device, DeviceId};
+use crate::env::PKG_NAME;
+use crate::metrics::{Counter, AWAY, DEVICE_ONLINE};
+
+const PANEL_WIDTH: u64 = 6;
+const PANEL_HEIGHT: u64 = 4;
+const PANELS_PER_ROW: u64 = 24 / PANEL_WIDTH;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum GenerateFormat {
+    /// Grafana dashboard (JSON)
+    Grafana,
+    /// Prometheus alerting rules (YAML)
+    Prometheus,
+}
+
+pub fn generate(format: GenerateFormat, config: &Configuration) -> String {
+    match format {
+        GenerateFormat::Grafana => {
+            serde_json::to_string_pretty(&grafana_dashboard(config)).unwrap()
+        }
+        GenerateFormat::Prometheus => prometheus_rules(config),
+    }
+}
+
+/// Returns all configured devices sorted by their ID together with their name and whether they
+/// are a server.
+fn sorted_devices(config: &Configuration) -> Vec<(&DeviceId, &str, bool)> {
+    let mut devices: Vec<(&DeviceId, &str, bool)> = config
+        .devices
+        .iter()
+        .map(|(device_id, device)| match device {
+            Device::Server(server) => (device_id, server.machine.name.as_str(), true),
+            Device::Machine(machine) => (device_id, machine.name.as_str(), false),
+        })
+        .collect();
+    devices.sort_by(|lhs, rhs| lhs.0.cmp(rhs.0));
+
+    devices
+}
+
+fn grid_pos(index: u64, y_offset: u64) -> Value {
+    json!({
+        "h": PANEL_HEIGHT,
+        "w": PANEL_WIDTH,
+        "x": (index % PANELS_PER_ROW) * PANEL_WIDTH,
+        "y": y_offset + (index / PANELS_PER_ROW) * PANEL_HEIGHT,
+    })
+}
+
+fn grafana_dashboard(config: &Configuration) -> Value {
+    let datasource = json!({ "type": "prometheus", "uid": "${datasource}" });
+    let devices = sorted_devices(config);
+    let servers: Vec<&(&DeviceId, &str, bool)> =
+        devices.iter().filter(|(_, _, server)| *server).collect();
+
+    // one prominent panel showing whether the away mode is active
+    let mut panels: Vec<Value> = vec![json!({
+        "type": "stat",
+        "title": "Away mode",
+        "datasource": datasource,
+        "gridPos": { "h": PANEL_HEIGHT, "w": 24, "x": 0, "y": 0 },
+        "targets": [{ "refId": "A", "expr": AWAY }],
+        "fieldConfig": {
+            "defaults": {
+                "mappings": [{
+                    "type": "value",
+                    "options": {
+                        "0": { "text": "Home", "color": "green" },
+                        "1": { "text": "Away", "color": "orange" },
+                    },
+                }],
+            },
+        },
+    })];
+
+    // one panel showing the online state of every device
+    panels.extend(
+        devices
+            .iter()
+            .enumerate()
+            .map(|(index, (device_id, name, _))| {
+                json!({
+                    "type": "stat",
+                    "title": name,
+                    "datasource": datasource,
+                    "gridPos": grid_pos(index as u64, PANEL_HEIGHT),
+                    "targets": [{
+                        "refId": "A",
+                        "expr": format!("{DEVICE_ONLINE}{{device=\"{device_id}\"}}"),
+                    }],
+                    "fieldConfig": {
+                        "defaults": {
+                            "mappings": [{
+                                "type": "value",
+                                "options": {
+                                    "0": { "text": "Offline", "color": "red" },
+                                    "1": { "text": "Online", "color": "green" },
+                                },
+                            }],
+                        },
+                    },
+                })
+            }),
+    );
+
+    // one panel showing the wakeups and shutdowns of every server
+    let y_offset = (1 + devices.chunks(PANELS_PER_ROW as usize).count() as u64) * PANEL_HEIGHT;
+    panels.extend(
+        servers
+            .iter()
+            .enumerate()
+            .map(|(index, (server_id, name, _))| {
+                let targets: Vec<Value> = [
+            Counter::WakeupSuccesses,
+            Counter::WakeupFailures,
+            Counter::ShutdownSuccesses,
+            Counter::ShutdownFailures,
+        ]
+        .iter()
+        .enumerate()
+        .map(|(ref_id, counter)| {
+            json!({
+                "refId": ((b'A' + ref_id as u8) as char).to_string(),
+                "expr": format!("increase({}_total{{server=\"{server_id}\"}}[1h])", counter.name()),
+                "legendFormat": counter.name(),
+            })
+        })
+        .collect();
+
+                json!({
+                    "type": "timeseries",
+                    "title": format!("{name} actions"),
+                    "datasource": datasource,
+                    "gridPos": grid_pos(index as u64, y_offset),
+                    "targets": targets,
+                })
+            }),
+    );
+
+    // panels need a unique ID
+    for (id, panel) in panels.iter_mut().enumerate() {
+        panel["id"] = json!(id + 1);
+    }
+
+    json!({
+        "title": PKG_NAME,
+        "uid": PKG_NAME,
+        "tags": [PKG_NAME],
+        "schemaVersion": 36,
+        "time": { "from": "now-24h", "to": "now" },
+        "templating": {
+            "list": [{
+                "name": "datasource",
+                "label": "Data source",
+                "type": "datasource",
+                "query": "prometheus",
+            }],
+        },
+        "panels": panels,
+    })
+}
+
+fn prometheus_rules(config: &Configuration) -> String {
+    // JSON strings are valid double-quoted YAML strings
+    let quote = |value: &str| serde_json::to_string(value).unwrap();
+
+    let mut rules = String::new();
+    writeln!(rules, "groups:").unwrap();
+    writeln!(rules, "  - name: {}", quote(PKG_NAME)).unwrap();
+    writeln!(rules, "    rules:").unwrap();
+
+    for (device_id, name, server) in sorted_devices(config) {
+        writeln!(rules, "      - alert: DeviceOffline").unwrap();
+        writeln!(
+            rules,
+            "        expr: {}",
+            quote(&format!("{DEVICE_ONLINE}{{device=\"{device_id}\"}} == 0"))
+        )
+        .unwrap();
+        writeln!(rules, "        for: 5m").unwrap();
+        writeln!(rules, "        labels:").unwrap();
+        writeln!(rules, "          severity: {}", quote("info")).unwrap();
+        writeln!(rules, "        annotations:").unwrap();
+        writeln!(
+            rules,
+            "          summary: {}",
+            quote(&format!("{name} is offline"))
+        )
+        .unwrap();
+
+        if !server {
+            continue;
+        }
+
+        for (alert, counter, action) in [
+            ("WakeupFailed", Counter::WakeupFailures, "wake up"),
+            ("ShutdownFailed", Counter::ShutdownFailures, "shut down"),
+        ] {
+            writeln!(rules, "      - alert: {alert}").unwrap();
+            writeln!(
+                rules,
+                "        expr: {}",
+                quote(&format!(
+                    "increase({}_total{{server=\"{device_id}\"}}[15m]) > 0",
+                    counter.name()
+                ))
+            )
+            .unwrap();
+            writeln!(rules, "        labels:").unwrap();
+            writeln!(rules, "          severity: {}", quote("warning")).unwrap();
+            writeln!(rules, "        annotations:").unwrap();
+            writeln!(
+                rules,
+                "          summary: {}",
+                quote(&format!("failed to {action} {name}"))
+            )
+            .unwrap();
+        }
+    }
+
+    rules
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::*;
+
+    use super::*;
+    use crate::configuration::parse_from_str;
+
+    #[fixture]
+    fn config() -> Configuration {
+        parse_from_str(
+            r#"{
+                "network": { "interface": "eth0", "ping": { "interval": 6, "timeout": 2 } },
+                "api": { "files": { "root": "/tmp" } },
+                "devices": {
+                    "myserver": {
+                        "name": "My Server",
+                        "mac": "aa:bb:cc:dd:ee:ff",
+                        "ip": "10.0.0.1",
+                        "timeout": 60,
+                        "ssh": { "username": "user", "password": "pass" }
+                    },
+                    "myphone": { "name": "My Phone", "ip": "10.0.0.2", "timeout": 300 }
+                },
+                "dependencies": { "myserver": [ "myphone" ] }
+            }"#,
+        )
+        .unwrap()
+    }
+
+    #[rstest]
+    fn test_grafana_dashboard_contains_panels_per_device(config: Configuration) {
+        let dashboard = grafana_dashboard(&config);
+        let panels = dashboard["panels"].as_array().unwrap();
+
+        // the away mode panel, one online panel per device and one actions panel per server
+        assert_eq!(panels.len(), 4);
+        assert_eq!(panels[0]["title"], "Away mode");
+        assert_eq!(panels[0]["targets"][0]["expr"], "home_monitor_away");
+        assert_eq!(panels[1]["title"], "My Phone");
+        assert_eq!(
+            panels[1]["targets"][0]["expr"],
+            "home_monitor_device_online{device=\"myphone\"}"
+        );
+        assert_eq!(panels[2]["title"], "My Server");
+        assert_eq!(panels[3]["title"], "My Server actions");
+        assert_eq!(panels[3]["targets"].as_array().unwrap().len(), 4);
+
+        let ids: Vec<&Value> = panels.iter().map(|panel| &panel["id"]).collect();
+        assert_eq!(ids, vec![1, 2, 3, 4]);
+    }
+
+    #[rstest]
+    fn test_prometheus_rules_contain_alerts_per_device(config: Configuration) {
+        let rules = prometheus_rules(&config);
+
+        assert!(rules.starts_with("groups:\n  - name: \"home-monitor-rs\"\n    rules:\n"));
+        assert_eq!(rules.matches("alert: DeviceOffline").count(), 2);
+        assert!(rules.contains("expr: \"home_monitor_device_online{device=\\\"myphone\\\"} == 0\""));
+        assert!(rules.contains("summary: \"My Phone is offline\""));
+        assert_eq!(rules.matches("alert: WakeupFailed").count(), 1);
+        assert_eq!(rules.matches("alert: ShutdownFailed").count(), 1);
+        assert!(rules.contains("summary: \"failed to shut down My Server\""));
+    }
+}
@@ -0,0 +1,245 @@
+use std::collections::BTreeMap;
+use std::net::IpAddr;
+
+use anyhow::anyhow;
+use log::debug;
+use serde::{Deserialize, Serialize};
+
+use crate::utils::MacAddr;
+
+/// Timeout (in seconds) of the generated device entries.
+const DEFAULT_TIMEOUT: u64 = 300;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum ImportFormat {
+    /// dnsmasq lease file (e.g. /var/lib/misc/dnsmasq.leases or /tmp/dhcp.leases)
+    Dnsmasq,
+    /// JSON output of the UniFi controller's clients endpoint (/api/s/<site>/stat/sta)
+    Unifi,
+    /// JSON output of `ubus call luci-rpc getDHCPLeases` on OpenWrt
+    Openwrt,
+}
+
+/// Device entry of the generated configuration fragment. The MAC address is not used for
+/// machines but simplifies turning a device into a server.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ImportedDevice {
+    pub name: String,
+    pub ip: IpAddr,
+    pub mac: MacAddr,
+    pub timeout: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Fragment {
+    pub devices: BTreeMap<String, ImportedDevice>,
+}
+
+#[derive(Deserialize)]
+struct UnifiClients {
+    data: Vec<UnifiClient>,
+}
+
+#[derive(Deserialize)]
+struct UnifiClient {
+    mac: String,
+    ip: Option<String>,
+    name: Option<String>,
+    hostname: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct OpenwrtLeases {
+    dhcp_leases: Vec<OpenwrtLease>,
+}
+
+#[derive(Deserialize)]
+struct OpenwrtLease {
+    macaddr: String,
+    ipaddr: Option<String>,
+    hostname: Option<String>,
+}
+
+/// A single lease / client with an optional hostname.
+struct Lease {
+    hostname: Option<String>,
+    ip: Option<String>,
+    mac: String,
+}
+
+pub fn import(format: ImportFormat, content: &str) -> anyhow::Result<Fragment> {
+    let leases = match format {
+        ImportFormat::Dnsmasq => parse_dnsmasq(content)?,
+        ImportFormat::Unifi => serde_json::from_str::<UnifiClients>(content)?
+            .data
+            .into_iter()
+            .map(|client| Lease {
+                hostname: client.name.or(client.hostname),
+                ip: client.ip,
+                mac: client.mac,
+            })
+            .collect(),
+        ImportFormat::Openwrt => serde_json::from_str::<OpenwrtLeases>(content)?
+            .dhcp_leases
+            .into_iter()
+            .map(|lease| Lease {
+                hostname: lease.hostname,
+                ip: lease.ipaddr,
+                mac: lease.macaddr,
+            })
+            .collect(),
+    };
+
+    let mut devices = BTreeMap::new();
+    for lease in leases {
+        let device = match to_device(&lease) {
+            Some(device) => device,
+            None => {
+                debug!("skipping lease of {} without a valid IP address", lease.mac);
+                continue;
+            }
+        };
+
+        // make sure every device gets a unique ID
+        let id = to_device_id(&device.name);
+        let mut unique_id = id.clone();
+        let mut suffix = 2;
+        while devices.contains_key(&unique_id) {
+            unique_id = format!("{id}{suffix}");
+            suffix += 1;
+        }
+
+        devices.insert(unique_id, device);
+    }
+
+    Ok(Fragment { devices })
+}
+
+fn parse_dnsmasq(content: &str) -> anyhow::Result<Vec<Lease>> {
+    content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            // <expiry> <mac> <ip> <hostname> <client id>
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() < 4 {
+                return Err(anyhow!("invalid dnsmasq lease: {}", line));
+            }
+
+            Ok(Lease {
+                hostname: match fields[3] {
+                    "*" => None,
+                    hostname => Some(hostname.to_string()),
+                },
+                ip: Some(fields[2].to_string()),
+                mac: fields[1].to_string(),
+            })
+        })
+        .collect()
+}
+
+fn to_device(lease: &Lease) -> Option<ImportedDevice> {
+    let ip: IpAddr = lease.ip.as_ref()?.parse().ok()?;
+    let mac: MacAddr = lease.mac.parse().ok()?;
+    let name = match &lease.hostname {
+        Some(hostname) if !hostname.trim().is_empty() => hostname.trim().to_string(),
+        _ => mac.to_string(),
+    };
+
+    Some(ImportedDevice {
+        name,
+        ip,
+        mac,
+        timeout: DEFAULT_TIMEOUT,
+    })
+}
+
+fn to_device_id(name: &str) -> String {
+    name.to_lowercase()
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::*;
+
+    use super::*;
+
+    fn device(name: &str, ip: &str, mac: &str) -> ImportedDevice {
+        ImportedDevice {
+            name: name.to_string(),
+            ip: ip.parse().unwrap(),
+            mac: mac.parse().unwrap(),
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+
+    #[rstest]
+    fn test_import_dnsmasq_leases() {
+        let leases = "\
+            1700000000 aa:bb:cc:dd:ee:01 192.168.1.10 Dads-Phone 01:aa:bb:cc:dd:ee:01\n\
+            1700000000 aa:bb:cc:dd:ee:02 192.168.1.11 * *\n\
+            \n\
+            1700000000 aa:bb:cc:dd:ee:03 192.168.1.12 dads-phone *\n";
+
+        let fragment = import(ImportFormat::Dnsmasq, leases).unwrap();
+
+        assert_eq!(fragment.devices.len(), 3);
+        assert_eq!(
+            fragment.devices["dadsphone"],
+            device("Dads-Phone", "192.168.1.10", "aa:bb:cc:dd:ee:01")
+        );
+        assert_eq!(
+            fragment.devices["aabbccddee02"],
+            device("AA:BB:CC:DD:EE:02", "192.168.1.11", "aa:bb:cc:dd:ee:02")
+        );
+        assert_eq!(
+            fragment.devices["dadsphone2"],
+            device("dads-phone", "192.168.1.12", "aa:bb:cc:dd:ee:03")
+        );
+    }
+
+    #[rstest]
+    fn test_import_fails_on_invalid_dnsmasq_lease() {
+        assert!(import(ImportFormat::Dnsmasq, "1700000000 aa:bb:cc:dd:ee:01").is_err());
+    }
+
+    #[rstest]
+    fn test_import_unifi_clients() {
+        let clients = r#"{"meta":{"rc":"ok"},"data":[
+            {"mac":"aa:bb:cc:dd:ee:01","ip":"192.168.1.10","hostname":"laptop","name":"Moms Laptop"},
+            {"mac":"aa:bb:cc:dd:ee:02","ip":"192.168.1.11","hostname":"tv"},
+            {"mac":"aa:bb:cc:dd:ee:03"}
+        ]}"#;
+
+        let fragment = import(ImportFormat::Unifi, clients).unwrap();
+
+        assert_eq!(fragment.devices.len(), 2);
+        assert_eq!(
+            fragment.devices["momslaptop"],
+            device("Moms Laptop", "192.168.1.10", "aa:bb:cc:dd:ee:01")
+        );
+        assert_eq!(
+            fragment.devices["tv"],
+            device("tv", "192.168.1.11", "aa:bb:cc:dd:ee:02")
+        );
+    }
+
+    #[rstest]
+    fn test_import_openwrt_leases() {
+        let leases = r#"{"dhcp_leases":[
+            {"expires":43000,"hostname":"printer","macaddr":"aa:bb:cc:dd:ee:01","ipaddr":"192.168.1.10"},
+            {"expires":43000,"macaddr":"aa:bb:cc:dd:ee:02","ipaddr":"invalid"}
+        ]}"#;
+
+        let fragment = import(ImportFormat::Openwrt, leases).unwrap();
+
+        assert_eq!(fragment.devices.len(), 1);
+        assert_eq!(
+            fragment.devices["printer"],
+            device("printer", "192.168.1.10", "aa:bb:cc:dd:ee:01")
+        );
+    }
+}
@@ -0,0 +1,169 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Utc};
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use simplelog::{Config, SharedLogger};
+
+/// Number of the most recent log records which are kept in memory.
+pub const LOG_BUFFER_CAPACITY: usize = 1000;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LogRecord {
+    pub timestamp: DateTime<Utc>,
+    pub level: Level,
+    pub target: String,
+    pub message: String,
+}
+
+/// Ring buffer of the most recent log records (e.g. to show recent warnings through the web API
+/// without access to the host).
+#[derive(Debug)]
+pub struct LogBuffer {
+    capacity: usize,
+    records: Mutex<VecDeque<LogRecord>>,
+}
+
+impl LogBuffer {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            records: Mutex::new(VecDeque::with_capacity(capacity)),
+        }
+    }
+
+    /// Adds a log record and drops the oldest one if the buffer is full.
+    pub fn push(&self, record: LogRecord) {
+        let mut records = self.records.lock().unwrap();
+        if records.len() >= self.capacity {
+            records.pop_front();
+        }
+        records.push_back(record);
+    }
+
+    /// Returns up to `limit` of the most recent records with at least the given level (oldest
+    /// first).
+    pub fn recent(&self, level: Level, limit: usize) -> Vec<LogRecord> {
+        let records = self.records.lock().unwrap();
+        let mut recent: Vec<LogRecord> = records
+            .iter()
+            .rev()
+            .filter(|record| record.level <= level)
+            .take(limit)
+            .cloned()
+            .collect();
+        recent.reverse();
+        recent
+    }
+}
+
+/// Logger which keeps the logged records in a [`LogBuffer`].
+pub struct BufferLogger {
+    level: LevelFilter,
+    buffer: Arc<LogBuffer>,
+}
+
+impl BufferLogger {
+    pub fn new(level: LevelFilter, buffer: Arc<LogBuffer>) -> Box<Self> {
+        Box::new(Self { level, buffer })
+    }
+}
+
+impl Log for BufferLogger {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        metadata.level() <= self.level
+    }
+
+    fn log(&self, record: &Record<'_>) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        self.buffer.push(LogRecord {
+            timestamp: Utc::now(),
+            level: record.level(),
+            target: record.target().to_string(),
+            message: record.args().to_string(),
+        });
+    }
+
+    fn flush(&self) {}
+}
+
+impl SharedLogger for BufferLogger {
+    fn level(&self) -> LevelFilter {
+        self.level
+    }
+
+    fn config(&self) -> Option<&Config> {
+        None
+    }
+
+    fn as_log(self: Box<Self>) -> Box<dyn Log> {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::*;
+
+    use super::*;
+
+    fn record(level: Level, message: &str) -> LogRecord {
+        LogRecord {
+            timestamp: Utc::now(),
+            level,
+            target: "home_monitor_rs".to_string(),
+            message: message.to_string(),
+        }
+    }
+
+    #[rstest]
+    fn test_log_buffer_keeps_most_recent_records() {
+        let buffer = LogBuffer::new(3);
+        buffer.push(record(Level::Error, "first"));
+        buffer.push(record(Level::Info, "second"));
+        buffer.push(record(Level::Warn, "third"));
+        buffer.push(record(Level::Debug, "fourth"));
+
+        let messages = |records: Vec<LogRecord>| -> Vec<String> {
+            records.into_iter().map(|record| record.message).collect()
+        };
+        assert_eq!(
+            messages(buffer.recent(Level::Trace, 10)),
+            vec!["second", "third", "fourth"]
+        );
+        assert_eq!(messages(buffer.recent(Level::Warn, 10)), vec!["third"]);
+        assert_eq!(
+            messages(buffer.recent(Level::Info, 1)),
+            vec!["third".to_string()]
+        );
+    }
+
+    #[rstest]
+    fn test_buffer_logger_filters_by_level() {
+        let buffer = Arc::new(LogBuffer::new(LOG_BUFFER_CAPACITY));
+        let logger = BufferLogger::new(LevelFilter::Info, buffer.clone());
+
+        logger.log(
+            &Record::builder()
+                .level(Level::Debug)
+                .args(format_args!("hidden"))
+                .build(),
+        );
+        logger.log(
+            &Record::builder()
+                .level(Level::Warn)
+                .target("home_monitor_rs::monitor")
+                .args(format_args!("NAS failed to shut down"))
+                .build(),
+        );
+
+        let records = buffer.recent(Level::Trace, 10);
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].level, Level::Warn);
+        assert_eq!(records[0].target, "home_monitor_rs::monitor");
+        assert_eq!(records[0].message, "NAS failed to shut down");
+    }
+}
@@ -0,0 +1,261 @@
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::Arc;
+
+use log::{info, warn};
+
+use crate::configuration::NotificationEvent;
+use crate::dom::communication::SharedStateMutex;
+use crate::dom::{Device, DeviceId};
+use crate::networking::ArpTable;
+use crate::notifications::{Notification, Notifier};
+use crate::utils::MacAddr;
+
+/// Regularly checks whether the ARP entries of the servers and the machines with a MAC address
+/// still match their configured MAC addresses (e.g. to detect an IP address which has been
+/// reassigned by a misconfigured DHCP server) and annotates the devices in the shared state with
+/// mismatching MAC addresses.
+pub struct MacVerifier {
+    arp_table: Box<dyn ArpTable>,
+    machine_macs: HashMap<DeviceId, MacAddr>,
+    shared_state: Arc<SharedStateMutex>,
+    notifier: Arc<dyn Notifier>,
+}
+
+impl MacVerifier {
+    pub fn new(
+        arp_table: Box<dyn ArpTable>,
+        machine_macs: HashMap<DeviceId, MacAddr>,
+        shared_state: Arc<SharedStateMutex>,
+        notifier: Arc<dyn Notifier>,
+    ) -> Self {
+        Self {
+            arp_table,
+            machine_macs,
+            shared_state,
+            notifier,
+        }
+    }
+
+    pub fn verify_once(&self) {
+        let entries = match self.arp_table.entries() {
+            Ok(entries) => entries,
+            Err(e) => {
+                warn!("failed to read the ARP table: {}", e);
+                return;
+            }
+        };
+
+        let devices: Vec<(DeviceId, String, IpAddr, MacAddr)> = self
+            .shared_state
+            .lock()
+            .unwrap()
+            .get_devices()
+            .iter()
+            .filter_map(|device| {
+                let (machine, mac) = match device {
+                    Device::Server(server) => (&server.machine, server.mac),
+                    Device::Machine(machine) => (machine, *self.machine_macs.get(&machine.id)?),
+                };
+                Some((machine.id.clone(), machine.name.clone(), machine.ip, mac))
+            })
+            .collect();
+
+        for (device_id, name, ip, mac) in devices {
+            // devices without an ARP entry (e.g. offline ones) keep their last result
+            let observed_mac = match entries.get(&ip) {
+                Some(observed_mac) => *observed_mac,
+                None => continue,
+            };
+            let mismatch = if observed_mac != mac {
+                Some(observed_mac)
+            } else {
+                None
+            };
+
+            let mut shared_state = self.shared_state.lock().unwrap();
+            let previous_mismatch = shared_state.get_mac_mismatch(&device_id).cloned();
+            if mismatch == previous_mismatch {
+                continue;
+            }
+            shared_state.set_mac_mismatch(&device_id, mismatch);
+            drop(shared_state);
+
+            match mismatch {
+                Some(observed_mac) => {
+                    warn!(
+                        "{}: {} belongs to {} instead of the configured {}",
+                        device_id, ip, observed_mac, mac
+                    );
+
+                    let notification = Notification::new(
+                        NotificationEvent::MacMismatch,
+                        &[
+                            ("name", name),
+                            ("id", device_id.to_string()),
+                            ("ip", ip.to_string()),
+                            ("mac", mac.to_string()),
+                            ("observedMac", observed_mac.to_string()),
+                        ],
+                    );
+                    if let Err(e) = self.notifier.notify(&notification) {
+                        warn!("failed to send MAC mismatch notification: {}", e);
+                    }
+                }
+                None => info!("{}: {} belongs to {} again", device_id, ip, mac),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    use rstest::*;
+
+    use super::*;
+    use crate::dom::communication::SharedState;
+    use crate::dom::device::test::*;
+    use crate::dom::{Machine, Server};
+    use crate::networking::MockArpTable;
+    use crate::notifications::MockNotifier;
+
+    fn arp_table(entries: Vec<HashMap<IpAddr, MacAddr>>) -> Box<MockArpTable> {
+        let mut arp_table = MockArpTable::new();
+        let mut entries = entries.into_iter();
+        arp_table
+            .expect_entries()
+            .times(entries.len())
+            .returning(move || Ok(entries.next().unwrap()));
+        Box::new(arp_table)
+    }
+
+    #[rstest]
+    fn test_verify_once_reports_mac_mismatches_once(server: Server, machine: Machine) {
+        // SETUP
+        let observed_mac: MacAddr = "11:22:33:44:55:66".parse().unwrap();
+        let shared_state = Arc::new(Mutex::new(SharedState::new(vec![
+            Device::Server(server),
+            Device::Machine(machine),
+        ])));
+
+        let mut notifier = MockNotifier::new();
+
+        // EXPECTATIONS
+        notifier
+            .expect_notify()
+            .withf(move |notification| {
+                notification.event == NotificationEvent::MacMismatch
+                    && notification.variables.get("observedMac") == Some(&observed_mac.to_string())
+            })
+            .once()
+            .returning(|_| Ok(()));
+
+        let mismatch: HashMap<IpAddr, MacAddr> =
+            [(server_ip(), observed_mac)].iter().cloned().collect();
+        let verifier = MacVerifier::new(
+            arp_table(vec![
+                mismatch.clone(),
+                mismatch,
+                HashMap::new(),
+                [(server_ip(), server_mac())].iter().cloned().collect(),
+            ]),
+            HashMap::new(),
+            shared_state.clone(),
+            Arc::new(notifier),
+        );
+
+        // TESTING
+        // the mismatch is only reported once
+        verifier.verify_once();
+        verifier.verify_once();
+        assert_eq!(
+            shared_state
+                .lock()
+                .unwrap()
+                .get_mac_mismatch(&server_id())
+                .cloned(),
+            Some(observed_mac)
+        );
+
+        // a missing ARP entry doesn't change anything
+        verifier.verify_once();
+        assert!(shared_state
+            .lock()
+            .unwrap()
+            .get_mac_mismatch(&server_id())
+            .is_some());
+
+        verifier.verify_once();
+        assert!(shared_state
+            .lock()
+            .unwrap()
+            .get_mac_mismatch(&server_id())
+            .is_none());
+    }
+
+    #[rstest]
+    fn test_verify_once_checks_machines_with_mac(server: Server, machine: Machine) {
+        // SETUP
+        let machine_mac: MacAddr = "11:22:33:44:55:66".parse().unwrap();
+        let observed_mac: MacAddr = "66:55:44:33:22:11".parse().unwrap();
+        let shared_state = Arc::new(Mutex::new(SharedState::new(vec![
+            Device::Server(server),
+            Device::Machine(machine),
+        ])));
+
+        let mut notifier = MockNotifier::new();
+
+        // EXPECTATIONS
+        notifier
+            .expect_notify()
+            .withf(move |notification| {
+                notification.variables.get("id") == Some(&machine_id().to_string())
+            })
+            .once()
+            .returning(|_| Ok(()));
+
+        let entries: HashMap<IpAddr, MacAddr> =
+            [(server_ip(), server_mac()), (machine_ip(), observed_mac)]
+                .iter()
+                .cloned()
+                .collect();
+        let verifier = MacVerifier::new(
+            arp_table(vec![entries.clone()]),
+            [(machine_id(), machine_mac)].iter().cloned().collect(),
+            shared_state.clone(),
+            Arc::new(notifier),
+        );
+
+        // TESTING
+        verifier.verify_once();
+        assert_eq!(
+            shared_state
+                .lock()
+                .unwrap()
+                .get_mac_mismatch(&machine_id())
+                .cloned(),
+            Some(observed_mac)
+        );
+
+        // machines without a configured MAC address aren't checked
+        shared_state
+            .lock()
+            .unwrap()
+            .set_mac_mismatch(&machine_id(), None);
+        let verifier = MacVerifier::new(
+            arp_table(vec![entries]),
+            HashMap::new(),
+            shared_state.clone(),
+            Arc::new(MockNotifier::new()),
+        );
+        verifier.verify_once();
+        assert!(shared_state
+            .lock()
+            .unwrap()
+            .get_mac_mismatch(&machine_id())
+            .is_none());
+    }
+}
@@ -1,21 +1,46 @@
-use std::collections::HashMap;
-use std::path::Path;
+use std::collections::{HashMap, HashSet};
+use std::net::{IpAddr, SocketAddr};
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
 use clap::Parser;
 use log::{debug, error, info, warn};
-use simplelog::{LevelFilter, SimpleLogger};
+use simplelog::{CombinedLogger, LevelFilter, SimpleLogger};
+use tokio_util::sync::CancellationToken;
+use zeroize::Zeroizing;
 
-use crate::networking::{PortChecker, TcpPortChecker};
+use exit_status::ExitStatus;
 
+mod beacon;
+mod calendar_sync;
 mod configuration;
 mod control;
+mod dns_trigger;
 mod dom;
 mod env;
+mod events;
+mod exit_status;
+mod failover;
+mod fingerprinter;
+mod generate;
+mod import;
+mod log_buffer;
+#[cfg(target_os = "linux")]
+mod mac_verifier;
+mod metrics;
 mod monitor;
 mod networking;
+mod notifications;
+mod panic_hook;
+mod presence_poller;
+mod recovery;
+mod remote;
+mod secrets;
+mod store;
+mod updates;
 mod utils;
+mod wake_on_lan_listener;
 mod web;
 
 #[derive(Parser)]
@@ -60,21 +85,96 @@ struct Opts {
         long = "wakeup",
         num_args = 1..,
         value_name = "SERVER",
-        conflicts_with_all = ["shutdown", "wait_online"],
+        conflicts_with = "shutdown",
         group = "mode"
     )]
     wakeup: Vec<String>,
 
-    // Wait until the specified server(s) is/are online
+    // Wait until the specified server(s) (or the server(s) to wake up) is/are online
     #[clap(
         short = 'n',
         long = "wait-online",
-        num_args = 1..,
+        num_args = 0..,
         value_name = "SERVER",
-        conflicts_with_all = ["shutdown", "wakeup"],
-        group = "mode"
+        conflicts_with = "shutdown"
+    )]
+    wait_online: Option<Vec<String>>,
+
+    // Show the status of the specified (or all) server(s) of the remote daemon
+    #[clap(
+        long = "status",
+        num_args = 0..,
+        value_name = "SERVER",
+        requires = "remote",
+        conflicts_with_all = ["shutdown", "wakeup", "wait_online"]
+    )]
+    status: Option<Vec<String>>,
+
+    // Control the server(s) through the REST API of the daemon running at the given URL
+    #[clap(
+        short = 'r',
+        long = "remote",
+        value_name = "URL",
+        conflicts_with = "wait_online"
+    )]
+    remote: Option<String>,
+
+    // Bearer token to authenticate against the remote daemon
+    #[clap(long = "token", value_name = "TOKEN", requires = "remote")]
+    token: Option<String>,
+
+    // Confirm shutdowns which have to be confirmed by the remote daemon
+    #[clap(long = "confirm", requires_all = ["remote", "shutdown"])]
+    confirm: bool,
+
+    // Generate a Grafana dashboard or Prometheus alerting rules for the configured devices
+    #[clap(
+        long = "generate",
+        value_name = "FORMAT",
+        value_enum,
+        conflicts_with_all = ["shutdown", "wakeup", "wait_online", "status", "remote", "import"]
+    )]
+    generate: Option<generate::GenerateFormat>,
+
+    // Encrypt a secret (e.g. an SSH password) to be used in the configuration
+    #[clap(
+        long = "encrypt-secret",
+        conflicts_with_all = ["shutdown", "wakeup", "wait_online", "status", "remote", "import", "generate"]
+    )]
+    encrypt_secret: bool,
+
+    // Activate the given profile instead of the one configured to be active on startup
+    #[clap(long = "profile", value_name = "PROFILE", conflicts_with = "remote")]
+    profile: Option<String>,
+
+    // Check the configuration and report suspicious setups without monitoring anything
+    #[clap(
+        long = "check-config",
+        conflicts_with_all = ["shutdown", "wakeup", "wait_online", "status", "remote", "import", "generate", "encrypt_secret"]
+    )]
+    check_config: bool,
+
+    // Generate device entries from the given lease / client list of a router or DHCP server
+    #[clap(
+        long = "import",
+        value_name = "FILE",
+        requires = "from",
+        conflicts_with_all = ["shutdown", "wakeup", "wait_online", "status", "remote"]
     )]
-    wait_online: Vec<String>,
+    import: Option<PathBuf>,
+
+    // Format of the lease / client list to import
+    #[clap(long = "from", value_name = "FORMAT", value_enum, requires = "import")]
+    from: Option<import::ImportFormat>,
+
+    // Overall timeout (in seconds) when waiting for server(s) to be online
+    #[clap(
+        short = 't',
+        long = "timeout",
+        value_name = "SECONDS",
+        requires = "wait_online"
+    )]
+    timeout: Option<u64>,
 }
 
 enum Mode {
@@ -83,25 +183,239 @@ enum Mode {
     WaitOnline,
 }
 
+fn create_action_context() -> dom::ActionContext {
+    dom::ActionContext::new(
+        dom::TraceContext::new(),
+        CancellationToken::new(),
+        control::ACTION_TIMEOUT,
+    )
+}
+
+fn wait_online(server_id: &str, server: &dom::Server, timeout: Duration) -> bool {
+    info!(
+        "waiting for {} ({}) to be online...",
+        server.machine.name, server_id
+    );
+
+    let checker = control::Factory::create_online_checker(server);
+    let start = std::time::Instant::now();
+    loop {
+        debug!(
+            "checking if {} ({}) is online",
+            server.machine.name, server_id
+        );
+        if checker.check() {
+            info!(
+                "{} ({}) is online after {} seconds",
+                server.machine.name,
+                server_id,
+                start.elapsed().as_secs()
+            );
+            return true;
+        }
+
+        if start.elapsed() >= timeout {
+            warn!(
+                "{} ({}) is not online after {} seconds",
+                server.machine.name,
+                server_id,
+                timeout.as_secs()
+            );
+            return false;
+        }
+
+        std::thread::sleep(Duration::from_secs(1));
+    }
+}
+
+fn log_remote_device(device: &web::serialization::Device) {
+    info!(
+        "  {} ({}): {}{}",
+        device.name,
+        device.ip,
+        if device.is_online {
+            "online"
+        } else {
+            "offline"
+        },
+        match &device.last_seen {
+            Some(last_seen) => format!(" (last seen {})", last_seen),
+            None => String::new(),
+        }
+    );
+    for service in device.services.iter() {
+        info!(
+            "    {}: {}",
+            service.name,
+            if service.is_online {
+                "online"
+            } else {
+                "offline"
+            }
+        );
+    }
+}
+
+fn run_remote(args: &Opts, url: &str) -> ExitStatus {
+    let client = remote::RemoteClient::new(url, args.token.clone());
+
+    let mut succeeded = 0;
+    let mut failed = 0;
+    if let Some(servers) = &args.status {
+        if servers.is_empty() {
+            match client.status(None) {
+                Err(e) => {
+                    error!("failed to get the status from {}: {}", url, e);
+                    failed += 1;
+                }
+                Ok(status) => {
+                    succeeded += 1;
+                    info!("devices ({}):", status.devices.len());
+                    status.devices.iter().for_each(log_remote_device);
+                }
+            }
+        }
+
+        for server_id in servers {
+            match client.status(Some(server_id)) {
+                Err(e) => {
+                    error!(
+                        "failed to get the status of {} from {}: {}",
+                        server_id, url, e
+                    );
+                    failed += 1;
+                }
+                Ok(status) => {
+                    succeeded += 1;
+                    if let Some(server) = &status.server {
+                        info!("server {}:", server_id);
+                        log_remote_device(server);
+                    }
+                    info!("dependencies ({}):", status.devices.len());
+                    status.devices.iter().for_each(log_remote_device);
+                }
+            }
+        }
+    } else if !args.wakeup.is_empty() {
+        for server_id in args.wakeup.iter() {
+            info!("waking up {} through {}...", server_id, url);
+            match client.wakeup(server_id) {
+                Err(e) => {
+                    error!("failed to wake up {}: {}", server_id, e);
+                    failed += 1;
+                }
+                Ok(_) => {
+                    succeeded += 1;
+                    info!("{} successfully woken up", server_id);
+                }
+            }
+        }
+    } else if !args.shutdown.is_empty() {
+        for server_id in args.shutdown.iter() {
+            info!("shutting down {} through {}...", server_id, url);
+            match client.shutdown(server_id, args.confirm) {
+                Err(e) => {
+                    error!("failed to shut down {}: {}", server_id, e);
+                    failed += 1;
+                }
+                Ok(_) => {
+                    succeeded += 1;
+                    info!("{} successfully shut down", server_id);
+                }
+            }
+        }
+    } else {
+        error!("--remote requires --wakeup, --shutdown or --status");
+        return ExitStatus::Usage;
+    }
+
+    ExitStatus::from_outcomes(succeeded, failed)
+}
+
+fn run_import(path: &Path, format: import::ImportFormat) -> ExitStatus {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) => {
+            error!("failed to read {}: {}", path.display(), e);
+            return ExitStatus::NoInput;
+        }
+    };
+
+    let fragment = match import::import(format, &content) {
+        Ok(fragment) => fragment,
+        Err(e) => {
+            error!("failed to import devices from {}: {}", path.display(), e);
+            return ExitStatus::DataError;
+        }
+    };
+
+    // print the configuration fragment so that it can be merged into the configuration
+    println!("{}", serde_json::to_string_pretty(&fragment).unwrap());
+    ExitStatus::Success
+}
+
+fn run_encrypt_secret(secrets: &configuration::Secrets) -> ExitStatus {
+    let key = match secrets::SecretKey::load(secrets) {
+        Ok(key) => key,
+        Err(e) => {
+            error!("failed to load the key for the secrets: {}", e);
+            return ExitStatus::NoInput;
+        }
+    };
+
+    let secret = match rpassword::prompt_password("secret to encrypt: ") {
+        Ok(secret) => Zeroizing::new(secret),
+        Err(e) => {
+            error!("failed to read the secret: {}", e);
+            return ExitStatus::NoInput;
+        }
+    };
+
+    match key.encrypt(&secret) {
+        Ok(encrypted) => {
+            // print the encrypted secret so that it can be pasted into the configuration
+            println!("{}", encrypted);
+            ExitStatus::Success
+        }
+        Err(e) => {
+            error!("{}", e);
+            ExitStatus::Software
+        }
+    }
+}
+
 fn run(
     args: Opts,
     config: configuration::Configuration,
+    device_store: Option<Arc<store::DeviceStore>>,
     configured_servers: HashMap<configuration::DeviceId, configuration::Server>,
     configured_machines: HashMap<configuration::DeviceId, configuration::Machine>,
-) -> exitcode::ExitCode {
+    log_buffer: Arc<log_buffer::LogBuffer>,
+) -> ExitStatus {
     // check if a manual option has been provided
-    if !args.wakeup.is_empty() || !args.shutdown.is_empty() || !args.wait_online.is_empty() {
+    if !args.wakeup.is_empty() || !args.shutdown.is_empty() || args.wait_online.is_some() {
+        let wait_online_servers = args.wait_online.clone().unwrap_or_default();
         let mode: Mode;
         let servers: &Vec<String>;
         if !args.wakeup.is_empty() {
+            if !wait_online_servers.is_empty() {
+                error!("--wait-online doesn't take any servers when combined with --wakeup");
+                return ExitStatus::Usage;
+            }
+
             mode = Mode::Wakeup;
             servers = &args.wakeup;
         } else if !args.shutdown.is_empty() {
             mode = Mode::Shutdown;
             servers = &args.shutdown;
         } else {
+            if wait_online_servers.is_empty() {
+                error!("no server(s) provided to wait for");
+                return ExitStatus::Usage;
+            }
+
             mode = Mode::WaitOnline;
-            servers = &args.wait_online;
+            servers = &wait_online_servers;
         }
 
         // make sure all provided servers are also configured
@@ -110,11 +424,27 @@ fn run(
             .all(|server_id| configured_servers.contains_key(&server_id.parse().unwrap()))
         {
             error!("unconfigured server(s) provided");
-            return exitcode::USAGE;
+            return ExitStatus::Usage;
         }
 
+        // create a tokio runtime to run the wakeup / shutdown actions
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("failed to build a tokio runtime");
+
+        // an overall timeout applies to all servers to wait for
+        let deadline = args
+            .timeout
+            .map(|timeout| std::time::Instant::now() + Duration::from_secs(timeout));
+        let wait_timeout = |server: &dom::Server| match deadline {
+            Some(deadline) => deadline.saturating_duration_since(std::time::Instant::now()),
+            None => Duration::from_secs(server.machine.last_seen_timeout),
+        };
+
         // process provided servers
-        let mut exitcode = exitcode::OK;
+        let mut failed_servers = HashSet::new();
+        let mut woken_up_servers = Vec::new();
         for server_id in servers {
             let configured_server = configured_servers.get(&server_id.parse().unwrap()).unwrap();
             let server = dom::Server::from(configured_server);
@@ -123,28 +453,31 @@ fn run(
                 Mode::Wakeup => {
                     info!("waking up {} ({})...", server.machine.name, server_id);
                     let wakeup_server = control::Factory::create_wakeup_server(&server);
-                    match wakeup_server.wakeup() {
+                    match rt.block_on(wakeup_server.wakeup(&create_action_context())) {
                         Err(_) => {
                             error!("failed to wake up {} ({})", server.machine.name, server_id);
-                            exitcode = exitcode::UNAVAILABLE;
+                            failed_servers.insert(server_id);
+                        }
+                        Ok(_) => {
+                            info!(
+                                "{} ({}) successfully woken up",
+                                server.machine.name, server_id
+                            );
+                            woken_up_servers.push((server_id, server));
                         }
-                        Ok(_) => info!(
-                            "{} ({}) successfully woken up",
-                            server.machine.name, server_id
-                        ),
                     };
                 }
 
                 Mode::Shutdown => {
                     info!("shutting down {} ({})...", server.machine.name, server_id);
                     let shutdown_server = control::Factory::create_shutdown_server(&server);
-                    match shutdown_server.shutdown() {
+                    match rt.block_on(shutdown_server.shutdown(&create_action_context())) {
                         Err(e) => {
                             error!(
                                 "failed to shut down {} ({}): {}",
                                 server.machine.name, server_id, e
                             );
-                            exitcode = exitcode::UNAVAILABLE;
+                            failed_servers.insert(server_id);
                         }
                         Ok(_) => info!(
                             "{} ({}) successfully shut down",
@@ -154,86 +487,126 @@ fn run(
                 }
 
                 Mode::WaitOnline => {
-                    info!(
-                        "waiting for {} ({}) to be online...",
-                        server.machine.name, server_id
-                    );
-
-                    let timeout = server.machine.last_seen_timeout;
-                    let tcp_port_checker = TcpPortChecker::new(
-                        server.machine.ip,
-                        server.ssh.port.into(),
-                        Duration::from_secs(1),
-                    );
-
-                    exitcode = exitcode::UNAVAILABLE;
-                    for secs in 0..timeout {
-                        debug!(
-                            "checking TCP port {} on {} ({})",
-                            Into::<u16>::into(server.ssh.port),
-                            server.machine.name,
-                            server_id
-                        );
-                        if tcp_port_checker.check() {
-                            info!(
-                                "{} ({}) is online after {} seconds",
-                                server.machine.name, server_id, secs
-                            );
-                            exitcode = exitcode::OK;
-                            break;
-                        }
+                    if !wait_online(server_id, &server, wait_timeout(&server)) {
+                        failed_servers.insert(server_id);
                     }
+                }
+            }
+        }
 
-                    if exitcode == exitcode::UNAVAILABLE {
-                        warn!(
-                            "{} ({}) is not online after {} seconds",
-                            server.machine.name, server_id, timeout
-                        );
-                    }
+        // wait for all successfully woken up servers to be online
+        if args.wait_online.is_some() {
+            for (server_id, server) in woken_up_servers {
+                if !wait_online(server_id, &server, wait_timeout(&server)) {
+                    failed_servers.insert(server_id);
                 }
             }
         }
 
-        exitcode
+        ExitStatus::from_outcomes(servers.len() - failed_servers.len(), failed_servers.len())
     } else {
         // make sure machines are configured
         if configured_machines.is_empty() {
             error!("configuration doesn't contain any machines to monitor");
-            std::process::exit(exitcode::CONFIG);
+            return ExitStatus::Config;
         }
 
         // make sure dependencies are configured
         if config.dependencies.0.is_empty() {
             error!("no dependencies configured");
-            std::process::exit(exitcode::CONFIG);
+            return ExitStatus::Config;
         }
         let ping_interval = Duration::from_secs(config.network.ping.interval);
 
         // create the server DOM objects from the parsed configuration
-        let servers: Vec<dom::Server> =
+        let mut servers: Vec<dom::Server> =
             configured_servers.values().map(dom::Server::from).collect();
 
         // create the machine DOM objects from the parsed configuration
-        let machines: Vec<dom::Machine> = configured_machines
+        let mut machines: Vec<dom::Machine> = configured_machines
             .values()
             .map(dom::Machine::from)
             .collect();
 
-        process(args, config, ping_interval, servers, machines)
+        check_subnets(&config.network.interface, &mut servers, &mut machines);
+
+        process(
+            args,
+            config,
+            device_store,
+            ping_interval,
+            servers,
+            machines,
+            log_buffer,
+        )
+    }
+}
+
+/// Marks (and warns about) the devices which aren't on a subnet of the monitored interface and
+/// therefore can't be reached by Wake-on-LAN or ARP requests.
+fn check_subnets(interface: &str, servers: &mut [dom::Server], machines: &mut [dom::Machine]) {
+    let interface = match networking::get_network_interface(interface) {
+        Ok(interface) => interface,
+        Err(e) => {
+            warn!("{}, not checking the subnets of the devices", e);
+            return;
+        }
+    };
+
+    for server in servers.iter_mut() {
+        server.machine.is_off_subnet =
+            !networking::is_on_subnet(&interface.ips, &server.machine.ip);
+        if server.machine.is_off_subnet && server.relay.is_none() {
+            warn!(
+                "{} ({}) isn't on a subnet of {}, it can't be woken up without a wakeup relay",
+                server.machine.name, server.machine.ip, interface.name
+            );
+        }
+    }
+    for machine in machines.iter_mut() {
+        machine.is_off_subnet = !networking::is_on_subnet(&interface.ips, &machine.ip);
+        if machine.is_off_subnet {
+            warn!(
+                "{} ({}) isn't on a subnet of {}, it can't be probed with ARP requests",
+                machine.name, machine.ip, interface.name
+            );
+        }
     }
 }
 
 fn process(
     args: Opts,
     config: configuration::Configuration,
+    device_store: Option<Arc<store::DeviceStore>>,
     ping_interval: Duration,
     servers: Vec<dom::Server>,
     machines: Vec<dom::Machine>,
-) -> exitcode::ExitCode {
-    // create the tokio runtime
+    log_buffer: Arc<log_buffer::LogBuffer>,
+) -> ExitStatus {
+    // create the tokio runtime and keep track of its threads
+    let runtime_stats = Arc::new(dom::communication::RuntimeStats::new(
+        web::Server::get_num_workers(),
+    ));
     let rt = tokio::runtime::Builder::new_multi_thread()
         .worker_threads(web::Server::get_num_workers())
+        .max_blocking_threads(dom::communication::MAX_BLOCKING_THREADS)
         .thread_name(web::Server::get_thread_name(env::PKG_NAME))
+        .on_thread_start({
+            let runtime_stats = runtime_stats.clone();
+            move || runtime_stats.thread_started()
+        })
+        .on_thread_stop({
+            let runtime_stats = runtime_stats.clone();
+            move || runtime_stats.thread_stopped()
+        })
+        .on_thread_park({
+            let runtime_stats = runtime_stats.clone();
+            move || runtime_stats.worker_parked()
+        })
+        .on_thread_unpark({
+            let runtime_stats = runtime_stats.clone();
+            move || runtime_stats.worker_unparked()
+        })
         .enable_all()
         .build()
         .expect("failed to build a tokio runtime");
@@ -243,34 +616,297 @@ fn process(
     let sigterm = tokio::signal::ctrl_c();
 
     // prepare a channel to communicate updates from monitoring to the web API
-    let (tx, rx) = dom::communication::mpsc_channel();
+    let sync_stats = Arc::new(dom::communication::SyncStats::new());
+    let (tx, rx) = dom::communication::mpsc_channel(sync_stats.clone());
+
+    // prepare a channel to register guest devices from the web API with monitoring
+    let (guest_tx, guest_rx) = dom::communication::guest_channel();
+
+    // prepare a channel to report servers woken up by other tools to monitoring
+    let (external_wakeup_tx, external_wakeup_rx) = dom::communication::external_wakeup_channel();
+    let (change_timeout_reset_tx, change_timeout_reset_rx) =
+        dom::communication::change_timeout_reset_channel();
 
-    // only start the web API (and shared state synchronization) if a valid port is configured
+    // only start the web API, the beacon and the DNS trigger if a valid port is configured
     let provide_web_api = config.api.web.port > 0;
+    let provide_beacon = config.api.beacon.port > 0;
+    let provide_dns_trigger = config.api.dns.port > 0;
+    let provide_wake_on_lan_listener = config.api.wake_on_lan.port > 0;
+    // all of them depend on the shared state synchronization
+    let provide_shared_state = provide_web_api || provide_beacon || provide_dns_trigger;
 
-    // prepare the server controls
-    let server_controls: Vec<control::ServerControl> = servers
+    // prepare a cancellation token to abort running actions on termination
+    let cancellation = CancellationToken::new();
+
+    // prepare a metrics registry shared by the server controls, the monitor and the web API
+    let metrics = Arc::new(metrics::Registry::new());
+
+    // queue notifications which couldn't be delivered in the device store or the files API root
+    if config.store.is_none() && config.api.files.is_none() {
+        warn!("queued notifications are lost on restart without a store or the files API");
+    }
+    let outbox = match notifications::Outbox::open_configured(
+        config.store.as_ref(),
+        config.api.files.as_ref(),
+        &config.notifications.retry,
+    ) {
+        Ok(outbox) => {
+            match outbox.len() {
+                Ok(0) => {}
+                Ok(queued) => info!("retrying {} queued notification(s)", queued),
+                Err(e) => warn!("failed to read the queued notifications: {}", e),
+            }
+            outbox
+        }
+        Err(e) => {
+            error!("failed to open the queue of failed notifications: {}", e);
+            return ExitStatus::Config;
+        }
+    };
+
+    // report any crashes through the configured notifications
+    let notifier = control::Factory::create_notifier(
+        &config.notifications,
+        config.proxy.as_deref(),
+        outbox,
+        &metrics,
+    );
+    panic_hook::install(notifier.clone());
+
+    // regularly retry delivering the queued notifications
+    let retry_notifier = notifier.clone();
+    rt.spawn(runtime_stats.track(async move {
+        let mut interval = tokio::time::interval(notifications::RETRY_INTERVAL);
+        loop {
+            interval.tick().await;
+            let retry_notifier = retry_notifier.clone();
+            let _ = tokio::task::spawn_blocking(move || retry_notifier.retry()).await;
+        }
+    }));
+
+    // regularly check for a newer release (unless disabled)
+    let update_status = updates::update_status(env::PKG_VERSION);
+    if config.updates.check {
+        let update_checker = updates::UpdateChecker::new(
+            Box::new(updates::GithubReleaseChecker::new(config.proxy.as_deref())),
+            notifier.clone(),
+            update_status.clone(),
+        );
+        let update_interval = Duration::from_secs(config.updates.interval);
+        rt.spawn(runtime_stats.track(async move {
+            let update_checker = Arc::new(update_checker);
+            let mut interval = tokio::time::interval(update_interval);
+            loop {
+                interval.tick().await;
+                let update_checker = update_checker.clone();
+                let _ = tokio::task::spawn_blocking(move || update_checker.check_once()).await;
+            }
+        }));
+    } else {
+        info!("checking for updates is disabled");
+    }
+
+    // prepare the group controls shared by all servers of a group
+    let group_controls: Vec<control::GroupControl> = config
+        .groups
         .iter()
-        .map(|server| control::Factory::create_control(server, &config.api.files.root))
+        .map(|(group_id, group)| {
+            control::Factory::create_group_control(group_id, group, &config.api.files)
+        })
         .collect();
 
-    // get and convert the dependency tree
-    let dependencies = config.dependencies.clone();
-    let dependencies: dom::Dependencies = dependencies
-        .0
+    // prepare the server controls sharing a limited number of SSH sessions
+    let ssh_sessions = control::Factory::create_ssh_sessions(&config.network);
+    // and a limited number of wakeups
+    let wakeup_limit = control::Factory::create_wakeup_limit(&config.network.limits);
+    // and the hooks run around every wakeup / shutdown
+    let action_hooks =
+        control::Factory::create_action_hooks(&config.hooks, config.proxy.as_deref());
+    // and the events published about every wakeup / shutdown
+    let action_events = events::events();
+    // and only take over the control of the servers once the active instance stops responding
+    let standby = config.failover.as_ref().map(|failover| {
+        info!("running as the standby of {}", failover.peer);
+        Arc::new(dom::communication::Standby::new(
+            Duration::from_secs(failover.timeout),
+            Arc::new(utils::SystemClock),
+        ))
+    });
+    let server_controls: Vec<control::ServerControl> = servers
         .iter()
-        .map(|(device_id, deps)| {
-            (
-                dom::DeviceId::from(device_id),
-                deps.iter().map(dom::DeviceId::from).collect(),
+        .map(|server| {
+            control::Factory::create_control(
+                server,
+                &group_controls,
+                &config.api.files,
+                &ssh_sessions,
+                &wakeup_limit,
+                &action_hooks,
+                &standby,
+                Duration::from_secs(config.network.duplicate_action_window),
+                &metrics,
+                &action_events,
             )
         })
         .collect();
 
+    // keep the events of all calendars up to date
+    let calendars: HashMap<String, dom::Calendar> = config
+        .schedules
+        .calendars
+        .iter()
+        .map(|(name, calendar_config)| {
+            let calendar = dom::Calendar::new(&calendar_config.categories);
+            let calendar_sync = calendar_sync::CalendarSync::new(
+                name,
+                calendar_config,
+                calendar.clone(),
+                config.proxy.as_deref(),
+            );
+            rt.spawn(runtime_stats.track(calendar_sync.sync()));
+            (name.clone(), calendar)
+        })
+        .collect();
+
+    // schedules aren't monitored like devices but evaluated whenever a server is processed
+    let get_schedules = |schedule_ids: &Vec<configuration::DeviceId>| -> Vec<dom::Schedule> {
+        schedule_ids
+            .iter()
+            .filter_map(|id| dom::Schedule::new(id, &config.schedules, &calendars))
+            .collect()
+    };
+    // get, resolve (rooms and persons) and convert the dependency tree of every profile
+    let get_profile = |dependencies: &configuration::Dependencies| -> dom::communication::Profile {
+        let dependencies = configuration::resolve_dependencies(
+            &config.devices,
+            &config.rooms,
+            &config.persons,
+            dependencies,
+        );
+        dom::communication::Profile {
+            schedules: dependencies
+                .0
+                .iter()
+                .map(|(device_id, deps)| (dom::DeviceId::from(device_id), get_schedules(deps)))
+                .collect(),
+            dependencies: dependencies
+                .0
+                .iter()
+                .map(|(device_id, deps)| {
+                    (
+                        dom::DeviceId::from(device_id),
+                        deps.iter()
+                            .filter(|dep| !config.schedules.contains(dep))
+                            .map(dom::DeviceId::from)
+                            .collect(),
+                    )
+                })
+                .collect(),
+        }
+    };
+    let default_profile = get_profile(&config.dependencies);
+    let schedules = default_profile.schedules.clone();
+    let dependencies = default_profile.dependencies.clone();
+    let profiles = Arc::new(dom::communication::Profiles::new(
+        default_profile,
+        config
+            .profiles
+            .iter()
+            .map(|(name, profile)| (name.clone(), get_profile(&profile.dependencies)))
+            .collect(),
+    ));
+    // the monitor applies the profile active on startup right away
+    profiles.activate(config.profile.as_deref());
+    let away = Arc::new(dom::communication::Away::new(config.away));
+    let blackouts: HashMap<dom::DeviceId, Vec<dom::Schedule>> =
+        configuration::get_servers(&config.devices)
+            .iter()
+            .map(|(server_id, server)| {
+                (
+                    dom::DeviceId::from(server_id),
+                    get_schedules(&server.blackouts),
+                )
+            })
+            .collect();
+    // devices being offline is ignored during their configured (or one-off) ignore windows
+    let ignore_windows = Arc::new(dom::communication::IgnoreWindows::new(
+        config
+            .devices
+            .iter()
+            .map(|(device_id, device)| {
+                let machine = match device {
+                    configuration::Device::Server(server) => &server.machine,
+                    configuration::Device::Machine(machine) => machine,
+                };
+                (
+                    dom::DeviceId::from(device_id),
+                    get_schedules(&machine.ignore_windows),
+                )
+            })
+            .collect(),
+    ));
+
+    // keep track of the readiness / liveness of the monitoring
+    let readiness = Arc::new(dom::communication::Readiness::new(
+        &config.network.interface,
+    ));
+    // and the timing of its loop
+    let monitor_stats = Arc::new(dom::communication::MonitorStats::new());
+
+    // regularly query the configured routers for their associated clients
+    let presence = if config.presence.is_empty() {
+        None
+    } else {
+        let presence = Arc::new(networking::Presence::new());
+        for (name, provider) in &config.presence {
+            let poller = Arc::new(presence_poller::PresencePoller::new(
+                name,
+                control::Factory::create_router_clients(provider, config.proxy.as_deref()),
+                presence.clone(),
+            ));
+            let poll_interval = Duration::from_secs(provider.interval);
+            rt.spawn(runtime_stats.track(async move {
+                let mut interval = tokio::time::interval(poll_interval);
+                loop {
+                    interval.tick().await;
+                    let poller = poller.clone();
+                    let _ = tokio::task::spawn_blocking(move || poller.poll_once()).await;
+                }
+            }));
+        }
+
+        Some(presence)
+    };
+
+    // poll the active instance to know when to take over the control of the servers
+    if let (Some(failover), Some(standby)) = (&config.failover, &standby) {
+        let interval = Duration::from_secs(failover.interval);
+        let poller = Arc::new(failover::FailoverPoller::new(
+            remote::RemoteClient::new(
+                &failover.peer,
+                failover
+                    .token
+                    .as_ref()
+                    .map(|token| token.expose().to_string()),
+            ),
+            interval,
+            standby.clone(),
+            notifier.clone(),
+        ));
+        rt.spawn(runtime_stats.track(async move {
+            let mut interval = tokio::time::interval(interval);
+            loop {
+                interval.tick().await;
+                let poller = poller.clone();
+                let _ = tokio::task::spawn_blocking(move || poller.poll_once()).await;
+            }
+        }));
+    }
+
     // run the main code asynchronously
     info!("monitoring the network for activity...");
     let monitoring = {
-        let sender = if provide_web_api {
+        let sender = if provide_shared_state {
             dom::communication::create_mpsc_sender(tx)
         } else {
             dom::communication::create_noop_sender()
@@ -278,8 +914,40 @@ fn process(
         let server_controls = server_controls.clone();
         let machines = machines.clone();
         let dependencies = dependencies.clone();
-        rt.spawn(async move {
-            let pinger = control::Factory::create_pinger(None);
+        let cancellation = cancellation.child_token();
+        let readiness = readiness.clone();
+        let monitor_stats = monitor_stats.clone();
+        let standby = standby.clone();
+        let ignore_windows = ignore_windows.clone();
+        let profiles = profiles.clone();
+        let blackouts = blackouts.clone();
+        let away = away.clone();
+        let metrics = metrics.clone();
+        let notifier = notifier.clone();
+        let startup_grace_period = Duration::from_secs(config.network.startup_grace_period);
+        let interface = config.network.interface.clone();
+        let ping = config.network.ping.clone();
+        let limits = config.network.limits.clone();
+        let wakeup_stagger = control::Factory::create_wakeup_stagger(&limits);
+        let recovery = config.recovery.clone();
+        // the devices which are pinged from a specific source address
+        let sources: HashMap<IpAddr, IpAddr> = server_controls
+            .iter()
+            .map(|control| &control.server.machine)
+            .chain(machines.iter())
+            .filter_map(|machine| machine.source.map(|source| (machine.ip, source)))
+            .collect();
+        rt.spawn(runtime_stats.track(async move {
+            let arp_responders = Arc::new(networking::ArpResponders::new());
+            let pinger = control::Factory::create_pinger(
+                None,
+                &interface,
+                &ping,
+                &limits,
+                &arp_responders,
+                &presence,
+                &sources,
+            );
 
             let mut monitor = monitor::Monitor::new(
                 sender,
@@ -289,14 +957,41 @@ fn process(
                 dependencies,
                 pinger,
             );
+            monitor.set_schedules(schedules);
+            monitor.set_blackouts(blackouts);
+            monitor.set_guest_receiver(guest_rx);
+            monitor.set_external_wakeup_receiver(external_wakeup_rx);
+            monitor.set_change_timeout_reset_receiver(change_timeout_reset_rx);
+            monitor.set_cancellation_token(cancellation);
+            monitor.set_readiness(readiness);
+            monitor.set_stats(monitor_stats);
+            monitor.set_ignore_windows(ignore_windows);
+            monitor.set_profiles(profiles);
+            monitor.set_away(away);
+            monitor.set_arp_responders(arp_responders);
+            monitor.set_metrics(metrics);
+            if let Some(wakeup_stagger) = wakeup_stagger {
+                monitor.set_wakeup_stagger(wakeup_stagger);
+            }
+            if let Some(recovery) = recovery {
+                monitor.set_recovery(recovery);
+            }
+            monitor.set_notifier(notifier);
+            if let Some(standby) = standby {
+                monitor.set_standby(standby);
+            }
+            monitor.set_startup_grace_period(startup_grace_period);
+
+            // reach correct decisions right away instead of after the first intervals
+            monitor.reconcile().await;
 
             let mut interval = tokio::time::interval(Duration::from_secs(1));
 
             loop {
                 interval.tick().await;
-                monitor.run_once();
+                monitor.run_once().await;
             }
-        })
+        }))
     };
 
     // create a list of all devices
@@ -314,11 +1009,96 @@ fn process(
     let shared_state: Arc<dom::communication::SharedStateMutex> =
         Arc::new(Mutex::new(dom::communication::SharedState::new(devices)));
 
+    // restore the custom attributes and notes of the devices set through the web API
+    if let Some(files) = &config.api.files {
+        match utils::AttributesFile::new(&files.root).load() {
+            Ok(attributes) => {
+                let mut shared_state = shared_state.lock().unwrap();
+                for (device_id, attributes) in attributes {
+                    shared_state.set_attributes(&device_id.parse().unwrap(), attributes);
+                }
+            }
+            Err(e) => warn!("failed to load the custom attributes of the devices: {}", e),
+        }
+        match utils::NotesFile::new(&files.root).load() {
+            Ok(notes) => {
+                let mut shared_state = shared_state.lock().unwrap();
+                for (device_id, notes) in notes {
+                    shared_state.set_notes(&device_id.parse().unwrap(), notes);
+                }
+            }
+            Err(e) => warn!("failed to load the notes of the devices: {}", e),
+        }
+    }
+
+    // regularly fingerprint the online devices for the web API (unless disabled)
+    if provide_web_api && config.fingerprinting.enabled {
+        let fingerprinter = fingerprinter::Fingerprinter::new(
+            Box::new(networking::NetworkFingerprintProber::new(
+                Duration::from_secs(config.network.ping.timeout),
+            )),
+            shared_state.clone(),
+            config.fingerprinting.ports.clone(),
+        );
+        let fingerprint_interval = Duration::from_secs(config.fingerprinting.interval);
+        rt.spawn(runtime_stats.track(async move {
+            let fingerprinter = Arc::new(fingerprinter);
+            let mut interval = tokio::time::interval(fingerprint_interval);
+            loop {
+                interval.tick().await;
+                let fingerprinter = fingerprinter.clone();
+                let _ = tokio::task::spawn_blocking(move || fingerprinter.fingerprint_once()).await;
+            }
+        }));
+    }
+
+    // regularly check whether the IP addresses of the devices still belong to their MAC addresses
+    // (the ARP table is only read from /proc on Linux)
+    #[cfg(target_os = "linux")]
+    if config.network.mac_check_interval > 0 {
+        let machine_macs = config
+            .devices
+            .iter()
+            .filter_map(|(device_id, device)| match device {
+                configuration::Device::Machine(machine) => {
+                    machine.mac.map(|mac| (dom::DeviceId::from(device_id), mac))
+                }
+                configuration::Device::Server(_) => None,
+            })
+            .collect();
+        let mac_verifier = mac_verifier::MacVerifier::new(
+            Box::new(networking::ProcArpTable),
+            machine_macs,
+            shared_state.clone(),
+            notifier.clone(),
+        );
+        let mac_check_interval = Duration::from_secs(config.network.mac_check_interval);
+        rt.spawn(runtime_stats.track(async move {
+            let mac_verifier = Arc::new(mac_verifier);
+            let mut interval = tokio::time::interval(mac_check_interval);
+            loop {
+                interval.tick().await;
+                let mac_verifier = mac_verifier.clone();
+                let _ = tokio::task::spawn_blocking(move || mac_verifier.verify_once()).await;
+            }
+        }));
+    }
+    #[cfg(not(target_os = "linux"))]
+    if config.network.mac_check_interval > 0 {
+        warn!("not checking the MAC addresses of the devices because the ARP table can only be read on Linux");
+    }
+
+    // broadcast all device updates to the web API
+    let device_events = dom::communication::device_events();
+
     let sync = {
         let shared_state = shared_state.clone();
-        rt.spawn(async move {
-            if provide_web_api {
-                let mut shared_state_sync = web::SharedStateSync::new(shared_state, rx);
+        let device_events = device_events.clone();
+        let sync_stats = sync_stats.clone();
+        rt.spawn(runtime_stats.track(async move {
+            if provide_shared_state {
+                let mut shared_state_sync =
+                    web::SharedStateSync::new(shared_state, rx, device_events, sync_stats);
                 shared_state_sync.sync().await;
             } else {
                 // make sure the task never ends
@@ -326,10 +1106,101 @@ fn process(
                     tokio::time::sleep(Duration::from_millis(100)).await;
                 }
             }
-        })
+        }))
+    };
+
+    let beacon = {
+        let shared_state = shared_state.clone();
+        let address = SocketAddr::new(config.api.beacon.ip, config.api.beacon.port);
+        rt.spawn(runtime_stats.track(async move {
+            if provide_beacon {
+                let beacon = match beacon::Beacon::bind(address, shared_state).await {
+                    Ok(beacon) => beacon,
+                    Err(e) => panic!("failed to start the beacon on {}: {}", address, e),
+                };
+
+                info!("answering beacon queries on {}", address);
+                beacon.serve().await;
+            } else {
+                // make sure the task never ends
+                loop {
+                    tokio::time::sleep(Duration::from_millis(100)).await;
+                }
+            }
+        }))
+    };
+
+    let dns_trigger = {
+        let shared_state = shared_state.clone();
+        let server_controls = server_controls.clone();
+        let away = away.clone();
+        let startup_grace_period = Duration::from_secs(config.network.startup_grace_period);
+        let address = SocketAddr::new(config.api.dns.ip, config.api.dns.port);
+        let ttl = config.api.dns.ttl;
+        rt.spawn(runtime_stats.track(async move {
+            if provide_dns_trigger {
+                let mut dns_trigger = match dns_trigger::DnsTrigger::bind(
+                    address,
+                    ttl,
+                    server_controls,
+                    shared_state,
+                )
+                .await
+                {
+                    Ok(dns_trigger) => dns_trigger,
+                    Err(e) => panic!("failed to start the DNS trigger on {}: {}", address, e),
+                };
+                dns_trigger.set_away(away);
+                dns_trigger.set_startup_grace_period(startup_grace_period);
+
+                info!("answering DNS queries on {}", address);
+                dns_trigger.serve().await;
+            } else {
+                // make sure the task never ends
+                loop {
+                    tokio::time::sleep(Duration::from_millis(100)).await;
+                }
+            }
+        }))
+    };
+
+    let wake_on_lan_listener = {
+        let standby = standby.clone();
+        let address = SocketAddr::new(config.api.wake_on_lan.ip, config.api.wake_on_lan.port);
+        let relay = config.api.wake_on_lan.relay;
+        rt.spawn(runtime_stats.track(async move {
+            if provide_wake_on_lan_listener {
+                let mut wake_on_lan_listener = match wake_on_lan_listener::WakeOnLanListener::bind(
+                    address,
+                    relay,
+                    &servers,
+                    external_wakeup_tx,
+                )
+                .await
+                {
+                    Ok(wake_on_lan_listener) => wake_on_lan_listener,
+                    Err(e) => panic!(
+                        "failed to start the Wake-on-LAN listener on {}: {}",
+                        address, e
+                    ),
+                };
+                if let Some(standby) = standby {
+                    wake_on_lan_listener.set_standby(standby);
+                }
+
+                info!("listening for Wake-on-LAN packets on {}", address);
+                wake_on_lan_listener.serve().await;
+            } else {
+                // make sure the task never ends
+                loop {
+                    tokio::time::sleep(Duration::from_millis(100)).await;
+                }
+            }
+        }))
     };
 
-    let rocket = rt.spawn(async move {
+    let web_runtime_stats = runtime_stats.clone();
+    let rocket = rt.spawn(runtime_stats.track(async move {
         if provide_web_api {
             // configure logging depending on cli arguments
             let mut log_level = rocket::config::LogLevel::Off;
@@ -342,13 +1213,40 @@ fn process(
             let ip = config.api.web.ip;
             let port = config.api.web.port;
 
+            // diagnose the traffic of devices on demand
+            let packet_capture: Arc<dyn networking::PacketCapture> = Arc::new(
+                networking::NetworkPacketCapture::new(&config.network.interface),
+            );
+            let latency_prober: Arc<dyn networking::LatencyProber> =
+                Arc::new(networking::CommandLatencyProber::new());
+
             let server = web::Server::new(
                 env::PKG_NAME,
                 env::PKG_VERSION,
                 config,
+                PathBuf::from(&args.config),
                 shared_state,
                 server_controls,
+                group_controls,
                 dependencies,
+                guest_tx,
+                change_timeout_reset_tx,
+                device_events,
+                action_events,
+                metrics,
+                update_status,
+                readiness,
+                sync_stats,
+                monitor_stats,
+                web_runtime_stats,
+                ignore_windows,
+                profiles,
+                blackouts,
+                away,
+                log_buffer,
+                packet_capture,
+                latency_prober,
+                device_store,
                 ip,
                 port,
                 log_level,
@@ -364,14 +1262,38 @@ fn process(
                 tokio::time::sleep(Duration::from_millis(100)).await;
             }
         }
-    });
+    }));
 
     rt.block_on(async move {
         tokio::select! {
-            _ = sigterm => exitcode::OK,
-            _ = monitoring => exitcode::SOFTWARE,
-            _ = sync => exitcode::SOFTWARE,
-            _ = rocket => exitcode::SOFTWARE,
+            _ = sigterm => {
+                cancellation.cancel();
+                ExitStatus::Signal
+            },
+            result = monitoring => {
+                panic_hook::report_task_exit(&*notifier, "monitoring", &result);
+                ExitStatus::Software
+            },
+            result = sync => {
+                panic_hook::report_task_exit(&*notifier, "shared state sync", &result);
+                ExitStatus::Software
+            },
+            result = rocket => {
+                panic_hook::report_task_exit(&*notifier, "web API", &result);
+                ExitStatus::Software
+            },
+            result = beacon => {
+                panic_hook::report_task_exit(&*notifier, "beacon", &result);
+                ExitStatus::Software
+            },
+            result = dns_trigger => {
+                panic_hook::report_task_exit(&*notifier, "DNS trigger", &result);
+                ExitStatus::Software
+            },
+            result = wake_on_lan_listener => {
+                panic_hook::report_task_exit(&*notifier, "Wake-on-LAN listener", &result);
+                ExitStatus::Software
+            },
         }
     })
 }
@@ -386,8 +1308,33 @@ fn main() {
     } else if args.debug {
         log_level = LevelFilter::Debug;
     }
+    // only log errors (to stderr) to keep generated output on stdout usable
+    if args.import.is_some() || args.generate.is_some() || args.encrypt_secret {
+        log_level = LevelFilter::Error;
+    }
+
+    // keep the most recent log records for the web API
+    let log_buffer = Arc::new(log_buffer::LogBuffer::new(log_buffer::LOG_BUFFER_CAPACITY));
+    let _ = CombinedLogger::init(vec![
+        SimpleLogger::new(log_level, simplelog::Config::default()),
+        log_buffer::BufferLogger::new(log_level, log_buffer.clone()),
+    ]);
+
+    let status = start(args, log_buffer);
+    debug!("exiting: {}", status);
+    std::process::exit(status.code());
+}
+
+fn start(args: Opts, log_buffer: Arc<log_buffer::LogBuffer>) -> ExitStatus {
+    // generate device entries without requiring any local configuration
+    if let (Some(path), Some(format)) = (&args.import, args.from) {
+        return run_import(path, format);
+    }
 
-    let _ = SimpleLogger::init(log_level, simplelog::Config::default());
+    // control a remote daemon without requiring any local configuration
+    if let Some(url) = &args.remote {
+        return run_remote(&args, url);
+    }
 
     // read the configuration file
     info!("loading configuration from {}...", args.config);
@@ -395,46 +1342,115 @@ fn main() {
     match &config_result {
         Err(e) => {
             error!("failed to load configuration from {}: {}", args.config, e);
-            std::process::exit(exitcode::CONFIG);
+            return ExitStatus::Config;
         }
         _ => info!("configuration successfully loaded"),
     }
 
-    let config = config_result.unwrap();
+    let mut config = config_result.unwrap();
+
+    // manage the devices and dependencies in the device store (if configured)
+    let device_store = match config.store.clone() {
+        None => None,
+        Some(store) => {
+            info!("loading devices from {}...", store.path.display());
+            match store::DeviceStore::open(&store.path)
+                .and_then(|device_store| device_store.load_into(&mut config).map(|_| device_store))
+            {
+                Err(e) => {
+                    error!(
+                        "failed to load devices from {}: {}",
+                        store.path.display(),
+                        e
+                    );
+                    return ExitStatus::Config;
+                }
+                Ok(device_store) => Some(Arc::new(device_store)),
+            }
+        }
+    };
+
+    // the profile selected on the command line overrides the configured one
+    if let Some(profile) = &args.profile {
+        if !config.profiles.contains_key(profile) {
+            error!("{} is not a configured profile", profile);
+            return ExitStatus::Config;
+        }
+        config.profile = Some(profile.clone());
+    }
+
+    // suspicious setups are only reported because they might be intended
+    let warnings = configuration::lint(&config);
+    for warning in warnings.iter() {
+        warn!("configuration: {}", warning);
+    }
+    if args.check_config {
+        info!("configuration checked with {} warning(s)", warnings.len());
+        return ExitStatus::Success;
+    }
+
+    // encrypt a secret with the configured key file or a passphrase
+    if args.encrypt_secret {
+        return run_encrypt_secret(&config.secrets);
+    }
+
+    // generate the observability definitions without monitoring anything
+    if let Some(format) = args.generate {
+        println!("{}", generate::generate(format, &config));
+        return ExitStatus::Success;
+    }
 
     // create the network
     let network_interface = match networking::get_network_interface(&config.network.interface) {
         Err(e) => {
             error!("{}", e);
-            std::process::exit(exitcode::CONFIG);
+            return ExitStatus::Network;
         }
         Ok(r) => r,
     };
 
     if config.devices.is_empty() {
         error!("configuration doesn't contain any devices to monitor/control");
-        std::process::exit(exitcode::CONFIG);
+        return ExitStatus::Config;
     }
 
-    let configured_servers = configuration::get_servers(&config.devices);
+    let mut configured_servers = configuration::get_servers(&config.devices);
     if configured_servers.is_empty() {
         error!("configuration doesn't contain any servers to control");
-        std::process::exit(exitcode::CONFIG);
+        return ExitStatus::Config;
+    }
+
+    // the encrypted secrets are only decrypted in memory
+    if let Err(e) = secrets::decrypt_servers(&mut configured_servers, || {
+        secrets::SecretKey::load(&config.secrets)
+    }) {
+        error!("failed to decrypt the secrets: {}", e);
+        return ExitStatus::Config;
+    }
+    let mut configured_machines = configuration::get_machines(&config.devices);
+
+    // the class defaults only apply to the monitored devices and not to the configuration itself
+    for server in configured_servers.values_mut() {
+        configuration::apply_class(&mut server.machine, &config.classes);
+    }
+    for machine in configured_machines.values_mut() {
+        configuration::apply_class(machine, &config.classes);
     }
-    let configured_machines = configuration::get_machines(&config.devices);
 
     {
         // log the always on / off files
-        let files = &config.api.files;
-        info!("files API root directory: {}", files.root.to_str().unwrap());
+        match &config.api.files {
+            Some(files) => info!("files API root directory: {}", files.root.display()),
+            None => info!("files API disabled: always on / off are only kept in memory"),
+        }
     }
 
     // log the details of the configured network interface
-    info!(
-        "network: [{}] {}",
-        network_interface.name,
-        network_interface.mac.unwrap()
-    );
+    // the MAC address isn't available for every interface on every platform
+    match network_interface.mac {
+        Some(mac) => info!("network: [{}] {}", network_interface.name, mac),
+        None => info!("network: [{}]", network_interface.name),
+    }
     for ip in network_interface.ips.iter() {
         info!("  {}", ip);
     }
@@ -443,6 +1459,18 @@ fn main() {
         // log the ping configuration
         let ping = &config.network.ping;
         info!("ping: every {}s for {}s", ping.interval, ping.timeout);
+        // log the limits of the probes (0 is unlimited)
+        let limits = &config.network.limits;
+        info!(
+            "limits: {} pings per second, {} wakeups per minute",
+            limits.max_pings_per_second, limits.max_wakeups_per_minute
+        );
+    }
+    if config.network.startup_grace_period > 0 {
+        info!(
+            "startup grace period: {}s",
+            config.network.startup_grace_period
+        );
     }
 
     // log the details of the configured servers
@@ -454,7 +1482,7 @@ fn main() {
             server.machine.name,
             server.machine.ip,
             server.mac,
-            server.machine.last_seen_timeout
+            server.machine.last_seen_timeout.unwrap_or_default()
         );
     }
 
@@ -464,7 +1492,45 @@ fn main() {
         for (_, machine) in configured_machines.iter() {
             info!(
                 "  {}: {} ({}s)",
-                machine.name, machine.ip, machine.last_seen_timeout
+                machine.name,
+                machine.ip,
+                machine.last_seen_timeout.unwrap_or_default()
+            );
+        }
+    }
+
+    // log the configured profiles
+    if !config.profiles.is_empty() {
+        let mut profiles: Vec<&String> = config.profiles.keys().collect();
+        profiles.sort();
+        info!(
+            "profiles: {} (active: {})",
+            profiles
+                .iter()
+                .map(|profile| profile.as_str())
+                .collect::<Vec<&str>>()
+                .join(", "),
+            config.profile.as_deref().unwrap_or("none")
+        );
+    }
+    if config.away {
+        info!("away mode is active");
+    }
+
+    // log the details of the configured server groups
+    if !config.groups.is_empty() {
+        info!("groups ({}):", config.groups.len());
+        for (group_id, group) in config.groups.iter() {
+            info!(
+                "  {} [{}]: {}",
+                group.name,
+                group_id,
+                group
+                    .servers
+                    .iter()
+                    .map(|server_id| server_id.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", ")
             );
         }
     }
@@ -472,6 +1538,12 @@ fn main() {
     info!("");
 
     // run the monitoring process
-    let result = run(args, config, configured_servers, configured_machines);
-    std::process::exit(result);
+    run(
+        args,
+        config,
+        device_store,
+        configured_servers,
+        configured_machines,
+        log_buffer,
+    )
 }
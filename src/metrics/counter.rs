@@ -0,0 +1,54 @@
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq, Ord, PartialOrd)]
+pub enum Counter {
+    WakeupAttempts,
+    WakeupSuccesses,
+    WakeupFailures,
+    ShutdownAttempts,
+    ShutdownSuccesses,
+    ShutdownFailures,
+    ApiRequests,
+    NotificationDeliveries,
+    PowerTransitions,
+}
+
+impl Counter {
+    pub const ALL: [Counter; 9] = [
+        Counter::WakeupAttempts,
+        Counter::WakeupSuccesses,
+        Counter::WakeupFailures,
+        Counter::ShutdownAttempts,
+        Counter::ShutdownSuccesses,
+        Counter::ShutdownFailures,
+        Counter::ApiRequests,
+        Counter::NotificationDeliveries,
+        Counter::PowerTransitions,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::WakeupAttempts => "home_monitor_wakeup_attempts",
+            Self::WakeupSuccesses => "home_monitor_wakeup_successes",
+            Self::WakeupFailures => "home_monitor_wakeup_failures",
+            Self::ShutdownAttempts => "home_monitor_shutdown_attempts",
+            Self::ShutdownSuccesses => "home_monitor_shutdown_successes",
+            Self::ShutdownFailures => "home_monitor_shutdown_failures",
+            Self::ApiRequests => "home_monitor_api_requests",
+            Self::NotificationDeliveries => "home_monitor_notification_deliveries",
+            Self::PowerTransitions => "home_monitor_power_transitions",
+        }
+    }
+
+    pub fn help(&self) -> &'static str {
+        match self {
+            Self::WakeupAttempts => "Number of attempts to wake up a server.",
+            Self::WakeupSuccesses => "Number of successful wakeups of a server.",
+            Self::WakeupFailures => "Number of failed wakeups of a server.",
+            Self::ShutdownAttempts => "Number of attempts to shut down a server.",
+            Self::ShutdownSuccesses => "Number of successful shutdowns of a server.",
+            Self::ShutdownFailures => "Number of failed shutdowns of a server.",
+            Self::ApiRequests => "Number of requests to the web API.",
+            Self::NotificationDeliveries => "Number of notification deliveries.",
+            Self::PowerTransitions => "Number of changes of the online state of a server.",
+        }
+    }
+}
@@ -0,0 +1,48 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use super::{Counter, Registry};
+use crate::dom::{ActionContext, DeviceId};
+use crate::networking::{ActionReport, ConnectionTest, ShutdownError, ShutdownServer};
+
+pub struct MeteredShutdownServer {
+    server_id: DeviceId,
+    shutdown_server: Arc<dyn ShutdownServer>,
+    metrics: Arc<Registry>,
+}
+
+impl MeteredShutdownServer {
+    pub fn new(
+        server_id: &DeviceId,
+        shutdown_server: Arc<dyn ShutdownServer>,
+        metrics: Arc<Registry>,
+    ) -> Self {
+        Self {
+            server_id: server_id.clone(),
+            shutdown_server,
+            metrics,
+        }
+    }
+}
+
+#[async_trait]
+impl ShutdownServer for MeteredShutdownServer {
+    async fn shutdown(&self, context: &ActionContext) -> Result<ActionReport, ShutdownError> {
+        let labels = [("server", self.server_id.as_str())];
+        self.metrics.increment(Counter::ShutdownAttempts, &labels);
+
+        let result = self.shutdown_server.shutdown(context).await;
+        match result {
+            Ok(_) => self.metrics.increment(Counter::ShutdownSuccesses, &labels),
+            Err(_) => self.metrics.increment(Counter::ShutdownFailures, &labels),
+        }
+
+        result
+    }
+
+    async fn test_connection(&self, context: &ActionContext) -> anyhow::Result<ConnectionTest> {
+        // testing the connection is not a shutdown attempt
+        self.shutdown_server.test_connection(context).await
+    }
+}
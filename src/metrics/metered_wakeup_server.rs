@@ -0,0 +1,87 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use super::{Counter, Registry};
+use crate::dom::{ActionContext, DeviceId};
+use crate::networking::{ActionReport, WakeupServer};
+
+pub struct MeteredWakeupServer {
+    server_id: DeviceId,
+    wakeup_server: Arc<dyn WakeupServer>,
+    metrics: Arc<Registry>,
+}
+
+impl MeteredWakeupServer {
+    pub fn new(
+        server_id: &DeviceId,
+        wakeup_server: Arc<dyn WakeupServer>,
+        metrics: Arc<Registry>,
+    ) -> Self {
+        Self {
+            server_id: server_id.clone(),
+            wakeup_server,
+            metrics,
+        }
+    }
+}
+
+#[async_trait]
+impl WakeupServer for MeteredWakeupServer {
+    async fn wakeup(&self, context: &ActionContext) -> anyhow::Result<ActionReport> {
+        let labels = [("server", self.server_id.as_str())];
+        self.metrics.increment(Counter::WakeupAttempts, &labels);
+
+        let result = self.wakeup_server.wakeup(context).await;
+        match result {
+            Ok(_) => self.metrics.increment(Counter::WakeupSuccesses, &labels),
+            Err(_) => self.metrics.increment(Counter::WakeupFailures, &labels),
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use rstest::*;
+    use tokio_util::sync::CancellationToken;
+
+    use super::*;
+    use crate::dom::device::test::*;
+    use crate::dom::TraceContext;
+    use crate::networking::MockWakeupServer;
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_metered_wakeup_server_counts_attempts_and_results(server_id: DeviceId) {
+        // SETUP
+        let metrics = Arc::new(Registry::new());
+        let mut wakeup_server = MockWakeupServer::new();
+
+        // EXPECTATIONS
+        let mut results = vec![Err(anyhow::anyhow!("failed")), Ok(ActionReport::default())];
+        wakeup_server
+            .expect_wakeup()
+            .times(2)
+            .returning(move |_| results.pop().unwrap());
+
+        // TESTING
+        let metered =
+            MeteredWakeupServer::new(&server_id, Arc::new(wakeup_server), metrics.clone());
+        let context = ActionContext::new(
+            TraceContext::new(),
+            CancellationToken::new(),
+            Duration::from_secs(10),
+        );
+        assert!(metered.wakeup(&context).await.is_ok());
+        assert!(metered.wakeup(&context).await.is_err());
+
+        let labels = [("server", server_id.as_str())];
+        assert_eq!(metrics.get(Counter::WakeupAttempts, &labels), 2);
+        assert_eq!(metrics.get(Counter::WakeupSuccesses, &labels), 1);
+        assert_eq!(metrics.get(Counter::WakeupFailures, &labels), 1);
+    }
+}
@@ -0,0 +1,11 @@
+mod counter;
+mod metered_shutdown_server;
+mod metered_wakeup_server;
+mod process_stats;
+mod registry;
+
+pub use counter::Counter;
+pub use metered_shutdown_server::MeteredShutdownServer;
+pub use metered_wakeup_server::MeteredWakeupServer;
+pub use process_stats::ProcessStats;
+pub use registry::{Registry, AWAY, DEVICE_ONLINE};
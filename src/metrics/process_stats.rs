@@ -0,0 +1,48 @@
+use std::fs;
+
+/// Resource usage of the daemon itself (only available on systems providing `/proc`).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ProcessStats {
+    pub resident_memory_bytes: Option<u64>,
+    pub open_fds: Option<u64>,
+}
+
+impl ProcessStats {
+    pub fn current() -> Self {
+        Self {
+            resident_memory_bytes: fs::read_to_string("/proc/self/status")
+                .ok()
+                .and_then(|status| parse_resident_memory(&status)),
+            open_fds: fs::read_dir("/proc/self/fd")
+                .ok()
+                .map(|fds| fds.count() as u64),
+        }
+    }
+}
+
+/// Parses the resident set size (`VmRSS`, in kB) of `/proc/self/status` into bytes.
+fn parse_resident_memory(status: &str) -> Option<u64> {
+    let kilobytes: u64 = status
+        .lines()
+        .find_map(|line| line.strip_prefix("VmRSS:"))?
+        .split_whitespace()
+        .next()?
+        .parse()
+        .ok()?;
+
+    Some(kilobytes * 1024)
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::*;
+
+    use super::*;
+
+    #[rstest]
+    #[case("Name:\thome-monitor-rs\nVmPeak:\t  20480 kB\nVmRSS:\t   8192 kB\nThreads:\t5\n", Some(8192 * 1024))]
+    #[case("Name:\thome-monitor-rs\n", None)]
+    fn test_parse_resident_memory(#[case] status: &str, #[case] expected: Option<u64>) {
+        assert_eq!(parse_resident_memory(status), expected);
+    }
+}
@@ -0,0 +1,523 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::Mutex;
+
+use super::{Counter, ProcessStats};
+use crate::dom::communication::{MonitorStats, RuntimeStats, SyncStats, MAX_BLOCKING_THREADS};
+use crate::dom::{Device, Server};
+
+/// Name of the gauge reporting whether a device is online (1) or offline (0).
+pub const DEVICE_ONLINE: &str = "home_monitor_device_online";
+/// Name of the gauge reporting whether the away mode is active (1) or not (0).
+pub const AWAY: &str = "home_monitor_away";
+/// Name of the gauges reporting the daily on-time budget of servers.
+pub const SERVER_ON_TIME_TODAY: &str = "home_monitor_server_on_time_today_seconds";
+pub const SERVER_BUDGET_EXHAUSTED: &str = "home_monitor_server_budget_exhausted";
+
+type Labels = Vec<(String, String)>;
+
+#[derive(Debug, Default)]
+pub struct Registry {
+    counters: Mutex<BTreeMap<Counter, BTreeMap<Labels, u64>>>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn increment(&self, counter: Counter, labels: &[(&str, &str)]) {
+        let labels: Labels = labels
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+
+        let mut counters = self.counters.lock().unwrap();
+        *counters
+            .entry(counter)
+            .or_default()
+            .entry(labels)
+            .or_default() += 1;
+    }
+
+    #[cfg(test)]
+    pub fn get(&self, counter: Counter, labels: &[(&str, &str)]) -> u64 {
+        let counters = self.counters.lock().unwrap();
+        counters
+            .get(&counter)
+            .and_then(|values| {
+                values.iter().find_map(|(value_labels, value)| {
+                    let matches = value_labels.len() == labels.len()
+                        && value_labels.iter().zip(labels.iter()).all(
+                            |((name, value), (label_name, label_value))| {
+                                name == label_name && value == label_value
+                            },
+                        );
+                    matches.then_some(*value)
+                })
+            })
+            .unwrap_or(0)
+    }
+
+    /// Encodes all counters, the online state of the given devices, whether the away mode is
+    /// active, the statistics of the shared state synchronization, the timing of the monitoring
+    /// loop and the resource usage of the tokio runtime and the process in the OpenMetrics text
+    /// exposition format.
+    pub fn encode(
+        &self,
+        devices: &[Device],
+        sync_stats: &SyncStats,
+        monitor_stats: &MonitorStats,
+        runtime_stats: &RuntimeStats,
+        process_stats: &ProcessStats,
+        away: bool,
+    ) -> String {
+        let counters = self.counters.lock().unwrap();
+
+        let mut output = String::new();
+        for counter in Counter::ALL {
+            let name = counter.name();
+            writeln!(output, "# TYPE {name} counter").unwrap();
+            writeln!(output, "# HELP {name} {}", counter.help()).unwrap();
+
+            if let Some(values) = counters.get(&counter) {
+                for (labels, value) in values {
+                    if labels.is_empty() {
+                        writeln!(output, "{name}_total {value}").unwrap();
+                    } else {
+                        let labels = labels
+                            .iter()
+                            .map(|(name, value)| format!("{name}=\"{}\"", escape(value)))
+                            .collect::<Vec<String>>()
+                            .join(",");
+                        writeln!(output, "{name}_total{{{labels}}} {value}").unwrap();
+                    }
+                }
+            }
+        }
+
+        writeln!(output, "# TYPE {DEVICE_ONLINE} gauge").unwrap();
+        writeln!(
+            output,
+            "# HELP {DEVICE_ONLINE} Whether the device is online (1) or offline (0)."
+        )
+        .unwrap();
+        for device in devices {
+            writeln!(
+                output,
+                "{DEVICE_ONLINE}{{device=\"{}\"}} {}",
+                escape(&device.id().to_string()),
+                u8::from(device.is_online())
+            )
+            .unwrap();
+        }
+        encode_metric(
+            &mut output,
+            AWAY,
+            "gauge",
+            "Whether the away mode is active (1) or not (0).",
+            &u8::from(away).to_string(),
+        );
+        encode_budgets(&mut output, devices);
+        encode_sync_stats(&mut output, sync_stats);
+        encode_monitor_stats(&mut output, monitor_stats);
+        encode_runtime_stats(&mut output, runtime_stats);
+        encode_process_stats(&mut output, process_stats);
+        output.push_str("# EOF\n");
+
+        output
+    }
+}
+
+fn encode_metric(output: &mut String, name: &str, kind: &str, help: &str, value: &str) {
+    writeln!(output, "# TYPE {name} {kind}").unwrap();
+    writeln!(output, "# HELP {name} {help}").unwrap();
+    match kind {
+        "counter" => writeln!(output, "{name}_total {value}").unwrap(),
+        _ => writeln!(output, "{name} {value}").unwrap(),
+    }
+}
+
+fn encode_budgets(output: &mut String, devices: &[Device]) {
+    let servers: Vec<&Server> = devices
+        .iter()
+        .filter_map(|device| match device {
+            Device::Server(server) if server.max_daily_on_time.is_some() => Some(server),
+            _ => None,
+        })
+        .collect();
+    if servers.is_empty() {
+        return;
+    }
+
+    writeln!(output, "# TYPE {SERVER_ON_TIME_TODAY} gauge").unwrap();
+    writeln!(
+        output,
+        "# HELP {SERVER_ON_TIME_TODAY} Time the server has been online today."
+    )
+    .unwrap();
+    for server in servers.iter() {
+        writeln!(
+            output,
+            "{SERVER_ON_TIME_TODAY}{{server=\"{}\"}} {}",
+            escape(&server.machine.id.to_string()),
+            server.on_time_today.as_secs()
+        )
+        .unwrap();
+    }
+
+    writeln!(output, "# TYPE {SERVER_BUDGET_EXHAUSTED} gauge").unwrap();
+    writeln!(
+        output,
+        "# HELP {SERVER_BUDGET_EXHAUSTED} Whether the daily on-time budget of the server is exhausted (1) or not (0)."
+    )
+    .unwrap();
+    for server in servers {
+        writeln!(
+            output,
+            "{SERVER_BUDGET_EXHAUSTED}{{server=\"{}\"}} {}",
+            escape(&server.machine.id.to_string()),
+            u8::from(server.is_budget_exhausted())
+        )
+        .unwrap();
+    }
+}
+
+fn encode_sync_stats(output: &mut String, sync_stats: &SyncStats) {
+    encode_metric(
+        output,
+        "home_monitor_state_sync_queue_depth",
+        "gauge",
+        "Number of device updates waiting to be applied to the shared state.",
+        &sync_stats.queue_depth().to_string(),
+    );
+    encode_metric(
+        output,
+        "home_monitor_state_sync_failed_sends",
+        "counter",
+        "Number of device updates which couldn't be sent to the shared state.",
+        &sync_stats.failed_sends().to_string(),
+    );
+    encode_metric(
+        output,
+        "home_monitor_state_sync_coalesced_updates",
+        "counter",
+        "Number of device updates which replaced an older update of the same device.",
+        &sync_stats.coalesced_updates().to_string(),
+    );
+    encode_metric(
+        output,
+        "home_monitor_state_sync_updates",
+        "counter",
+        "Number of device updates applied to the shared state.",
+        &sync_stats.synced_updates().to_string(),
+    );
+    encode_metric(
+        output,
+        "home_monitor_state_sync_latency_seconds",
+        "gauge",
+        "Time it took to apply the last device update to the shared state.",
+        &sync_stats.last_latency().as_secs_f64().to_string(),
+    );
+    encode_metric(
+        output,
+        "home_monitor_state_sync_max_latency_seconds",
+        "gauge",
+        "Longest time it took to apply a device update to the shared state.",
+        &sync_stats.max_latency().as_secs_f64().to_string(),
+    );
+    encode_metric(
+        output,
+        "home_monitor_device_events_backlog",
+        "gauge",
+        "Number of device events not yet received by all subscribers.",
+        &sync_stats.event_backlog().to_string(),
+    );
+}
+
+fn encode_monitor_stats(output: &mut String, monitor_stats: &MonitorStats) {
+    encode_metric(
+        output,
+        "home_monitor_loop_ping_cycles",
+        "counter",
+        "Number of times the devices have been pinged by the monitoring loop.",
+        &monitor_stats.ping_cycles().to_string(),
+    );
+    encode_metric(
+        output,
+        "home_monitor_loop_ping_drift_seconds",
+        "gauge",
+        "Time by which the last ping of the devices exceeded the configured ping interval.",
+        &monitor_stats.last_drift().as_secs_f64().to_string(),
+    );
+    encode_metric(
+        output,
+        "home_monitor_loop_max_ping_drift_seconds",
+        "gauge",
+        "Maximum time by which a ping of the devices exceeded the configured ping interval.",
+        &monitor_stats.max_drift().as_secs_f64().to_string(),
+    );
+    encode_metric(
+        output,
+        "home_monitor_loop_duration_seconds",
+        "gauge",
+        "Time the last run of the monitoring loop took.",
+        &monitor_stats.last_duration().as_secs_f64().to_string(),
+    );
+    encode_metric(
+        output,
+        "home_monitor_loop_overruns",
+        "counter",
+        "Number of runs of the monitoring loop which took longer than the ping interval.",
+        &monitor_stats.overruns().to_string(),
+    );
+}
+
+fn encode_runtime_stats(output: &mut String, runtime_stats: &RuntimeStats) {
+    encode_metric(
+        output,
+        "home_monitor_runtime_workers",
+        "gauge",
+        "Number of worker threads of the tokio runtime.",
+        &runtime_stats.workers().to_string(),
+    );
+    encode_metric(
+        output,
+        "home_monitor_runtime_busy_workers",
+        "gauge",
+        "Number of worker threads of the tokio runtime which are currently running tasks.",
+        &runtime_stats.busy_workers().to_string(),
+    );
+    encode_metric(
+        output,
+        "home_monitor_runtime_tasks",
+        "gauge",
+        "Number of running long-lived tasks (e.g. the monitoring loop or the web API).",
+        &runtime_stats.tasks().to_string(),
+    );
+    encode_metric(
+        output,
+        "home_monitor_runtime_blocking_threads",
+        "gauge",
+        "Number of threads of the blocking pool of the tokio runtime.",
+        &runtime_stats.blocking_threads().to_string(),
+    );
+    encode_metric(
+        output,
+        "home_monitor_runtime_max_blocking_threads",
+        "gauge",
+        "Maximum number of threads of the blocking pool of the tokio runtime.",
+        &MAX_BLOCKING_THREADS.to_string(),
+    );
+}
+
+fn encode_process_stats(output: &mut String, process_stats: &ProcessStats) {
+    if let Some(resident_memory_bytes) = process_stats.resident_memory_bytes {
+        encode_metric(
+            output,
+            "home_monitor_process_resident_memory_bytes",
+            "gauge",
+            "Resident memory size of the process.",
+            &resident_memory_bytes.to_string(),
+        );
+    }
+    if let Some(open_fds) = process_stats.open_fds {
+        encode_metric(
+            output,
+            "home_monitor_process_open_fds",
+            "gauge",
+            "Number of open file descriptors of the process.",
+            &open_fds.to_string(),
+        );
+    }
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use rstest::*;
+
+    use super::*;
+    use crate::dom::device::test::*;
+    use crate::dom::{Machine, Server};
+
+    #[fixture]
+    fn registry() -> Registry {
+        Registry::new()
+    }
+
+    #[rstest]
+    fn test_registry_increments_counters_per_labels(registry: Registry) {
+        registry.increment(Counter::WakeupAttempts, &[("server", "a")]);
+        registry.increment(Counter::WakeupAttempts, &[("server", "a")]);
+        registry.increment(Counter::WakeupAttempts, &[("server", "b")]);
+
+        assert_eq!(registry.get(Counter::WakeupAttempts, &[("server", "a")]), 2);
+        assert_eq!(registry.get(Counter::WakeupAttempts, &[("server", "b")]), 1);
+        assert_eq!(registry.get(Counter::WakeupAttempts, &[("server", "c")]), 0);
+        assert_eq!(registry.get(Counter::WakeupFailures, &[("server", "a")]), 0);
+    }
+
+    #[rstest]
+    fn test_registry_encodes_openmetrics(registry: Registry) {
+        registry.increment(Counter::ShutdownFailures, &[("server", "my\"server")]);
+
+        let output = registry.encode(
+            &[],
+            &SyncStats::new(),
+            &MonitorStats::new(),
+            &RuntimeStats::new(1),
+            &ProcessStats::default(),
+            false,
+        );
+
+        assert!(output.contains("# TYPE home_monitor_shutdown_failures counter\n"));
+        assert!(
+            output.contains("home_monitor_shutdown_failures_total{server=\"my\\\"server\"} 1\n")
+        );
+        assert!(output.ends_with("# EOF\n"));
+    }
+
+    #[rstest]
+    fn test_registry_encodes_device_online_gauge(
+        registry: Registry,
+        server: Server,
+        mut machine: Machine,
+    ) {
+        machine.set_online(true);
+        let devices = vec![Device::Server(server), Device::Machine(machine)];
+
+        let output = registry.encode(
+            &devices,
+            &SyncStats::new(),
+            &MonitorStats::new(),
+            &RuntimeStats::new(1),
+            &ProcessStats::default(),
+            false,
+        );
+
+        assert!(output.contains("# TYPE home_monitor_device_online gauge\n"));
+        assert!(output.contains("home_monitor_device_online{device=\"testserver\"} 0\n"));
+        assert!(output.contains("home_monitor_device_online{device=\"testmachine\"} 1\n"));
+        assert!(output.contains("# TYPE home_monitor_away gauge\n"));
+        assert!(output.contains("home_monitor_away 0\n"));
+        assert!(output.ends_with("# EOF\n"));
+    }
+
+    #[rstest]
+    fn test_registry_encodes_budget_gauges_of_servers_with_budget(
+        registry: Registry,
+        mut server: Server,
+    ) {
+        // servers without a budget aren't reported
+        let output = registry.encode(
+            &[Device::Server(server.clone())],
+            &SyncStats::new(),
+            &MonitorStats::new(),
+            &RuntimeStats::new(1),
+            &ProcessStats::default(),
+            false,
+        );
+        assert!(!output.contains("home_monitor_server_budget_exhausted"));
+
+        server.max_daily_on_time = Some(Duration::from_secs(3600));
+        server.on_time_today = Duration::from_secs(3600);
+        let output = registry.encode(
+            &[Device::Server(server)],
+            &SyncStats::new(),
+            &MonitorStats::new(),
+            &RuntimeStats::new(1),
+            &ProcessStats::default(),
+            false,
+        );
+
+        assert!(output.contains("# TYPE home_monitor_server_on_time_today_seconds gauge\n"));
+        assert!(output
+            .contains("home_monitor_server_on_time_today_seconds{server=\"testserver\"} 3600\n"));
+        assert!(output.contains("home_monitor_server_budget_exhausted{server=\"testserver\"} 1\n"));
+    }
+
+    #[rstest]
+    fn test_registry_encodes_sync_stats(registry: Registry) {
+        let sync_stats = SyncStats::new();
+        sync_stats.sent();
+        sync_stats.sent();
+        sync_stats.send_failed();
+
+        let output = registry.encode(
+            &[],
+            &sync_stats,
+            &MonitorStats::new(),
+            &RuntimeStats::new(1),
+            &ProcessStats::default(),
+            false,
+        );
+
+        assert!(output.contains("# TYPE home_monitor_state_sync_queue_depth gauge\n"));
+        assert!(output.contains("home_monitor_state_sync_queue_depth 1\n"));
+        assert!(output.contains("home_monitor_state_sync_failed_sends_total 1\n"));
+        assert!(output.contains("home_monitor_state_sync_updates_total 0\n"));
+        assert!(output.ends_with("# EOF\n"));
+    }
+
+    #[rstest]
+    fn test_registry_encodes_monitor_stats(registry: Registry) {
+        let monitor_stats = MonitorStats::new();
+        let interval = Duration::from_secs(5);
+        monitor_stats.ping_cycle(interval, Duration::from_millis(5500));
+        monitor_stats.run(interval, Duration::from_secs(6));
+
+        let output = registry.encode(
+            &[],
+            &SyncStats::new(),
+            &monitor_stats,
+            &RuntimeStats::new(1),
+            &ProcessStats::default(),
+            false,
+        );
+
+        assert!(output.contains("home_monitor_loop_ping_cycles_total 1\n"));
+        assert!(output.contains("# TYPE home_monitor_loop_ping_drift_seconds gauge\n"));
+        assert!(output.contains("home_monitor_loop_ping_drift_seconds 0.5\n"));
+        assert!(output.contains("home_monitor_loop_duration_seconds 6\n"));
+        assert!(output.contains("home_monitor_loop_overruns_total 1\n"));
+    }
+
+    #[rstest]
+    fn test_registry_encodes_runtime_and_process_stats(registry: Registry) {
+        let runtime_stats = RuntimeStats::new(2);
+        for _ in 0..3 {
+            runtime_stats.thread_started();
+        }
+        runtime_stats.worker_parked();
+        let process_stats = ProcessStats {
+            resident_memory_bytes: Some(8192 * 1024),
+            open_fds: None,
+        };
+
+        let output = registry.encode(
+            &[],
+            &SyncStats::new(),
+            &MonitorStats::new(),
+            &runtime_stats,
+            &process_stats,
+            false,
+        );
+
+        assert!(output.contains("home_monitor_runtime_workers 2\n"));
+        assert!(output.contains("home_monitor_runtime_busy_workers 1\n"));
+        assert!(output.contains("home_monitor_runtime_blocking_threads 1\n"));
+        assert!(output.contains("home_monitor_runtime_max_blocking_threads 512\n"));
+        assert!(output.contains("home_monitor_process_resident_memory_bytes 8388608\n"));
+        // metrics which aren't available aren't reported
+        assert!(!output.contains("home_monitor_process_open_fds"));
+    }
+}
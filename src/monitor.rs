@@ -1,26 +1,92 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::net::IpAddr;
 use std::ops::Sub;
 use std::sync::{Arc, RwLock};
 use std::time::Duration;
 
+use chrono::NaiveDate;
 use log::{debug, error, info, trace, warn};
-
-use super::control::ServerControl;
-use super::dom::{communication, Dependencies, Device, DeviceId, Machine, Server};
-use super::networking::Pinger;
-use super::utils::Instant;
+use tokio_util::sync::CancellationToken;
+
+use super::configuration::{self, NotificationEvent};
+use super::control::{ServerControl, ACTION_TIMEOUT};
+use super::dom::device::{ExternalCheck, ServiceCheck};
+use super::dom::{
+    communication, ActionContext, Dependencies, Device, DeviceId, Guest, Machine, ProbeMethod,
+    Schedule, Server, TraceContext, Transition, TransitionCause,
+};
+use super::metrics::{Counter, Registry};
+use super::networking::{
+    check_all, ActionInProgress, ArpResponders, HttpChecker, Pinger, PortChecker,
+    SnmpTrafficChecker, SshSessionsChecker, TcpPortChecker,
+};
+use super::notifications::{format_duration, Notification, Notifier};
+use super::recovery::RecoveryDetector;
+use super::utils::{Clock, Instant, SystemClock, WakeupStagger};
 
 const CHANGE_TIMEOUT: Duration = Duration::from_secs(120);
+const SERVICE_CHECK_TIMEOUT: Duration = Duration::from_secs(1);
+const STARTUP_PING_ROUNDS: u32 = 3;
 
 type SharedDevice = Arc<RwLock<Device>>;
 
+fn create_service_checker(machine: &Machine, check: &ServiceCheck) -> Arc<dyn PortChecker> {
+    match check {
+        ServiceCheck::Tcp(port) => Arc::new(
+            TcpPortChecker::new(machine.ip, *port, SERVICE_CHECK_TIMEOUT)
+                .with_source(machine.source),
+        ),
+        ServiceCheck::Http(http) => Arc::new(HttpChecker::new(http)),
+    }
+}
+
+fn create_external_checker(server: &Server, check: &ExternalCheck) -> Arc<dyn PortChecker> {
+    match check {
+        ExternalCheck::Http(http) => Arc::new(HttpChecker::new(http)),
+        ExternalCheck::Snmp(snmp) => Arc::new(SnmpTrafficChecker::new(snmp)),
+        ExternalCheck::Sessions(sessions) => Arc::new(SshSessionsChecker::new(server, sessions)),
+    }
+}
+
 struct MonitoredServer {
     pub control: ServerControl,
     pub server: SharedDevice,
     pub devices: Vec<SharedDevice>,
+    pub schedules: Vec<Schedule>,
+    pub blackouts: Vec<Schedule>,
+    pub service_checkers: Vec<Arc<dyn PortChecker>>,
+    pub external_checkers: Vec<Arc<dyn PortChecker>>,
+    pub external_checks: Vec<Option<Instant>>,
     pub always_off_state: bool,
     pub always_on_state: bool,
     pub last_change: Instant,
+    pub shut_down: bool,
+    /// Time the server has first been seen offline while it's expected to be offline.
+    pub offline_since: Option<Instant>,
+    /// Time of the last shutdown which hasn't been verified yet.
+    pub unverified_shutdown: Option<Instant>,
+    on_time: Duration,
+    on_time_date: NaiveDate,
+    on_time_updated: Instant,
+    online_state: Option<bool>,
+    online_state_since: Option<Instant>,
+    /// Change of the online state observed last which hasn't been reported yet.
+    transition: Option<Transition>,
+    wakeup_stagger: Option<Arc<WakeupStagger>>,
+    /// Whether shutdowns are suppressed during the recovery from a power outage.
+    pub recovering: bool,
+    /// Whether the server is woken up as the next core server of the recovery.
+    pub recovery_wakeup: bool,
+    /// Whether the away mode is active.
+    pub away: bool,
+    /// Number of consecutive failed shutdowns (reset once the server has been shut down).
+    consecutive_failed_shutdowns: u32,
+    /// Number of failed shutdowns after which the control has been disabled which hasn't been
+    /// reported yet.
+    control_disabled: Option<u32>,
+    /// Whether the server rejected its credentials which hasn't been reported yet.
+    credentials_rejected: bool,
+    clock: Arc<dyn Clock>,
 }
 
 impl MonitoredServer {
@@ -29,14 +95,51 @@ impl MonitoredServer {
         server: SharedDevice,
         devices: Vec<SharedDevice>,
         last_change: Instant,
+        clock: Arc<dyn Clock>,
     ) -> Self {
+        let service_checkers = control
+            .server
+            .services
+            .iter()
+            .map(|service| create_service_checker(&control.server.machine, &service.check))
+            .collect();
+        let external_checkers: Vec<Arc<dyn PortChecker>> = control
+            .server
+            .external_dependencies
+            .iter()
+            .map(|dependency| create_external_checker(&control.server, &dependency.check))
+            .collect();
+        let external_checks = vec![None; external_checkers.len()];
+
         Self {
             control,
             server,
             devices,
+            schedules: Vec::new(),
+            blackouts: Vec::new(),
+            service_checkers,
+            external_checkers,
+            external_checks,
             always_off_state: false,
             always_on_state: false,
             last_change,
+            shut_down: false,
+            offline_since: None,
+            unverified_shutdown: None,
+            on_time: Duration::ZERO,
+            on_time_date: clock.local_now().date_naive(),
+            on_time_updated: clock.now(),
+            online_state: None,
+            online_state_since: None,
+            transition: None,
+            wakeup_stagger: None,
+            recovering: false,
+            recovery_wakeup: false,
+            away: false,
+            consecutive_failed_shutdowns: 0,
+            control_disabled: None,
+            credentials_rejected: false,
+            clock,
         }
     }
 
@@ -44,11 +147,12 @@ impl MonitoredServer {
         &self.control.server
     }
 
-    pub fn process(&mut self) {
+    pub async fn process(&mut self, cancellation: &CancellationToken) -> bool {
         trace!("processing {}...", self.server());
 
         // first update the internal state of the files API
         self.update_files_api();
+        let mut changed = self.update_online_state();
 
         // check if any device is online
         let any_device_is_online = self
@@ -56,48 +160,706 @@ impl MonitoredServer {
             .iter()
             .any(|device| device.read().unwrap().is_online());
 
+        // check if any schedule (time window or position of the sun) is active
+        let now = self.clock.local_now();
+        let any_schedule_is_active = self
+            .schedules
+            .iter()
+            .any(|schedule| schedule.is_active(&now));
+        // the away mode replaces the dependencies: only the servers kept on when away have one
+        let any_dependency_is_active = if self.away {
+            self.server().keep_on_when_away
+        } else {
+            any_device_is_online || any_schedule_is_active
+        };
+
         // process the collected information
-        if self.always_off_state
-            || self.always_on_state
-            || self.last_change.elapsed() > CHANGE_TIMEOUT
-        {
-            let server = self.server.read().unwrap();
+        changed |= self.update_on_time(now.date_naive());
+
+        // only monitor the server while its control is disabled
+        changed |= self.update_control_switch() | self.update_credentials();
+        if self.control.control_switch.is_disabled() {
+            trace!(
+                "{}: not processing while its control is disabled",
+                self.server()
+            );
+            return changed;
+        }
+
+        let (always_off, always_on) = (self.always_off_state, self.always_on_state);
+        let mut action_result = None;
+        if always_off || always_on || self.clock.elapsed(self.last_change) > CHANGE_TIMEOUT {
+            // remember which dependencies decided about the server to be able to spot stale ones
+            if !always_off && !always_on && !self.away {
+                changed |= self.record_triggers();
+            }
+
+            // don't hold the lock on the server while running any action
+            let server = self.server.read().unwrap().clone();
 
             // if the server is not online and
             //   the always on file exists or
-            //   any device is online
+            //   any device is online or any schedule is active (within the daily budget)
             // then wake the server up
             if !server.is_online()
-                && !self.always_off_state
-                && (self.always_on_state || any_device_is_online)
+                && !always_off
+                && (always_on
+                    || self.recovery_wakeup
+                    || (any_dependency_is_active
+                        && !self.is_budget_exhausted(&server)
+                        && !self.is_within_min_duration(&server)))
+                && !self.defer_wakeup(&mut changed)
             {
-                info!("waking up {}...", server);
-                match self.control.wakeup.wakeup() {
-                    Err(_) => error!("failed to wake up {}", server),
-                    Ok(_) => {
-                        self.last_change = Instant::now();
+                let context = Self::create_action_context(cancellation);
+                info!("[{}] waking up {}...", context, server);
+                match self.control.wakeup.wakeup(&context).await {
+                    // another wakeup of the server is neither a success nor a failure
+                    Err(e) if e.downcast_ref::<ActionInProgress>().is_some() => {
+                        debug!("[{}] not waking up {}: {}", context, server, e);
+                    }
+                    Err(e) => {
+                        error!("[{}] failed to wake up {}: {}", context, server, e);
+                        action_result = Some(false);
+                    }
+                    Ok(report) => {
+                        info!("[{}] woke up {}: {}", context, server, report);
+                        let cause = if !always_on
+                            && !self.away
+                            && !any_device_is_online
+                            && any_schedule_is_active
+                        {
+                            TransitionCause::Schedule
+                        } else {
+                            TransitionCause::Monitor
+                        };
+                        self.control.expected_transition.expect(true, cause);
+                        self.record_change();
+                        self.shut_down = false;
+                        action_result = Some(true);
                     }
                 }
             } else if server.is_online()
-                && !self.always_on_state
-                && (self.always_off_state || !any_device_is_online)
+                && !always_on
+                && !self.control.invalid_credentials.are_invalid()
+                && (always_off
+                    || (!any_dependency_is_active
+                        && !self.recovering
+                        && !self.is_within_min_duration(&server)))
+                && !self.defer_shutdown(&mut changed).await
+            {
+                let context = Self::create_action_context(cancellation);
+                info!("[{}] shutting down {}...", context, server);
+                match self.control.shutdown.shutdown(&context).await {
+                    Err(e) if e.in_progress().is_some() => {
+                        debug!("[{}] not shutting down {}: {}", context, server, e);
+                    }
+                    Err(e) => {
+                        error!("[{}] failed to shut down {}: {}", context, server, e);
+                        self.record_failed_shutdown();
+                        action_result = Some(false);
+                    }
+                    Ok(report) => {
+                        info!("[{}] shut down {}: {}", context, server, report);
+                        // without verification a successful shutdown command has to suffice
+                        if let Device::Server(server) = &server {
+                            if server.shutdown_verification.is_none() {
+                                self.consecutive_failed_shutdowns = 0;
+                            }
+                        }
+                        self.control
+                            .expected_transition
+                            .expect(false, TransitionCause::Monitor);
+                        self.record_change();
+                        self.shut_down = true;
+                        self.unverified_shutdown = Some(self.last_change);
+                        action_result = Some(true);
+                    }
+                }
+            }
+        }
+
+        // keep track of consecutive failed actions
+        changed |= self.update_credentials();
+        match action_result {
+            Some(succeeded) => self.update_action_result(succeeded) || changed,
+            None => changed,
+        }
+    }
+
+    fn is_budget_exhausted(&self, server: &Device) -> bool {
+        match server {
+            Device::Server(server) if server.is_budget_exhausted() => {
+                debug!(
+                    "{}: not waking up because the daily on-time budget is exhausted",
+                    server
+                );
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Remembers since when the server has been in its current online state. The state observed
+    /// first is not considered a change because it's unknown since when the server is in it.
+    ///
+    /// Every change is classified by whether it has been caused by home-monitor-rs (or its API) or
+    /// externally (e.g. by manually pressing the power button) and recorded with the server.
+    fn update_online_state(&mut self) -> bool {
+        let is_online = self.server.read().unwrap().is_online();
+        let previous_state = self.online_state.replace(is_online);
+        match previous_state {
+            Some(online_state) if online_state != is_online => {
+                self.online_state_since = Some(self.clock.now());
+
+                let cause = self.control.expected_transition.classify(is_online);
+                let transition = Transition::new(is_online, cause);
+                info!("{}: {}", self.server(), transition);
+                if let Device::Server(server) = &mut *self.server.write().unwrap() {
+                    server.add_transition(transition.clone());
+                }
+                self.transition = Some(transition);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns the change of the online state which hasn't been reported yet.
+    pub fn take_transition(&mut self) -> Option<Transition> {
+        self.transition.take()
+    }
+
+    /// Whether the server hasn't been in its current online state for its minimum on / off
+    /// duration yet.
+    fn is_within_min_duration(&self, server: &Device) -> bool {
+        let (server, online_state_since) = match (server, self.online_state_since) {
+            (Device::Server(server), Some(online_state_since)) => (server, online_state_since),
+            _ => return false,
+        };
+
+        let (min_duration, state) = if server.machine.is_online {
+            (server.min_on_duration, "online")
+        } else {
+            (server.min_off_duration, "offline")
+        };
+        match min_duration {
+            Some(min_duration) if self.clock.elapsed(online_state_since) < min_duration => {
+                debug!(
+                    "{}: keeping the server {} for at least {}s",
+                    server,
+                    state,
+                    min_duration.as_secs()
+                );
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Adds the time the server has been online since the last update to the on-time of the given
+    /// day (if the server has a daily budget).
+    pub fn update_on_time(&mut self, today: NaiveDate) -> bool {
+        let now = self.clock.now();
+        let elapsed = now.saturating_duration_since(self.on_time_updated);
+        self.on_time_updated = now;
+        if today != self.on_time_date {
+            self.on_time_date = today;
+            self.on_time = Duration::ZERO;
+        }
+
+        match &mut *self.server.write().unwrap() {
+            Device::Server(server) if server.max_daily_on_time.is_some() => {
+                if server.machine.is_online {
+                    self.on_time += elapsed;
+                }
+
+                let was_exhausted = server.is_budget_exhausted();
+                let changed = server.set_on_time_today(self.on_time);
+                if !was_exhausted && server.is_budget_exhausted() {
+                    info!(
+                        "{}: daily on-time budget is exhausted, no longer waking it up automatically",
+                        server
+                    );
+                }
+
+                changed
+            }
+            _ => false,
+        }
+    }
+
+    /// Records the online dependencies as the trigger of the current decision about the server.
+    fn record_triggers(&self) -> bool {
+        let online_devices: Vec<DeviceId> = self
+            .devices
+            .iter()
+            .map(|device| device.read().unwrap())
+            .filter(|device| device.is_online())
+            .map(|device| device.id().clone())
+            .collect();
+        if online_devices.is_empty() {
+            return false;
+        }
+
+        let now = self.clock.utc_now();
+        match &mut *self.server.write().unwrap() {
+            Device::Server(server) => {
+                let mut changed = false;
+                for device_id in online_devices.iter() {
+                    changed |= server.record_trigger(device_id, now);
+                }
+
+                changed
+            }
+            Device::Machine(_) => false,
+        }
+    }
+
+    /// Reserves the wakeup in the current batch of staggered wakeups (deferred if it is full).
+    fn defer_wakeup(&mut self, changed: &mut bool) -> bool {
+        let stagger = match &self.wakeup_stagger {
+            Some(stagger) => stagger.clone(),
+            None => return false,
+        };
+
+        let mut server = self.server.write().unwrap();
+        let server = match &mut *server {
+            Device::Server(server) => server,
+            Device::Machine(_) => return false,
+        };
+
+        match stagger.try_acquire() {
+            Ok(position) => {
+                info!(
+                    "{}: staggered wakeup {} of at most {} every {}s",
+                    server,
+                    position,
+                    stagger.max_parallel(),
+                    stagger.delay().as_secs()
+                );
+                *changed |= server.wakeup_deferred_until.take().is_some();
+                false
+            }
+            Err(remaining) => {
+                let now = self.clock.utc_now();
+                if server.remaining_wakeup_deferral(now).is_zero() {
+                    info!(
+                        "{}: deferring wakeup by {}s to stagger it with other wakeups",
+                        server,
+                        remaining.as_secs()
+                    );
+                    server.wakeup_deferred_until =
+                        Some(now + chrono::Duration::from_std(remaining).unwrap());
+                    *changed = true;
+                } else {
+                    debug!("{}: wakeup is still deferred", server);
+                }
+                true
+            }
+        }
+    }
+
+    /// Checks the blackouts and external dependencies before an automatic shutdown and defers the
+    /// shutdown as long as any of them is active. An explicit ALWAYS OFF is never deferred.
+    async fn defer_shutdown(&mut self, changed: &mut bool) -> bool {
+        if self.always_off_state {
+            return false;
+        }
+
+        let now = self.clock.local_now();
+        let active_blackouts: Vec<String> = self
+            .blackouts
+            .iter()
+            .filter(|blackout| blackout.is_active(&now))
+            .map(|blackout| blackout.to_string())
+            .collect();
+        if !active_blackouts.is_empty() {
+            debug!(
+                "{}: not shutting down because of active blackouts: {}",
+                self.server(),
+                active_blackouts.join(", ")
+            );
+            return true;
+        }
+
+        if self.external_checkers.is_empty() {
+            return false;
+        }
+
+        *changed |= self.update_external_dependencies().await;
+
+        let active_dependencies: Vec<String> = match &*self.server.read().unwrap() {
+            Device::Server(server) => server
+                .external_dependencies
+                .iter()
+                .filter(|dependency| dependency.is_active)
+                .map(|dependency| dependency.name.clone())
+                .collect(),
+            Device::Machine(_) => Vec::new(),
+        };
+        if active_dependencies.is_empty() {
+            return false;
+        }
+
+        debug!(
+            "{}: not shutting down because of active external dependencies: {}",
+            self.server(),
+            active_dependencies.join(", ")
+        );
+        true
+    }
+
+    /// Checks all external dependencies whose cached result has expired.
+    pub async fn update_external_dependencies(&mut self) -> bool {
+        let server = match &*self.server.read().unwrap() {
+            Device::Server(server) => server.clone(),
+            Device::Machine(_) => return false,
+        };
+
+        let expired: Vec<usize> = server
+            .external_dependencies
+            .iter()
+            .zip(self.external_checks.iter())
+            .enumerate()
+            .filter(|(_, (dependency, checked))| match checked {
+                Some(checked) => self.clock.elapsed(*checked) >= dependency.cache,
+                None => true,
+            })
+            .map(|(index, _)| index)
+            .collect();
+        if expired.is_empty() {
+            return false;
+        }
+
+        let external_checkers: Vec<Arc<dyn PortChecker>> = expired
+            .iter()
+            .map(|index| self.external_checkers[*index].clone())
+            .collect();
+        debug!(
+            "checking {} external dependencies of {}...",
+            external_checkers.len(),
+            server
+        );
+        let dependencies_active =
+            match tokio::task::spawn_blocking(move || check_all(&external_checkers)).await {
+                Ok(dependencies_active) => dependencies_active,
+                Err(e) => {
+                    error!("failed to check external dependencies of {}: {}", server, e);
+                    return false;
+                }
+            };
+
+        let now = self.clock.now();
+        for index in expired.iter() {
+            self.external_checks[*index] = Some(now);
+        }
+
+        match &mut *self.server.write().unwrap() {
+            Device::Server(server) => {
+                let mut changed = false;
+                for (index, is_active) in expired.into_iter().zip(dependencies_active) {
+                    let dependency = &mut server.external_dependencies[index];
+                    if dependency.is_active != is_active {
+                        info!(
+                            "{}: external dependency {} is {}",
+                            server.machine,
+                            dependency.name,
+                            if is_active { "active" } else { "inactive" }
+                        );
+                        dependency.is_active = is_active;
+                        changed = true;
+                    }
+                }
+
+                changed
+            }
+            Device::Machine(_) => false,
+        }
+    }
+
+    /// Starts the timeout after a wakeup / shutdown during which no further action is run.
+    fn record_change(&mut self) {
+        self.last_change = self.clock.now();
+        if let Device::Server(server) = &mut *self.server.write().unwrap() {
+            server.change_timeout_until =
+                Some(self.clock.utc_now() + chrono::Duration::from_std(CHANGE_TIMEOUT).unwrap());
+        }
+    }
+
+    /// Ends the timeout after the last wakeup / shutdown so that the server is processed right
+    /// away and returns whether the timeout was still running.
+    pub fn reset_change_timeout(&mut self) -> bool {
+        let was_running = self.clock.elapsed(self.last_change) <= CHANGE_TIMEOUT;
+        self.last_change = self.clock.now().sub(CHANGE_TIMEOUT);
+        if let Device::Server(server) = &mut *self.server.write().unwrap() {
+            server.change_timeout_until = None;
+        }
+
+        was_running
+    }
+
+    /// Takes note of a wakeup of the server by another tool so that the server coming online isn't
+    /// considered unexpected and isn't shut down again right away.
+    pub fn record_external_wakeup(&mut self) {
+        self.record_change();
+        self.shut_down = false;
+        self.offline_since = None;
+
+        if let Device::Server(server) = &mut *self.server.write().unwrap() {
+            server.add_external_wakeup();
+            info!(
+                "{} has been woken up externally ({} times)",
+                server, server.external_wakeups
+            );
+        }
+    }
+
+    /// Verifies that the server stopped answering pings within its shutdown verification timeout
+    /// after it has been shut down. A server which is still online is considered a failed
+    /// shutdown (and shut down again following the usual rules).
+    pub fn verify_shutdown(&mut self) -> bool {
+        let shut_down = match self.unverified_shutdown {
+            Some(shut_down) => shut_down,
+            None => return false,
+        };
+
+        let timeout = {
+            let server = self.server.read().unwrap();
+            let server = match &*server {
+                Device::Server(server) => server,
+                Device::Machine(_) => return false,
+            };
+            let timeout = match server.shutdown_verification {
+                Some(timeout) => timeout,
+                None => {
+                    self.unverified_shutdown = None;
+                    return false;
+                }
+            };
+
+            if !server.machine.is_online || server.machine.consecutive_missed_pings > 0 {
+                debug!("{}: verified that the server has been shut down", server);
+                self.unverified_shutdown = None;
+                self.consecutive_failed_shutdowns = 0;
+                return false;
+            }
+
+            timeout
+        };
+        if self.clock.elapsed(shut_down) <= timeout {
+            return false;
+        }
+
+        warn!(
+            "{} is still online {}s after it has been shut down",
+            self.server(),
+            timeout.as_secs()
+        );
+        self.unverified_shutdown = None;
+        self.shut_down = false;
+        self.record_failed_shutdown();
+        self.update_action_result(false);
+        true
+    }
+
+    /// Counts a failed shutdown and disables the control of the server once it failed to shut
+    /// down too many times in a row (e.g. because its SSH credentials changed).
+    fn record_failed_shutdown(&mut self) {
+        self.consecutive_failed_shutdowns += 1;
+
+        let mut device = self.server.write().unwrap();
+        let server = match &mut *device {
+            Device::Server(server) => server,
+            Device::Machine(_) => return,
+        };
+        match server.max_failed_shutdowns {
+            Some(max_failed_shutdowns)
+                if self.consecutive_failed_shutdowns >= max_failed_shutdowns
+                    && !server.is_control_disabled =>
             {
-                info!("shutting down {}...", server);
-                match self.control.shutdown.shutdown() {
-                    Err(e) => error!("failed to shut down {}: {}", server, e),
-                    Ok(_) => {
-                        self.last_change = Instant::now();
+                error!(
+                    "ControlDisabled: {} failed to shut down {} times in a row, only monitoring it until its control is re-enabled",
+                    server, self.consecutive_failed_shutdowns
+                );
+                self.control.control_switch.disable();
+                server.is_control_disabled = true;
+                self.control_disabled = Some(self.consecutive_failed_shutdowns);
+            }
+            _ => {}
+        }
+    }
+
+    /// Takes over the control state of the server (e.g. re-enabled through the web API) and
+    /// returns whether it changed.
+    fn update_control_switch(&mut self) -> bool {
+        let is_disabled = self.control.control_switch.is_disabled();
+        match &mut *self.server.write().unwrap() {
+            Device::Server(server) if server.is_control_disabled != is_disabled => {
+                if is_disabled {
+                    warn!("{}: control has been disabled", server);
+                } else {
+                    info!("{}: control has been re-enabled", server);
+                    self.consecutive_failed_shutdowns = 0;
+                }
+                server.is_control_disabled = is_disabled;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Takes note of the server having rejected its credentials (during a shutdown by the monitor
+    /// or through the web API) and returns whether that's new.
+    fn update_credentials(&mut self) -> bool {
+        if !self.control.invalid_credentials.are_invalid() {
+            return false;
+        }
+
+        match &mut *self.server.write().unwrap() {
+            Device::Server(server) if !server.has_invalid_credentials => {
+                server.has_invalid_credentials = true;
+                self.credentials_rejected = true;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns whether the server has just rejected its credentials.
+    pub fn take_credentials_rejected(&mut self) -> bool {
+        std::mem::take(&mut self.credentials_rejected)
+    }
+
+    /// Returns the number of failed shutdowns if the control of the server has just been disabled.
+    pub fn take_control_disabled(&mut self) -> Option<u32> {
+        self.control_disabled.take()
+    }
+
+    /// Detects if the server came back online even though it has been shut down or should be
+    /// kept off without it being woken up.
+    /// Returns how long the server has been offline if it came back online unexpectedly.
+    pub fn detect_unexpected_online(&mut self) -> Option<Duration> {
+        // while away the servers which aren't kept on are expected to stay offline as well
+        let away_off = self.away && !self.server().keep_on_when_away;
+        let expected_offline =
+            (self.shut_down || self.always_off_state || away_off) && !self.always_on_state;
+        if !expected_offline {
+            self.offline_since = None;
+            return None;
+        }
+
+        match &mut *self.server.write().unwrap() {
+            Device::Server(server) => {
+                if !server.machine.is_online {
+                    // only consider the server offline once a ping has actually been missed
+                    if server.machine.consecutive_missed_pings > 0 && self.offline_since.is_none() {
+                        self.offline_since = Some(self.clock.now());
+                    }
+                    None
+                } else if let Some(offline_since) = self.offline_since.take() {
+                    server.add_unexpected_online();
+                    warn!(
+                        "UnexpectedOnline: {} came back online without being woken up ({} times)",
+                        server, server.unexpected_onlines
+                    );
+                    Some(self.clock.elapsed(offline_since))
+                } else {
+                    None
+                }
+            }
+            Device::Machine(_) => None,
+        }
+    }
+
+    pub async fn update_services(&self) -> bool {
+        let server = match &*self.server.read().unwrap() {
+            Device::Server(server) if !server.services.is_empty() => server.clone(),
+            _ => return false,
+        };
+
+        // only check the services if the server itself is online
+        let services_online = if server.machine.is_online {
+            let service_checkers = self.service_checkers.clone();
+            debug!(
+                "checking {} services of {}...",
+                service_checkers.len(),
+                server
+            );
+            match tokio::task::spawn_blocking(move || check_all(&service_checkers)).await {
+                Ok(services_online) => services_online,
+                Err(e) => {
+                    error!("failed to check services of {}: {}", server, e);
+                    return false;
+                }
+            }
+        } else {
+            vec![false; server.services.len()]
+        };
+
+        match &mut *self.server.write().unwrap() {
+            Device::Server(server) => {
+                let mut changed = false;
+                for (service, is_online) in server.services.iter_mut().zip(services_online) {
+                    if service.is_online != is_online {
+                        info!(
+                            "{}: service {} ({}) is {}",
+                            server.machine,
+                            service.name,
+                            service.check,
+                            if is_online { "online" } else { "offline" }
+                        );
+                        service.is_online = is_online;
+                        changed = true;
                     }
                 }
+
+                changed
+            }
+            Device::Machine(_) => false,
+        }
+    }
+
+    fn create_action_context(cancellation: &CancellationToken) -> ActionContext {
+        ActionContext::new(
+            TraceContext::new(),
+            cancellation.child_token(),
+            ACTION_TIMEOUT,
+        )
+    }
+
+    fn update_action_result(&mut self, succeeded: bool) -> bool {
+        match &mut *self.server.write().unwrap() {
+            Device::Server(server) => {
+                let previous_failed_actions = server.consecutive_failed_actions;
+                server.set_action_result(succeeded);
+                if server.consecutive_failed_actions > 1 {
+                    warn!(
+                        "{} failed {} consecutive actions",
+                        server, server.consecutive_failed_actions
+                    );
+                }
+
+                server.consecutive_failed_actions != previous_failed_actions
             }
+            Device::Machine(_) => false,
         }
     }
 
     fn update_files_api(&mut self) {
-        // check the always off file
-        let always_off_file_exists = self.control.always_off.is_always_off();
-        // check the always on file
-        let always_on_file_exists = self.control.always_on.is_always_on();
+        // check the always off file of the server and its groups
+        let always_off_file_exists = self.control.always_off.is_always_off()
+            || self
+                .control
+                .groups
+                .iter()
+                .any(|group| group.always_off.is_always_off());
+        // check the always on file of the server and its groups
+        let always_on_file_exists = self.control.always_on.is_always_on()
+            || self
+                .control
+                .groups
+                .iter()
+                .any(|group| group.always_on.is_always_on());
 
         // make sure we don't have always off and on simultaneously
         if always_off_file_exists && always_on_file_exists {
@@ -127,16 +889,50 @@ impl MonitoredServer {
     }
 }
 
+struct MonitoredGuest {
+    pub id: DeviceId,
+    pub registered: Instant,
+    pub ttl: Duration,
+}
+
 pub struct Monitor {
     sender: Box<dyn communication::Sender>,
+    updates: communication::UpdateCoalescer,
 
     servers: Vec<MonitoredServer>,
     devices: Vec<SharedDevice>,
+    device_checkers: HashMap<DeviceId, Arc<dyn PortChecker>>,
+    last_checks: HashMap<DeviceId, Instant>,
+    due_devices: HashSet<DeviceId>,
+    online_ips: HashSet<IpAddr>,
+
+    guest_receiver: Option<communication::GuestReceiver>,
+    guests: Vec<MonitoredGuest>,
+
+    external_wakeup_receiver: Option<communication::ExternalWakeupReceiver>,
+    change_timeout_reset_receiver: Option<communication::ChangeTimeoutResetReceiver>,
+
+    cancellation: CancellationToken,
+    readiness: Option<Arc<communication::Readiness>>,
+    stats: Option<Arc<communication::MonitorStats>>,
+    notifier: Option<Arc<dyn Notifier>>,
+    standby: Option<Arc<communication::Standby>>,
+    ignore_windows: Option<Arc<communication::IgnoreWindows>>,
+    profiles: Option<Arc<communication::Profiles>>,
+    away: Option<Arc<communication::Away>>,
+    recovery: Option<RecoveryDetector>,
+    recovery_servers: VecDeque<DeviceId>,
+    arp_responders: Option<Arc<ArpResponders>>,
+    metrics: Option<Arc<Registry>>,
 
     last_ping: Instant,
     ping_interval: Duration,
 
+    started: Instant,
+    startup_grace_period: Duration,
+
     pinger: Box<dyn Pinger>,
+    clock: Arc<dyn Clock>,
 }
 
 impl Monitor {
@@ -147,6 +943,27 @@ impl Monitor {
         machines: Vec<Machine>,
         dependencies: Dependencies,
         pinger: Box<dyn Pinger>,
+    ) -> Self {
+        Self::with_clock(
+            sender,
+            ping_interval,
+            server_controls,
+            machines,
+            dependencies,
+            pinger,
+            Arc::new(SystemClock),
+        )
+    }
+
+    /// Creates a monitor which measures all timeouts and intervals with the given clock.
+    pub fn with_clock(
+        sender: Box<dyn communication::Sender>,
+        ping_interval: Duration,
+        server_controls: Vec<ServerControl>,
+        machines: Vec<Machine>,
+        dependencies: Dependencies,
+        pinger: Box<dyn Pinger>,
+        clock: Arc<dyn Clock>,
     ) -> Self {
         assert!(!machines.is_empty(), "no machines to monitor");
 
@@ -168,6 +985,26 @@ impl Monitor {
             )
         }));
 
+        // create the HTTP / TCP checkers of all devices with a configured HTTP check or port
+        let device_checkers = monitored_devices
+            .iter()
+            .filter_map(|(device_id, device)| {
+                let machine = match &*device.read().unwrap() {
+                    Device::Server(server) => server.machine.clone(),
+                    Device::Machine(machine) => machine.clone(),
+                };
+                let checker: Arc<dyn PortChecker> = match (&machine.http, machine.port) {
+                    (Some(http), _) => Arc::new(HttpChecker::new(http)),
+                    (None, Some(port)) => Arc::new(
+                        TcpPortChecker::new(machine.ip, port, SERVICE_CHECK_TIMEOUT)
+                            .with_source(machine.source),
+                    ),
+                    (None, None) => return None,
+                };
+                Some((device_id.clone(), checker))
+            })
+            .collect();
+
         // get a mutable binding to pinger
         let mut mut_pinger = pinger;
 
@@ -186,11 +1023,13 @@ impl Monitor {
         }
 
         // send the initial state of all devices
+        let mut updates = communication::UpdateCoalescer::new();
         for (_, device) in monitored_devices.iter() {
-            Self::publish_device_update(&*sender, device.read().unwrap().clone());
+            updates.push(device.read().unwrap().clone());
         }
+        updates.flush(&*sender);
 
-        let now = Instant::now();
+        let now = clock.now();
         let last_ping = now.sub(ping_interval);
         let last_change = now.sub(CHANGE_TIMEOUT);
 
@@ -211,119 +1050,803 @@ impl Monitor {
                 .map(|device_id| monitored_devices.get(device_id).unwrap().clone())
                 .collect();
 
-            servers.push(MonitoredServer::new(control, server, devices, last_change));
+            servers.push(MonitoredServer::new(
+                control,
+                server,
+                devices,
+                last_change,
+                clock.clone(),
+            ));
         }
 
         Self {
             sender,
+            updates,
             servers,
             devices: monitored_devices.into_values().collect(),
+            device_checkers,
+            last_checks: HashMap::new(),
+            due_devices: HashSet::new(),
+            online_ips: HashSet::new(),
+            guest_receiver: None,
+            guests: Vec::new(),
+            external_wakeup_receiver: None,
+            change_timeout_reset_receiver: None,
+            cancellation: CancellationToken::new(),
+            readiness: None,
+            stats: None,
+            notifier: None,
+            standby: None,
+            ignore_windows: None,
+            profiles: None,
+            away: None,
+            recovery: None,
+            recovery_servers: VecDeque::new(),
+            arp_responders: None,
+            metrics: None,
             last_ping,
             ping_interval,
+            started: now,
+            startup_grace_period: Duration::ZERO,
             pinger: mut_pinger,
+            clock,
         }
     }
 
-    pub fn run_once(&mut self) {
-        // check if the devices are online
-        if self.last_ping.elapsed() > self.ping_interval {
-            self.last_ping = Instant::now();
+    pub fn set_cancellation_token(&mut self, cancellation: CancellationToken) {
+        self.cancellation = cancellation;
+    }
 
-            // determine the number of machines (+ server)
-            let num_devices = self.devices.len();
+    pub fn set_readiness(&mut self, readiness: Arc<communication::Readiness>) {
+        self.readiness = Some(readiness);
+    }
 
-            // run the pinger once
-            debug!("pinging {} devices...", num_devices);
-            self.pinger.ping_once();
-            // and receive all responses (pongs)
-            if let Err(e) = self.pinger.recv_pong() {
-                panic!("Pinger failed to receive responses: {}", e)
-            }
+    pub fn set_stats(&mut self, stats: Arc<communication::MonitorStats>) {
+        self.stats = Some(stats);
+    }
 
-            // update the online state of all devices
-            for device in self.devices.iter_mut() {
-                trace!("updating online state of {}...", device.read().unwrap());
-                let is_device_online = self.pinger.is_online(device.read().unwrap().ip());
-                if Self::update_device_online(&mut device.write().unwrap(), is_device_online) {
-                    Self::publish_device_update(&*self.sender, device.read().unwrap().clone());
-                }
-            }
-        }
+    /// Records which devices are only found online through ARP requests.
+    pub fn set_arp_responders(&mut self, arp_responders: Arc<ArpResponders>) {
+        self.arp_responders = Some(arp_responders);
+    }
 
-        // go through all controlled servers
-        for server in self.servers.iter_mut() {
-            server.process();
-        }
+    pub fn set_metrics(&mut self, metrics: Arc<Registry>) {
+        self.metrics = Some(metrics);
     }
 
-    fn update_device_online(device: &mut Device, is_online: bool) -> bool {
-        let device_was_online = device.is_online();
+    pub fn set_notifier(&mut self, notifier: Arc<dyn Notifier>) {
+        self.notifier = Some(notifier);
+    }
 
-        // update the machines online state
-        //   either if it is currently online
-        //   or if it has become offline
-        if is_online {
-            trace!("received ping response from {}", device);
-            device.set_online(true)
-        } else {
-            trace!("no ping response received from {}", device);
+    /// Only lets the monitor control the servers once the active instance stopped responding.
+    pub fn set_standby(&mut self, standby: Arc<communication::Standby>) {
+        self.standby = Some(standby);
+    }
 
-            if device_was_online
-                && device.last_seen().unwrap().elapsed()
-                    > Duration::from_secs(device.last_seen_timeout())
-            {
-                device.set_online(false)
+    pub fn set_startup_grace_period(&mut self, startup_grace_period: Duration) {
+        self.startup_grace_period = startup_grace_period;
+    }
+
+    /// Sets the schedules (pseudo devices) the controlled servers depend on in addition to
+    /// their devices.
+    pub fn set_schedules(&mut self, schedules: HashMap<DeviceId, Vec<Schedule>>) {
+        for server in self.servers.iter_mut() {
+            if let Some(server_schedules) = schedules.get(&server.server().machine.id) {
+                server.schedules = server_schedules.clone();
             }
         }
+    }
 
-        let device_is_online = device.is_online();
-        if device_is_online != device_was_online {
-            if device_is_online {
-                info!("{} is now online", device);
-            } else {
-                info!("{} is now offline", device);
-            }
+    /// Sets the windows during which devices being offline is ignored.
+    pub fn set_ignore_windows(&mut self, ignore_windows: Arc<communication::IgnoreWindows>) {
+        self.ignore_windows = Some(ignore_windows);
+    }
 
-            return true;
-        }
+    /// Applies the profile switched through the web API (or on startup).
+    pub fn set_profiles(&mut self, profiles: Arc<communication::Profiles>) {
+        self.profiles = Some(profiles);
+    }
 
-        false
+    /// Keeps only the servers with `keep_on_when_away` online while the away mode is active.
+    pub fn set_away(&mut self, away: Arc<communication::Away>) {
+        self.away = Some(away);
     }
 
-    fn publish_device_update(sender: &dyn communication::Sender, device: Device) {
-        debug!("publishing update for {}", device);
-        if let Err(e) = sender.send(device.clone()) {
-            warn!("failed to publish update for {}: {}", device, e);
-        }
+    /// Detects probable power outages and applies the recovery policy once the devices reappear.
+    pub fn set_recovery(&mut self, recovery: configuration::Recovery) {
+        self.recovery = Some(RecoveryDetector::new(recovery, self.clock.clone()));
     }
-}
+
+    /// Staggers the wakeups of the controlled servers to avoid a simultaneous power inrush.
+    pub fn set_wakeup_stagger(&mut self, wakeup_stagger: Arc<WakeupStagger>) {
+        for server in self.servers.iter_mut() {
+            server.wakeup_stagger = Some(wakeup_stagger.clone());
+        }
+    }
+
+    /// Sets the schedules (e.g. calendars) during which the controlled servers are never shut down
+    /// automatically.
+    pub fn set_blackouts(&mut self, blackouts: HashMap<DeviceId, Vec<Schedule>>) {
+        for server in self.servers.iter_mut() {
+            if let Some(server_blackouts) = blackouts.get(&server.server().machine.id) {
+                server.blackouts = server_blackouts.clone();
+            }
+        }
+    }
+
+    pub fn set_guest_receiver(&mut self, guest_receiver: communication::GuestReceiver) {
+        self.guest_receiver = Some(guest_receiver);
+    }
+
+    pub fn set_external_wakeup_receiver(
+        &mut self,
+        external_wakeup_receiver: communication::ExternalWakeupReceiver,
+    ) {
+        self.external_wakeup_receiver = Some(external_wakeup_receiver);
+    }
+
+    pub fn set_change_timeout_reset_receiver(
+        &mut self,
+        change_timeout_reset_receiver: communication::ChangeTimeoutResetReceiver,
+    ) {
+        self.change_timeout_reset_receiver = Some(change_timeout_reset_receiver);
+    }
+
+    pub async fn run_once(&mut self) {
+        if let Some(readiness) = &self.readiness {
+            readiness.heartbeat();
+        }
+        let start = self.clock.now();
+
+        // add new and remove expired guest devices
+        self.process_guests();
+
+        // take note of servers woken up by other tools
+        self.process_external_wakeups();
+
+        // let servers be processed right away if requested through the web API
+        self.process_change_timeout_resets();
+
+        // replace the dependencies of all servers if another profile has been activated
+        self.process_profile_change();
+
+        // check if the devices are online
+        let since_last_ping = self.clock.elapsed(self.last_ping);
+        if since_last_ping > self.ping_interval {
+            if let Some(stats) = &self.stats {
+                stats.ping_cycle(self.ping_interval, since_last_ping);
+            }
+            self.last_ping = self.clock.now();
+            self.probe_devices(1).await;
+        }
+
+        // go through all controlled servers
+        self.process_servers().await;
+
+        // publish the resulting state of all changed devices at once
+        self.updates.flush(&*self.sender);
+
+        // a run taking longer than the ping interval delays the following pings
+        let duration = self.clock.elapsed(start);
+        let overrun = match &self.stats {
+            Some(stats) => stats.run(self.ping_interval, duration),
+            None => duration > self.ping_interval,
+        };
+        if overrun {
+            warn!(
+                "monitoring took {}ms which is longer than the ping interval of {}ms",
+                duration.as_millis(),
+                self.ping_interval.as_millis()
+            );
+        }
+    }
+
+    /// Probes all devices right away and processes all controlled servers once so that a
+    /// (re)started daemon doesn't have to wait for the ping interval and the change timeout
+    /// to reach correct decisions. The ALWAYS OFF / ON state is restored from the files API.
+    pub async fn reconcile(&mut self) {
+        info!("reconciling the state of all devices...");
+        self.last_ping = self.clock.now();
+
+        // ping all devices multiple times to not miss any device dropping a single ping
+        self.probe_devices(STARTUP_PING_ROUNDS).await;
+
+        self.process_servers().await;
+        self.updates.flush(&*self.sender);
+    }
+
+    async fn probe_devices(&mut self, ping_rounds: u32) {
+        // determine the number of machines (+ server)
+        let num_devices = self.devices.len();
+
+        // only check the devices with their own interval once it has elapsed
+        let now = self.clock.now();
+        // reuse the buffers of the previous cycle to avoid allocations
+        let mut due_devices = std::mem::take(&mut self.due_devices);
+        due_devices.clear();
+        for device in self.devices.iter() {
+            let device = device.read().unwrap();
+            let is_due = match (device.interval(), self.last_checks.get(device.id())) {
+                (Some(interval), Some(last_check)) => self.clock.elapsed(*last_check) >= interval,
+                _ => true,
+            };
+            if is_due {
+                due_devices.insert(device.id().clone());
+                if device.interval().is_some() {
+                    self.last_checks.insert(device.id().clone(), now);
+                }
+            }
+        }
+
+        // run the pinger (multiple times) and collect all devices which responded
+        let mut online_ips = std::mem::take(&mut self.online_ips);
+        online_ips.clear();
+        for _ in 0..ping_rounds {
+            debug!("pinging {} devices...", num_devices);
+            self.pinger.ping_once();
+            // and receive all responses (pongs)
+            if let Err(e) = self.pinger.recv_pong() {
+                panic!("Pinger failed to receive responses: {}", e)
+            }
+
+            for device in self.devices.iter() {
+                let device = device.read().unwrap();
+                // devices with an HTTP or TCP check don't rely on pings
+                if self.device_checkers.contains_key(device.id()) {
+                    continue;
+                }
+                if self.pinger.is_online(device.ip()) {
+                    online_ips.insert(*device.ip());
+                }
+            }
+        }
+        if let Some(readiness) = &self.readiness {
+            readiness.set_pinger_running();
+        }
+
+        // run the HTTP / TCP checks of all due devices which have one
+        let device_checkers = self
+            .device_checkers
+            .iter()
+            .filter(|(device_id, _)| due_devices.contains(*device_id))
+            .map(|(device_id, checker)| (device_id.clone(), checker.clone()))
+            .collect();
+        let http_checks = Self::check_devices(&device_checkers).await;
+
+        // update the online state of all due devices
+        let now = self.clock.local_now();
+        for device in self.devices.iter() {
+            let mut device = device.write().unwrap();
+            if !due_devices.contains(device.id()) {
+                continue;
+            }
+
+            trace!("updating online state of {}...", device);
+            let is_device_online = match http_checks.get(device.id()) {
+                Some(is_online) => *is_online,
+                None => online_ips.contains(device.ip()),
+            };
+            let is_ignored = self
+                .ignore_windows
+                .as_ref()
+                .is_some_and(|ignore_windows| ignore_windows.is_active(device.id(), &now));
+            let mut changed = device.is_ignored() != is_ignored;
+            device.set_ignored(is_ignored);
+            // a device being offline during its ignore window neither counts as a missed ping nor
+            // as going offline
+            if is_ignored && !is_device_online {
+                trace!("ignoring missing ping response from {}", device);
+            } else {
+                changed |= Self::update_device_online(&mut device, is_device_online, &*self.clock);
+            }
+            if is_device_online && !http_checks.contains_key(device.id()) {
+                changed |= self.update_probe_method(&mut device);
+            }
+            if changed {
+                self.updates.push(device.clone());
+            }
+        }
+        self.due_devices = due_devices;
+        self.online_ips = online_ips;
+
+        // update the state of the services of all controlled servers
+        for server in self.servers.iter() {
+            if server.update_services().await {
+                self.updates.push(server.server.read().unwrap().clone());
+            }
+        }
+    }
+
+    async fn process_servers(&mut self) {
+        // only observe the network during the startup grace period
+        if self.clock.elapsed(self.started) < self.startup_grace_period {
+            trace!("not processing any servers during the startup grace period");
+            return;
+        }
+
+        // only observe the network while the active instance controls the servers
+        if let Some(standby) = &self.standby {
+            if !standby.is_active() {
+                trace!("not processing any servers while the active instance is responding");
+                return;
+            }
+        }
+
+        self.update_recovery();
+
+        let away = self.away.as_ref().is_some_and(|away| away.is_active());
+        for server in self.servers.iter_mut() {
+            server.away = away;
+
+            let failed_shutdown = server.verify_shutdown();
+            if failed_shutdown {
+                Self::notify_failed_shutdown(&self.notifier, server.server()).await;
+            }
+
+            let offline_duration = server.detect_unexpected_online();
+            if let Some(offline_duration) = offline_duration {
+                Self::notify_unexpected_online(&self.notifier, server.server(), offline_duration)
+                    .await;
+            }
+            let unexpected_online = offline_duration.is_some();
+
+            let changed = server.process(&self.cancellation).await;
+            if server.take_credentials_rejected() {
+                Self::notify_authentication_failed(&self.notifier, server.server()).await;
+            }
+            if let Some(failed_shutdowns) = server.take_control_disabled() {
+                Self::notify_control_disabled(&self.notifier, server.server(), failed_shutdowns)
+                    .await;
+            }
+            if let Some(transition) = server.take_transition() {
+                Self::count_transition(&self.metrics, server.server(), &transition);
+                // an unexpected wakeup has already been reported
+                if transition.cause == TransitionCause::External && !unexpected_online {
+                    Self::notify_power_changed(&self.notifier, server.server(), &transition).await;
+                }
+            }
+
+            if changed || unexpected_online || failed_shutdown {
+                self.updates.push(server.server.read().unwrap().clone());
+            }
+        }
+    }
+
+    /// Detects the recovery from a probable power outage and decides which core server to wake up
+    /// next.
+    fn update_recovery(&mut self) {
+        let recovery = match &mut self.recovery {
+            Some(recovery) => recovery,
+            None => return,
+        };
+
+        let online = self
+            .devices
+            .iter()
+            .filter(|device| device.read().unwrap().is_online())
+            .count();
+        if recovery.update(online, self.devices.len()) {
+            self.recovery_servers = recovery.servers().iter().map(DeviceId::from).collect();
+        }
+
+        let recovering = recovery.is_recovering();
+        if !recovering && !self.recovery_servers.is_empty() {
+            warn!(
+                "recovery ended before waking up {}",
+                self.recovery_servers
+                    .iter()
+                    .map(|server_id| server_id.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            );
+            self.recovery_servers.clear();
+        }
+
+        // wake up the core servers one after another once the previous one is online
+        while let Some(server_id) = self.recovery_servers.front() {
+            let is_online = self
+                .servers
+                .iter()
+                .find(|server| &server.server().machine.id == server_id)
+                .is_none_or(|server| server.server.read().unwrap().is_online());
+            if !is_online {
+                break;
+            }
+
+            info!("core server {} is online after the recovery", server_id);
+            self.recovery_servers.pop_front();
+        }
+
+        for server in self.servers.iter_mut() {
+            server.recovering = recovering;
+            server.recovery_wakeup =
+                self.recovery_servers.front() == Some(&server.server().machine.id);
+        }
+    }
+
+    fn count_transition(metrics: &Option<Arc<Registry>>, server: &Server, transition: &Transition) {
+        if let Some(metrics) = metrics {
+            let state = if transition.is_online {
+                "online"
+            } else {
+                "offline"
+            };
+            metrics.increment(
+                Counter::PowerTransitions,
+                &[
+                    ("server", server.machine.id.as_str()),
+                    ("state", state),
+                    ("cause", &transition.cause.to_string()),
+                ],
+            );
+        }
+    }
+
+    async fn notify_power_changed(
+        notifier: &Option<Arc<dyn Notifier>>,
+        server: &Server,
+        transition: &Transition,
+    ) {
+        let state = if transition.is_online {
+            "online"
+        } else {
+            "offline"
+        };
+        let notification = Notification::new(
+            NotificationEvent::PowerChanged,
+            &[
+                ("name", server.machine.name.clone()),
+                ("id", server.machine.id.to_string()),
+                ("state", state.to_string()),
+                ("cause", transition.cause.to_string()),
+            ],
+        );
+        Self::notify(notifier, notification, "power state change").await;
+    }
+
+    async fn notify_unexpected_online(
+        notifier: &Option<Arc<dyn Notifier>>,
+        server: &Server,
+        offline_duration: Duration,
+    ) {
+        let notification = Notification::new(
+            NotificationEvent::UnexpectedOnline,
+            &[
+                ("name", server.machine.name.clone()),
+                ("id", server.machine.id.to_string()),
+                ("offlineDuration", format_duration(offline_duration)),
+            ],
+        );
+        Self::notify(notifier, notification, "unexpected online server").await;
+    }
+
+    async fn notify_failed_shutdown(notifier: &Option<Arc<dyn Notifier>>, server: &Server) {
+        let notification = Notification::new(
+            NotificationEvent::ShutdownFailed,
+            &[
+                ("name", server.machine.name.clone()),
+                ("id", server.machine.id.to_string()),
+            ],
+        );
+        Self::notify(notifier, notification, "failed shutdown").await;
+    }
+
+    async fn notify_authentication_failed(notifier: &Option<Arc<dyn Notifier>>, server: &Server) {
+        let notification = Notification::new(
+            NotificationEvent::AuthenticationFailed,
+            &[
+                ("name", server.machine.name.clone()),
+                ("id", server.machine.id.to_string()),
+                ("username", server.ssh.username.clone()),
+            ],
+        );
+        Self::notify(notifier, notification, "rejected credentials").await;
+    }
+
+    async fn notify_control_disabled(
+        notifier: &Option<Arc<dyn Notifier>>,
+        server: &Server,
+        failed_shutdowns: u32,
+    ) {
+        let notification = Notification::new(
+            NotificationEvent::ControlDisabled,
+            &[
+                ("name", server.machine.name.clone()),
+                ("id", server.machine.id.to_string()),
+                ("failedShutdowns", failed_shutdowns.to_string()),
+            ],
+        );
+        Self::notify(notifier, notification, "disabled control").await;
+    }
+
+    async fn notify(notifier: &Option<Arc<dyn Notifier>>, notification: Notification, about: &str) {
+        let notifier = match notifier {
+            Some(notifier) => notifier.clone(),
+            None => return,
+        };
+
+        match tokio::task::spawn_blocking(move || notifier.notify(&notification)).await {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => warn!("failed to notify about {}: {}", about, e),
+            Err(e) => error!("failed to notify about {}: {}", about, e),
+        }
+    }
+
+    async fn check_devices(
+        device_checkers: &HashMap<DeviceId, Arc<dyn PortChecker>>,
+    ) -> HashMap<DeviceId, bool> {
+        if device_checkers.is_empty() {
+            return HashMap::new();
+        }
+
+        let (device_ids, checkers): (Vec<DeviceId>, Vec<Arc<dyn PortChecker>>) = device_checkers
+            .iter()
+            .map(|(device_id, checker)| (device_id.clone(), checker.clone()))
+            .unzip();
+
+        debug!("checking {} devices over HTTP...", device_ids.len());
+        match tokio::task::spawn_blocking(move || check_all(&checkers)).await {
+            Ok(results) => device_ids.into_iter().zip(results).collect(),
+            Err(e) => {
+                error!("failed to check devices over HTTP: {}", e);
+                HashMap::new()
+            }
+        }
+    }
+
+    fn process_external_wakeups(&mut self) {
+        let external_wakeup_receiver = match self.external_wakeup_receiver.as_mut() {
+            Some(external_wakeup_receiver) => external_wakeup_receiver,
+            None => return,
+        };
+
+        while let Ok(server_id) = external_wakeup_receiver.try_recv() {
+            let server = self
+                .servers
+                .iter_mut()
+                .find(|server| server.server().machine.id == server_id);
+            if let Some(server) = server {
+                server.record_external_wakeup();
+                self.updates.push(server.server.read().unwrap().clone());
+            }
+        }
+    }
+
+    fn process_change_timeout_resets(&mut self) {
+        let change_timeout_reset_receiver = match self.change_timeout_reset_receiver.as_mut() {
+            Some(change_timeout_reset_receiver) => change_timeout_reset_receiver,
+            None => return,
+        };
+
+        while let Ok(server_id) = change_timeout_reset_receiver.try_recv() {
+            let server = self
+                .servers
+                .iter_mut()
+                .find(|server| server.server().machine.id == server_id);
+            if let Some(server) = server {
+                if server.reset_change_timeout() {
+                    info!("reset the change timeout of {}", server.server());
+                }
+                self.updates.push(server.server.read().unwrap().clone());
+            }
+        }
+    }
+
+    fn process_profile_change(&mut self) {
+        let profile = match self
+            .profiles
+            .as_ref()
+            .and_then(|profiles| profiles.take_change())
+        {
+            Some(profile) => profile,
+            None => return,
+        };
+        if let Some(profiles) = &self.profiles {
+            match profiles.active() {
+                Some(name) => info!("activating profile {}", name),
+                None => info!("activating the configured dependencies"),
+            }
+        }
+
+        let guests = &self.guests;
+        for server in self.servers.iter_mut() {
+            let server_id = server.server().machine.id.clone();
+            let deps = profile
+                .dependencies
+                .get(&server_id)
+                .cloned()
+                .unwrap_or_default();
+
+            // guests stay dependencies of the servers independent of the profile
+            let mut devices: Vec<SharedDevice> = self
+                .devices
+                .iter()
+                .filter(|device| deps.contains(device.read().unwrap().id()))
+                .cloned()
+                .collect();
+            devices.extend(
+                server
+                    .devices
+                    .iter()
+                    .filter(|device| {
+                        let device_id = device.read().unwrap().id().clone();
+                        guests.iter().any(|guest| guest.id == device_id)
+                    })
+                    .cloned(),
+            );
+            server.devices = devices;
+            server.schedules = profile
+                .schedules
+                .get(&server_id)
+                .cloned()
+                .unwrap_or_default();
+        }
+    }
+
+    fn process_guests(&mut self) {
+        // add all newly registered guest devices
+        let mut new_guests = Vec::new();
+        if let Some(guest_receiver) = self.guest_receiver.as_mut() {
+            while let Ok(guest) = guest_receiver.try_recv() {
+                new_guests.push(guest);
+            }
+        }
+        for guest in new_guests {
+            self.add_guest(guest);
+        }
+
+        // remove all guest devices whose TTL has expired
+        let expired_guests: Vec<DeviceId> = self
+            .guests
+            .iter()
+            .filter(|guest| self.clock.elapsed(guest.registered) > guest.ttl)
+            .map(|guest| guest.id.clone())
+            .collect();
+        for guest_id in expired_guests {
+            self.remove_guest(&guest_id);
+        }
+    }
+
+    fn add_guest(&mut self, guest: Guest) {
+        let guest_id = guest.machine.id.clone();
+        if self
+            .devices
+            .iter()
+            .any(|device| *device.read().unwrap().id() == guest_id)
+        {
+            warn!("ignoring guest {} because it already exists", guest_id);
+            return;
+        }
+
+        if !self.pinger.add_target(guest.machine.ip) {
+            warn!(
+                "ignoring guest {} because {} is already monitored",
+                guest_id, guest.machine.ip
+            );
+            return;
+        }
+
+        info!(
+            "adding guest {} for {}s",
+            guest.machine,
+            guest.ttl.as_secs()
+        );
+        let device: SharedDevice = Arc::new(RwLock::new(Device::Machine(guest.machine)));
+
+        // add the guest as a dependency of the given (or all) servers
+        for server in self.servers.iter_mut() {
+            if guest.servers.is_empty() || guest.servers.contains(&server.server().machine.id) {
+                server.devices.push(device.clone());
+            }
+        }
+
+        self.updates.push(device.read().unwrap().clone());
+        self.devices.push(device);
+        self.guests.push(MonitoredGuest {
+            id: guest_id,
+            registered: self.clock.now(),
+            ttl: guest.ttl,
+        });
+    }
+
+    fn remove_guest(&mut self, guest_id: &DeviceId) {
+        info!("removing expired guest {}", guest_id);
+
+        let is_guest = |device: &SharedDevice| *device.read().unwrap().id() == *guest_id;
+        if let Some(device) = self.devices.iter().find(|device| is_guest(device)) {
+            let ip = *device.read().unwrap().ip();
+            self.pinger.remove_target(&ip);
+        }
+
+        self.devices.retain(|device| !is_guest(device));
+        for server in self.servers.iter_mut() {
+            server.devices.retain(|device| !is_guest(device));
+        }
+        self.guests.retain(|guest| guest.id != *guest_id);
+
+        debug!("publishing removal of {}", guest_id);
+        self.updates.remove(guest_id);
+        if let Err(e) = self.sender.send_removal(guest_id.clone()) {
+            warn!("failed to publish removal of {}: {}", guest_id, e);
+        }
+    }
+
+    /// Records whether the device answered pings or only ARP requests.
+    fn update_probe_method(&self, device: &mut Device) -> bool {
+        let probe_method = match &self.arp_responders {
+            Some(arp_responders) if arp_responders.is_responding(device.ip()) => ProbeMethod::Arp,
+            _ => ProbeMethod::Ping,
+        };
+        if device.probe_method() == Some(probe_method) {
+            return false;
+        }
+
+        debug!("{} is online according to {} probes", device, probe_method);
+        device.set_probe_method(probe_method);
+        true
+    }
+
+    fn update_device_online(device: &mut Device, is_online: bool, clock: &dyn Clock) -> bool {
+        let device_was_online = device.is_online();
+
+        // update the machines online state
+        //   either if it is currently online
+        //   or if it has become offline
+        if is_online {
+            trace!("received ping response from {}", device);
+            device.set_online_at(true, clock.now(), clock.utc_now())
+        } else {
+            trace!("no ping response received from {}", device);
+            device.add_missed_ping();
+
+            if device_was_online
+                && clock.elapsed(device.last_seen().unwrap())
+                    > Duration::from_secs(device.last_seen_timeout())
+            {
+                device.set_online_at(false, clock.now(), clock.utc_now())
+            }
+        }
+
+        let device_is_online = device.is_online();
+        if device_is_online != device_was_online {
+            if device_is_online {
+                info!("{} is now online", device);
+            } else {
+                info!("{} is now offline", device);
+            }
+
+            return true;
+        }
+
+        // the missed pings alone are not worth an update until the offline threshold is crossed
+        false
+    }
+}
 
 #[cfg(test)]
 mod tests {
-    use std::convert::TryInto;
     use std::net::IpAddr;
-    use std::ops::Add;
     use std::sync::mpsc::RecvError;
 
+    use chrono::NaiveTime;
     use mockall::predicate::*;
     use mockall::Sequence;
     use rstest::*;
 
     use super::*;
     use crate::control::test::*;
+    use crate::dom::calendar::CalendarEvent;
     use crate::dom::device::test::*;
     use crate::dom::test::*;
+    use crate::dom::Calendar;
+    use crate::networking::{ActionReport, ShutdownError};
+    use crate::utils::ManualClock;
 
     static PING_INTERVAL: Duration = Duration::from_secs(1);
 
     #[fixture]
-    fn fake_clock() {
-        let mut max_duration: Duration = std::cmp::max(
-            CHANGE_TIMEOUT,
-            Duration::from_secs(MACHINE_LAST_SEEN_TIMEOUT),
-        );
-        max_duration = max_duration.add(Duration::from_secs(1));
-        Instant::set_time(max_duration.as_millis().try_into().unwrap());
+    fn clock() -> Arc<ManualClock> {
+        Arc::new(ManualClock::new(Instant::now()))
     }
 
     fn default_mocks() -> (
@@ -340,7 +1863,7 @@ mod tests {
     #[should_panic(expected = "no machines to monitor")]
     #[allow(unused_variables)]
     fn test_monitor_fails_without_machines(
-        fake_clock: (),
+        clock: Arc<ManualClock>,
         server_ip: IpAddr,
         mocked_server_control: MockServerControl,
         dependencies: Dependencies,
@@ -360,13 +1883,14 @@ mod tests {
 
         // TESTING
         #[allow(unused_variables)]
-        let monitor = Monitor::new(
+        let monitor = Monitor::with_clock(
             sender,
             PING_INTERVAL,
             servers,
             machines,
             dependencies,
             pinger,
+            clock.clone(),
         );
     }
 
@@ -374,7 +1898,7 @@ mod tests {
     #[should_panic(expected = "failed to add")]
     #[allow(unused_variables)]
     fn test_monitor_fails_on_duplicate_ips(
-        fake_clock: (),
+        clock: Arc<ManualClock>,
         server_ip: IpAddr,
         mocked_server_control: MockServerControl,
         machine_ip: IpAddr,
@@ -410,20 +1934,22 @@ mod tests {
 
         // TESTING
         #[allow(unused_variables)]
-        let monitor = Monitor::new(
+        let monitor = Monitor::with_clock(
             sender,
             PING_INTERVAL,
             servers,
             machines,
             dependencies,
             pinger,
+            clock.clone(),
         );
     }
 
     #[rstest]
+    #[tokio::test]
     #[allow(unused_variables)]
-    fn test_monitor_always_off_and_on_checked_in_run_once(
-        fake_clock: (),
+    async fn test_monitor_always_off_and_on_checked_in_run_once(
+        clock: Arc<ManualClock>,
         mut mocked_server_control: MockServerControl,
         machine: Machine,
         dependencies: Dependencies,
@@ -457,22 +1983,24 @@ mod tests {
         // TESTING
         let servers = vec![ServerControl::from(mocked_server_control)];
 
-        let mut monitor = Monitor::new(
+        let mut monitor = Monitor::with_clock(
             sender,
             PING_INTERVAL,
             servers,
             machines,
             dependencies,
             pinger,
+            clock.clone(),
         );
 
-        monitor.run_once();
+        monitor.run_once().await;
     }
 
     #[rstest]
+    #[tokio::test]
     #[allow(unused_variables)]
-    fn test_monitor_ignore_if_always_off_and_on(
-        fake_clock: (),
+    async fn test_monitor_ignore_if_always_off_and_on(
+        clock: Arc<ManualClock>,
         server_ip: IpAddr,
         mut mocked_server_control: MockServerControl,
         machine_ip: IpAddr,
@@ -505,22 +2033,24 @@ mod tests {
         // TESTING
         let servers = vec![ServerControl::from(mocked_server_control)];
 
-        let mut monitor = Monitor::new(
+        let mut monitor = Monitor::with_clock(
             sender,
             PING_INTERVAL,
             servers,
             machines,
             dependencies,
             pinger,
+            clock.clone(),
         );
 
-        monitor.run_once();
+        monitor.run_once().await;
     }
 
     #[rstest]
+    #[tokio::test]
     #[allow(unused_variables)]
-    fn test_monitor_shutdown_server_if_always_off(
-        fake_clock: (),
+    async fn test_monitor_shutdown_server_if_always_off(
+        clock: Arc<ManualClock>,
         server_ip: IpAddr,
         mut mocked_server_control: MockServerControl,
         machine_ip: IpAddr,
@@ -578,30 +2108,32 @@ mod tests {
             .shutdown
             .expect_shutdown()
             .once()
-            .return_once(|| Ok(()));
+            .return_once(|_| Ok(ActionReport::default()));
 
         // TESTING
         let servers = vec![ServerControl::from(mocked_server_control)];
 
-        let mut monitor = Monitor::new(
+        let mut monitor = Monitor::with_clock(
             sender,
             PING_INTERVAL,
             servers,
             machines,
             dependencies,
             pinger,
+            clock.clone(),
         );
 
         // advance FakeClock by at least ping interval (1s)
-        Instant::advance_time((2 * PING_INTERVAL).as_millis().try_into().unwrap());
+        clock.advance(2 * PING_INTERVAL);
 
-        monitor.run_once();
+        monitor.run_once().await;
     }
 
     #[rstest]
+    #[tokio::test]
     #[allow(unused_variables)]
-    fn test_monitor_wakeup_server_if_always_on(
-        fake_clock: (),
+    async fn test_monitor_wakeup_server_if_always_on(
+        clock: Arc<ManualClock>,
         server_ip: IpAddr,
         mut mocked_server_control: MockServerControl,
         machine_ip: IpAddr,
@@ -632,27 +2164,29 @@ mod tests {
             .wakeup
             .expect_wakeup()
             .once()
-            .return_once(|| Ok(()));
+            .return_once(|_| Ok(ActionReport::default()));
 
         // TESTING
         let servers = vec![ServerControl::from(mocked_server_control)];
 
-        let mut monitor = Monitor::new(
+        let mut monitor = Monitor::with_clock(
             sender,
             PING_INTERVAL,
             servers,
             machines,
             dependencies,
             pinger,
+            clock.clone(),
         );
 
-        monitor.run_once();
+        monitor.run_once().await;
     }
 
     #[rstest]
+    #[tokio::test]
     #[allow(unused_variables)]
-    fn test_monitor_ping_once_if_interval_elapsed(
-        fake_clock: (),
+    async fn test_monitor_shutdown_server_while_away(
+        clock: Arc<ManualClock>,
         server_ip: IpAddr,
         mut mocked_server_control: MockServerControl,
         machine_ip: IpAddr,
@@ -666,7 +2200,7 @@ mod tests {
 
         // EXPECTATIONS
         pinger.expect_add_target().returning(|_| true);
-        sender.expect_send().times(2).returning(|_| Ok(()));
+        sender.expect_send().returning(|_| Ok(()));
 
         mocked_server_control
             .always_off
@@ -679,56 +2213,52 @@ mod tests {
             .once()
             .return_once(|| false);
 
-        {
-            let mut seq = Sequence::new();
-            pinger
-                .expect_ping_once()
-                .once()
-                .return_once(|| {})
-                .in_sequence(&mut seq);
-            pinger
-                .expect_recv_pong()
-                .once()
-                .return_once(|| Ok(()))
-                .in_sequence(&mut seq);
-        }
+        pinger.expect_ping_once().once().return_once(|| {});
+        pinger.expect_recv_pong().once().return_once(|| Ok(()));
+        // the machine the server depends on is online as well
         pinger
             .expect_is_online()
             .with(eq(server_ip))
             .once()
-            .return_once(|_| false);
+            .return_once(|_| true);
         pinger
             .expect_is_online()
             .with(eq(machine_ip))
             .once()
-            .return_once(|_| false);
+            .return_once(|_| true);
+
+        mocked_server_control
+            .shutdown
+            .expect_shutdown()
+            .once()
+            .return_once(|_| Ok(ActionReport::default()));
 
         // TESTING
         let servers = vec![ServerControl::from(mocked_server_control)];
 
-        let mut monitor = Monitor::new(
+        let mut monitor = Monitor::with_clock(
             sender,
             PING_INTERVAL,
             servers,
             machines,
             dependencies,
             pinger,
+            clock.clone(),
         );
+        monitor.set_away(Arc::new(communication::Away::new(true)));
 
         // advance FakeClock by at least ping interval (1s)
-        Instant::advance_time((2 * PING_INTERVAL).as_millis().try_into().unwrap());
+        clock.advance(2 * PING_INTERVAL);
 
-        monitor.run_once();
+        monitor.run_once().await;
     }
 
     #[rstest]
-    #[should_panic(expected = "Pinger failed to receive responses")]
+    #[tokio::test]
     #[allow(unused_variables)]
-    fn test_monitor_fails_if_recv_pong_fails(
-        fake_clock: (),
-        server_ip: IpAddr,
+    async fn test_monitor_wakeup_server_kept_on_while_away(
+        clock: Arc<ManualClock>,
         mut mocked_server_control: MockServerControl,
-        machine_ip: IpAddr,
         machine: Machine,
         dependencies: Dependencies,
     ) {
@@ -736,10 +2266,11 @@ mod tests {
         let (mut sender, mut pinger) = default_mocks();
 
         let machines = vec![machine];
+        mocked_server_control.server.keep_on_when_away = true;
 
         // EXPECTATIONS
         pinger.expect_add_target().returning(|_| true);
-        sender.expect_send().times(2).returning(|_| Ok(()));
+        sender.expect_send().returning(|_| Ok(()));
 
         mocked_server_control
             .always_off
@@ -752,43 +2283,45 @@ mod tests {
             .once()
             .return_once(|| false);
 
-        let mut seq = Sequence::new();
-        pinger
-            .expect_ping_once()
-            .once()
-            .return_once(|| {})
-            .in_sequence(&mut seq);
-        pinger
-            .expect_recv_pong()
+        // neither the server nor any of its dependencies is online
+        pinger.expect_ping_once().once().return_once(|| {});
+        pinger.expect_recv_pong().once().return_once(|| Ok(()));
+        pinger.expect_is_online().returning(|_| false);
+
+        // the server is woken up although none of its dependencies is online
+        mocked_server_control
+            .wakeup
+            .expect_wakeup()
             .once()
-            .return_once(|| Err(RecvError))
-            .in_sequence(&mut seq);
+            .return_once(|_| Ok(ActionReport::default()));
 
         // TESTING
         let servers = vec![ServerControl::from(mocked_server_control)];
 
-        let mut monitor = Monitor::new(
+        let mut monitor = Monitor::with_clock(
             sender,
             PING_INTERVAL,
             servers,
             machines,
             dependencies,
             pinger,
+            clock.clone(),
         );
+        monitor.set_away(Arc::new(communication::Away::new(true)));
 
         // advance FakeClock by at least ping interval (1s)
-        Instant::advance_time((2 * PING_INTERVAL).as_millis().try_into().unwrap());
+        clock.advance(2 * PING_INTERVAL);
 
-        monitor.run_once();
+        monitor.run_once().await;
     }
 
     #[rstest]
+    #[tokio::test]
     #[allow(unused_variables)]
-    fn test_monitor_wakeup_server_if_at_least_one_machine_is_online(
-        fake_clock: (),
-        server_ip: IpAddr,
+    async fn test_monitor_wakeup_server_if_group_is_always_on(
+        clock: Arc<ManualClock>,
+        server_id: DeviceId,
         mut mocked_server_control: MockServerControl,
-        machine_ip: IpAddr,
         machine: Machine,
         dependencies: Dependencies,
     ) {
@@ -812,60 +2345,50 @@ mod tests {
             .once()
             .return_once(|| false);
 
-        {
-            let mut seq = Sequence::new();
-            pinger
-                .expect_ping_once()
-                .once()
-                .return_once(|| {})
-                .in_sequence(&mut seq);
-            pinger
-                .expect_recv_pong()
-                .once()
-                .return_once(|| Ok(()))
-                .in_sequence(&mut seq);
-        }
-
-        pinger
-            .expect_is_online()
-            .with(eq(server_ip))
+        let mut group_always_off = crate::utils::MockAlwaysOff::new();
+        group_always_off
+            .expect_is_always_off()
             .once()
-            .return_once(|_| false);
-        pinger
-            .expect_is_online()
-            .with(eq(machine_ip))
+            .return_once(|| false);
+        let mut group_always_on = crate::utils::MockAlwaysOn::new();
+        group_always_on
+            .expect_is_always_on()
             .once()
-            .return_once(|_| true);
-        sender.expect_send().once().return_once(|_| Ok(()));
+            .return_once(|| true);
+        mocked_server_control.groups = vec![mocked_group_control(
+            "lab",
+            vec![server_id],
+            group_always_off,
+            group_always_on,
+        )];
 
         mocked_server_control
             .wakeup
             .expect_wakeup()
             .once()
-            .return_once(|| Ok(()));
+            .return_once(|_| Ok(ActionReport::default()));
 
         // TESTING
         let servers = vec![ServerControl::from(mocked_server_control)];
 
-        let mut monitor = Monitor::new(
+        let mut monitor = Monitor::with_clock(
             sender,
             PING_INTERVAL,
             servers,
             machines,
             dependencies,
             pinger,
+            clock.clone(),
         );
 
-        // advance FakeClock by at least ping interval (1s)
-        Instant::advance_time((2 * PING_INTERVAL).as_millis().try_into().unwrap());
-
-        monitor.run_once();
+        monitor.run_once().await;
     }
 
     #[rstest]
+    #[tokio::test]
     #[allow(unused_variables)]
-    fn test_monitor_only_wakeup_server_again_if_change_timeout_expired(
-        fake_clock: (),
+    async fn test_monitor_reconcile_pings_repeatedly_and_wakes_up_server(
+        clock: Arc<ManualClock>,
         server_ip: IpAddr,
         mut mocked_server_control: MockServerControl,
         machine_ip: IpAddr,
@@ -881,67 +2404,76 @@ mod tests {
         pinger.expect_add_target().returning(|_| true);
         sender.expect_send().times(2).returning(|_| Ok(()));
 
+        // the ALWAYS OFF / ON state is restored from the files API
         mocked_server_control
             .always_off
             .expect_is_always_off()
-            .returning(|| false);
+            .once()
+            .return_once(|| false);
         mocked_server_control
             .always_on
             .expect_is_always_on()
-            .returning(|| false);
+            .once()
+            .return_once(|| false);
 
-        pinger.expect_ping_once().returning(|| {});
-        pinger.expect_recv_pong().returning(|| Ok(()));
+        // all devices are pinged multiple times without waiting for the ping interval
+        pinger
+            .expect_ping_once()
+            .times(STARTUP_PING_ROUNDS as usize)
+            .returning(|| {});
+        pinger
+            .expect_recv_pong()
+            .times(STARTUP_PING_ROUNDS as usize)
+            .returning(|| Ok(()));
         pinger
             .expect_is_online()
             .with(eq(server_ip))
+            .times(STARTUP_PING_ROUNDS as usize)
             .returning(|_| false);
+
+        // the machine only responds to a single ping
+        let mut machine_pings = 0;
         pinger
             .expect_is_online()
             .with(eq(machine_ip))
-            .returning(|_| true);
-        sender.expect_send().once().return_once(|_| Ok(()));
+            .times(STARTUP_PING_ROUNDS as usize)
+            .returning(move |_| {
+                machine_pings += 1;
+                machine_pings == 2
+            });
 
+        // the server and the machine are updated and the server is woken up
+        sender.expect_send().times(2).returning(|_| Ok(()));
         mocked_server_control
             .wakeup
             .expect_wakeup()
-            .times(2)
-            .returning(|| Ok(()));
+            .once()
+            .return_once(|_| Ok(ActionReport::default()));
 
         // TESTING
         let servers = vec![ServerControl::from(mocked_server_control)];
 
-        let mut monitor = Monitor::new(
+        let mut monitor = Monitor::with_clock(
             sender,
             PING_INTERVAL,
             servers,
             machines,
             dependencies,
             pinger,
+            clock.clone(),
         );
 
-        // advance FakeClock by at least ping interval (1s)
-        Instant::advance_time((2 * PING_INTERVAL).as_millis().try_into().unwrap());
-
-        monitor.run_once();
+        // let some time pass since the monitor has been created
+        clock.advance(Duration::from_millis(1));
 
-        // advance FakeClock by at least ping interval (1s)
-        Instant::advance_time((2 * PING_INTERVAL).as_millis().try_into().unwrap());
-
-        // this run should not wakeup the server
-        monitor.run_once();
-
-        // advance FakeClock by at least change timeout (120s)
-        Instant::advance_time((2 * CHANGE_TIMEOUT).as_millis().try_into().unwrap());
-
-        // this run should wakeup the server again
-        monitor.run_once();
+        monitor.reconcile().await;
     }
 
     #[rstest]
+    #[tokio::test]
     #[allow(unused_variables)]
-    fn test_monitor_shutdown_server_if_no_machine_is_online(
-        fake_clock: (),
+    async fn test_monitor_only_observes_during_startup_grace_period(
+        clock: Arc<ManualClock>,
         server_ip: IpAddr,
         mut mocked_server_control: MockServerControl,
         machine_ip: IpAddr,
@@ -952,11 +2484,27 @@ mod tests {
         let (mut sender, mut pinger) = default_mocks();
 
         let machines = vec![machine];
+        let startup_grace_period = Duration::from_secs(10);
 
         // EXPECTATIONS
         pinger.expect_add_target().returning(|_| true);
-        sender.expect_send().times(2).returning(|_| Ok(()));
+        sender.expect_send().returning(|_| Ok(()));
+
+        // the devices are pinged during and after the startup grace period
+        pinger.expect_ping_once().times(2).returning(|| {});
+        pinger.expect_recv_pong().times(2).returning(|| Ok(()));
+        pinger
+            .expect_is_online()
+            .with(eq(server_ip))
+            .times(2)
+            .returning(|_| false);
+        pinger
+            .expect_is_online()
+            .with(eq(machine_ip))
+            .times(2)
+            .returning(|_| true);
 
+        // the server is only processed (and woken up) after the startup grace period
         mocked_server_control
             .always_off
             .expect_is_always_off()
@@ -965,48 +2513,40 @@ mod tests {
         mocked_server_control
             .always_on
             .expect_is_always_on()
-            .returning(|| false);
-
-        pinger.expect_ping_once().returning(|| {});
-        pinger.expect_recv_pong().returning(|| Ok(()));
-        pinger
-            .expect_is_online()
-            .with(eq(server_ip))
-            .returning(|_| true);
-        sender.expect_send().once().return_once(|_| Ok(()));
-        pinger
-            .expect_is_online()
-            .with(eq(machine_ip))
-            .returning(|_| false);
-
+            .once()
+            .return_once(|| false);
         mocked_server_control
-            .shutdown
-            .expect_shutdown()
+            .wakeup
+            .expect_wakeup()
             .once()
-            .return_once(|| Ok(()));
+            .return_once(|_| Ok(ActionReport::default()));
 
         // TESTING
         let servers = vec![ServerControl::from(mocked_server_control)];
 
-        let mut monitor = Monitor::new(
+        let mut monitor = Monitor::with_clock(
             sender,
             PING_INTERVAL,
             servers,
             machines,
             dependencies,
             pinger,
+            clock.clone(),
         );
+        monitor.set_startup_grace_period(startup_grace_period);
 
         // advance FakeClock by at least ping interval (1s)
-        Instant::advance_time((2 * PING_INTERVAL).as_millis().try_into().unwrap());
+        clock.advance(2 * PING_INTERVAL);
+        monitor.run_once().await;
 
-        monitor.run_once();
+        // advance FakeClock beyond the startup grace period
+        clock.advance(startup_grace_period);
+        monitor.run_once().await;
     }
 
     #[rstest]
-    #[allow(unused_variables)]
-    fn test_monitor_only_shutdown_server_after_wakeup_if_change_timeout_expired(
-        fake_clock: (),
+    #[tokio::test]
+    async fn test_monitor_measures_time_with_given_clock(
         server_ip: IpAddr,
         mut mocked_server_control: MockServerControl,
         machine_ip: IpAddr,
@@ -1017,67 +2557,29 @@ mod tests {
         let (mut sender, mut pinger) = default_mocks();
 
         let machines = vec![machine];
+        let startup_grace_period = Duration::from_secs(10);
+        // the clock is independent of the (fake) system clock
+        let clock = Arc::new(ManualClock::new(Instant::now() + Duration::from_secs(3600)));
 
         // EXPECTATIONS
         pinger.expect_add_target().returning(|_| true);
-        sender.expect_send().times(2).returning(|_| Ok(()));
-
-        // first call to ping_once() which will wakeup the server
-        mocked_server_control
-            .always_off
-            .expect_is_always_off()
-            .once()
-            .return_once(|| false);
-        mocked_server_control
-            .always_on
-            .expect_is_always_on()
-            .once()
-            .return_once(|| false);
-        pinger.expect_ping_once().once().return_once(|| {});
-        pinger.expect_recv_pong().once().return_once(|| Ok(()));
-        pinger
-            .expect_is_online()
-            .with(eq(server_ip))
-            .once()
-            .return_once(|_| false);
-        pinger
-            .expect_is_online()
-            .with(eq(machine_ip))
-            .once()
-            .return_once(|_| true);
-        sender.expect_send().once().return_once(|_| Ok(()));
-        mocked_server_control
-            .wakeup
-            .expect_wakeup()
-            .once()
-            .return_once(|| Ok(()));
+        sender.expect_send().returning(|_| Ok(()));
 
-        // second call to ping_once() which will not shutdown the server
-        mocked_server_control
-            .always_off
-            .expect_is_always_off()
-            .once()
-            .return_once(|| false);
-        mocked_server_control
-            .always_on
-            .expect_is_always_on()
-            .once()
-            .return_once(|| false);
-        pinger.expect_ping_once().once().return_once(|| {});
-        pinger.expect_recv_pong().once().return_once(|| Ok(()));
+        // the devices are only pinged once the clock has been advanced by the ping interval
+        pinger.expect_ping_once().times(2).returning(|| {});
+        pinger.expect_recv_pong().times(2).returning(|| Ok(()));
         pinger
             .expect_is_online()
             .with(eq(server_ip))
-            .once()
-            .return_once(|_| true);
-        sender.expect_send().once().return_once(|_| Ok(()));
+            .times(2)
+            .returning(|_| false);
         pinger
             .expect_is_online()
             .with(eq(machine_ip))
-            .once()
-            .return_once(|_| false);
+            .times(2)
+            .returning(|_| true);
 
-        // third call to ping_once() which will shutdown the server
+        // the server is only processed (and woken up) after the startup grace period
         mocked_server_control
             .always_off
             .expect_is_always_off()
@@ -1088,63 +2590,91 @@ mod tests {
             .expect_is_always_on()
             .once()
             .return_once(|| false);
-        pinger.expect_ping_once().once().return_once(|| {});
-        pinger.expect_recv_pong().once().return_once(|| Ok(()));
-        pinger
-            .expect_is_online()
-            .with(eq(server_ip))
-            .once()
-            .return_once(|_| true);
-        pinger
-            .expect_is_online()
-            .with(eq(machine_ip))
-            .once()
-            .return_once(|_| false);
-        sender.expect_send().once().return_once(|_| Ok(()));
         mocked_server_control
-            .shutdown
-            .expect_shutdown()
+            .wakeup
+            .expect_wakeup()
             .once()
-            .return_once(|| Ok(()));
+            .return_once(|_| Ok(ActionReport::default()));
 
         // TESTING
         let servers = vec![ServerControl::from(mocked_server_control)];
 
-        let mut monitor = Monitor::new(
+        let mut monitor = Monitor::with_clock(
             sender,
             PING_INTERVAL,
             servers,
             machines,
             dependencies,
             pinger,
+            clock.clone(),
         );
+        monitor.set_startup_grace_period(startup_grace_period);
 
-        // advance FakeClock by at least ping interval (1s)
-        Instant::advance_time((2 * PING_INTERVAL).as_millis().try_into().unwrap());
+        // the ping interval hasn't elapsed yet
+        monitor.run_once().await;
 
-        monitor.run_once();
+        clock.advance(2 * PING_INTERVAL);
+        monitor.run_once().await;
 
-        // advance FakeClock by at least ping interval (1s)
-        Instant::advance_time((2 * PING_INTERVAL).as_millis().try_into().unwrap());
+        clock.advance(startup_grace_period);
+        monitor.run_once().await;
+    }
 
-        // this run should not shutdown the server
-        monitor.run_once();
+    #[rstest]
+    #[tokio::test]
+    async fn test_monitor_records_ping_drift(
+        mocked_server_control: MockServerControl,
+        machine: Machine,
+        dependencies: Dependencies,
+    ) {
+        // SETUP
+        let (mut sender, mut pinger) = default_mocks();
 
-        // advance FakeClock by at least change timeout (120s) or last seen timeout (300s)
-        let max_timeout = std::cmp::max(
-            Duration::from_secs(MACHINE_LAST_SEEN_TIMEOUT),
-            CHANGE_TIMEOUT,
-        );
-        Instant::advance_time((2 * max_timeout).as_millis().try_into().unwrap());
+        let machines = vec![machine];
+        let clock = Arc::new(ManualClock::new(Instant::now() + Duration::from_secs(3600)));
+        let stats = Arc::new(communication::MonitorStats::new());
 
-        // this run should shutdown the server
-        monitor.run_once();
+        // EXPECTATIONS
+        pinger.expect_add_target().returning(|_| true);
+        pinger.expect_ping_once().once().returning(|| {});
+        pinger.expect_recv_pong().once().returning(|| Ok(()));
+        pinger.expect_is_online().returning(|_| false);
+        sender.expect_send().returning(|_| Ok(()));
+
+        // TESTING
+        let servers = vec![ServerControl::from(mocked_server_control)];
+
+        let mut monitor = Monitor::with_clock(
+            sender,
+            PING_INTERVAL,
+            servers,
+            machines,
+            dependencies,
+            pinger,
+            clock.clone(),
+        );
+        // don't process the servers
+        monitor.set_startup_grace_period(Duration::from_secs(3600));
+        monitor.set_stats(stats.clone());
+
+        // the ping interval hasn't elapsed yet
+        monitor.run_once().await;
+        assert_eq!(stats.ping_cycles(), 0);
+
+        // the ping interval has already elapsed on startup so the devices are pinged half a ping
+        // interval late
+        clock.advance(PING_INTERVAL / 2);
+        monitor.run_once().await;
+        assert_eq!(stats.ping_cycles(), 1);
+        assert_eq!(stats.last_drift(), PING_INTERVAL / 2);
+        assert_eq!(stats.overruns(), 0);
     }
 
     #[rstest]
+    #[tokio::test]
     #[allow(unused_variables)]
-    fn test_monitor_dont_wakeup_server_if_always_off(
-        fake_clock: (),
+    async fn test_monitor_ping_once_if_interval_elapsed(
+        clock: Arc<ManualClock>,
         server_ip: IpAddr,
         mut mocked_server_control: MockServerControl,
         machine_ip: IpAddr,
@@ -1163,49 +2693,63 @@ mod tests {
         mocked_server_control
             .always_off
             .expect_is_always_off()
-            .returning(|| true);
-
+            .once()
+            .return_once(|| false);
         mocked_server_control
             .always_on
             .expect_is_always_on()
-            .returning(|| false);
+            .once()
+            .return_once(|| false);
 
-        pinger.expect_ping_once().returning(|| {});
-        pinger.expect_recv_pong().returning(|| Ok(()));
+        {
+            let mut seq = Sequence::new();
+            pinger
+                .expect_ping_once()
+                .once()
+                .return_once(|| {})
+                .in_sequence(&mut seq);
+            pinger
+                .expect_recv_pong()
+                .once()
+                .return_once(|| Ok(()))
+                .in_sequence(&mut seq);
+        }
         pinger
             .expect_is_online()
             .with(eq(server_ip))
-            .returning(|_| false);
+            .once()
+            .return_once(|_| false);
         pinger
             .expect_is_online()
             .with(eq(machine_ip))
-            .returning(|_| true);
-        sender.expect_send().once().return_once(|_| Ok(()));
-
-        mocked_server_control.wakeup.expect_wakeup().never();
+            .once()
+            .return_once(|_| false);
 
         // TESTING
         let servers = vec![ServerControl::from(mocked_server_control)];
 
-        let mut monitor = Monitor::new(
+        let mut monitor = Monitor::with_clock(
             sender,
             PING_INTERVAL,
             servers,
             machines,
             dependencies,
             pinger,
+            clock.clone(),
         );
 
         // advance FakeClock by at least ping interval (1s)
-        Instant::advance_time((2 * PING_INTERVAL).as_millis().try_into().unwrap());
+        clock.advance(2 * PING_INTERVAL);
 
-        monitor.run_once();
+        monitor.run_once().await;
     }
 
     #[rstest]
+    #[tokio::test]
+    #[should_panic(expected = "Pinger failed to receive responses")]
     #[allow(unused_variables)]
-    fn test_monitor_dont_shutdown_server_if_always_on(
-        fake_clock: (),
+    async fn test_monitor_fails_if_recv_pong_fails(
+        clock: Arc<ManualClock>,
         server_ip: IpAddr,
         mut mocked_server_control: MockServerControl,
         machine_ip: IpAddr,
@@ -1224,42 +2768,2116 @@ mod tests {
         mocked_server_control
             .always_off
             .expect_is_always_off()
-            .returning(|| false);
-
-        mocked_server_control
+            .once()
+            .return_once(|| false);
+        mocked_server_control
             .always_on
             .expect_is_always_on()
-            .returning(|| true);
+            .once()
+            .return_once(|| false);
+
+        let mut seq = Sequence::new();
+        pinger
+            .expect_ping_once()
+            .once()
+            .return_once(|| {})
+            .in_sequence(&mut seq);
+        pinger
+            .expect_recv_pong()
+            .once()
+            .return_once(|| Err(RecvError))
+            .in_sequence(&mut seq);
+
+        // TESTING
+        let servers = vec![ServerControl::from(mocked_server_control)];
+
+        let mut monitor = Monitor::with_clock(
+            sender,
+            PING_INTERVAL,
+            servers,
+            machines,
+            dependencies,
+            pinger,
+            clock.clone(),
+        );
+
+        // advance FakeClock by at least ping interval (1s)
+        clock.advance(2 * PING_INTERVAL);
+
+        monitor.run_once().await;
+    }
+
+    #[rstest]
+    #[tokio::test]
+    #[allow(unused_variables)]
+    async fn test_monitor_wakeup_server_if_at_least_one_machine_is_online(
+        clock: Arc<ManualClock>,
+        server_ip: IpAddr,
+        mut mocked_server_control: MockServerControl,
+        machine_ip: IpAddr,
+        machine: Machine,
+        dependencies: Dependencies,
+    ) {
+        // SETUP
+        let (mut sender, mut pinger) = default_mocks();
+
+        let machines = vec![machine];
+
+        // EXPECTATIONS
+        pinger.expect_add_target().returning(|_| true);
+        sender.expect_send().times(2).returning(|_| Ok(()));
+
+        mocked_server_control
+            .always_off
+            .expect_is_always_off()
+            .once()
+            .return_once(|| false);
+        mocked_server_control
+            .always_on
+            .expect_is_always_on()
+            .once()
+            .return_once(|| false);
+
+        {
+            let mut seq = Sequence::new();
+            pinger
+                .expect_ping_once()
+                .once()
+                .return_once(|| {})
+                .in_sequence(&mut seq);
+            pinger
+                .expect_recv_pong()
+                .once()
+                .return_once(|| Ok(()))
+                .in_sequence(&mut seq);
+        }
 
-        pinger.expect_ping_once().returning(|| {});
-        pinger.expect_recv_pong().returning(|| Ok(()));
         pinger
             .expect_is_online()
             .with(eq(server_ip))
-            .returning(|_| true);
-        sender.expect_send().once().return_once(|_| Ok(()));
+            .once()
+            .return_once(|_| false);
         pinger
             .expect_is_online()
             .with(eq(machine_ip))
-            .returning(|_| false);
+            .once()
+            .return_once(|_| true);
+        sender.expect_send().times(2).returning(|_| Ok(()));
 
-        mocked_server_control.shutdown.expect_shutdown().never();
+        mocked_server_control
+            .wakeup
+            .expect_wakeup()
+            .once()
+            .return_once(|_| Ok(ActionReport::default()));
+
+        // TESTING
+        let servers = vec![ServerControl::from(mocked_server_control)];
+
+        let mut monitor = Monitor::with_clock(
+            sender,
+            PING_INTERVAL,
+            servers,
+            machines,
+            dependencies,
+            pinger,
+            clock.clone(),
+        );
+
+        // advance FakeClock by at least ping interval (1s)
+        clock.advance(2 * PING_INTERVAL);
+
+        monitor.run_once().await;
+    }
+
+    #[rstest]
+    #[tokio::test]
+    #[allow(unused_variables)]
+    async fn test_monitor_wakeup_server_if_schedule_is_active(
+        clock: Arc<ManualClock>,
+        server_ip: IpAddr,
+        server_id: DeviceId,
+        mut mocked_server_control: MockServerControl,
+        machine_ip: IpAddr,
+        machine: Machine,
+        dependencies: Dependencies,
+    ) {
+        // SETUP
+        let (mut sender, mut pinger) = default_mocks();
+
+        let machines = vec![machine];
+        // a time window starting and ending at midnight is active all day long
+        let schedule = Schedule::Time {
+            id: "time:always".parse().unwrap(),
+            from: NaiveTime::from_hms_opt(0, 0, 0).unwrap(),
+            to: NaiveTime::from_hms_opt(0, 0, 0).unwrap(),
+            days: Vec::new(),
+        };
+
+        // EXPECTATIONS
+        pinger.expect_add_target().returning(|_| true);
+        sender.expect_send().returning(|_| Ok(()));
+
+        mocked_server_control
+            .always_off
+            .expect_is_always_off()
+            .once()
+            .return_once(|| false);
+        mocked_server_control
+            .always_on
+            .expect_is_always_on()
+            .once()
+            .return_once(|| false);
+
+        pinger.expect_ping_once().once().return_once(|| {});
+        pinger.expect_recv_pong().once().return_once(|| Ok(()));
+        pinger
+            .expect_is_online()
+            .with(eq(server_ip))
+            .once()
+            .return_once(|_| false);
+        pinger
+            .expect_is_online()
+            .with(eq(machine_ip))
+            .once()
+            .return_once(|_| false);
+
+        mocked_server_control
+            .wakeup
+            .expect_wakeup()
+            .once()
+            .return_once(|_| Ok(ActionReport::default()));
+
+        // TESTING
+        let servers = vec![ServerControl::from(mocked_server_control)];
+
+        let mut monitor = Monitor::with_clock(
+            sender,
+            PING_INTERVAL,
+            servers,
+            machines,
+            dependencies,
+            pinger,
+            clock.clone(),
+        );
+        monitor.set_schedules([(server_id, vec![schedule])].iter().cloned().collect());
+
+        // advance FakeClock by at least ping interval (1s)
+        clock.advance(2 * PING_INTERVAL);
+
+        monitor.run_once().await;
+    }
+
+    #[rstest]
+    #[tokio::test]
+    #[allow(unused_variables)]
+    async fn test_monitor_uses_http_check_instead_of_ping_if_configured(
+        clock: Arc<ManualClock>,
+        server_ip: IpAddr,
+        mut mocked_server_control: MockServerControl,
+        mut machine: Machine,
+        dependencies: Dependencies,
+    ) {
+        // SETUP
+        let (mut sender, mut pinger) = default_mocks();
+
+        // serve a single successful HTTP response
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/health", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            use std::io::{Read, Write};
+
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0; 1024];
+            let _ = stream.read(&mut request).unwrap();
+            stream
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")
+                .unwrap();
+        });
+
+        machine.http = Some(crate::dom::device::HttpCheck {
+            url,
+            expected_status: 200,
+            expected_body: None,
+            timeout: Duration::from_secs(1),
+            insecure: false,
+        });
+        let machines = vec![machine];
+
+        // EXPECTATIONS
+        pinger.expect_add_target().returning(|_| true);
+        sender.expect_send().times(2).returning(|_| Ok(()));
+
+        mocked_server_control
+            .always_off
+            .expect_is_always_off()
+            .once()
+            .return_once(|| false);
+        mocked_server_control
+            .always_on
+            .expect_is_always_on()
+            .once()
+            .return_once(|| false);
+
+        pinger.expect_ping_once().once().return_once(|| {});
+        pinger.expect_recv_pong().once().return_once(|| Ok(()));
+
+        // only the server is checked using the pinger
+        pinger
+            .expect_is_online()
+            .with(eq(server_ip))
+            .once()
+            .return_once(|_| false);
+        sender.expect_send().times(2).returning(|_| Ok(()));
+
+        // the machine is online based on the HTTP check
+        mocked_server_control
+            .wakeup
+            .expect_wakeup()
+            .once()
+            .return_once(|_| Ok(ActionReport::default()));
 
         // TESTING
         let servers = vec![ServerControl::from(mocked_server_control)];
 
-        let mut monitor = Monitor::new(
+        let mut monitor = Monitor::with_clock(
             sender,
             PING_INTERVAL,
             servers,
             machines,
             dependencies,
             pinger,
+            clock.clone(),
         );
 
         // advance FakeClock by at least ping interval (1s)
-        Instant::advance_time((2 * PING_INTERVAL).as_millis().try_into().unwrap());
+        clock.advance(2 * PING_INTERVAL);
+
+        monitor.run_once().await;
+    }
+
+    #[rstest]
+    #[tokio::test]
+    #[allow(unused_variables)]
+    async fn test_monitor_records_probe_method_of_online_devices(
+        clock: Arc<ManualClock>,
+        server_ip: IpAddr,
+        mut mocked_server_control: MockServerControl,
+        machine_ip: IpAddr,
+        machine: Machine,
+        dependencies: Dependencies,
+    ) {
+        // SETUP
+        let (mut sender, mut pinger) = default_mocks();
+
+        let machines = vec![machine];
+
+        // EXPECTATIONS
+        pinger.expect_add_target().returning(|_| true);
+        sender.expect_send().times(2).returning(|_| Ok(()));
+
+        mocked_server_control
+            .always_off
+            .expect_is_always_off()
+            .once()
+            .return_once(|| false);
+        mocked_server_control
+            .always_on
+            .expect_is_always_on()
+            .once()
+            .return_once(|| false);
+
+        pinger.expect_ping_once().once().return_once(|| {});
+        pinger.expect_recv_pong().once().return_once(|| Ok(()));
+        pinger
+            .expect_is_online()
+            .with(eq(server_ip))
+            .once()
+            .return_once(|_| false);
+        pinger
+            .expect_is_online()
+            .with(eq(machine_ip))
+            .once()
+            .return_once(|_| true);
+
+        // the machine only answered ARP requests
+        sender
+            .expect_send()
+            .withf(|device| device.probe_method() == Some(ProbeMethod::Arp))
+            .once()
+            .returning(|_| Ok(()));
+        sender.expect_send().returning(|_| Ok(()));
+
+        mocked_server_control
+            .wakeup
+            .expect_wakeup()
+            .once()
+            .return_once(|_| Ok(ActionReport::default()));
 
-        monitor.run_once();
+        // TESTING
+        let servers = vec![ServerControl::from(mocked_server_control)];
+
+        let mut monitor = Monitor::with_clock(
+            sender,
+            PING_INTERVAL,
+            servers,
+            machines,
+            dependencies,
+            pinger,
+            clock.clone(),
+        );
+        let arp_responders = Arc::new(ArpResponders::new());
+        arp_responders.set_responding(machine_ip, true);
+        monitor.set_arp_responders(arp_responders);
+
+        // advance FakeClock by at least ping interval (1s)
+        clock.advance(2 * PING_INTERVAL);
+
+        monitor.run_once().await;
+    }
+
+    #[rstest]
+    #[tokio::test]
+    #[allow(unused_variables)]
+    async fn test_monitor_checks_port_of_machine_in_its_interval(
+        clock: Arc<ManualClock>,
+        server_ip: IpAddr,
+        mut mocked_server_control: MockServerControl,
+        mut machine: Machine,
+        dependencies: Dependencies,
+    ) {
+        // SETUP
+        let (mut sender, mut pinger) = default_mocks();
+
+        let closed_port = std::net::TcpListener::bind(("127.0.0.1", 0))
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let interval = Duration::from_secs(10);
+        machine.ip = "127.0.0.1".parse().unwrap();
+        machine.port = Some(closed_port);
+        machine.interval = Some(interval);
+        let machine_id = machine.id.clone();
+        let machines = vec![machine];
+
+        // EXPECTATIONS
+        pinger.expect_add_target().returning(|_| true);
+        pinger.expect_ping_once().returning(|| {});
+        pinger.expect_recv_pong().returning(|| Ok(()));
+
+        // only the server is checked using the pinger
+        pinger
+            .expect_is_online()
+            .with(eq(server_ip))
+            .times(3)
+            .returning(|_| false);
+
+        // only the initial state of both devices is published
+        sender.expect_send().times(2).returning(|_| Ok(()));
+
+        mocked_server_control
+            .always_off
+            .expect_is_always_off()
+            .returning(|| false);
+        mocked_server_control
+            .always_on
+            .expect_is_always_on()
+            .returning(|| false);
+
+        // TESTING
+        let servers = vec![ServerControl::from(mocked_server_control)];
+
+        let mut monitor = Monitor::with_clock(
+            sender,
+            PING_INTERVAL,
+            servers,
+            machines,
+            dependencies,
+            pinger,
+            clock.clone(),
+        );
+
+        let missed_checks = |monitor: &Monitor| {
+            monitor
+                .devices
+                .iter()
+                .map(|device| device.read().unwrap())
+                .find(|device| device.id() == &machine_id)
+                .unwrap()
+                .consecutive_missed_pings()
+        };
+
+        // the machine is checked together with the first ping
+        clock.advance(2 * PING_INTERVAL);
+        monitor.run_once().await;
+        assert_eq!(missed_checks(&monitor), 1);
+
+        // but not again before its interval has elapsed
+        clock.advance(2 * PING_INTERVAL);
+        monitor.run_once().await;
+        assert_eq!(missed_checks(&monitor), 1);
+
+        clock.advance(interval);
+        monitor.run_once().await;
+        assert_eq!(missed_checks(&monitor), 2);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    #[allow(unused_variables)]
+    async fn test_monitor_only_wakeup_server_again_if_change_timeout_expired(
+        clock: Arc<ManualClock>,
+        server_ip: IpAddr,
+        mut mocked_server_control: MockServerControl,
+        machine_ip: IpAddr,
+        machine: Machine,
+        dependencies: Dependencies,
+    ) {
+        // SETUP
+        let (mut sender, mut pinger) = default_mocks();
+
+        let machines = vec![machine];
+
+        // EXPECTATIONS
+        pinger.expect_add_target().returning(|_| true);
+        sender.expect_send().times(2).returning(|_| Ok(()));
+
+        mocked_server_control
+            .always_off
+            .expect_is_always_off()
+            .returning(|| false);
+        mocked_server_control
+            .always_on
+            .expect_is_always_on()
+            .returning(|| false);
+
+        pinger.expect_ping_once().returning(|| {});
+        pinger.expect_recv_pong().returning(|| Ok(()));
+        pinger
+            .expect_is_online()
+            .with(eq(server_ip))
+            .returning(|_| false);
+        pinger
+            .expect_is_online()
+            .with(eq(machine_ip))
+            .returning(|_| true);
+        // the machine coming online and the change timeout of the woken up server are published
+        sender.expect_send().times(2).returning(|_| Ok(()));
+
+        mocked_server_control
+            .wakeup
+            .expect_wakeup()
+            .times(2)
+            .returning(|_| Ok(ActionReport::default()));
+
+        // TESTING
+        let servers = vec![ServerControl::from(mocked_server_control)];
+
+        let mut monitor = Monitor::with_clock(
+            sender,
+            PING_INTERVAL,
+            servers,
+            machines,
+            dependencies,
+            pinger,
+            clock.clone(),
+        );
+
+        // advance FakeClock by at least ping interval (1s)
+        clock.advance(2 * PING_INTERVAL);
+
+        monitor.run_once().await;
+
+        // advance FakeClock by at least ping interval (1s)
+        clock.advance(2 * PING_INTERVAL);
+
+        // this run should not wakeup the server
+        monitor.run_once().await;
+
+        // advance FakeClock by at least change timeout (120s)
+        clock.advance(2 * CHANGE_TIMEOUT);
+
+        // this run should wakeup the server again
+        monitor.run_once().await;
+    }
+
+    #[rstest]
+    #[tokio::test]
+    #[allow(unused_variables)]
+    async fn test_monitor_shutdown_server_if_no_machine_is_online(
+        clock: Arc<ManualClock>,
+        server_ip: IpAddr,
+        mut mocked_server_control: MockServerControl,
+        machine_ip: IpAddr,
+        machine: Machine,
+        dependencies: Dependencies,
+    ) {
+        // SETUP
+        let (mut sender, mut pinger) = default_mocks();
+
+        let machines = vec![machine];
+
+        // EXPECTATIONS
+        pinger.expect_add_target().returning(|_| true);
+        sender.expect_send().times(2).returning(|_| Ok(()));
+
+        mocked_server_control
+            .always_off
+            .expect_is_always_off()
+            .once()
+            .return_once(|| false);
+        mocked_server_control
+            .always_on
+            .expect_is_always_on()
+            .returning(|| false);
+
+        pinger.expect_ping_once().returning(|| {});
+        pinger.expect_recv_pong().returning(|| Ok(()));
+        pinger
+            .expect_is_online()
+            .with(eq(server_ip))
+            .returning(|_| true);
+        sender.expect_send().once().return_once(|_| Ok(()));
+        pinger
+            .expect_is_online()
+            .with(eq(machine_ip))
+            .returning(|_| false);
+
+        mocked_server_control
+            .shutdown
+            .expect_shutdown()
+            .once()
+            .return_once(|_| Ok(ActionReport::default()));
+
+        // TESTING
+        let servers = vec![ServerControl::from(mocked_server_control)];
+
+        let mut monitor = Monitor::with_clock(
+            sender,
+            PING_INTERVAL,
+            servers,
+            machines,
+            dependencies,
+            pinger,
+            clock.clone(),
+        );
+
+        // advance FakeClock by at least ping interval (1s)
+        clock.advance(2 * PING_INTERVAL);
+
+        monitor.run_once().await;
+    }
+
+    #[rstest]
+    #[tokio::test]
+    #[allow(unused_variables)]
+    async fn test_monitor_ignores_offline_machine_during_ignore_window(
+        clock: Arc<ManualClock>,
+        server_ip: IpAddr,
+        mut mocked_server_control: MockServerControl,
+        machine_ip: IpAddr,
+        mut machine: Machine,
+        dependencies: Dependencies,
+    ) {
+        // SETUP
+        let (mut sender, mut pinger) = default_mocks();
+
+        machine.set_online(true);
+        let machine_id = machine.id.clone();
+        let machines = vec![machine];
+
+        let ignore_windows = Arc::new(communication::IgnoreWindows::new(HashMap::new()));
+        ignore_windows.set(
+            &machine_id,
+            communication::IgnoreWindow {
+                from: clock.utc_now() - chrono::Duration::minutes(1),
+                until: clock.utc_now() + chrono::Duration::hours(1),
+            },
+        );
+
+        // EXPECTATIONS
+        pinger.expect_add_target().returning(|_| true);
+        sender.expect_send().returning(|_| Ok(()));
+
+        mocked_server_control
+            .always_off
+            .expect_is_always_off()
+            .returning(|| false);
+        mocked_server_control
+            .always_on
+            .expect_is_always_on()
+            .returning(|| false);
+
+        pinger.expect_ping_once().returning(|| {});
+        pinger.expect_recv_pong().returning(|| Ok(()));
+        pinger
+            .expect_is_online()
+            .with(eq(server_ip))
+            .returning(|_| true);
+        pinger
+            .expect_is_online()
+            .with(eq(machine_ip))
+            .returning(|_| false);
+
+        // the machine being offline doesn't count against the server
+        mocked_server_control.shutdown.expect_shutdown().never();
+
+        // TESTING
+        let servers = vec![ServerControl::from(mocked_server_control)];
+
+        let mut monitor = Monitor::with_clock(
+            sender,
+            PING_INTERVAL,
+            servers,
+            machines,
+            dependencies,
+            pinger,
+            clock.clone(),
+        );
+        monitor.set_ignore_windows(ignore_windows);
+
+        // advance FakeClock beyond the last seen timeout of the machine
+        clock.advance(Duration::from_secs(MACHINE_LAST_SEEN_TIMEOUT) + 2 * PING_INTERVAL);
+
+        monitor.run_once().await;
+
+        let machine = monitor
+            .devices
+            .iter()
+            .find(|device| *device.read().unwrap().id() == machine_id)
+            .unwrap()
+            .read()
+            .unwrap()
+            .clone();
+        assert!(machine.is_online());
+        assert!(machine.is_ignored());
+        assert_eq!(machine.consecutive_missed_pings(), 0);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    #[allow(unused_variables)]
+    async fn test_monitor_only_shutdown_server_after_wakeup_if_change_timeout_expired(
+        clock: Arc<ManualClock>,
+        server_ip: IpAddr,
+        mut mocked_server_control: MockServerControl,
+        machine_ip: IpAddr,
+        machine: Machine,
+        dependencies: Dependencies,
+    ) {
+        // SETUP
+        let (mut sender, mut pinger) = default_mocks();
+
+        let machines = vec![machine];
+
+        // EXPECTATIONS
+        pinger.expect_add_target().returning(|_| true);
+        sender.expect_send().times(2).returning(|_| Ok(()));
+
+        // first call to ping_once() which will wakeup the server
+        mocked_server_control
+            .always_off
+            .expect_is_always_off()
+            .once()
+            .return_once(|| false);
+        mocked_server_control
+            .always_on
+            .expect_is_always_on()
+            .once()
+            .return_once(|| false);
+        pinger.expect_ping_once().once().return_once(|| {});
+        pinger.expect_recv_pong().once().return_once(|| Ok(()));
+        pinger
+            .expect_is_online()
+            .with(eq(server_ip))
+            .once()
+            .return_once(|_| false);
+        pinger
+            .expect_is_online()
+            .with(eq(machine_ip))
+            .once()
+            .return_once(|_| true);
+        // the machine coming online and the change timeout of the woken up server are published
+        sender.expect_send().times(2).returning(|_| Ok(()));
+        mocked_server_control
+            .wakeup
+            .expect_wakeup()
+            .once()
+            .return_once(|_| Ok(ActionReport::default()));
+
+        // second call to ping_once() which will not shutdown the server
+        mocked_server_control
+            .always_off
+            .expect_is_always_off()
+            .once()
+            .return_once(|| false);
+        mocked_server_control
+            .always_on
+            .expect_is_always_on()
+            .once()
+            .return_once(|| false);
+        pinger.expect_ping_once().once().return_once(|| {});
+        pinger.expect_recv_pong().once().return_once(|| Ok(()));
+        pinger
+            .expect_is_online()
+            .with(eq(server_ip))
+            .once()
+            .return_once(|_| true);
+        sender.expect_send().once().return_once(|_| Ok(()));
+        pinger
+            .expect_is_online()
+            .with(eq(machine_ip))
+            .once()
+            .return_once(|_| false);
+
+        // third call to ping_once() which will shutdown the server
+        mocked_server_control
+            .always_off
+            .expect_is_always_off()
+            .once()
+            .return_once(|| false);
+        mocked_server_control
+            .always_on
+            .expect_is_always_on()
+            .once()
+            .return_once(|| false);
+        pinger.expect_ping_once().once().return_once(|| {});
+        pinger.expect_recv_pong().once().return_once(|| Ok(()));
+        pinger
+            .expect_is_online()
+            .with(eq(server_ip))
+            .once()
+            .return_once(|_| true);
+        pinger
+            .expect_is_online()
+            .with(eq(machine_ip))
+            .once()
+            .return_once(|_| false);
+        sender.expect_send().once().return_once(|_| Ok(()));
+        mocked_server_control
+            .shutdown
+            .expect_shutdown()
+            .once()
+            .return_once(|_| Ok(ActionReport::default()));
+
+        // TESTING
+        let servers = vec![ServerControl::from(mocked_server_control)];
+
+        let mut monitor = Monitor::with_clock(
+            sender,
+            PING_INTERVAL,
+            servers,
+            machines,
+            dependencies,
+            pinger,
+            clock.clone(),
+        );
+
+        // advance FakeClock by at least ping interval (1s)
+        clock.advance(2 * PING_INTERVAL);
+
+        monitor.run_once().await;
+
+        // advance FakeClock by at least ping interval (1s)
+        clock.advance(2 * PING_INTERVAL);
+
+        // this run should not shutdown the server
+        monitor.run_once().await;
+
+        // advance FakeClock by at least change timeout (120s) or last seen timeout (300s)
+        let max_timeout = std::cmp::max(
+            Duration::from_secs(MACHINE_LAST_SEEN_TIMEOUT),
+            CHANGE_TIMEOUT,
+        );
+        clock.advance(2 * max_timeout);
+
+        // this run should shutdown the server
+        monitor.run_once().await;
+    }
+
+    #[rstest]
+    #[tokio::test]
+    #[allow(unused_variables)]
+    async fn test_monitor_dont_wakeup_server_if_always_off(
+        clock: Arc<ManualClock>,
+        server_ip: IpAddr,
+        mut mocked_server_control: MockServerControl,
+        machine_ip: IpAddr,
+        machine: Machine,
+        dependencies: Dependencies,
+    ) {
+        // SETUP
+        let (mut sender, mut pinger) = default_mocks();
+
+        let machines = vec![machine];
+
+        // EXPECTATIONS
+        pinger.expect_add_target().returning(|_| true);
+        sender.expect_send().times(2).returning(|_| Ok(()));
+
+        mocked_server_control
+            .always_off
+            .expect_is_always_off()
+            .returning(|| true);
+
+        mocked_server_control
+            .always_on
+            .expect_is_always_on()
+            .returning(|| false);
+
+        pinger.expect_ping_once().returning(|| {});
+        pinger.expect_recv_pong().returning(|| Ok(()));
+        pinger
+            .expect_is_online()
+            .with(eq(server_ip))
+            .returning(|_| false);
+        pinger
+            .expect_is_online()
+            .with(eq(machine_ip))
+            .returning(|_| true);
+        sender.expect_send().once().return_once(|_| Ok(()));
+
+        mocked_server_control.wakeup.expect_wakeup().never();
+
+        // TESTING
+        let servers = vec![ServerControl::from(mocked_server_control)];
+
+        let mut monitor = Monitor::with_clock(
+            sender,
+            PING_INTERVAL,
+            servers,
+            machines,
+            dependencies,
+            pinger,
+            clock.clone(),
+        );
+
+        // advance FakeClock by at least ping interval (1s)
+        clock.advance(2 * PING_INTERVAL);
+
+        monitor.run_once().await;
+    }
+
+    #[rstest]
+    #[tokio::test]
+    #[allow(unused_variables)]
+    async fn test_monitor_dont_shutdown_server_if_always_on(
+        clock: Arc<ManualClock>,
+        server_ip: IpAddr,
+        mut mocked_server_control: MockServerControl,
+        machine_ip: IpAddr,
+        machine: Machine,
+        dependencies: Dependencies,
+    ) {
+        // SETUP
+        let (mut sender, mut pinger) = default_mocks();
+
+        let machines = vec![machine];
+
+        // EXPECTATIONS
+        pinger.expect_add_target().returning(|_| true);
+        sender.expect_send().times(2).returning(|_| Ok(()));
+
+        mocked_server_control
+            .always_off
+            .expect_is_always_off()
+            .returning(|| false);
+
+        mocked_server_control
+            .always_on
+            .expect_is_always_on()
+            .returning(|| true);
+
+        pinger.expect_ping_once().returning(|| {});
+        pinger.expect_recv_pong().returning(|| Ok(()));
+        pinger
+            .expect_is_online()
+            .with(eq(server_ip))
+            .returning(|_| true);
+        sender.expect_send().once().return_once(|_| Ok(()));
+        pinger
+            .expect_is_online()
+            .with(eq(machine_ip))
+            .returning(|_| false);
+
+        mocked_server_control.shutdown.expect_shutdown().never();
+
+        // TESTING
+        let servers = vec![ServerControl::from(mocked_server_control)];
+
+        let mut monitor = Monitor::with_clock(
+            sender,
+            PING_INTERVAL,
+            servers,
+            machines,
+            dependencies,
+            pinger,
+            clock.clone(),
+        );
+
+        // advance FakeClock by at least ping interval (1s)
+        clock.advance(2 * PING_INTERVAL);
+
+        monitor.run_once().await;
+    }
+
+    #[rstest]
+    #[tokio::test]
+    #[allow(unused_variables)]
+    async fn test_monitor_publishes_consecutive_failed_actions(
+        clock: Arc<ManualClock>,
+        server_ip: IpAddr,
+        mut mocked_server_control: MockServerControl,
+        machine_ip: IpAddr,
+        machine: Machine,
+        dependencies: Dependencies,
+    ) {
+        // SETUP
+        let (mut sender, mut pinger) = default_mocks();
+
+        let machines = vec![machine];
+
+        // EXPECTATIONS
+        pinger.expect_add_target().returning(|_| true);
+        sender.expect_send().times(2).returning(|_| Ok(()));
+
+        mocked_server_control
+            .always_off
+            .expect_is_always_off()
+            .returning(|| false);
+        mocked_server_control
+            .always_on
+            .expect_is_always_on()
+            .returning(|| false);
+
+        pinger.expect_ping_once().returning(|| {});
+        pinger.expect_recv_pong().returning(|| Ok(()));
+        pinger
+            .expect_is_online()
+            .with(eq(server_ip))
+            .returning(|_| false);
+        pinger
+            .expect_is_online()
+            .with(eq(machine_ip))
+            .returning(|_| true);
+        // the machine coming online is published
+        sender
+            .expect_send()
+            .withf(|device| matches!(&**device, Device::Machine(_)))
+            .once()
+            .return_once(|_| Ok(()));
+
+        mocked_server_control
+            .wakeup
+            .expect_wakeup()
+            .once()
+            .return_once(|_| Err(anyhow::anyhow!("failed")));
+
+        // the failed wakeup of the server is published
+        sender
+            .expect_send()
+            .withf(|device| match &**device {
+                Device::Server(server) => server.consecutive_failed_actions == 1,
+                Device::Machine(_) => false,
+            })
+            .once()
+            .return_once(|_| Ok(()));
+
+        // TESTING
+        let servers = vec![ServerControl::from(mocked_server_control)];
+
+        let mut monitor = Monitor::with_clock(
+            sender,
+            PING_INTERVAL,
+            servers,
+            machines,
+            dependencies,
+            pinger,
+            clock.clone(),
+        );
+
+        // advance FakeClock by at least ping interval (1s)
+        clock.advance(2 * PING_INTERVAL);
+
+        monitor.run_once().await;
+    }
+
+    #[rstest]
+    #[tokio::test]
+    #[allow(unused_variables)]
+    async fn test_monitored_server_updates_services(
+        clock: Arc<ManualClock>,
+        mut mocked_server_control: MockServerControl,
+    ) {
+        // SETUP
+        let listener = std::net::TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        let open_port = listener.local_addr().unwrap().port();
+        let closed_port = std::net::TcpListener::bind(("127.0.0.1", 0))
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+
+        let mut server = mocked_server_control.server.clone();
+        server.machine.ip = "127.0.0.1".parse().unwrap();
+        server.services = vec![
+            crate::dom::device::Service {
+                name: "open".to_string(),
+                check: ServiceCheck::Tcp(open_port),
+                is_online: false,
+            },
+            crate::dom::device::Service {
+                name: "closed".to_string(),
+                check: ServiceCheck::Tcp(closed_port),
+                is_online: false,
+            },
+        ];
+        mocked_server_control.server = server.clone();
+
+        let shared_server: SharedDevice = Arc::new(RwLock::new(Device::Server(server)));
+        let monitored_server = MonitoredServer::new(
+            ServerControl::from(mocked_server_control),
+            shared_server.clone(),
+            Vec::new(),
+            clock.now(),
+            clock.clone(),
+        );
+
+        let services_online = |device: &SharedDevice| match &*device.read().unwrap() {
+            Device::Server(server) => server
+                .services
+                .iter()
+                .map(|service| service.is_online)
+                .collect::<Vec<bool>>(),
+            Device::Machine(_) => panic!("expected a server"),
+        };
+
+        // TESTING
+        // services of an offline server are not checked
+        assert!(!monitored_server.update_services().await);
+        assert_eq!(services_online(&shared_server), vec![false, false]);
+
+        // services of an online server are checked
+        shared_server.write().unwrap().set_online(true);
+        assert!(monitored_server.update_services().await);
+        assert_eq!(services_online(&shared_server), vec![true, false]);
+
+        // nothing changes if the services are checked again
+        assert!(!monitored_server.update_services().await);
+
+        // all services go offline together with the server
+        shared_server.write().unwrap().set_online(false);
+        assert!(monitored_server.update_services().await);
+        assert_eq!(services_online(&shared_server), vec![false, false]);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    #[allow(unused_variables)]
+    async fn test_monitored_server_defers_shutdown_while_external_dependency_is_active(
+        clock: Arc<ManualClock>,
+        mut mocked_server_control: MockServerControl,
+    ) {
+        // SETUP
+        // the backup job reports that it is running exactly once
+        let listener = std::net::TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        let url = format!("http://{}/backup", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            use std::io::{Read, Write};
+
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0; 1024];
+            let _ = stream.read(&mut request).unwrap();
+            stream
+                .write_all(
+                    b"HTTP/1.1 200 OK\r\nContent-Length: 7\r\nConnection: close\r\n\r\nrunning",
+                )
+                .unwrap();
+        });
+
+        let cache = Duration::from_secs(60);
+        let mut server = mocked_server_control.server.clone();
+        server.machine.set_online(true);
+        server.external_dependencies = vec![crate::dom::device::ExternalDependency {
+            name: "backup".to_string(),
+            check: crate::dom::device::ExternalCheck::Http(crate::dom::device::HttpCheck {
+                url,
+                expected_status: 200,
+                expected_body: Some("running".to_string()),
+                timeout: Duration::from_secs(1),
+                insecure: false,
+            }),
+            cache,
+            is_active: false,
+        }];
+        mocked_server_control.server = server.clone();
+
+        // EXPECTATIONS
+        mocked_server_control
+            .always_off
+            .expect_is_always_off()
+            .returning(|| false);
+        mocked_server_control
+            .always_on
+            .expect_is_always_on()
+            .returning(|| false);
+        mocked_server_control
+            .shutdown
+            .expect_shutdown()
+            .once()
+            .return_once(|_| Ok(ActionReport::default()));
+
+        let shared_server: SharedDevice = Arc::new(RwLock::new(Device::Server(server)));
+        let mut monitored_server = MonitoredServer::new(
+            ServerControl::from(mocked_server_control),
+            shared_server.clone(),
+            Vec::new(),
+            clock.now(),
+            clock.clone(),
+        );
+        let cancellation = CancellationToken::new();
+
+        let dependency_active = |device: &SharedDevice| match &*device.read().unwrap() {
+            Device::Server(server) => server.external_dependencies[0].is_active,
+            Device::Machine(_) => panic!("expected a server"),
+        };
+
+        // TESTING
+        clock.advance(CHANGE_TIMEOUT + PING_INTERVAL);
+
+        // the shutdown is deferred while the backup job is running
+        assert!(monitored_server.process(&cancellation).await);
+        assert!(dependency_active(&shared_server));
+        assert!(!monitored_server.shut_down);
+
+        // the cached result keeps deferring the shutdown
+        assert!(!monitored_server.process(&cancellation).await);
+        assert!(!monitored_server.shut_down);
+
+        // once the cache expired the backup job is checked again and the server is shut down
+        clock.advance(cache + PING_INTERVAL);
+        assert!(monitored_server.process(&cancellation).await);
+        assert!(!dependency_active(&shared_server));
+        assert!(monitored_server.shut_down);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    #[allow(unused_variables)]
+    async fn test_monitored_server_defers_shutdown_during_blackout(
+        clock: Arc<ManualClock>,
+        mut mocked_server_control: MockServerControl,
+    ) {
+        // SETUP
+        let mut server = mocked_server_control.server.clone();
+        server.machine.set_online(true);
+        mocked_server_control.server = server.clone();
+
+        // the calendar contains a backup running right now
+        let calendar = Calendar::new(&["BACKUP".to_string()]);
+        let now = clock.utc_now();
+        let backup = CalendarEvent {
+            summary: "backup".to_string(),
+            categories: vec!["BACKUP".to_string()],
+            start: now - chrono::Duration::hours(1),
+            end: now + chrono::Duration::hours(1),
+        };
+        calendar.set_events(vec![backup]);
+
+        // EXPECTATIONS
+        mocked_server_control
+            .always_off
+            .expect_is_always_off()
+            .returning(|| false);
+        mocked_server_control
+            .always_on
+            .expect_is_always_on()
+            .returning(|| false);
+        mocked_server_control
+            .shutdown
+            .expect_shutdown()
+            .once()
+            .return_once(|_| Ok(ActionReport::default()));
+
+        let shared_server: SharedDevice = Arc::new(RwLock::new(Device::Server(server)));
+        let mut monitored_server = MonitoredServer::new(
+            ServerControl::from(mocked_server_control),
+            shared_server,
+            Vec::new(),
+            clock.now(),
+            clock.clone(),
+        );
+        monitored_server.blackouts = vec![Schedule::Calendar {
+            id: "calendar:backups".parse().unwrap(),
+            calendar: calendar.clone(),
+        }];
+        let cancellation = CancellationToken::new();
+
+        // TESTING
+        clock.advance(CHANGE_TIMEOUT + PING_INTERVAL);
+
+        // the shutdown is deferred during the backup
+        monitored_server.process(&cancellation).await;
+        assert!(!monitored_server.shut_down);
+
+        // once the backup is over the server is shut down
+        calendar.set_events(Vec::new());
+        monitored_server.process(&cancellation).await;
+        assert!(monitored_server.shut_down);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    #[allow(unused_variables)]
+    async fn test_monitored_server_defers_shutdown_while_away(
+        clock: Arc<ManualClock>,
+        mut mocked_server_control: MockServerControl,
+    ) {
+        // SETUP
+        let mut server = mocked_server_control.server.clone();
+        server.machine.set_online(true);
+        mocked_server_control.server = server.clone();
+
+        // the calendar contains a backup running right now
+        let calendar = Calendar::new(&["BACKUP".to_string()]);
+        let now = clock.utc_now();
+        let backup = CalendarEvent {
+            summary: "backup".to_string(),
+            categories: vec!["BACKUP".to_string()],
+            start: now - chrono::Duration::hours(1),
+            end: now + chrono::Duration::hours(1),
+        };
+        calendar.set_events(vec![backup]);
+
+        // EXPECTATIONS
+        mocked_server_control
+            .always_off
+            .expect_is_always_off()
+            .returning(|| false);
+        mocked_server_control
+            .always_on
+            .expect_is_always_on()
+            .returning(|| false);
+        mocked_server_control
+            .shutdown
+            .expect_shutdown()
+            .once()
+            .return_once(|_| Ok(ActionReport::default()));
+
+        let shared_server: SharedDevice = Arc::new(RwLock::new(Device::Server(server)));
+        let mut monitored_server = MonitoredServer::new(
+            ServerControl::from(mocked_server_control),
+            shared_server,
+            Vec::new(),
+            clock.now(),
+            clock.clone(),
+        );
+        monitored_server.blackouts = vec![Schedule::Calendar {
+            id: "calendar:backups".parse().unwrap(),
+            calendar: calendar.clone(),
+        }];
+        monitored_server.away = true;
+        let cancellation = CancellationToken::new();
+
+        // TESTING
+        clock.advance(CHANGE_TIMEOUT + PING_INTERVAL);
+
+        // the shutdown is deferred during the backup although everyone is away
+        monitored_server.process(&cancellation).await;
+        assert!(!monitored_server.shut_down);
+
+        // and during the recovery from a power outage
+        calendar.set_events(Vec::new());
+        monitored_server.recovering = true;
+        monitored_server.process(&cancellation).await;
+        assert!(!monitored_server.shut_down);
+
+        // once the recovery is over the server is shut down
+        monitored_server.recovering = false;
+        monitored_server.process(&cancellation).await;
+        assert!(monitored_server.shut_down);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_monitored_server_records_trigger_of_online_dependencies(
+        clock: Arc<ManualClock>,
+        mut mocked_server_control: MockServerControl,
+        machine: Machine,
+    ) {
+        // SETUP
+        let mut server = mocked_server_control.server.clone();
+        server.machine.set_online(true);
+        mocked_server_control.server = server.clone();
+        let mut offline_machine = machine.clone();
+        offline_machine.id = "offlinemachine".parse().unwrap();
+        let mut online_machine = machine;
+        online_machine.set_online(true);
+
+        // EXPECTATIONS
+        mocked_server_control
+            .always_off
+            .expect_is_always_off()
+            .returning(|| false);
+        mocked_server_control
+            .always_on
+            .expect_is_always_on()
+            .returning(|| false);
+        mocked_server_control.wakeup.expect_wakeup().never();
+        mocked_server_control.shutdown.expect_shutdown().never();
+
+        let shared_server: SharedDevice = Arc::new(RwLock::new(Device::Server(server)));
+        let mut monitored_server = MonitoredServer::new(
+            ServerControl::from(mocked_server_control),
+            shared_server.clone(),
+            vec![
+                Arc::new(RwLock::new(Device::Machine(online_machine.clone()))),
+                Arc::new(RwLock::new(Device::Machine(offline_machine))),
+            ],
+            clock.now(),
+            clock.clone(),
+        );
+        let cancellation = CancellationToken::new();
+
+        // TESTING
+        clock.advance(CHANGE_TIMEOUT + PING_INTERVAL);
+
+        // the online machine keeps the server online
+        assert!(monitored_server.process(&cancellation).await);
+        match &*shared_server.read().unwrap() {
+            Device::Server(server) => {
+                assert_eq!(server.last_triggers.len(), 1);
+                assert!(server.last_triggers.contains_key(&online_machine.id));
+            }
+            Device::Machine(_) => panic!("expected a server"),
+        }
+
+        // the last trigger isn't updated with every cycle
+        assert!(!monitored_server.process(&cancellation).await);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    #[allow(unused_variables)]
+    async fn test_monitored_server_stops_waking_up_once_budget_is_exhausted(
+        clock: Arc<ManualClock>,
+        mut mocked_server_control: MockServerControl,
+        mut machine: Machine,
+    ) {
+        // SETUP
+        machine.set_online(true);
+
+        // EXPECTATIONS
+        mocked_server_control
+            .always_off
+            .expect_is_always_off()
+            .returning(|| false);
+        mocked_server_control
+            .always_on
+            .expect_is_always_on()
+            .returning(|| false);
+        mocked_server_control.wakeup.expect_wakeup().never();
+        mocked_server_control.shutdown.expect_shutdown().never();
+
+        let mut server = mocked_server_control.server.clone();
+        server.max_daily_on_time = Some(Duration::from_secs(3600));
+        server.machine.set_online(true);
+        mocked_server_control.server = server.clone();
+
+        let shared_server: SharedDevice = Arc::new(RwLock::new(Device::Server(server)));
+        let mut monitored_server = MonitoredServer::new(
+            ServerControl::from(mocked_server_control),
+            shared_server.clone(),
+            vec![Arc::new(RwLock::new(Device::Machine(machine)))],
+            clock.now(),
+            clock.clone(),
+        );
+        let today = clock.local_now().date_naive();
+        let on_time_today = |device: &SharedDevice| match &*device.read().unwrap() {
+            Device::Server(server) => (server.on_time_today, server.is_budget_exhausted()),
+            Device::Machine(_) => panic!("expected a server"),
+        };
+
+        // TESTING
+        // the on-time is only updated once per minute
+        clock.advance(Duration::from_millis(30_000));
+        assert!(!monitored_server.update_on_time(today));
+        clock.advance(Duration::from_millis(3_570_000));
+        assert!(monitored_server.update_on_time(today));
+        assert_eq!(
+            on_time_today(&shared_server),
+            (Duration::from_secs(3600), true)
+        );
+
+        // the online dependency doesn't wake up the server anymore
+        shared_server.write().unwrap().set_online(false);
+        clock.advance(CHANGE_TIMEOUT);
+        monitored_server.process(&CancellationToken::new()).await;
+
+        // the budget is reset on the next day
+        assert!(monitored_server.update_on_time(today.succ_opt().unwrap()));
+        assert_eq!(on_time_today(&shared_server), (Duration::ZERO, false));
+    }
+
+    #[rstest]
+    #[tokio::test]
+    #[allow(unused_variables)]
+    async fn test_monitored_server_defers_wakeup_exceeding_stagger(
+        clock: Arc<ManualClock>,
+        mut mocked_server_control: MockServerControl,
+    ) {
+        // EXPECTATIONS
+        mocked_server_control
+            .always_off
+            .expect_is_always_off()
+            .returning(|| false);
+        mocked_server_control
+            .always_on
+            .expect_is_always_on()
+            .returning(|| true);
+        mocked_server_control
+            .wakeup
+            .expect_wakeup()
+            .once()
+            .returning(|_| Ok(ActionReport::default()));
+
+        // SETUP
+        let server = mocked_server_control.server.clone();
+        let shared_server: SharedDevice = Arc::new(RwLock::new(Device::Server(server)));
+        let mut monitored_server = MonitoredServer::new(
+            ServerControl::from(mocked_server_control),
+            shared_server.clone(),
+            Vec::new(),
+            clock.now().sub(CHANGE_TIMEOUT),
+            clock.clone(),
+        );
+
+        // another server has already been woken up in the current batch
+        let stagger = Arc::new(WakeupStagger::with_clock(
+            1,
+            Duration::from_secs(30),
+            clock.clone(),
+        ));
+        assert!(stagger.try_acquire().is_ok());
+        monitored_server.wakeup_stagger = Some(stagger);
+
+        let wakeup_deferral = |device: &SharedDevice| match &*device.read().unwrap() {
+            Device::Server(server) => server.remaining_wakeup_deferral(clock.utc_now()),
+            Device::Machine(_) => panic!("expected a server"),
+        };
+
+        // TESTING
+        clock.advance(Duration::from_millis(1));
+        assert!(monitored_server.process(&CancellationToken::new()).await);
+        assert!(wakeup_deferral(&shared_server) > Duration::ZERO);
+
+        // the server is woken up in the next batch
+        clock.advance(Duration::from_millis(30_000));
+        assert!(monitored_server.process(&CancellationToken::new()).await);
+        assert_eq!(wakeup_deferral(&shared_server), Duration::ZERO);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    #[allow(unused_variables)]
+    async fn test_monitored_server_applies_recovery_policy(
+        clock: Arc<ManualClock>,
+        mut mocked_server_control: MockServerControl,
+    ) {
+        // EXPECTATIONS
+        mocked_server_control
+            .always_off
+            .expect_is_always_off()
+            .returning(|| false);
+        mocked_server_control
+            .always_on
+            .expect_is_always_on()
+            .returning(|| false);
+        mocked_server_control
+            .wakeup
+            .expect_wakeup()
+            .once()
+            .returning(|_| Ok(ActionReport::default()));
+        mocked_server_control.shutdown.expect_shutdown().never();
+
+        // SETUP
+        let server = mocked_server_control.server.clone();
+        let shared_server: SharedDevice = Arc::new(RwLock::new(Device::Server(server)));
+        let mut monitored_server = MonitoredServer::new(
+            ServerControl::from(mocked_server_control),
+            shared_server.clone(),
+            Vec::new(),
+            clock.now().sub(CHANGE_TIMEOUT),
+            clock.clone(),
+        );
+
+        // TESTING
+        // the core server is woken up without any online dependency
+        monitored_server.recovering = true;
+        monitored_server.recovery_wakeup = true;
+        clock.advance(Duration::from_millis(1));
+        monitored_server.process(&CancellationToken::new()).await;
+
+        // and isn't shut down again during the recovery
+        shared_server.write().unwrap().set_online(true);
+        monitored_server.recovery_wakeup = false;
+        clock.advance(CHANGE_TIMEOUT);
+        monitored_server.process(&CancellationToken::new()).await;
+    }
+
+    #[rstest]
+    #[tokio::test]
+    #[allow(unused_variables)]
+    async fn test_monitored_server_keeps_server_online_for_min_on_duration(
+        clock: Arc<ManualClock>,
+        mut mocked_server_control: MockServerControl,
+        machine: Machine,
+    ) {
+        // SETUP
+        const MIN_ON_DURATION: Duration = Duration::from_secs(600);
+
+        // EXPECTATIONS
+        mocked_server_control
+            .always_off
+            .expect_is_always_off()
+            .returning(|| false);
+        mocked_server_control
+            .always_on
+            .expect_is_always_on()
+            .returning(|| false);
+        mocked_server_control.wakeup.expect_wakeup().never();
+        mocked_server_control
+            .shutdown
+            .expect_shutdown()
+            .once()
+            .return_once(|_| Ok(ActionReport::default()));
+
+        let mut server = mocked_server_control.server.clone();
+        server.min_on_duration = Some(MIN_ON_DURATION);
+        mocked_server_control.server = server.clone();
+
+        let shared_server: SharedDevice = Arc::new(RwLock::new(Device::Server(server)));
+        let mut monitored_server = MonitoredServer::new(
+            ServerControl::from(mocked_server_control),
+            shared_server.clone(),
+            vec![Arc::new(RwLock::new(Device::Machine(machine)))],
+            clock.now().sub(CHANGE_TIMEOUT),
+            clock.clone(),
+        );
+        let cancellation = CancellationToken::new();
+
+        // TESTING
+        // the server is offline and no dependency is online
+        assert!(!monitored_server.process(&cancellation).await);
+
+        // the server comes online but isn't shut down before its minimum on duration has passed
+        shared_server.write().unwrap().set_online(true);
+        clock.advance(CHANGE_TIMEOUT);
+        monitored_server.process(&cancellation).await;
+
+        clock.advance(MIN_ON_DURATION - Duration::from_millis(1));
+        monitored_server.process(&cancellation).await;
+
+        // once the minimum on duration has passed the server is shut down
+        clock.advance(Duration::from_millis(1));
+        monitored_server.process(&cancellation).await;
+    }
+
+    #[rstest]
+    #[case(true, false, false, true)]
+    #[case(false, true, false, true)]
+    #[case(false, false, false, false)]
+    #[case(true, false, true, false)]
+    fn test_monitored_server_detects_unexpected_online(
+        clock: Arc<ManualClock>,
+        mocked_server_control: MockServerControl,
+        #[case] shut_down: bool,
+        #[case] always_off_state: bool,
+        #[case] always_on_state: bool,
+        #[case] expected: bool,
+    ) {
+        // SETUP
+        let server = mocked_server_control.server.clone();
+        let shared_server: SharedDevice = Arc::new(RwLock::new(Device::Server(server)));
+        let mut monitored_server = MonitoredServer::new(
+            ServerControl::from(mocked_server_control),
+            shared_server.clone(),
+            Vec::new(),
+            clock.now(),
+            clock.clone(),
+        );
+        monitored_server.shut_down = shut_down;
+        monitored_server.always_off_state = always_off_state;
+        monitored_server.always_on_state = always_on_state;
+
+        let unexpected_onlines = |device: &SharedDevice| match &*device.read().unwrap() {
+            Device::Server(server) => server.unexpected_onlines,
+            Device::Machine(_) => panic!("expected a server"),
+        };
+
+        // TESTING
+        // the initial offline state without any missed pings isn't considered
+        shared_server.write().unwrap().set_online(true);
+        assert!(monitored_server.detect_unexpected_online().is_none());
+
+        // the server goes offline
+        shared_server.write().unwrap().set_online(false);
+        shared_server.write().unwrap().add_missed_ping();
+        assert!(monitored_server.detect_unexpected_online().is_none());
+
+        // and comes back online
+        shared_server.write().unwrap().set_online(true);
+        assert_eq!(
+            monitored_server.detect_unexpected_online().is_some(),
+            expected
+        );
+        assert_eq!(unexpected_onlines(&shared_server), expected as u64);
+
+        // staying online doesn't trigger again
+        assert!(monitored_server.detect_unexpected_online().is_none());
+    }
+
+    #[rstest]
+    #[case(false, false)]
+    #[case(true, true)]
+    fn test_monitored_server_verifies_shutdown(
+        clock: Arc<ManualClock>,
+        mocked_server_control: MockServerControl,
+        #[case] stays_online: bool,
+        #[case] expected: bool,
+    ) {
+        // SETUP
+        const SHUTDOWN_VERIFICATION: Duration = Duration::from_secs(60);
+
+        let mut server = mocked_server_control.server.clone();
+        server.shutdown_verification = Some(SHUTDOWN_VERIFICATION);
+        server.machine.set_online(true);
+        let shared_server: SharedDevice = Arc::new(RwLock::new(Device::Server(server)));
+        let mut monitored_server = MonitoredServer::new(
+            ServerControl::from(mocked_server_control),
+            shared_server.clone(),
+            Vec::new(),
+            clock.now(),
+            clock.clone(),
+        );
+        monitored_server.shut_down = true;
+        monitored_server.unverified_shutdown = Some(clock.now());
+
+        let consecutive_failed_actions = |device: &SharedDevice| match &*device.read().unwrap() {
+            Device::Server(server) => server.consecutive_failed_actions,
+            Device::Machine(_) => panic!("expected a server"),
+        };
+
+        // TESTING
+        // the server may take until the verification timeout to stop answering pings
+        clock.advance(SHUTDOWN_VERIFICATION);
+        assert!(!monitored_server.verify_shutdown());
+
+        if !stays_online {
+            shared_server.write().unwrap().add_missed_ping();
+        }
+        clock.advance(Duration::from_millis(1));
+        assert_eq!(monitored_server.verify_shutdown(), expected);
+        assert_eq!(monitored_server.shut_down, !expected);
+        assert_eq!(consecutive_failed_actions(&shared_server), expected as u64);
+
+        // the shutdown is only verified once
+        assert!(monitored_server.unverified_shutdown.is_none());
+        assert!(!monitored_server.verify_shutdown());
+    }
+
+    #[rstest]
+    fn test_monitored_server_expects_online_after_external_wakeup(
+        clock: Arc<ManualClock>,
+        mocked_server_control: MockServerControl,
+    ) {
+        // SETUP
+        let server = mocked_server_control.server.clone();
+        let shared_server: SharedDevice = Arc::new(RwLock::new(Device::Server(server)));
+        let mut monitored_server = MonitoredServer::new(
+            ServerControl::from(mocked_server_control),
+            shared_server.clone(),
+            Vec::new(),
+            clock.now(),
+            clock.clone(),
+        );
+        monitored_server.shut_down = true;
+
+        // TESTING
+        // the server has been shut down and is offline
+        shared_server.write().unwrap().set_online(false);
+        shared_server.write().unwrap().add_missed_ping();
+        assert!(monitored_server.detect_unexpected_online().is_none());
+
+        // another tool wakes up the server
+        monitored_server.record_external_wakeup();
+        assert!(!monitored_server.shut_down);
+        match &*shared_server.read().unwrap() {
+            Device::Server(server) => {
+                assert_eq!(server.external_wakeups, 1);
+                assert!(server.last_external_wakeup.is_some());
+            }
+            Device::Machine(_) => panic!("expected a server"),
+        }
+
+        // so it coming back online isn't unexpected
+        shared_server.write().unwrap().set_online(true);
+        assert!(monitored_server.detect_unexpected_online().is_none());
+    }
+
+    #[rstest]
+    fn test_monitored_server_resets_change_timeout(
+        clock: Arc<ManualClock>,
+        mocked_server_control: MockServerControl,
+    ) {
+        // SETUP
+        let server = mocked_server_control.server.clone();
+        let shared_server: SharedDevice = Arc::new(RwLock::new(Device::Server(server)));
+        let mut monitored_server = MonitoredServer::new(
+            ServerControl::from(mocked_server_control),
+            shared_server.clone(),
+            Vec::new(),
+            clock.now().sub(CHANGE_TIMEOUT),
+            clock.clone(),
+        );
+
+        let change_timeout = |device: &SharedDevice| match &*device.read().unwrap() {
+            Device::Server(server) => server.remaining_change_timeout(clock.utc_now()),
+            Device::Machine(_) => panic!("expected a server"),
+        };
+
+        // TESTING
+        // without a change there's no timeout to reset
+        clock.advance(Duration::from_millis(1));
+        assert!(!monitored_server.reset_change_timeout());
+        assert_eq!(change_timeout(&shared_server), Duration::ZERO);
+
+        // a wakeup starts the change timeout
+        monitored_server.record_external_wakeup();
+        assert!(change_timeout(&shared_server) > Duration::ZERO);
+
+        clock.advance(Duration::from_millis(1));
+        assert!(monitored_server.reset_change_timeout());
+        assert_eq!(change_timeout(&shared_server), Duration::ZERO);
+
+        // the server is processed right away again
+        clock.advance(Duration::from_millis(1));
+        assert!(monitored_server.clock.elapsed(monitored_server.last_change) > CHANGE_TIMEOUT);
+    }
+
+    #[rstest]
+    fn test_monitored_server_classifies_transitions(
+        clock: Arc<ManualClock>,
+        mocked_server_control: MockServerControl,
+    ) {
+        // SETUP
+        let server = mocked_server_control.server.clone();
+        let shared_server: SharedDevice = Arc::new(RwLock::new(Device::Server(server)));
+        let mut monitored_server = MonitoredServer::new(
+            ServerControl::from(mocked_server_control),
+            shared_server.clone(),
+            Vec::new(),
+            clock.now(),
+            clock.clone(),
+        );
+
+        let transitions = |device: &SharedDevice| match &*device.read().unwrap() {
+            Device::Server(server) => server.transitions.clone(),
+            Device::Machine(_) => panic!("expected a server"),
+        };
+
+        // TESTING
+        // the state observed first is not a transition
+        assert!(!monitored_server.update_online_state());
+        assert!(monitored_server.take_transition().is_none());
+
+        // an expected wakeup is attributed to its cause
+        monitored_server
+            .control
+            .expected_transition
+            .expect(true, TransitionCause::Api);
+        shared_server.write().unwrap().set_online(true);
+        assert!(monitored_server.update_online_state());
+        let transition = monitored_server.take_transition().unwrap();
+        assert!(transition.is_online);
+        assert_eq!(transition.cause, TransitionCause::Api);
+        assert!(monitored_server.take_transition().is_none());
+
+        // an unexpected shutdown is attributed to an external cause
+        shared_server.write().unwrap().set_online(false);
+        assert!(monitored_server.update_online_state());
+        let transition = monitored_server.take_transition().unwrap();
+        assert!(!transition.is_online);
+        assert_eq!(transition.cause, TransitionCause::External);
+
+        assert!(!monitored_server.update_online_state());
+        assert_eq!(transitions(&shared_server).len(), 2);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_monitored_server_disables_control_after_failed_shutdowns(
+        clock: Arc<ManualClock>,
+        mut mocked_server_control: MockServerControl,
+    ) {
+        // SETUP
+        mocked_server_control.server.max_failed_shutdowns = Some(2);
+        let mut server = mocked_server_control.server.clone();
+        server.machine.is_online = true;
+        let shared_server: SharedDevice = Arc::new(RwLock::new(Device::Server(server)));
+
+        // EXPECTATIONS
+        mocked_server_control
+            .always_off
+            .expect_is_always_off()
+            .returning(|| false);
+        mocked_server_control
+            .always_on
+            .expect_is_always_on()
+            .returning(|| false);
+        // the server isn't shut down while its control is disabled
+        mocked_server_control
+            .shutdown
+            .expect_shutdown()
+            .times(3)
+            .returning(|_| Err(ShutdownError::new("authentication failed".to_string())));
+
+        let mut monitored_server = MonitoredServer::new(
+            ServerControl::from(mocked_server_control),
+            shared_server.clone(),
+            Vec::new(),
+            clock.now().sub(CHANGE_TIMEOUT),
+            clock.clone(),
+        );
+        let cancellation = CancellationToken::new();
+        let is_control_disabled = |device: &SharedDevice| match &*device.read().unwrap() {
+            Device::Server(server) => server.is_control_disabled,
+            Device::Machine(_) => panic!("expected a server"),
+        };
+
+        // TESTING
+        clock.advance(Duration::from_millis(1));
+        assert!(monitored_server.process(&cancellation).await);
+        assert!(monitored_server.take_control_disabled().is_none());
+
+        assert!(monitored_server.process(&cancellation).await);
+        assert_eq!(monitored_server.take_control_disabled(), Some(2));
+        assert!(monitored_server.control.control_switch.is_disabled());
+        assert!(is_control_disabled(&shared_server));
+
+        assert!(!monitored_server.process(&cancellation).await);
+
+        // once re-enabled the server is shut down again
+        assert!(monitored_server.control.control_switch.enable());
+        assert!(monitored_server.process(&cancellation).await);
+        assert!(!is_control_disabled(&shared_server));
+        assert!(monitored_server.take_control_disabled().is_none());
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_monitor_notifies_about_control_disabled(server: Server) {
+        let mut notifier = crate::notifications::MockNotifier::new();
+        notifier
+            .expect_notify()
+            .withf(|notification| {
+                notification.event == NotificationEvent::ControlDisabled
+                    && notification.message.contains("3 times")
+            })
+            .once()
+            .returning(|_| Ok(()));
+        let notifier: Option<Arc<dyn Notifier>> = Some(Arc::new(notifier));
+
+        Monitor::notify_control_disabled(&notifier, &server, 3).await;
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_monitor_notifies_about_unexpected_online(server: Server) {
+        let mut notifier = crate::notifications::MockNotifier::new();
+        notifier
+            .expect_notify()
+            .withf(|notification| {
+                notification.event == NotificationEvent::UnexpectedOnline
+                    && notification.message.contains("after 2m 5s")
+            })
+            .once()
+            .returning(|_| Ok(()));
+        let notifier: Option<Arc<dyn Notifier>> = Some(Arc::new(notifier));
+
+        Monitor::notify_unexpected_online(&notifier, &server, Duration::from_secs(125)).await;
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_monitor_notifies_about_failed_shutdown(server: Server) {
+        let mut notifier = crate::notifications::MockNotifier::new();
+        notifier
+            .expect_notify()
+            .withf(|notification| notification.event == NotificationEvent::ShutdownFailed)
+            .once()
+            .returning(|_| Ok(()));
+        let notifier: Option<Arc<dyn Notifier>> = Some(Arc::new(notifier));
+
+        Monitor::notify_failed_shutdown(&notifier, &server).await;
+    }
+
+    #[rstest]
+    #[tokio::test]
+    #[allow(unused_variables)]
+    async fn test_monitor_adds_guest_and_removes_it_once_ttl_expired(
+        clock: Arc<ManualClock>,
+        mut mocked_server_control: MockServerControl,
+        machine: Machine,
+        dependencies: Dependencies,
+    ) {
+        // SETUP
+        let (mut sender, mut pinger) = default_mocks();
+
+        let machines = vec![machine];
+
+        let guest_id: DeviceId = "guest".parse().unwrap();
+        let guest_ip: IpAddr = "10.0.0.99".parse().unwrap();
+        let guest_ttl = Duration::from_secs(60);
+
+        // EXPECTATIONS
+        pinger
+            .expect_add_target()
+            .with(ne(guest_ip))
+            .returning(|_| true);
+        pinger
+            .expect_add_target()
+            .with(eq(guest_ip))
+            .once()
+            .returning(|_| true);
+        pinger
+            .expect_remove_target()
+            .with(eq(guest_ip))
+            .once()
+            .returning(|_| true);
+        pinger.expect_ping_once().returning(|| {});
+        pinger.expect_recv_pong().returning(|| Ok(()));
+        pinger.expect_is_online().returning(|_| false);
+
+        mocked_server_control
+            .always_off
+            .expect_is_always_off()
+            .returning(|| false);
+        mocked_server_control
+            .always_on
+            .expect_is_always_on()
+            .returning(|| false);
+
+        {
+            let guest_id = guest_id.clone();
+            sender
+                .expect_send()
+                .withf(move |device| *device.id() == guest_id)
+                .once()
+                .returning(|_| Ok(()));
+        }
+        sender.expect_send().returning(|_| Ok(()));
+        sender
+            .expect_send_removal()
+            .with(eq(guest_id.clone()))
+            .once()
+            .returning(|_| Ok(()));
+
+        // TESTING
+        let servers = vec![ServerControl::from(mocked_server_control)];
+
+        let mut monitor = Monitor::with_clock(
+            sender,
+            PING_INTERVAL,
+            servers,
+            machines,
+            dependencies,
+            pinger,
+            clock.clone(),
+        );
+
+        let (guest_sender, guest_receiver) = communication::guest_channel();
+        monitor.set_guest_receiver(guest_receiver);
+
+        guest_sender
+            .send(Guest::new(
+                Machine::new(&guest_id, "Guest", guest_ip, MACHINE_LAST_SEEN_TIMEOUT),
+                vec![],
+                guest_ttl,
+            ))
+            .unwrap();
+
+        monitor.run_once().await;
+        assert!(monitor
+            .devices
+            .iter()
+            .any(|device| *device.read().unwrap().id() == guest_id));
+        assert!(monitor.servers[0]
+            .devices
+            .iter()
+            .any(|device| *device.read().unwrap().id() == guest_id));
+
+        // advance FakeClock beyond the TTL of the guest
+        clock.advance(2 * guest_ttl);
+
+        monitor.run_once().await;
+        assert!(!monitor
+            .devices
+            .iter()
+            .any(|device| *device.read().unwrap().id() == guest_id));
+        assert!(!monitor.servers[0]
+            .devices
+            .iter()
+            .any(|device| *device.read().unwrap().id() == guest_id));
+    }
+
+    #[rstest]
+    #[allow(unused_variables)]
+    fn test_monitor_replaces_dependencies_of_activated_profile(
+        clock: Arc<ManualClock>,
+        mocked_server_control: MockServerControl,
+        machine: Machine,
+        dependencies: Dependencies,
+    ) {
+        // SETUP
+        let (mut sender, mut pinger) = default_mocks();
+
+        // EXPECTATIONS
+        pinger.expect_add_target().returning(|_| true);
+        sender.expect_send().returning(|_| Ok(()));
+
+        // TESTING
+        let servers = vec![ServerControl::from(mocked_server_control)];
+
+        let mut monitor = Monitor::with_clock(
+            sender,
+            PING_INTERVAL,
+            servers,
+            vec![machine],
+            dependencies.clone(),
+            pinger,
+            clock.clone(),
+        );
+
+        let profiles = Arc::new(communication::Profiles::new(
+            communication::Profile {
+                dependencies,
+                ..Default::default()
+            },
+            [("vacation".to_string(), communication::Profile::default())]
+                .iter()
+                .cloned()
+                .collect(),
+        ));
+        monitor.set_profiles(profiles.clone());
+
+        // the server doesn't depend on anything while on vacation
+        assert!(profiles.activate(Some("vacation")));
+        monitor.process_profile_change();
+        assert!(monitor.servers[0].devices.is_empty());
+
+        assert!(profiles.activate(None));
+        monitor.process_profile_change();
+        assert_eq!(monitor.servers[0].devices.len(), 1);
     }
 }
@@ -0,0 +1,211 @@
+use std::net::IpAddr;
+use std::process::Command;
+
+use anyhow::{anyhow, bail};
+use log::{debug, warn};
+use serde::Serialize;
+
+use super::super::configuration::{Hook, HookEvent, HookTarget};
+use super::super::dom::{ActionContext, Server};
+use super::{agent_builder, run_blocking};
+
+/// Context of a wakeup or shutdown passed to its hooks.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct HookPayload {
+    event: HookEvent,
+    server_id: String,
+    server_name: String,
+    server_ip: IpAddr,
+    trace_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    success: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl HookPayload {
+    fn environment(&self) -> Vec<(&'static str, String)> {
+        let mut environment = vec![
+            ("HOME_MONITOR_EVENT", self.event.to_string()),
+            ("HOME_MONITOR_SERVER_ID", self.server_id.clone()),
+            ("HOME_MONITOR_SERVER_NAME", self.server_name.clone()),
+            ("HOME_MONITOR_SERVER_IP", self.server_ip.to_string()),
+            ("HOME_MONITOR_TRACE_ID", self.trace_id.clone()),
+        ];
+        if let Some(success) = self.success {
+            environment.push(("HOME_MONITOR_SUCCESS", success.to_string()));
+        }
+        if let Some(error) = &self.error {
+            environment.push(("HOME_MONITOR_ERROR", error.clone()));
+        }
+
+        environment
+    }
+}
+
+/// Commands and webhooks run before and after the wakeups and shutdowns of the servers.
+pub struct ActionHooks {
+    agent: ureq::Agent,
+    hooks: Vec<Hook>,
+}
+
+impl ActionHooks {
+    pub fn new(hooks: &[Hook], proxy: Option<&str>) -> Self {
+        Self {
+            agent: agent_builder(proxy).build(),
+            hooks: hooks.to_vec(),
+        }
+    }
+
+    /// Runs all hooks of the given event and only fails if a fatal hook failed. The result of the
+    /// action is passed to the hooks run after it.
+    pub async fn run(
+        &self,
+        event: HookEvent,
+        server: &Server,
+        context: &ActionContext,
+        result: Option<Result<(), String>>,
+    ) -> anyhow::Result<()> {
+        let payload = HookPayload {
+            event,
+            server_id: server.machine.id.to_string(),
+            server_name: server.machine.name.clone(),
+            server_ip: server.machine.ip,
+            trace_id: context.trace.id().to_string(),
+            success: result.as_ref().map(Result::is_ok),
+            error: result.and_then(Result::err),
+        };
+
+        for hook in self
+            .hooks
+            .iter()
+            .filter(|hook| hook.events.contains(&event))
+        {
+            let agent = self.agent.clone();
+            let target = hook.target.clone();
+            let hook_payload = payload.clone();
+            if let Err(e) =
+                run_blocking(context, move || run_hook(&agent, &target, &hook_payload)).await
+            {
+                warn!(
+                    "{} hook of {} failed [{}]: {}",
+                    event, server.machine.name, context.trace, e
+                );
+                if hook.fatal {
+                    bail!("{} hook failed: {}", event, e);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn run_hook(agent: &ureq::Agent, target: &HookTarget, payload: &HookPayload) -> anyhow::Result<()> {
+    match target {
+        HookTarget::Command { command } => {
+            let (program, args) = command
+                .split_first()
+                .ok_or_else(|| anyhow!("the hook has an empty command"))?;
+            debug!("running {} hook {}", payload.event, program);
+            let status = Command::new(program)
+                .args(args)
+                .envs(payload.environment())
+                .status()?;
+            if !status.success() {
+                bail!("{} exited with {}", program, status);
+            }
+        }
+        HookTarget::Webhook { url } => {
+            debug!("sending {} hook to {}", payload.event, url);
+            agent
+                .post(url)
+                .set("Content-Type", "application/json")
+                .send_string(&serde_json::to_string(payload)?)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use rstest::*;
+    use tokio_util::sync::CancellationToken;
+
+    use super::*;
+    use crate::dom::device::test::*;
+    use crate::dom::TraceContext;
+
+    fn context() -> ActionContext {
+        ActionContext::new(
+            TraceContext::from("trace"),
+            CancellationToken::new(),
+            Duration::from_secs(10),
+        )
+    }
+
+    fn hook(command: &str, fatal: bool) -> Hook {
+        Hook {
+            events: vec![HookEvent::PostWakeup],
+            target: HookTarget::Command {
+                command: vec!["sh".to_string(), "-c".to_string(), command.to_string()],
+            },
+            fatal,
+        }
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_action_hooks_pass_context_in_environment(server: Server) {
+        let command = format!(
+            r#"test "$HOME_MONITOR_EVENT" = postWakeup && test "$HOME_MONITOR_SERVER_ID" = "{}" && test "$HOME_MONITOR_TRACE_ID" = trace && test "$HOME_MONITOR_SUCCESS" = false && test "$HOME_MONITOR_ERROR" = unreachable"#,
+            server.machine.id
+        );
+        let hooks = ActionHooks::new(&[hook(&command, true)], None);
+
+        let result = hooks
+            .run(
+                HookEvent::PostWakeup,
+                &server,
+                &context(),
+                Some(Err("unreachable".to_string())),
+            )
+            .await;
+
+        assert!(result.is_ok());
+    }
+
+    #[rstest]
+    #[case(false, true)]
+    #[case(true, false)]
+    #[tokio::test]
+    async fn test_action_hooks_only_fail_for_fatal_hooks(
+        server: Server,
+        #[case] fatal: bool,
+        #[case] expected: bool,
+    ) {
+        let hooks = ActionHooks::new(&[hook("exit 1", fatal)], None);
+
+        let result = hooks
+            .run(HookEvent::PostWakeup, &server, &context(), Some(Ok(())))
+            .await;
+
+        assert_eq!(result.is_ok(), expected);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_action_hooks_only_run_hooks_of_event(server: Server) {
+        let hooks = ActionHooks::new(&[hook("exit 1", true)], None);
+
+        let result = hooks
+            .run(HookEvent::PreShutdown, &server, &context(), None)
+            .await;
+
+        assert!(result.is_ok());
+    }
+}
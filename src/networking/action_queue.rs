@@ -0,0 +1,161 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use super::super::utils::Instant;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Action {
+    Wakeup,
+    Shutdown,
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Wakeup => "wakeup",
+            Self::Shutdown => "shutdown",
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ActionInProgress {
+    pub action: Action,
+    pub server: String,
+}
+
+impl std::error::Error for ActionInProgress {}
+
+impl fmt::Display for ActionInProgress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} of {} is already in progress",
+            self.action, self.server
+        )
+    }
+}
+
+enum ActionState {
+    Running,
+    Succeeded(Instant),
+}
+
+/// Keeps track of the wakeups and shutdowns of a server requested by the monitoring, the web API
+/// and the DNS trigger to not run the same action multiple times simultaneously or again right
+/// after it succeeded.
+pub struct ActionQueue {
+    server: String,
+    window: Duration,
+    actions: Mutex<HashMap<Action, ActionState>>,
+}
+
+impl ActionQueue {
+    pub fn new(server: &str, window: Duration) -> Self {
+        Self {
+            server: server.to_string(),
+            window,
+            actions: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Starts the given action unless it is already running or succeeded within the window.
+    pub fn start(&self, action: Action) -> Result<ActionGuard<'_>, ActionInProgress> {
+        let mut actions = self.actions.lock().unwrap();
+        let is_duplicate = match actions.get(&action) {
+            Some(ActionState::Running) => true,
+            Some(ActionState::Succeeded(finished)) => finished.elapsed() < self.window,
+            None => false,
+        };
+        if is_duplicate {
+            return Err(ActionInProgress {
+                action,
+                server: self.server.clone(),
+            });
+        }
+
+        actions.insert(action, ActionState::Running);
+        Ok(ActionGuard {
+            queue: self,
+            action,
+            succeeded: false,
+        })
+    }
+}
+
+/// Marks the action as finished when dropped. Only successful actions deduplicate further
+/// requests within the window so that a failed action can be retried right away.
+pub struct ActionGuard<'a> {
+    queue: &'a ActionQueue,
+    action: Action,
+    succeeded: bool,
+}
+
+impl ActionGuard<'_> {
+    pub fn succeeded(&mut self) {
+        self.succeeded = true;
+    }
+}
+
+impl Drop for ActionGuard<'_> {
+    fn drop(&mut self) {
+        let mut actions = self.queue.actions.lock().unwrap();
+        if self.succeeded {
+            actions.insert(self.action, ActionState::Succeeded(Instant::now()));
+        } else {
+            actions.remove(&self.action);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::*;
+
+    use super::*;
+
+    static SERVER: &str = "Server";
+    const WINDOW: Duration = Duration::from_secs(10);
+
+    #[rstest]
+    fn test_action_queue_deduplicates_running_action() {
+        let queue = ActionQueue::new(SERVER, WINDOW);
+
+        let _wakeup = queue.start(Action::Wakeup).unwrap();
+
+        assert_eq!(
+            queue.start(Action::Wakeup).err(),
+            Some(ActionInProgress {
+                action: Action::Wakeup,
+                server: SERVER.to_string()
+            })
+        );
+        // other actions aren't affected
+        assert!(queue.start(Action::Shutdown).is_ok());
+    }
+
+    #[rstest]
+    fn test_action_queue_deduplicates_succeeded_action_within_window() {
+        let queue = ActionQueue::new(SERVER, WINDOW);
+
+        queue.start(Action::Shutdown).unwrap().succeeded();
+        assert!(queue.start(Action::Shutdown).is_err());
+
+        queue.actions.lock().unwrap().insert(
+            Action::Shutdown,
+            ActionState::Succeeded(Instant::now() - WINDOW),
+        );
+        assert!(queue.start(Action::Shutdown).is_ok());
+    }
+
+    #[rstest]
+    fn test_action_queue_allows_retrying_failed_action() {
+        let queue = ActionQueue::new(SERVER, WINDOW);
+
+        drop(queue.start(Action::Wakeup).unwrap());
+
+        assert!(queue.start(Action::Wakeup).is_ok());
+    }
+}
@@ -0,0 +1,31 @@
+use std::fmt;
+use std::time::Duration;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ActionReport {
+    pub duration: Duration,
+    pub attempts: u32,
+    pub detail: String,
+}
+
+impl ActionReport {
+    pub fn new(duration: Duration, attempts: u32, detail: String) -> Self {
+        Self {
+            duration,
+            attempts,
+            detail,
+        }
+    }
+}
+
+impl fmt::Display for ActionReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} ({} attempt(s) in {}ms)",
+            self.detail,
+            self.attempts,
+            self.duration.as_millis()
+        )
+    }
+}
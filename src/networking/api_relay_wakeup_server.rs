@@ -0,0 +1,171 @@
+use std::time::Instant;
+
+use async_trait::async_trait;
+use log::{debug, warn};
+
+use super::super::dom::{self, ActionContext};
+use super::super::utils::Secret;
+use super::{run_blocking, ActionReport, WakeupServer};
+
+pub struct ApiRelayWakeupServer {
+    name: String,
+    url: String,
+    token: Option<Secret>,
+}
+
+impl ApiRelayWakeupServer {
+    pub fn new(server: &dom::Server, relay: &dom::device::ApiRelay) -> Self {
+        // use the ID of the server on the relay if configured otherwise assume the same ID
+        let server_id = relay.server.as_ref().unwrap_or(&server.machine.id);
+
+        Self {
+            name: server.machine.name.to_string(),
+            url: format!(
+                "{}/api/v1/server/{}/wakeup",
+                relay.url.trim_end_matches('/'),
+                server_id
+            ),
+            token: relay.token.clone(),
+        }
+    }
+}
+
+#[async_trait]
+impl WakeupServer for ApiRelayWakeupServer {
+    async fn wakeup(&self, context: &ActionContext) -> anyhow::Result<ActionReport> {
+        debug!(
+            "[{}] relaying wakeup request for {} to {}",
+            context, self.name, self.url
+        );
+        let start = Instant::now();
+
+        // don't leave the request hanging once the action has exceeded its deadline
+        let timeout = context
+            .deadline
+            .saturating_duration_since(tokio::time::Instant::now());
+
+        // forward the trace ID so the relay logs can be correlated as well
+        let mut request = ureq::put(&self.url)
+            .timeout(timeout)
+            .set("X-Request-Id", context.trace.id());
+        if let Some(token) = &self.token {
+            request = request.set("Authorization", &format!("Bearer {}", token.expose()));
+        }
+        match run_blocking(context, move || {
+            request
+                .call()
+                .map(|response| response.status())
+                .map_err(anyhow::Error::from)
+        })
+        .await
+        {
+            Err(e) => {
+                warn!(
+                    "[{}] failed to relay wakeup request for {} to {}: {}",
+                    context, self.name, self.url, e
+                );
+                Err(e)
+            }
+            Ok(status) => Ok(ActionReport::new(
+                start.elapsed(),
+                1,
+                format!("relayed wakeup request to {} ({})", self.url, status),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    use rstest::*;
+    use tokio_util::sync::CancellationToken;
+
+    use super::*;
+    use crate::dom::device::test::*;
+    use crate::dom::TraceContext;
+
+    fn serve_once(status: &'static str) -> (String, mpsc::Receiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0; 4096];
+            let size = stream.read(&mut request).unwrap();
+            tx.send(String::from_utf8_lossy(&request[..size]).to_string())
+                .unwrap();
+
+            let response = format!(
+                "HTTP/1.1 {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                status
+            );
+            stream.write_all(response.as_bytes()).unwrap();
+        });
+
+        (url, rx)
+    }
+
+    fn context() -> ActionContext {
+        ActionContext::new(
+            TraceContext::new(),
+            CancellationToken::new(),
+            Duration::from_secs(10),
+        )
+    }
+
+    fn relay(url: String, token: Option<&str>) -> dom::device::ApiRelay {
+        dom::device::ApiRelay {
+            url,
+            server: Some("relayed".parse().unwrap()),
+            token: token.map(Secret::from),
+        }
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_api_relay_wakeup_server_sends_wakeup_request(server: dom::Server) {
+        let (url, requests) = serve_once("202 Accepted");
+        let wakeup_server = ApiRelayWakeupServer::new(&server, &relay(url, Some("secret")));
+
+        assert!(wakeup_server.wakeup(&context()).await.is_ok());
+
+        let request = requests.recv().unwrap();
+        assert!(request.starts_with("PUT /api/v1/server/relayed/wakeup "));
+        assert!(request.contains("Authorization: Bearer secret\r\n"));
+        assert!(request.contains("X-Request-Id: "));
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_api_relay_wakeup_server_fails_on_error_status(server: dom::Server) {
+        let (url, requests) = serve_once("401 Unauthorized");
+        let wakeup_server = ApiRelayWakeupServer::new(&server, &relay(url, None));
+
+        assert!(wakeup_server.wakeup(&context()).await.is_err());
+
+        let request = requests.recv().unwrap();
+        assert!(!request.contains("Authorization"));
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_api_relay_wakeup_server_times_out_at_deadline(server: dom::Server) {
+        // accept the connection but never respond
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let wakeup_server = ApiRelayWakeupServer::new(&server, &relay(url, None));
+
+        let context = ActionContext::new(
+            TraceContext::new(),
+            CancellationToken::new(),
+            Duration::from_millis(200),
+        );
+        assert!(wakeup_server.wakeup(&context).await.is_err());
+    }
+}
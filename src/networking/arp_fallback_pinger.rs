@@ -0,0 +1,182 @@
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::mpsc::RecvError;
+use std::sync::Arc;
+
+use log::debug;
+use pnet::ipnetwork::IpNetwork;
+
+use super::{is_on_subnet, ArpProber, ArpResponders, Pinger};
+
+/// Probes devices on the local network with ARP requests once they missed a number of
+/// consecutive pings (e.g. because a firewall blocks ICMP).
+pub struct ArpFallbackPinger {
+    pinger: Box<dyn Pinger>,
+    prober: Box<dyn ArpProber>,
+    responders: Arc<ArpResponders>,
+    missed_pings: u32,
+    networks: Vec<IpNetwork>,
+    targets: HashMap<IpAddr, u32>,
+}
+
+impl ArpFallbackPinger {
+    pub fn new(
+        pinger: Box<dyn Pinger>,
+        prober: Box<dyn ArpProber>,
+        responders: Arc<ArpResponders>,
+        missed_pings: u32,
+        networks: Vec<IpNetwork>,
+    ) -> Self {
+        Self {
+            pinger,
+            prober,
+            responders,
+            missed_pings,
+            networks,
+            targets: HashMap::new(),
+        }
+    }
+}
+
+impl Pinger for ArpFallbackPinger {
+    fn add_target(&mut self, ip_addr: IpAddr) -> bool {
+        let added = self.pinger.add_target(ip_addr);
+        // ARP requests don't reach devices outside the subnets of the probed interface
+        if added && is_on_subnet(&self.networks, &ip_addr) {
+            self.targets.insert(ip_addr, 0);
+        }
+
+        added
+    }
+
+    fn remove_target(&mut self, ip_addr: &IpAddr) -> bool {
+        let removed = self.pinger.remove_target(ip_addr);
+        if removed {
+            self.targets.remove(ip_addr);
+            self.responders.set_responding(*ip_addr, false);
+        }
+
+        removed
+    }
+
+    fn ping_once(&self) {
+        self.pinger.ping_once()
+    }
+
+    fn recv_pong(&mut self) -> Result<(), RecvError> {
+        self.pinger.recv_pong()?;
+
+        let mut silent = Vec::new();
+        for (ip_addr, missed_pings) in self.targets.iter_mut() {
+            if self.pinger.is_online(ip_addr) {
+                *missed_pings = 0;
+                self.responders.set_responding(*ip_addr, false);
+            } else {
+                *missed_pings = missed_pings.saturating_add(1);
+                if *missed_pings >= self.missed_pings {
+                    silent.push(*ip_addr);
+                }
+            }
+        }
+
+        if !silent.is_empty() {
+            debug!(
+                "probing {} silent devices with ARP requests...",
+                silent.len()
+            );
+            let responders = self.prober.probe(&silent);
+            for ip_addr in silent {
+                self.responders
+                    .set_responding(ip_addr, responders.contains(&ip_addr));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn is_online(&self, ip_addr: &IpAddr) -> bool {
+        self.pinger.is_online(ip_addr) || self.responders.is_responding(ip_addr)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    use mockall::predicate::*;
+    use rstest::*;
+
+    use super::*;
+    use crate::networking::{MockArpProber, MockPinger};
+
+    #[rstest]
+    fn test_arp_fallback_pinger_probes_silent_devices() {
+        // SETUP
+        let pinging: IpAddr = "192.168.1.2".parse().unwrap();
+        let silent: IpAddr = "192.168.1.3".parse().unwrap();
+        let off_subnet: IpAddr = "192.168.20.3".parse().unwrap();
+
+        let answers_pings = Arc::new(AtomicBool::new(false));
+        let mut pinger = MockPinger::new();
+        pinger.expect_add_target().return_const(true);
+        pinger.expect_ping_once().return_const(());
+        pinger.expect_recv_pong().returning(|| Ok(()));
+        pinger
+            .expect_is_online()
+            .with(eq(pinging))
+            .return_const(true);
+        {
+            let answers_pings = answers_pings.clone();
+            pinger
+                .expect_is_online()
+                .with(eq(silent))
+                .returning(move |_| answers_pings.load(Ordering::SeqCst));
+        }
+        pinger
+            .expect_is_online()
+            .with(eq(off_subnet))
+            .return_const(false);
+
+        // EXPECTATIONS
+        // only the silent device on the subnet is probed and only once it missed two pings
+        let mut prober = MockArpProber::new();
+        prober
+            .expect_probe()
+            .withf(move |ips| ips == [silent])
+            .times(2)
+            .returning(|ips| ips.iter().copied().collect::<HashSet<IpAddr>>());
+
+        // TESTING
+        let responders = Arc::new(ArpResponders::new());
+        let mut arp_pinger = ArpFallbackPinger::new(
+            Box::new(pinger),
+            Box::new(prober),
+            responders.clone(),
+            2,
+            vec!["192.168.1.10/24".parse().unwrap()],
+        );
+        assert!(arp_pinger.add_target(pinging));
+        assert!(arp_pinger.add_target(silent));
+        assert!(arp_pinger.add_target(off_subnet));
+
+        arp_pinger.recv_pong().unwrap();
+        assert!(arp_pinger.is_online(&pinging));
+        assert!(!arp_pinger.is_online(&silent));
+
+        arp_pinger.recv_pong().unwrap();
+        assert!(arp_pinger.is_online(&silent));
+        assert!(responders.is_responding(&silent));
+        assert!(!responders.is_responding(&pinging));
+        assert!(!arp_pinger.is_online(&off_subnet));
+
+        arp_pinger.recv_pong().unwrap();
+        assert!(responders.is_responding(&silent));
+
+        // the device answers pings again
+        answers_pings.store(true, Ordering::SeqCst);
+        arp_pinger.recv_pong().unwrap();
+        assert!(arp_pinger.is_online(&silent));
+        assert!(!responders.is_responding(&silent));
+    }
+}
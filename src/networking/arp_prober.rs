@@ -0,0 +1,172 @@
+use std::collections::HashSet;
+use std::net::{IpAddr, Ipv4Addr};
+use std::time::{Duration, Instant};
+
+#[cfg(test)]
+use mockall::automock;
+
+use log::warn;
+use pnet::datalink::{self, Channel, NetworkInterface};
+use pnet::ipnetwork::IpNetwork;
+use pnet::packet::arp::{ArpHardwareTypes, ArpOperations, ArpPacket, MutableArpPacket};
+use pnet::packet::ethernet::{EtherTypes, EthernetPacket, MutableEthernetPacket};
+use pnet::packet::{MutablePacket, Packet};
+use pnet::util::MacAddr;
+
+const ETHERNET_HEADER_LEN: usize = 14;
+const ARP_PACKET_LEN: usize = 28;
+
+#[cfg_attr(test, automock)]
+pub trait ArpProber: Send {
+    /// Sends an ARP request to all given IP addresses on the local network and returns the ones
+    /// which answered.
+    fn probe(&self, ips: &[IpAddr]) -> HashSet<IpAddr>;
+}
+
+/// Sends ARP requests through a raw socket on the configured network interface (which requires
+/// the privileges to open raw sockets).
+pub struct NetworkArpProber {
+    interface: NetworkInterface,
+    timeout: Duration,
+}
+
+impl NetworkArpProber {
+    pub fn new(interface: NetworkInterface, timeout: Duration) -> Self {
+        Self { interface, timeout }
+    }
+
+    /// Returns the address of the interface in the same network as the given IP address.
+    fn source_ip(&self, ip: &Ipv4Addr) -> Option<Ipv4Addr> {
+        self.interface.ips.iter().find_map(|network| match network {
+            IpNetwork::V4(network) if network.contains(*ip) => Some(network.ip()),
+            _ => None,
+        })
+    }
+
+    fn request(source_mac: MacAddr, source_ip: Ipv4Addr, target_ip: Ipv4Addr) -> Vec<u8> {
+        let mut buffer = vec![0u8; ETHERNET_HEADER_LEN + ARP_PACKET_LEN];
+        let mut ethernet = MutableEthernetPacket::new(&mut buffer).unwrap();
+        ethernet.set_destination(MacAddr::broadcast());
+        ethernet.set_source(source_mac);
+        ethernet.set_ethertype(EtherTypes::Arp);
+
+        let mut arp = MutableArpPacket::new(ethernet.payload_mut()).unwrap();
+        arp.set_hardware_type(ArpHardwareTypes::Ethernet);
+        arp.set_protocol_type(EtherTypes::Ipv4);
+        arp.set_hw_addr_len(6);
+        arp.set_proto_addr_len(4);
+        arp.set_operation(ArpOperations::Request);
+        arp.set_sender_hw_addr(source_mac);
+        arp.set_sender_proto_addr(source_ip);
+        arp.set_target_hw_addr(MacAddr::zero());
+        arp.set_target_proto_addr(target_ip);
+
+        buffer
+    }
+}
+
+impl ArpProber for NetworkArpProber {
+    fn probe(&self, ips: &[IpAddr]) -> HashSet<IpAddr> {
+        let mut responders = HashSet::new();
+
+        let source_mac = match self.interface.mac {
+            Some(mac) => mac,
+            None => {
+                warn!(
+                    "can't send ARP requests without the MAC address of {}",
+                    self.interface.name
+                );
+                return responders;
+            }
+        };
+
+        // ARP only reaches IPv4 addresses on the local network
+        let requests: Vec<(Ipv4Addr, Ipv4Addr)> = ips
+            .iter()
+            .filter_map(|ip| match ip {
+                IpAddr::V4(ip) => self.source_ip(ip).map(|source_ip| (*ip, source_ip)),
+                IpAddr::V6(_) => None,
+            })
+            .collect();
+        if requests.is_empty() {
+            return responders;
+        }
+
+        let config = datalink::Config {
+            read_timeout: Some(self.timeout.min(Duration::from_millis(100))),
+            ..Default::default()
+        };
+        let (mut tx, mut rx) = match datalink::channel(&self.interface, config) {
+            Ok(Channel::Ethernet(tx, rx)) => (tx, rx),
+            Ok(_) => {
+                warn!("unsupported channel type on {}", self.interface.name);
+                return responders;
+            }
+            Err(e) => {
+                warn!(
+                    "failed to send ARP requests on {}: {}",
+                    self.interface.name, e
+                );
+                return responders;
+            }
+        };
+
+        for (target_ip, source_ip) in requests.iter() {
+            let request = Self::request(source_mac, *source_ip, *target_ip);
+            if let Some(Err(e)) = tx.send_to(&request, None) {
+                warn!("failed to send ARP request to {}: {}", target_ip, e);
+            }
+        }
+
+        // collect the replies until all targets answered or the timeout expired
+        let deadline = Instant::now() + self.timeout;
+        while responders.len() < requests.len() && Instant::now() < deadline {
+            let frame = match rx.next() {
+                Ok(frame) => frame,
+                // the read timeout expired
+                Err(_) => continue,
+            };
+
+            let ethernet = match EthernetPacket::new(frame) {
+                Some(ethernet) if ethernet.get_ethertype() == EtherTypes::Arp => ethernet,
+                _ => continue,
+            };
+            if let Some(arp) = ArpPacket::new(ethernet.payload()) {
+                let sender = arp.get_sender_proto_addr();
+                if arp.get_operation() == ArpOperations::Reply
+                    && requests.iter().any(|(target_ip, _)| *target_ip == sender)
+                {
+                    responders.insert(IpAddr::V4(sender));
+                }
+            }
+        }
+
+        responders
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::*;
+
+    use super::*;
+
+    #[rstest]
+    fn test_network_arp_prober_builds_request() {
+        let source_mac = MacAddr::new(0xaa, 0xbb, 0xcc, 0xdd, 0xee, 0xff);
+        let source_ip: Ipv4Addr = "192.168.1.1".parse().unwrap();
+        let target_ip: Ipv4Addr = "192.168.1.2".parse().unwrap();
+
+        let request = NetworkArpProber::request(source_mac, source_ip, target_ip);
+
+        let ethernet = EthernetPacket::new(&request).unwrap();
+        assert_eq!(ethernet.get_destination(), MacAddr::broadcast());
+        assert_eq!(ethernet.get_ethertype(), EtherTypes::Arp);
+
+        let arp = ArpPacket::new(ethernet.payload()).unwrap();
+        assert_eq!(arp.get_operation(), ArpOperations::Request);
+        assert_eq!(arp.get_sender_hw_addr(), source_mac);
+        assert_eq!(arp.get_sender_proto_addr(), source_ip);
+        assert_eq!(arp.get_target_proto_addr(), target_ip);
+    }
+}
@@ -0,0 +1,29 @@
+use std::collections::HashSet;
+use std::net::IpAddr;
+use std::sync::RwLock;
+
+/// IP addresses of the devices which don't answer pings but have been found online through ARP
+/// requests.
+#[derive(Debug, Default)]
+pub struct ArpResponders {
+    responders: RwLock<HashSet<IpAddr>>,
+}
+
+impl ArpResponders {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_responding(&self, ip: IpAddr, is_responding: bool) {
+        let mut responders = self.responders.write().unwrap();
+        if is_responding {
+            responders.insert(ip);
+        } else {
+            responders.remove(&ip);
+        }
+    }
+
+    pub fn is_responding(&self, ip: &IpAddr) -> bool {
+        self.responders.read().unwrap().contains(ip)
+    }
+}
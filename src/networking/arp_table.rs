@@ -0,0 +1,82 @@
+use std::collections::HashMap;
+#[cfg(target_os = "linux")]
+use std::fs;
+use std::net::IpAddr;
+
+#[cfg(test)]
+use mockall::automock;
+
+use crate::utils::MacAddr;
+
+// the ARP flag of entries whose MAC address has been resolved
+#[cfg(any(target_os = "linux", test))]
+const ATF_COM: u32 = 0x2;
+
+#[cfg_attr(test, automock)]
+pub trait ArpTable: Send + Sync {
+    /// Returns the MAC addresses currently resolved for the IP addresses on the local network.
+    fn entries(&self) -> anyhow::Result<HashMap<IpAddr, MacAddr>>;
+}
+
+/// Reads the ARP table of the Linux kernel from `/proc`.
+#[cfg(target_os = "linux")]
+pub struct ProcArpTable;
+
+#[cfg(target_os = "linux")]
+impl ArpTable for ProcArpTable {
+    fn entries(&self) -> anyhow::Result<HashMap<IpAddr, MacAddr>> {
+        Ok(parse_arp_table(&fs::read_to_string("/proc/net/arp")?))
+    }
+}
+
+/// Parses the complete entries of `/proc/net/arp`.
+#[cfg(any(target_os = "linux", test))]
+fn parse_arp_table(table: &str) -> HashMap<IpAddr, MacAddr> {
+    table
+        .lines()
+        // skip the header
+        .skip(1)
+        .filter_map(|line| {
+            let columns: Vec<&str> = line.split_whitespace().collect();
+            if columns.len() < 4 {
+                return None;
+            }
+
+            let flags = u32::from_str_radix(columns[2].trim_start_matches("0x"), 16).ok()?;
+            if flags & ATF_COM == 0 {
+                return None;
+            }
+
+            Some((columns[0].parse().ok()?, columns[3].parse().ok()?))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::*;
+
+    use super::*;
+
+    #[rstest]
+    fn test_parse_arp_table_only_returns_complete_entries() {
+        let table = "\
+IP address       HW type     Flags       HW address            Mask     Device
+192.168.1.2      0x1         0x2         aa:bb:cc:dd:ee:ff     *        eth0
+192.168.1.3      0x1         0x0         00:00:00:00:00:00     *        eth0
+192.168.1.4      0x1         0x6         11:22:33:44:55:66     *        eth0
+";
+
+        let entries = parse_arp_table(table);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(
+            entries.get(&"192.168.1.2".parse().unwrap()),
+            Some(&"aa:bb:cc:dd:ee:ff".parse().unwrap())
+        );
+        assert!(!entries.contains_key(&"192.168.1.3".parse().unwrap()));
+        assert_eq!(
+            entries.get(&"192.168.1.4".parse().unwrap()),
+            Some(&"11:22:33:44:55:66".parse().unwrap())
+        );
+    }
+}
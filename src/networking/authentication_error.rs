@@ -0,0 +1,22 @@
+use std::fmt;
+
+/// The server rejected the configured credentials (e.g. because they expired or changed) as
+/// opposed to not being reachable at all.
+#[derive(Clone, Debug)]
+pub struct AuthenticationError {
+    message: String,
+}
+
+impl AuthenticationError {
+    pub fn new(error_msg: String) -> Self {
+        Self { message: error_msg }
+    }
+}
+
+impl std::error::Error for AuthenticationError {}
+
+impl fmt::Display for AuthenticationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[AuthenticationError] {}", self.message)
+    }
+}
@@ -0,0 +1,75 @@
+use anyhow::anyhow;
+
+use super::super::dom::ActionContext;
+
+/// Runs a blocking action on tokio's blocking thread pool while honouring the
+/// cancellation token and deadline of the given context.
+///
+/// If the action is cancelled or exceeds its deadline the blocking thread is
+/// left to finish on its own but its result is discarded.
+pub async fn run_blocking<T, E, F>(context: &ActionContext, action: F) -> anyhow::Result<T>
+where
+    T: Send + 'static,
+    E: Into<anyhow::Error> + Send + 'static,
+    F: FnOnce() -> Result<T, E> + Send + 'static,
+{
+    let task = tokio::task::spawn_blocking(action);
+
+    tokio::select! {
+        _ = context.cancellation.cancelled() => Err(anyhow!("action has been cancelled")),
+        result = tokio::time::timeout_at(context.deadline, task) => match result {
+            Err(_) => Err(anyhow!("action exceeded its deadline")),
+            Ok(Err(e)) => Err(anyhow!(e)),
+            Ok(Ok(result)) => result.map_err(Into::into),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use tokio_util::sync::CancellationToken;
+
+    use super::*;
+    use crate::dom::TraceContext;
+
+    fn context(timeout: Duration) -> ActionContext {
+        ActionContext::new(TraceContext::new(), CancellationToken::new(), timeout)
+    }
+
+    #[tokio::test]
+    async fn test_run_blocking_returns_result_of_action() {
+        let result = run_blocking(&context(Duration::from_secs(10)), || {
+            Ok::<u32, anyhow::Error>(42)
+        })
+        .await;
+
+        assert_eq!(result.unwrap(), 42);
+    }
+
+    #[tokio::test]
+    async fn test_run_blocking_fails_if_deadline_exceeded() {
+        let result = run_blocking(&context(Duration::from_millis(10)), || {
+            std::thread::sleep(Duration::from_millis(500));
+            Ok::<(), anyhow::Error>(())
+        })
+        .await;
+
+        assert!(result.unwrap_err().to_string().contains("deadline"));
+    }
+
+    #[tokio::test]
+    async fn test_run_blocking_fails_if_cancelled() {
+        let context = context(Duration::from_secs(10));
+        context.cancellation.cancel();
+
+        let result = run_blocking(&context, || {
+            std::thread::sleep(Duration::from_millis(500));
+            Ok::<(), anyhow::Error>(())
+        })
+        .await;
+
+        assert!(result.unwrap_err().to_string().contains("cancelled"));
+    }
+}
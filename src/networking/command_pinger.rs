@@ -0,0 +1,207 @@
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc::RecvError;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use log::warn;
+
+use super::Pinger;
+
+pub(super) const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+const PING: &str = "ping";
+// the BSDs ship a separate command to ping IPv6 addresses
+#[cfg(any(
+    target_os = "freebsd",
+    target_os = "openbsd",
+    target_os = "netbsd",
+    target_os = "dragonfly"
+))]
+pub(super) const PING6: &str = "ping6";
+#[cfg(not(any(
+    target_os = "freebsd",
+    target_os = "openbsd",
+    target_os = "netbsd",
+    target_os = "dragonfly"
+)))]
+pub(super) const PING6: &str = PING;
+
+// option of the ping command binding it to a source address
+#[cfg(target_os = "linux")]
+const SOURCE_OPTION: &str = "-I";
+#[cfg(not(target_os = "linux"))]
+const SOURCE_OPTION: &str = "-S";
+
+/// Pings the devices by running the `ping` command of the operating system. This doesn't require
+/// raw sockets (and therefore no root privileges) and doesn't depend on how the operating system
+/// delivers ICMP responses to raw sockets (which e.g. differs on the BSDs).
+pub struct CommandPinger {
+    timeout: Duration,
+    sources: HashMap<IpAddr, IpAddr>,
+    targets: HashMap<IpAddr, bool>,
+    pings: Mutex<Vec<(IpAddr, Child)>>,
+}
+
+impl CommandPinger {
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            timeout,
+            sources: HashMap::new(),
+            targets: HashMap::new(),
+            pings: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Pings the given targets from their source address.
+    pub fn with_sources(mut self, sources: HashMap<IpAddr, IpAddr>) -> Self {
+        self.sources = sources;
+        self
+    }
+
+    pub(super) fn ping_command(ip_addr: &IpAddr, source: Option<&IpAddr>) -> Command {
+        let program = match ip_addr {
+            IpAddr::V4(_) => PING,
+            IpAddr::V6(_) => PING6,
+        };
+
+        let mut command = Command::new(program);
+        command.args(["-c", "1"]);
+        if let Some(source) = source {
+            command.args([SOURCE_OPTION, &source.to_string()]);
+        }
+        command
+            .arg(ip_addr.to_string())
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null());
+        command
+    }
+}
+
+impl Pinger for CommandPinger {
+    fn add_target(&mut self, ip_addr: IpAddr) -> bool {
+        // only add the target IP address if it doesn't already exist
+        if self.targets.contains_key(&ip_addr) {
+            return false;
+        }
+
+        self.targets.insert(ip_addr, false);
+        true
+    }
+
+    fn remove_target(&mut self, ip_addr: &IpAddr) -> bool {
+        self.targets.remove(ip_addr).is_some()
+    }
+
+    fn ping_once(&self) {
+        let mut pings = self.pings.lock().unwrap();
+        for ip_addr in self.targets.keys() {
+            match Self::ping_command(ip_addr, self.sources.get(ip_addr)).spawn() {
+                Ok(child) => pings.push((*ip_addr, child)),
+                Err(e) => warn!("failed to ping {}: {}", ip_addr, e),
+            }
+        }
+    }
+
+    fn recv_pong(&mut self) -> Result<(), RecvError> {
+        // the ping commands don't agree on how to specify a timeout so enforce it here
+        let deadline = Instant::now() + self.timeout;
+        let mut pings = std::mem::take(&mut *self.pings.lock().unwrap());
+        while !pings.is_empty() {
+            let mut running = Vec::new();
+            for (ip_addr, mut child) in pings {
+                match child.try_wait() {
+                    Ok(Some(status)) => {
+                        if let Some(is_online) = self.targets.get_mut(&ip_addr) {
+                            *is_online = status.success();
+                        }
+                    }
+                    Ok(None) if Instant::now() < deadline => running.push((ip_addr, child)),
+                    result => {
+                        if let Err(e) = result {
+                            warn!("failed to wait for the ping of {}: {}", ip_addr, e);
+                        }
+                        let _ = child.kill();
+                        let _ = child.wait();
+                        if let Some(is_online) = self.targets.get_mut(&ip_addr) {
+                            *is_online = false;
+                        }
+                    }
+                }
+            }
+
+            pings = running;
+            if !pings.is_empty() {
+                std::thread::sleep(POLL_INTERVAL);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn is_online(&self, ip_addr: &IpAddr) -> bool {
+        self.targets.get(ip_addr).copied().unwrap_or(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::*;
+
+    use super::*;
+
+    #[rstest]
+    #[case("192.168.1.2", PING)]
+    #[case("fd00::2", PING6)]
+    fn test_command_pinger_pings_once(#[case] ip_addr: IpAddr, #[case] expected_program: &str) {
+        let command = CommandPinger::ping_command(&ip_addr, None);
+
+        assert_eq!(command.get_program(), expected_program);
+        let args: Vec<String> = command
+            .get_args()
+            .map(|arg| arg.to_string_lossy().to_string())
+            .collect();
+        assert_eq!(args, vec!["-c", "1", &ip_addr.to_string()]);
+    }
+
+    #[rstest]
+    fn test_command_pinger_pings_from_source() {
+        let ip_addr: IpAddr = "192.168.20.2".parse().unwrap();
+        let source: IpAddr = "192.168.20.1".parse().unwrap();
+        let command = CommandPinger::ping_command(&ip_addr, Some(&source));
+
+        let args: Vec<String> = command
+            .get_args()
+            .map(|arg| arg.to_string_lossy().to_string())
+            .collect();
+        assert_eq!(
+            args,
+            vec!["-c", "1", SOURCE_OPTION, "192.168.20.1", "192.168.20.2"]
+        );
+    }
+
+    #[rstest]
+    fn test_command_pinger_adds_and_removes_targets() {
+        let mut pinger = CommandPinger::new(Duration::from_secs(1));
+        let ip_addr: IpAddr = "192.168.1.2".parse().unwrap();
+
+        assert!(pinger.add_target(ip_addr));
+        assert!(!pinger.add_target(ip_addr));
+        assert!(!pinger.is_online(&ip_addr));
+
+        assert!(pinger.remove_target(&ip_addr));
+        assert!(!pinger.remove_target(&ip_addr));
+    }
+
+    #[rstest]
+    fn test_command_pinger_without_targets_doesnt_wait() {
+        let mut pinger = CommandPinger::new(Duration::from_secs(10));
+
+        let start = Instant::now();
+        pinger.ping_once();
+        assert!(pinger.recv_pong().is_ok());
+        assert!(start.elapsed() < Duration::from_secs(1));
+    }
+}
@@ -0,0 +1,10 @@
+/// Diagnostics of the stages of a connection to a server (without running any action).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ConnectionTest {
+    pub reachable: bool,
+    pub handshake: bool,
+    pub authenticated: bool,
+    pub sudo: bool,
+    /// Error of the first failing stage.
+    pub error: Option<String>,
+}
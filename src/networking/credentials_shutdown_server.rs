@@ -0,0 +1,142 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use log::error;
+
+use super::super::dom::communication::InvalidCredentials;
+use super::super::dom::ActionContext;
+use super::{ActionReport, AuthenticationError, ConnectionTest, ShutdownError, ShutdownServer};
+
+/// Stops shutting down a server once it rejected the configured credentials instead of retrying
+/// (and possibly getting locked out) until the configuration has been updated.
+pub struct CredentialsShutdownServer {
+    name: String,
+    shutdown_server: Arc<dyn ShutdownServer>,
+    invalid_credentials: Arc<InvalidCredentials>,
+}
+
+impl CredentialsShutdownServer {
+    pub fn new(
+        name: &str,
+        shutdown_server: Arc<dyn ShutdownServer>,
+        invalid_credentials: Arc<InvalidCredentials>,
+    ) -> Self {
+        Self {
+            name: name.to_string(),
+            shutdown_server,
+            invalid_credentials,
+        }
+    }
+}
+
+#[async_trait]
+impl ShutdownServer for CredentialsShutdownServer {
+    async fn shutdown(&self, context: &ActionContext) -> Result<ActionReport, ShutdownError> {
+        if self.invalid_credentials.are_invalid() {
+            return Err(ShutdownError::from(AuthenticationError::new(format!(
+                "not shutting down {} because it rejected the configured credentials",
+                self.name
+            ))));
+        }
+
+        let result = self.shutdown_server.shutdown(context).await;
+        if let Err(e) = &result {
+            if e.authentication().is_some() && self.invalid_credentials.invalidate() {
+                error!(
+                    "[{}] {} rejected the configured credentials, not shutting it down again until they have been updated",
+                    context, self.name
+                );
+            }
+        }
+
+        result
+    }
+
+    async fn test_connection(&self, context: &ActionContext) -> anyhow::Result<ConnectionTest> {
+        // testing the connection still tells how far the credentials get
+        self.shutdown_server.test_connection(context).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use rstest::*;
+    use tokio_util::sync::CancellationToken;
+
+    use super::*;
+    use crate::dom::TraceContext;
+    use crate::networking::{MockShutdownServer, SshError};
+
+    fn context() -> ActionContext {
+        ActionContext::new(
+            TraceContext::new(),
+            CancellationToken::new(),
+            Duration::from_secs(10),
+        )
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_credentials_shutdown_server_stops_after_authentication_error() {
+        // SETUP
+        let mut shutdown_server = MockShutdownServer::new();
+        let invalid_credentials = Arc::new(InvalidCredentials::new());
+
+        // EXPECTATIONS
+        shutdown_server.expect_shutdown().once().return_once(|_| {
+            Err(ShutdownError::from(SshError::authentication(
+                "wrong password".to_string(),
+            )))
+        });
+
+        // TESTING
+        let server = CredentialsShutdownServer::new(
+            "server",
+            Arc::new(shutdown_server),
+            invalid_credentials.clone(),
+        );
+
+        assert!(server
+            .shutdown(&context())
+            .await
+            .unwrap_err()
+            .authentication()
+            .is_some());
+        assert!(invalid_credentials.are_invalid());
+
+        // the server isn't contacted again
+        assert!(server
+            .shutdown(&context())
+            .await
+            .unwrap_err()
+            .authentication()
+            .is_some());
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_credentials_shutdown_server_retries_after_other_errors() {
+        // SETUP
+        let mut shutdown_server = MockShutdownServer::new();
+        let invalid_credentials = Arc::new(InvalidCredentials::new());
+
+        // EXPECTATIONS
+        shutdown_server
+            .expect_shutdown()
+            .times(2)
+            .returning(|_| Err(ShutdownError::new("unreachable".to_string())));
+
+        // TESTING
+        let server = CredentialsShutdownServer::new(
+            "server",
+            Arc::new(shutdown_server),
+            invalid_credentials.clone(),
+        );
+
+        assert!(server.shutdown(&context()).await.is_err());
+        assert!(server.shutdown(&context()).await.is_err());
+        assert!(!invalid_credentials.are_invalid());
+    }
+}
@@ -2,6 +2,7 @@ use std::collections::HashMap;
 use std::net::IpAddr;
 use std::sync::mpsc::{Receiver, RecvError};
 
+use anyhow::anyhow;
 use fastping_rs::PingResult;
 use fastping_rs::PingResult::{Idle, Receive};
 use log::warn;
@@ -16,17 +17,16 @@ pub struct FastPinger {
 }
 
 impl FastPinger {
-    pub fn new(max_rtt: Option<u64>) -> Self {
-        let (pinger, pinger_results) = match fastping_rs::Pinger::new(max_rtt, None) {
-            Ok((pinger, results)) => (pinger, results),
-            Err(e) => panic!("Failed to create fastping_rs::Pinger: {}", e),
-        };
+    pub fn new(max_rtt: Option<u64>) -> anyhow::Result<Self> {
+        // fails without the privileges to open raw sockets
+        let (pinger, pinger_results) = fastping_rs::Pinger::new(max_rtt, None)
+            .map_err(|e| anyhow!("failed to create fastping_rs::Pinger: {}", e))?;
 
-        Self {
+        Ok(Self {
             pinger,
             pinger_results,
             targets: HashMap::<IpAddr, bool>::new(),
-        }
+        })
     }
 
     fn set_online(&mut self, ip_addr: &IpAddr, is_online: bool) {
@@ -60,6 +60,18 @@ impl Pinger for FastPinger {
         }
     }
 
+    fn remove_target(&mut self, ip_addr: &IpAddr) -> bool {
+        // only remove the target IP address if it exists
+        if self.targets.remove(ip_addr).is_some() {
+            self.pinger
+                .remove_ipaddr(Self::ip_to_string(ip_addr).as_str());
+
+            true
+        } else {
+            false
+        }
+    }
+
     fn ping_once(&self) {
         self.pinger.ping_once()
     }
@@ -0,0 +1,130 @@
+use std::io::Read;
+use std::net::IpAddr;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use log::warn;
+#[cfg(test)]
+use mockall::automock;
+
+use super::command_pinger::POLL_INTERVAL;
+use super::{check_all, mdns, CommandPinger, PortChecker, TcpPortChecker};
+
+/// Collects the characteristics of a device the fingerprint of the device is inferred from.
+#[cfg_attr(test, automock)]
+pub trait FingerprintProber: Send + Sync {
+    /// Returns the TTL of the response to a ping (if the device responds).
+    fn ttl(&self, ip: IpAddr) -> Option<u8>;
+    /// Returns the given ports which are open on the device.
+    fn open_ports(&self, ip: IpAddr, ports: &[u16]) -> Vec<u16>;
+    /// Returns the service types the device announces through mDNS.
+    fn services(&self, ip: IpAddr) -> Vec<String>;
+}
+
+pub struct NetworkFingerprintProber {
+    timeout: Duration,
+}
+
+impl NetworkFingerprintProber {
+    pub fn new(timeout: Duration) -> Self {
+        Self { timeout }
+    }
+
+    fn parse_ttl(output: &str) -> Option<u8> {
+        // Windows prints "TTL=" whereas most other operating systems print "ttl="
+        let output = output.to_ascii_lowercase();
+        let ttl = &output[output.find("ttl=")? + 4..];
+        let end = ttl.find(|c: char| !c.is_ascii_digit()).unwrap_or(ttl.len());
+        ttl[..end].parse().ok()
+    }
+}
+
+impl FingerprintProber for NetworkFingerprintProber {
+    fn ttl(&self, ip: IpAddr) -> Option<u8> {
+        let mut child = match CommandPinger::ping_command(&ip, None)
+            .stdout(Stdio::piped())
+            .spawn()
+        {
+            Ok(child) => child,
+            Err(e) => {
+                warn!("failed to ping {}: {}", ip, e);
+                return None;
+            }
+        };
+
+        // the ping commands don't agree on how to specify a timeout so enforce it here
+        let deadline = Instant::now() + self.timeout;
+        loop {
+            match child.try_wait() {
+                Ok(Some(_)) => break,
+                Ok(None) if Instant::now() < deadline => std::thread::sleep(POLL_INTERVAL),
+                _ => {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    return None;
+                }
+            }
+        }
+
+        let mut output = String::new();
+        child.stdout.take()?.read_to_string(&mut output).ok()?;
+        Self::parse_ttl(&output)
+    }
+
+    fn open_ports(&self, ip: IpAddr, ports: &[u16]) -> Vec<u16> {
+        let checkers: Vec<Arc<dyn PortChecker>> = ports
+            .iter()
+            .map(|port| {
+                Arc::new(TcpPortChecker::new(ip, *port, self.timeout)) as Arc<dyn PortChecker>
+            })
+            .collect();
+
+        ports
+            .iter()
+            .zip(check_all(&checkers))
+            .filter(|(_, is_open)| *is_open)
+            .map(|(port, _)| *port)
+            .collect()
+    }
+
+    fn services(&self, ip: IpAddr) -> Vec<String> {
+        mdns::query_services(ip, self.timeout)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::TcpListener;
+
+    use rstest::*;
+
+    use super::*;
+
+    #[rstest]
+    #[case("64 bytes from 192.168.1.2: icmp_seq=1 ttl=64 time=0.512 ms", Some(64))]
+    #[case("Reply from 192.168.1.2: bytes=32 time<1ms TTL=128", Some(128))]
+    #[case("Request timed out.", None)]
+    #[case("ttl=", None)]
+    fn test_parse_ttl(#[case] output: &str, #[case] expected_ttl: Option<u8>) {
+        assert_eq!(NetworkFingerprintProber::parse_ttl(output), expected_ttl);
+    }
+
+    #[rstest]
+    fn test_open_ports_only_returns_open_ports() {
+        let ip: IpAddr = "127.0.0.1".parse().unwrap();
+        let listener = TcpListener::bind((ip, 0)).unwrap();
+        let open_port = listener.local_addr().unwrap().port();
+        let closed_port = {
+            let listener = TcpListener::bind((ip, 0)).unwrap();
+            listener.local_addr().unwrap().port()
+        };
+
+        let prober = NetworkFingerprintProber::new(Duration::from_millis(500));
+
+        assert_eq!(
+            prober.open_ports(ip, &[closed_port, open_port]),
+            vec![open_port]
+        );
+    }
+}
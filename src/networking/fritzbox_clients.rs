@@ -0,0 +1,337 @@
+use std::collections::{HashMap, HashSet};
+use std::net::IpAddr;
+
+use anyhow::{anyhow, bail};
+use rand::rngs::OsRng;
+use rand::RngCore;
+
+use super::http_checker::create_agent;
+use super::router_clients::REQUEST_TIMEOUT;
+use super::RouterClients;
+use crate::configuration::PresenceProvider;
+use crate::utils::Secret;
+
+static CONTROL_PATH: &str = "/upnp/control/hosts";
+static HOSTS_SERVICE: &str = "urn:dslforum-org:service:Hosts:1";
+static HOST_LIST_PATH_ACTION: &str = "X_AVM-DE_GetHostListPath";
+
+/// Returns the text of all elements with the given tag.
+fn xml_values<'a>(content: &'a str, tag: &str) -> Vec<&'a str> {
+    let start_tag = format!("<{}>", tag);
+    let end_tag = format!("</{}>", tag);
+
+    let mut values = Vec::new();
+    let mut remaining = content;
+    while let Some(start) = remaining.find(&start_tag) {
+        remaining = &remaining[start + start_tag.len()..];
+        match remaining.find(&end_tag) {
+            Some(end) => {
+                values.push(remaining[..end].trim());
+                remaining = &remaining[end + end_tag.len()..];
+            }
+            None => break,
+        }
+    }
+
+    values
+}
+
+/// Returns the parameters (with lowercase names) of the digest challenge of a `WWW-Authenticate`
+/// header. Quoted values may contain commas and escaped characters.
+fn digest_parameters(challenge: &str) -> anyhow::Result<HashMap<String, String>> {
+    let challenge = challenge.trim_start();
+    let challenge = match challenge.get(..6) {
+        Some(scheme) if scheme.eq_ignore_ascii_case("Digest") => &challenge[6..],
+        _ => bail!("unsupported authentication challenge"),
+    };
+
+    let mut parameters = HashMap::new();
+    let mut chars = challenge.chars().peekable();
+    loop {
+        while chars.next_if(|c| c.is_whitespace() || *c == ',').is_some() {}
+        if chars.peek().is_none() {
+            return Ok(parameters);
+        }
+
+        let mut name = String::new();
+        while let Some(c) = chars.next_if(|c| *c != '=' && *c != ',' && !c.is_whitespace()) {
+            name.push(c);
+        }
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        if chars.next() != Some('=') {
+            bail!("missing value of digest parameter {}", name);
+        }
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+
+        let mut value = String::new();
+        if chars.next_if_eq(&'"').is_some() {
+            loop {
+                match chars.next() {
+                    Some('"') => break,
+                    Some('\\') => match chars.next() {
+                        Some(c) => value.push(c),
+                        None => bail!("unterminated value of digest parameter {}", name),
+                    },
+                    Some(c) => value.push(c),
+                    None => bail!("unterminated value of digest parameter {}", name),
+                }
+            }
+        } else {
+            while let Some(c) = chars.next_if(|c| *c != ',' && !c.is_whitespace()) {
+                value.push(c);
+            }
+        }
+
+        parameters.insert(name.to_ascii_lowercase(), value);
+    }
+}
+
+/// Computes the `Authorization` header answering the digest challenge of the router.
+fn digest_authorization(
+    challenge: &str,
+    username: &str,
+    password: &str,
+    method: &str,
+    uri: &str,
+    cnonce: &str,
+) -> anyhow::Result<String> {
+    let parameters = digest_parameters(challenge)?;
+    let realm = parameters
+        .get("realm")
+        .ok_or_else(|| anyhow!("missing realm"))?;
+    let nonce = parameters
+        .get("nonce")
+        .ok_or_else(|| anyhow!("missing nonce"))?;
+    if let Some(algorithm) = parameters.get("algorithm") {
+        if !algorithm.eq_ignore_ascii_case("MD5") {
+            bail!("unsupported digest algorithm {}", algorithm);
+        }
+    }
+
+    let ha1 = md5::compute(format!("{}:{}:{}", username, realm, password));
+    let ha2 = md5::compute(format!("{}:{}", method, uri));
+    let mut authorization = format!(
+        "Digest username=\"{}\", realm=\"{}\", nonce=\"{}\", uri=\"{}\"",
+        username, realm, nonce, uri
+    );
+    match parameters.get("qop") {
+        // challenges without a quality of protection are answered as defined by RFC 2069
+        None => {
+            let response = md5::compute(format!("{:x}:{}:{:x}", ha1, nonce, ha2));
+            authorization.push_str(&format!(", response=\"{:x}\"", response));
+        }
+        Some(qop)
+            if qop
+                .split(',')
+                .any(|qop| qop.trim().eq_ignore_ascii_case("auth")) =>
+        {
+            // the nonce is only used for a single request
+            let nc = "00000001";
+            let response = md5::compute(format!(
+                "{:x}:{}:{}:{}:auth:{:x}",
+                ha1, nonce, nc, cnonce, ha2
+            ));
+            authorization.push_str(&format!(
+                ", qop=auth, nc={}, cnonce=\"{}\", response=\"{:x}\"",
+                nc, cnonce, response
+            ));
+        }
+        Some(qop) => bail!("unsupported digest quality of protection {}", qop),
+    }
+    if let Some(opaque) = parameters.get("opaque") {
+        authorization.push_str(&format!(", opaque=\"{}\"", opaque));
+    }
+
+    Ok(authorization)
+}
+
+/// Lists the clients of a Fritz!Box through its TR-064 API.
+pub struct FritzboxRouterClients {
+    agent: ureq::Agent,
+    url: String,
+    username: String,
+    password: Secret,
+}
+
+impl FritzboxRouterClients {
+    pub fn new(provider: &PresenceProvider, proxy: Option<&str>) -> Self {
+        Self {
+            agent: create_agent(REQUEST_TIMEOUT, provider.insecure, proxy),
+            url: provider.url.trim_end_matches('/').to_string(),
+            username: provider.username.clone(),
+            password: provider.password.clone(),
+        }
+    }
+
+    fn host_list_path_request(&self, authorization: Option<&str>) -> ureq::Request {
+        let request = self
+            .agent
+            .post(&format!("{}{}", self.url, CONTROL_PATH))
+            .set("Content-Type", "text/xml; charset=\"utf-8\"")
+            .set(
+                "SOAPAction",
+                &format!("{}#{}", HOSTS_SERVICE, HOST_LIST_PATH_ACTION),
+            );
+        match authorization {
+            Some(authorization) => request.set("Authorization", authorization),
+            None => request,
+        }
+    }
+
+    /// Returns the path of the host list which is only valid for a short time.
+    fn get_host_list_path(&self) -> anyhow::Result<String> {
+        let body = format!(
+            "<?xml version=\"1.0\" encoding=\"utf-8\"?>\
+            <s:Envelope xmlns:s=\"http://schemas.xmlsoap.org/soap/envelope/\" \
+            s:encodingStyle=\"http://schemas.xmlsoap.org/soap/encoding/\">\
+            <s:Body><u:{action} xmlns:u=\"{service}\"/></s:Body></s:Envelope>",
+            action = HOST_LIST_PATH_ACTION,
+            service = HOSTS_SERVICE
+        );
+
+        // the first request is answered with the digest challenge
+        let response = match self.host_list_path_request(None).send_string(&body) {
+            Err(ureq::Error::Status(401, response)) => {
+                let challenge = response
+                    .header("WWW-Authenticate")
+                    .ok_or_else(|| anyhow!("{} didn't return a digest challenge", self.url))?;
+                let authorization = digest_authorization(
+                    challenge,
+                    &self.username,
+                    self.password.expose(),
+                    "POST",
+                    CONTROL_PATH,
+                    &format!("{:016x}", OsRng.next_u64()),
+                )?;
+                self.host_list_path_request(Some(&authorization))
+                    .send_string(&body)?
+            }
+            result => result?,
+        };
+        let content = response.into_string()?;
+
+        match xml_values(&content, "NewX_AVM-DE_HostListPath").first() {
+            Some(path) => Ok(path.to_string()),
+            None => bail!("{} didn't return the path of the host list", self.url),
+        }
+    }
+
+    fn parse_clients(content: &str) -> HashSet<IpAddr> {
+        xml_values(content, "Item")
+            .into_iter()
+            .filter(|item| xml_values(item, "Active").first() == Some(&"1"))
+            .filter_map(|item| xml_values(item, "IPAddress").first()?.parse().ok())
+            .collect()
+    }
+}
+
+impl RouterClients for FritzboxRouterClients {
+    fn clients(&self) -> anyhow::Result<HashSet<IpAddr>> {
+        let path = self.get_host_list_path()?;
+        let content = self
+            .agent
+            .get(&format!("{}{}", self.url, path))
+            .call()?
+            .into_string()?;
+
+        Ok(Self::parse_clients(&content))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::*;
+
+    use super::*;
+
+    #[rstest]
+    fn test_digest_authorization_answers_challenge() {
+        // example of RFC 2617
+        let challenge = r#"Digest realm="testrealm@host.com", qop="auth,auth-int", nonce="dcd98b7102dd2f0e8b11d0f600bfb0c093", opaque="5ccc069c403ebaf9f0171e9517f40e41""#;
+
+        let authorization = digest_authorization(
+            challenge,
+            "Mufasa",
+            "Circle Of Life",
+            "GET",
+            "/dir/index.html",
+            "0a4f113b",
+        )
+        .unwrap();
+
+        assert!(authorization.contains(r#"realm="testrealm@host.com""#));
+        assert!(authorization.contains(r#"response="6629fae49393a05397450978507c4ef1""#));
+        assert!(authorization.contains(r#"opaque="5ccc069c403ebaf9f0171e9517f40e41""#));
+    }
+
+    #[rstest]
+    fn test_digest_parameters_parses_quoted_values() {
+        let challenge = r#"Digest realm="F!Box, \"Home\"", nonce=1234, qop = "auth,auth-int""#;
+
+        let parameters = digest_parameters(challenge).unwrap();
+        assert_eq!(parameters["realm"], r#"F!Box, "Home""#);
+        assert_eq!(parameters["nonce"], "1234");
+        assert_eq!(parameters["qop"], "auth,auth-int");
+
+        assert!(digest_parameters(r#"Digest realm="F!Box"#).is_err());
+        assert!(digest_parameters(r#"Basic realm="F!Box""#).is_err());
+    }
+
+    #[rstest]
+    #[case(
+        r#"Digest realm="testrealm@host.com", nonce="dcd98b7102dd2f0e8b11d0f600bfb0c093""#,
+        Some("670fd8c2df070c60b045671b8b24ff02")
+    )]
+    #[case(r#"Digest realm="testrealm@host.com", nonce="dcd98b7102dd2f0e8b11d0f600bfb0c093", qop="auth-int""#, None)]
+    fn test_digest_authorization_without_qop_auth(
+        #[case] challenge: &str,
+        #[case] expected_response: Option<&str>,
+    ) {
+        let authorization = digest_authorization(
+            challenge,
+            "Mufasa",
+            "Circle Of Life",
+            "GET",
+            "/dir/index.html",
+            "0a4f113b",
+        );
+
+        match expected_response {
+            Some(expected_response) => {
+                let authorization = authorization.unwrap();
+                assert!(!authorization.contains("qop"));
+                assert!(authorization.contains(&format!(r#"response="{}""#, expected_response)));
+            }
+            None => assert!(authorization.is_err()),
+        }
+    }
+
+    #[rstest]
+    fn test_fritzbox_router_clients_parses_active_clients() {
+        let content = r#"<?xml version="1.0" encoding="utf-8"?>
+            <List>
+                <Item>
+                    <Index>1</Index>
+                    <IPAddress>192.168.178.20</IPAddress>
+                    <MACAddress>AA:BB:CC:DD:EE:01</MACAddress>
+                    <Active>1</Active>
+                    <HostName>phone</HostName>
+                </Item>
+                <Item>
+                    <Index>2</Index>
+                    <IPAddress>192.168.178.21</IPAddress>
+                    <Active>0</Active>
+                </Item>
+                <Item>
+                    <Index>3</Index>
+                    <IPAddress></IPAddress>
+                    <Active>1</Active>
+                </Item>
+            </List>"#;
+
+        assert_eq!(
+            FritzboxRouterClients::parse_clients(content),
+            HashSet::from(["192.168.178.20".parse().unwrap()])
+        );
+    }
+}
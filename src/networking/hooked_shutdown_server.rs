@@ -0,0 +1,57 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use super::super::configuration::HookEvent;
+use super::super::dom::{ActionContext, Server};
+use super::{ActionHooks, ActionReport, ConnectionTest, ShutdownError, ShutdownServer};
+
+/// Runs the configured hooks before and after every shutdown of a server.
+pub struct HookedShutdownServer {
+    server: Server,
+    shutdown_server: Arc<dyn ShutdownServer>,
+    hooks: Arc<ActionHooks>,
+}
+
+impl HookedShutdownServer {
+    pub fn new(
+        server: &Server,
+        shutdown_server: Arc<dyn ShutdownServer>,
+        hooks: Arc<ActionHooks>,
+    ) -> Self {
+        Self {
+            server: server.clone(),
+            shutdown_server,
+            hooks,
+        }
+    }
+}
+
+#[async_trait]
+impl ShutdownServer for HookedShutdownServer {
+    async fn shutdown(&self, context: &ActionContext) -> Result<ActionReport, ShutdownError> {
+        self.hooks
+            .run(HookEvent::PreShutdown, &self.server, context, None)
+            .await?;
+
+        let result = self.shutdown_server.shutdown(context).await;
+        let outcome = result.as_ref().map(|_| ()).map_err(|e| e.to_string());
+        let hooks = self
+            .hooks
+            .run(
+                HookEvent::PostShutdown,
+                &self.server,
+                context,
+                Some(outcome),
+            )
+            .await;
+
+        let report = result?;
+        hooks?;
+        Ok(report)
+    }
+
+    async fn test_connection(&self, context: &ActionContext) -> anyhow::Result<ConnectionTest> {
+        self.shutdown_server.test_connection(context).await
+    }
+}
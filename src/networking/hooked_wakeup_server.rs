@@ -0,0 +1,89 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use super::super::configuration::HookEvent;
+use super::super::dom::{ActionContext, Server};
+use super::{ActionHooks, ActionReport, WakeupServer};
+
+/// Runs the configured hooks before and after every wakeup of a server.
+pub struct HookedWakeupServer {
+    server: Server,
+    wakeup_server: Arc<dyn WakeupServer>,
+    hooks: Arc<ActionHooks>,
+}
+
+impl HookedWakeupServer {
+    pub fn new(
+        server: &Server,
+        wakeup_server: Arc<dyn WakeupServer>,
+        hooks: Arc<ActionHooks>,
+    ) -> Self {
+        Self {
+            server: server.clone(),
+            wakeup_server,
+            hooks,
+        }
+    }
+}
+
+#[async_trait]
+impl WakeupServer for HookedWakeupServer {
+    async fn wakeup(&self, context: &ActionContext) -> anyhow::Result<ActionReport> {
+        self.hooks
+            .run(HookEvent::PreWakeup, &self.server, context, None)
+            .await?;
+
+        let result = self.wakeup_server.wakeup(context).await;
+        let outcome = result.as_ref().map(|_| ()).map_err(|e| e.to_string());
+        let hooks = self
+            .hooks
+            .run(HookEvent::PostWakeup, &self.server, context, Some(outcome))
+            .await;
+
+        let report = result?;
+        hooks?;
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use rstest::*;
+    use tokio_util::sync::CancellationToken;
+
+    use super::*;
+    use crate::configuration::{Hook, HookTarget};
+    use crate::dom::device::test::*;
+    use crate::dom::TraceContext;
+    use crate::networking::MockWakeupServer;
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_hooked_wakeup_server_skips_wakeup_if_fatal_pre_hook_fails(server: Server) {
+        let mut wakeup_server = MockWakeupServer::new();
+        wakeup_server.expect_wakeup().never();
+        let hooks = Arc::new(ActionHooks::new(
+            &[Hook {
+                events: vec![HookEvent::PreWakeup],
+                target: HookTarget::Command {
+                    command: vec!["false".to_string()],
+                },
+                fatal: true,
+            }],
+            None,
+        ));
+        let hooked = HookedWakeupServer::new(&server, Arc::new(wakeup_server), hooks);
+
+        let context = ActionContext::new(
+            TraceContext::new(),
+            CancellationToken::new(),
+            Duration::from_secs(10),
+        );
+        let result = hooked.wakeup(&context).await;
+
+        assert!(result.unwrap_err().to_string().contains("preWakeup"));
+    }
+}
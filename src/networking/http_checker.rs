@@ -0,0 +1,182 @@
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use log::{debug, warn};
+use rustls::client::{ServerCertVerified, ServerCertVerifier};
+use rustls::{Certificate, ClientConfig, ServerName};
+
+use super::PortChecker;
+use crate::dom::device::HttpCheck;
+
+// The checks use the same blocking ureq client as the other outbound requests instead of
+// reqwest which would add a second HTTP stack (and an async runtime for every blocking request)
+// only to be able to skip the certificate verification. ureq doesn't provide such an option
+// itself so the `insecure` checks plug this verifier into the rustls configuration used by ureq.
+struct NoCertificateVerification;
+
+impl ServerCertVerifier for NoCertificateVerification {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &Certificate,
+        _intermediates: &[Certificate],
+        _server_name: &ServerName,
+        _scts: &mut dyn Iterator<Item = &[u8]>,
+        _ocsp_response: &[u8],
+        _now: SystemTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+}
+
+/// Creates the builder of an HTTP agent which connects through the given proxy (if any).
+pub fn agent_builder(proxy: Option<&str>) -> ureq::AgentBuilder {
+    let builder = ureq::AgentBuilder::new();
+    match proxy.map(ureq::Proxy::new) {
+        Some(Ok(proxy)) => builder.proxy(proxy),
+        Some(Err(e)) => {
+            warn!("connecting without the invalid proxy: {}", e);
+            builder
+        }
+        None => builder,
+    }
+}
+
+/// Creates an HTTP agent which connects through the given proxy (if any) and optionally accepts
+/// any (e.g. self-signed) certificate.
+pub(super) fn create_agent(timeout: Duration, insecure: bool, proxy: Option<&str>) -> ureq::Agent {
+    let mut builder = agent_builder(proxy).timeout(timeout);
+    if insecure {
+        let tls_config = ClientConfig::builder()
+            .with_safe_defaults()
+            .with_custom_certificate_verifier(Arc::new(NoCertificateVerification))
+            .with_no_client_auth();
+        builder = builder.tls_config(Arc::new(tls_config));
+    }
+
+    builder.build()
+}
+
+pub struct HttpChecker {
+    agent: ureq::Agent,
+    http: HttpCheck,
+}
+
+impl HttpChecker {
+    pub fn new(http: &HttpCheck) -> Self {
+        Self {
+            // the checked servers are part of the local network
+            agent: create_agent(http.timeout, http.insecure, None),
+            http: http.clone(),
+        }
+    }
+
+    fn check_response(&self, response: ureq::Response) -> bool {
+        if response.status() != self.http.expected_status {
+            debug!(
+                "{} returned status {} instead of {}",
+                self.http.url,
+                response.status(),
+                self.http.expected_status
+            );
+            return false;
+        }
+
+        match &self.http.expected_body {
+            None => true,
+            Some(expected_body) => match response.into_string() {
+                Ok(body) => body.contains(expected_body),
+                Err(e) => {
+                    debug!("failed to read the response of {}: {}", self.http.url, e);
+                    false
+                }
+            },
+        }
+    }
+}
+
+impl PortChecker for HttpChecker {
+    fn check(&self) -> bool {
+        debug!("checking {}", self.http.url);
+        match self.agent.get(&self.http.url).call() {
+            Ok(response) => self.check_response(response),
+            Err(ureq::Error::Status(_, response)) => self.check_response(response),
+            Err(e) => {
+                debug!("failed to request {}: {}", self.http.url, e);
+                false
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::time::Duration;
+
+    use rstest::*;
+
+    use super::*;
+
+    fn serve_once(status: &str, body: &str) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/health", listener.local_addr().unwrap());
+
+        let response = format!(
+            "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            status,
+            body.len(),
+            body
+        );
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0; 1024];
+            let _ = stream.read(&mut request).unwrap();
+            stream.write_all(response.as_bytes()).unwrap();
+        });
+
+        url
+    }
+
+    fn http_check(url: String, expected_status: u16, expected_body: Option<&str>) -> HttpCheck {
+        HttpCheck {
+            url,
+            expected_status,
+            expected_body: expected_body.map(str::to_string),
+            timeout: Duration::from_secs(1),
+            insecure: false,
+        }
+    }
+
+    #[rstest]
+    #[case("200 OK", "Healthy", 200, None, true)]
+    #[case("200 OK", "Healthy", 200, Some("Healthy"), true)]
+    #[case("200 OK", "Unhealthy", 200, Some("Degraded"), false)]
+    #[case("503 Service Unavailable", "", 200, None, false)]
+    #[case("503 Service Unavailable", "", 503, None, true)]
+    #[case("204 No Content", "", 200, None, false)]
+    fn test_http_checker_matches_status_and_body(
+        #[case] status: &str,
+        #[case] body: &str,
+        #[case] expected_status: u16,
+        #[case] expected_body: Option<&str>,
+        #[case] expected: bool,
+    ) {
+        let url = serve_once(status, body);
+        let checker = HttpChecker::new(&http_check(url, expected_status, expected_body));
+
+        assert_eq!(checker.check(), expected);
+    }
+
+    #[rstest]
+    fn test_http_checker_fails_if_unreachable() {
+        // find a port which is (very likely) closed
+        let url = {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            format!("http://{}/health", listener.local_addr().unwrap())
+        };
+
+        let checker = HttpChecker::new(&http_check(url, 200, None));
+        assert!(!checker.check());
+    }
+}
@@ -0,0 +1,289 @@
+use std::io::Read;
+use std::net::{IpAddr, SocketAddr, TcpStream};
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[cfg(test)]
+use mockall::automock;
+
+use log::{debug, warn};
+
+use super::command_pinger::POLL_INTERVAL;
+
+const PING_COUNT: u32 = 5;
+const PING_TIMEOUT: Duration = Duration::from_secs(5);
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+const TRACEROUTE_MAX_HOPS: u32 = 15;
+const TRACEROUTE_TIMEOUT: Duration = Duration::from_secs(20);
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PingStatistics {
+    pub transmitted: u32,
+    pub received: u32,
+    /// Round trip times in milliseconds (only if any ping has been answered).
+    pub min_ms: Option<f64>,
+    pub avg_ms: Option<f64>,
+    pub max_ms: Option<f64>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PortLatency {
+    pub port: u16,
+    /// Time it took to connect to the port (if it could be connected to at all).
+    pub connect_time: Option<Duration>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Traceroute {
+    pub hops: u32,
+    /// Whether the device answered at all (otherwise `hops` is the number of tried hops).
+    pub reached: bool,
+}
+
+/// Diagnostics of the latency of a device to tell why it seems to be flaky.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LatencyReport {
+    pub ping: Option<PingStatistics>,
+    pub ports: Vec<PortLatency>,
+    pub traceroute: Option<Traceroute>,
+}
+
+#[cfg_attr(test, automock)]
+pub trait LatencyProber: Send + Sync {
+    /// Pings the device with the given IP address several times, connects to the given TCP ports
+    /// and traces the route to it.
+    fn probe(&self, ip: IpAddr, ports: &[u16]) -> LatencyReport;
+}
+
+/// Probes the latency of devices by running the `ping` and `traceroute` commands of the operating
+/// system (which doesn't require raw sockets) and by connecting to their TCP ports.
+#[derive(Default)]
+pub struct CommandLatencyProber {}
+
+impl CommandLatencyProber {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    /// Runs the given command and returns its output unless it failed to run or didn't finish in
+    /// time.
+    fn run(mut command: Command, timeout: Duration) -> Option<String> {
+        let program = command.get_program().to_string_lossy().to_string();
+        let mut child = match command
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+        {
+            Ok(child) => child,
+            Err(e) => {
+                warn!("failed to run {}: {}", program, e);
+                return None;
+            }
+        };
+
+        let deadline = Instant::now() + timeout;
+        loop {
+            match child.try_wait() {
+                Ok(Some(_)) => break,
+                Ok(None) if Instant::now() < deadline => thread::sleep(POLL_INTERVAL),
+                Ok(None) => {
+                    debug!("{} didn't finish within {}s", program, timeout.as_secs());
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    return None;
+                }
+                Err(e) => {
+                    warn!("failed to wait for {}: {}", program, e);
+                    return None;
+                }
+            }
+        }
+
+        let mut output = String::new();
+        child.stdout?.read_to_string(&mut output).ok()?;
+        Some(output)
+    }
+
+    fn ping(ip: IpAddr) -> Option<PingStatistics> {
+        let mut command = Command::new(match ip {
+            IpAddr::V4(_) => "ping",
+            IpAddr::V6(_) => super::command_pinger::PING6,
+        });
+        command.args(["-c", &PING_COUNT.to_string(), "-i", "0.2", &ip.to_string()]);
+        parse_ping(&Self::run(command, PING_TIMEOUT)?)
+    }
+
+    fn connect(ip: IpAddr, port: u16) -> PortLatency {
+        let start = Instant::now();
+        let connect_time = TcpStream::connect_timeout(&SocketAddr::new(ip, port), CONNECT_TIMEOUT)
+            .ok()
+            .map(|_| start.elapsed());
+        PortLatency { port, connect_time }
+    }
+
+    fn traceroute(ip: IpAddr) -> Option<Traceroute> {
+        let mut command = Command::new("traceroute");
+        command.args([
+            "-n",
+            "-q",
+            "1",
+            "-w",
+            "1",
+            "-m",
+            &TRACEROUTE_MAX_HOPS.to_string(),
+            &ip.to_string(),
+        ]);
+        parse_traceroute(&Self::run(command, TRACEROUTE_TIMEOUT)?, ip)
+    }
+}
+
+impl LatencyProber for CommandLatencyProber {
+    fn probe(&self, ip: IpAddr, ports: &[u16]) -> LatencyReport {
+        LatencyReport {
+            ping: Self::ping(ip),
+            ports: ports.iter().map(|port| Self::connect(ip, *port)).collect(),
+            traceroute: Self::traceroute(ip),
+        }
+    }
+}
+
+/// Parses the summary of the `ping` command on Linux (`rtt min/avg/max/mdev = ...`) and the BSDs
+/// (`round-trip min/avg/max/stddev = ...`).
+fn parse_ping(output: &str) -> Option<PingStatistics> {
+    let mut statistics = None;
+    for line in output.lines() {
+        if line.contains("packets transmitted") {
+            let numbers: Vec<u32> = line
+                .split(',')
+                .filter_map(|part| part.split_whitespace().next()?.parse().ok())
+                .collect();
+            statistics = Some(PingStatistics {
+                transmitted: *numbers.first()?,
+                received: *numbers.get(1)?,
+                ..Default::default()
+            });
+        } else if line.starts_with("rtt") || line.starts_with("round-trip") {
+            let times: Vec<f64> = line
+                .split('=')
+                .nth(1)?
+                .split_whitespace()
+                .next()?
+                .split('/')
+                .filter_map(|time| time.parse().ok())
+                .collect();
+            if let Some(statistics) = statistics.as_mut() {
+                statistics.min_ms = times.first().copied();
+                statistics.avg_ms = times.get(1).copied();
+                statistics.max_ms = times.get(2).copied();
+            }
+        }
+    }
+
+    statistics
+}
+
+/// Parses the hops of the `traceroute` command (run with `-n -q 1`).
+fn parse_traceroute(output: &str, ip: IpAddr) -> Option<Traceroute> {
+    let mut traceroute = None;
+    for line in output.lines() {
+        let mut parts = line.split_whitespace();
+        let hop = match parts.next().and_then(|hop| hop.parse().ok()) {
+            Some(hop) => hop,
+            // e.g. the header
+            None => continue,
+        };
+        let reached = parts.next().and_then(|address| address.parse().ok()) == Some(ip);
+        traceroute = Some(Traceroute { hops: hop, reached });
+        if reached {
+            break;
+        }
+    }
+
+    traceroute
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::TcpListener;
+
+    use rstest::*;
+
+    use super::*;
+
+    #[rstest]
+    #[case(
+        "PING 192.168.1.2 (192.168.1.2) 56(84) bytes of data.\n\
+         64 bytes from 192.168.1.2: icmp_seq=1 ttl=64 time=0.512 ms\n\
+         \n\
+         --- 192.168.1.2 ping statistics ---\n\
+         5 packets transmitted, 4 received, 20% packet loss, time 803ms\n\
+         rtt min/avg/max/mdev = 0.412/1.234/3.456/0.789 ms\n",
+        Some(PingStatistics {
+            transmitted: 5,
+            received: 4,
+            min_ms: Some(0.412),
+            avg_ms: Some(1.234),
+            max_ms: Some(3.456),
+        })
+    )]
+    #[case(
+        "--- 192.168.1.2 ping statistics ---\n\
+         5 packets transmitted, 5 packets received, 0.0% packet loss\n\
+         round-trip min/avg/max/stddev = 1.000/2.000/3.000/0.500 ms\n",
+        Some(PingStatistics {
+            transmitted: 5,
+            received: 5,
+            min_ms: Some(1.0),
+            avg_ms: Some(2.0),
+            max_ms: Some(3.0),
+        })
+    )]
+    #[case(
+        "--- 192.168.1.2 ping statistics ---\n\
+         5 packets transmitted, 0 received, 100% packet loss, time 4096ms\n",
+        Some(PingStatistics {
+            transmitted: 5,
+            received: 0,
+            ..Default::default()
+        })
+    )]
+    #[case("ping: unknown host\n", None)]
+    fn test_parse_ping(#[case] output: &str, #[case] expected: Option<PingStatistics>) {
+        assert_eq!(parse_ping(output), expected);
+    }
+
+    #[rstest]
+    #[case(
+        "traceroute to 192.168.2.2 (192.168.2.2), 15 hops max, 60 byte packets\n \
+         1  192.168.1.1  0.512 ms\n \
+         2  192.168.2.2  1.024 ms\n",
+        Some(Traceroute { hops: 2, reached: true })
+    )]
+    #[case(
+        "traceroute to 192.168.2.2 (192.168.2.2), 15 hops max, 60 byte packets\n \
+         1  192.168.1.1  0.512 ms\n \
+         2  *\n \
+         3  *\n",
+        Some(Traceroute { hops: 3, reached: false })
+    )]
+    #[case("", None)]
+    fn test_parse_traceroute(#[case] output: &str, #[case] expected: Option<Traceroute>) {
+        assert_eq!(
+            parse_traceroute(output, "192.168.2.2".parse().unwrap()),
+            expected
+        );
+    }
+
+    #[rstest]
+    fn test_command_latency_prober_measures_connect_time() {
+        let ip: IpAddr = "127.0.0.1".parse().unwrap();
+        let listener = TcpListener::bind((ip, 0)).unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        assert!(CommandLatencyProber::connect(ip, port)
+            .connect_time
+            .is_some());
+    }
+}
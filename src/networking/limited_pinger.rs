@@ -0,0 +1,105 @@
+use std::net::IpAddr;
+use std::sync::mpsc::RecvError;
+use std::sync::Arc;
+
+use log::debug;
+
+use super::super::utils::RateLimiter;
+use super::Pinger;
+
+/// Delays pinging all targets at once if it would exceed the maximum number of pings per second
+/// (e.g. after importing thousands of devices).
+pub struct LimitedPinger {
+    pinger: Box<dyn Pinger>,
+    pings: Arc<RateLimiter>,
+    targets: u32,
+}
+
+impl LimitedPinger {
+    pub fn new(pinger: Box<dyn Pinger>, pings: Arc<RateLimiter>) -> Self {
+        Self {
+            pinger,
+            pings,
+            targets: 0,
+        }
+    }
+}
+
+impl Pinger for LimitedPinger {
+    fn add_target(&mut self, ip_addr: IpAddr) -> bool {
+        let added = self.pinger.add_target(ip_addr);
+        if added {
+            self.targets += 1;
+        }
+
+        added
+    }
+
+    fn remove_target(&mut self, ip_addr: &IpAddr) -> bool {
+        let removed = self.pinger.remove_target(ip_addr);
+        if removed {
+            self.targets -= 1;
+        }
+
+        removed
+    }
+
+    fn ping_once(&self) {
+        let delay = self.pings.reserve(self.targets);
+        if !delay.is_zero() {
+            debug!(
+                "delaying pinging {} devices by {}ms to stay below {} pings per second",
+                self.targets,
+                delay.as_millis(),
+                self.pings.limit()
+            );
+            std::thread::sleep(delay);
+        }
+
+        self.pinger.ping_once()
+    }
+
+    fn recv_pong(&mut self) -> Result<(), RecvError> {
+        self.pinger.recv_pong()
+    }
+
+    fn is_online(&self, ip_addr: &IpAddr) -> bool {
+        self.pinger.is_online(ip_addr)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use rstest::*;
+
+    use super::*;
+    use crate::networking::MockPinger;
+    use crate::utils::{Instant, ManualClock};
+
+    #[rstest]
+    fn test_limited_pinger_reserves_a_ping_per_target() {
+        // SETUP
+        let mut pinger = MockPinger::new();
+        pinger.expect_add_target().times(2).return_const(true);
+        pinger.expect_remove_target().once().return_const(false);
+        pinger.expect_ping_once().once().return_const(());
+
+        let clock = Arc::new(ManualClock::new(Instant::now()));
+        let pings = Arc::new(RateLimiter::with_clock(3, Duration::from_secs(1), clock));
+        let mut limited = LimitedPinger::new(Box::new(pinger), pings.clone());
+
+        // TESTING
+        assert!(limited.add_target("192.168.1.2".parse().unwrap()));
+        assert!(limited.add_target("192.168.1.3".parse().unwrap()));
+        // unknown targets aren't counted
+        assert!(!limited.remove_target(&"192.168.1.4".parse().unwrap()));
+
+        limited.ping_once();
+
+        // only a single ping is left
+        assert!(pings.try_acquire(1));
+        assert!(!pings.try_acquire(1));
+    }
+}
@@ -0,0 +1,110 @@
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use log::debug;
+use tokio::sync::{Semaphore, SemaphorePermit};
+
+use super::super::dom::ActionContext;
+use super::{ActionReport, ConnectionTest, ShutdownError, ShutdownServer};
+
+/// Limits the number of simultaneous SSH sessions of all shutdown servers sharing the same
+/// semaphore (e.g. when shutting down all servers at once).
+pub struct LimitedShutdownServer {
+    shutdown_server: Arc<dyn ShutdownServer>,
+    sessions: Arc<Semaphore>,
+}
+
+impl LimitedShutdownServer {
+    pub fn new(shutdown_server: Arc<dyn ShutdownServer>, sessions: Arc<Semaphore>) -> Self {
+        Self {
+            shutdown_server,
+            sessions,
+        }
+    }
+
+    /// Waits for a free session while honouring the cancellation token and deadline of the
+    /// given context.
+    async fn acquire(&self, context: &ActionContext) -> anyhow::Result<SemaphorePermit<'_>> {
+        if self.sessions.available_permits() == 0 {
+            debug!("[{}] waiting for a free SSH session...", context);
+        }
+
+        tokio::select! {
+            _ = context.cancellation.cancelled() => Err(anyhow!("action has been cancelled")),
+            permit = tokio::time::timeout_at(context.deadline, self.sessions.acquire()) => {
+                match permit {
+                    Err(_) => Err(anyhow!(
+                        "action exceeded its deadline while waiting for a free SSH session"
+                    )),
+                    Ok(permit) => permit.map_err(anyhow::Error::from),
+                }
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl ShutdownServer for LimitedShutdownServer {
+    async fn shutdown(&self, context: &ActionContext) -> Result<ActionReport, ShutdownError> {
+        let _permit = self.acquire(context).await?;
+        self.shutdown_server.shutdown(context).await
+    }
+
+    async fn test_connection(&self, context: &ActionContext) -> anyhow::Result<ConnectionTest> {
+        let _permit = self.acquire(context).await?;
+        self.shutdown_server.test_connection(context).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use rstest::*;
+    use tokio_util::sync::CancellationToken;
+
+    use super::*;
+    use crate::dom::TraceContext;
+    use crate::networking::MockShutdownServer;
+
+    fn context(timeout: Duration) -> ActionContext {
+        ActionContext::new(TraceContext::new(), CancellationToken::new(), timeout)
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_limited_shutdown_server_shuts_down_with_free_session() {
+        let mut shutdown_server = MockShutdownServer::new();
+        shutdown_server
+            .expect_shutdown()
+            .once()
+            .return_once(|_| Ok(ActionReport::default()));
+
+        let sessions = Arc::new(Semaphore::new(1));
+        let limited = LimitedShutdownServer::new(Arc::new(shutdown_server), sessions.clone());
+
+        assert!(limited
+            .shutdown(&context(Duration::from_secs(10)))
+            .await
+            .is_ok());
+        // the session is released again
+        assert_eq!(sessions.available_permits(), 1);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_limited_shutdown_server_fails_if_no_session_frees_up_in_time() {
+        let mut shutdown_server = MockShutdownServer::new();
+        shutdown_server.expect_shutdown().never();
+
+        let sessions = Arc::new(Semaphore::new(1));
+        let limited = LimitedShutdownServer::new(Arc::new(shutdown_server), sessions.clone());
+
+        // another server is being shut down
+        let _permit = sessions.acquire().await.unwrap();
+
+        let result = limited.shutdown(&context(Duration::from_millis(10))).await;
+        assert!(result.unwrap_err().to_string().contains("deadline"));
+    }
+}
@@ -0,0 +1,85 @@
+use std::sync::Arc;
+
+use anyhow::bail;
+use async_trait::async_trait;
+use log::warn;
+
+use super::super::dom::ActionContext;
+use super::super::utils::RateLimiter;
+use super::{ActionReport, WakeupServer};
+
+/// Refuses to wake up servers once all wakeup servers sharing the same rate limiter have sent
+/// the maximum number of wakeup requests per minute (e.g. when waking up all servers at once).
+pub struct LimitedWakeupServer {
+    wakeup_server: Arc<dyn WakeupServer>,
+    wakeups: Arc<RateLimiter>,
+}
+
+impl LimitedWakeupServer {
+    pub fn new(wakeup_server: Arc<dyn WakeupServer>, wakeups: Arc<RateLimiter>) -> Self {
+        Self {
+            wakeup_server,
+            wakeups,
+        }
+    }
+}
+
+#[async_trait]
+impl WakeupServer for LimitedWakeupServer {
+    async fn wakeup(&self, context: &ActionContext) -> anyhow::Result<ActionReport> {
+        if !self.wakeups.try_acquire(1) {
+            warn!(
+                "[{}] not sending wakeup request because of the limit of {} wakeups per minute",
+                context,
+                self.wakeups.limit()
+            );
+            bail!(
+                "exceeded the limit of {} wakeups per minute",
+                self.wakeups.limit()
+            );
+        }
+
+        self.wakeup_server.wakeup(context).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use rstest::*;
+    use tokio_util::sync::CancellationToken;
+
+    use super::*;
+    use crate::dom::TraceContext;
+    use crate::networking::MockWakeupServer;
+
+    fn context() -> ActionContext {
+        ActionContext::new(
+            TraceContext::new(),
+            CancellationToken::new(),
+            Duration::from_secs(10),
+        )
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_limited_wakeup_server_refuses_wakeups_exceeding_limit() {
+        let mut wakeup_server = MockWakeupServer::new();
+        wakeup_server
+            .expect_wakeup()
+            .times(2)
+            .returning(|_| Ok(ActionReport::default()));
+
+        let wakeups = Arc::new(RateLimiter::new(2, Duration::from_secs(60)));
+        let limited = LimitedWakeupServer::new(Arc::new(wakeup_server), wakeups);
+
+        assert!(limited.wakeup(&context()).await.is_ok());
+        assert!(limited.wakeup(&context()).await.is_ok());
+        let result = limited.wakeup(&context()).await;
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("limit of 2 wakeups"));
+    }
+}
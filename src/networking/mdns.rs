@@ -0,0 +1,252 @@
+use std::collections::BTreeSet;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, UdpSocket};
+use std::time::{Duration, Instant};
+
+use log::debug;
+
+const MDNS_PORT: u16 = 5353;
+const MAX_RESPONSE_SIZE: usize = 9000;
+// protects against compression pointers pointing at each other
+const MAX_POINTERS: usize = 16;
+
+const HEADER_SIZE: usize = 12;
+const TYPE_PTR: u16 = 12;
+const CLASS_IN: u16 = 1;
+// asks for a unicast response
+const CLASS_UNICAST_RESPONSE: u16 = 0x8000;
+
+static SERVICES_NAME: &str = "_services._dns-sd._udp.local";
+static LOCAL_SUFFIX: &str = ".local";
+
+/// Asks the device at the given IP address directly for the DNS-SD service types it announces
+/// through mDNS and returns them without the `.local` suffix (e.g. `_ipp._tcp`).
+pub fn query_services(ip: IpAddr, timeout: Duration) -> Vec<String> {
+    match try_query_services(ip, timeout) {
+        Ok(services) => services,
+        Err(e) => {
+            debug!("failed to query mDNS services of {}: {}", ip, e);
+            Vec::new()
+        }
+    }
+}
+
+fn try_query_services(ip: IpAddr, timeout: Duration) -> std::io::Result<Vec<String>> {
+    let local_ip = match ip {
+        IpAddr::V4(_) => IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+        IpAddr::V6(_) => IpAddr::V6(Ipv6Addr::UNSPECIFIED),
+    };
+    let socket = UdpSocket::bind(SocketAddr::new(local_ip, 0))?;
+    socket.send_to(&build_query(), SocketAddr::new(ip, MDNS_PORT))?;
+
+    // devices may answer with multiple responses
+    let deadline = Instant::now() + timeout;
+    let mut services = BTreeSet::new();
+    let mut response = [0; MAX_RESPONSE_SIZE];
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            break;
+        }
+        socket.set_read_timeout(Some(remaining))?;
+
+        let (size, peer) = match socket.recv_from(&mut response) {
+            Ok(received) => received,
+            Err(_) => break,
+        };
+        if peer.ip() == ip {
+            services.extend(parse_services(&response[..size]));
+        }
+    }
+
+    Ok(services.into_iter().collect())
+}
+
+fn build_query() -> Vec<u8> {
+    // the header only contains a single question
+    let mut query = vec![0; HEADER_SIZE];
+    query[5] = 1;
+
+    for label in SERVICES_NAME.split('.') {
+        query.push(label.len() as u8);
+        query.extend_from_slice(label.as_bytes());
+    }
+    query.push(0);
+    query.extend_from_slice(&TYPE_PTR.to_be_bytes());
+    query.extend_from_slice(&(CLASS_IN | CLASS_UNICAST_RESPONSE).to_be_bytes());
+
+    query
+}
+
+fn read_u16(packet: &[u8], offset: usize) -> Option<u16> {
+    let bytes = packet.get(offset..offset + 2)?;
+    Some(u16::from_be_bytes([bytes[0], bytes[1]]))
+}
+
+/// Reads the (possibly compressed) name at the given offset and returns it together with the
+/// offset right after it.
+fn read_name(packet: &[u8], offset: usize) -> Option<(String, usize)> {
+    let mut labels = Vec::new();
+    let mut position = offset;
+    let mut end = None;
+    let mut pointers = 0;
+    loop {
+        let length = *packet.get(position)? as usize;
+        if length == 0 {
+            position += 1;
+            break;
+        }
+
+        if length & 0xc0 == 0xc0 {
+            pointers += 1;
+            if pointers > MAX_POINTERS {
+                return None;
+            }
+            let pointer = (read_u16(packet, position)? & 0x3fff) as usize;
+            end.get_or_insert(position + 2);
+            position = pointer;
+            continue;
+        }
+
+        let label = packet.get(position + 1..position + 1 + length)?;
+        labels.push(String::from_utf8_lossy(label).to_string());
+        position += 1 + length;
+    }
+
+    Some((labels.join("."), end.unwrap_or(position)))
+}
+
+fn parse_services(response: &[u8]) -> Vec<String> {
+    let mut services = Vec::new();
+    if response.len() < HEADER_SIZE {
+        return services;
+    }
+
+    let questions = read_u16(response, 4).unwrap_or(0);
+    let records = [6, 8, 10]
+        .iter()
+        .map(|offset| read_u16(response, *offset).unwrap_or(0) as usize)
+        .sum::<usize>();
+
+    let mut offset = HEADER_SIZE;
+    for _ in 0..questions {
+        match read_name(response, offset) {
+            Some((_, end)) => offset = end + 4,
+            None => return services,
+        }
+    }
+
+    for _ in 0..records {
+        let (name, end) = match read_name(response, offset) {
+            Some(name) => name,
+            None => break,
+        };
+        let (record_type, length) = match (read_u16(response, end), read_u16(response, end + 8)) {
+            (Some(record_type), Some(length)) => (record_type, length as usize),
+            _ => break,
+        };
+        let data = end + 10;
+
+        if record_type == TYPE_PTR {
+            // either the answer to the query for all service types or an instance of a service
+            let service = if name.eq_ignore_ascii_case(SERVICES_NAME) {
+                read_name(response, data).map(|(service, _)| service)
+            } else if name.starts_with('_') {
+                Some(name)
+            } else {
+                None
+            };
+            if let Some(service) = service {
+                services.push(
+                    service
+                        .strip_suffix(LOCAL_SUFFIX)
+                        .unwrap_or(&service)
+                        .to_string(),
+                );
+            }
+        }
+
+        offset = data + length;
+    }
+
+    services
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::*;
+
+    use super::*;
+
+    fn encode_name(name: &str) -> Vec<u8> {
+        let mut encoded = Vec::new();
+        for label in name.split('.') {
+            encoded.push(label.len() as u8);
+            encoded.extend_from_slice(label.as_bytes());
+        }
+        encoded.push(0);
+        encoded
+    }
+
+    fn ptr_record(name: &[u8], target: &[u8]) -> Vec<u8> {
+        let mut record = name.to_vec();
+        record.extend_from_slice(&TYPE_PTR.to_be_bytes());
+        record.extend_from_slice(&CLASS_IN.to_be_bytes());
+        record.extend_from_slice(&120u32.to_be_bytes());
+        record.extend_from_slice(&(target.len() as u16).to_be_bytes());
+        record.extend_from_slice(target);
+        record
+    }
+
+    #[rstest]
+    fn test_build_query_asks_for_service_types() {
+        let query = build_query();
+
+        assert_eq!(read_u16(&query, 4), Some(1));
+        assert_eq!(
+            read_name(&query, HEADER_SIZE),
+            Some((SERVICES_NAME.to_string(), query.len() - 4))
+        );
+        assert_eq!(read_u16(&query, query.len() - 4), Some(TYPE_PTR));
+    }
+
+    #[rstest]
+    fn test_parse_services_reads_compressed_names() {
+        let mut response = vec![0, 0, 0x84, 0, 0, 0, 0, 3, 0, 0, 0, 0];
+        // the first record contains the full name of the query
+        let services_offset = response.len() as u16;
+        response.extend(ptr_record(
+            &encode_name(SERVICES_NAME),
+            &encode_name("_ipp._tcp.local"),
+        ));
+        // the second one points to the "_tcp.local" of the first one which follows the name, the
+        // fixed size fields and the "_ipp" label
+        let tcp_offset = services_offset + encode_name(SERVICES_NAME).len() as u16 + 10 + 5;
+        let tcp_pointer = (0xc000 | tcp_offset).to_be_bytes();
+        let mut target = vec![11];
+        target.extend_from_slice(b"_googlecast");
+        target.extend_from_slice(&tcp_pointer);
+        response.extend(ptr_record(
+            &(0xc000 | services_offset).to_be_bytes(),
+            &target,
+        ));
+        // the third one is an instance of a service
+        response.extend(ptr_record(
+            &encode_name("_hap._tcp.local"),
+            &encode_name("Lamp._hap._tcp.local"),
+        ));
+
+        assert_eq!(
+            parse_services(&response),
+            vec!["_ipp._tcp", "_googlecast._tcp", "_hap._tcp"]
+        );
+    }
+
+    #[rstest]
+    fn test_parse_services_ignores_invalid_responses() {
+        assert!(parse_services(&[]).is_empty());
+        // the record claims to be longer than the response
+        let mut response = vec![0, 0, 0x84, 0, 0, 0, 0, 1, 0, 0, 0, 0];
+        response.extend_from_slice(&[0xc0, 0x0c]);
+        assert!(parse_services(&response).is_empty());
+    }
+}
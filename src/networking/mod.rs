@@ -1,33 +1,134 @@
 use pnet::datalink::{interfaces, NetworkInterface};
 
+mod action_hooks;
+mod action_queue;
+mod action_report;
+mod api_relay_wakeup_server;
+mod arp_fallback_pinger;
+mod arp_prober;
+mod arp_responders;
+mod arp_table;
+mod authentication_error;
+mod blocking_action;
+mod command_pinger;
+mod connection_test;
+mod credentials_shutdown_server;
 mod fast_pinger;
+mod fingerprint_prober;
+mod fritzbox_clients;
+mod hooked_shutdown_server;
+mod hooked_wakeup_server;
+mod http_checker;
+mod latency_prober;
+mod limited_pinger;
+mod limited_shutdown_server;
+mod limited_wakeup_server;
+mod mdns;
 mod networking_error;
+mod openwrt_clients;
+mod packet_capture;
 mod pinger;
 mod port_checker;
+mod presence;
+mod presence_pinger;
+mod queued_shutdown_server;
+mod queued_wakeup_server;
+mod router_clients;
 mod shutdown_error;
 mod shutdown_server;
+mod snmp;
+mod snmp_traffic_checker;
+mod source_pinger;
+mod ssh2_client;
+mod ssh2_relay_wakeup_server;
 mod ssh2_shutdown_server;
+mod ssh_error;
+mod ssh_sessions_checker;
+mod standby_shutdown_server;
+mod standby_wakeup_server;
+mod subnet;
 mod tcp_port_checker;
+mod timed_shutdown_server;
+mod timed_wakeup_server;
+mod unifi_clients;
 mod wake_on_lan_server;
 mod wakeup_server;
 
+pub use action_hooks::ActionHooks;
+pub use action_queue::{Action, ActionInProgress, ActionQueue};
+pub use action_report::ActionReport;
+pub use api_relay_wakeup_server::ApiRelayWakeupServer;
+pub use arp_fallback_pinger::ArpFallbackPinger;
+#[cfg(test)]
+pub use arp_prober::MockArpProber;
+pub use arp_prober::{ArpProber, NetworkArpProber};
+pub use arp_responders::ArpResponders;
+pub use arp_table::ArpTable;
+#[cfg(test)]
+pub use arp_table::MockArpTable;
+#[cfg(target_os = "linux")]
+pub use arp_table::ProcArpTable;
+pub use authentication_error::AuthenticationError;
+pub use command_pinger::CommandPinger;
+pub use connection_test::ConnectionTest;
+pub use credentials_shutdown_server::CredentialsShutdownServer;
 pub use fast_pinger::FastPinger;
+#[cfg(test)]
+pub use fingerprint_prober::MockFingerprintProber;
+pub use fingerprint_prober::{FingerprintProber, NetworkFingerprintProber};
+pub use fritzbox_clients::FritzboxRouterClients;
+pub use hooked_shutdown_server::HookedShutdownServer;
+pub use hooked_wakeup_server::HookedWakeupServer;
+pub use http_checker::{agent_builder, HttpChecker};
+pub use latency_prober::{CommandLatencyProber, LatencyProber, LatencyReport};
+#[cfg(test)]
+pub use latency_prober::{MockLatencyProber, PingStatistics, PortLatency, Traceroute};
+pub use limited_pinger::LimitedPinger;
+pub use limited_shutdown_server::LimitedShutdownServer;
+pub use limited_wakeup_server::LimitedWakeupServer;
 pub use networking_error::NetworkingError;
+pub use openwrt_clients::OpenwrtRouterClients;
+#[cfg(test)]
+pub use packet_capture::MockPacketCapture;
+pub use packet_capture::{Capture, NetworkPacketCapture, PacketCapture};
+#[cfg(test)]
+pub use packet_capture::{CapturedPacket, PacketKind};
 #[cfg(test)]
 pub use pinger::MockPinger;
 pub use pinger::Pinger;
-pub use port_checker::PortChecker;
+pub use port_checker::{check_all, PortChecker};
+pub use presence::Presence;
+pub use presence_pinger::PresencePinger;
+pub use queued_shutdown_server::QueuedShutdownServer;
+pub use queued_wakeup_server::QueuedWakeupServer;
+#[cfg(test)]
+pub use router_clients::MockRouterClients;
+pub use router_clients::RouterClients;
 pub use shutdown_error::ShutdownError;
 #[cfg(test)]
 pub use shutdown_server::MockShutdownServer;
 pub use shutdown_server::ShutdownServer;
+pub use snmp_traffic_checker::SnmpTrafficChecker;
+pub use source_pinger::SourcePinger;
+pub use ssh2_client::Ssh2Client;
+pub use ssh2_relay_wakeup_server::Ssh2RelayWakeupServer;
 pub use ssh2_shutdown_server::Ssh2ShutdownServer;
+pub use ssh_error::SshError;
+pub use ssh_sessions_checker::SshSessionsChecker;
+pub use standby_shutdown_server::StandbyShutdownServer;
+pub use standby_wakeup_server::StandbyWakeupServer;
+pub use subnet::is_on_subnet;
 pub use tcp_port_checker::TcpPortChecker;
+pub use timed_shutdown_server::TimedShutdownServer;
+pub use timed_wakeup_server::TimedWakeupServer;
+pub use unifi_clients::UnifiRouterClients;
 pub use wake_on_lan_server::WakeOnLanServer;
 #[cfg(test)]
 pub use wakeup_server::MockWakeupServer;
 pub use wakeup_server::WakeupServer;
 
+use blocking_action::run_blocking;
+
 pub fn get_network_interface(
     interface_name: &str,
 ) -> Result<NetworkInterface, networking_error::NetworkingError> {
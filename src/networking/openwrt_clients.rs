@@ -0,0 +1,182 @@
+use std::collections::{HashMap, HashSet};
+use std::net::IpAddr;
+
+use anyhow::anyhow;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use super::http_checker::create_agent;
+use super::router_clients::REQUEST_TIMEOUT;
+use super::RouterClients;
+use crate::configuration::PresenceProvider;
+use crate::utils::Secret;
+
+// session used to log in
+static ANONYMOUS_SESSION: &str = "00000000000000000000000000000000";
+
+#[derive(Deserialize)]
+struct Session {
+    ubus_rpc_session: String,
+}
+
+#[derive(Deserialize)]
+struct WirelessDevices {
+    devices: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct AssociatedStations {
+    results: Vec<AssociatedStation>,
+}
+
+#[derive(Deserialize)]
+struct AssociatedStation {
+    mac: String,
+}
+
+#[derive(Deserialize)]
+struct HostHint {
+    #[serde(default)]
+    ipaddrs: Vec<IpAddr>,
+}
+
+/// Lists the Wi-Fi clients associated with an OpenWrt router through its ubus JSON-RPC API.
+pub struct OpenwrtRouterClients {
+    agent: ureq::Agent,
+    url: String,
+    username: String,
+    password: Secret,
+}
+
+impl OpenwrtRouterClients {
+    pub fn new(provider: &PresenceProvider, proxy: Option<&str>) -> Self {
+        Self {
+            agent: create_agent(REQUEST_TIMEOUT, provider.insecure, proxy),
+            url: format!("{}/ubus", provider.url.trim_end_matches('/')),
+            username: provider.username.clone(),
+            password: provider.password.clone(),
+        }
+    }
+
+    fn call<T: DeserializeOwned>(
+        &self,
+        session: &str,
+        object: &str,
+        method: &str,
+        arguments: Value,
+    ) -> anyhow::Result<T> {
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "call",
+            "params": [session, object, method, arguments],
+        });
+        let response = self
+            .agent
+            .post(&self.url)
+            .set("Content-Type", "application/json")
+            .send_string(&request.to_string())?
+            .into_string()?;
+
+        Self::parse_result(&response).map_err(|e| anyhow!("{}.{}: {}", object, method, e))
+    }
+
+    /// Extracts the data of a ubus result which consists of a status code and the data.
+    fn parse_result<T: DeserializeOwned>(response: &str) -> anyhow::Result<T> {
+        let response: Value = serde_json::from_str(response)?;
+        match response["result"].as_array().map(Vec::as_slice) {
+            Some([status, data]) if status == 0 => Ok(T::deserialize(data)?),
+            Some([status, ..]) => Err(anyhow!("failed with status {}", status)),
+            _ => Err(anyhow!("{}", response["error"]["message"])),
+        }
+    }
+
+    fn get_clients(
+        stations: Vec<AssociatedStation>,
+        hints: HashMap<String, HostHint>,
+    ) -> HashSet<IpAddr> {
+        // the MAC addresses of the host hints are upper case
+        let hints: HashMap<String, HostHint> = hints
+            .into_iter()
+            .map(|(mac, hint)| (mac.to_lowercase(), hint))
+            .collect();
+
+        stations
+            .iter()
+            .filter_map(|station| hints.get(&station.mac.to_lowercase()))
+            .flat_map(|hint| hint.ipaddrs.iter().cloned())
+            .collect()
+    }
+}
+
+impl RouterClients for OpenwrtRouterClients {
+    fn clients(&self) -> anyhow::Result<HashSet<IpAddr>> {
+        let session: Session = self.call(
+            ANONYMOUS_SESSION,
+            "session",
+            "login",
+            json!({ "username": self.username, "password": self.password.expose() }),
+        )?;
+        let session = session.ubus_rpc_session;
+
+        let devices: WirelessDevices = self.call(&session, "iwinfo", "devices", json!({}))?;
+        let mut stations = Vec::new();
+        for device in devices.devices {
+            let associated: AssociatedStations =
+                self.call(&session, "iwinfo", "assoclist", json!({ "device": device }))?;
+            stations.extend(associated.results);
+        }
+
+        // the associated stations are only known by their MAC addresses
+        let hints = self.call(&session, "luci-rpc", "getHostHints", json!({}))?;
+
+        Ok(Self::get_clients(stations, hints))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::*;
+
+    use super::*;
+
+    #[rstest]
+    fn test_openwrt_router_clients_parses_result() {
+        let session: Session = OpenwrtRouterClients::parse_result(
+            r#"{"jsonrpc":"2.0","id":1,"result":[0,{"ubus_rpc_session":"c1ed6c7b"}]}"#,
+        )
+        .unwrap();
+        assert_eq!(session.ubus_rpc_session, "c1ed6c7b");
+
+        // access denied
+        assert!(OpenwrtRouterClients::parse_result::<Session>(
+            r#"{"jsonrpc":"2.0","id":1,"result":[6]}"#
+        )
+        .is_err());
+        assert!(OpenwrtRouterClients::parse_result::<Session>(
+            r#"{"jsonrpc":"2.0","id":1,"error":{"code":-32002,"message":"Access denied"}}"#
+        )
+        .is_err());
+    }
+
+    #[rstest]
+    fn test_openwrt_router_clients_maps_stations_to_ips() {
+        let stations: AssociatedStations = serde_json::from_str(
+            r#"{"results":[{"mac":"AA:BB:CC:DD:EE:01","signal":-50},{"mac":"AA:BB:CC:DD:EE:09"}]}"#,
+        )
+        .unwrap();
+        let hints: HashMap<String, HostHint> = serde_json::from_str(
+            r#"{
+                "AA:BB:CC:DD:EE:01": { "ipaddrs": ["192.168.1.10"], "name": "phone" },
+                "AA:BB:CC:DD:EE:02": { "ipaddrs": ["192.168.1.11"] }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            OpenwrtRouterClients::get_clients(stations.results, hints),
+            HashSet::from(["192.168.1.10".parse().unwrap()])
+        );
+    }
+}
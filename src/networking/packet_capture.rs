@@ -0,0 +1,320 @@
+use std::fmt;
+use std::net::IpAddr;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+#[cfg(test)]
+use mockall::automock;
+
+use anyhow::anyhow;
+use pnet::datalink::{self, Channel};
+use pnet::packet::arp::{ArpOperations, ArpPacket};
+use pnet::packet::ethernet::{EtherType, EtherTypes, EthernetPacket};
+use pnet::packet::icmp::{IcmpPacket, IcmpTypes};
+use pnet::packet::ip::IpNextHeaderProtocols;
+use pnet::packet::ipv4::Ipv4Packet;
+use pnet::packet::Packet;
+
+use super::get_network_interface;
+use crate::wake_on_lan_listener::parse_magic_packet;
+
+// Wake-on-LAN magic packets can also be sent as raw ethernet frames
+const WAKE_ON_LAN_ETHER_TYPE: EtherType = EtherType(0x0842);
+
+const PCAP_MAGIC: u32 = 0xa1b2c3d4;
+const PCAP_VERSION_MAJOR: u16 = 2;
+const PCAP_VERSION_MINOR: u16 = 4;
+const PCAP_SNAPLEN: u32 = 65535;
+const PCAP_LINKTYPE_ETHERNET: u32 = 1;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PacketKind {
+    ArpRequest,
+    ArpReply,
+    EchoRequest,
+    EchoReply,
+    /// Any other ICMP message (e.g. destination unreachable).
+    Icmp,
+    WakeOnLan,
+}
+
+impl fmt::Display for PacketKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ArpRequest => write!(f, "arpRequest"),
+            Self::ArpReply => write!(f, "arpReply"),
+            Self::EchoRequest => write!(f, "echoRequest"),
+            Self::EchoReply => write!(f, "echoReply"),
+            Self::Icmp => write!(f, "icmp"),
+            Self::WakeOnLan => write!(f, "wakeOnLan"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CapturedPacket {
+    pub kind: PacketKind,
+    /// Whether the device sent the packet (or received it).
+    pub from_device: bool,
+    pub timestamp: SystemTime,
+    pub frame: Vec<u8>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Capture {
+    pub packets: Vec<CapturedPacket>,
+}
+
+impl Capture {
+    /// Returns the captured packets in the pcap format (e.g. for Wireshark or tcpdump).
+    pub fn pcap(&self) -> Vec<u8> {
+        let mut pcap = Vec::new();
+        pcap.extend_from_slice(&PCAP_MAGIC.to_le_bytes());
+        pcap.extend_from_slice(&PCAP_VERSION_MAJOR.to_le_bytes());
+        pcap.extend_from_slice(&PCAP_VERSION_MINOR.to_le_bytes());
+        // GMT offset and accuracy of the timestamps
+        pcap.extend_from_slice(&[0; 8]);
+        pcap.extend_from_slice(&PCAP_SNAPLEN.to_le_bytes());
+        pcap.extend_from_slice(&PCAP_LINKTYPE_ETHERNET.to_le_bytes());
+
+        for packet in self.packets.iter() {
+            let timestamp = packet
+                .timestamp
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default();
+            let len = packet.frame.len() as u32;
+            for value in [
+                timestamp.as_secs() as u32,
+                timestamp.subsec_micros(),
+                len,
+                len,
+            ] {
+                pcap.extend_from_slice(&value.to_le_bytes());
+            }
+            pcap.extend_from_slice(&packet.frame);
+        }
+
+        pcap
+    }
+}
+
+#[cfg_attr(test, automock)]
+pub trait PacketCapture: Send + Sync {
+    /// Captures the ARP, ICMP and Wake-on-LAN traffic of the device with the given IP (and MAC)
+    /// address for the given duration.
+    fn capture(
+        &self,
+        ip: IpAddr,
+        mac: Option<[u8; 6]>,
+        duration: Duration,
+    ) -> anyhow::Result<Capture>;
+}
+
+/// Captures packets through a raw socket on the configured network interface (which requires the
+/// privileges to open raw sockets).
+pub struct NetworkPacketCapture {
+    interface: String,
+}
+
+impl NetworkPacketCapture {
+    pub fn new(interface: &str) -> Self {
+        Self {
+            interface: interface.to_string(),
+        }
+    }
+}
+
+impl PacketCapture for NetworkPacketCapture {
+    fn capture(
+        &self,
+        ip: IpAddr,
+        mac: Option<[u8; 6]>,
+        duration: Duration,
+    ) -> anyhow::Result<Capture> {
+        let interface = get_network_interface(&self.interface).map_err(|e| anyhow!("{}", e))?;
+        let config = datalink::Config {
+            read_timeout: Some(Duration::from_millis(100)),
+            ..Default::default()
+        };
+        let mut rx = match datalink::channel(&interface, config) {
+            Ok(Channel::Ethernet(_, rx)) => rx,
+            Ok(_) => return Err(anyhow!("unsupported channel type on {}", interface.name)),
+            Err(e) => {
+                return Err(anyhow!(
+                    "failed to capture packets on {}: {}",
+                    interface.name,
+                    e
+                ))
+            }
+        };
+
+        let mut capture = Capture::default();
+        let deadline = Instant::now() + duration;
+        while Instant::now() < deadline {
+            let frame = match rx.next() {
+                Ok(frame) => frame,
+                // the read timeout expired
+                Err(_) => continue,
+            };
+
+            if let Some((kind, from_device)) = classify(frame, ip, mac.as_ref()) {
+                capture.packets.push(CapturedPacket {
+                    kind,
+                    from_device,
+                    timestamp: SystemTime::now(),
+                    frame: frame.to_vec(),
+                });
+            }
+        }
+
+        Ok(capture)
+    }
+}
+
+/// Returns the kind of the given ethernet frame and whether it has been sent by the device with
+/// the given IP (and MAC) address if it is relevant to the device.
+pub fn classify(frame: &[u8], ip: IpAddr, mac: Option<&[u8; 6]>) -> Option<(PacketKind, bool)> {
+    let ethernet = EthernetPacket::new(frame)?;
+    let is_magic_packet_for_device = |payload: &[u8]| match (mac, parse_magic_packet(payload)) {
+        (Some(mac), Some(target)) => mac[..] == *target,
+        _ => false,
+    };
+
+    match ethernet.get_ethertype() {
+        EtherTypes::Arp => {
+            let arp = ArpPacket::new(ethernet.payload())?;
+            let from_device = IpAddr::V4(arp.get_sender_proto_addr()) == ip;
+            if !from_device && IpAddr::V4(arp.get_target_proto_addr()) != ip {
+                return None;
+            }
+            match arp.get_operation() {
+                ArpOperations::Request => Some((PacketKind::ArpRequest, from_device)),
+                ArpOperations::Reply => Some((PacketKind::ArpReply, from_device)),
+                _ => None,
+            }
+        }
+        EtherTypes::Ipv4 => {
+            let ipv4 = Ipv4Packet::new(ethernet.payload())?;
+            match ipv4.get_next_level_protocol() {
+                IpNextHeaderProtocols::Icmp => {
+                    let from_device = IpAddr::V4(ipv4.get_source()) == ip;
+                    if !from_device && IpAddr::V4(ipv4.get_destination()) != ip {
+                        return None;
+                    }
+                    let icmp = IcmpPacket::new(ipv4.payload())?;
+                    let kind = match icmp.get_icmp_type() {
+                        IcmpTypes::EchoRequest => PacketKind::EchoRequest,
+                        IcmpTypes::EchoReply => PacketKind::EchoReply,
+                        _ => PacketKind::Icmp,
+                    };
+                    Some((kind, from_device))
+                }
+                // magic packets are usually broadcasted over UDP
+                IpNextHeaderProtocols::Udp if is_magic_packet_for_device(ipv4.payload()) => {
+                    Some((PacketKind::WakeOnLan, false))
+                }
+                _ => None,
+            }
+        }
+        WAKE_ON_LAN_ETHER_TYPE if is_magic_packet_for_device(ethernet.payload()) => {
+            Some((PacketKind::WakeOnLan, false))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv4Addr;
+
+    use pnet::packet::arp::{ArpHardwareTypes, MutableArpPacket};
+    use pnet::packet::ethernet::MutableEthernetPacket;
+    use pnet::packet::icmp::MutableIcmpPacket;
+    use pnet::packet::ipv4::MutableIpv4Packet;
+    use pnet::packet::MutablePacket;
+    use rstest::*;
+
+    use super::*;
+
+    const DEVICE_IP: Ipv4Addr = Ipv4Addr::new(192, 168, 1, 2);
+    const OTHER_IP: Ipv4Addr = Ipv4Addr::new(192, 168, 1, 1);
+    const DEVICE_MAC: [u8; 6] = [0xaa, 0xbb, 0xcc, 0xdd, 0xee, 0xff];
+
+    fn frame(ether_type: EtherType, payload: &[u8]) -> Vec<u8> {
+        let mut buffer = vec![0u8; 14 + payload.len()];
+        let mut ethernet = MutableEthernetPacket::new(&mut buffer).unwrap();
+        ethernet.set_ethertype(ether_type);
+        ethernet.payload_mut().copy_from_slice(payload);
+        buffer
+    }
+
+    fn arp(sender: Ipv4Addr, target: Ipv4Addr) -> Vec<u8> {
+        let mut buffer = vec![0u8; 28];
+        let mut arp = MutableArpPacket::new(&mut buffer).unwrap();
+        arp.set_hardware_type(ArpHardwareTypes::Ethernet);
+        arp.set_operation(ArpOperations::Request);
+        arp.set_sender_proto_addr(sender);
+        arp.set_target_proto_addr(target);
+        frame(EtherTypes::Arp, &buffer)
+    }
+
+    fn ping(source: Ipv4Addr, destination: Ipv4Addr) -> Vec<u8> {
+        let mut buffer = vec![0u8; 20 + 8];
+        let mut ipv4 = MutableIpv4Packet::new(&mut buffer).unwrap();
+        ipv4.set_version(4);
+        ipv4.set_header_length(5);
+        ipv4.set_total_length(28);
+        ipv4.set_next_level_protocol(IpNextHeaderProtocols::Icmp);
+        ipv4.set_source(source);
+        ipv4.set_destination(destination);
+        let mut icmp = MutableIcmpPacket::new(ipv4.payload_mut()).unwrap();
+        icmp.set_icmp_type(IcmpTypes::EchoReply);
+        frame(EtherTypes::Ipv4, &buffer)
+    }
+
+    fn magic_packet(mac: &[u8]) -> Vec<u8> {
+        let mut packet = vec![0xff; 6];
+        for _ in 0..16 {
+            packet.extend_from_slice(mac);
+        }
+        frame(WAKE_ON_LAN_ETHER_TYPE, &packet)
+    }
+
+    #[rstest]
+    #[case(arp(OTHER_IP, DEVICE_IP), Some((PacketKind::ArpRequest, false)))]
+    #[case(arp(DEVICE_IP, OTHER_IP), Some((PacketKind::ArpRequest, true)))]
+    #[case(arp(OTHER_IP, OTHER_IP), None)]
+    #[case(ping(DEVICE_IP, OTHER_IP), Some((PacketKind::EchoReply, true)))]
+    #[case(ping(OTHER_IP, OTHER_IP), None)]
+    #[case(magic_packet(&DEVICE_MAC), Some((PacketKind::WakeOnLan, false)))]
+    #[case(magic_packet(&[1, 2, 3, 4, 5, 6]), None)]
+    fn test_classify_packets_of_device(
+        #[case] frame: Vec<u8>,
+        #[case] expected: Option<(PacketKind, bool)>,
+    ) {
+        assert_eq!(
+            classify(&frame, IpAddr::V4(DEVICE_IP), Some(&DEVICE_MAC)),
+            expected
+        );
+    }
+
+    #[rstest]
+    fn test_capture_is_written_as_pcap() {
+        let frame = arp(OTHER_IP, DEVICE_IP);
+        let capture = Capture {
+            packets: vec![CapturedPacket {
+                kind: PacketKind::ArpRequest,
+                from_device: false,
+                timestamp: UNIX_EPOCH + Duration::from_micros(1_000_002),
+                frame: frame.clone(),
+            }],
+        };
+
+        let pcap = capture.pcap();
+        assert_eq!(pcap.len(), 24 + 16 + frame.len());
+        assert_eq!(pcap[..4], PCAP_MAGIC.to_le_bytes());
+        // seconds and microseconds of the packet
+        assert_eq!(pcap[24..28], 1u32.to_le_bytes());
+        assert_eq!(pcap[28..32], 2u32.to_le_bytes());
+        assert_eq!(pcap[40..], frame[..]);
+    }
+}
@@ -7,6 +7,7 @@ use mockall::automock;
 #[cfg_attr(test, automock)]
 pub trait Pinger: Send {
     fn add_target(&mut self, ip_addr: IpAddr) -> bool;
+    fn remove_target(&mut self, ip_addr: &IpAddr) -> bool;
 
     fn ping_once(&self);
     fn recv_pong(&mut self) -> Result<(), RecvError>;
@@ -1,7 +1,47 @@
+use std::sync::Arc;
+
 #[cfg(test)]
 use mockall::automock;
 
 #[cfg_attr(test, automock)]
-pub trait PortChecker {
+pub trait PortChecker: Send + Sync {
     fn check(&self) -> bool;
 }
+
+/// Runs all the given checks concurrently and returns their results in the same order.
+pub fn check_all(checkers: &[Arc<dyn PortChecker>]) -> Vec<bool> {
+    std::thread::scope(|scope| {
+        let checks: Vec<_> = checkers
+            .iter()
+            .map(|checker| scope.spawn(move || checker.check()))
+            .collect();
+
+        checks
+            .into_iter()
+            .map(|check| check.join().unwrap_or(false))
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mocked_checker(result: bool) -> Arc<dyn PortChecker> {
+        let mut checker = MockPortChecker::new();
+        checker.expect_check().once().return_const(result);
+
+        Arc::new(checker)
+    }
+
+    #[test]
+    fn test_check_all_keeps_order_of_results() {
+        let checkers = vec![
+            mocked_checker(true),
+            mocked_checker(false),
+            mocked_checker(true),
+        ];
+
+        assert_eq!(check_all(&checkers), vec![true, false, true]);
+    }
+}
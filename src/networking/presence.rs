@@ -0,0 +1,31 @@
+use std::collections::{HashMap, HashSet};
+use std::net::IpAddr;
+use std::sync::RwLock;
+
+/// IP addresses of the clients currently associated with every configured router.
+#[derive(Debug, Default)]
+pub struct Presence {
+    clients: RwLock<HashMap<String, HashSet<IpAddr>>>,
+}
+
+impl Presence {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replaces the clients reported by the given router.
+    pub fn set_clients(&self, router: &str, clients: HashSet<IpAddr>) {
+        self.clients
+            .write()
+            .unwrap()
+            .insert(router.to_string(), clients);
+    }
+
+    pub fn is_present(&self, ip: &IpAddr) -> bool {
+        self.clients
+            .read()
+            .unwrap()
+            .values()
+            .any(|clients| clients.contains(ip))
+    }
+}
@@ -0,0 +1,79 @@
+use std::net::IpAddr;
+use std::sync::mpsc::RecvError;
+use std::sync::Arc;
+
+use super::{Pinger, Presence};
+
+/// Considers devices which are associated with one of the routers as online even if they don't
+/// respond to pings (e.g. phones in power saving mode).
+pub struct PresencePinger {
+    pinger: Box<dyn Pinger>,
+    presence: Arc<Presence>,
+}
+
+impl PresencePinger {
+    pub fn new(pinger: Box<dyn Pinger>, presence: Arc<Presence>) -> Self {
+        Self { pinger, presence }
+    }
+}
+
+impl Pinger for PresencePinger {
+    fn add_target(&mut self, ip_addr: IpAddr) -> bool {
+        self.pinger.add_target(ip_addr)
+    }
+
+    fn remove_target(&mut self, ip_addr: &IpAddr) -> bool {
+        self.pinger.remove_target(ip_addr)
+    }
+
+    fn ping_once(&self) {
+        self.pinger.ping_once()
+    }
+
+    fn recv_pong(&mut self) -> Result<(), RecvError> {
+        self.pinger.recv_pong()
+    }
+
+    fn is_online(&self, ip_addr: &IpAddr) -> bool {
+        self.pinger.is_online(ip_addr) || self.presence.is_present(ip_addr)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use mockall::predicate::*;
+    use rstest::*;
+
+    use super::*;
+    use crate::networking::MockPinger;
+
+    #[rstest]
+    fn test_presence_pinger_considers_router_clients_online() {
+        // SETUP
+        let pinging: IpAddr = "192.168.1.2".parse().unwrap();
+        let associated: IpAddr = "192.168.1.3".parse().unwrap();
+        let offline: IpAddr = "192.168.1.4".parse().unwrap();
+
+        let mut pinger = MockPinger::new();
+        pinger
+            .expect_is_online()
+            .with(eq(pinging))
+            .return_const(true);
+        pinger.expect_is_online().return_const(false);
+
+        let presence = Arc::new(Presence::new());
+        presence.set_clients("router", HashSet::from([associated]));
+        let presence_pinger = PresencePinger::new(Box::new(pinger), presence.clone());
+
+        // TESTING
+        assert!(presence_pinger.is_online(&pinging));
+        assert!(presence_pinger.is_online(&associated));
+        assert!(!presence_pinger.is_online(&offline));
+
+        // the client left the router
+        presence.set_clients("router", HashSet::new());
+        assert!(!presence_pinger.is_online(&associated));
+    }
+}
@@ -0,0 +1,90 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use super::super::dom::ActionContext;
+use super::{Action, ActionQueue, ActionReport, ConnectionTest, ShutdownError, ShutdownServer};
+
+/// Deduplicates shutdowns of a server requested from multiple places at the same time and
+/// returns a [`ShutdownError`] with the shutdown already in progress for the duplicates.
+pub struct QueuedShutdownServer {
+    shutdown_server: Arc<dyn ShutdownServer>,
+    queue: Arc<ActionQueue>,
+}
+
+impl QueuedShutdownServer {
+    pub fn new(shutdown_server: Arc<dyn ShutdownServer>, queue: Arc<ActionQueue>) -> Self {
+        Self {
+            shutdown_server,
+            queue,
+        }
+    }
+}
+
+#[async_trait]
+impl ShutdownServer for QueuedShutdownServer {
+    async fn shutdown(&self, context: &ActionContext) -> Result<ActionReport, ShutdownError> {
+        let mut guard = self.queue.start(Action::Shutdown)?;
+        let report = self.shutdown_server.shutdown(context).await?;
+        guard.succeeded();
+
+        Ok(report)
+    }
+
+    async fn test_connection(&self, context: &ActionContext) -> anyhow::Result<ConnectionTest> {
+        self.shutdown_server.test_connection(context).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use rstest::*;
+    use tokio_util::sync::CancellationToken;
+
+    use super::*;
+    use crate::dom::TraceContext;
+    use crate::networking::MockShutdownServer;
+
+    fn context() -> ActionContext {
+        ActionContext::new(
+            TraceContext::new(),
+            CancellationToken::new(),
+            Duration::from_secs(10),
+        )
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_queued_shutdown_server_only_deduplicates_successful_shutdowns() {
+        // SETUP
+        let mut shutdown_server = MockShutdownServer::new();
+        let mut sequence = mockall::Sequence::new();
+        shutdown_server
+            .expect_shutdown()
+            .once()
+            .in_sequence(&mut sequence)
+            .return_once(|_| Err(ShutdownError::new("unreachable".to_string())));
+        shutdown_server
+            .expect_shutdown()
+            .once()
+            .in_sequence(&mut sequence)
+            .return_once(|_| Ok(ActionReport::default()));
+
+        let queued = QueuedShutdownServer::new(
+            Arc::new(shutdown_server),
+            Arc::new(ActionQueue::new("Server", Duration::from_secs(10))),
+        );
+
+        // TESTING
+        // a failed shutdown can be retried right away
+        let error = queued.shutdown(&context()).await.unwrap_err();
+        assert!(error.in_progress().is_none());
+        assert!(queued.shutdown(&context()).await.is_ok());
+
+        // whereas a successful one isn't repeated within the window
+        let error = queued.shutdown(&context()).await.unwrap_err();
+        assert!(error.in_progress().is_some());
+    }
+}
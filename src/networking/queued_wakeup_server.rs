@@ -0,0 +1,88 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use super::super::dom::ActionContext;
+use super::{Action, ActionQueue, ActionReport, WakeupServer};
+
+/// Deduplicates wakeups of a server requested from multiple places at the same time and returns
+/// an [`ActionInProgress`](super::ActionInProgress) error for the duplicates.
+pub struct QueuedWakeupServer {
+    wakeup_server: Arc<dyn WakeupServer>,
+    queue: Arc<ActionQueue>,
+}
+
+impl QueuedWakeupServer {
+    pub fn new(wakeup_server: Arc<dyn WakeupServer>, queue: Arc<ActionQueue>) -> Self {
+        Self {
+            wakeup_server,
+            queue,
+        }
+    }
+}
+
+#[async_trait]
+impl WakeupServer for QueuedWakeupServer {
+    async fn wakeup(&self, context: &ActionContext) -> anyhow::Result<ActionReport> {
+        let mut guard = self.queue.start(Action::Wakeup)?;
+        let report = self.wakeup_server.wakeup(context).await?;
+        guard.succeeded();
+
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use rstest::*;
+    use tokio::sync::oneshot;
+    use tokio_util::sync::CancellationToken;
+
+    use super::*;
+    use crate::dom::TraceContext;
+    use crate::networking::{ActionInProgress, MockWakeupServer};
+
+    fn context() -> ActionContext {
+        ActionContext::new(
+            TraceContext::new(),
+            CancellationToken::new(),
+            Duration::from_secs(10),
+        )
+    }
+
+    #[rstest]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_queued_wakeup_server_deduplicates_simultaneous_wakeups() {
+        // SETUP
+        let (started_tx, started_rx) = oneshot::channel();
+        let (finish_tx, finish_rx) = std::sync::mpsc::channel::<()>();
+        let mut wakeup_server = MockWakeupServer::new();
+        wakeup_server.expect_wakeup().once().return_once(move |_| {
+            started_tx.send(()).unwrap();
+            finish_rx.recv().unwrap();
+            Ok(ActionReport::default())
+        });
+
+        let queued = Arc::new(QueuedWakeupServer::new(
+            Arc::new(wakeup_server),
+            Arc::new(ActionQueue::new("Server", Duration::from_secs(10))),
+        ));
+
+        // TESTING
+        let wakeup = {
+            let queued = queued.clone();
+            tokio::spawn(async move { queued.wakeup(&context()).await })
+        };
+        started_rx.await.unwrap();
+
+        let duplicate = queued.wakeup(&context()).await.unwrap_err();
+        assert!(duplicate.downcast_ref::<ActionInProgress>().is_some());
+
+        finish_tx.send(()).unwrap();
+        assert!(wakeup.await.unwrap().is_ok());
+        // a wakeup right after a successful one is a duplicate as well
+        assert!(queued.wakeup(&context()).await.is_err());
+    }
+}
@@ -0,0 +1,16 @@
+use std::collections::HashSet;
+use std::net::IpAddr;
+use std::time::Duration;
+
+#[cfg(test)]
+use mockall::automock;
+
+/// Maximum time a single request to a router may take.
+pub(super) const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Queries a router for the clients which are currently associated with it.
+#[cfg_attr(test, automock)]
+pub trait RouterClients: Send + Sync {
+    /// Returns the IP addresses of all associated clients.
+    fn clients(&self) -> anyhow::Result<HashSet<IpAddr>>;
+}
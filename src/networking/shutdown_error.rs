@@ -1,11 +1,31 @@
 use std::fmt;
 
+use super::{ActionInProgress, AuthenticationError};
+
 #[derive(Debug)]
-pub struct ShutdownError(String);
+pub struct ShutdownError {
+    message: String,
+    in_progress: Option<ActionInProgress>,
+    authentication: Option<AuthenticationError>,
+}
 
 impl ShutdownError {
     pub fn new(error_msg: String) -> Self {
-        Self(error_msg)
+        Self {
+            message: error_msg,
+            in_progress: None,
+            authentication: None,
+        }
+    }
+
+    /// Returns the shutdown already in progress if the shutdown has been deduplicated.
+    pub fn in_progress(&self) -> Option<&ActionInProgress> {
+        self.in_progress.as_ref()
+    }
+
+    /// Returns the authentication error if the server rejected the configured credentials.
+    pub fn authentication(&self) -> Option<&AuthenticationError> {
+        self.authentication.as_ref()
     }
 }
 
@@ -13,6 +33,72 @@ impl std::error::Error for ShutdownError {}
 
 impl fmt::Display for ShutdownError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "[ShutdownError] {}", self.0)
+        match &self.authentication {
+            Some(authentication) => write!(f, "{authentication}"),
+            None => write!(f, "[ShutdownError] {}", self.message),
+        }
+    }
+}
+
+impl From<super::SshError> for ShutdownError {
+    fn from(e: super::SshError) -> Self {
+        if e.is_authentication() {
+            Self::from(AuthenticationError::new(e.to_string()))
+        } else {
+            Self::new(e.to_string())
+        }
+    }
+}
+
+impl From<anyhow::Error> for ShutdownError {
+    fn from(e: anyhow::Error) -> Self {
+        // keep the details of SSH errors which went through a blocking task
+        match e.downcast::<super::SshError>() {
+            Ok(e) => Self::from(e),
+            Err(e) => Self::new(e.to_string()),
+        }
+    }
+}
+
+impl From<ActionInProgress> for ShutdownError {
+    fn from(e: ActionInProgress) -> Self {
+        Self {
+            message: e.to_string(),
+            in_progress: Some(e),
+            authentication: None,
+        }
+    }
+}
+
+impl From<AuthenticationError> for ShutdownError {
+    fn from(e: AuthenticationError) -> Self {
+        Self {
+            message: e.to_string(),
+            in_progress: None,
+            authentication: Some(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::*;
+
+    use super::*;
+    use crate::networking::SshError;
+
+    #[rstest]
+    fn test_shutdown_error_keeps_authentication_errors_of_blocking_tasks() {
+        let e = ShutdownError::from(anyhow::Error::from(SshError::authentication(
+            "wrong password".to_string(),
+        )));
+        assert!(e.authentication().is_some());
+        assert!(e.to_string().starts_with("[AuthenticationError]"));
+
+        let e = ShutdownError::from(anyhow::Error::from(SshError::new(
+            "connection refused".to_string(),
+        )));
+        assert!(e.authentication().is_none());
+        assert!(e.to_string().starts_with("[ShutdownError]"));
     }
 }
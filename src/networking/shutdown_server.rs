@@ -1,9 +1,18 @@
+use anyhow::anyhow;
+use async_trait::async_trait;
 #[cfg(test)]
 use mockall::automock;
 
-use super::ShutdownError;
+use super::super::dom::ActionContext;
+use super::{ActionReport, ConnectionTest, ShutdownError};
 
 #[cfg_attr(test, automock)]
+#[async_trait]
 pub trait ShutdownServer: Send + Sync {
-    fn shutdown(&self) -> Result<(), ShutdownError>;
+    async fn shutdown(&self, context: &ActionContext) -> Result<ActionReport, ShutdownError>;
+
+    /// Tests the connection used to shut down the server without shutting it down.
+    async fn test_connection(&self, _context: &ActionContext) -> anyhow::Result<ConnectionTest> {
+        Err(anyhow!("the connection to the server can't be tested"))
+    }
 }
@@ -0,0 +1,382 @@
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, ToSocketAddrs, UdpSocket};
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, bail};
+use rand::rngs::OsRng;
+use rand::RngCore;
+
+const MAX_RESPONSE_SIZE: usize = 1500;
+const VERSION_2C: i64 = 1;
+
+const TAG_INTEGER: u8 = 0x02;
+const TAG_OCTET_STRING: u8 = 0x04;
+const TAG_NULL: u8 = 0x05;
+const TAG_OID: u8 = 0x06;
+const TAG_SEQUENCE: u8 = 0x30;
+const TAG_COUNTER32: u8 = 0x41;
+const TAG_GAUGE32: u8 = 0x42;
+const TAG_COUNTER64: u8 = 0x46;
+const TAG_GET_REQUEST: u8 = 0xa0;
+const TAG_RESPONSE: u8 = 0xa2;
+
+/// Asks the SNMP agent at the given address for the given (numeric) values using SNMPv2c and
+/// returns them in the same order.
+pub fn get(
+    host: &str,
+    port: u16,
+    community: &str,
+    oids: &[Vec<u32>],
+    timeout: Duration,
+) -> anyhow::Result<Vec<u64>> {
+    let address = (host, port)
+        .to_socket_addrs()?
+        .next()
+        .ok_or_else(|| anyhow!("failed to resolve {}", host))?;
+    let local_ip = match address.ip() {
+        IpAddr::V4(_) => IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+        IpAddr::V6(_) => IpAddr::V6(Ipv6Addr::UNSPECIFIED),
+    };
+    let socket = UdpSocket::bind(SocketAddr::new(local_ip, 0))?;
+
+    // keep the request ID positive to not have to care about its sign
+    let request_id = (OsRng.next_u32() >> 1) as i64;
+    socket.send_to(&build_get_request(community, request_id, oids), address)?;
+
+    // unrelated packets don't extend the time to wait for the response
+    let deadline = Instant::now() + timeout;
+    let mut response = [0; MAX_RESPONSE_SIZE];
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            bail!("timed out waiting for the response of {}", address);
+        }
+        socket.set_read_timeout(Some(remaining))?;
+
+        let (size, peer) = socket.recv_from(&mut response)?;
+        if peer != address {
+            continue;
+        }
+
+        let (response_id, values) = parse_response(&response[..size])?;
+        if response_id == request_id {
+            return Ok(values);
+        }
+    }
+}
+
+fn encode_length(length: usize, encoded: &mut Vec<u8>) {
+    if length < 0x80 {
+        encoded.push(length as u8);
+        return;
+    }
+
+    let bytes: Vec<u8> = length
+        .to_be_bytes()
+        .iter()
+        .copied()
+        .skip_while(|byte| *byte == 0)
+        .collect();
+    encoded.push(0x80 | bytes.len() as u8);
+    encoded.extend(bytes);
+}
+
+fn encode(tag: u8, value: &[u8]) -> Vec<u8> {
+    let mut encoded = vec![tag];
+    encode_length(value.len(), &mut encoded);
+    encoded.extend_from_slice(value);
+    encoded
+}
+
+fn encode_integer(value: i64) -> Vec<u8> {
+    // the shortest two's complement representation
+    let bytes = value.to_be_bytes();
+    let mut start = 0;
+    while start < bytes.len() - 1
+        && ((bytes[start] == 0 && bytes[start + 1] & 0x80 == 0)
+            || (bytes[start] == 0xff && bytes[start + 1] & 0x80 != 0))
+    {
+        start += 1;
+    }
+
+    encode(TAG_INTEGER, &bytes[start..])
+}
+
+fn encode_oid(oid: &[u32]) -> Vec<u8> {
+    let mut value = Vec::new();
+    if oid.len() >= 2 {
+        value.push((oid[0] * 40 + oid[1]) as u8);
+    }
+    for &component in oid.iter().skip(2) {
+        // base 128 with the highest bit marking all but the last byte
+        let mut bytes = vec![(component & 0x7f) as u8];
+        let mut remaining = component >> 7;
+        while remaining > 0 {
+            bytes.push(0x80 | (remaining & 0x7f) as u8);
+            remaining >>= 7;
+        }
+        value.extend(bytes.into_iter().rev());
+    }
+
+    encode(TAG_OID, &value)
+}
+
+fn build_get_request(community: &str, request_id: i64, oids: &[Vec<u32>]) -> Vec<u8> {
+    let bindings: Vec<u8> = oids
+        .iter()
+        .flat_map(|oid| {
+            let mut binding = encode_oid(oid);
+            binding.extend(encode(TAG_NULL, &[]));
+            encode(TAG_SEQUENCE, &binding)
+        })
+        .collect();
+
+    let mut pdu = encode_integer(request_id);
+    // error status and error index
+    pdu.extend(encode_integer(0));
+    pdu.extend(encode_integer(0));
+    pdu.extend(encode(TAG_SEQUENCE, &bindings));
+
+    let mut message = encode_integer(VERSION_2C);
+    message.extend(encode(TAG_OCTET_STRING, community.as_bytes()));
+    message.extend(encode(TAG_GET_REQUEST, &pdu));
+    encode(TAG_SEQUENCE, &message)
+}
+
+/// Reads the element at the beginning of the given data and returns its tag, its value and the
+/// remaining data.
+fn read(data: &[u8]) -> anyhow::Result<(u8, &[u8], &[u8])> {
+    let (&tag, data) = data
+        .split_first()
+        .ok_or_else(|| anyhow!("truncated response"))?;
+    let (&length, mut data) = data
+        .split_first()
+        .ok_or_else(|| anyhow!("truncated response"))?;
+
+    let length = if length & 0x80 == 0 {
+        length as usize
+    } else {
+        let bytes = (length & 0x7f) as usize;
+        if bytes > std::mem::size_of::<usize>() || data.len() < bytes {
+            bail!("invalid length");
+        }
+        let (length, remaining) = data.split_at(bytes);
+        data = remaining;
+        length
+            .iter()
+            .fold(0, |length, byte| (length << 8) | *byte as usize)
+    };
+    if data.len() < length {
+        bail!("truncated response");
+    }
+
+    let (value, remaining) = data.split_at(length);
+    Ok((tag, value, remaining))
+}
+
+fn read_expected(data: &[u8], expected_tag: u8) -> anyhow::Result<(&[u8], &[u8])> {
+    let (tag, value, remaining) = read(data)?;
+    if tag != expected_tag {
+        bail!(
+            "unexpected tag {:#04x} instead of {:#04x}",
+            tag,
+            expected_tag
+        );
+    }
+
+    Ok((value, remaining))
+}
+
+fn decode_unsigned(value: &[u8]) -> anyhow::Result<u64> {
+    // unsigned values may have a leading zero byte to keep them positive
+    let value = match value {
+        [0, rest @ ..] => rest,
+        _ => value,
+    };
+    if value.len() > 8 {
+        bail!("value is too large");
+    }
+
+    Ok(value
+        .iter()
+        .fold(0, |number, byte| (number << 8) | *byte as u64))
+}
+
+fn decode_integer(value: &[u8]) -> anyhow::Result<i64> {
+    if value.is_empty() || value.len() > 8 {
+        bail!("invalid integer");
+    }
+
+    let initial = if value[0] & 0x80 != 0 { -1 } else { 0 };
+    Ok(value
+        .iter()
+        .fold(initial, |number, byte| (number << 8) | *byte as i64))
+}
+
+/// Returns the request ID and the values of the bindings of the given response.
+fn parse_response(response: &[u8]) -> anyhow::Result<(i64, Vec<u64>)> {
+    let (message, _) = read_expected(response, TAG_SEQUENCE)?;
+    let (_, message) = read_expected(message, TAG_INTEGER)?;
+    let (_, message) = read_expected(message, TAG_OCTET_STRING)?;
+    let (pdu, _) = read_expected(message, TAG_RESPONSE)?;
+
+    let (request_id, pdu) = read_expected(pdu, TAG_INTEGER)?;
+    let (error_status, pdu) = read_expected(pdu, TAG_INTEGER)?;
+    let (_, pdu) = read_expected(pdu, TAG_INTEGER)?;
+    let error_status = decode_integer(error_status)?;
+    if error_status != 0 {
+        bail!("agent returned error status {}", error_status);
+    }
+
+    let (mut bindings, _) = read_expected(pdu, TAG_SEQUENCE)?;
+    let mut values = Vec::new();
+    while !bindings.is_empty() {
+        let (binding, remaining) = read_expected(bindings, TAG_SEQUENCE)?;
+        bindings = remaining;
+
+        let (_, binding) = read_expected(binding, TAG_OID)?;
+        let (tag, value, _) = read(binding)?;
+        match tag {
+            TAG_COUNTER32 | TAG_GAUGE32 | TAG_COUNTER64 => values.push(decode_unsigned(value)?),
+            TAG_INTEGER => match decode_integer(value)? {
+                value if value < 0 => bail!("unexpected negative value {}", value),
+                value => values.push(value as u64),
+            },
+            // e.g. noSuchObject or noSuchInstance
+            _ => bail!("unsupported value {:#04x}", tag),
+        }
+    }
+
+    Ok((decode_integer(request_id)?, values))
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::*;
+
+    use super::*;
+
+    #[rstest]
+    #[case(0, vec![0x02, 0x01, 0x00])]
+    #[case(127, vec![0x02, 0x01, 0x7f])]
+    #[case(128, vec![0x02, 0x02, 0x00, 0x80])]
+    #[case(-1, vec![0x02, 0x01, 0xff])]
+    #[case(305419896, vec![0x02, 0x04, 0x12, 0x34, 0x56, 0x78])]
+    fn test_encode_integer(#[case] value: i64, #[case] expected: Vec<u8>) {
+        assert_eq!(encode_integer(value), expected);
+        assert_eq!(decode_integer(&expected[2..]).unwrap(), value);
+    }
+
+    #[rstest]
+    fn test_build_get_request_encodes_oids() {
+        // ifHCInOctets.1 requested with the community "public"
+        let request = build_get_request("public", 1, &[vec![1, 3, 6, 1, 2, 1, 31, 1, 1, 1, 6, 1]]);
+
+        assert_eq!(
+            request,
+            vec![
+                0x30, 0x29, 0x02, 0x01, 0x01, 0x04, 0x06, b'p', b'u', b'b', b'l', b'i', b'c', 0xa0,
+                0x1c, 0x02, 0x01, 0x01, 0x02, 0x01, 0x00, 0x02, 0x01, 0x00, 0x30, 0x11, 0x30, 0x0f,
+                0x06, 0x0b, 0x2b, 0x06, 0x01, 0x02, 0x01, 0x1f, 0x01, 0x01, 0x01, 0x06, 0x01, 0x05,
+                0x00,
+            ]
+        );
+    }
+
+    #[rstest]
+    fn test_parse_response_reads_counters() {
+        let binding = |value: Vec<u8>| {
+            let mut binding = encode_oid(&[1, 3, 6, 1, 2, 1, 31, 1, 1, 1, 6, 1]);
+            binding.extend(value);
+            encode(TAG_SEQUENCE, &binding)
+        };
+        let mut bindings = binding(encode(TAG_COUNTER64, &[0x00, 0xff, 0x00, 0x00, 0x00, 0x01]));
+        bindings.extend(binding(encode(TAG_COUNTER32, &[0x01, 0x00])));
+
+        let mut pdu = encode_integer(42);
+        pdu.extend(encode_integer(0));
+        pdu.extend(encode_integer(0));
+        pdu.extend(encode(TAG_SEQUENCE, &bindings));
+        let mut message = encode_integer(VERSION_2C);
+        message.extend(encode(TAG_OCTET_STRING, b"public"));
+        message.extend(encode(TAG_RESPONSE, &pdu));
+
+        assert_eq!(
+            parse_response(&encode(TAG_SEQUENCE, &message)).unwrap(),
+            (42, vec![0xff00000001, 256])
+        );
+    }
+
+    #[rstest]
+    fn test_parse_response_fails_for_missing_values() {
+        let mut binding = encode_oid(&[1, 3, 6, 1, 2, 1, 31, 1, 1, 1, 6, 99]);
+        // noSuchInstance
+        binding.extend(encode(0x81, &[]));
+
+        let mut pdu = encode_integer(42);
+        pdu.extend(encode_integer(0));
+        pdu.extend(encode_integer(0));
+        pdu.extend(encode(TAG_SEQUENCE, &encode(TAG_SEQUENCE, &binding)));
+        let mut message = encode_integer(VERSION_2C);
+        message.extend(encode(TAG_OCTET_STRING, b"public"));
+        message.extend(encode(TAG_RESPONSE, &pdu));
+
+        assert!(parse_response(&encode(TAG_SEQUENCE, &message)).is_err());
+        assert!(parse_response(&[0x30, 0x10, 0x02]).is_err());
+    }
+
+    #[rstest]
+    #[case(encode_integer(1000), Some(1000))]
+    #[case(encode_integer(-1), None)]
+    fn test_parse_response_rejects_negative_integers(
+        #[case] value: Vec<u8>,
+        #[case] expected: Option<u64>,
+    ) {
+        let mut binding = encode_oid(&[1, 3, 6, 1, 2, 1, 2, 2, 1, 5, 1]);
+        binding.extend(value);
+
+        let mut pdu = encode_integer(42);
+        pdu.extend(encode_integer(0));
+        pdu.extend(encode_integer(0));
+        pdu.extend(encode(TAG_SEQUENCE, &encode(TAG_SEQUENCE, &binding)));
+        let mut message = encode_integer(VERSION_2C);
+        message.extend(encode(TAG_OCTET_STRING, b"public"));
+        message.extend(encode(TAG_RESPONSE, &pdu));
+
+        assert_eq!(
+            parse_response(&encode(TAG_SEQUENCE, &message))
+                .ok()
+                .map(|(_, values)| values[0]),
+            expected
+        );
+    }
+
+    #[rstest]
+    fn test_get_times_out_despite_unrelated_packets() {
+        let agent = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let port = agent.local_addr().unwrap().port();
+        let timeout = Duration::from_millis(300);
+
+        // keep answering with responses to other requests
+        std::thread::spawn(move || {
+            let mut request = [0; MAX_RESPONSE_SIZE];
+            let mut pdu = encode_integer(-1);
+            pdu.extend(encode_integer(0));
+            pdu.extend(encode_integer(0));
+            pdu.extend(encode(TAG_SEQUENCE, &[]));
+            let mut message = encode_integer(VERSION_2C);
+            message.extend(encode(TAG_OCTET_STRING, b"public"));
+            message.extend(encode(TAG_RESPONSE, &pdu));
+            let response = encode(TAG_SEQUENCE, &message);
+
+            let (_, peer) = agent.recv_from(&mut request).unwrap();
+            for _ in 0..10 {
+                let _ = agent.send_to(&response, peer);
+                std::thread::sleep(Duration::from_millis(100));
+            }
+        });
+
+        let start = Instant::now();
+        assert!(get("127.0.0.1", port, "public", &[vec![1, 3, 6, 1]], timeout).is_err());
+        assert!(start.elapsed() < 3 * timeout);
+    }
+}
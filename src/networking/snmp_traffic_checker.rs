@@ -0,0 +1,121 @@
+use std::sync::Mutex;
+
+use log::debug;
+
+use super::{snmp, PortChecker};
+use crate::dom::device::SnmpCheck;
+use crate::utils::Instant;
+
+// ifHCInOctets and ifHCOutOctets of the interface table
+static IN_OCTETS_OID: [u32; 11] = [1, 3, 6, 1, 2, 1, 31, 1, 1, 1, 6];
+static OUT_OCTETS_OID: [u32; 11] = [1, 3, 6, 1, 2, 1, 31, 1, 1, 1, 10];
+
+/// Considers a switch port in use while its traffic since the previous check (i.e. averaged over
+/// the cache duration of the check) reaches the configured threshold. The first check only takes
+/// a sample of the traffic counters and is therefore never in use.
+pub struct SnmpTrafficChecker {
+    snmp: SnmpCheck,
+    last_sample: Mutex<Option<(Instant, u64)>>,
+}
+
+impl SnmpTrafficChecker {
+    pub fn new(snmp: &SnmpCheck) -> Self {
+        Self {
+            snmp: snmp.clone(),
+            last_sample: Mutex::new(None),
+        }
+    }
+
+    fn query_octets(&self) -> anyhow::Result<u64> {
+        let oid = |oid: &[u32]| {
+            let mut oid = oid.to_vec();
+            oid.push(self.snmp.interface);
+            oid
+        };
+        let values = snmp::get(
+            &self.snmp.host,
+            self.snmp.port,
+            self.snmp.community.expose(),
+            &[oid(&IN_OCTETS_OID), oid(&OUT_OCTETS_OID)],
+            self.snmp.timeout,
+        )?;
+
+        Ok(values
+            .iter()
+            .fold(0u64, |octets, value| octets.wrapping_add(*value)))
+    }
+
+    /// Remembers the given sample and returns whether the traffic since the last sample reaches
+    /// the threshold.
+    fn add_sample(&self, now: Instant, octets: u64) -> bool {
+        let last_sample = self.last_sample.lock().unwrap().replace((now, octets));
+        let (then, last_octets) = match last_sample {
+            Some(last_sample) => last_sample,
+            None => return false,
+        };
+
+        // the counters are reset if the switch restarts
+        if octets < last_octets {
+            return false;
+        }
+        let elapsed = now.duration_since(then).as_secs_f64();
+        if elapsed <= 0.0 {
+            return false;
+        }
+
+        let bytes_per_second = (octets - last_octets) as f64 / elapsed;
+        debug!("{}: {:.0} bytes per second", self.snmp, bytes_per_second);
+
+        bytes_per_second >= self.snmp.threshold as f64
+    }
+}
+
+impl PortChecker for SnmpTrafficChecker {
+    fn check(&self) -> bool {
+        match self.query_octets() {
+            Ok(octets) => self.add_sample(Instant::now(), octets),
+            Err(e) => {
+                debug!("failed to query the traffic of {}: {}", self.snmp, e);
+                // don't compare the next sample with an outdated one
+                *self.last_sample.lock().unwrap() = None;
+                false
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use rstest::*;
+
+    use super::*;
+    use crate::utils::Secret;
+
+    #[rstest]
+    fn test_snmp_traffic_checker_compares_rate_with_threshold() {
+        // SETUP
+        let checker = SnmpTrafficChecker::new(&SnmpCheck {
+            host: "192.168.1.254".to_string(),
+            port: 161,
+            community: Secret::from("public"),
+            interface: 3,
+            threshold: 1000,
+            timeout: Duration::from_secs(1),
+        });
+
+        // TESTING
+        // the first sample has nothing to be compared with
+        let start = Instant::now();
+        let minute = Duration::from_secs(60);
+        assert!(!checker.add_sample(start, 1_000_000));
+
+        assert!(checker.add_sample(start + minute, 1_060_000));
+
+        assert!(!checker.add_sample(start + minute * 2, 1_100_000));
+
+        // the switch restarted
+        assert!(!checker.add_sample(start + minute * 3, 500));
+    }
+}
@@ -0,0 +1,111 @@
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::mpsc::RecvError;
+
+use super::{CommandPinger, Pinger};
+
+/// Pings the devices with a source address using the ping command (which can be bound to the
+/// source address) and all other devices using the given pinger.
+pub struct SourcePinger {
+    pinger: Box<dyn Pinger>,
+    source_pinger: Box<dyn Pinger>,
+    sources: HashMap<IpAddr, IpAddr>,
+}
+
+impl SourcePinger {
+    pub fn new(
+        pinger: Box<dyn Pinger>,
+        source_pinger: CommandPinger,
+        sources: HashMap<IpAddr, IpAddr>,
+    ) -> Self {
+        Self {
+            pinger,
+            source_pinger: Box::new(source_pinger.with_sources(sources.clone())),
+            sources,
+        }
+    }
+
+    fn pinger_of(&self, ip_addr: &IpAddr) -> &dyn Pinger {
+        if self.sources.contains_key(ip_addr) {
+            self.source_pinger.as_ref()
+        } else {
+            self.pinger.as_ref()
+        }
+    }
+
+    fn pinger_of_mut(&mut self, ip_addr: &IpAddr) -> &mut dyn Pinger {
+        if self.sources.contains_key(ip_addr) {
+            self.source_pinger.as_mut()
+        } else {
+            self.pinger.as_mut()
+        }
+    }
+}
+
+impl Pinger for SourcePinger {
+    fn add_target(&mut self, ip_addr: IpAddr) -> bool {
+        self.pinger_of_mut(&ip_addr).add_target(ip_addr)
+    }
+
+    fn remove_target(&mut self, ip_addr: &IpAddr) -> bool {
+        self.pinger_of_mut(ip_addr).remove_target(ip_addr)
+    }
+
+    fn ping_once(&self) {
+        self.source_pinger.ping_once();
+        self.pinger.ping_once();
+    }
+
+    fn recv_pong(&mut self) -> Result<(), RecvError> {
+        // the ping commands keep running while waiting for the pongs of the other pinger
+        self.pinger.recv_pong()?;
+        self.source_pinger.recv_pong()
+    }
+
+    fn is_online(&self, ip_addr: &IpAddr) -> bool {
+        self.pinger_of(ip_addr).is_online(ip_addr)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use rstest::*;
+
+    use super::*;
+    use crate::networking::MockPinger;
+
+    #[rstest]
+    fn test_source_pinger_pings_devices_with_source_address_separately() {
+        let device: IpAddr = "192.168.1.2".parse().unwrap();
+        let iot_device: IpAddr = "192.168.20.2".parse().unwrap();
+        let source: IpAddr = "192.168.20.1".parse().unwrap();
+
+        let mut pinger = MockPinger::new();
+        pinger
+            .expect_add_target()
+            .withf(move |ip_addr| *ip_addr == device)
+            .once()
+            .returning(|_| true);
+        pinger
+            .expect_is_online()
+            .withf(move |ip_addr| *ip_addr == device)
+            .returning(|_| true);
+
+        let mut pinger = SourcePinger::new(
+            Box::new(pinger),
+            CommandPinger::new(Duration::from_secs(1)),
+            [(iot_device, source)].iter().cloned().collect(),
+        );
+
+        assert!(pinger.add_target(device));
+        assert!(pinger.add_target(iot_device));
+        assert!(!pinger.add_target(iot_device));
+
+        assert!(pinger.is_online(&device));
+        assert!(!pinger.is_online(&iot_device));
+
+        assert!(pinger.remove_target(&iot_device));
+    }
+}
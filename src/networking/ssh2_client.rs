@@ -0,0 +1,386 @@
+use std::io::Read;
+use std::net::{IpAddr, SocketAddr, TcpStream, ToSocketAddrs};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use log::debug;
+use ssh2::{Channel, Session};
+
+use super::super::dom::{self, TraceContext};
+use super::super::utils::Secret;
+use super::{ConnectionTest, SshError};
+
+// only used to test the connection to not wait for the operating system's timeout
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+// interval (in seconds) of the keepalive messages of pooled sessions
+const KEEPALIVE_INTERVAL: u32 = 30;
+// don't block on a pooled session whose connection silently died
+const REUSE_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Clone)]
+struct PrivateKeyAuthentication {
+    file: String,
+    passphrase: Secret,
+}
+
+#[derive(Clone)]
+enum Authentication {
+    Password(Secret),
+    PrivateKey(PrivateKeyAuthentication),
+}
+
+#[derive(Clone)]
+pub struct Ssh2Client {
+    name: String,
+    ip: IpAddr,
+    host: Option<String>,
+    port: u16,
+    username: String,
+    authentication: Authentication,
+    // shared by all clones to reuse the session for consecutive commands
+    session: Arc<Mutex<Option<Session>>>,
+}
+
+impl Ssh2Client {
+    pub fn new(name: &str, ip: IpAddr, ssh: &dom::device::Ssh) -> Self {
+        let authentication = match &ssh.authentication {
+            dom::device::SshAuthentication::Password(auth) => {
+                Authentication::Password(auth.clone())
+            }
+            dom::device::SshAuthentication::PrivateKey(auth) => {
+                Authentication::PrivateKey(PrivateKeyAuthentication {
+                    file: auth.file.clone(),
+                    passphrase: auth.passphrase.clone(),
+                })
+            }
+        };
+
+        Self {
+            name: name.to_string(),
+            ip,
+            host: ssh.host.clone(),
+            port: ssh.port.into(),
+            username: ssh.username.to_string(),
+            authentication,
+            session: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Executes the given command using the pooled session (if it is still alive) or otherwise a
+    /// new session which is pooled for the next command.
+    pub fn execute(&self, command: &str, context: &TraceContext) -> Result<(), SshError> {
+        self.with_channel(context, |channel| {
+            self.run(channel, command, context).map(|_| ())
+        })
+    }
+
+    /// Executes the given command like [`Self::execute`] and returns its output if it succeeded.
+    pub fn query(&self, command: &str, context: &TraceContext) -> Result<String, SshError> {
+        self.with_channel(context, |channel| {
+            match self.run_with_output(channel, command, context)? {
+                (0, output) => Ok(output),
+                (status, _) => Err(SshError::new(format!(
+                    "\"{command}\" failed with exit status {status}"
+                ))),
+            }
+        })
+    }
+
+    fn with_channel<T, F>(&self, context: &TraceContext, f: F) -> Result<T, SshError>
+    where
+        F: FnOnce(Channel) -> Result<T, SshError>,
+    {
+        let mut pooled = self.session.lock().unwrap();
+
+        let (session, channel) = match pooled
+            .take()
+            .and_then(|session| self.reuse(session, context))
+        {
+            Some(reused) => reused,
+            None => {
+                let session = self.connect(context)?;
+                let channel = session.channel_session()?;
+                (session, channel)
+            }
+        };
+        let result = f(channel)?;
+
+        *pooled = Some(session);
+        Ok(result)
+    }
+
+    /// Opens a channel on the pooled session unless the session is no longer usable.
+    fn reuse(&self, session: Session, context: &TraceContext) -> Option<(Session, Channel)> {
+        session.set_timeout(REUSE_TIMEOUT.as_millis() as u32);
+        let channel = session
+            .keepalive_send()
+            .and_then(|_| session.channel_session());
+        session.set_timeout(0);
+
+        match channel {
+            Ok(channel) => {
+                debug!(
+                    "[{}] reusing the SSH session to {} [{}]",
+                    context, self.name, self.ip
+                );
+                Some((session, channel))
+            }
+            Err(e) => {
+                debug!(
+                    "[{}] dropping the broken SSH session to {} [{}]: {}",
+                    context, self.name, self.ip, e
+                );
+                None
+            }
+        }
+    }
+
+    /// Goes through all the stages of executing a command (including the given command verifying
+    /// the privileges, e.g. sudo) without executing anything and reports how far it got.
+    pub fn test_connection(
+        &self,
+        privileges_command: &str,
+        context: &TraceContext,
+    ) -> ConnectionTest {
+        let mut test = ConnectionTest::default();
+        if let Err(e) = self.test_stages(&mut test, privileges_command, context) {
+            test.error = Some(e.to_string());
+        }
+
+        test
+    }
+
+    fn test_stages(
+        &self,
+        test: &mut ConnectionTest,
+        privileges_command: &str,
+        context: &TraceContext,
+    ) -> Result<(), SshError> {
+        debug!(
+            "[{}] testing the SSH connection to {} [{}]",
+            context, self.name, self.ip
+        );
+        let tcp = TcpStream::connect_timeout(&self.address()?, CONNECT_TIMEOUT)
+            .map_err(|e| SshError::new(format!("{e}")))?;
+        test.reachable = true;
+
+        let session = Self::handshake(tcp)?;
+        test.handshake = true;
+
+        self.authenticate(&session, context)?;
+        test.authenticated = true;
+
+        test.sudo = self.run(session.channel_session()?, privileges_command, context)? == 0;
+        Ok(())
+    }
+
+    /// Returns the address to connect to which is resolved from the DNS name (if configured).
+    fn address(&self) -> Result<SocketAddr, SshError> {
+        let host = match &self.host {
+            Some(host) => host,
+            None => return Ok(SocketAddr::new(self.ip, self.port)),
+        };
+
+        (host.as_str(), self.port)
+            .to_socket_addrs()
+            .map_err(|e| SshError::new(format!("failed to resolve {host}: {e}")))?
+            .next()
+            .ok_or_else(|| SshError::new(format!("failed to resolve {host}")))
+    }
+
+    fn connect(&self, context: &TraceContext) -> Result<Session, SshError> {
+        debug!(
+            "[{}] creating an SSH session to {} [{}]",
+            context, self.name, self.ip
+        );
+        let tcp = match TcpStream::connect(self.address()?) {
+            Ok(s) => s,
+            Err(e) => return Err(SshError::new(format!("{e}"))),
+        };
+        let session = Self::handshake(tcp)?;
+
+        self.authenticate(&session, context)?;
+        session.set_keepalive(true, KEEPALIVE_INTERVAL);
+
+        Ok(session)
+    }
+
+    fn handshake(tcp: TcpStream) -> Result<Session, SshError> {
+        let mut session = Session::new()?;
+        session.set_tcp_stream(tcp);
+        session.handshake()?;
+
+        Ok(session)
+    }
+
+    /// Executes the given command and returns its exit status.
+    fn run(
+        &self,
+        channel: Channel,
+        command: &str,
+        context: &TraceContext,
+    ) -> Result<i32, SshError> {
+        self.run_with_output(channel, command, context)
+            .map(|(status, _)| status)
+    }
+
+    /// Executes the given command and returns its exit status and its output.
+    fn run_with_output(
+        &self,
+        mut channel: Channel,
+        command: &str,
+        context: &TraceContext,
+    ) -> Result<(i32, String), SshError> {
+        debug!("[{}] executing \"{}\" on {}", context, command, self.name);
+        channel.exec(command)?;
+
+        // drain the output so that the command can finish
+        let mut output = String::new();
+        let _ = channel.read_to_string(&mut output);
+
+        channel.send_eof()?;
+        channel.wait_eof()?;
+        channel.close()?;
+        channel.wait_close()?;
+
+        Ok((channel.exit_status()?, output))
+    }
+
+    fn authenticate(&self, session: &Session, context: &TraceContext) -> Result<(), SshError> {
+        match &self.authentication {
+            Authentication::Password(password) => {
+                debug!(
+                    "[{}] authenticating SSH session to {} for {} using password",
+                    context, self.name, self.username
+                );
+                session
+                    .userauth_password(&self.username, password.expose())
+                    .map_err(SshError::from_authentication)?;
+            }
+            Authentication::PrivateKey(pk) => {
+                debug!(
+                    "[{}] authenticating SSH session to {} for {} using private key",
+                    context, self.name, self.username
+                );
+
+                // make sure the private key exists
+                let pk_path = Path::new(&pk.file);
+                match pk_path.try_exists() {
+                    Ok(exists) => {
+                        if !exists {
+                            return Err(SshError::authentication(
+                                format!("missing private key at {} to authenticate SSH session to {} for {}",
+                                    pk.file, self.name, self.username)));
+                        }
+                    },
+                    Err(err) => return Err(SshError::authentication(
+                        format!("error loading private key from {}to authenticate SSH session to {} for {}: {}",
+                            pk.file, self.name, self.username, err))),
+                }
+
+                session
+                    .userauth_pubkey_file(
+                        &self.username,
+                        Option::None,
+                        pk_path,
+                        Some(pk.passphrase.expose()),
+                    )
+                    .map_err(SshError::from_authentication)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::TcpListener;
+
+    use rstest::*;
+
+    use super::*;
+    use crate::dom::device::{Ssh, SshAuthentication, SshPort};
+
+    fn client(port: u16) -> Ssh2Client {
+        client_with_host(None, port)
+    }
+
+    fn client_with_host(host: Option<&str>, port: u16) -> Ssh2Client {
+        let ssh = Ssh {
+            host: host.map(str::to_string),
+            port: SshPort(port),
+            username: "username".to_string(),
+            authentication: SshAuthentication::Password("password".into()),
+        };
+        Ssh2Client::new("Test Server", "127.0.0.1".parse().unwrap(), &ssh)
+    }
+
+    #[rstest]
+    fn test_ssh2_client_test_connection_reports_unreachable_server() {
+        let closed_port = TcpListener::bind(("127.0.0.1", 0))
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+
+        let test = client(closed_port).test_connection("sudo -n true", &TraceContext::new());
+
+        assert!(!test.reachable);
+        assert!(!test.handshake);
+        assert!(test.error.is_some());
+    }
+
+    #[rstest]
+    #[case(None, "127.0.0.1:2222")]
+    #[case(Some("192.0.2.1"), "192.0.2.1:2222")]
+    fn test_ssh2_client_connects_to_configured_port(
+        #[case] host: Option<&str>,
+        #[case] expected: SocketAddr,
+    ) {
+        assert_eq!(client_with_host(host, 2222).address().unwrap(), expected);
+    }
+
+    #[rstest]
+    fn test_ssh2_client_resolves_configured_host() {
+        let address = client_with_host(Some("localhost"), 2222).address().unwrap();
+
+        assert!(address.ip().is_loopback());
+        assert_eq!(address.port(), 2222);
+    }
+
+    #[rstest]
+    fn test_ssh2_client_test_connection_reports_failed_handshake() {
+        // accept the connection but close it without speaking SSH
+        let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        let port = listener.local_addr().unwrap().port();
+        std::thread::spawn(move || {
+            let _ = listener.accept().unwrap();
+        });
+
+        let test = client(port).test_connection("sudo -n true", &TraceContext::new());
+
+        assert!(test.reachable);
+        assert!(!test.handshake);
+        assert!(!test.authenticated);
+        assert!(test.error.is_some());
+    }
+    #[rstest]
+    fn test_ssh2_client_only_pools_working_sessions() {
+        // accept the connection but close it without speaking SSH
+        let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        let port = listener.local_addr().unwrap().port();
+        std::thread::spawn(move || {
+            let _ = listener.accept().unwrap();
+        });
+
+        let client = client(port);
+        let clone = client.clone();
+
+        assert!(clone.execute("true", &TraceContext::new()).is_err());
+        assert!(client.session.lock().unwrap().is_none());
+        // all clones share the same pool
+        assert!(Arc::ptr_eq(&client.session, &clone.session));
+    }
+}
@@ -0,0 +1,56 @@
+use std::time::Instant;
+
+use async_trait::async_trait;
+use log::{debug, warn};
+
+use super::super::dom::{self, ActionContext};
+use super::{run_blocking, ActionReport, Ssh2Client, WakeupServer};
+
+pub struct Ssh2RelayWakeupServer {
+    name: String,
+    command: String,
+    client: Ssh2Client,
+}
+
+impl Ssh2RelayWakeupServer {
+    pub fn new(server: &dom::Server, relay: &dom::device::SshRelay) -> Self {
+        Self {
+            name: server.machine.name.to_string(),
+            command: relay.command.replace("{mac}", &server.mac.to_string()),
+            client: Ssh2Client::new(
+                &format!("wake-on-lan relay for {}", server.machine.name),
+                relay.ip,
+                &relay.ssh,
+            ),
+        }
+    }
+}
+
+#[async_trait]
+impl WakeupServer for Ssh2RelayWakeupServer {
+    async fn wakeup(&self, context: &ActionContext) -> anyhow::Result<ActionReport> {
+        debug!(
+            "[{}] relaying wake-on-lan request for {} via SSH",
+            context, self.name
+        );
+        let start = Instant::now();
+
+        let client = self.client.clone();
+        let command = self.command.clone();
+        let trace = context.trace.clone();
+        match run_blocking(context, move || client.execute(&command, &trace)).await {
+            Err(e) => {
+                warn!(
+                    "[{}] failed to relay wake-on-lan request for {} via SSH: {}",
+                    context, self.name, e
+                );
+                Err(e)
+            }
+            Ok(_) => Ok(ActionReport::new(
+                start.elapsed(),
+                1,
+                format!("executed \"{}\" on the SSH relay", self.command),
+            )),
+        }
+    }
+}
@@ -1,137 +1,50 @@
-use std::net::TcpStream;
-use std::path::Path;
+use std::time::Instant;
 
-use log::debug;
-use ssh2::Session;
+use async_trait::async_trait;
 
-use super::super::dom;
-use super::{ShutdownError, ShutdownServer};
-
-struct PrivateKeyAuthentication {
-    file: String,
-    passphrase: String,
-}
-
-enum Authentication {
-    Password(String),
-    PrivateKey(PrivateKeyAuthentication),
-}
+use super::super::dom::{self, ActionContext};
+use super::{
+    run_blocking, ActionReport, ConnectionTest, ShutdownError, ShutdownServer, Ssh2Client,
+};
 
 pub struct Ssh2ShutdownServer {
-    name: String,
-    ip: String,
-    port: u16,
-    username: String,
-    authentication: Authentication,
+    client: Ssh2Client,
+    profile: dom::device::ShutdownProfile,
 }
 
 impl Ssh2ShutdownServer {
     pub fn new(server: &dom::Server) -> Self {
-        let authentication = match &server.ssh.authentication {
-            dom::device::SshAuthentication::Password(auth) => {
-                Authentication::Password(auth.clone())
-            }
-            dom::device::SshAuthentication::PrivateKey(auth) => {
-                Authentication::PrivateKey(PrivateKeyAuthentication {
-                    file: auth.file.clone(),
-                    passphrase: auth.passphrase.clone(),
-                })
-            }
-        };
-
         Self {
-            name: server.machine.name.to_string(),
-            ip: server.machine.ip.to_string(),
-            port: server.ssh.port.into(),
-            username: server.ssh.username.to_string(),
-            authentication,
-        }
-    }
-
-    fn ssh2_to_shutdown_error(e: ssh2::Error) -> ShutdownError {
-        ShutdownError::new(format!(
-            "[{code}] {message}",
-            code = e.code(),
-            message = e.message()
-        ))
-    }
-
-    fn handle_shutdown_error<T>(result: Result<T, ssh2::Error>) -> Result<T, ShutdownError> {
-        match result {
-            Ok(r) => Ok(r),
-            Err(e) => Err(Self::ssh2_to_shutdown_error(e)),
-        }
-    }
-
-    fn connect(&self) -> Result<Session, ShutdownError> {
-        debug!("creating an SSH session to {} [{}]", self.name, self.ip);
-        let tcp = match TcpStream::connect(format!("{}:{}", &self.ip, self.port)) {
-            Ok(s) => s,
-            Err(e) => return Err(ShutdownError::new(format!("{e}"))),
-        };
-        let mut session = Self::handle_shutdown_error(Session::new())?;
-        session.set_tcp_stream(tcp);
-        Self::handle_shutdown_error(session.handshake())?;
-
-        self.authenticate(&session)?;
-
-        Ok(session)
-    }
-
-    fn authenticate(&self, session: &Session) -> Result<(), ShutdownError> {
-        match &self.authentication {
-            Authentication::Password(password) => {
-                debug!(
-                    "authenticating SSH session to {} for {} using password",
-                    self.name, self.username
-                );
-                Self::handle_shutdown_error(session.userauth_password(&self.username, password))?;
-            }
-            Authentication::PrivateKey(pk) => {
-                debug!(
-                    "authenticating SSH session to {} for {} using private key",
-                    self.name, self.username
-                );
-
-                // make sure the private key exists
-                let pk_path = Path::new(&pk.file);
-                match pk_path.try_exists() {
-                    Ok(exists) => {
-                        if !exists {
-                            return Err(ShutdownError::new(
-                                format!("missing private key at {} to authenticate SSH session to {} for {}",
-                                    pk.file, self.name, self.username)));
-                        }
-                    },
-                    Err(err) => return Err(ShutdownError::new(
-                        format!("error loading private key from {}to authenticate SSH session to {} for {}: {}",
-                            pk.file, self.name, self.username, err))),
-                }
-
-                Self::handle_shutdown_error(session.userauth_pubkey_file(
-                    &self.username,
-                    Option::None,
-                    pk_path,
-                    Some(&pk.passphrase),
-                ))?;
-            }
+            client: Ssh2Client::new(&server.machine.name, server.machine.ip, &server.ssh),
+            profile: server.shutdown_profile.clone(),
         }
-
-        Ok(())
     }
 }
 
+#[async_trait]
 impl ShutdownServer for Ssh2ShutdownServer {
-    fn shutdown(&self) -> Result<(), ShutdownError> {
-        let session = self.connect()?;
-
-        debug!("executing \"shutdown -h now\" on {}", self.name);
-        let mut channel = Self::handle_shutdown_error(session.channel_session())?;
-        Self::handle_shutdown_error(channel.exec("shutdown -h now"))?;
+    async fn shutdown(&self, context: &ActionContext) -> Result<ActionReport, ShutdownError> {
+        let start = Instant::now();
+
+        let client = self.client.clone();
+        let command = self.profile.command.clone();
+        let trace = context.trace.clone();
+        run_blocking(context, move || client.execute(&command, &trace)).await?;
+
+        Ok(ActionReport::new(
+            start.elapsed(),
+            1,
+            format!("executed \"{}\" via SSH", self.profile.command),
+        ))
+    }
 
-        Self::handle_shutdown_error(channel.send_eof())?;
-        Self::handle_shutdown_error(channel.wait_eof())?;
-        Self::handle_shutdown_error(channel.close())?;
-        Self::handle_shutdown_error(channel.wait_close())
+    async fn test_connection(&self, context: &ActionContext) -> anyhow::Result<ConnectionTest> {
+        let client = self.client.clone();
+        let privileges_command = self.profile.privileges_command.clone();
+        let trace = context.trace.clone();
+        run_blocking(context, move || {
+            Ok::<ConnectionTest, anyhow::Error>(client.test_connection(&privileges_command, &trace))
+        })
+        .await
     }
 }
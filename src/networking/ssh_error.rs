@@ -0,0 +1,87 @@
+use std::fmt;
+
+use ssh2::ErrorCode;
+
+// libssh2 errors of rejected credentials (expired password, unreadable private key, failed
+// authentication and unverified public key)
+const AUTHENTICATION_ERRORS: [i32; 4] = [-15, -16, -18, -19];
+
+#[derive(Debug)]
+pub struct SshError {
+    message: String,
+    authentication: bool,
+}
+
+impl SshError {
+    pub fn new(error_msg: String) -> Self {
+        Self {
+            message: error_msg,
+            authentication: false,
+        }
+    }
+
+    /// Creates an error about the server rejecting the configured credentials.
+    pub fn authentication(error_msg: String) -> Self {
+        Self {
+            message: error_msg,
+            authentication: true,
+        }
+    }
+
+    /// Classifies an error during the authentication as rejected credentials unless it was
+    /// caused by something else (e.g. the connection dropping).
+    pub fn from_authentication(e: ssh2::Error) -> Self {
+        let authentication = matches!(
+            e.code(),
+            ErrorCode::Session(code) if AUTHENTICATION_ERRORS.contains(&code)
+        );
+        Self {
+            authentication,
+            ..Self::from(e)
+        }
+    }
+
+    /// Whether the server rejected the configured credentials (as opposed to e.g. not being
+    /// reachable).
+    pub fn is_authentication(&self) -> bool {
+        self.authentication
+    }
+}
+
+impl std::error::Error for SshError {}
+
+impl fmt::Display for SshError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[SshError] {}", self.message)
+    }
+}
+
+impl From<ssh2::Error> for SshError {
+    fn from(e: ssh2::Error) -> Self {
+        Self::new(format!(
+            "[{code}] {message}",
+            code = e.code(),
+            message = e.message()
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::*;
+
+    use super::*;
+
+    #[rstest]
+    #[case(ErrorCode::Session(-18), true)]
+    #[case(ErrorCode::Session(-15), true)]
+    #[case(ErrorCode::Session(-43), false)]
+    #[case(ErrorCode::Session(-9), false)]
+    fn test_ssh_error_classifies_authentication_errors(
+        #[case] code: ErrorCode,
+        #[case] authentication: bool,
+    ) {
+        let e = SshError::from_authentication(ssh2::Error::new(code, "failed"));
+        assert_eq!(e.is_authentication(), authentication);
+    }
+}
@@ -0,0 +1,82 @@
+use log::debug;
+
+use super::{PortChecker, Ssh2Client};
+use crate::dom::device::SessionsCheck;
+use crate::dom::{Server, TraceContext};
+
+/// Considers a server busy while the number of active file sharing sessions (e.g. mounted SMB or
+/// NFS shares) reported by a command executed on the server reaches the threshold.
+pub struct SshSessionsChecker {
+    client: Ssh2Client,
+    sessions: SessionsCheck,
+}
+
+impl SshSessionsChecker {
+    pub fn new(server: &Server, sessions: &SessionsCheck) -> Self {
+        Self {
+            client: Ssh2Client::new(&server.machine.name, server.machine.ip, &server.ssh),
+            sessions: sessions.clone(),
+        }
+    }
+
+    fn is_busy(&self, output: &str) -> bool {
+        match output.trim().parse::<u32>() {
+            Ok(sessions) => {
+                debug!("{}: {} sessions", self.sessions, sessions);
+                sessions >= self.sessions.threshold
+            }
+            Err(_) => {
+                debug!(
+                    "{} didn't print a number of sessions: {}",
+                    self.sessions,
+                    output.trim()
+                );
+                false
+            }
+        }
+    }
+}
+
+impl PortChecker for SshSessionsChecker {
+    fn check(&self) -> bool {
+        match self
+            .client
+            .query(&self.sessions.command, &TraceContext::new())
+        {
+            Ok(output) => self.is_busy(&output),
+            Err(e) => {
+                debug!("failed to count the sessions with {}: {}", self.sessions, e);
+                false
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::*;
+
+    use super::*;
+    use crate::dom::device::test::*;
+
+    #[rstest]
+    #[case("0\n", false)]
+    #[case("2\n", true)]
+    #[case(" 3 ", true)]
+    #[case("command not found", false)]
+    fn test_ssh_sessions_checker_compares_sessions_with_threshold(
+        server: Server,
+        #[case] output: &str,
+        #[case] expected_busy: bool,
+    ) {
+        let checker = SshSessionsChecker::new(
+            &server,
+            &SessionsCheck {
+                command: "ss -Htn state established '( sport = :445 )' | wc -l".to_string(),
+                threshold: 2,
+            },
+        );
+
+        assert_eq!(checker.is_busy(output), expected_busy);
+    }
+}
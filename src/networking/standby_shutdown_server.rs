@@ -0,0 +1,85 @@
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use log::debug;
+
+use super::super::dom::communication::Standby;
+use super::super::dom::ActionContext;
+use super::{ActionReport, ConnectionTest, ShutdownError, ShutdownServer};
+
+/// Refuses to shut down the server while this standby instance hasn't taken over the control of
+/// the servers from the active instance.
+pub struct StandbyShutdownServer {
+    shutdown_server: Arc<dyn ShutdownServer>,
+    standby: Arc<Standby>,
+}
+
+impl StandbyShutdownServer {
+    pub fn new(shutdown_server: Arc<dyn ShutdownServer>, standby: Arc<Standby>) -> Self {
+        Self {
+            shutdown_server,
+            standby,
+        }
+    }
+}
+
+#[async_trait]
+impl ShutdownServer for StandbyShutdownServer {
+    async fn shutdown(&self, context: &ActionContext) -> Result<ActionReport, ShutdownError> {
+        if !self.standby.is_active() {
+            debug!(
+                "[{}] not sending shutdown request because the active instance controls the servers",
+                context
+            );
+            return Err(anyhow!("the active instance controls the servers").into());
+        }
+
+        self.shutdown_server.shutdown(context).await
+    }
+
+    // testing the connection doesn't change the state of the server
+    async fn test_connection(&self, context: &ActionContext) -> anyhow::Result<ConnectionTest> {
+        self.shutdown_server.test_connection(context).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use rstest::*;
+    use tokio_util::sync::CancellationToken;
+
+    use super::*;
+    use crate::dom::TraceContext;
+    use crate::networking::MockShutdownServer;
+    use crate::utils::{Instant, ManualClock};
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_standby_shutdown_server_only_shuts_down_once_active() {
+        let context = ActionContext::new(
+            TraceContext::new(),
+            CancellationToken::new(),
+            Duration::from_secs(10),
+        );
+        let mut shutdown_server = MockShutdownServer::new();
+        shutdown_server
+            .expect_shutdown()
+            .once()
+            .returning(|_| Ok(ActionReport::default()));
+
+        let timeout = Duration::from_secs(30);
+        let clock = Arc::new(ManualClock::new(Instant::now()));
+        let standby = Arc::new(Standby::new(timeout, clock.clone()));
+        let standby_shutdown_server =
+            StandbyShutdownServer::new(Arc::new(shutdown_server), standby.clone());
+
+        assert!(standby_shutdown_server.shutdown(&context).await.is_err());
+
+        clock.advance(timeout + Duration::from_secs(1));
+        standby.update(false);
+        assert!(standby_shutdown_server.shutdown(&context).await.is_ok());
+    }
+}
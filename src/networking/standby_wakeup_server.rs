@@ -0,0 +1,80 @@
+use std::sync::Arc;
+
+use anyhow::bail;
+use async_trait::async_trait;
+use log::debug;
+
+use super::super::dom::communication::Standby;
+use super::super::dom::ActionContext;
+use super::{ActionReport, WakeupServer};
+
+/// Refuses to wake up the server while this standby instance hasn't taken over the control of the
+/// servers from the active instance.
+pub struct StandbyWakeupServer {
+    wakeup_server: Arc<dyn WakeupServer>,
+    standby: Arc<Standby>,
+}
+
+impl StandbyWakeupServer {
+    pub fn new(wakeup_server: Arc<dyn WakeupServer>, standby: Arc<Standby>) -> Self {
+        Self {
+            wakeup_server,
+            standby,
+        }
+    }
+}
+
+#[async_trait]
+impl WakeupServer for StandbyWakeupServer {
+    async fn wakeup(&self, context: &ActionContext) -> anyhow::Result<ActionReport> {
+        if !self.standby.is_active() {
+            debug!(
+                "[{}] not sending wakeup request because the active instance controls the servers",
+                context
+            );
+            bail!("the active instance controls the servers");
+        }
+
+        self.wakeup_server.wakeup(context).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use rstest::*;
+    use tokio_util::sync::CancellationToken;
+
+    use super::*;
+    use crate::dom::TraceContext;
+    use crate::networking::MockWakeupServer;
+    use crate::utils::{Instant, ManualClock};
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_standby_wakeup_server_only_wakes_up_once_active() {
+        let context = ActionContext::new(
+            TraceContext::new(),
+            CancellationToken::new(),
+            Duration::from_secs(10),
+        );
+        let mut wakeup_server = MockWakeupServer::new();
+        wakeup_server
+            .expect_wakeup()
+            .once()
+            .returning(|_| Ok(ActionReport::default()));
+
+        let timeout = Duration::from_secs(30);
+        let clock = Arc::new(ManualClock::new(Instant::now()));
+        let standby = Arc::new(Standby::new(timeout, clock.clone()));
+        let standby_wakeup_server =
+            StandbyWakeupServer::new(Arc::new(wakeup_server), standby.clone());
+
+        assert!(standby_wakeup_server.wakeup(&context).await.is_err());
+
+        clock.advance(timeout + Duration::from_secs(1));
+        standby.update(false);
+        assert!(standby_wakeup_server.wakeup(&context).await.is_ok());
+    }
+}
@@ -0,0 +1,30 @@
+use std::net::IpAddr;
+
+use pnet::ipnetwork::IpNetwork;
+
+/// Whether the given IP address is on one of the given networks (e.g. of the monitored
+/// interface) and can therefore be reached by broadcasts (Wake-on-LAN) and ARP requests.
+pub fn is_on_subnet(networks: &[IpNetwork], ip: &IpAddr) -> bool {
+    networks.iter().any(|network| network.contains(*ip))
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::*;
+
+    use super::*;
+
+    #[rstest]
+    #[case("192.168.1.2", true)]
+    #[case("fd00::2", true)]
+    #[case("192.168.20.2", false)]
+    #[case("fd01::2", false)]
+    fn test_is_on_subnet(#[case] ip: IpAddr, #[case] expected: bool) {
+        let networks: Vec<IpNetwork> = vec![
+            "192.168.1.10/24".parse().unwrap(),
+            "fd00::10/64".parse().unwrap(),
+        ];
+
+        assert_eq!(is_on_subnet(&networks, &ip), expected);
+    }
+}
@@ -1,12 +1,15 @@
+use std::io;
 use std::net::{IpAddr, SocketAddr, TcpStream};
 use std::time::Duration;
 
 use log::debug;
+use socket2::{Domain, Protocol, Socket, Type};
 
 use super::PortChecker;
 
 pub struct TcpPortChecker {
     socket_addr: SocketAddr,
+    source: Option<IpAddr>,
     timeout: Duration,
 }
 
@@ -14,9 +17,26 @@ impl TcpPortChecker {
     pub fn new(ip: IpAddr, port: u16, timeout: Duration) -> Self {
         Self {
             socket_addr: SocketAddr::new(ip, port),
+            source: None,
             timeout,
         }
     }
+
+    /// Connects from the given local IP address (instead of the one chosen by the routing table).
+    pub fn with_source(mut self, source: Option<IpAddr>) -> Self {
+        self.source = source;
+        self
+    }
+
+    fn connect_from(&self, source: IpAddr) -> io::Result<()> {
+        let socket = Socket::new(
+            Domain::for_address(self.socket_addr),
+            Type::STREAM,
+            Some(Protocol::TCP),
+        )?;
+        socket.bind(&SocketAddr::new(source, 0).into())?;
+        socket.connect_timeout(&self.socket_addr.into(), self.timeout)
+    }
 }
 
 impl PortChecker for TcpPortChecker {
@@ -26,6 +46,47 @@ impl PortChecker for TcpPortChecker {
             self.socket_addr.port(),
             self.socket_addr.ip()
         );
-        TcpStream::connect_timeout(&self.socket_addr, self.timeout).is_ok()
+        match self.source {
+            Some(source) => self.connect_from(source).is_ok(),
+            None => TcpStream::connect_timeout(&self.socket_addr, self.timeout).is_ok(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::TcpListener;
+
+    use super::*;
+
+    #[test]
+    fn test_check_open_and_closed_port() {
+        // SETUP
+        let ip: IpAddr = "127.0.0.1".parse().unwrap();
+        let listener = TcpListener::bind((ip, 0)).unwrap();
+        let open_port = listener.local_addr().unwrap().port();
+
+        // find a port which is (very likely) closed
+        let closed_port = {
+            let listener = TcpListener::bind((ip, 0)).unwrap();
+            listener.local_addr().unwrap().port()
+        };
+
+        // TESTING
+        let timeout = Duration::from_millis(500);
+        assert!(TcpPortChecker::new(ip, open_port, timeout).check());
+        assert!(!TcpPortChecker::new(ip, closed_port, timeout).check());
+
+        // connecting from a local address works just the same
+        assert!(TcpPortChecker::new(ip, open_port, timeout)
+            .with_source(Some(ip))
+            .check());
+        assert!(!TcpPortChecker::new(ip, closed_port, timeout)
+            .with_source(Some(ip))
+            .check());
+        // but not from an address which isn't local
+        assert!(!TcpPortChecker::new(ip, open_port, timeout)
+            .with_source(Some("192.0.2.1".parse().unwrap()))
+            .check());
     }
 }
@@ -0,0 +1,88 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+
+use super::super::dom::ActionContext;
+use super::{ActionReport, ConnectionTest, ShutdownError, ShutdownServer};
+
+/// Aborts shutdowns of a server which take longer than its configured timeout.
+pub struct TimedShutdownServer {
+    shutdown_server: Arc<dyn ShutdownServer>,
+    timeout: Duration,
+}
+
+impl TimedShutdownServer {
+    pub fn new(shutdown_server: Arc<dyn ShutdownServer>, timeout: Duration) -> Self {
+        Self {
+            shutdown_server,
+            timeout,
+        }
+    }
+}
+
+#[async_trait]
+impl ShutdownServer for TimedShutdownServer {
+    async fn shutdown(&self, context: &ActionContext) -> Result<ActionReport, ShutdownError> {
+        let context = context.with_timeout(self.timeout);
+        match tokio::time::timeout_at(context.deadline, self.shutdown_server.shutdown(&context))
+            .await
+        {
+            Ok(result) => result,
+            Err(_) => {
+                // stop any blocking work still running in the background
+                context.cancellation.cancel();
+                Err(ShutdownError::new(format!(
+                    "shutdown exceeded its timeout of {}s",
+                    self.timeout.as_secs()
+                )))
+            }
+        }
+    }
+
+    async fn test_connection(&self, context: &ActionContext) -> anyhow::Result<ConnectionTest> {
+        self.shutdown_server.test_connection(context).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::*;
+    use tokio_util::sync::CancellationToken;
+
+    use super::*;
+    use crate::dom::TraceContext;
+
+    struct StuckShutdownServer {}
+
+    #[async_trait]
+    impl ShutdownServer for StuckShutdownServer {
+        async fn shutdown(&self, _: &ActionContext) -> Result<ActionReport, ShutdownError> {
+            tokio::time::sleep(Duration::from_secs(60)).await;
+            Ok(ActionReport::default())
+        }
+
+        async fn test_connection(&self, _: &ActionContext) -> anyhow::Result<ConnectionTest> {
+            Err(anyhow::anyhow!("not supported"))
+        }
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_timed_shutdown_server_aborts_shutdown_exceeding_timeout() {
+        let timed =
+            TimedShutdownServer::new(Arc::new(StuckShutdownServer {}), Duration::from_millis(10));
+
+        let context = ActionContext::new(
+            TraceContext::new(),
+            CancellationToken::new(),
+            Duration::from_secs(10),
+        );
+        let result = timed.shutdown(&context).await;
+
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("exceeded its timeout"));
+    }
+}
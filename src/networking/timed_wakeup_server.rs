@@ -0,0 +1,108 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+
+use super::super::dom::ActionContext;
+use super::{ActionReport, WakeupServer};
+
+/// Aborts wakeups of a server which take longer than its configured timeout.
+pub struct TimedWakeupServer {
+    wakeup_server: Arc<dyn WakeupServer>,
+    timeout: Duration,
+}
+
+impl TimedWakeupServer {
+    pub fn new(wakeup_server: Arc<dyn WakeupServer>, timeout: Duration) -> Self {
+        Self {
+            wakeup_server,
+            timeout,
+        }
+    }
+}
+
+#[async_trait]
+impl WakeupServer for TimedWakeupServer {
+    async fn wakeup(&self, context: &ActionContext) -> anyhow::Result<ActionReport> {
+        let context = context.with_timeout(self.timeout);
+        match tokio::time::timeout_at(context.deadline, self.wakeup_server.wakeup(&context)).await {
+            Ok(result) => result,
+            Err(_) => {
+                // stop any blocking work still running in the background
+                context.cancellation.cancel();
+                Err(anyhow!(
+                    "wakeup exceeded its timeout of {}s",
+                    self.timeout.as_secs()
+                ))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use rstest::*;
+    use tokio_util::sync::CancellationToken;
+
+    use super::*;
+    use crate::dom::TraceContext;
+    use crate::networking::MockWakeupServer;
+
+    /// Wakeup server which never finishes and keeps the cancellation token of its context.
+    #[derive(Default)]
+    struct StuckWakeupServer {
+        cancellation: Mutex<Option<CancellationToken>>,
+    }
+
+    #[async_trait]
+    impl WakeupServer for StuckWakeupServer {
+        async fn wakeup(&self, context: &ActionContext) -> anyhow::Result<ActionReport> {
+            *self.cancellation.lock().unwrap() = Some(context.cancellation.clone());
+            tokio::time::sleep(Duration::from_secs(60)).await;
+            Ok(ActionReport::default())
+        }
+    }
+
+    fn context() -> ActionContext {
+        ActionContext::new(
+            TraceContext::new(),
+            CancellationToken::new(),
+            Duration::from_secs(10),
+        )
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_timed_wakeup_server_aborts_wakeup_exceeding_timeout() {
+        let stuck = Arc::new(StuckWakeupServer::default());
+        let timed = TimedWakeupServer::new(stuck.clone(), Duration::from_millis(10));
+
+        let result = timed.wakeup(&context()).await;
+
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("exceeded its timeout"));
+        let cancellation = stuck.cancellation.lock().unwrap().take().unwrap();
+        assert!(cancellation.is_cancelled());
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_timed_wakeup_server_extends_default_timeout() {
+        let mut wakeup_server = MockWakeupServer::new();
+        wakeup_server
+            .expect_wakeup()
+            .once()
+            .withf(|context| {
+                context.deadline > tokio::time::Instant::now() + Duration::from_secs(60)
+            })
+            .return_once(|_| Ok(ActionReport::default()));
+        let timed = TimedWakeupServer::new(Arc::new(wakeup_server), Duration::from_secs(120));
+
+        assert!(timed.wakeup(&context()).await.is_ok());
+    }
+}
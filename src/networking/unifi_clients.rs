@@ -0,0 +1,128 @@
+use std::collections::HashSet;
+use std::net::IpAddr;
+
+use anyhow::bail;
+use log::debug;
+use serde::Deserialize;
+
+use super::http_checker::create_agent;
+use super::router_clients::REQUEST_TIMEOUT;
+use super::RouterClients;
+use crate::configuration::PresenceProvider;
+use crate::utils::Secret;
+
+#[derive(Deserialize)]
+struct UnifiClients {
+    data: Vec<UnifiClient>,
+}
+
+#[derive(Deserialize)]
+struct UnifiClient {
+    mac: String,
+    ip: Option<String>,
+}
+
+/// Lists the clients connected to the access points of a UniFi controller.
+pub struct UnifiRouterClients {
+    agent: ureq::Agent,
+    url: String,
+    site: String,
+    username: String,
+    password: Secret,
+}
+
+impl UnifiRouterClients {
+    pub fn new(provider: &PresenceProvider, proxy: Option<&str>) -> Self {
+        Self {
+            agent: create_agent(REQUEST_TIMEOUT, provider.insecure, proxy),
+            url: provider.url.trim_end_matches('/').to_string(),
+            site: provider.site.clone(),
+            username: provider.username.clone(),
+            password: provider.password.clone(),
+        }
+    }
+
+    /// Logs in and returns the session cookies.
+    fn login(&self) -> anyhow::Result<String> {
+        let response = self
+            .agent
+            .post(&format!("{}/api/login", self.url))
+            .set("Content-Type", "application/json")
+            .send_string(
+                &serde_json::json!({
+                    "username": self.username,
+                    "password": self.password.expose(),
+                })
+                .to_string(),
+            )?;
+
+        let cookies: Vec<&str> = response
+            .all("set-cookie")
+            .into_iter()
+            .filter_map(|cookie| cookie.split(';').next())
+            .collect();
+        if cookies.is_empty() {
+            bail!("{} didn't return a session", self.url);
+        }
+
+        Ok(cookies.join("; "))
+    }
+
+    fn parse_clients(content: &str) -> anyhow::Result<HashSet<IpAddr>> {
+        let clients: UnifiClients = serde_json::from_str(content)?;
+        Ok(clients
+            .data
+            .into_iter()
+            .filter_map(|client| match client.ip.as_deref().map(str::parse) {
+                Some(Ok(ip)) => Some(ip),
+                _ => {
+                    debug!("ignoring UniFi client {} without an IP address", client.mac);
+                    None
+                }
+            })
+            .collect())
+    }
+}
+
+impl RouterClients for UnifiRouterClients {
+    fn clients(&self) -> anyhow::Result<HashSet<IpAddr>> {
+        let cookies = self.login()?;
+        let content = self
+            .agent
+            .get(&format!("{}/api/s/{}/stat/sta", self.url, self.site))
+            .set("Cookie", &cookies)
+            .call()?
+            .into_string()?;
+
+        Self::parse_clients(&content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::*;
+
+    use super::*;
+
+    #[rstest]
+    fn test_unifi_router_clients_parses_clients() {
+        let content = r#"{
+            "meta": { "rc": "ok" },
+            "data": [
+                { "mac": "aa:bb:cc:dd:ee:01", "ip": "192.168.1.10", "hostname": "phone" },
+                { "mac": "aa:bb:cc:dd:ee:02" },
+                { "mac": "aa:bb:cc:dd:ee:03", "ip": "192.168.1.12" }
+            ]
+        }"#;
+
+        let clients = UnifiRouterClients::parse_clients(content).unwrap();
+
+        assert_eq!(
+            clients,
+            HashSet::from([
+                "192.168.1.10".parse().unwrap(),
+                "192.168.1.12".parse().unwrap()
+            ])
+        );
+    }
+}
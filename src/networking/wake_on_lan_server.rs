@@ -1,46 +1,95 @@
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::time::Instant;
+
 use anyhow::anyhow;
+use async_trait::async_trait;
 use log::{debug, warn};
+use pnet::ipnetwork::IpNetwork;
 
-use super::super::dom;
+use super::super::dom::{self, ActionContext, TraceContext};
 use super::super::utils::MacAddr;
-use super::WakeupServer;
+use super::{run_blocking, ActionReport, WakeupServer};
+
+const WAKE_ON_LAN_PORT: u16 = 9;
 
 pub struct WakeOnLanServer {
     name: String,
+    ip: IpAddr,
     mac: MacAddr,
+    is_off_subnet: bool,
 }
 
 impl WakeOnLanServer {
     pub fn new(server: &dom::Server) -> Self {
         Self {
             name: server.machine.name.to_string(),
+            ip: server.machine.ip,
             mac: server.mac,
+            is_off_subnet: server.machine.is_off_subnet,
         }
     }
-}
 
-impl WakeupServer for WakeOnLanServer {
-    fn wakeup(&self) -> anyhow::Result<()> {
+    /// Returns the broadcast address of the local network containing the given IP address.
+    fn directed_broadcast(networks: &[IpNetwork], ip: IpAddr) -> Option<Ipv4Addr> {
+        let ip = match ip {
+            IpAddr::V4(ip) => ip,
+            IpAddr::V6(_) => return None,
+        };
+
+        networks.iter().find_map(|network| match network {
+            IpNetwork::V4(network) if network.contains(ip) => Some(network.broadcast()),
+            _ => None,
+        })
+    }
+
+    // the BSDs don't send the limited broadcast (255.255.255.255) through the interface of the
+    // server's network but through the one of the default route (if there is one at all)
+    fn broadcast_address(ip: IpAddr) -> Ipv4Addr {
+        if !cfg!(any(
+            target_os = "freebsd",
+            target_os = "openbsd",
+            target_os = "netbsd",
+            target_os = "dragonfly"
+        )) {
+            return Ipv4Addr::BROADCAST;
+        }
+
+        let networks: Vec<IpNetwork> = pnet::datalink::interfaces()
+            .into_iter()
+            .flat_map(|iface| iface.ips)
+            .collect();
+        Self::directed_broadcast(&networks, ip).unwrap_or(Ipv4Addr::BROADCAST)
+    }
+
+    fn send_magic_packet(
+        name: &str,
+        ip: IpAddr,
+        mac: MacAddr,
+        context: &TraceContext,
+    ) -> anyhow::Result<()> {
+        let broadcast = Self::broadcast_address(ip);
         debug!(
-            "sending wake-on-lan request to {} [{}]",
-            self.name, self.mac
+            "[{}] sending wake-on-lan request to {} [{}] through {}",
+            context, name, mac, broadcast
         );
-        let wol = match wakey::WolPacket::from_bytes(self.mac.as_bytes()) {
+        let wol = match wakey::WolPacket::from_bytes(mac.as_bytes()) {
             Err(e) => {
                 warn!(
-                    "failed to create wake-on-lan packet for {} [{}]: {}",
-                    self.name, self.mac, e
+                    "[{}] failed to create wake-on-lan packet for {} [{}]: {}",
+                    context, name, mac, e
                 );
                 Err(anyhow!(e))
             }
             Ok(wol) => Ok(wol),
         };
 
-        match wol?.send_magic() {
+        let src = SocketAddr::from((Ipv4Addr::UNSPECIFIED, 0));
+        let dst = SocketAddr::from((broadcast, WAKE_ON_LAN_PORT));
+        match wol?.send_magic_to(src, dst) {
             Err(e) => {
                 warn!(
-                    "failed to send wake-on-lan packet {} [{}]: {}",
-                    self.name, self.mac, e
+                    "[{}] failed to send wake-on-lan packet {} [{}]: {}",
+                    context, name, mac, e
                 );
                 Err(anyhow!(e))
             }
@@ -48,3 +97,81 @@ impl WakeupServer for WakeOnLanServer {
         }
     }
 }
+
+#[async_trait]
+impl WakeupServer for WakeOnLanServer {
+    async fn wakeup(&self, context: &ActionContext) -> anyhow::Result<ActionReport> {
+        // magic packets are broadcast and never leave the local network
+        if self.is_off_subnet {
+            return Err(anyhow!(
+                "{} ({}) isn't on the local network and can only be woken up through a relay",
+                self.name,
+                self.ip
+            ));
+        }
+
+        let start = Instant::now();
+
+        let name = self.name.clone();
+        let ip = self.ip;
+        let mac = self.mac;
+        let trace = context.trace.clone();
+        run_blocking(context, move || {
+            Self::send_magic_packet(&name, ip, mac, &trace)
+        })
+        .await?;
+
+        Ok(ActionReport::new(
+            start.elapsed(),
+            1,
+            format!("sent wake-on-lan packet to {}", self.mac),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use rstest::*;
+    use tokio_util::sync::CancellationToken;
+
+    use super::*;
+    use crate::dom::device::test::*;
+
+    #[rstest]
+    #[case("192.168.1.2", Some("192.168.1.255"))]
+    #[case("10.0.3.4", Some("10.0.255.255"))]
+    #[case("172.16.0.1", None)]
+    #[case("fd00::2", None)]
+    fn test_wake_on_lan_server_finds_directed_broadcast(
+        #[case] ip: IpAddr,
+        #[case] expected_broadcast: Option<&str>,
+    ) {
+        let networks: Vec<IpNetwork> = vec![
+            "fd00::1/64".parse().unwrap(),
+            "192.168.1.1/24".parse().unwrap(),
+            "10.0.0.1/16".parse().unwrap(),
+        ];
+
+        assert_eq!(
+            WakeOnLanServer::directed_broadcast(&networks, ip),
+            expected_broadcast.map(|broadcast| broadcast.parse().unwrap())
+        );
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_wake_on_lan_server_refuses_off_subnet_server(mut server: dom::Server) {
+        server.machine.is_off_subnet = true;
+        let wakeup_server = WakeOnLanServer::new(&server);
+
+        let context = ActionContext::new(
+            TraceContext::new(),
+            CancellationToken::new(),
+            Duration::from_secs(10),
+        );
+        let error = wakeup_server.wakeup(&context).await.unwrap_err();
+        assert!(error.to_string().contains("relay"));
+    }
+}
@@ -1,7 +1,12 @@
+use async_trait::async_trait;
 #[cfg(test)]
 use mockall::automock;
 
+use super::super::dom::ActionContext;
+use super::ActionReport;
+
 #[cfg_attr(test, automock)]
+#[async_trait]
 pub trait WakeupServer: Send + Sync {
-    fn wakeup(&self) -> anyhow::Result<()>;
+    async fn wakeup(&self, context: &ActionContext) -> anyhow::Result<ActionReport>;
 }
@@ -0,0 +1,15 @@
+mod notification;
+mod notifier;
+mod notifiers;
+mod outbox;
+mod templates;
+mod webhook_notifier;
+
+pub use notification::Notification;
+#[cfg(test)]
+pub use notifier::MockNotifier;
+pub use notifier::Notifier;
+pub use notifiers::{Notifiers, RETRY_INTERVAL};
+pub use outbox::{Outbox, QueuedNotification};
+pub use templates::{format_duration, Templates};
+pub use webhook_notifier::WebhookNotifier;
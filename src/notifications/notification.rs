@@ -0,0 +1,40 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use super::templates;
+use crate::configuration::{Locale, NotificationEvent};
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Notification {
+    pub event: NotificationEvent,
+    pub title: String,
+    pub message: String,
+    pub variables: BTreeMap<String, String>,
+}
+
+impl Notification {
+    /// Creates a notification about the given event using the built-in (english) title and
+    /// message.
+    pub fn new(event: NotificationEvent, variables: &[(&str, String)]) -> Self {
+        let variables: BTreeMap<String, String> = variables
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.clone()))
+            .collect();
+        let (title, message) = templates::builtin(Locale::En, event);
+
+        Self {
+            event,
+            title: templates::render(title, &variables),
+            message: templates::render(message, &variables),
+            variables,
+        }
+    }
+}
+
+impl fmt::Display for Notification {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.title, self.message)
+    }
+}
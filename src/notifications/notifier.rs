@@ -0,0 +1,9 @@
+#[cfg(test)]
+use mockall::automock;
+
+use super::Notification;
+
+#[cfg_attr(test, automock)]
+pub trait Notifier: Send + Sync {
+    fn notify(&self, notification: &Notification) -> anyhow::Result<()>;
+}
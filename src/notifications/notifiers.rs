@@ -0,0 +1,290 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::Utc;
+use log::{info, warn};
+
+use super::{Notification, Notifier, Outbox, QueuedNotification, Templates};
+use crate::metrics::{Counter, Registry};
+
+/// Interval in which the queued notifications are checked for being due to be retried.
+pub const RETRY_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Delivers notifications (rendered using the configured templates) to all configured notifiers.
+/// Notifications which couldn't be delivered to a notifier are queued in the outbox and retried
+/// until they have been delivered.
+pub struct Notifiers {
+    notifiers: Vec<(String, Box<dyn Notifier>)>,
+    templates: Templates,
+    outbox: Outbox,
+    // makes sure a queued notification isn't retried concurrently
+    retrying: Mutex<()>,
+    metrics: Arc<Registry>,
+}
+
+impl Notifiers {
+    /// Creates the notifiers from the given notifiers and their (unique) targets.
+    pub fn new(
+        notifiers: Vec<(String, Box<dyn Notifier>)>,
+        templates: Templates,
+        outbox: Outbox,
+        metrics: Arc<Registry>,
+    ) -> Self {
+        Self {
+            notifiers,
+            templates,
+            outbox,
+            retrying: Mutex::new(()),
+            metrics,
+        }
+    }
+
+    /// Retries delivering all queued notifications which are due.
+    pub fn retry(&self) {
+        let _retrying = self.retrying.lock().unwrap();
+        let now = Utc::now().timestamp();
+        match self.outbox.due(now) {
+            Ok(due) => {
+                for queued in due {
+                    self.redeliver(&queued, now);
+                }
+            }
+            Err(e) => warn!("failed to read the queued notifications: {}", e),
+        }
+    }
+
+    /// Delivers all queued notifications of the given target right away (e.g. because it is
+    /// reachable again).
+    fn flush(&self, target: &str) {
+        let _retrying = self.retrying.lock().unwrap();
+        let now = Utc::now().timestamp();
+        match self.outbox.pending(target) {
+            Ok(pending) => {
+                for queued in pending {
+                    if !self.redeliver(&queued, now) {
+                        break;
+                    }
+                }
+            }
+            Err(e) => warn!("failed to read the queued notifications: {}", e),
+        }
+    }
+
+    /// Retries delivering a queued notification and returns whether it has been delivered.
+    fn redeliver(&self, queued: &QueuedNotification, now: i64) -> bool {
+        let notifier = self
+            .notifiers
+            .iter()
+            .find(|(target, _)| *target == queued.target);
+        let delivered = match notifier {
+            // the notifier has been removed from the configuration
+            None => {
+                warn!(
+                    "dropping queued notification \"{}\" for the unknown notifier {}",
+                    queued.notification, queued.target
+                );
+                true
+            }
+            Some((_, notifier)) => match notifier.notify(&queued.notification) {
+                Ok(_) => {
+                    info!(
+                        "delivered queued notification \"{}\" after {} retries",
+                        queued.notification,
+                        queued.attempts + 1
+                    );
+                    self.metrics
+                        .increment(Counter::NotificationDeliveries, &[("result", "success")]);
+                    true
+                }
+                Err(e) => {
+                    warn!(
+                        "failed to deliver queued notification \"{}\": {}",
+                        queued.notification, e
+                    );
+                    self.metrics
+                        .increment(Counter::NotificationDeliveries, &[("result", "failure")]);
+                    false
+                }
+            },
+        };
+
+        let result = if delivered {
+            self.outbox.remove(queued.id)
+        } else {
+            self.outbox.postpone(queued, now)
+        };
+        if let Err(e) = result {
+            warn!("failed to update the queued notification: {}", e);
+        }
+
+        delivered
+    }
+
+    /// Queues a notification which couldn't be delivered to the given target.
+    fn queue(&self, target: &str, notification: &Notification) {
+        match self
+            .outbox
+            .push(target, notification, Utc::now().timestamp())
+        {
+            Ok(dropped) => {
+                for _ in 0..dropped {
+                    self.metrics
+                        .increment(Counter::NotificationDeliveries, &[("result", "dropped")]);
+                }
+                if dropped > 0 {
+                    warn!(
+                        "dropped the {} oldest queued notification(s) because too many are queued",
+                        dropped
+                    );
+                }
+            }
+            Err(e) => warn!(
+                "failed to queue notification \"{}\" for a retry: {}",
+                notification, e
+            ),
+        }
+    }
+}
+
+impl Notifier for Notifiers {
+    fn notify(&self, notification: &Notification) -> anyhow::Result<()> {
+        let mut notification = notification.clone();
+        self.templates.apply(&mut notification);
+
+        let mut failures = 0;
+        for (target, notifier) in self.notifiers.iter() {
+            match notifier.notify(&notification) {
+                Ok(_) => {
+                    self.metrics
+                        .increment(Counter::NotificationDeliveries, &[("result", "success")]);
+                    // the notifier is reachable (again)
+                    self.flush(target);
+                }
+                Err(e) => {
+                    warn!("failed to deliver notification \"{}\": {}", notification, e);
+                    self.metrics
+                        .increment(Counter::NotificationDeliveries, &[("result", "failure")]);
+                    self.queue(target, &notification);
+                    failures += 1;
+                }
+            }
+        }
+
+        if failures > 0 {
+            return Err(anyhow::anyhow!(
+                "failed to deliver notification to {} of {} notifiers",
+                failures,
+                self.notifiers.len()
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    use super::*;
+    use crate::configuration::{NotificationEvent, NotificationRetry};
+    use crate::notifications::MockNotifier;
+
+    fn outbox() -> Outbox {
+        Outbox::open(None, &NotificationRetry::default()).unwrap()
+    }
+
+    #[test]
+    fn test_notifiers_deliver_to_all_notifiers_and_count_results() {
+        // SETUP
+        let metrics = Arc::new(Registry::new());
+
+        let mut succeeding = MockNotifier::new();
+        succeeding.expect_notify().once().returning(|_| Ok(()));
+        let mut failing = MockNotifier::new();
+        failing
+            .expect_notify()
+            .once()
+            .returning(|_| Err(anyhow::anyhow!("failed")));
+
+        let notifiers = Notifiers::new(
+            vec![
+                ("succeeding".to_string(), Box::new(succeeding)),
+                ("failing".to_string(), Box::new(failing)),
+            ],
+            Templates::default(),
+            outbox(),
+            metrics.clone(),
+        );
+
+        // TESTING
+        assert!(notifiers
+            .notify(&Notification::new(NotificationEvent::Failover, &[]))
+            .is_err());
+
+        assert_eq!(
+            metrics.get(Counter::NotificationDeliveries, &[("result", "success")]),
+            1
+        );
+        assert_eq!(
+            metrics.get(Counter::NotificationDeliveries, &[("result", "failure")]),
+            1
+        );
+    }
+
+    #[test]
+    fn test_notifiers_queue_failed_notifications_until_notifier_is_reachable() {
+        // SETUP
+        let metrics = Arc::new(Registry::new());
+
+        let reachable = Arc::new(AtomicBool::new(false));
+        let delivered = Arc::new(Mutex::new(Vec::new()));
+        let mut notifier = MockNotifier::new();
+        notifier.expect_notify().returning({
+            let reachable = reachable.clone();
+            let delivered = delivered.clone();
+            move |notification| {
+                if !reachable.load(Ordering::SeqCst) {
+                    return Err(anyhow::anyhow!("unreachable"));
+                }
+                delivered.lock().unwrap().push(notification.event);
+                Ok(())
+            }
+        });
+
+        let notifiers = Notifiers::new(
+            vec![("webhook".to_string(), Box::new(notifier))],
+            Templates::default(),
+            outbox(),
+            metrics.clone(),
+        );
+
+        // TESTING
+        assert!(notifiers
+            .notify(&Notification::new(NotificationEvent::ShutdownFailed, &[]))
+            .is_err());
+        assert_eq!(notifiers.outbox.len().unwrap(), 1);
+
+        // a failed retry keeps the notification queued
+        notifiers.flush("webhook");
+        assert_eq!(notifiers.outbox.len().unwrap(), 1);
+
+        // once the notifier is reachable again the queued notification is delivered as well
+        reachable.store(true, Ordering::SeqCst);
+        assert!(notifiers
+            .notify(&Notification::new(NotificationEvent::Failover, &[]))
+            .is_ok());
+        assert_eq!(
+            *delivered.lock().unwrap(),
+            vec![
+                NotificationEvent::Failover,
+                NotificationEvent::ShutdownFailed
+            ]
+        );
+        assert_eq!(notifiers.outbox.len().unwrap(), 0);
+        assert_eq!(
+            metrics.get(Counter::NotificationDeliveries, &[("result", "failure")]),
+            2
+        );
+    }
+}
@@ -0,0 +1,280 @@
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use rusqlite::{params, Connection};
+
+use super::Notification;
+use crate::configuration::{Files, NotificationRetry, Store};
+
+// how long to wait for another writer (e.g. the device store) to release the database
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+// database of the outbox under the root of the files API (if there's no device store)
+const OUTBOX_FILE: &str = "outbox.db";
+
+static SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS outbox (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        target TEXT NOT NULL,
+        notification TEXT NOT NULL,
+        attempts INTEGER NOT NULL,
+        next_attempt INTEGER NOT NULL
+    );
+";
+
+/// Notification which couldn't be delivered to one of the notifiers.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct QueuedNotification {
+    pub id: i64,
+    pub target: String,
+    pub notification: Notification,
+    pub attempts: u32,
+}
+
+/// Bounded queue of notifications which couldn't be delivered and are retried with an
+/// exponential backoff. It is kept in the device store or under the root of the files API (if
+/// configured) to survive restarts.
+pub struct Outbox {
+    connection: Mutex<Connection>,
+    retry: NotificationRetry,
+}
+
+impl Outbox {
+    /// Opens the outbox in the database of the device store or otherwise in its own database
+    /// under the root of the files API. Without either of them it is kept in memory only.
+    pub fn open_configured(
+        store: Option<&Store>,
+        files: Option<&Files>,
+        retry: &NotificationRetry,
+    ) -> anyhow::Result<Self> {
+        match (store, files) {
+            (Some(store), _) => Self::open(Some(&store.path), retry),
+            (None, Some(files)) => {
+                std::fs::create_dir_all(&files.root)?;
+                Self::open(Some(&files.root.join(OUTBOX_FILE)), retry)
+            }
+            (None, None) => Self::open(None, retry),
+        }
+    }
+
+    /// Opens the outbox in the given database or keeps it in memory only.
+    pub fn open(path: Option<&Path>, retry: &NotificationRetry) -> anyhow::Result<Self> {
+        let connection = match path {
+            Some(path) => Connection::open(path)?,
+            None => Connection::open_in_memory()?,
+        };
+        connection.busy_timeout(BUSY_TIMEOUT)?;
+        connection.execute_batch(SCHEMA)?;
+
+        Ok(Self {
+            connection: Mutex::new(connection),
+            retry: retry.clone(),
+        })
+    }
+
+    /// Queues a notification for the given target and returns how many of the oldest queued
+    /// notifications have been dropped to stay within the limit.
+    pub fn push(
+        &self,
+        target: &str,
+        notification: &Notification,
+        now: i64,
+    ) -> anyhow::Result<usize> {
+        let connection = self.connection.lock().unwrap();
+        connection.execute(
+            "INSERT INTO outbox (target, notification, attempts, next_attempt)
+                VALUES (?1, ?2, 0, ?3)",
+            params![
+                target,
+                serde_json::to_string(notification)?,
+                now + self.backoff(0) as i64
+            ],
+        )?;
+
+        let dropped = connection.execute(
+            "DELETE FROM outbox WHERE id NOT IN (SELECT id FROM outbox ORDER BY id DESC LIMIT ?1)",
+            params![self.retry.max_queued as i64],
+        )?;
+
+        Ok(dropped)
+    }
+
+    /// Returns the queued notifications (oldest first) which are due to be retried.
+    pub fn due(&self, now: i64) -> anyhow::Result<Vec<QueuedNotification>> {
+        self.query(
+            "SELECT id, target, notification, attempts FROM outbox
+                WHERE next_attempt <= ?1 ORDER BY id",
+            params![now],
+        )
+    }
+
+    /// Returns all queued notifications (oldest first) of the given target.
+    pub fn pending(&self, target: &str) -> anyhow::Result<Vec<QueuedNotification>> {
+        self.query(
+            "SELECT id, target, notification, attempts FROM outbox
+                WHERE target = ?1 ORDER BY id",
+            params![target],
+        )
+    }
+
+    pub fn remove(&self, id: i64) -> anyhow::Result<()> {
+        let connection = self.connection.lock().unwrap();
+        connection.execute("DELETE FROM outbox WHERE id = ?1", params![id])?;
+        Ok(())
+    }
+
+    /// Records another failed attempt and postpones the next one.
+    pub fn postpone(&self, queued: &QueuedNotification, now: i64) -> anyhow::Result<()> {
+        let attempts = queued.attempts + 1;
+        let connection = self.connection.lock().unwrap();
+        connection.execute(
+            "UPDATE outbox SET attempts = ?1, next_attempt = ?2 WHERE id = ?3",
+            params![attempts, now + self.backoff(attempts) as i64, queued.id],
+        )?;
+        Ok(())
+    }
+
+    pub fn len(&self) -> anyhow::Result<usize> {
+        let connection = self.connection.lock().unwrap();
+        let count: i64 =
+            connection.query_row("SELECT COUNT(*) FROM outbox", [], |row| row.get(0))?;
+        Ok(count as usize)
+    }
+
+    /// Delay (in seconds) after the given number of failed retries.
+    fn backoff(&self, attempts: u32) -> u64 {
+        self.retry
+            .backoff
+            .saturating_mul(2u64.saturating_pow(attempts))
+            .min(self.retry.max_backoff)
+    }
+
+    fn query(
+        &self,
+        sql: &str,
+        params: impl rusqlite::Params,
+    ) -> anyhow::Result<Vec<QueuedNotification>> {
+        let connection = self.connection.lock().unwrap();
+        let mut statement = connection.prepare(sql)?;
+        let rows = statement.query_map(params, |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, u32>(3)?,
+            ))
+        })?;
+
+        let mut queued = Vec::new();
+        for row in rows {
+            let (id, target, notification, attempts) = row?;
+            queued.push(QueuedNotification {
+                id,
+                target,
+                notification: serde_json::from_str(&notification)?,
+                attempts,
+            });
+        }
+
+        Ok(queued)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::*;
+    use temp_dir::TempDir;
+
+    use super::*;
+    use crate::configuration::NotificationEvent;
+
+    #[fixture]
+    fn retry() -> NotificationRetry {
+        NotificationRetry {
+            max_queued: 2,
+            backoff: 10,
+            max_backoff: 30,
+        }
+    }
+
+    fn notification(name: &str) -> Notification {
+        Notification::new(
+            NotificationEvent::ShutdownFailed,
+            &[("name", name.to_string())],
+        )
+    }
+
+    #[rstest]
+    fn test_outbox_retries_with_backoff(retry: NotificationRetry) {
+        let outbox = Outbox::open(None, &retry).unwrap();
+        assert_eq!(
+            outbox.push("webhook", &notification("NAS"), 100).unwrap(),
+            0
+        );
+
+        assert!(outbox.due(109).unwrap().is_empty());
+        let due = outbox.due(110).unwrap();
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].notification, notification("NAS"));
+
+        // the delay is doubled after every failed retry up to the maximum
+        outbox.postpone(&due[0], 110).unwrap();
+        assert!(outbox.due(129).unwrap().is_empty());
+        let due = outbox.due(130).unwrap();
+        assert_eq!(due[0].attempts, 1);
+
+        outbox.postpone(&due[0], 130).unwrap();
+        assert!(outbox.due(159).unwrap().is_empty());
+        assert_eq!(outbox.due(160).unwrap().len(), 1);
+
+        outbox.remove(due[0].id).unwrap();
+        assert_eq!(outbox.len().unwrap(), 0);
+    }
+
+    #[rstest]
+    fn test_outbox_drops_oldest_notifications(retry: NotificationRetry) {
+        let outbox = Outbox::open(None, &retry).unwrap();
+        outbox.push("webhook", &notification("NAS"), 100).unwrap();
+        outbox.push("other", &notification("Backup"), 100).unwrap();
+        assert_eq!(
+            outbox.push("webhook", &notification("Media"), 100).unwrap(),
+            1
+        );
+
+        let pending = outbox.pending("webhook").unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].notification, notification("Media"));
+        assert_eq!(outbox.len().unwrap(), 2);
+    }
+
+    #[rstest]
+    fn test_outbox_survives_restarts(retry: NotificationRetry) {
+        let dir = TempDir::new().unwrap();
+        let path = dir.child("home-monitor-rs.db");
+
+        Outbox::open(Some(&path), &retry)
+            .unwrap()
+            .push("webhook", &notification("NAS"), 100)
+            .unwrap();
+
+        let outbox = Outbox::open(Some(&path), &retry).unwrap();
+        assert_eq!(outbox.pending("webhook").unwrap().len(), 1);
+    }
+
+    #[rstest]
+    fn test_outbox_survives_restarts_under_files_root(retry: NotificationRetry) {
+        let dir = TempDir::new().unwrap();
+        let files = Files {
+            root: dir.child("files"),
+        };
+
+        Outbox::open_configured(None, Some(&files), &retry)
+            .unwrap()
+            .push("webhook", &notification("NAS"), 100)
+            .unwrap();
+        assert!(files.root.join(OUTBOX_FILE).exists());
+
+        let outbox = Outbox::open_configured(None, Some(&files), &retry).unwrap();
+        assert_eq!(outbox.pending("webhook").unwrap().len(), 1);
+    }
+}
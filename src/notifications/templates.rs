@@ -0,0 +1,200 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use crate::configuration::{Locale, NotificationEvent, NotificationTemplate, Notifications};
+
+/// Renders the title and message of notifications in the configured language or using the
+/// configured templates.
+#[derive(Clone, Debug, Default)]
+pub struct Templates {
+    locale: Locale,
+    templates: BTreeMap<NotificationEvent, NotificationTemplate>,
+}
+
+impl Templates {
+    pub fn new(notifications: &Notifications) -> Self {
+        Self {
+            locale: notifications.locale,
+            templates: notifications.templates.clone(),
+        }
+    }
+
+    pub fn apply(&self, notification: &mut super::Notification) {
+        let (title, message) = builtin(self.locale, notification.event);
+        let template = self.templates.get(&notification.event);
+        let title = template
+            .and_then(|template| template.title.as_deref())
+            .unwrap_or(title);
+        let message = template
+            .and_then(|template| template.message.as_deref())
+            .unwrap_or(message);
+
+        notification.title = render(title, &notification.variables);
+        notification.message = render(message, &notification.variables);
+    }
+}
+
+/// Returns the built-in title and message of notifications about the given event.
+pub fn builtin(locale: Locale, event: NotificationEvent) -> (&'static str, &'static str) {
+    match (locale, event) {
+        (Locale::En, NotificationEvent::UnexpectedOnline) => (
+            "UnexpectedOnline",
+            "{name} ({id}) came back online after {offlineDuration} without being woken up",
+        ),
+        (Locale::En, NotificationEvent::ShutdownFailed) => (
+            "ShutdownFailed",
+            "{name} ({id}) is still online after it has been shut down",
+        ),
+        (Locale::En, NotificationEvent::Failover) => (
+            "Failover",
+            "the active instance stopped responding and the standby took over the control of the servers",
+        ),
+        (Locale::En, NotificationEvent::UpdateAvailable) => (
+            "Update available",
+            "version {version} is available (currently running {currentVersion})",
+        ),
+        (Locale::En, NotificationEvent::Crashed) => (
+            "{application} crashed",
+            "{application} crashed in task {task}: {reason}",
+        ),
+        (Locale::En, NotificationEvent::PowerChanged) => (
+            "Power state changed",
+            "{name} ({id}) went {state} without being woken up or shut down ({cause})",
+        ),
+        (Locale::En, NotificationEvent::ControlDisabled) => (
+            "ControlDisabled",
+            "{name} ({id}) failed to shut down {failedShutdowns} times in a row and is only monitored until its control is re-enabled",
+        ),
+        (Locale::En, NotificationEvent::AuthenticationFailed) => (
+            "AuthenticationFailed",
+            "{name} ({id}) rejected the SSH credentials of {username} and isn't shut down anymore until they have been updated",
+        ),
+        (Locale::En, NotificationEvent::MacMismatch) => (
+            "MacMismatch",
+            "{ip} of {name} ({id}) belongs to {observedMac} instead of {mac}",
+        ),
+        (Locale::De, NotificationEvent::UnexpectedOnline) => (
+            "Unerwartet online",
+            "{name} ({id}) ist nach {offlineDuration} wieder online, ohne geweckt worden zu sein",
+        ),
+        (Locale::De, NotificationEvent::ShutdownFailed) => (
+            "Herunterfahren fehlgeschlagen",
+            "{name} ({id}) ist nach dem Herunterfahren immer noch online",
+        ),
+        (Locale::De, NotificationEvent::Failover) => (
+            "Failover",
+            "die aktive Instanz antwortet nicht mehr und die Standby-Instanz hat die Steuerung der Server übernommen",
+        ),
+        (Locale::De, NotificationEvent::UpdateAvailable) => (
+            "Update verfügbar",
+            "Version {version} ist verfügbar (aktuell läuft {currentVersion})",
+        ),
+        (Locale::De, NotificationEvent::Crashed) => (
+            "{application} abgestürzt",
+            "{application} ist im Task {task} abgestürzt: {reason}",
+        ),
+        (Locale::De, NotificationEvent::PowerChanged) => (
+            "Betriebszustand geändert",
+            "{name} ({id}) ist {state}, ohne geweckt oder heruntergefahren worden zu sein ({cause})",
+        ),
+        (Locale::De, NotificationEvent::ControlDisabled) => (
+            "Steuerung deaktiviert",
+            "{name} ({id}) konnte {failedShutdowns} Mal in Folge nicht heruntergefahren werden und wird nur noch überwacht, bis die Steuerung wieder aktiviert wird",
+        ),
+        (Locale::De, NotificationEvent::AuthenticationFailed) => (
+            "Anmeldung fehlgeschlagen",
+            "{name} ({id}) hat die SSH-Zugangsdaten von {username} abgelehnt und wird bis zu deren Aktualisierung nicht mehr heruntergefahren",
+        ),
+        (Locale::De, NotificationEvent::MacMismatch) => (
+            "MAC-Adresse geändert",
+            "{ip} von {name} ({id}) gehört zu {observedMac} statt zu {mac}",
+        ),
+    }
+}
+
+/// Replaces every `{variable}` in the template with its value (unknown variables are kept).
+pub fn render(template: &str, variables: &BTreeMap<String, String>) -> String {
+    let mut rendered = template.to_string();
+    for (name, value) in variables.iter() {
+        rendered = rendered.replace(&format!("{{{name}}}"), value);
+    }
+
+    rendered
+}
+
+/// Formats a duration for humans (e.g. `1h 5m 3s`).
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    let (hours, minutes, seconds) = (secs / 3600, secs / 60 % 60, secs % 60);
+    match (hours, minutes) {
+        (0, 0) => format!("{seconds}s"),
+        (0, _) => format!("{minutes}m {seconds}s"),
+        _ => format!("{hours}h {minutes}m {seconds}s"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::*;
+
+    use super::*;
+    use crate::notifications::Notification;
+
+    fn failed_shutdown() -> Notification {
+        Notification::new(
+            NotificationEvent::ShutdownFailed,
+            &[("name", "NAS".to_string()), ("id", "nas".to_string())],
+        )
+    }
+
+    #[rstest]
+    fn test_notification_uses_english_by_default() {
+        let notification = failed_shutdown();
+
+        assert_eq!(notification.title, "ShutdownFailed");
+        assert_eq!(
+            notification.message,
+            "NAS (nas) is still online after it has been shut down"
+        );
+    }
+
+    #[rstest]
+    fn test_templates_translate_and_override_notifications() {
+        let mut notifications = Notifications {
+            locale: Locale::De,
+            ..Default::default()
+        };
+        let templates = Templates::new(&notifications);
+
+        let mut notification = failed_shutdown();
+        templates.apply(&mut notification);
+        assert_eq!(notification.title, "Herunterfahren fehlgeschlagen");
+        assert_eq!(
+            notification.message,
+            "NAS (nas) ist nach dem Herunterfahren immer noch online"
+        );
+
+        // a custom template only replaces the configured parts
+        notifications.templates.insert(
+            NotificationEvent::ShutdownFailed,
+            NotificationTemplate {
+                title: None,
+                message: Some("{name} läuft noch ({unknown})".to_string()),
+            },
+        );
+        let templates = Templates::new(&notifications);
+
+        let mut notification = failed_shutdown();
+        templates.apply(&mut notification);
+        assert_eq!(notification.title, "Herunterfahren fehlgeschlagen");
+        assert_eq!(notification.message, "NAS läuft noch ({unknown})");
+    }
+
+    #[rstest]
+    #[case(Duration::from_secs(42), "42s")]
+    #[case(Duration::from_secs(125), "2m 5s")]
+    #[case(Duration::from_secs(3 * 3600 + 7), "3h 0m 7s")]
+    fn test_format_duration(#[case] duration: Duration, #[case] expected: &str) {
+        assert_eq!(format_duration(duration), expected);
+    }
+}
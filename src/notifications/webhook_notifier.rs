@@ -0,0 +1,92 @@
+use std::time::Duration;
+
+use hmac::{Hmac, Mac};
+use log::debug;
+use sha2::Sha256;
+
+use super::{Notification, Notifier};
+use crate::configuration;
+use crate::networking::agent_builder;
+use crate::utils::Secret;
+
+/// Maximum time a single webhook request may take (like a request to a router) so that a hanging
+/// receiver doesn't stall the monitoring.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Header containing the time (seconds since the UNIX epoch) at which a payload has been signed.
+pub const TIMESTAMP_HEADER: &str = "X-Home-Monitor-Timestamp";
+/// Header containing the HMAC-SHA256 signature of a payload as `sha256=<hex>`.
+pub const SIGNATURE_HEADER: &str = "X-Home-Monitor-Signature";
+
+pub struct WebhookNotifier {
+    agent: ureq::Agent,
+    url: String,
+    secret: Option<Secret>,
+}
+
+impl WebhookNotifier {
+    pub fn new(webhook: &configuration::Webhook, proxy: Option<&str>) -> Self {
+        Self {
+            agent: agent_builder(proxy).timeout(REQUEST_TIMEOUT).build(),
+            url: webhook.url.clone(),
+            secret: webhook.secret.clone(),
+        }
+    }
+}
+
+/// Signs `{timestamp}.{body}` so that the receiver can verify the payload as well as the time at
+/// which it has been sent (to reject replayed payloads).
+fn sign(secret: &Secret, timestamp: i64, body: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.expose().as_bytes())
+        .expect("HMAC accepts keys of any length");
+    mac.update(format!("{}.{}", timestamp, body).as_bytes());
+
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+impl Notifier for WebhookNotifier {
+    fn notify(&self, notification: &Notification) -> anyhow::Result<()> {
+        debug!("sending notification \"{}\" to {}", notification, self.url);
+        let body = serde_json::to_string(notification)?;
+        let mut request = self
+            .agent
+            .post(&self.url)
+            .set("Content-Type", "application/json");
+        if let Some(secret) = &self.secret {
+            let timestamp = chrono::Utc::now().timestamp();
+            request = request
+                .set(TIMESTAMP_HEADER, &timestamp.to_string())
+                .set(SIGNATURE_HEADER, &sign(secret, timestamp, &body));
+        }
+        request.send_string(&body).map_err(anyhow::Error::from)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::*;
+
+    use super::*;
+
+    #[rstest]
+    fn test_sign_payload_with_timestamp() {
+        let secret = Secret::from("s3cr3t");
+        let body = r#"{"event":"unexpectedOnline"}"#;
+
+        assert_eq!(
+            sign(&secret, 1700000000, body),
+            "sha256=2e9b9648c6aa14012a5a1eb4fe7210c7ddd9fb04a56dbd2726f293ae7d274aa9"
+        );
+        // the timestamp is part of the signature
+        assert_ne!(
+            sign(&secret, 1700000000, body),
+            sign(&secret, 1700000001, body)
+        );
+        assert_ne!(
+            sign(&secret, 1700000000, body),
+            sign(&Secret::from("other"), 1700000000, body)
+        );
+    }
+}
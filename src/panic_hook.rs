@@ -0,0 +1,88 @@
+use std::backtrace::Backtrace;
+use std::sync::{Arc, Mutex};
+
+use log::error;
+
+use crate::configuration::NotificationEvent;
+use crate::env::PKG_NAME;
+use crate::notifications::{Notification, Notifier};
+
+static LAST_PANIC: Mutex<Option<String>> = Mutex::new(None);
+
+/// Installs a process-wide panic hook which logs every panic with a backtrace.
+///
+/// Panics inside tokio tasks are caught by the runtime so they are only
+/// remembered here and reported through [`report_task_exit`] once the task has
+/// ended. Panics on the main thread are reported immediately.
+pub fn install(notifier: Arc<dyn Notifier>) {
+    std::panic::set_hook(Box::new(move |info| {
+        let thread = std::thread::current();
+        let thread_name = thread.name().unwrap_or("<unnamed>");
+        let backtrace = Backtrace::force_capture();
+
+        error!("panic in thread {}: {}\n{}", thread_name, info, backtrace);
+
+        let message = info.to_string();
+        if thread_name == "main" {
+            notify(&*notifier, "main", &message);
+        }
+        *LAST_PANIC.lock().unwrap_or_else(|e| e.into_inner()) = Some(message);
+    }));
+}
+
+/// Reports the unexpected exit of the task with the given name.
+pub fn report_task_exit<T>(
+    notifier: &dyn Notifier,
+    task: &str,
+    result: &Result<T, tokio::task::JoinError>,
+) {
+    let reason = match result {
+        Err(e) if e.is_panic() => LAST_PANIC
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take()
+            .unwrap_or_else(|| "panicked".to_string()),
+        Err(e) => e.to_string(),
+        Ok(_) => "stopped unexpectedly".to_string(),
+    };
+
+    error!("{} task exited: {}", task, reason);
+    notify(notifier, task, &reason);
+}
+
+fn notify(notifier: &dyn Notifier, task: &str, reason: &str) {
+    let notification = Notification::new(
+        NotificationEvent::Crashed,
+        &[
+            ("application", PKG_NAME.to_string()),
+            ("task", task.to_string()),
+            ("reason", reason.to_string()),
+        ],
+    );
+    if let Err(e) = notifier.notify(&notification) {
+        error!("failed to send crash notification: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notifications::MockNotifier;
+
+    #[tokio::test]
+    async fn test_report_task_exit_notifies_about_stopped_task() {
+        // SETUP
+        let mut notifier = MockNotifier::new();
+
+        // EXPECTATIONS
+        notifier
+            .expect_notify()
+            .withf(|notification| notification.message.contains("in task monitoring"))
+            .once()
+            .returning(|_| Ok(()));
+
+        // TESTING
+        let result = tokio::spawn(async {}).await;
+        report_task_exit(&notifier, "monitoring", &result);
+    }
+}
@@ -0,0 +1,75 @@
+use std::sync::Arc;
+
+use log::{debug, warn};
+
+use crate::networking::{Presence, RouterClients};
+
+/// Regularly queries a router for its associated clients which are considered online even if they
+/// don't respond to pings.
+pub struct PresencePoller {
+    name: String,
+    clients: Box<dyn RouterClients>,
+    presence: Arc<Presence>,
+}
+
+impl PresencePoller {
+    pub fn new(name: &str, clients: Box<dyn RouterClients>, presence: Arc<Presence>) -> Self {
+        Self {
+            name: name.to_string(),
+            clients,
+            presence,
+        }
+    }
+
+    pub fn poll_once(&self) {
+        match self.clients.clients() {
+            Ok(clients) => {
+                debug!("{} reported {} clients", self.name, clients.len());
+                self.presence.set_clients(&self.name, clients);
+            }
+            Err(e) => {
+                // fall back to pinging the devices instead of keeping outdated clients
+                warn!("failed to query the clients of {}: {}", self.name, e);
+                self.presence.set_clients(&self.name, Default::default());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::net::IpAddr;
+
+    use rstest::*;
+
+    use super::*;
+    use crate::networking::MockRouterClients;
+
+    #[rstest]
+    fn test_presence_poller_updates_and_clears_clients() {
+        // SETUP
+        let ip: IpAddr = "192.168.1.10".parse().unwrap();
+
+        let mut clients = MockRouterClients::new();
+        clients
+            .expect_clients()
+            .once()
+            .returning(move || Ok(HashSet::from([ip])));
+        clients
+            .expect_clients()
+            .once()
+            .returning(|| Err(anyhow::anyhow!("login failed")));
+
+        let presence = Arc::new(Presence::new());
+        let poller = PresencePoller::new("router", Box::new(clients), presence.clone());
+
+        // TESTING
+        poller.poll_once();
+        assert!(presence.is_present(&ip));
+
+        // the clients are forgotten if the router can't be queried
+        poller.poll_once();
+        assert!(!presence.is_present(&ip));
+    }
+}
@@ -0,0 +1,198 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use log::{debug, info};
+
+use crate::configuration::{self, DeviceId};
+use crate::utils::{Clock, Instant};
+
+/// Time within which the configured percentage of the devices has to reappear.
+const REAPPEAR_WINDOW: Duration = Duration::from_secs(300);
+
+/// Detects that most devices reappeared after all of them have been offline for a long time
+/// (which most likely means that the power has been restored after an outage) and keeps track of
+/// the recovery from it.
+pub struct RecoveryDetector {
+    config: configuration::Recovery,
+    clock: Arc<dyn Clock>,
+    // time since which all devices have been offline
+    offline_since: Option<Instant>,
+    // whether the devices are considered to have been offline long enough since the start
+    offline_on_startup: bool,
+    // time at which the first device reappeared after the long offline period
+    reappearing_since: Option<Instant>,
+    // time at which the current recovery started
+    recovering_since: Option<Instant>,
+}
+
+impl RecoveryDetector {
+    pub fn new(config: configuration::Recovery, clock: Arc<dyn Clock>) -> Self {
+        // the devices may have been offline just as long as home-monitor-rs itself
+        let offline_since = config.on_startup.then(|| clock.now());
+        let offline_on_startup = config.on_startup;
+
+        Self {
+            config,
+            clock,
+            offline_since,
+            offline_on_startup,
+            reappearing_since: None,
+            recovering_since: None,
+        }
+    }
+
+    /// Core servers which are woken up one after another during the recovery.
+    pub fn servers(&self) -> &[DeviceId] {
+        &self.config.servers
+    }
+
+    /// Updates the detection with the number of online devices and returns whether a recovery
+    /// just started.
+    pub fn update(&mut self, online: usize, total: usize) -> bool {
+        let now = self.clock.now();
+        if online == 0 {
+            if self.offline_since.is_none() {
+                debug!("all devices are offline");
+                self.offline_since = Some(now);
+            }
+            self.reappearing_since = None;
+            return false;
+        }
+
+        let offline_since = match self.offline_since {
+            Some(offline_since) => offline_since,
+            None => return false,
+        };
+        let reappearing_since = *self.reappearing_since.get_or_insert(now);
+        if !self.offline_on_startup
+            && reappearing_since.saturating_duration_since(offline_since)
+                < Duration::from_secs(self.config.min_offline)
+        {
+            // the devices haven't been offline long enough
+            self.reset();
+            return false;
+        }
+
+        if online * 100 >= total * self.config.min_reappeared as usize {
+            info!(
+                "{} of {} devices reappeared after being offline for {}s, recovering from a probable power outage",
+                online,
+                total,
+                reappearing_since
+                    .saturating_duration_since(offline_since)
+                    .as_secs()
+            );
+            self.reset();
+            self.recovering_since = Some(now);
+            return true;
+        }
+
+        if self.clock.elapsed(reappearing_since) > REAPPEAR_WINDOW {
+            debug!(
+                "only {} of {} devices reappeared after being offline, not recovering",
+                online, total
+            );
+            self.reset();
+        }
+
+        false
+    }
+
+    fn reset(&mut self) {
+        self.offline_since = None;
+        self.offline_on_startup = false;
+        self.reappearing_since = None;
+    }
+
+    /// Whether the grace period of the current recovery is still running.
+    pub fn is_recovering(&self) -> bool {
+        match self.recovering_since {
+            Some(recovering_since) => {
+                self.clock.elapsed(recovering_since) < Duration::from_secs(self.config.grace_period)
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::*;
+
+    use super::*;
+    use crate::utils::ManualClock;
+
+    #[fixture]
+    fn clock() -> Arc<ManualClock> {
+        Arc::new(ManualClock::new(Instant::now()))
+    }
+
+    #[fixture]
+    fn config() -> configuration::Recovery {
+        configuration::Recovery {
+            min_offline: 600,
+            min_reappeared: 50,
+            servers: Vec::new(),
+            grace_period: 1800,
+            on_startup: false,
+        }
+    }
+
+    #[rstest]
+    fn test_recovery_detector_detects_reappearing_devices_after_long_offline_period(
+        clock: Arc<ManualClock>,
+        config: configuration::Recovery,
+    ) {
+        let mut detector = RecoveryDetector::new(config, clock.clone());
+
+        // devices going offline and online shortly after isn't a recovery
+        assert!(!detector.update(4, 4));
+        assert!(!detector.update(0, 4));
+        clock.advance(Duration::from_secs(60));
+        assert!(!detector.update(4, 4));
+        assert!(!detector.is_recovering());
+
+        // whereas most devices reappearing after a long offline period is
+        assert!(!detector.update(0, 4));
+        clock.advance(Duration::from_secs(3600));
+        assert!(!detector.update(1, 4));
+        clock.advance(Duration::from_secs(60));
+        assert!(detector.update(2, 4));
+        assert!(detector.is_recovering());
+        assert!(!detector.update(4, 4));
+
+        // until the grace period has passed
+        clock.advance(Duration::from_secs(1800));
+        assert!(!detector.is_recovering());
+    }
+
+    #[rstest]
+    fn test_recovery_detector_ignores_few_reappearing_devices(
+        clock: Arc<ManualClock>,
+        config: configuration::Recovery,
+    ) {
+        let mut detector = RecoveryDetector::new(config, clock.clone());
+
+        assert!(!detector.update(0, 4));
+        clock.advance(Duration::from_secs(3600));
+        assert!(!detector.update(1, 4));
+        clock.advance(REAPPEAR_WINDOW + Duration::from_secs(1));
+        assert!(!detector.update(1, 4));
+
+        // later reappearing devices don't start a recovery anymore
+        assert!(!detector.update(4, 4));
+        assert!(!detector.is_recovering());
+    }
+
+    #[rstest]
+    fn test_recovery_detector_considers_startup_as_recovery_if_configured(
+        clock: Arc<ManualClock>,
+        mut config: configuration::Recovery,
+    ) {
+        config.on_startup = true;
+        let mut detector = RecoveryDetector::new(config, clock.clone());
+
+        assert!(detector.update(3, 4));
+        assert!(detector.is_recovering());
+    }
+}
@@ -0,0 +1,342 @@
+use std::time::Duration;
+
+use anyhow::bail;
+use log::debug;
+use serde::Deserialize;
+
+use crate::dom::TraceContext;
+use crate::web::serialization::Device;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteStatus {
+    #[serde(default)]
+    pub server: Option<Device>,
+    pub devices: Vec<Device>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RemoteAction {
+    #[serde(default)]
+    action_id: Option<String>,
+    #[serde(default)]
+    confirmation_token: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RemoteActionStatus {
+    state: String,
+    #[serde(default)]
+    result: Option<String>,
+}
+
+/// Client to control the servers of a running `home-monitor-rs` daemon through its REST API.
+pub struct RemoteClient {
+    url: String,
+    token: Option<String>,
+}
+
+impl RemoteClient {
+    pub fn new(url: &str, token: Option<String>) -> Self {
+        Self {
+            url: format!("{}/api/v1", url.trim_end_matches('/')),
+            token,
+        }
+    }
+
+    pub fn wakeup(&self, server_id: &str) -> anyhow::Result<()> {
+        self.run_action(&format!("/server/{}/wakeup", server_id), false)
+    }
+
+    /// Shuts down the given server. If the daemon requires the shutdown to be confirmed it is
+    /// only re-submitted with the received token if `confirm` is set.
+    pub fn shutdown(&self, server_id: &str, confirm: bool) -> anyhow::Result<()> {
+        self.run_action(&format!("/server/{}/shutdown", server_id), confirm)
+    }
+
+    /// Whether the daemon answers its liveness probe within the given timeout.
+    pub fn is_alive(&self, timeout: Duration) -> bool {
+        match self.request("GET", "/livez").timeout(timeout).call() {
+            Ok(_) => true,
+            Err(e) => {
+                debug!("{} isn't alive: {}", self.url, e);
+                false
+            }
+        }
+    }
+
+    pub fn status(&self, server_id: Option<&str>) -> anyhow::Result<RemoteStatus> {
+        let endpoint = match server_id {
+            Some(server_id) => format!("/server/{}/status", server_id),
+            None => "/status".to_string(),
+        };
+
+        let response = self
+            .request("GET", &endpoint)
+            .call()
+            .map_err(anyhow::Error::from)?;
+        Ok(serde_json::from_reader(response.into_reader())?)
+    }
+
+    /// Starts the action behind the given endpoint and waits until it has finished in the
+    /// background. The daemon aborts actions exceeding their timeout so they always finish.
+    fn run_action(&self, endpoint: &str, confirm: bool) -> anyhow::Result<()> {
+        let action = match self.start_action(endpoint)? {
+            RemoteAction {
+                confirmation_token: Some(token),
+                ..
+            } => {
+                if !confirm {
+                    bail!("the action has to be confirmed (use --confirm)");
+                }
+
+                let action = self.start_action(&format!("{}?confirm={}", endpoint, token))?;
+                if action.confirmation_token.is_some() {
+                    bail!("the confirmation of the action has been rejected");
+                }
+                action
+            }
+            action => action,
+        };
+        let action_id = match action.action_id {
+            Some(action_id) => action_id,
+            None => return Ok(()),
+        };
+
+        loop {
+            let response = self
+                .request("GET", &format!("/action/{}", action_id))
+                .call()
+                .map_err(anyhow::Error::from)?;
+            let status: RemoteActionStatus = serde_json::from_reader(response.into_reader())?;
+            match status.state.as_str() {
+                "running" => {}
+                "failed" => bail!("{}", status.result.unwrap_or_default()),
+                _ => return Ok(()),
+            }
+
+            std::thread::sleep(POLL_INTERVAL);
+        }
+    }
+
+    fn start_action(&self, endpoint: &str) -> anyhow::Result<RemoteAction> {
+        let response = self
+            .request("PUT", endpoint)
+            .call()
+            .map_err(anyhow::Error::from)?;
+        match response.status() {
+            202 => Ok(serde_json::from_reader(response.into_reader())?),
+            _ => Ok(RemoteAction::default()),
+        }
+    }
+
+    fn request(&self, method: &str, endpoint: &str) -> ureq::Request {
+        let trace = TraceContext::new();
+        let url = format!("{}{}", self.url, endpoint);
+        debug!("[{}] {} {}", trace, method, url);
+
+        let request = ureq::request(method, &url).set("X-Request-Id", trace.id());
+        match &self.token {
+            Some(token) => request.set("Authorization", &format!("Bearer {}", token)),
+            None => request,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::sync::mpsc;
+
+    use rstest::*;
+
+    use super::*;
+
+    fn serve(responses: Vec<(&'static str, &'static str)>) -> (String, mpsc::Receiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            for (status, body) in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut request = [0; 4096];
+                let size = stream.read(&mut request).unwrap();
+                tx.send(String::from_utf8_lossy(&request[..size]).to_string())
+                    .unwrap();
+
+                let response = format!(
+                    "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+        });
+
+        (url, rx)
+    }
+
+    fn serve_once(body: &'static str) -> (String, mpsc::Receiver<String>) {
+        serve(vec![("200 OK", body)])
+    }
+
+    #[rstest]
+    fn test_wakeup_sends_token() {
+        let (url, requests) = serve_once("");
+        let client = RemoteClient::new(&url, Some("secret".to_string()));
+
+        client.wakeup("myserver").unwrap();
+
+        let request = requests.recv().unwrap();
+        assert!(request.starts_with("PUT /api/v1/server/myserver/wakeup "));
+        assert!(request.contains("Authorization: Bearer secret\r\n"));
+        assert!(request.contains("X-Request-Id: "));
+    }
+
+    #[rstest]
+    fn test_shutdown_without_token() {
+        let (url, requests) = serve_once("");
+        let client = RemoteClient::new(&url, None);
+
+        client.shutdown("myserver", false).unwrap();
+
+        let request = requests.recv().unwrap();
+        assert!(request.starts_with("PUT /api/v1/server/myserver/shutdown "));
+        assert!(!request.contains("Authorization"));
+    }
+
+    #[rstest]
+    fn test_wakeup_waits_for_action() {
+        let (url, requests) = serve(vec![
+            ("202 Accepted", r#"{"actionId":"1234"}"#),
+            (
+                "200 OK",
+                r#"{"id":"1234","server":"myserver","action":"wakeup","state":"succeeded","started":"2023-01-01T00:00:00+00:00"}"#,
+            ),
+        ]);
+        let client = RemoteClient::new(&url, None);
+
+        client.wakeup("myserver").unwrap();
+
+        assert!(requests
+            .recv()
+            .unwrap()
+            .starts_with("PUT /api/v1/server/myserver/wakeup "));
+        assert!(requests
+            .recv()
+            .unwrap()
+            .starts_with("GET /api/v1/action/1234 "));
+    }
+
+    #[rstest]
+    fn test_shutdown_fails_if_action_failed() {
+        let (url, _requests) = serve(vec![
+            ("202 Accepted", r#"{"actionId":"1234"}"#),
+            (
+                "200 OK",
+                r#"{"id":"1234","server":"myserver","action":"shutdown","state":"failed","started":"2023-01-01T00:00:00+00:00","result":"unreachable"}"#,
+            ),
+        ]);
+        let client = RemoteClient::new(&url, None);
+
+        let error = client.shutdown("myserver", false).unwrap_err();
+
+        assert_eq!(error.to_string(), "unreachable");
+    }
+
+    #[rstest]
+    fn test_shutdown_fails_if_confirmation_required() {
+        let (url, requests) = serve(vec![(
+            "202 Accepted",
+            r#"{"confirmationToken":"abcd","expiresIn":60}"#,
+        )]);
+        let client = RemoteClient::new(&url, None);
+
+        assert!(client.shutdown("myserver", false).is_err());
+
+        assert!(requests
+            .recv()
+            .unwrap()
+            .starts_with("PUT /api/v1/server/myserver/shutdown "));
+    }
+
+    #[rstest]
+    fn test_shutdown_confirms_with_token() {
+        let (url, requests) = serve(vec![
+            (
+                "202 Accepted",
+                r#"{"confirmationToken":"abcd","expiresIn":60}"#,
+            ),
+            ("202 Accepted", r#"{"actionId":"1234"}"#),
+            (
+                "200 OK",
+                r#"{"id":"1234","server":"myserver","action":"shutdown","state":"succeeded","started":"2023-01-01T00:00:00+00:00"}"#,
+            ),
+        ]);
+        let client = RemoteClient::new(&url, None);
+
+        client.shutdown("myserver", true).unwrap();
+
+        assert!(requests
+            .recv()
+            .unwrap()
+            .starts_with("PUT /api/v1/server/myserver/shutdown "));
+        assert!(requests
+            .recv()
+            .unwrap()
+            .starts_with("PUT /api/v1/server/myserver/shutdown?confirm=abcd "));
+        assert!(requests
+            .recv()
+            .unwrap()
+            .starts_with("GET /api/v1/action/1234 "));
+    }
+
+    #[rstest]
+    #[case("200 OK", true)]
+    #[case("503 Service Unavailable", false)]
+    fn test_is_alive_checks_liveness_probe(#[case] status: &'static str, #[case] expected: bool) {
+        let (url, requests) = serve(vec![(status, "")]);
+        let client = RemoteClient::new(&url, None);
+
+        assert_eq!(client.is_alive(Duration::from_secs(5)), expected);
+
+        let request = requests.recv().unwrap();
+        assert!(request.starts_with("GET /api/v1/livez "));
+    }
+
+    #[rstest]
+    fn test_is_alive_fails_if_unreachable() {
+        let closed_port = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let client = RemoteClient::new(&format!("http://127.0.0.1:{closed_port}"), None);
+
+        assert!(!client.is_alive(Duration::from_secs(5)));
+    }
+
+    #[rstest]
+    fn test_status_parses_devices() {
+        let (url, requests) = serve_once(
+            r#"{"devices":[{"name":"Server","ip":"10.0.0.1","lastSeenTimeout":60,"isOnline":true,"lastSeen":null,"consecutiveMissedPings":0}]}"#,
+        );
+        let client = RemoteClient::new(&url, None);
+
+        let status = client.status(None).unwrap();
+
+        let request = requests.recv().unwrap();
+        assert!(request.starts_with("GET /api/v1/status "));
+        assert!(status.server.is_none());
+        assert_eq!(status.devices.len(), 1);
+        assert_eq!(status.devices[0].name, "Server");
+        assert!(status.devices[0].is_online);
+    }
+}
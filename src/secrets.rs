@@ -0,0 +1,242 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use anyhow::{anyhow, bail};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use sha2::Sha256;
+use zeroize::Zeroizing;
+
+use crate::configuration::{self, DeviceId, Server, Ssh, SshAuthentication, WakeupRelay};
+use crate::utils::Secret;
+
+static PREFIX: &str = "enc:";
+const KEY_LENGTH: usize = 32;
+const SALT_LENGTH: usize = 16;
+const NONCE_LENGTH: usize = 12;
+// makes guessing weak passphrases expensive
+const KDF_ROUNDS: u32 = 100_000;
+
+pub fn is_encrypted(value: &str) -> bool {
+    value.starts_with(PREFIX)
+}
+
+/// Key material (the content of a key file or a passphrase) to encrypt and decrypt secrets with.
+pub struct SecretKey(Zeroizing<Vec<u8>>);
+
+impl SecretKey {
+    pub fn from_file(path: &Path) -> anyhow::Result<Self> {
+        let content = Zeroizing::new(std::fs::read(path)?);
+        // ignore the trailing newline added by most editors
+        let content = content.strip_suffix(b"\n").unwrap_or(&content);
+        if content.is_empty() {
+            bail!("{} is empty", path.display());
+        }
+
+        Ok(Self(Zeroizing::new(content.to_vec())))
+    }
+
+    pub fn from_passphrase(passphrase: &str) -> Self {
+        Self(Zeroizing::new(passphrase.as_bytes().to_vec()))
+    }
+
+    pub fn prompt() -> anyhow::Result<Self> {
+        let passphrase =
+            Zeroizing::new(rpassword::prompt_password("passphrase for the secrets: ")?);
+        if passphrase.is_empty() {
+            bail!("empty passphrase");
+        }
+
+        Ok(Self::from_passphrase(&passphrase))
+    }
+
+    /// Reads the configured key file or otherwise prompts for a passphrase.
+    pub fn load(secrets: &configuration::Secrets) -> anyhow::Result<Self> {
+        match &secrets.key_file {
+            Some(key_file) => Self::from_file(key_file),
+            None => Self::prompt(),
+        }
+    }
+
+    fn cipher(&self, salt: &[u8]) -> ChaCha20Poly1305 {
+        // wipe the derived key as soon as the cipher has been set up
+        let mut key = Zeroizing::new([0u8; KEY_LENGTH]);
+        pbkdf2::pbkdf2_hmac::<Sha256>(&self.0, salt, KDF_ROUNDS, key.as_mut());
+        ChaCha20Poly1305::new(Key::from_slice(key.as_ref()))
+    }
+
+    pub fn encrypt(&self, secret: &str) -> anyhow::Result<String> {
+        let mut salt = [0u8; SALT_LENGTH];
+        OsRng.fill_bytes(&mut salt);
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+
+        let ciphertext = self
+            .cipher(&salt)
+            .encrypt(&nonce, secret.as_bytes())
+            .map_err(|_| anyhow!("failed to encrypt the secret"))?;
+
+        let mut data = salt.to_vec();
+        data.extend_from_slice(&nonce);
+        data.extend(ciphertext);
+        Ok(format!("{}{}", PREFIX, STANDARD.encode(data)))
+    }
+
+    pub fn decrypt(&self, value: &str) -> anyhow::Result<String> {
+        let data = match value.strip_prefix(PREFIX) {
+            Some(encoded) => STANDARD.decode(encoded)?,
+            None => bail!("not an encrypted secret"),
+        };
+        if data.len() < SALT_LENGTH + NONCE_LENGTH {
+            bail!("encrypted secret is too short");
+        }
+
+        let (salt, data) = data.split_at(SALT_LENGTH);
+        let (nonce, ciphertext) = data.split_at(NONCE_LENGTH);
+        let secret = self
+            .cipher(salt)
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| anyhow!("failed to decrypt the secret (wrong key or passphrase?)"))?;
+
+        Ok(String::from_utf8(secret)?)
+    }
+}
+
+fn get_ssh_secret(ssh: &mut Ssh) -> &mut Secret {
+    match &mut ssh.authentication {
+        SshAuthentication::Password(password) => password,
+        SshAuthentication::PrivateKey(private_key) => &mut private_key.passphrase,
+    }
+}
+
+fn get_server_secrets(server: &mut Server) -> Vec<&mut Secret> {
+    let mut secrets = vec![get_ssh_secret(&mut server.ssh)];
+    match &mut server.relay {
+        Some(WakeupRelay::Ssh(relay)) => secrets.push(get_ssh_secret(&mut relay.ssh)),
+        Some(WakeupRelay::Api(relay)) => secrets.extend(relay.token.as_mut()),
+        None => {}
+    }
+
+    secrets
+}
+
+/// Decrypts the encrypted SSH credentials and relay tokens of the given servers in memory. The key is only loaded
+/// if there is at least one encrypted secret.
+pub fn decrypt_servers<F>(
+    servers: &mut HashMap<DeviceId, Server>,
+    load_key: F,
+) -> anyhow::Result<()>
+where
+    F: FnOnce() -> anyhow::Result<SecretKey>,
+{
+    let mut load_key = Some(load_key);
+    let mut key: Option<SecretKey> = None;
+    for (server_id, server) in servers.iter_mut() {
+        for secret in get_server_secrets(server) {
+            if !is_encrypted(secret.expose()) {
+                continue;
+            }
+
+            if key.is_none() {
+                key = Some(load_key.take().unwrap()()?);
+            }
+            *secret = key
+                .as_ref()
+                .unwrap()
+                .decrypt(secret.expose())
+                .map(Secret::new)
+                .map_err(|e| anyhow!("{}: {}", server_id, e))?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::*;
+
+    use super::*;
+    use crate::configuration::Configuration;
+    use crate::web::server::test::*;
+
+    static PASSPHRASE: &str = "correct horse battery staple";
+
+    #[rstest]
+    fn test_secret_key_encrypts_and_decrypts() {
+        let key = SecretKey::from_passphrase(PASSPHRASE);
+
+        let encrypted = key.encrypt("password").unwrap();
+        assert!(is_encrypted(&encrypted));
+        assert!(!encrypted.contains("password"));
+
+        assert_eq!(key.decrypt(&encrypted).unwrap(), "password");
+    }
+
+    #[rstest]
+    fn test_secret_key_fails_to_decrypt_with_wrong_key() {
+        let encrypted = SecretKey::from_passphrase(PASSPHRASE)
+            .encrypt("password")
+            .unwrap();
+
+        assert!(SecretKey::from_passphrase("wrong")
+            .decrypt(&encrypted)
+            .is_err());
+        assert!(SecretKey::from_passphrase(PASSPHRASE)
+            .decrypt("password")
+            .is_err());
+    }
+
+    #[rstest]
+    fn test_decrypt_servers_only_loads_key_if_necessary(config: Configuration) {
+        let mut servers = configuration::get_servers(&config.devices);
+        let expected_servers = servers.clone();
+
+        decrypt_servers(&mut servers, || panic!("no key required")).unwrap();
+
+        assert_eq!(servers, expected_servers);
+    }
+
+    #[rstest]
+    fn test_decrypt_servers_decrypts_ssh_credentials(config: Configuration) {
+        let key = SecretKey::from_passphrase(PASSPHRASE);
+        let mut servers = configuration::get_servers(&config.devices);
+        let expected_servers = servers.clone();
+        for server in servers.values_mut() {
+            for secret in get_server_secrets(server) {
+                *secret = Secret::new(key.encrypt(secret.expose()).unwrap());
+            }
+        }
+        assert_ne!(servers, expected_servers);
+
+        decrypt_servers(&mut servers, || Ok(SecretKey::from_passphrase(PASSPHRASE))).unwrap();
+
+        assert_eq!(servers, expected_servers);
+    }
+
+    #[rstest]
+    fn test_decrypt_servers_decrypts_api_relay_token(config: Configuration) {
+        let key = SecretKey::from_passphrase(PASSPHRASE);
+        let mut servers = configuration::get_servers(&config.devices);
+        for server in servers.values_mut() {
+            server.relay = Some(WakeupRelay::Api(configuration::ApiRelay {
+                url: "http://192.168.2.254:8000".to_string(),
+                server: None,
+                token: Some(Secret::new(key.encrypt("token").unwrap())),
+            }));
+        }
+
+        decrypt_servers(&mut servers, || Ok(SecretKey::from_passphrase(PASSPHRASE))).unwrap();
+
+        for server in servers.values() {
+            match &server.relay {
+                Some(WakeupRelay::Api(relay)) => {
+                    assert_eq!(relay.token, Some(Secret::from("token")))
+                }
+                relay => panic!("unexpected relay {:?}", relay),
+            }
+        }
+    }
+}
@@ -0,0 +1,497 @@
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use chrono::Utc;
+use rusqlite::{params, Connection, OptionalExtension, Transaction, TransactionBehavior};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::configuration::{
+    self, Configuration, Dependencies, DependencyError, Device, DeviceId, DeviceMap,
+};
+
+// how long to wait for another writer (e.g. a second instance) to release the database
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+// older revisions are dropped to keep the database small
+const MAX_REVISIONS: i64 = 100;
+
+static SCHEMA: &str = "
+    PRAGMA foreign_keys = ON;
+
+    CREATE TABLE IF NOT EXISTS devices (
+        id TEXT PRIMARY KEY NOT NULL,
+        name TEXT NOT NULL,
+        ip TEXT NOT NULL,
+        server INTEGER NOT NULL,
+        definition TEXT NOT NULL
+    );
+
+    CREATE TABLE IF NOT EXISTS dependencies (
+        server TEXT NOT NULL REFERENCES devices(id),
+        device TEXT NOT NULL,
+        position INTEGER NOT NULL,
+        PRIMARY KEY (server, device)
+    );
+
+    CREATE TABLE IF NOT EXISTS revisions (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        timestamp TEXT NOT NULL,
+        author TEXT,
+        reason TEXT NOT NULL,
+        changes TEXT NOT NULL,
+        inventory TEXT NOT NULL
+    );
+";
+
+/// The devices and dependencies kept in the device store.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Inventory {
+    #[serde(serialize_with = "configuration::serialize_sorted")]
+    pub devices: DeviceMap,
+    pub dependencies: Dependencies,
+}
+
+impl Inventory {
+    /// Makes sure the devices and dependencies are consistent with the rooms, persons and groups
+    /// of the given configuration.
+    pub fn check(&self, config: &Configuration) -> Result<(), DependencyError> {
+        configuration::check(&Configuration {
+            devices: self.devices.clone(),
+            dependencies: self.dependencies.clone(),
+            ..config.clone()
+        })
+    }
+}
+
+/// Devices and servers with dependencies which differ between two revisions.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Changes {
+    pub added: Vec<DeviceId>,
+    pub removed: Vec<DeviceId>,
+    pub changed: Vec<DeviceId>,
+    pub dependencies: Vec<DeviceId>,
+}
+
+impl Changes {
+    pub fn between(old: &Inventory, new: &Inventory) -> Self {
+        let mut changes = Self::default();
+        for (device_id, device) in new.devices.iter() {
+            match old.devices.get(device_id) {
+                None => changes.added.push(device_id.clone()),
+                Some(old_device) if old_device != device => changes.changed.push(device_id.clone()),
+                Some(_) => {}
+            }
+        }
+        changes.removed = old
+            .devices
+            .keys()
+            .filter(|device_id| !new.devices.contains_key(device_id))
+            .cloned()
+            .collect();
+
+        let servers: BTreeSet<&DeviceId> = old
+            .dependencies
+            .0
+            .keys()
+            .chain(new.dependencies.0.keys())
+            .collect();
+        changes.dependencies = servers
+            .into_iter()
+            .filter(|server| old.dependencies.0.get(server) != new.dependencies.0.get(server))
+            .cloned()
+            .collect();
+
+        changes.added.sort();
+        changes.removed.sort();
+        changes.changed.sort();
+        changes
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.changed.is_empty()
+            && self.dependencies.is_empty()
+    }
+}
+
+/// A change of the devices and dependencies kept in the device store.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Revision {
+    pub revision: i64,
+    pub timestamp: String,
+    /// Identifies the token used to authenticate the change without revealing it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    pub reason: String,
+    pub changes: Changes,
+}
+
+/// SQLite database holding the devices and dependencies instead of the configuration file.
+pub struct DeviceStore {
+    connection: Mutex<Connection>,
+}
+
+impl DeviceStore {
+    pub fn open<P: AsRef<Path>>(path: P) -> anyhow::Result<Self> {
+        Self::init(Connection::open(path)?)
+    }
+
+    #[cfg(test)]
+    pub fn open_in_memory() -> anyhow::Result<Self> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(connection: Connection) -> anyhow::Result<Self> {
+        connection.busy_timeout(BUSY_TIMEOUT)?;
+        connection.execute_batch(SCHEMA)?;
+
+        Ok(Self {
+            connection: Mutex::new(connection),
+        })
+    }
+
+    pub fn is_empty(&self) -> anyhow::Result<bool> {
+        let connection = self.connection.lock().unwrap();
+        let count: i64 =
+            connection.query_row("SELECT COUNT(*) FROM devices", [], |row| row.get(0))?;
+        Ok(count == 0)
+    }
+
+    pub fn load(&self) -> anyhow::Result<Inventory> {
+        let mut connection = self.connection.lock().unwrap();
+        let transaction = connection.transaction()?;
+        read(&transaction)
+    }
+
+    /// Replaces all stored devices and dependencies.
+    pub fn replace(
+        &self,
+        inventory: Inventory,
+        reason: &str,
+        author: Option<&str>,
+    ) -> anyhow::Result<Inventory> {
+        self.update(reason, author, |stored| {
+            *stored = inventory;
+            Ok(())
+        })
+    }
+
+    /// Applies the given modification to the stored devices and dependencies within a single
+    /// transaction and returns the result. Every actual change is recorded as a new revision.
+    pub fn update<F>(
+        &self,
+        reason: &str,
+        author: Option<&str>,
+        modify: F,
+    ) -> anyhow::Result<Inventory>
+    where
+        F: FnOnce(&mut Inventory) -> anyhow::Result<()>,
+    {
+        let mut connection = self.connection.lock().unwrap();
+        // lock the database right away so that concurrent writers can't interleave
+        let transaction = connection.transaction_with_behavior(TransactionBehavior::Immediate)?;
+
+        let stored = read(&transaction)?;
+        let mut inventory = stored.clone();
+        modify(&mut inventory)?;
+        configuration::fill_ids(&mut inventory.devices);
+
+        let changes = Changes::between(&stored, &inventory);
+        if !changes.is_empty() {
+            write(&transaction, &inventory)?;
+            write_revision(&transaction, &inventory, &changes, reason, author)?;
+        }
+        transaction.commit()?;
+
+        Ok(inventory)
+    }
+
+    /// Returns all kept revisions starting with the latest one.
+    pub fn revisions(&self) -> anyhow::Result<Vec<Revision>> {
+        let connection = self.connection.lock().unwrap();
+        let mut statement = connection.prepare(
+            "SELECT id, timestamp, author, reason, changes FROM revisions ORDER BY id DESC",
+        )?;
+        let mut rows = statement.query([])?;
+
+        let mut revisions = Vec::new();
+        while let Some(row) = rows.next()? {
+            let changes: String = row.get(4)?;
+            revisions.push(Revision {
+                revision: row.get(0)?,
+                timestamp: row.get(1)?,
+                author: row.get(2)?,
+                reason: row.get(3)?,
+                changes: serde_json::from_str(&changes)?,
+            });
+        }
+
+        Ok(revisions)
+    }
+
+    /// Returns the devices and dependencies as they were stored by the given revision.
+    pub fn revision(&self, revision: i64) -> anyhow::Result<Option<Inventory>> {
+        let connection = self.connection.lock().unwrap();
+        let inventory: Option<String> = connection
+            .query_row(
+                "SELECT inventory FROM revisions WHERE id = ?1",
+                params![revision],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        inventory
+            .map(|inventory| {
+                let mut inventory: Inventory = serde_json::from_str(&inventory)?;
+                configuration::fill_ids(&mut inventory.devices);
+                Ok(inventory)
+            })
+            .transpose()
+    }
+
+    /// Seeds an empty store with the devices and dependencies of the given configuration and
+    /// afterwards replaces them in the configuration with the stored ones.
+    pub fn load_into(&self, config: &mut Configuration) -> anyhow::Result<()> {
+        if self.is_empty()? {
+            self.replace(
+                Inventory {
+                    devices: config.devices.clone(),
+                    dependencies: config.dependencies.clone(),
+                },
+                "seeded from the configuration file",
+                None,
+            )?;
+        }
+
+        let inventory = self.load()?;
+        inventory.check(config)?;
+        config.devices = inventory.devices;
+        config.dependencies = inventory.dependencies;
+
+        Ok(())
+    }
+}
+
+fn read(transaction: &Transaction) -> anyhow::Result<Inventory> {
+    let mut devices = DeviceMap::new();
+    {
+        let mut statement = transaction.prepare("SELECT id, definition FROM devices")?;
+        let mut rows = statement.query([])?;
+        while let Some(row) = rows.next()? {
+            let id: String = row.get(0)?;
+            let definition: String = row.get(1)?;
+            devices.insert(id.parse()?, serde_json::from_str(&definition)?);
+        }
+    }
+    configuration::fill_ids(&mut devices);
+
+    let mut dependencies = Dependencies::default();
+    {
+        let mut statement = transaction
+            .prepare("SELECT server, device FROM dependencies ORDER BY server, position")?;
+        let mut rows = statement.query([])?;
+        while let Some(row) = rows.next()? {
+            let server: String = row.get(0)?;
+            let device: String = row.get(1)?;
+            dependencies
+                .0
+                .entry(server.parse()?)
+                .or_insert_with(Vec::new)
+                .push(device.parse()?);
+        }
+    }
+
+    Ok(Inventory {
+        devices,
+        dependencies,
+    })
+}
+
+fn write(transaction: &Transaction, inventory: &Inventory) -> anyhow::Result<()> {
+    transaction.execute("DELETE FROM dependencies", [])?;
+    transaction.execute("DELETE FROM devices", [])?;
+
+    for (device_id, device) in inventory.devices.iter() {
+        let machine = match device {
+            Device::Server(server) => &server.machine,
+            Device::Machine(machine) => machine,
+        };
+        transaction.execute(
+            "INSERT INTO devices (id, name, ip, server, definition) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                device_id.0,
+                machine.name,
+                machine.ip.to_string(),
+                matches!(device, Device::Server(_)),
+                serde_json::to_string(device)?
+            ],
+        )?;
+    }
+
+    for (server_id, deps) in inventory.dependencies.0.iter() {
+        for (position, device_id) in deps.iter().enumerate() {
+            transaction.execute(
+                "INSERT INTO dependencies (server, device, position) VALUES (?1, ?2, ?3)",
+                params![server_id.0, device_id.0, position as i64],
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+fn write_revision(
+    transaction: &Transaction,
+    inventory: &Inventory,
+    changes: &Changes,
+    reason: &str,
+    author: Option<&str>,
+) -> anyhow::Result<()> {
+    transaction.execute(
+        "INSERT INTO revisions (timestamp, author, reason, changes, inventory) \
+            VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![
+            Utc::now().to_rfc3339(),
+            author,
+            reason,
+            serde_json::to_string(changes)?,
+            serde_json::to_string(inventory)?
+        ],
+    )?;
+    transaction.execute(
+        "DELETE FROM revisions WHERE id <= (SELECT MAX(id) FROM revisions) - ?1",
+        params![MAX_REVISIONS],
+    )?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::*;
+
+    use super::*;
+    use crate::dom;
+    use crate::dom::device::test::*;
+    use crate::web::server::test::*;
+
+    #[rstest]
+    fn test_device_store_seeds_from_configuration(mut config: Configuration) {
+        let store = DeviceStore::open_in_memory().unwrap();
+        assert!(store.is_empty().unwrap());
+
+        let devices = config.devices.clone();
+        let dependencies = config.dependencies.clone();
+        store.load_into(&mut config).unwrap();
+
+        assert!(!store.is_empty().unwrap());
+        assert_eq!(config.devices, devices);
+        assert_eq!(config.dependencies, dependencies);
+    }
+
+    #[rstest]
+    fn test_device_store_replaces_configuration_if_not_empty(
+        mut config: Configuration,
+        machine: dom::Machine,
+    ) {
+        let store = DeviceStore::open_in_memory().unwrap();
+        store.load_into(&mut config.clone()).unwrap();
+
+        // remove the machine from the store but not from the configuration
+        let machine_id: configuration::DeviceId = machine.id.to_string().parse().unwrap();
+        store
+            .update("test", None, |inventory| {
+                inventory.devices.remove(&machine_id);
+                inventory.dependencies.0.clear();
+                Ok(())
+            })
+            .unwrap();
+
+        store.load_into(&mut config).unwrap();
+
+        assert!(!config.devices.contains_key(&machine_id));
+        assert!(config.dependencies.0.is_empty());
+    }
+
+    #[rstest]
+    fn test_device_store_rolls_back_failed_updates(config: Configuration) {
+        let store = DeviceStore::open_in_memory().unwrap();
+        store.load_into(&mut config.clone()).unwrap();
+        let inventory = store.load().unwrap();
+
+        let result = store.update("test", None, |inventory| {
+            inventory.devices.clear();
+            Err(anyhow::anyhow!("failed"))
+        });
+
+        assert!(result.is_err());
+        assert_eq!(store.load().unwrap(), inventory);
+        assert_eq!(store.revisions().unwrap().len(), 1);
+    }
+
+    #[rstest]
+    fn test_device_store_records_revisions(config: Configuration, machine: dom::Machine) {
+        let store = DeviceStore::open_in_memory().unwrap();
+        store.load_into(&mut config.clone()).unwrap();
+        let seeded = store.load().unwrap();
+
+        let machine_id: configuration::DeviceId = machine.id.to_string().parse().unwrap();
+        store
+            .update("remove machine", Some("0123abcd"), |inventory| {
+                inventory.devices.remove(&machine_id);
+                inventory.dependencies.0.clear();
+                Ok(())
+            })
+            .unwrap();
+        // changes without any effect don't create a revision
+        store.update("noop", None, |_| Ok(())).unwrap();
+
+        let revisions = store.revisions().unwrap();
+        assert_eq!(revisions.len(), 2);
+        assert_eq!(revisions[0].revision, 2);
+        assert_eq!(revisions[0].reason, "remove machine");
+        assert_eq!(revisions[0].author, Some("0123abcd".to_string()));
+        assert_eq!(revisions[0].changes.removed, vec![machine_id]);
+        assert_eq!(
+            revisions[0].changes.dependencies,
+            config.dependencies.0.keys().cloned().collect::<Vec<_>>()
+        );
+        assert_eq!(revisions[1].reason, "seeded from the configuration file");
+        assert_eq!(revisions[1].author, None);
+        assert_eq!(revisions[1].changes.added.len(), config.devices.len());
+
+        assert_eq!(store.revision(1).unwrap(), Some(seeded));
+        assert_eq!(store.revision(3).unwrap(), None);
+    }
+
+    #[rstest]
+    fn test_device_store_rolls_back_to_revision(config: Configuration, machine: dom::Machine) {
+        let store = DeviceStore::open_in_memory().unwrap();
+        store.load_into(&mut config.clone()).unwrap();
+        let seeded = store.load().unwrap();
+
+        let machine_id: configuration::DeviceId = machine.id.to_string().parse().unwrap();
+        store
+            .update("remove machine", None, |inventory| {
+                inventory.devices.remove(&machine_id);
+                inventory.dependencies.0.clear();
+                Ok(())
+            })
+            .unwrap();
+
+        let revision = store.revision(1).unwrap().unwrap();
+        assert_eq!(store.replace(revision, "rollback", None).unwrap(), seeded);
+        assert_eq!(store.load().unwrap(), seeded);
+
+        let revisions = store.revisions().unwrap();
+        assert_eq!(revisions.len(), 3);
+        assert_eq!(revisions[0].reason, "rollback");
+        assert_eq!(revisions[0].changes.added, vec![machine_id]);
+    }
+}
@@ -0,0 +1,44 @@
+use log::debug;
+use serde::Deserialize;
+
+use super::ReleaseChecker;
+use crate::env::PKG_NAME;
+use crate::networking::agent_builder;
+
+static GITHUB_RELEASES_URL: &str =
+    "https://api.github.com/repos/Montellese/home-monitor-rs/releases/latest";
+
+#[derive(Deserialize)]
+struct Release {
+    tag_name: String,
+}
+
+pub struct GithubReleaseChecker {
+    agent: ureq::Agent,
+    url: String,
+}
+
+impl GithubReleaseChecker {
+    pub fn new(proxy: Option<&str>) -> Self {
+        Self {
+            agent: agent_builder(proxy).build(),
+            url: GITHUB_RELEASES_URL.to_string(),
+        }
+    }
+}
+
+impl ReleaseChecker for GithubReleaseChecker {
+    fn latest_version(&self) -> anyhow::Result<String> {
+        debug!("checking {} for the latest release", self.url);
+        let response = self
+            .agent
+            .get(&self.url)
+            .set("Accept", "application/vnd.github+json")
+            .set("User-Agent", PKG_NAME)
+            .call()
+            .map_err(anyhow::Error::from)?;
+
+        let release: Release = serde_json::from_reader(response.into_reader())?;
+        Ok(release.tag_name)
+    }
+}
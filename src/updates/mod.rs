@@ -0,0 +1,40 @@
+mod github_release_checker;
+mod release_checker;
+mod update_checker;
+
+pub use github_release_checker::GithubReleaseChecker;
+#[cfg(test)]
+pub use release_checker::MockReleaseChecker;
+pub use release_checker::ReleaseChecker;
+pub use update_checker::{update_status, SharedUpdateStatus, UpdateChecker};
+
+fn parse_version(version: &str) -> Vec<u64> {
+    version
+        .trim_start_matches('v')
+        .split(['.', '-', '+'])
+        .map_while(|part| part.parse::<u64>().ok())
+        .collect()
+}
+
+/// Checks whether `version` is newer than `current_version` (ignoring pre-release suffixes).
+pub fn is_newer_version(version: &str, current_version: &str) -> bool {
+    parse_version(version) > parse_version(current_version)
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::*;
+
+    use super::*;
+
+    #[rstest]
+    #[case("v0.2.0", "0.1.0", true)]
+    #[case("0.1.1", "0.1.0", true)]
+    #[case("v1.0.0", "0.9.9", true)]
+    #[case("v0.1.0", "0.1.0", false)]
+    #[case("0.0.9", "0.1.0", false)]
+    #[case("0.10.0", "0.9.0", true)]
+    fn test_is_newer_version(#[case] version: &str, #[case] current: &str, #[case] newer: bool) {
+        assert_eq!(is_newer_version(version, current), newer);
+    }
+}
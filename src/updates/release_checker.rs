@@ -0,0 +1,7 @@
+#[cfg(test)]
+use mockall::automock;
+
+#[cfg_attr(test, automock)]
+pub trait ReleaseChecker: Send + Sync {
+    fn latest_version(&self) -> anyhow::Result<String>;
+}
@@ -0,0 +1,140 @@
+use std::sync::{Arc, Mutex};
+
+use log::{info, warn};
+
+use super::{is_newer_version, ReleaseChecker};
+use crate::configuration::NotificationEvent;
+use crate::notifications::{Notification, Notifier};
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct UpdateStatus {
+    pub current_version: String,
+    pub latest_version: Option<String>,
+    pub update_available: bool,
+}
+
+pub type SharedUpdateStatus = Arc<Mutex<UpdateStatus>>;
+
+pub fn update_status(current_version: &str) -> SharedUpdateStatus {
+    Arc::new(Mutex::new(UpdateStatus {
+        current_version: current_version.to_string(),
+        ..Default::default()
+    }))
+}
+
+pub struct UpdateChecker {
+    release_checker: Box<dyn ReleaseChecker>,
+    notifier: Arc<dyn Notifier>,
+    status: SharedUpdateStatus,
+}
+
+impl UpdateChecker {
+    pub fn new(
+        release_checker: Box<dyn ReleaseChecker>,
+        notifier: Arc<dyn Notifier>,
+        status: SharedUpdateStatus,
+    ) -> Self {
+        Self {
+            release_checker,
+            notifier,
+            status,
+        }
+    }
+
+    pub fn check_once(&self) {
+        let latest_version = match self.release_checker.latest_version() {
+            Ok(version) => version,
+            Err(e) => {
+                warn!("failed to check for updates: {}", e);
+                return;
+            }
+        };
+
+        let mut status = self.status.lock().unwrap();
+        let update_available = is_newer_version(&latest_version, &status.current_version);
+
+        // only report a newly available version once
+        let already_reported =
+            status.update_available && status.latest_version.as_ref() == Some(&latest_version);
+        if update_available && !already_reported {
+            info!(
+                "a new version {} is available (currently running {})",
+                latest_version, status.current_version
+            );
+
+            let notification = Notification::new(
+                NotificationEvent::UpdateAvailable,
+                &[
+                    ("version", latest_version.clone()),
+                    ("currentVersion", status.current_version.clone()),
+                ],
+            );
+            if let Err(e) = self.notifier.notify(&notification) {
+                warn!("failed to send update notification: {}", e);
+            }
+        }
+
+        status.latest_version = Some(latest_version);
+        status.update_available = update_available;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::*;
+
+    use super::*;
+    use crate::notifications::MockNotifier;
+    use crate::updates::MockReleaseChecker;
+
+    fn release_checker(version: &'static str) -> Box<MockReleaseChecker> {
+        let mut release_checker = MockReleaseChecker::new();
+        release_checker
+            .expect_latest_version()
+            .returning(move || Ok(version.to_string()));
+        Box::new(release_checker)
+    }
+
+    #[rstest]
+    fn test_update_checker_reports_newer_version_once() {
+        // SETUP
+        let status = update_status("0.1.0");
+        let mut notifier = MockNotifier::new();
+
+        // EXPECTATIONS
+        notifier.expect_notify().once().returning(|_| Ok(()));
+
+        // TESTING
+        let update_checker = UpdateChecker::new(
+            release_checker("v0.2.0"),
+            Arc::new(notifier),
+            status.clone(),
+        );
+        update_checker.check_once();
+        update_checker.check_once();
+
+        let status = status.lock().unwrap();
+        assert!(status.update_available);
+        assert_eq!(status.latest_version, Some("v0.2.0".to_string()));
+    }
+
+    #[rstest]
+    fn test_update_checker_ignores_same_version() {
+        // SETUP
+        let status = update_status("0.1.0");
+        let mut notifier = MockNotifier::new();
+
+        // EXPECTATIONS
+        notifier.expect_notify().never();
+
+        // TESTING
+        let update_checker = UpdateChecker::new(
+            release_checker("v0.1.0"),
+            Arc::new(notifier),
+            status.clone(),
+        );
+        update_checker.check_once();
+
+        assert!(!status.lock().unwrap().update_available);
+    }
+}
@@ -1,10 +1,8 @@
 use std::convert::From;
 use std::path::{Path, PathBuf};
 
-use anyhow::anyhow;
-
 use super::super::configuration;
-use super::AlwaysOff;
+use super::{AlwaysOff, FileError};
 
 #[derive(Debug)]
 pub struct AlwaysOffFile {
@@ -14,9 +12,6 @@ pub struct AlwaysOffFile {
 impl AlwaysOffFile {
     #[allow(dead_code)]
     pub fn new(path: &Path) -> Self {
-        // make sure the given path exists
-        std::fs::create_dir_all(path).unwrap();
-
         // append alwaysoff to the path
         let mut file = path.to_path_buf();
         file.push("alwaysoff");
@@ -36,7 +31,12 @@ impl AlwaysOff for AlwaysOffFile {
     }
 
     fn set_always_off(&self) -> anyhow::Result<()> {
-        std::fs::write(&self.file, "")?;
+        // the directory is only created when needed so that e.g. a read-only file system doesn't
+        // prevent starting up
+        if let Some(dir) = self.file.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| FileError::new(dir, e))?;
+        }
+        std::fs::write(&self.file, "").map_err(|e| FileError::new(&self.file, e))?;
         Ok(())
     }
 
@@ -49,7 +49,7 @@ impl AlwaysOff for AlwaysOffFile {
                     // it's OK if the file didn't exist anyway
                     NotFound => Ok(()),
                     // otherwise return the error
-                    _ => Err(anyhow!(e)),
+                    _ => Err(FileError::new(&self.file, e).into()),
                 }
             }
         }
@@ -122,4 +122,33 @@ mod tests {
 
         assert!(always_off.reset_always_off().is_ok());
     }
+
+    #[rstest]
+    fn new_doesnt_create_directory(root: TempDir) {
+        let path = root.path().join("server");
+        let always_off = AlwaysOffFile::new(&path);
+
+        assert!(!path.exists());
+        assert!(!always_off.is_always_off());
+    }
+
+    #[rstest]
+    fn set_always_off_creates_directory(root: TempDir) {
+        let always_off = AlwaysOffFile::new(&root.path().join("server"));
+
+        assert!(always_off.set_always_off().is_ok());
+        assert!(always_off.is_always_off());
+    }
+
+    #[rstest]
+    fn set_always_off_fails_if_directory_cannot_be_created(root: TempDir) {
+        // a file blocks the creation of the directory
+        let path = root.path().join("server");
+        assert!(std::fs::write(&path, "").is_ok());
+        let always_off = AlwaysOffFile::new(&path);
+
+        let error = always_off.set_always_off().unwrap_err();
+        let error = error.downcast_ref::<FileError>().unwrap();
+        assert_eq!(error.path(), path);
+    }
 }
@@ -0,0 +1,56 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use super::AlwaysOff;
+
+/// Keeps the always off state in memory if the files API is disabled.
+#[derive(Debug, Default)]
+pub struct AlwaysOffMemory {
+    always_off: AtomicBool,
+}
+
+impl AlwaysOffMemory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl AlwaysOff for AlwaysOffMemory {
+    fn is_always_off(&self) -> bool {
+        self.always_off.load(Ordering::Relaxed)
+    }
+
+    fn set_always_off(&self) -> anyhow::Result<()> {
+        self.always_off.store(true, Ordering::Relaxed);
+        Ok(())
+    }
+
+    fn reset_always_off(&self) -> anyhow::Result<()> {
+        self.always_off.store(false, Ordering::Relaxed);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::*;
+
+    use super::*;
+
+    #[rstest]
+    fn is_always_off_fails_by_default() {
+        let always_off = AlwaysOffMemory::new();
+
+        assert!(!always_off.is_always_off());
+    }
+
+    #[rstest]
+    fn set_and_reset_always_off_change_state() {
+        let always_off = AlwaysOffMemory::new();
+
+        assert!(always_off.set_always_off().is_ok());
+        assert!(always_off.is_always_off());
+
+        assert!(always_off.reset_always_off().is_ok());
+        assert!(!always_off.is_always_off());
+    }
+}
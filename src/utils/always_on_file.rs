@@ -1,10 +1,8 @@
 use std::convert::From;
 use std::path::{Path, PathBuf};
 
-use anyhow::anyhow;
-
 use super::super::configuration;
-use super::AlwaysOn;
+use super::{AlwaysOn, FileError};
 
 #[derive(Debug)]
 pub struct AlwaysOnFile {
@@ -14,9 +12,6 @@ pub struct AlwaysOnFile {
 impl AlwaysOnFile {
     #[allow(dead_code)]
     pub fn new(path: &Path) -> Self {
-        // make sure the given path exists
-        std::fs::create_dir_all(path).unwrap();
-
         // append alwayson to the path
         let mut file = path.to_path_buf();
         file.push("alwayson");
@@ -36,7 +31,12 @@ impl AlwaysOn for AlwaysOnFile {
     }
 
     fn set_always_on(&self) -> anyhow::Result<()> {
-        std::fs::write(&self.file, "")?;
+        // the directory is only created when needed so that e.g. a read-only file system doesn't
+        // prevent starting up
+        if let Some(dir) = self.file.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| FileError::new(dir, e))?;
+        }
+        std::fs::write(&self.file, "").map_err(|e| FileError::new(&self.file, e))?;
         Ok(())
     }
 
@@ -49,7 +49,7 @@ impl AlwaysOn for AlwaysOnFile {
                     // it's OK if the file didn't exist anyway
                     NotFound => Ok(()),
                     // otherwise return the error
-                    _ => Err(anyhow!(e)),
+                    _ => Err(FileError::new(&self.file, e).into()),
                 }
             }
         }
@@ -120,4 +120,33 @@ mod tests {
 
         assert!(always_on.reset_always_on().is_ok());
     }
+
+    #[rstest]
+    fn new_doesnt_create_directory(root: TempDir) {
+        let path = root.path().join("server");
+        let always_on = AlwaysOnFile::new(&path);
+
+        assert!(!path.exists());
+        assert!(!always_on.is_always_on());
+    }
+
+    #[rstest]
+    fn set_always_on_creates_directory(root: TempDir) {
+        let always_on = AlwaysOnFile::new(&root.path().join("server"));
+
+        assert!(always_on.set_always_on().is_ok());
+        assert!(always_on.is_always_on());
+    }
+
+    #[rstest]
+    fn set_always_on_fails_if_directory_cannot_be_created(root: TempDir) {
+        // a file blocks the creation of the directory
+        let path = root.path().join("server");
+        assert!(std::fs::write(&path, "").is_ok());
+        let always_on = AlwaysOnFile::new(&path);
+
+        let error = always_on.set_always_on().unwrap_err();
+        let error = error.downcast_ref::<FileError>().unwrap();
+        assert_eq!(error.path(), path);
+    }
 }
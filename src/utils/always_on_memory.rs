@@ -0,0 +1,56 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use super::AlwaysOn;
+
+/// Keeps the always on state in memory if the files API is disabled.
+#[derive(Debug, Default)]
+pub struct AlwaysOnMemory {
+    always_on: AtomicBool,
+}
+
+impl AlwaysOnMemory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl AlwaysOn for AlwaysOnMemory {
+    fn is_always_on(&self) -> bool {
+        self.always_on.load(Ordering::Relaxed)
+    }
+
+    fn set_always_on(&self) -> anyhow::Result<()> {
+        self.always_on.store(true, Ordering::Relaxed);
+        Ok(())
+    }
+
+    fn reset_always_on(&self) -> anyhow::Result<()> {
+        self.always_on.store(false, Ordering::Relaxed);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::*;
+
+    use super::*;
+
+    #[rstest]
+    fn is_always_on_fails_by_default() {
+        let always_on = AlwaysOnMemory::new();
+
+        assert!(!always_on.is_always_on());
+    }
+
+    #[rstest]
+    fn set_and_reset_always_on_change_state() {
+        let always_on = AlwaysOnMemory::new();
+
+        assert!(always_on.set_always_on().is_ok());
+        assert!(always_on.is_always_on());
+
+        assert!(always_on.reset_always_on().is_ok());
+        assert!(!always_on.is_always_on());
+    }
+}
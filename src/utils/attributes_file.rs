@@ -0,0 +1,62 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use super::FileError;
+
+/// Custom attributes of every device (by its identifier).
+pub type DeviceAttributes = BTreeMap<String, BTreeMap<String, String>>;
+
+/// Keeps the custom attributes set through the web API as JSON in the files API root directory.
+#[derive(Debug)]
+pub struct AttributesFile {
+    file: PathBuf,
+}
+
+impl AttributesFile {
+    pub fn new(path: &Path) -> Self {
+        Self {
+            file: path.join("attributes.json"),
+        }
+    }
+
+    pub fn load(&self) -> anyhow::Result<DeviceAttributes> {
+        match std::fs::read_to_string(&self.file) {
+            Ok(content) => Ok(serde_json::from_str(&content)?),
+            // no attributes have been set so far
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(DeviceAttributes::new()),
+            Err(e) => Err(FileError::new(&self.file, e).into()),
+        }
+    }
+
+    pub fn save(&self, attributes: &DeviceAttributes) -> anyhow::Result<()> {
+        if let Some(dir) = self.file.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| FileError::new(dir, e))?;
+        }
+        std::fs::write(&self.file, serde_json::to_string_pretty(attributes)?)
+            .map_err(|e| FileError::new(&self.file, e))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::*;
+    use temp_dir::*;
+
+    use super::*;
+
+    #[rstest]
+    fn test_attributes_file_saves_and_loads_attributes() {
+        let root = TempDir::new().unwrap();
+        let file = AttributesFile::new(&root.path().join("files"));
+        assert_eq!(file.load().unwrap(), DeviceAttributes::new());
+
+        let attributes = DeviceAttributes::from([(
+            "server".to_string(),
+            BTreeMap::from([("backup_last_ok".to_string(), "2024-01-01".to_string())]),
+        )]);
+        file.save(&attributes).unwrap();
+
+        assert_eq!(file.load().unwrap(), attributes);
+    }
+}
@@ -0,0 +1,96 @@
+#[cfg(test)]
+use std::sync::Mutex;
+use std::time::Duration;
+
+use chrono::{DateTime, Local, Utc};
+
+use super::Instant;
+
+/// Source of the current time which allows controlling the passing of time independent of the
+/// system clock (e.g. in tests).
+pub trait Clock: Send + Sync {
+    /// Monotonic time to measure timeouts and intervals with.
+    fn now(&self) -> Instant;
+
+    /// Wall-clock time to evaluate schedules and to report points in time with.
+    fn utc_now(&self) -> DateTime<Utc>;
+
+    fn local_now(&self) -> DateTime<Local> {
+        self.utc_now().with_timezone(&Local)
+    }
+
+    fn elapsed(&self, earlier: Instant) -> Duration {
+        self.now().saturating_duration_since(earlier)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn utc_now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// Clock which only moves forward when it is advanced explicitly. Its wall-clock time starts at
+/// the current system time.
+#[cfg(test)]
+#[derive(Debug)]
+pub struct ManualClock {
+    now: Mutex<(Instant, DateTime<Utc>)>,
+}
+
+#[cfg(test)]
+impl ManualClock {
+    pub fn new(now: Instant) -> Self {
+        Self {
+            now: Mutex::new((now, Utc::now())),
+        }
+    }
+
+    pub fn advance(&self, duration: Duration) {
+        let mut now = self.now.lock().unwrap();
+        now.0 += duration;
+        now.1 += chrono::Duration::from_std(duration).unwrap();
+    }
+}
+
+#[cfg(test)]
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        self.now.lock().unwrap().0
+    }
+
+    fn utc_now(&self) -> DateTime<Utc> {
+        self.now.lock().unwrap().1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::*;
+
+    use super::*;
+
+    #[rstest]
+    fn test_manual_clock_only_moves_when_advanced() {
+        let start = Instant::now();
+        let clock = ManualClock::new(start);
+        let utc_start = clock.utc_now();
+        assert_eq!(clock.now(), start);
+        assert_eq!(clock.elapsed(start), Duration::ZERO);
+
+        clock.advance(Duration::from_secs(5));
+        assert_eq!(clock.elapsed(start), Duration::from_secs(5));
+        assert_eq!(
+            clock.elapsed(clock.now() + Duration::from_secs(1)),
+            Duration::ZERO
+        );
+        assert_eq!(clock.utc_now() - utc_start, chrono::Duration::seconds(5));
+    }
+}
@@ -1,20 +1,37 @@
 mod always_off;
 mod always_off_file;
+mod always_off_memory;
 mod always_on;
 mod always_on_file;
+mod always_on_memory;
+mod attributes_file;
+mod clock;
+mod file_error;
 mod mac_addr;
+mod notes_file;
+mod rate_limiter;
+mod secret;
+mod wakeup_stagger;
 
-#[cfg(not(test))]
 pub use std::time::Instant;
 
 pub use always_off::AlwaysOff;
 #[cfg(test)]
 pub use always_off::MockAlwaysOff;
 pub use always_off_file::AlwaysOffFile;
+pub use always_off_memory::AlwaysOffMemory;
 pub use always_on::AlwaysOn;
 #[cfg(test)]
 pub use always_on::MockAlwaysOn;
 pub use always_on_file::AlwaysOnFile;
-pub use mac_addr::MacAddr;
+pub use always_on_memory::AlwaysOnMemory;
+pub use attributes_file::{AttributesFile, DeviceAttributes};
 #[cfg(test)]
-pub use sn_fake_clock::FakeClock as Instant;
+pub use clock::ManualClock;
+pub use clock::{Clock, SystemClock};
+pub use file_error::FileError;
+pub use mac_addr::MacAddr;
+pub use notes_file::{DeviceNotes, NotesFile};
+pub use rate_limiter::RateLimiter;
+pub use secret::{Secret, REDACTED};
+pub use wakeup_stagger::WakeupStagger;
@@ -0,0 +1,68 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use super::FileError;
+use crate::dom::Note;
+
+/// Notes of every device (by its identifier).
+pub type DeviceNotes = BTreeMap<String, Vec<Note>>;
+
+/// Keeps the notes attached through the web API as JSON in the files API root directory.
+#[derive(Debug)]
+pub struct NotesFile {
+    file: PathBuf,
+}
+
+impl NotesFile {
+    pub fn new(path: &Path) -> Self {
+        Self {
+            file: path.join("notes.json"),
+        }
+    }
+
+    pub fn load(&self) -> anyhow::Result<DeviceNotes> {
+        match std::fs::read_to_string(&self.file) {
+            Ok(content) => Ok(serde_json::from_str(&content)?),
+            // no notes have been attached so far
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(DeviceNotes::new()),
+            Err(e) => Err(FileError::new(&self.file, e).into()),
+        }
+    }
+
+    pub fn save(&self, notes: &DeviceNotes) -> anyhow::Result<()> {
+        if let Some(dir) = self.file.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| FileError::new(dir, e))?;
+        }
+        std::fs::write(&self.file, serde_json::to_string_pretty(notes)?)
+            .map_err(|e| FileError::new(&self.file, e))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use rstest::*;
+    use temp_dir::*;
+
+    use super::*;
+
+    #[rstest]
+    fn test_notes_file_saves_and_loads_notes() {
+        let root = TempDir::new().unwrap();
+        let file = NotesFile::new(&root.path().join("files"));
+        assert_eq!(file.load().unwrap(), DeviceNotes::new());
+
+        let notes = DeviceNotes::from([(
+            "server".to_string(),
+            vec![Note {
+                date: Utc.with_ymd_and_hms(2024, 3, 2, 10, 0, 0).unwrap(),
+                text: "PSU replaced".to_string(),
+                is_incident: false,
+            }],
+        )]);
+        file.save(&notes).unwrap();
+
+        assert_eq!(file.load().unwrap(), notes);
+    }
+}
@@ -0,0 +1,105 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use super::{Clock, Instant, SystemClock};
+
+/// Limits how many probes (e.g. pings or wake-on-lan broadcasts) may be sent within a period
+/// while still allowing a burst of up to the limit at once.
+pub struct RateLimiter {
+    limit: u32,
+    period: Duration,
+    clock: Arc<dyn Clock>,
+    // point in time at which all probes sent so far are paid off
+    paid_off: Mutex<Option<Instant>>,
+}
+
+impl RateLimiter {
+    pub fn new(limit: u32, period: Duration) -> Self {
+        Self::with_clock(limit, period, Arc::new(SystemClock))
+    }
+
+    pub fn with_clock(limit: u32, period: Duration, clock: Arc<dyn Clock>) -> Self {
+        assert!(limit > 0, "the limit of a rate limiter must not be 0");
+        Self {
+            limit,
+            period,
+            clock,
+            paid_off: Mutex::new(None),
+        }
+    }
+
+    pub fn limit(&self) -> u32 {
+        self.limit
+    }
+
+    fn cost(&self, probes: u32) -> Duration {
+        self.period / self.limit * probes
+    }
+
+    /// Reserves the given number of probes and returns how long to wait before sending them.
+    pub fn reserve(&self, probes: u32) -> Duration {
+        let now = self.clock.now();
+        let mut paid_off = self.paid_off.lock().unwrap();
+        let start = paid_off.map_or(now, |paid_off| paid_off.max(now));
+        let end = start + self.cost(probes);
+        *paid_off = Some(end);
+
+        end.saturating_duration_since(now)
+            .saturating_sub(self.period)
+    }
+
+    /// Reserves the given number of probes if they can be sent right away.
+    pub fn try_acquire(&self, probes: u32) -> bool {
+        let now = self.clock.now();
+        let mut paid_off = self.paid_off.lock().unwrap();
+        let start = paid_off.map_or(now, |paid_off| paid_off.max(now));
+        let end = start + self.cost(probes);
+        if end.saturating_duration_since(now) > self.period {
+            return false;
+        }
+
+        *paid_off = Some(end);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::*;
+
+    use super::*;
+    use crate::utils::ManualClock;
+
+    #[fixture]
+    fn clock() -> Arc<ManualClock> {
+        Arc::new(ManualClock::new(Instant::now()))
+    }
+
+    #[rstest]
+    fn test_try_acquire_allows_burst_up_to_limit(clock: Arc<ManualClock>) {
+        let limiter = RateLimiter::with_clock(3, Duration::from_secs(60), clock.clone());
+
+        assert!(limiter.try_acquire(1));
+        assert!(limiter.try_acquire(2));
+        assert!(!limiter.try_acquire(1));
+
+        // a third of the period pays off a single probe
+        clock.advance(Duration::from_secs(20));
+        assert!(limiter.try_acquire(1));
+        assert!(!limiter.try_acquire(1));
+    }
+
+    #[rstest]
+    fn test_reserve_delays_probes_exceeding_limit(clock: Arc<ManualClock>) {
+        let limiter = RateLimiter::with_clock(100, Duration::from_secs(1), clock.clone());
+
+        // the first 100 probes can be sent right away
+        assert_eq!(limiter.reserve(100), Duration::ZERO);
+        // whereas the next 50 have to wait until half of them are paid off
+        assert_eq!(limiter.reserve(50), Duration::from_millis(500));
+
+        // large bursts are spread over multiple periods
+        clock.advance(Duration::from_secs(10));
+        assert_eq!(limiter.reserve(5000), Duration::from_secs(49));
+    }
+}
@@ -0,0 +1,94 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use super::{Clock, Instant, SystemClock};
+
+/// Staggers the wakeups of servers into batches of at most `max_parallel` wakeups which are
+/// started at least `delay` apart so that many servers booting at once (e.g. after a power
+/// outage) don't cause a simultaneous power inrush.
+pub struct WakeupStagger {
+    max_parallel: u32,
+    delay: Duration,
+    clock: Arc<dyn Clock>,
+    // start and number of wakeups of the current batch
+    batch: Mutex<Option<(Instant, u32)>>,
+}
+
+impl WakeupStagger {
+    pub fn new(max_parallel: u32, delay: Duration) -> Self {
+        Self::with_clock(max_parallel, delay, Arc::new(SystemClock))
+    }
+
+    pub fn with_clock(max_parallel: u32, delay: Duration, clock: Arc<dyn Clock>) -> Self {
+        assert!(
+            max_parallel > 0,
+            "the maximum number of parallel wakeups must not be 0"
+        );
+        Self {
+            max_parallel,
+            delay,
+            clock,
+            batch: Mutex::new(None),
+        }
+    }
+
+    pub fn max_parallel(&self) -> u32 {
+        self.max_parallel
+    }
+
+    pub fn delay(&self) -> Duration {
+        self.delay
+    }
+
+    /// Reserves a wakeup in the current batch (or starts a new one) and returns its position in
+    /// the batch or how long the wakeup has to be deferred until the next batch.
+    pub fn try_acquire(&self) -> Result<u32, Duration> {
+        let now = self.clock.now();
+        let mut batch = self.batch.lock().unwrap();
+        match *batch {
+            Some((start, wakeups)) if self.clock.elapsed(start) < self.delay => {
+                if wakeups >= self.max_parallel {
+                    return Err(self.delay - self.clock.elapsed(start));
+                }
+
+                *batch = Some((start, wakeups + 1));
+                Ok(wakeups + 1)
+            }
+            _ => {
+                *batch = Some((now, 1));
+                Ok(1)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::*;
+
+    use super::*;
+    use crate::utils::ManualClock;
+
+    #[fixture]
+    fn clock() -> Arc<ManualClock> {
+        Arc::new(ManualClock::new(Instant::now()))
+    }
+
+    #[rstest]
+    fn test_try_acquire_defers_wakeups_exceeding_batch(clock: Arc<ManualClock>) {
+        let stagger = WakeupStagger::with_clock(2, Duration::from_secs(30), clock.clone());
+
+        assert_eq!(stagger.try_acquire(), Ok(1));
+        assert_eq!(stagger.try_acquire(), Ok(2));
+        assert_eq!(stagger.try_acquire(), Err(Duration::from_secs(30)));
+
+        clock.advance(Duration::from_secs(20));
+        assert_eq!(stagger.try_acquire(), Err(Duration::from_secs(10)));
+
+        // the next batch starts once the delay has passed
+        clock.advance(Duration::from_secs(10));
+        assert_eq!(stagger.try_acquire(), Ok(1));
+        assert_eq!(stagger.try_acquire(), Ok(2));
+        assert!(stagger.try_acquire().is_err());
+    }
+}
@@ -0,0 +1,264 @@
+use std::collections::HashSet;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+
+use log::{debug, info, warn};
+use tokio::net::UdpSocket;
+
+use crate::dom::communication::{ExternalWakeupSender, Standby};
+use crate::dom::{DeviceId, Server};
+use crate::utils::MacAddr;
+
+const WAKE_ON_LAN_PORT: u16 = 9;
+
+const SYNC_STREAM: [u8; 6] = [0xff; 6];
+const MAC_REPETITIONS: usize = 16;
+const MAX_PACKET_SIZE: usize = 1024;
+
+/// Listener for Wake-on-LAN magic packets sent by other tools to the controlled servers which
+/// reports them to the monitoring and optionally relays them to another network segment.
+pub struct WakeOnLanListener {
+    socket: UdpSocket,
+    relay: Option<SocketAddr>,
+    servers: Vec<(DeviceId, MacAddr)>,
+    local_ips: HashSet<IpAddr>,
+    external_wakeups: ExternalWakeupSender,
+    standby: Option<Arc<Standby>>,
+}
+
+impl WakeOnLanListener {
+    pub async fn bind(
+        address: SocketAddr,
+        relay: Option<IpAddr>,
+        servers: &[Server],
+        external_wakeups: ExternalWakeupSender,
+    ) -> std::io::Result<Self> {
+        let socket = UdpSocket::bind(address).await?;
+        if relay.is_some() {
+            socket.set_broadcast(true)?;
+        }
+
+        // magic packets sent by home-monitor-rs itself aren't external wakeups
+        let local_ips = pnet::datalink::interfaces()
+            .into_iter()
+            .flat_map(|iface| iface.ips)
+            .map(|network| network.ip())
+            .collect();
+
+        Ok(Self {
+            socket,
+            relay: relay.map(|ip| SocketAddr::new(ip, WAKE_ON_LAN_PORT)),
+            servers: servers
+                .iter()
+                .map(|server| (server.machine.id.clone(), server.mac))
+                .collect(),
+            local_ips,
+            external_wakeups,
+            standby: None,
+        })
+    }
+
+    /// Only relays packets once this standby instance took over the control of the servers.
+    pub fn set_standby(&mut self, standby: Arc<Standby>) {
+        self.standby = Some(standby);
+    }
+
+    fn should_relay(&self) -> Option<SocketAddr> {
+        match &self.standby {
+            Some(standby) if !standby.is_active() => None,
+            _ => self.relay,
+        }
+    }
+
+    pub async fn serve(&self) {
+        let mut packet = [0; MAX_PACKET_SIZE];
+        loop {
+            let (size, peer) = match self.socket.recv_from(&mut packet).await {
+                Ok(received) => received,
+                Err(e) => {
+                    warn!("failed to receive Wake-on-LAN packet: {}", e);
+                    continue;
+                }
+            };
+
+            let server_id = match self.handle(&packet[..size], peer.ip()) {
+                Some(server_id) => server_id,
+                None => continue,
+            };
+
+            if let Some(relay) = self.should_relay() {
+                match self.socket.send_to(&packet[..size], relay).await {
+                    Ok(_) => debug!("relayed Wake-on-LAN packet for {} to {}", server_id, relay),
+                    Err(e) => warn!(
+                        "failed to relay Wake-on-LAN packet for {} to {}: {}",
+                        server_id, relay, e
+                    ),
+                }
+            }
+        }
+    }
+
+    /// Reports the server the given packet wakes up (if any) as woken up externally.
+    fn handle(&self, packet: &[u8], peer: IpAddr) -> Option<DeviceId> {
+        if self.local_ips.contains(&peer) {
+            return None;
+        }
+
+        let mac = match parse_magic_packet(packet) {
+            Some(mac) => mac,
+            None => {
+                debug!("ignoring invalid Wake-on-LAN packet from {}", peer);
+                return None;
+            }
+        };
+        let server_id = match self
+            .servers
+            .iter()
+            .find(|(_, server_mac)| server_mac.as_bytes() == mac)
+        {
+            Some((server_id, _)) => server_id.clone(),
+            None => {
+                debug!(
+                    "ignoring Wake-on-LAN packet from {} for another device",
+                    peer
+                );
+                return None;
+            }
+        };
+
+        info!("{} is being woken up by {}", server_id, peer);
+        if self.external_wakeups.send(server_id.clone()).is_err() {
+            warn!(
+                "failed to report the external wakeup of {} to the monitoring",
+                server_id
+            );
+        }
+
+        Some(server_id)
+    }
+}
+
+/// Returns the MAC address a magic packet (six bytes of 0xff followed by 16 repetitions of the
+/// MAC address anywhere in the payload) is meant for.
+pub fn parse_magic_packet(packet: &[u8]) -> Option<&[u8]> {
+    let mac_len = 6;
+    let size = SYNC_STREAM.len() + MAC_REPETITIONS * mac_len;
+    if packet.len() < size {
+        return None;
+    }
+
+    (0..=packet.len() - size).find_map(|start| {
+        let (sync_stream, macs) = packet[start..start + size].split_at(SYNC_STREAM.len());
+        let mac = &macs[..mac_len];
+        if sync_stream == SYNC_STREAM && macs.chunks(mac_len).all(|chunk| chunk == mac) {
+            Some(mac)
+        } else {
+            None
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use rstest::*;
+
+    use super::*;
+    use crate::dom::communication::{external_wakeup_channel, ExternalWakeupReceiver};
+    use crate::dom::device::test::*;
+    use crate::utils::{Instant, ManualClock};
+
+    static SERVER_MAC: [u8; 6] = [0xaa, 0xbb, 0xcc, 0xdd, 0xee, 0xff];
+
+    fn magic_packet(mac: &[u8]) -> Vec<u8> {
+        let mut packet = SYNC_STREAM.to_vec();
+        for _ in 0..MAC_REPETITIONS {
+            packet.extend_from_slice(mac);
+        }
+        packet
+    }
+
+    #[fixture]
+    async fn listener(mut server: Server) -> (WakeOnLanListener, ExternalWakeupReceiver) {
+        server.mac = "aa:bb:cc:dd:ee:ff".parse().unwrap();
+        let (tx, rx) = external_wakeup_channel();
+        let listener = WakeOnLanListener::bind("127.0.0.1:0".parse().unwrap(), None, &[server], tx)
+            .await
+            .unwrap();
+        (listener, rx)
+    }
+
+    #[rstest]
+    #[case(magic_packet(&SERVER_MAC), Some(&SERVER_MAC[..]))]
+    #[case([vec![0; 4], magic_packet(&SERVER_MAC), vec![1, 2, 3, 4]].concat(), Some(&SERVER_MAC[..]))]
+    #[case(magic_packet(&SERVER_MAC)[1..].to_vec(), None)]
+    #[case([magic_packet(&SERVER_MAC)[..96].to_vec(), vec![0; 6]].concat(), None)]
+    #[case(Vec::new(), None)]
+    fn test_parse_magic_packet(#[case] packet: Vec<u8>, #[case] expected: Option<&[u8]>) {
+        assert_eq!(parse_magic_packet(&packet), expected);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_wake_on_lan_listener_reports_external_wakeups(
+        #[future] listener: (WakeOnLanListener, ExternalWakeupReceiver),
+    ) {
+        let (listener, mut rx) = listener.await;
+        let peer: IpAddr = "192.0.2.1".parse().unwrap();
+
+        // packets for other devices and invalid packets are ignored
+        assert_eq!(
+            listener.handle(&magic_packet(&[1, 2, 3, 4, 5, 6]), peer),
+            None
+        );
+        assert_eq!(listener.handle(&[0xff; 6], peer), None);
+        assert!(rx.try_recv().is_err());
+
+        let server_id: DeviceId = SERVER_ID.parse().unwrap();
+        assert_eq!(
+            listener.handle(&magic_packet(&SERVER_MAC), peer),
+            Some(server_id.clone())
+        );
+        assert_eq!(rx.try_recv().unwrap(), server_id);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_wake_on_lan_listener_ignores_own_packets(
+        #[future] listener: (WakeOnLanListener, ExternalWakeupReceiver),
+    ) {
+        let (listener, mut rx) = listener.await;
+
+        assert_eq!(
+            listener.handle(&magic_packet(&SERVER_MAC), "127.0.0.1".parse().unwrap()),
+            None
+        );
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_wake_on_lan_listener_only_relays_packets_once_active(server: Server) {
+        let (tx, _rx) = external_wakeup_channel();
+        let relay: IpAddr = "192.0.2.255".parse().unwrap();
+        let mut listener =
+            WakeOnLanListener::bind("127.0.0.1:0".parse().unwrap(), Some(relay), &[server], tx)
+                .await
+                .unwrap();
+        assert!(listener.should_relay().is_some());
+
+        let timeout = Duration::from_secs(30);
+        let clock = Arc::new(ManualClock::new(Instant::now()));
+        let standby = Arc::new(Standby::new(timeout, clock.clone()));
+        listener.set_standby(standby.clone());
+        assert!(listener.should_relay().is_none());
+
+        clock.advance(timeout + Duration::from_secs(1));
+        standby.update(false);
+        assert_eq!(
+            listener.should_relay(),
+            Some(SocketAddr::new(relay, WAKE_ON_LAN_PORT))
+        );
+    }
+}
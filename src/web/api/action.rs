@@ -0,0 +1,230 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use chrono::Utc;
+use rand::rngs::OsRng;
+use rand::RngCore;
+use rocket::get;
+use rocket::serde::json::Json;
+use rocket_okapi::{openapi, JsonSchema};
+use serde::{Deserialize, Serialize};
+
+use crate::dom::DeviceId;
+use crate::networking::{Action, ActionInProgress};
+use crate::web::api::UnknownActionError;
+
+// keeps the results of the most recently finished actions to be polled
+const MAX_FINISHED_ACTIONS: usize = 100;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum ActionState {
+    Running,
+    Succeeded,
+    Failed,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionStatus {
+    pub id: String,
+    pub server: String,
+    pub action: String,
+    pub state: ActionState,
+    pub started: String,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub finished: Option<String>,
+    /// Report of a successful action or error of a failed action.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionAccepted {
+    pub action_id: String,
+}
+
+struct RunningAction {
+    server_id: DeviceId,
+    action: Action,
+    status: ActionStatus,
+}
+
+/// Wakeups and shutdowns requested through the web API which run in the background and can be
+/// polled until they are finished.
+pub struct Actions {
+    actions: Mutex<VecDeque<RunningAction>>,
+}
+
+impl Actions {
+    pub fn new() -> Self {
+        Self {
+            actions: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// Registers a new action of the given server unless the same action is already running.
+    pub fn start(
+        &self,
+        server_id: &DeviceId,
+        server: &str,
+        action: Action,
+    ) -> Result<String, ActionInProgress> {
+        let mut actions = self.actions.lock().unwrap();
+        let is_running = actions.iter().any(|running| {
+            running.server_id == *server_id
+                && running.action == action
+                && running.status.state == ActionState::Running
+        });
+        if is_running {
+            return Err(ActionInProgress {
+                action,
+                server: server.to_string(),
+            });
+        }
+
+        let id = format!("{:016x}", OsRng.next_u64());
+        actions.push_back(RunningAction {
+            server_id: server_id.clone(),
+            action,
+            status: ActionStatus {
+                id: id.clone(),
+                server: server_id.to_string(),
+                action: action.to_string(),
+                state: ActionState::Running,
+                started: Utc::now().to_rfc3339(),
+                finished: None,
+                result: None,
+            },
+        });
+
+        Ok(id)
+    }
+
+    pub fn finish(&self, id: &str, result: Result<String, String>) {
+        let mut actions = self.actions.lock().unwrap();
+        if let Some(running) = actions.iter_mut().find(|running| running.status.id == id) {
+            let (state, result) = match result {
+                Ok(report) => (ActionState::Succeeded, report),
+                Err(e) => (ActionState::Failed, e),
+            };
+            running.status.state = state;
+            running.status.finished = Some(Utc::now().to_rfc3339());
+            running.status.result = Some(result);
+        }
+
+        // forget the oldest finished actions
+        let finished = actions
+            .iter()
+            .filter(|running| running.status.state != ActionState::Running)
+            .count();
+        for _ in MAX_FINISHED_ACTIONS..finished {
+            if let Some(index) = actions
+                .iter()
+                .position(|running| running.status.state != ActionState::Running)
+            {
+                actions.remove(index);
+            }
+        }
+    }
+
+    pub fn get(&self, id: &str) -> Option<ActionStatus> {
+        self.actions
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|running| running.status.id == id)
+            .map(|running| running.status.clone())
+    }
+}
+
+#[openapi(tag = "Server")]
+#[get("/action/<id>")]
+pub fn get_action(
+    id: String,
+    actions: &rocket::State<Arc<Actions>>,
+) -> Result<Json<ActionStatus>, UnknownActionError> {
+    match actions.get(&id) {
+        Some(status) => Ok(Json(status)),
+        None => Err(UnknownActionError::from(id)),
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use rocket::http::Status;
+    use rocket::local::blocking::{Client, LocalResponse};
+    use rstest::*;
+
+    use super::*;
+    use crate::dom::device::test::*;
+    use crate::web::server::test::*;
+
+    /// Polls the action accepted with the given response until it is finished.
+    pub fn wait_for_action(client: &Client, response: LocalResponse) -> ActionStatus {
+        assert_eq!(response.status(), Status::Accepted);
+        let accepted = response.into_json::<ActionAccepted>().unwrap();
+
+        for _ in 0..100 {
+            let status = client
+                .get(get_api_endpoint(&format!("/action/{}", accepted.action_id)))
+                .dispatch()
+                .into_json::<ActionStatus>()
+                .unwrap();
+            if status.state != ActionState::Running {
+                return status;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+
+        panic!("action {} didn't finish", accepted.action_id);
+    }
+
+    #[rstest]
+    fn test_actions_deduplicate_running_action(server_id: DeviceId) {
+        let actions = Actions::new();
+
+        let id = actions
+            .start(&server_id, SERVER_NAME, Action::Wakeup)
+            .unwrap();
+        assert!(actions
+            .start(&server_id, SERVER_NAME, Action::Wakeup)
+            .is_err());
+        assert!(actions
+            .start(&server_id, SERVER_NAME, Action::Shutdown)
+            .is_ok());
+
+        actions.finish(&id, Ok("woke up".to_string()));
+
+        let status = actions.get(&id).unwrap();
+        assert_eq!(status.state, ActionState::Succeeded);
+        assert_eq!(status.result, Some("woke up".to_string()));
+        assert!(actions
+            .start(&server_id, SERVER_NAME, Action::Wakeup)
+            .is_ok());
+    }
+
+    #[rstest]
+    fn test_actions_forget_oldest_finished_actions(server_id: DeviceId) {
+        let actions = Actions::new();
+
+        let ids: Vec<String> = (0..=MAX_FINISHED_ACTIONS)
+            .map(|_| {
+                let id = actions
+                    .start(&server_id, SERVER_NAME, Action::Shutdown)
+                    .unwrap();
+                actions.finish(&id, Err("failed".to_string()));
+                id
+            })
+            .collect();
+
+        assert!(actions.get(&ids[0]).is_none());
+        assert_eq!(
+            actions.get(&ids[1]).map(|status| status.state),
+            Some(ActionState::Failed)
+        );
+    }
+}
@@ -0,0 +1,117 @@
+use std::sync::Arc;
+
+use log::info;
+use rocket::serde::json::Json;
+use rocket::{get, put};
+use rocket_okapi::{openapi, JsonSchema};
+use serde::{Deserialize, Serialize};
+
+use crate::dom::communication::Away;
+
+#[derive(Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AwayStatus {
+    /// Whether only the servers with `keepOnWhenAway` are kept online.
+    away: bool,
+}
+
+impl From<&Away> for AwayStatus {
+    fn from(away: &Away) -> Self {
+        Self {
+            away: away.is_active(),
+        }
+    }
+}
+
+/// Returns whether the away mode is active.
+#[openapi(tag = "General")]
+#[get("/away")]
+pub fn get_away(away: &rocket::State<Arc<Away>>) -> Json<AwayStatus> {
+    Json(AwayStatus::from(away.inner().as_ref()))
+}
+
+/// Activates / deactivates the away mode which shuts down all servers except the ones with
+/// `keepOnWhenAway` (which are kept online) independent of their dependencies.
+#[openapi(tag = "General")]
+#[put("/away", data = "<status>")]
+pub fn put_away(status: Json<AwayStatus>, away: &rocket::State<Arc<Away>>) -> Json<AwayStatus> {
+    let active = status.into_inner().away;
+    if away.set(active) {
+        if active {
+            info!("away mode activated through the web API");
+        } else {
+            info!("away mode deactivated through the web API");
+        }
+    }
+
+    Json(AwayStatus::from(away.inner().as_ref()))
+}
+
+#[cfg(test)]
+mod test {
+    use std::net::IpAddr;
+
+    use rocket::http::{ContentType, Status};
+    use rocket::log::LogLevel;
+    use rstest::*;
+    use serde_json::json;
+
+    use super::*;
+    use crate::configuration::Configuration;
+    use crate::control::test::*;
+    use crate::dom::communication::SharedStateMutex;
+    use crate::dom::test::*;
+    use crate::dom::Dependencies;
+    use crate::web::server::test::*;
+
+    #[rstest]
+    fn test_web_api_can_switch_away_mode(
+        config: Configuration,
+        shared_state: Arc<SharedStateMutex>,
+        mocked_server_control: MockServerControl,
+        dependencies: Dependencies,
+        ip: IpAddr,
+        port: u16,
+        log_level: LogLevel,
+    ) {
+        // SETUP
+        let client = get_client(
+            &config,
+            shared_state,
+            mocked_server_control,
+            dependencies,
+            ip,
+            port,
+            log_level,
+        );
+
+        // TESTING
+        let response = client.get(get_api_endpoint("/away")).dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.content_type(), Some(ContentType::JSON));
+        assert_eq!(
+            response.into_json::<AwayStatus>(),
+            Some(AwayStatus { away: false })
+        );
+
+        let response = client
+            .put(get_api_endpoint("/away"))
+            .json(&json!({ "away": true }))
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(
+            response.into_json::<AwayStatus>(),
+            Some(AwayStatus { away: true })
+        );
+
+        let away = client.rocket().state::<Arc<Away>>().unwrap();
+        assert!(away.is_active());
+
+        let response = client
+            .put(get_api_endpoint("/away"))
+            .json(&json!({ "away": false }))
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert!(!away.is_active());
+    }
+}
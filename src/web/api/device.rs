@@ -0,0 +1,264 @@
+use std::net::IpAddr;
+use std::result::Result;
+use std::sync::Arc;
+use std::time::Duration;
+
+use log::warn;
+use rocket::post;
+use rocket::serde::json::Json;
+use rocket_okapi::{openapi, JsonSchema};
+use serde::{Deserialize, Serialize};
+
+use crate::configuration::{self, Configuration};
+use crate::control::ServerControl;
+use crate::dom::communication::{GuestSender, SharedStateMutex};
+use crate::dom::{self, DeviceId};
+use crate::networking;
+use crate::web::api;
+use crate::web::api::server::UnknownDeviceError;
+use crate::web::api::DeviceExistsError;
+use crate::web::serialization::Device;
+
+#[derive(Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GuestDevice {
+    id: String,
+    name: String,
+    ip: IpAddr,
+    timeout: u64,
+    #[serde(default)]
+    servers: Vec<String>,
+}
+
+#[openapi(tag = "General")]
+#[post("/device?<ttl>", data = "<guest>")]
+pub fn post_device(
+    ttl: u64,
+    guest: Json<GuestDevice>,
+    config: &rocket::State<Configuration>,
+    shared_state: &rocket::State<Arc<SharedStateMutex>>,
+    server_controls: &rocket::State<Vec<ServerControl>>,
+    guest_sender: &rocket::State<GuestSender>,
+) -> Result<Json<Device>, api::Error> {
+    let guest = guest.into_inner();
+    let guest_id: DeviceId = guest.id.parse().unwrap();
+
+    // make sure the guest device doesn't clash with any known device
+    {
+        let config_id: configuration::DeviceId = guest.id.parse().unwrap();
+        let shared_state = shared_state.lock().unwrap();
+        if config.devices.contains_key(&config_id)
+            || config.rooms.contains_key(&config_id)
+            || config.persons.contains_key(&config_id)
+            || shared_state
+                .get_devices()
+                .iter()
+                .any(|device| *device.id() == guest_id || *device.ip() == guest.ip)
+        {
+            return Err(api::Error::from(DeviceExistsError::from(guest_id)));
+        }
+    }
+
+    // make sure all the servers the guest device should keep awake exist
+    let mut servers: Vec<DeviceId> = Vec::new();
+    for server in guest.servers {
+        let server_id: DeviceId = server.parse().unwrap();
+        if !server_controls
+            .iter()
+            .any(|control| control.server.machine.id == server_id)
+        {
+            return Err(api::Error::from(UnknownDeviceError::from(server_id)));
+        }
+        servers.push(server_id);
+    }
+
+    let mut machine = dom::Machine::new(&guest_id, &guest.name, guest.ip, guest.timeout);
+
+    // check the subnet of the guest device like the configured devices on startup
+    match networking::get_network_interface(&config.network.interface) {
+        Ok(interface) => {
+            machine.is_off_subnet = !networking::is_on_subnet(&interface.ips, &machine.ip);
+            if machine.is_off_subnet {
+                warn!(
+                    "guest {} ({}) isn't on a subnet of {}, it can't be probed with ARP requests",
+                    machine.name, machine.ip, interface.name
+                );
+            }
+        }
+        Err(e) => warn!("{}, not checking the subnet of guest {}", e, machine.name),
+    }
+    let device = Device::from(&machine);
+
+    match guest_sender.send(dom::Guest::new(machine, servers, Duration::from_secs(ttl))) {
+        Ok(_) => Ok(Json(device)),
+        Err(e) => Err(api::Error::from(api::InternalServerError::from(
+            anyhow::Error::from(e),
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::net::IpAddr;
+    use std::sync::Arc;
+
+    use rocket::http::{ContentType, Status};
+    use rocket::log::LogLevel;
+    use rstest::*;
+
+    use super::*;
+    use crate::control::test::*;
+    use crate::dom::device::test::*;
+    use crate::dom::test::*;
+    use crate::dom::Dependencies;
+    use crate::web::server::test::*;
+
+    fn guest_device(ip: &str, servers: Vec<String>) -> GuestDevice {
+        GuestDevice {
+            id: "guest".to_string(),
+            name: "Guest".to_string(),
+            ip: ip.parse().unwrap(),
+            timeout: 300,
+            servers,
+        }
+    }
+
+    #[rstest]
+    fn test_web_api_can_post_device(
+        config: Configuration,
+        shared_state: Arc<SharedStateMutex>,
+        mocked_server_control: MockServerControl,
+        dependencies: Dependencies,
+        ip: IpAddr,
+        port: u16,
+        log_level: LogLevel,
+    ) {
+        // SETUP
+        let (guest_sender, mut guest_receiver) = crate::dom::communication::guest_channel();
+
+        // TESTING
+        let client = get_client_with_guest_sender(
+            &config,
+            shared_state,
+            mocked_server_control,
+            dependencies,
+            guest_sender,
+            ip,
+            port,
+            log_level,
+        );
+
+        let response = client
+            .post(get_api_endpoint("/device?ttl=86400"))
+            .json(&guest_device("10.0.0.99", vec![SERVER_ID.to_string()]))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.content_type(), Some(ContentType::JSON));
+
+        let guest = guest_receiver.try_recv().unwrap();
+        assert_eq!(guest.machine.id, "guest".parse().unwrap());
+        assert_eq!(guest.servers, vec![SERVER_ID.parse().unwrap()]);
+        assert_eq!(guest.ttl, Duration::from_secs(86400));
+    }
+
+    #[rstest]
+    fn test_web_api_post_device_fails_for_existing_ip(
+        config: Configuration,
+        shared_state: Arc<SharedStateMutex>,
+        mocked_server_control: MockServerControl,
+        dependencies: Dependencies,
+        machine_ip: IpAddr,
+        ip: IpAddr,
+        port: u16,
+        log_level: LogLevel,
+    ) {
+        // TESTING
+        let client = get_client(
+            &config,
+            shared_state,
+            mocked_server_control,
+            dependencies,
+            ip,
+            port,
+            log_level,
+        );
+
+        let response = client
+            .post(get_api_endpoint("/device?ttl=86400"))
+            .json(&guest_device(&machine_ip.to_string(), vec![]))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Conflict);
+    }
+
+    #[rstest]
+    fn test_web_api_post_device_fails_for_unknown_server(
+        config: Configuration,
+        shared_state: Arc<SharedStateMutex>,
+        mocked_server_control: MockServerControl,
+        dependencies: Dependencies,
+        ip: IpAddr,
+        port: u16,
+        log_level: LogLevel,
+    ) {
+        // TESTING
+        let client = get_client(
+            &config,
+            shared_state,
+            mocked_server_control,
+            dependencies,
+            ip,
+            port,
+            log_level,
+        );
+
+        let response = client
+            .post(get_api_endpoint("/device?ttl=86400"))
+            .json(&guest_device("10.0.0.99", vec!["unknown".to_string()]))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::NotFound);
+    }
+
+    #[rstest]
+    #[case("127.0.0.99", false)]
+    #[case("10.0.0.99", true)]
+    fn test_web_api_post_device_checks_subnet(
+        #[case] guest_ip: &str,
+        #[case] is_off_subnet: bool,
+        mut config: Configuration,
+        shared_state: Arc<SharedStateMutex>,
+        mocked_server_control: MockServerControl,
+        dependencies: Dependencies,
+        ip: IpAddr,
+        port: u16,
+        log_level: LogLevel,
+    ) {
+        // SETUP
+        config.network.interface = "lo".to_string();
+        let (guest_sender, mut guest_receiver) = crate::dom::communication::guest_channel();
+
+        // TESTING
+        let client = get_client_with_guest_sender(
+            &config,
+            shared_state,
+            mocked_server_control,
+            dependencies,
+            guest_sender,
+            ip,
+            port,
+            log_level,
+        );
+
+        let response = client
+            .post(get_api_endpoint("/device?ttl=86400"))
+            .json(&guest_device(guest_ip, vec![]))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+
+        let guest = guest_receiver.try_recv().unwrap();
+        assert_eq!(guest.machine.is_off_subnet, is_off_subnet);
+    }
+}
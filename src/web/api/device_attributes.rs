@@ -0,0 +1,172 @@
+use std::collections::BTreeMap;
+use std::result::Result;
+use std::sync::Arc;
+
+use rocket::patch;
+use rocket::serde::json::Json;
+use rocket_okapi::openapi;
+
+use crate::configuration::Configuration;
+use crate::dom::communication::SharedStateMutex;
+use crate::dom::{Attributes, DeviceId};
+use crate::utils::{AttributesFile, DeviceAttributes};
+use crate::web::api;
+use crate::web::api::server::UnknownDeviceError;
+
+/// Sets (or removes if `null`) the given custom attributes of a device and returns all of its
+/// attributes. The attributes are kept in the files API root directory (if configured).
+#[openapi(tag = "General")]
+#[patch("/device/<device>/attributes", data = "<attributes>")]
+pub fn patch_device_attributes(
+    device: String,
+    attributes: Json<BTreeMap<String, Option<String>>>,
+    config: &rocket::State<Configuration>,
+    shared_state: &rocket::State<Arc<SharedStateMutex>>,
+    files_api: &rocket::State<api::FilesApiStatus>,
+) -> Result<Json<Attributes>, api::Error> {
+    let device_id: DeviceId = device.parse().unwrap();
+
+    let mut shared_state = shared_state.lock().unwrap();
+    if !shared_state
+        .get_devices()
+        .iter()
+        .any(|device| *device.id() == device_id)
+    {
+        return Err(api::Error::from(UnknownDeviceError::from(device_id)));
+    }
+
+    let mut device_attributes = shared_state
+        .get_attributes(&device_id)
+        .cloned()
+        .unwrap_or_default();
+    for (key, value) in attributes.into_inner() {
+        match value {
+            Some(value) => device_attributes.insert(key, value),
+            None => device_attributes.remove(&key),
+        };
+    }
+
+    // only apply the attributes once they have been persisted
+    if let Some(files) = &config.api.files {
+        let mut all_attributes: DeviceAttributes = shared_state
+            .get_all_attributes()
+            .iter()
+            .filter(|(id, _)| **id != device_id)
+            .map(|(id, attributes)| (id.to_string(), attributes.clone()))
+            .collect();
+        if !device_attributes.is_empty() {
+            all_attributes.insert(device_id.to_string(), device_attributes.clone());
+        }
+        files_api.record(AttributesFile::new(&files.root).save(&all_attributes))?;
+    }
+
+    shared_state.set_attributes(&device_id, device_attributes.clone());
+    Ok(Json(device_attributes))
+}
+
+#[cfg(test)]
+mod test {
+    use std::net::IpAddr;
+    use std::sync::Arc;
+
+    use rocket::http::{ContentType, Status};
+    use rocket::log::LogLevel;
+    use rstest::*;
+    use serde_json::json;
+    use temp_dir::TempDir;
+
+    use super::*;
+    use crate::configuration::Files;
+    use crate::control::test::*;
+    use crate::dom::device::test::*;
+    use crate::dom::test::*;
+    use crate::dom::Dependencies;
+    use crate::web::serialization;
+    use crate::web::server::test::*;
+
+    #[rstest]
+    fn test_web_api_can_patch_device_attributes(
+        mut config: Configuration,
+        shared_state: Arc<SharedStateMutex>,
+        mocked_server_control: MockServerControl,
+        dependencies: Dependencies,
+        ip: IpAddr,
+        port: u16,
+        log_level: LogLevel,
+    ) {
+        // SETUP
+        let root = TempDir::new().unwrap();
+        config.api.files = Some(Files {
+            root: root.path().to_path_buf(),
+        });
+
+        let client = get_client(
+            &config,
+            shared_state,
+            mocked_server_control,
+            dependencies,
+            ip,
+            port,
+            log_level,
+        );
+        let endpoint = get_api_endpoint(&format!("/device/{MACHINE_ID}/attributes"));
+
+        // TESTING
+        let response = client
+            .patch(&endpoint)
+            .json(&json!({ "backup_last_ok": "2024-01-01", "owner": "me" }))
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.content_type(), Some(ContentType::JSON));
+
+        // null removes an attribute
+        let response = client
+            .patch(&endpoint)
+            .json(&json!({ "owner": null }))
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        let expected = Attributes::from([("backup_last_ok".to_string(), "2024-01-01".to_string())]);
+        assert_eq!(response.into_json::<Attributes>().unwrap(), expected);
+
+        // the attributes are persisted
+        assert_eq!(
+            AttributesFile::new(root.path()).load().unwrap(),
+            DeviceAttributes::from([(MACHINE_ID.to_string(), expected.clone())])
+        );
+
+        // and echoed in the status
+        let response = client.get(get_api_endpoint("/status")).dispatch();
+        let status: serde_json::Value = response.into_json().unwrap();
+        let machine: serialization::Device =
+            serde_json::from_value(status["devices"][0].clone()).unwrap();
+        assert_eq!(machine.attributes, expected);
+    }
+
+    #[rstest]
+    fn test_web_api_patch_device_attributes_fails_for_unknown_device(
+        config: Configuration,
+        shared_state: Arc<SharedStateMutex>,
+        mocked_server_control: MockServerControl,
+        dependencies: Dependencies,
+        ip: IpAddr,
+        port: u16,
+        log_level: LogLevel,
+    ) {
+        let client = get_client(
+            &config,
+            shared_state,
+            mocked_server_control,
+            dependencies,
+            ip,
+            port,
+            log_level,
+        );
+
+        let response = client
+            .patch(get_api_endpoint("/device/unknown/attributes"))
+            .json(&json!({ "key": "value" }))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::NotFound);
+    }
+}
@@ -0,0 +1,256 @@
+use std::result::Result;
+use std::sync::Arc;
+
+use log::info;
+use rocket::get;
+use rocket::serde::json::Json;
+use rocket_okapi::{openapi, JsonSchema};
+use serde::{Deserialize, Serialize};
+
+use crate::dom::communication::SharedStateMutex;
+use crate::dom::{self, DeviceId};
+use crate::networking::{self, LatencyProber};
+use crate::web::api;
+use crate::web::api::server::UnknownDeviceError;
+
+#[derive(Debug, PartialEq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PingStatistics {
+    pub transmitted: u32,
+    pub received: u32,
+    /// Round trip times in milliseconds (only if any ping has been answered).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_ms: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub avg_ms: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_ms: Option<f64>,
+}
+
+#[derive(Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PortLatency {
+    pub port: u16,
+    /// Milliseconds it took to connect to the port (missing if it couldn't be connected to).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub connect_ms: Option<u64>,
+}
+
+#[derive(Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Traceroute {
+    pub hops: u32,
+    /// The device answered (otherwise `hops` is the number of tried hops).
+    pub reached: bool,
+}
+
+#[derive(Debug, PartialEq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceDiagnostics {
+    /// Statistics of a burst of pings (missing if the `ping` command couldn't be run).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ping: Option<PingStatistics>,
+    pub ports: Vec<PortLatency>,
+    /// Number of hops to the device (missing if the `traceroute` command couldn't be run).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub traceroute: Option<Traceroute>,
+}
+
+impl From<networking::LatencyReport> for DeviceDiagnostics {
+    fn from(report: networking::LatencyReport) -> Self {
+        Self {
+            ping: report.ping.map(|ping| PingStatistics {
+                transmitted: ping.transmitted,
+                received: ping.received,
+                min_ms: ping.min_ms,
+                avg_ms: ping.avg_ms,
+                max_ms: ping.max_ms,
+            }),
+            ports: report
+                .ports
+                .into_iter()
+                .map(|port| PortLatency {
+                    port: port.port,
+                    connect_ms: port
+                        .connect_time
+                        .map(|connect_time| connect_time.as_millis() as u64),
+                })
+                .collect(),
+            traceroute: report.traceroute.map(|traceroute| Traceroute {
+                hops: traceroute.hops,
+                reached: traceroute.reached,
+            }),
+        }
+    }
+}
+
+/// Pings the given device several times, connects to its TCP ports (its configured `port` and
+/// the SSH port of servers) and traces the route to it to tell why it seems to be flaky.
+#[openapi(tag = "General")]
+#[get("/device/<device>/diagnostics")]
+pub async fn get_device_diagnostics(
+    device: String,
+    shared_state: &rocket::State<Arc<SharedStateMutex>>,
+    latency_prober: &rocket::State<Arc<dyn LatencyProber>>,
+) -> Result<Json<DeviceDiagnostics>, api::Error> {
+    let device_id: DeviceId = device.parse().unwrap();
+    let (ip, ports) = {
+        let shared_state = shared_state.lock().unwrap();
+        match shared_state
+            .get_devices()
+            .iter()
+            .find(|device| *device.id() == device_id)
+        {
+            Some(dom::Device::Server(server)) => {
+                let mut ports: Vec<u16> = server.machine.port.into_iter().collect();
+                ports.push(u16::from(server.ssh.port));
+                ports.dedup();
+                (server.machine.ip, ports)
+            }
+            Some(dom::Device::Machine(machine)) => (machine.ip, machine.port.into_iter().collect()),
+            None => return Err(api::Error::from(UnknownDeviceError::from(device_id))),
+        }
+    };
+
+    info!("diagnosing the latency of {}", device_id);
+    let latency_prober = latency_prober.inner().clone();
+    match tokio::task::spawn_blocking(move || latency_prober.probe(ip, &ports)).await {
+        Ok(report) => Ok(Json(DeviceDiagnostics::from(report))),
+        Err(e) => Err(api::Error::from(api::InternalServerError::from(
+            anyhow::Error::from(e),
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::net::IpAddr;
+    use std::time::Duration;
+
+    use mockall::predicate::*;
+    use rocket::http::{ContentType, Status};
+    use rocket::log::LogLevel;
+    use rstest::*;
+
+    use super::*;
+    use crate::configuration::Configuration;
+    use crate::control::test::*;
+    use crate::dom::device::test::*;
+    use crate::dom::test::*;
+    use crate::dom::Dependencies;
+    use crate::networking::MockLatencyProber;
+    use crate::web::server::test::*;
+
+    #[rstest]
+    fn test_web_api_diagnoses_latency_of_server(
+        config: Configuration,
+        shared_state: Arc<SharedStateMutex>,
+        mocked_server_control: MockServerControl,
+        dependencies: Dependencies,
+        ip: IpAddr,
+        port: u16,
+        log_level: LogLevel,
+    ) {
+        // SETUP
+        let server_ip: IpAddr = SERVER_IP.parse().unwrap();
+        let ssh_port = u16::from(SERVER_SSH_PORT);
+        let mut latency_prober = MockLatencyProber::new();
+
+        // EXPECTATIONS
+        latency_prober
+            .expect_probe()
+            .with(
+                eq(server_ip),
+                function(move |ports: &[u16]| ports == [ssh_port]),
+            )
+            .once()
+            .returning(|_, ports| networking::LatencyReport {
+                ping: Some(networking::PingStatistics {
+                    transmitted: 5,
+                    received: 4,
+                    min_ms: Some(0.5),
+                    avg_ms: Some(1.5),
+                    max_ms: Some(3.0),
+                }),
+                ports: vec![networking::PortLatency {
+                    port: ports[0],
+                    connect_time: Some(Duration::from_millis(12)),
+                }],
+                traceroute: Some(networking::Traceroute {
+                    hops: 1,
+                    reached: true,
+                }),
+            });
+
+        // TESTING
+        let client = get_client_with_latency_prober(
+            &config,
+            shared_state,
+            mocked_server_control,
+            dependencies,
+            latency_prober,
+            ip,
+            port,
+            log_level,
+        );
+
+        let response = client
+            .get(get_api_endpoint(&format!(
+                "/device/{SERVER_ID}/diagnostics"
+            )))
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.content_type(), Some(ContentType::JSON));
+
+        let diagnostics: DeviceDiagnostics = response.into_json().unwrap();
+        assert_eq!(diagnostics.ping.unwrap().received, 4);
+        assert_eq!(
+            diagnostics.ports,
+            vec![PortLatency {
+                port: ssh_port,
+                connect_ms: Some(12)
+            }]
+        );
+        assert_eq!(
+            diagnostics.traceroute,
+            Some(Traceroute {
+                hops: 1,
+                reached: true
+            })
+        );
+    }
+
+    #[rstest]
+    fn test_web_api_diagnostics_fail_for_unknown_device(
+        config: Configuration,
+        shared_state: Arc<SharedStateMutex>,
+        mocked_server_control: MockServerControl,
+        dependencies: Dependencies,
+        ip: IpAddr,
+        port: u16,
+        log_level: LogLevel,
+    ) {
+        // SETUP
+        let mut latency_prober = MockLatencyProber::new();
+
+        // EXPECTATIONS
+        latency_prober.expect_probe().never();
+
+        // TESTING
+        let client = get_client_with_latency_prober(
+            &config,
+            shared_state,
+            mocked_server_control,
+            dependencies,
+            latency_prober,
+            ip,
+            port,
+            log_level,
+        );
+
+        let response = client
+            .get(get_api_endpoint("/device/unknown/diagnostics"))
+            .dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+    }
+}
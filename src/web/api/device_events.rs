@@ -0,0 +1,180 @@
+use std::sync::Arc;
+
+use log::warn;
+use rocket::futures::stream::{self, BoxStream, StreamExt};
+use rocket::get;
+use rocket::response::stream::{Event, EventStream};
+use rocket::tokio::select;
+use rocket::tokio::sync::broadcast::error::RecvError;
+use rocket::Shutdown;
+use rocket_okapi::openapi;
+
+use crate::dom::communication::{DeviceEvents, DeviceUpdate, SharedStateMutex};
+use crate::dom::DeviceId;
+use crate::web::api::server::UnknownDeviceError;
+use crate::web::serialization::Device;
+
+type DeviceEventStream = EventStream<BoxStream<'static, Event>>;
+
+fn changed_event(device: &Device) -> Event {
+    Event::json(device).event("changed")
+}
+
+#[openapi(tag = "General")]
+#[get("/device/<device>/events")]
+pub fn get_device_events(
+    device: String,
+    shared_state: &rocket::State<Arc<SharedStateMutex>>,
+    device_events: &rocket::State<DeviceEvents>,
+    shutdown: Shutdown,
+) -> Result<DeviceEventStream, UnknownDeviceError> {
+    let device_id: DeviceId = device.parse().unwrap();
+
+    // subscribe before getting the current state to not miss any update
+    let receiver = device_events.subscribe();
+    let current_device = shared_state
+        .lock()
+        .unwrap()
+        .get_devices()
+        .iter()
+        .find(|device| *device.id() == device_id)
+        .map(Device::from)
+        .ok_or_else(|| UnknownDeviceError::from(&device_id))?;
+
+    // only forward the updates of the requested device until it is removed
+    let updates = stream::unfold(Some((receiver, shutdown)), move |state| {
+        let device_id = device_id.clone();
+        async move {
+            let (mut receiver, mut shutdown) = state?;
+            loop {
+                let update = select! {
+                    update = receiver.recv() => update,
+                    _ = &mut shutdown => return None,
+                };
+
+                match update {
+                    Ok(DeviceUpdate::Changed(device)) if *device.id() == device_id => {
+                        let event = changed_event(&Device::from(&*device));
+                        return Some((event, Some((receiver, shutdown))));
+                    }
+                    Ok(DeviceUpdate::Removed(removed_id)) if removed_id == device_id => {
+                        let event = Event::data(device_id.to_string()).event("removed");
+                        return Some((event, None));
+                    }
+                    Ok(_) => {}
+                    Err(RecvError::Lagged(missed)) => {
+                        warn!("missed {} device updates for {}", missed, device_id);
+                    }
+                    Err(RecvError::Closed) => return None,
+                }
+            }
+        }
+    });
+
+    // start with the current state of the device
+    let events = stream::once(async move { changed_event(&current_device) })
+        .chain(updates)
+        .boxed();
+
+    Ok(EventStream::from(events))
+}
+
+#[cfg(test)]
+mod test {
+    use std::net::IpAddr;
+
+    use rocket::http::{ContentType, Status};
+    use rocket::log::LogLevel;
+    use rstest::*;
+
+    use super::*;
+    use crate::configuration::Configuration;
+    use crate::control::test::*;
+    use crate::dom::communication::device_events;
+    use crate::dom::device::test::*;
+    use crate::dom::test::*;
+    use crate::dom::{self, Dependencies};
+    use crate::web::server::test::*;
+
+    #[rstest]
+    fn test_web_api_streams_updates_of_a_single_device(
+        config: Configuration,
+        shared_state: Arc<SharedStateMutex>,
+        mocked_server_control: MockServerControl,
+        dependencies: Dependencies,
+        ip: IpAddr,
+        port: u16,
+        log_level: LogLevel,
+        server: dom::Server,
+        mut machine: dom::Machine,
+    ) {
+        // SETUP
+        let events = device_events();
+        let client = get_client_with_device_events(
+            &config,
+            shared_state,
+            mocked_server_control,
+            dependencies,
+            events.clone(),
+            ip,
+            port,
+            log_level,
+        );
+
+        // TESTING
+        let response = client
+            .get(get_api_endpoint(&format!("/device/{MACHINE_ID}/events")))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.content_type(), Some(ContentType::EventStream));
+
+        // updates of other devices are filtered out and the stream ends once the device is removed
+        machine.set_online(true);
+        events
+            .send(DeviceUpdate::Changed(Arc::new(dom::Device::Server(server))))
+            .unwrap();
+        events
+            .send(DeviceUpdate::Changed(Arc::new(dom::Device::Machine(
+                machine,
+            ))))
+            .unwrap();
+        events.send(DeviceUpdate::Removed(machine_id())).unwrap();
+
+        let body = response.into_string().unwrap();
+        assert_eq!(body.matches("event:changed").count(), 2);
+        assert!(body.contains(r#""isOnline":false"#));
+        assert!(body.contains(r#""isOnline":true"#));
+        assert!(!body.contains(SERVER_NAME));
+        assert!(body.contains(&format!("event:removed\ndata:{MACHINE_ID}")));
+    }
+
+    #[rstest]
+    fn test_web_api_cannot_stream_updates_of_unknown_device(
+        config: Configuration,
+        shared_state: Arc<SharedStateMutex>,
+        mocked_server_control: MockServerControl,
+        dependencies: Dependencies,
+        ip: IpAddr,
+        port: u16,
+        log_level: LogLevel,
+    ) {
+        // SETUP
+        let client = get_client(
+            &config,
+            shared_state,
+            mocked_server_control,
+            dependencies,
+            ip,
+            port,
+            log_level,
+        );
+
+        // TESTING
+        let response = client
+            .get(get_api_endpoint("/device/invaliddeviceid/events"))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::NotFound);
+    }
+}
@@ -0,0 +1,62 @@
+use std::fmt;
+use std::io::Cursor;
+
+use rocket::response::Responder;
+use rocket::{http, response, Request, Response};
+use rocket_okapi::gen::OpenApiGenerator;
+use rocket_okapi::okapi::openapi3::Responses;
+use rocket_okapi::response::OpenApiResponderInner;
+
+use crate::dom::DeviceId;
+
+#[derive(Debug)]
+pub struct DeviceExistsError(DeviceId);
+
+impl std::error::Error for DeviceExistsError {}
+
+impl From<DeviceId> for DeviceExistsError {
+    fn from(device_id: DeviceId) -> Self {
+        Self(device_id)
+    }
+}
+
+impl fmt::Display for DeviceExistsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[DeviceExistsError] {}", self.0)
+    }
+}
+
+impl<'r, 'o: 'r> Responder<'r, 'o> for DeviceExistsError {
+    fn respond_to(self, _: &Request) -> response::Result<'o> {
+        let error_msg = self.to_string();
+        Response::build()
+            .header(http::ContentType::Plain)
+            .status(http::Status::Conflict)
+            .sized_body(error_msg.len(), Cursor::new(error_msg))
+            .ok()
+    }
+}
+
+impl OpenApiResponderInner for DeviceExistsError {
+    fn responses(_: &mut OpenApiGenerator) -> rocket_okapi::Result<Responses> {
+        let mut responses = Responses::default();
+        add_409_error(&mut responses);
+        Ok(responses)
+    }
+}
+
+fn add_409_error(responses: &mut Responses) {
+    responses
+        .responses
+        .entry("409".to_owned())
+        .or_insert_with(|| {
+            let response = rocket_okapi::okapi::openapi3::Response {
+                description: "\
+                    [409 Conflict](https://developer.mozilla.org/en-US/docs/Web/HTTP/Status/409)\n\n\
+                    This response is given when a device with the same ID or IP address already exists.\
+                    ".to_owned(),
+                ..Default::default()
+            };
+            response.into()
+        });
+}
@@ -0,0 +1,185 @@
+use std::result::Result;
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use rocket::serde::json::Json;
+use rocket::{delete, put};
+use rocket_okapi::{openapi, JsonSchema};
+use serde::{Deserialize, Serialize};
+
+use crate::dom::communication::{self, IgnoreWindows, SharedStateMutex};
+use crate::dom::DeviceId;
+use crate::web::api;
+use crate::web::api::server::UnknownDeviceError;
+
+#[derive(Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct IgnoreWindow {
+    /// Start of the window in RFC 3339 format (defaults to now).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(with = "Option<String>")]
+    from: Option<DateTime<Utc>>,
+    /// End of the window in RFC 3339 format.
+    #[schemars(with = "String")]
+    until: DateTime<Utc>,
+}
+
+fn check_device(
+    device_id: &DeviceId,
+    shared_state: &rocket::State<Arc<SharedStateMutex>>,
+) -> Result<(), api::Error> {
+    if shared_state
+        .lock()
+        .unwrap()
+        .get_devices()
+        .iter()
+        .any(|device| device.id() == device_id)
+    {
+        Ok(())
+    } else {
+        Err(api::Error::from(UnknownDeviceError::from(
+            device_id.clone(),
+        )))
+    }
+}
+
+/// Ignores the given device being offline during a one-off window (e.g. planned maintenance)
+/// which replaces any previous one-off window of the device.
+#[openapi(tag = "General")]
+#[put("/device/<device>/ignore", data = "<window>")]
+pub fn put_device_ignore(
+    device: String,
+    window: Json<IgnoreWindow>,
+    shared_state: &rocket::State<Arc<SharedStateMutex>>,
+    ignore_windows: &rocket::State<Arc<IgnoreWindows>>,
+) -> Result<Json<IgnoreWindow>, api::Error> {
+    let device_id: DeviceId = device.parse().unwrap();
+    check_device(&device_id, shared_state)?;
+
+    let window = window.into_inner();
+    let from = window.from.unwrap_or_else(Utc::now);
+    ignore_windows.set(
+        &device_id,
+        communication::IgnoreWindow {
+            from,
+            until: window.until,
+        },
+    );
+
+    Ok(Json(IgnoreWindow {
+        from: Some(from),
+        until: window.until,
+    }))
+}
+
+/// Removes the one-off window of the given device. Configured ignore windows are not affected.
+#[openapi(tag = "General")]
+#[delete("/device/<device>/ignore")]
+pub fn delete_device_ignore(
+    device: String,
+    shared_state: &rocket::State<Arc<SharedStateMutex>>,
+    ignore_windows: &rocket::State<Arc<IgnoreWindows>>,
+) -> Result<(), api::Error> {
+    let device_id: DeviceId = device.parse().unwrap();
+    check_device(&device_id, shared_state)?;
+
+    ignore_windows.remove(&device_id);
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::net::IpAddr;
+
+    use chrono::Duration;
+    use rocket::http::{ContentType, Status};
+    use rocket::log::LogLevel;
+    use rstest::*;
+    use serde_json::json;
+
+    use super::*;
+    use crate::configuration::Configuration;
+    use crate::control::test::*;
+    use crate::dom::device::test::*;
+    use crate::dom::test::*;
+    use crate::dom::Dependencies;
+    use crate::web::server::test::*;
+
+    #[rstest]
+    fn test_web_api_can_put_and_delete_device_ignore(
+        config: Configuration,
+        shared_state: Arc<SharedStateMutex>,
+        mocked_server_control: MockServerControl,
+        dependencies: Dependencies,
+        ip: IpAddr,
+        port: u16,
+        log_level: LogLevel,
+    ) {
+        // SETUP
+        let client = get_client(
+            &config,
+            shared_state,
+            mocked_server_control,
+            dependencies,
+            ip,
+            port,
+            log_level,
+        );
+        let endpoint = get_api_endpoint(&format!("/device/{MACHINE_ID}/ignore"));
+        let until = Utc::now() + Duration::hours(1);
+
+        // TESTING
+        let response = client
+            .put(&endpoint)
+            .json(&json!({ "until": until.to_rfc3339() }))
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.content_type(), Some(ContentType::JSON));
+
+        let window: IgnoreWindow = response.into_json().unwrap();
+        assert_eq!(window.until, until);
+        assert!(window.from.unwrap() <= Utc::now());
+
+        let ignore_windows = client.rocket().state::<Arc<IgnoreWindows>>().unwrap();
+        let machine_id: DeviceId = MACHINE_ID.parse().unwrap();
+        assert!(ignore_windows.is_active(&machine_id, &Utc::now()));
+
+        let response = client.delete(&endpoint).dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert!(!ignore_windows.is_active(&machine_id, &Utc::now()));
+    }
+
+    #[rstest]
+    fn test_web_api_put_device_ignore_fails_for_unknown_device(
+        config: Configuration,
+        shared_state: Arc<SharedStateMutex>,
+        mocked_server_control: MockServerControl,
+        dependencies: Dependencies,
+        ip: IpAddr,
+        port: u16,
+        log_level: LogLevel,
+    ) {
+        // SETUP
+        let client = get_client(
+            &config,
+            shared_state,
+            mocked_server_control,
+            dependencies,
+            ip,
+            port,
+            log_level,
+        );
+
+        // TESTING
+        let response = client
+            .put(get_api_endpoint("/device/unknown/ignore"))
+            .json(&json!({ "until": Utc::now().to_rfc3339() }))
+            .dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+
+        let response = client
+            .delete(get_api_endpoint("/device/unknown/ignore"))
+            .dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+    }
+}
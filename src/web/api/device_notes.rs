@@ -0,0 +1,228 @@
+use std::result::Result;
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use rocket::serde::json::Json;
+use rocket::{get, post};
+use rocket_okapi::{openapi, JsonSchema};
+use serde::{Deserialize, Serialize};
+
+use crate::configuration::Configuration;
+use crate::dom::communication::SharedStateMutex;
+use crate::dom::{self, DeviceId};
+use crate::utils::{DeviceNotes, NotesFile};
+use crate::web::api;
+use crate::web::api::server::UnknownDeviceError;
+use crate::web::serialization::Note;
+
+#[derive(Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NewNote {
+    pub text: String,
+    /// Whether the note describes an incident (e.g. an outage).
+    #[serde(default)]
+    pub is_incident: bool,
+    /// Date of the note in RFC 3339 format (defaults to now).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(with = "Option<String>")]
+    pub date: Option<DateTime<Utc>>,
+}
+
+fn check_device(device_id: &DeviceId, devices: &[dom::Device]) -> Result<(), api::Error> {
+    if devices.iter().any(|device| device.id() == device_id) {
+        Ok(())
+    } else {
+        Err(api::Error::from(UnknownDeviceError::from(
+            device_id.clone(),
+        )))
+    }
+}
+
+/// Returns all notes of the given device (oldest first).
+#[openapi(tag = "General")]
+#[get("/device/<device>/notes")]
+pub fn get_device_notes(
+    device: String,
+    shared_state: &rocket::State<Arc<SharedStateMutex>>,
+) -> Result<Json<Vec<Note>>, api::Error> {
+    let device_id: DeviceId = device.parse().unwrap();
+
+    let shared_state = shared_state.lock().unwrap();
+    check_device(&device_id, shared_state.get_devices())?;
+
+    Ok(Json(
+        shared_state
+            .get_notes(&device_id)
+            .map(|notes| notes.iter().map(Note::from).collect())
+            .unwrap_or_default(),
+    ))
+}
+
+/// Attaches a note or incident (e.g. "PSU replaced") to the given device and returns all of its
+/// notes. The notes are kept in the files API root directory (if configured).
+#[openapi(tag = "General")]
+#[post("/device/<device>/notes", data = "<note>")]
+pub fn post_device_note(
+    device: String,
+    note: Json<NewNote>,
+    config: &rocket::State<Configuration>,
+    shared_state: &rocket::State<Arc<SharedStateMutex>>,
+    files_api: &rocket::State<api::FilesApiStatus>,
+) -> Result<Json<Vec<Note>>, api::Error> {
+    let device_id: DeviceId = device.parse().unwrap();
+
+    let mut shared_state = shared_state.lock().unwrap();
+    check_device(&device_id, shared_state.get_devices())?;
+
+    let note = note.into_inner();
+    let mut device_notes = shared_state
+        .get_notes(&device_id)
+        .cloned()
+        .unwrap_or_default();
+    device_notes.push(dom::Note {
+        date: note.date.unwrap_or_else(Utc::now),
+        text: note.text,
+        is_incident: note.is_incident,
+    });
+    device_notes.sort_by_key(|note| note.date);
+
+    // only attach the note once it has been persisted
+    if let Some(files) = &config.api.files {
+        let mut all_notes: DeviceNotes = shared_state
+            .get_all_notes()
+            .iter()
+            .map(|(id, notes)| (id.to_string(), notes.clone()))
+            .collect();
+        all_notes.insert(device_id.to_string(), device_notes.clone());
+        files_api.record(NotesFile::new(&files.root).save(&all_notes))?;
+    }
+
+    let notes = device_notes.iter().map(Note::from).collect();
+    shared_state.set_notes(&device_id, device_notes);
+    Ok(Json(notes))
+}
+
+#[cfg(test)]
+mod test {
+    use std::net::IpAddr;
+
+    use chrono::TimeZone;
+    use rocket::http::{ContentType, Status};
+    use rocket::log::LogLevel;
+    use rstest::*;
+    use serde_json::json;
+    use temp_dir::TempDir;
+
+    use super::*;
+    use crate::configuration::Files;
+    use crate::control::test::*;
+    use crate::dom::device::test::*;
+    use crate::dom::test::*;
+    use crate::dom::Dependencies;
+    use crate::web::serialization;
+    use crate::web::server::test::*;
+
+    #[rstest]
+    fn test_web_api_can_post_device_notes(
+        mut config: Configuration,
+        shared_state: Arc<SharedStateMutex>,
+        mocked_server_control: MockServerControl,
+        dependencies: Dependencies,
+        ip: IpAddr,
+        port: u16,
+        log_level: LogLevel,
+    ) {
+        // SETUP
+        let root = TempDir::new().unwrap();
+        config.api.files = Some(Files {
+            root: root.path().to_path_buf(),
+        });
+
+        let client = get_client(
+            &config,
+            shared_state,
+            mocked_server_control,
+            dependencies,
+            ip,
+            port,
+            log_level,
+        );
+        let endpoint = get_api_endpoint(&format!("/device/{MACHINE_ID}/notes"));
+        let date = Utc.with_ymd_and_hms(2024, 3, 2, 10, 0, 0).unwrap();
+
+        // TESTING
+        let response = client
+            .post(&endpoint)
+            .json(&json!({ "text": "power outage", "isIncident": true }))
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.content_type(), Some(ContentType::JSON));
+
+        // older notes are sorted before newer ones
+        let response = client
+            .post(&endpoint)
+            .json(&json!({ "text": "PSU replaced", "date": date.to_rfc3339() }))
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        let notes: Vec<Note> = response.into_json().unwrap();
+        assert_eq!(notes.len(), 2);
+        assert_eq!(
+            notes[0],
+            Note {
+                date: date.to_string(),
+                text: "PSU replaced".to_string(),
+                is_incident: false,
+            }
+        );
+        assert_eq!(notes[1].text, "power outage");
+        assert!(notes[1].is_incident);
+
+        let response = client.get(&endpoint).dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.into_json::<Vec<Note>>().unwrap(), notes);
+
+        // the notes are persisted
+        let persisted = NotesFile::new(root.path()).load().unwrap();
+        assert_eq!(persisted[MACHINE_ID].len(), 2);
+        assert_eq!(persisted[MACHINE_ID][0].date, date);
+
+        // and included in the status
+        let response = client.get(get_api_endpoint("/status")).dispatch();
+        let status: serde_json::Value = response.into_json().unwrap();
+        let machine: serialization::Device =
+            serde_json::from_value(status["devices"][0].clone()).unwrap();
+        assert_eq!(machine.notes, notes);
+    }
+
+    #[rstest]
+    fn test_web_api_device_notes_fail_for_unknown_device(
+        config: Configuration,
+        shared_state: Arc<SharedStateMutex>,
+        mocked_server_control: MockServerControl,
+        dependencies: Dependencies,
+        ip: IpAddr,
+        port: u16,
+        log_level: LogLevel,
+    ) {
+        let client = get_client(
+            &config,
+            shared_state,
+            mocked_server_control,
+            dependencies,
+            ip,
+            port,
+            log_level,
+        );
+
+        let response = client
+            .post(get_api_endpoint("/device/unknown/notes"))
+            .json(&json!({ "text": "PSU replaced" }))
+            .dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+
+        let response = client
+            .get(get_api_endpoint("/device/unknown/notes"))
+            .dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+    }
+}
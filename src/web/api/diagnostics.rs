@@ -0,0 +1,242 @@
+use std::collections::BTreeMap;
+use std::convert::TryInto;
+use std::result::Result;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use log::info;
+use rocket::post;
+use rocket::serde::json::Json;
+use rocket_okapi::{openapi, JsonSchema};
+use serde::{Deserialize, Serialize};
+
+use crate::dom::communication::SharedStateMutex;
+use crate::dom::{self, DeviceId};
+use crate::networking::{Capture, PacketCapture};
+use crate::web::api;
+use crate::web::api::server::UnknownDeviceError;
+
+const DEFAULT_CAPTURE_SECONDS: u64 = 10;
+const MAX_CAPTURE_SECONDS: u64 = 60;
+const MAX_REPORTED_PACKETS: usize = 100;
+
+#[derive(Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CapturedPacket {
+    /// Milliseconds since the start of the capture.
+    pub offset_ms: u64,
+    /// `arpRequest`, `arpReply`, `echoRequest`, `echoReply`, `icmp` or `wakeOnLan`.
+    pub kind: String,
+    pub from_device: bool,
+}
+
+#[derive(Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptureSummary {
+    pub seconds: u64,
+    /// Number of captured packets of every kind.
+    pub counts: BTreeMap<String, u64>,
+    /// The first captured packets (oldest first).
+    pub packets: Vec<CapturedPacket>,
+    /// All captured packets in the pcap format (base64 encoded) if requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pcap: Option<String>,
+}
+
+impl CaptureSummary {
+    fn new(capture: &Capture, start: SystemTime, seconds: u64, pcap: bool) -> Self {
+        let mut counts = BTreeMap::new();
+        for packet in capture.packets.iter() {
+            *counts.entry(packet.kind.to_string()).or_insert(0) += 1;
+        }
+
+        Self {
+            seconds,
+            counts,
+            packets: capture
+                .packets
+                .iter()
+                .take(MAX_REPORTED_PACKETS)
+                .map(|packet| CapturedPacket {
+                    offset_ms: packet
+                        .timestamp
+                        .duration_since(start)
+                        .unwrap_or_default()
+                        .as_millis() as u64,
+                    kind: packet.kind.to_string(),
+                    from_device: packet.from_device,
+                })
+                .collect(),
+            pcap: pcap.then(|| STANDARD.encode(capture.pcap())),
+        }
+    }
+}
+
+/// Captures the ARP, ICMP and Wake-on-LAN traffic of the given device on the configured network
+/// interface for the given number of seconds (up to 60) to debug why pings or wakeups fail.
+#[openapi(tag = "General")]
+#[post("/diagnostics/capture?<device>&<seconds>&<pcap>")]
+pub async fn post_diagnostics_capture(
+    device: String,
+    seconds: Option<u64>,
+    pcap: Option<bool>,
+    shared_state: &rocket::State<Arc<SharedStateMutex>>,
+    packet_capture: &rocket::State<Arc<dyn PacketCapture>>,
+) -> Result<Json<CaptureSummary>, api::Error> {
+    let device_id: DeviceId = device.parse().unwrap();
+    let (ip, mac) = {
+        let shared_state = shared_state.lock().unwrap();
+        match shared_state
+            .get_devices()
+            .iter()
+            .find(|device| *device.id() == device_id)
+        {
+            // only servers are woken up and therefore have a MAC address
+            Some(dom::Device::Server(server)) => {
+                (server.machine.ip, server.mac.as_bytes().try_into().ok())
+            }
+            Some(dom::Device::Machine(machine)) => (machine.ip, None),
+            None => return Err(api::Error::from(UnknownDeviceError::from(device_id))),
+        }
+    };
+
+    let seconds = seconds
+        .unwrap_or(DEFAULT_CAPTURE_SECONDS)
+        .min(MAX_CAPTURE_SECONDS);
+    info!("capturing the traffic of {} for {}s", device_id, seconds);
+
+    let packet_capture = packet_capture.inner().clone();
+    let start = SystemTime::now();
+    let capture = tokio::task::spawn_blocking(move || {
+        packet_capture.capture(ip, mac, Duration::from_secs(seconds))
+    })
+    .await
+    .map_err(anyhow::Error::from)
+    .and_then(|capture| capture)
+    .map_err(|e| api::Error::from(api::InternalServerError::from(e)))?;
+
+    Ok(Json(CaptureSummary::new(
+        &capture,
+        start,
+        seconds,
+        pcap.unwrap_or(false),
+    )))
+}
+
+#[cfg(test)]
+mod test {
+    use std::net::IpAddr;
+
+    use mockall::predicate::*;
+    use rocket::http::{ContentType, Status};
+    use rocket::log::LogLevel;
+    use rstest::*;
+
+    use super::*;
+    use crate::configuration::Configuration;
+    use crate::control::test::*;
+    use crate::dom::device::test::*;
+    use crate::dom::test::*;
+    use crate::dom::Dependencies;
+    use crate::networking::{self, MockPacketCapture, PacketKind};
+    use crate::web::server::test::*;
+
+    #[rstest]
+    fn test_web_api_captures_packets_of_device(
+        config: Configuration,
+        shared_state: Arc<SharedStateMutex>,
+        mocked_server_control: MockServerControl,
+        dependencies: Dependencies,
+        ip: IpAddr,
+        port: u16,
+        log_level: LogLevel,
+    ) {
+        // SETUP
+        let machine_ip: IpAddr = MACHINE_IP.parse().unwrap();
+        let mut packet_capture = MockPacketCapture::new();
+
+        // EXPECTATIONS
+        packet_capture
+            .expect_capture()
+            .with(eq(machine_ip), eq(None), eq(Duration::from_secs(2)))
+            .once()
+            .returning(|_, _, _| {
+                Ok(Capture {
+                    packets: vec![networking::CapturedPacket {
+                        kind: PacketKind::ArpRequest,
+                        from_device: false,
+                        timestamp: SystemTime::now(),
+                        frame: vec![0; 42],
+                    }],
+                })
+            });
+
+        // TESTING
+        let client = get_client_with_packet_capture(
+            &config,
+            shared_state,
+            mocked_server_control,
+            dependencies,
+            packet_capture,
+            ip,
+            port,
+            log_level,
+        );
+
+        let response = client
+            .post(get_api_endpoint(&format!(
+                "/diagnostics/capture?device={MACHINE_ID}&seconds=2&pcap=true"
+            )))
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.content_type(), Some(ContentType::JSON));
+
+        let summary: CaptureSummary = response.into_json().unwrap();
+        assert_eq!(summary.seconds, 2);
+        assert_eq!(
+            summary.counts,
+            BTreeMap::from([("arpRequest".to_string(), 1)])
+        );
+        assert_eq!(summary.packets.len(), 1);
+        assert!(!summary.packets[0].from_device);
+        // the pcap consists of its header, the header of the packet and the packet itself
+        let pcap = STANDARD.decode(summary.pcap.unwrap()).unwrap();
+        assert_eq!(pcap.len(), 24 + 16 + 42);
+    }
+
+    #[rstest]
+    fn test_web_api_capture_fails_for_unknown_device(
+        config: Configuration,
+        shared_state: Arc<SharedStateMutex>,
+        mocked_server_control: MockServerControl,
+        dependencies: Dependencies,
+        ip: IpAddr,
+        port: u16,
+        log_level: LogLevel,
+    ) {
+        // SETUP
+        let mut packet_capture = MockPacketCapture::new();
+
+        // EXPECTATIONS
+        packet_capture.expect_capture().never();
+
+        // TESTING
+        let client = get_client_with_packet_capture(
+            &config,
+            shared_state,
+            mocked_server_control,
+            dependencies,
+            packet_capture,
+            ip,
+            port,
+            log_level,
+        );
+
+        let response = client
+            .post(get_api_endpoint("/diagnostics/capture?device=unknown"))
+            .dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+    }
+}
@@ -6,12 +6,19 @@ use rocket_okapi::gen::OpenApiGenerator;
 use rocket_okapi::okapi::openapi3::Responses;
 use rocket_okapi::response::OpenApiResponderInner;
 
-use crate::web::api::server::UnknownDeviceError;
-use crate::web::api::InternalServerError;
+use crate::web::api::server::{
+    ActionInProgressError, AuthenticationError, UnconfirmedShutdownError, UnknownDeviceError,
+};
+use crate::web::api::{DeviceExistsError, FilesApiError, InternalServerError};
 
 #[derive(Debug)]
 pub enum Error {
     UnknownDevice(UnknownDeviceError),
+    DeviceExists(DeviceExistsError),
+    UnconfirmedShutdown(UnconfirmedShutdownError),
+    ActionInProgress(ActionInProgressError),
+    Authentication(AuthenticationError),
+    Files(FilesApiError),
     Internal(InternalServerError),
 }
 
@@ -23,6 +30,36 @@ impl From<UnknownDeviceError> for Error {
     }
 }
 
+impl From<DeviceExistsError> for Error {
+    fn from(error: DeviceExistsError) -> Self {
+        Self::DeviceExists(error)
+    }
+}
+
+impl From<UnconfirmedShutdownError> for Error {
+    fn from(error: UnconfirmedShutdownError) -> Self {
+        Self::UnconfirmedShutdown(error)
+    }
+}
+
+impl From<ActionInProgressError> for Error {
+    fn from(error: ActionInProgressError) -> Self {
+        Self::ActionInProgress(error)
+    }
+}
+
+impl From<AuthenticationError> for Error {
+    fn from(error: AuthenticationError) -> Self {
+        Self::Authentication(error)
+    }
+}
+
+impl From<FilesApiError> for Error {
+    fn from(error: FilesApiError) -> Self {
+        Self::Files(error)
+    }
+}
+
 impl From<InternalServerError> for Error {
     fn from(error: InternalServerError) -> Self {
         Self::Internal(error)
@@ -33,6 +70,11 @@ impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::UnknownDevice(error) => error.fmt(f),
+            Self::DeviceExists(error) => error.fmt(f),
+            Self::UnconfirmedShutdown(error) => error.fmt(f),
+            Self::ActionInProgress(error) => error.fmt(f),
+            Self::Authentication(error) => error.fmt(f),
+            Self::Files(error) => error.fmt(f),
             Self::Internal(error) => error.fmt(f),
         }
     }
@@ -42,6 +84,11 @@ impl<'r, 'o: 'r> Responder<'r, 'o> for Error {
     fn respond_to(self, req: &Request) -> response::Result<'o> {
         match self {
             Self::UnknownDevice(error) => error.respond_to(req),
+            Self::DeviceExists(error) => error.respond_to(req),
+            Self::UnconfirmedShutdown(error) => error.respond_to(req),
+            Self::ActionInProgress(error) => error.respond_to(req),
+            Self::Authentication(error) => error.respond_to(req),
+            Self::Files(error) => error.respond_to(req),
             Self::Internal(error) => error.respond_to(req),
         }
     }
@@ -56,6 +103,36 @@ impl OpenApiResponderInner for Error {
                 .responses
                 .extend(responses_unknown_device.responses);
         }
+        {
+            let responses_device_exists = DeviceExistsError::responses(gen)?;
+            responses
+                .responses
+                .extend(responses_device_exists.responses);
+        }
+        {
+            // shares the 409 response with an already existing device
+            let responses_action_in_progress = ActionInProgressError::responses(gen)?;
+            for (status, response) in responses_action_in_progress.responses {
+                responses.responses.entry(status).or_insert(response);
+            }
+        }
+        {
+            // shares the 409 response with an already existing device
+            let responses_authentication = AuthenticationError::responses(gen)?;
+            for (status, response) in responses_authentication.responses {
+                responses.responses.entry(status).or_insert(response);
+            }
+        }
+        {
+            let responses_unconfirmed_shutdown = UnconfirmedShutdownError::responses(gen)?;
+            responses
+                .responses
+                .extend(responses_unconfirmed_shutdown.responses);
+        }
+        {
+            let responses_files_api = FilesApiError::responses(gen)?;
+            responses.responses.extend(responses_files_api.responses);
+        }
         {
             let responses_internal_server_error = InternalServerError::responses(gen)?;
             responses
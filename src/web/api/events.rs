@@ -0,0 +1,111 @@
+use log::warn;
+use rocket::futures::stream::{self, BoxStream, StreamExt};
+use rocket::get;
+use rocket::response::stream::{Event, EventStream};
+use rocket::serde::json::Json;
+use rocket::tokio::sync::broadcast::error::RecvError;
+use rocket::Shutdown;
+use rocket_okapi::openapi;
+
+use crate::dom::communication::{DeviceEvents, DeviceUpdate};
+use crate::events::{self, Events};
+
+type IntegrationEventStream = EventStream<BoxStream<'static, Event>>;
+
+#[openapi(tag = "General")]
+#[get("/events")]
+pub fn get_events(
+    device_events: &rocket::State<DeviceEvents>,
+    action_events: &rocket::State<Events>,
+    shutdown: Shutdown,
+) -> IntegrationEventStream {
+    let device_updates = stream::unfold(device_events.subscribe(), |mut receiver| async move {
+        loop {
+            match receiver.recv().await {
+                Ok(DeviceUpdate::Changed(device)) => {
+                    return Some((events::Event::device_state_changed(&device), receiver));
+                }
+                Ok(DeviceUpdate::Removed(_)) => {}
+                Err(RecvError::Lagged(missed)) => warn!("missed {} device updates", missed),
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    });
+    let actions = stream::unfold(action_events.subscribe(), |mut receiver| async move {
+        loop {
+            match receiver.recv().await {
+                Ok(event) => return Some((event, receiver)),
+                Err(RecvError::Lagged(missed)) => warn!("missed {} action events", missed),
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    });
+
+    let events = stream::select(device_updates, actions)
+        .take_until(shutdown)
+        .map(|event| Event::json(&event))
+        .boxed();
+
+    EventStream::from(events)
+}
+
+#[openapi(tag = "General")]
+#[get("/events/schema")]
+pub fn get_events_schema() -> Json<serde_json::Value> {
+    Json(serde_json::to_value(events::schema()).unwrap())
+}
+
+#[cfg(test)]
+mod test {
+    use std::net::IpAddr;
+    use std::sync::Arc;
+
+    use rocket::http::{ContentType, Status};
+    use rocket::log::LogLevel;
+    use rstest::*;
+
+    use crate::configuration::Configuration;
+    use crate::control::test::*;
+    use crate::dom::communication::SharedStateMutex;
+    use crate::dom::test::*;
+    use crate::dom::Dependencies;
+    use crate::web::server::test::*;
+
+    #[rstest]
+    fn test_web_api_serves_events_schema(
+        config: Configuration,
+        shared_state: Arc<SharedStateMutex>,
+        mocked_server_control: MockServerControl,
+        dependencies: Dependencies,
+        ip: IpAddr,
+        port: u16,
+        log_level: LogLevel,
+    ) {
+        // SETUP
+        let client = get_client(
+            &config,
+            shared_state,
+            mocked_server_control,
+            dependencies,
+            ip,
+            port,
+            log_level,
+        );
+
+        // TESTING
+        let response = client.get(get_api_endpoint("/events/schema")).dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.content_type(), Some(ContentType::JSON));
+
+        let schema = response.into_string().unwrap();
+        for event_type in [
+            "device_state_changed",
+            "action_started",
+            "action_finished",
+            "config_reloaded",
+        ] {
+            assert!(schema.contains(event_type), "missing {}", event_type);
+        }
+    }
+}
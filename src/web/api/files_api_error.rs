@@ -0,0 +1,186 @@
+use std::fmt;
+use std::io;
+use std::io::Cursor;
+
+use rocket::response::Responder;
+use rocket::{http, response, Request, Response};
+use rocket_okapi::gen::OpenApiGenerator;
+use rocket_okapi::okapi::openapi3::Responses;
+use rocket_okapi::response::OpenApiResponderInner;
+use rocket_okapi::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::utils::FileError;
+
+// io::ErrorKind::ReadOnlyFilesystem and io::ErrorKind::StorageFull aren't stable yet but the
+// errno values are the same on Linux, macOS and the BSDs
+const EROFS: i32 = 30;
+const ENOSPC: i32 = 28;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum FilesApiErrorCode {
+    PermissionDenied,
+    ReadOnlyFileSystem,
+    NoSpaceLeft,
+    Io,
+}
+
+impl FilesApiErrorCode {
+    fn hint(&self) -> &'static str {
+        match self {
+            Self::PermissionDenied => "make sure the files API root directory is writable",
+            Self::ReadOnlyFileSystem => {
+                "configure a files API root directory on a writable file system"
+            }
+            Self::NoSpaceLeft => "free up space on the file system of the files API root directory",
+            Self::Io => "check the files API root directory and the log",
+        }
+    }
+}
+
+impl From<&io::Error> for FilesApiErrorCode {
+    fn from(error: &io::Error) -> Self {
+        match (error.kind(), error.raw_os_error()) {
+            (_, Some(EROFS)) => Self::ReadOnlyFileSystem,
+            (_, Some(ENOSPC)) => Self::NoSpaceLeft,
+            (io::ErrorKind::PermissionDenied, _) => Self::PermissionDenied,
+            _ => Self::Io,
+        }
+    }
+}
+
+/// Failure to change one of the always on / off files.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FilesApiError {
+    pub code: FilesApiErrorCode,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub errno: Option<i32>,
+    pub message: String,
+    pub hint: String,
+}
+
+impl FilesApiError {
+    fn new(code: FilesApiErrorCode, path: Option<String>, error: &io::Error) -> Self {
+        Self {
+            code,
+            path,
+            errno: error.raw_os_error(),
+            message: error.to_string(),
+            hint: code.hint().to_string(),
+        }
+    }
+}
+
+impl std::error::Error for FilesApiError {}
+
+impl From<anyhow::Error> for FilesApiError {
+    fn from(error: anyhow::Error) -> Self {
+        if let Some(error) = error.downcast_ref::<FileError>() {
+            let io_error = error.io_error();
+            return Self::new(
+                FilesApiErrorCode::from(io_error),
+                Some(error.path().display().to_string()),
+                io_error,
+            );
+        }
+        if let Some(error) = error.downcast_ref::<io::Error>() {
+            return Self::new(FilesApiErrorCode::from(error), None, error);
+        }
+
+        let code = FilesApiErrorCode::Io;
+        Self {
+            code,
+            path: None,
+            errno: None,
+            message: error.to_string(),
+            hint: code.hint().to_string(),
+        }
+    }
+}
+
+impl fmt::Display for FilesApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.path {
+            Some(path) => write!(f, "[FilesApiError] {}: {}", path, self.message),
+            None => write!(f, "[FilesApiError] {}", self.message),
+        }
+    }
+}
+
+impl<'r, 'o: 'r> Responder<'r, 'o> for FilesApiError {
+    fn respond_to(self, _: &Request) -> response::Result<'o> {
+        let body = serde_json::to_string(&self).map_err(|_| http::Status::InternalServerError)?;
+        Response::build()
+            .header(http::ContentType::JSON)
+            .status(http::Status::InternalServerError)
+            .sized_body(body.len(), Cursor::new(body))
+            .ok()
+    }
+}
+
+impl OpenApiResponderInner for FilesApiError {
+    fn responses(_: &mut OpenApiGenerator) -> rocket_okapi::Result<Responses> {
+        let mut responses = Responses::default();
+        add_500_error(&mut responses);
+        Ok(responses)
+    }
+}
+
+fn add_500_error(responses: &mut Responses) {
+    responses
+        .responses
+        .entry("500".to_owned())
+        .or_insert_with(|| {
+            let response = rocket_okapi::okapi::openapi3::Response {
+                description: "\
+                    [500 Internal Server Error](https://developer.mozilla.org/en-US/docs/Web/HTTP/Status/500)\n\n\
+                    This response is given when the always on / off file couldn't be changed. The JSON body \
+                    contains the error `code`, the affected `path`, the `errno` and a `hint` on how to fix it.\
+                    ".to_owned(),
+                ..Default::default()
+            };
+            response.into()
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use anyhow::anyhow;
+    use rstest::*;
+
+    use super::*;
+
+    #[rstest]
+    #[case(
+        io::Error::from_raw_os_error(EROFS),
+        FilesApiErrorCode::ReadOnlyFileSystem
+    )]
+    #[case(io::Error::from_raw_os_error(ENOSPC), FilesApiErrorCode::NoSpaceLeft)]
+    #[case(
+        io::Error::new(io::ErrorKind::PermissionDenied, "denied"),
+        FilesApiErrorCode::PermissionDenied
+    )]
+    #[case(
+        io::Error::new(io::ErrorKind::InvalidInput, "invalid"),
+        FilesApiErrorCode::Io
+    )]
+    fn test_files_api_error_maps_io_errors(
+        #[case] error: io::Error,
+        #[case] expected_code: FilesApiErrorCode,
+    ) {
+        let errno = error.raw_os_error();
+        let error =
+            FilesApiError::from(anyhow!(FileError::new(Path::new("/files/alwayson"), error)));
+
+        assert_eq!(error.code, expected_code);
+        assert_eq!(error.path, Some("/files/alwayson".to_string()));
+        assert_eq!(error.errno, errno);
+        assert_eq!(error.hint, expected_code.hint());
+    }
+}
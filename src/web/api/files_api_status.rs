@@ -0,0 +1,74 @@
+use std::sync::Mutex;
+
+use crate::web::api::FilesApiError;
+
+#[derive(Default)]
+struct Failures {
+    consecutive: u64,
+    last_error: Option<FilesApiError>,
+}
+
+/// Failures of the files API since its last successful change.
+#[derive(Default)]
+pub struct FilesApiStatus {
+    failures: Mutex<Failures>,
+}
+
+impl FilesApiStatus {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keeps track of the result of changing one of the always on / off files.
+    pub fn record(&self, result: anyhow::Result<()>) -> Result<(), FilesApiError> {
+        let mut failures = self.failures.lock().unwrap();
+        match result {
+            Ok(()) => {
+                *failures = Failures::default();
+                Ok(())
+            }
+            Err(e) => {
+                let error = FilesApiError::from(e);
+                failures.consecutive += 1;
+                failures.last_error = Some(error.clone());
+                Err(error)
+            }
+        }
+    }
+
+    pub fn consecutive_failures(&self) -> u64 {
+        self.failures.lock().unwrap().consecutive
+    }
+
+    pub fn last_error(&self) -> Option<FilesApiError> {
+        self.failures.lock().unwrap().last_error.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Error, ErrorKind};
+
+    use anyhow::anyhow;
+    use rstest::*;
+
+    use super::*;
+
+    #[rstest]
+    fn test_files_api_status_resets_failures_on_success() {
+        let status = FilesApiStatus::new();
+        assert_eq!(status.consecutive_failures(), 0);
+
+        for _ in 0..2 {
+            assert!(status
+                .record(Err(anyhow!(Error::new(ErrorKind::PermissionDenied, ""))))
+                .is_err());
+        }
+        assert_eq!(status.consecutive_failures(), 2);
+        assert!(status.last_error().is_some());
+
+        assert!(status.record(Ok(())).is_ok());
+        assert_eq!(status.consecutive_failures(), 0);
+        assert_eq!(status.last_error(), None);
+    }
+}
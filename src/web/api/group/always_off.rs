@@ -0,0 +1,216 @@
+use std::result::Result;
+
+use rocket::serde::json::Json;
+use rocket::{delete, get, post};
+use rocket_okapi::openapi;
+
+use super::get_group_control;
+use crate::control::GroupControl;
+use crate::web::api;
+use crate::web::api::server::{AlwaysOffResponse, UnknownDeviceError};
+
+#[openapi(tag = "Group")]
+#[get("/group/<group>/always_off")]
+pub fn get_group_always_off(
+    group: String,
+    state: &rocket::State<Vec<GroupControl>>,
+) -> Result<Json<AlwaysOffResponse>, UnknownDeviceError> {
+    let control = get_group_control(state.inner(), group)?;
+    Ok(Json(AlwaysOffResponse {
+        always_off: control.always_off.is_always_off(),
+    }))
+}
+
+#[openapi(tag = "Group")]
+#[post("/group/<group>/always_off")]
+pub fn post_group_always_off(
+    group: String,
+    state: &rocket::State<Vec<GroupControl>>,
+    files_api: &rocket::State<api::FilesApiStatus>,
+) -> Result<Json<AlwaysOffResponse>, api::Error> {
+    let control = get_group_control(state.inner(), group)?;
+
+    files_api.record(control.always_off.set_always_off())?;
+    Ok(Json(AlwaysOffResponse { always_off: true }))
+}
+
+#[openapi(tag = "Group")]
+#[delete("/group/<group>/always_off")]
+pub fn delete_group_always_off(
+    group: String,
+    state: &rocket::State<Vec<GroupControl>>,
+    files_api: &rocket::State<api::FilesApiStatus>,
+) -> Result<Json<AlwaysOffResponse>, api::Error> {
+    let control = get_group_control(state.inner(), group)?;
+
+    files_api.record(control.always_off.reset_always_off())?;
+    Ok(Json(AlwaysOffResponse { always_off: false }))
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::{Error, ErrorKind};
+    use std::net::IpAddr;
+    use std::sync::Arc;
+
+    use anyhow::anyhow;
+    use rocket::http::{ContentType, Status};
+    use rocket::log::LogLevel;
+    use rstest::*;
+
+    use super::*;
+    use crate::configuration::Configuration;
+    use crate::control::test::*;
+    use crate::dom::communication::SharedStateMutex;
+    use crate::dom::test::*;
+    use crate::dom::Dependencies;
+    use crate::utils::{MockAlwaysOff, MockAlwaysOn};
+    use crate::web::api::group::test::*;
+    use crate::web::server::test::*;
+
+    #[rstest]
+    fn test_web_api_can_get_group_always_off(
+        config: Configuration,
+        shared_state: Arc<SharedStateMutex>,
+        mut mocked_server_control: MockServerControl,
+        dependencies: Dependencies,
+        ip: IpAddr,
+        port: u16,
+        log_level: LogLevel,
+    ) {
+        // EXPECTATIONS
+        let mut always_off = MockAlwaysOff::new();
+        always_off
+            .expect_is_always_off()
+            .once()
+            .return_once(|| true);
+        add_mocked_group(&mut mocked_server_control, always_off, MockAlwaysOn::new());
+
+        // TESTING
+        let client = get_client(
+            &config,
+            shared_state,
+            mocked_server_control,
+            dependencies,
+            ip,
+            port,
+            log_level,
+        );
+
+        let response = client
+            .get(get_group_api_endpoint("/always_off", GROUP_ID))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.content_type(), Some(ContentType::JSON));
+
+        assert_eq!(
+            response.into_json::<AlwaysOffResponse>(),
+            Some(AlwaysOffResponse { always_off: true })
+        );
+    }
+
+    #[rstest]
+    fn test_web_api_cannot_get_always_off_for_invalid_group(
+        config: Configuration,
+        shared_state: Arc<SharedStateMutex>,
+        mocked_server_control: MockServerControl,
+        dependencies: Dependencies,
+        ip: IpAddr,
+        port: u16,
+        log_level: LogLevel,
+    ) {
+        // TESTING
+        let client = get_client(
+            &config,
+            shared_state,
+            mocked_server_control,
+            dependencies,
+            ip,
+            port,
+            log_level,
+        );
+
+        let response = client
+            .get(get_group_api_endpoint("/always_off", "invalidgroupid"))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::NotFound);
+    }
+
+    #[rstest]
+    fn test_web_api_can_set_group_always_off(
+        config: Configuration,
+        shared_state: Arc<SharedStateMutex>,
+        mut mocked_server_control: MockServerControl,
+        dependencies: Dependencies,
+        ip: IpAddr,
+        port: u16,
+        log_level: LogLevel,
+    ) {
+        // EXPECTATIONS
+        let mut always_off = MockAlwaysOff::new();
+        always_off
+            .expect_set_always_off()
+            .once()
+            .return_once(|| Ok(()));
+        add_mocked_group(&mut mocked_server_control, always_off, MockAlwaysOn::new());
+
+        // TESTING
+        let client = get_client(
+            &config,
+            shared_state,
+            mocked_server_control,
+            dependencies,
+            ip,
+            port,
+            log_level,
+        );
+
+        let response = client
+            .post(get_group_api_endpoint("/always_off", GROUP_ID))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(
+            response.into_json::<AlwaysOffResponse>(),
+            Some(AlwaysOffResponse { always_off: true })
+        );
+    }
+
+    #[rstest]
+    fn test_web_api_returns_internal_server_error_if_delete_group_always_off_fails(
+        config: Configuration,
+        shared_state: Arc<SharedStateMutex>,
+        mut mocked_server_control: MockServerControl,
+        dependencies: Dependencies,
+        ip: IpAddr,
+        port: u16,
+        log_level: LogLevel,
+    ) {
+        // EXPECTATIONS
+        let mut always_off = MockAlwaysOff::new();
+        always_off
+            .expect_reset_always_off()
+            .once()
+            .return_once(|| Err(anyhow!(Error::new(ErrorKind::PermissionDenied, ""))));
+        add_mocked_group(&mut mocked_server_control, always_off, MockAlwaysOn::new());
+
+        // TESTING
+        let client = get_client(
+            &config,
+            shared_state,
+            mocked_server_control,
+            dependencies,
+            ip,
+            port,
+            log_level,
+        );
+
+        let response = client
+            .delete(get_group_api_endpoint("/always_off", GROUP_ID))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::InternalServerError);
+    }
+}
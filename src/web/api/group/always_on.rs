@@ -0,0 +1,213 @@
+use std::result::Result;
+
+use rocket::serde::json::Json;
+use rocket::{delete, get, post};
+use rocket_okapi::openapi;
+
+use super::get_group_control;
+use crate::control::GroupControl;
+use crate::web::api;
+use crate::web::api::server::{AlwaysOnResponse, UnknownDeviceError};
+
+#[openapi(tag = "Group")]
+#[get("/group/<group>/always_on")]
+pub fn get_group_always_on(
+    group: String,
+    state: &rocket::State<Vec<GroupControl>>,
+) -> Result<Json<AlwaysOnResponse>, UnknownDeviceError> {
+    let control = get_group_control(state.inner(), group)?;
+    Ok(Json(AlwaysOnResponse {
+        always_on: control.always_on.is_always_on(),
+    }))
+}
+
+#[openapi(tag = "Group")]
+#[post("/group/<group>/always_on")]
+pub fn post_group_always_on(
+    group: String,
+    state: &rocket::State<Vec<GroupControl>>,
+    files_api: &rocket::State<api::FilesApiStatus>,
+) -> Result<Json<AlwaysOnResponse>, api::Error> {
+    let control = get_group_control(state.inner(), group)?;
+
+    files_api.record(control.always_on.set_always_on())?;
+    Ok(Json(AlwaysOnResponse { always_on: true }))
+}
+
+#[openapi(tag = "Group")]
+#[delete("/group/<group>/always_on")]
+pub fn delete_group_always_on(
+    group: String,
+    state: &rocket::State<Vec<GroupControl>>,
+    files_api: &rocket::State<api::FilesApiStatus>,
+) -> Result<Json<AlwaysOnResponse>, api::Error> {
+    let control = get_group_control(state.inner(), group)?;
+
+    files_api.record(control.always_on.reset_always_on())?;
+    Ok(Json(AlwaysOnResponse { always_on: false }))
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::{Error, ErrorKind};
+    use std::net::IpAddr;
+    use std::sync::Arc;
+
+    use anyhow::anyhow;
+    use rocket::http::{ContentType, Status};
+    use rocket::log::LogLevel;
+    use rstest::*;
+
+    use super::*;
+    use crate::configuration::Configuration;
+    use crate::control::test::*;
+    use crate::dom::communication::SharedStateMutex;
+    use crate::dom::test::*;
+    use crate::dom::Dependencies;
+    use crate::utils::{MockAlwaysOff, MockAlwaysOn};
+    use crate::web::api::group::test::*;
+    use crate::web::server::test::*;
+
+    #[rstest]
+    fn test_web_api_can_get_group_always_on(
+        config: Configuration,
+        shared_state: Arc<SharedStateMutex>,
+        mut mocked_server_control: MockServerControl,
+        dependencies: Dependencies,
+        ip: IpAddr,
+        port: u16,
+        log_level: LogLevel,
+    ) {
+        // EXPECTATIONS
+        let mut always_on = MockAlwaysOn::new();
+        always_on.expect_is_always_on().once().return_once(|| true);
+        add_mocked_group(&mut mocked_server_control, MockAlwaysOff::new(), always_on);
+
+        // TESTING
+        let client = get_client(
+            &config,
+            shared_state,
+            mocked_server_control,
+            dependencies,
+            ip,
+            port,
+            log_level,
+        );
+
+        let response = client
+            .get(get_group_api_endpoint("/always_on", GROUP_ID))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.content_type(), Some(ContentType::JSON));
+
+        assert_eq!(
+            response.into_json::<AlwaysOnResponse>(),
+            Some(AlwaysOnResponse { always_on: true })
+        );
+    }
+
+    #[rstest]
+    fn test_web_api_cannot_get_always_on_for_invalid_group(
+        config: Configuration,
+        shared_state: Arc<SharedStateMutex>,
+        mocked_server_control: MockServerControl,
+        dependencies: Dependencies,
+        ip: IpAddr,
+        port: u16,
+        log_level: LogLevel,
+    ) {
+        // TESTING
+        let client = get_client(
+            &config,
+            shared_state,
+            mocked_server_control,
+            dependencies,
+            ip,
+            port,
+            log_level,
+        );
+
+        let response = client
+            .get(get_group_api_endpoint("/always_on", "invalidgroupid"))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::NotFound);
+    }
+
+    #[rstest]
+    fn test_web_api_can_set_group_always_on(
+        config: Configuration,
+        shared_state: Arc<SharedStateMutex>,
+        mut mocked_server_control: MockServerControl,
+        dependencies: Dependencies,
+        ip: IpAddr,
+        port: u16,
+        log_level: LogLevel,
+    ) {
+        // EXPECTATIONS
+        let mut always_on = MockAlwaysOn::new();
+        always_on
+            .expect_set_always_on()
+            .once()
+            .return_once(|| Ok(()));
+        add_mocked_group(&mut mocked_server_control, MockAlwaysOff::new(), always_on);
+
+        // TESTING
+        let client = get_client(
+            &config,
+            shared_state,
+            mocked_server_control,
+            dependencies,
+            ip,
+            port,
+            log_level,
+        );
+
+        let response = client
+            .post(get_group_api_endpoint("/always_on", GROUP_ID))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(
+            response.into_json::<AlwaysOnResponse>(),
+            Some(AlwaysOnResponse { always_on: true })
+        );
+    }
+
+    #[rstest]
+    fn test_web_api_returns_internal_server_error_if_delete_group_always_on_fails(
+        config: Configuration,
+        shared_state: Arc<SharedStateMutex>,
+        mut mocked_server_control: MockServerControl,
+        dependencies: Dependencies,
+        ip: IpAddr,
+        port: u16,
+        log_level: LogLevel,
+    ) {
+        // EXPECTATIONS
+        let mut always_on = MockAlwaysOn::new();
+        always_on
+            .expect_reset_always_on()
+            .once()
+            .return_once(|| Err(anyhow!(Error::new(ErrorKind::PermissionDenied, ""))));
+        add_mocked_group(&mut mocked_server_control, MockAlwaysOff::new(), always_on);
+
+        // TESTING
+        let client = get_client(
+            &config,
+            shared_state,
+            mocked_server_control,
+            dependencies,
+            ip,
+            port,
+            log_level,
+        );
+
+        let response = client
+            .delete(get_group_api_endpoint("/always_on", GROUP_ID))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::InternalServerError);
+    }
+}
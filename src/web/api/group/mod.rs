@@ -0,0 +1,48 @@
+mod always_off;
+mod always_on;
+
+pub use always_off::*;
+pub use always_on::*;
+
+use crate::control::GroupControl;
+use crate::web::api::server::UnknownDeviceError;
+
+fn get_group_control(
+    groups: &[GroupControl],
+    group_id: String,
+) -> Result<&GroupControl, UnknownDeviceError> {
+    let group_id = group_id.parse().unwrap();
+    match groups.iter().find(|control| control.id == group_id) {
+        Some(control) => Ok(control),
+        None => Err(UnknownDeviceError::from(group_id)),
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use crate::control::test::*;
+    use crate::dom::device::test::*;
+    use crate::dom::DeviceId;
+    use crate::utils::{MockAlwaysOff, MockAlwaysOn};
+
+    pub static GROUP_ID: &str = "lab";
+
+    pub fn get_group_api_endpoint(endpoint: &str, group_id: &str) -> String {
+        format!("/api/v1/group/{group_id}{endpoint}")
+    }
+
+    /// Adds a group (with the given always off / on mocks) containing the mocked server.
+    pub fn add_mocked_group(
+        mocked_server_control: &mut MockServerControl,
+        always_off: MockAlwaysOff,
+        always_on: MockAlwaysOn,
+    ) {
+        let server_id: DeviceId = server_id();
+        mocked_server_control.groups.push(mocked_group_control(
+            GROUP_ID,
+            vec![server_id],
+            always_off,
+            always_on,
+        ));
+    }
+}
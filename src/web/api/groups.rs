@@ -0,0 +1,120 @@
+use rocket::get;
+use rocket::serde::json::Json;
+use rocket_okapi::{openapi, JsonSchema};
+use serde::{Deserialize, Serialize};
+
+use crate::control::GroupControl;
+
+#[derive(Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Group {
+    id: String,
+    name: String,
+    servers: Vec<String>,
+    always_off: bool,
+    always_on: bool,
+}
+
+#[derive(Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Groups {
+    groups: Vec<Group>,
+}
+
+impl Groups {
+    pub fn new(groups: Vec<Group>) -> Self {
+        Self { groups }
+    }
+}
+
+#[openapi(tag = "Group")]
+#[get("/groups")]
+pub fn get_groups(state: &rocket::State<Vec<GroupControl>>) -> Json<Groups> {
+    let mut groups: Vec<Group> = state
+        .iter()
+        .map(|control| Group {
+            id: control.id.to_string(),
+            name: control.name.clone(),
+            servers: control
+                .servers
+                .iter()
+                .map(|server_id| server_id.to_string())
+                .collect(),
+            always_off: control.always_off.is_always_off(),
+            always_on: control.always_on.is_always_on(),
+        })
+        .collect();
+    groups.sort_by(|lhs, rhs| lhs.id.cmp(&rhs.id));
+
+    Json(Groups::new(groups))
+}
+
+#[cfg(test)]
+mod test {
+    use std::net::IpAddr;
+    use std::sync::Arc;
+
+    use rocket::http::{ContentType, Status};
+    use rocket::log::LogLevel;
+    use rstest::*;
+
+    use super::*;
+    use crate::configuration::Configuration;
+    use crate::control::test::*;
+    use crate::dom::communication::SharedStateMutex;
+    use crate::dom::device::test::*;
+    use crate::dom::test::*;
+    use crate::dom::{Dependencies, DeviceId};
+    use crate::utils::{MockAlwaysOff, MockAlwaysOn};
+    use crate::web::api::group::test::*;
+    use crate::web::server::test::*;
+
+    #[rstest]
+    fn test_web_api_can_get_groups(
+        config: Configuration,
+        shared_state: Arc<SharedStateMutex>,
+        mut mocked_server_control: MockServerControl,
+        dependencies: Dependencies,
+        ip: IpAddr,
+        port: u16,
+        log_level: LogLevel,
+        server_id: DeviceId,
+    ) {
+        // EXPECTATIONS
+        let mut always_off = MockAlwaysOff::new();
+        always_off
+            .expect_is_always_off()
+            .once()
+            .return_once(|| false);
+        let mut always_on = MockAlwaysOn::new();
+        always_on.expect_is_always_on().once().return_once(|| true);
+        add_mocked_group(&mut mocked_server_control, always_off, always_on);
+
+        // TESTING
+        let client = get_client(
+            &config,
+            shared_state,
+            mocked_server_control,
+            dependencies,
+            ip,
+            port,
+            log_level,
+        );
+
+        let response = client.get(get_api_endpoint("/groups")).dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.content_type(), Some(ContentType::JSON));
+
+        assert_eq!(
+            response.into_json::<Groups>(),
+            Some(Groups::new(vec![Group {
+                id: GROUP_ID.to_string(),
+                name: GROUP_ID.to_string(),
+                servers: vec![server_id.to_string()],
+                always_off: false,
+                always_on: true,
+            }]))
+        );
+    }
+}
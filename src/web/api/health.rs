@@ -0,0 +1,243 @@
+use rocket::get;
+use rocket::serde::json::Json;
+use rocket_okapi::{openapi, JsonSchema};
+use serde::{Deserialize, Serialize};
+
+use std::sync::Arc;
+
+use crate::configuration::{self, Configuration};
+use crate::dom::communication::{MonitorStats, RuntimeStats, SyncStats, MAX_BLOCKING_THREADS};
+use crate::metrics::ProcessStats;
+use crate::updates::SharedUpdateStatus;
+use crate::web::api::{FilesApiError, FilesApiStatus};
+
+#[derive(Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Update {
+    current_version: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    latest_version: Option<String>,
+    update_available: bool,
+}
+
+#[derive(Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StateSync {
+    queue_depth: usize,
+    event_backlog: usize,
+    failed_sends: u64,
+    coalesced_updates: u64,
+    last_latency_ms: u64,
+    max_latency_ms: u64,
+}
+
+impl From<&SyncStats> for StateSync {
+    fn from(sync_stats: &SyncStats) -> Self {
+        Self {
+            queue_depth: sync_stats.queue_depth(),
+            event_backlog: sync_stats.event_backlog(),
+            failed_sends: sync_stats.failed_sends(),
+            coalesced_updates: sync_stats.coalesced_updates(),
+            last_latency_ms: sync_stats.last_latency().as_millis() as u64,
+            max_latency_ms: sync_stats.max_latency().as_millis() as u64,
+        }
+    }
+}
+
+/// Timing of the monitoring loop compared to the configured ping interval.
+#[derive(Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MonitorLoop {
+    ping_cycles: u64,
+    last_drift_ms: u64,
+    max_drift_ms: u64,
+    last_duration_ms: u64,
+    max_duration_ms: u64,
+    overruns: u64,
+}
+
+impl From<&MonitorStats> for MonitorLoop {
+    fn from(monitor_stats: &MonitorStats) -> Self {
+        Self {
+            ping_cycles: monitor_stats.ping_cycles(),
+            last_drift_ms: monitor_stats.last_drift().as_millis() as u64,
+            max_drift_ms: monitor_stats.max_drift().as_millis() as u64,
+            last_duration_ms: monitor_stats.last_duration().as_millis() as u64,
+            max_duration_ms: monitor_stats.max_duration().as_millis() as u64,
+            overruns: monitor_stats.overruns(),
+        }
+    }
+}
+
+/// Utilization of the tokio runtime.
+#[derive(Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Runtime {
+    workers: usize,
+    busy_workers: usize,
+    /// Long-lived tasks (e.g. the monitoring loop or the web API).
+    tasks: usize,
+    blocking_threads: usize,
+    max_blocking_threads: usize,
+}
+
+impl From<&RuntimeStats> for Runtime {
+    fn from(runtime_stats: &RuntimeStats) -> Self {
+        Self {
+            workers: runtime_stats.workers(),
+            busy_workers: runtime_stats.busy_workers(),
+            tasks: runtime_stats.tasks(),
+            blocking_threads: runtime_stats.blocking_threads(),
+            max_blocking_threads: MAX_BLOCKING_THREADS,
+        }
+    }
+}
+
+/// Resource usage of the process (missing if it isn't available on the system).
+#[derive(Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Process {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    resident_memory_bytes: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    open_fds: Option<u64>,
+}
+
+impl From<ProcessStats> for Process {
+    fn from(process_stats: ProcessStats) -> Self {
+        Self {
+            resident_memory_bytes: process_stats.resident_memory_bytes,
+            open_fds: process_stats.open_fds,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FilesApi {
+    failing: bool,
+    consecutive_failures: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_error: Option<FilesApiError>,
+}
+
+impl From<&FilesApiStatus> for FilesApi {
+    fn from(files_api: &FilesApiStatus) -> Self {
+        let consecutive_failures = files_api.consecutive_failures();
+        Self {
+            failing: consecutive_failures > 0,
+            consecutive_failures,
+            last_error: files_api.last_error(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Health {
+    status: String,
+    update: Update,
+    state_sync: StateSync,
+    monitor_loop: MonitorLoop,
+    runtime: Runtime,
+    process: Process,
+    files_api: FilesApi,
+    /// Suspicious but valid parts of the configuration.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    config_warnings: Vec<String>,
+}
+
+#[openapi(tag = "General")]
+#[get("/health")]
+pub fn get_health(
+    config: &rocket::State<Configuration>,
+    update_status: &rocket::State<SharedUpdateStatus>,
+    sync_stats: &rocket::State<Arc<SyncStats>>,
+    monitor_stats: &rocket::State<Arc<MonitorStats>>,
+    runtime_stats: &rocket::State<Arc<RuntimeStats>>,
+    files_api: &rocket::State<FilesApiStatus>,
+) -> Json<Health> {
+    let update_status = update_status.lock().unwrap();
+    // the files API keeps failing until one of the always on / off files could be changed again
+    let files_api = FilesApi::from(files_api.inner());
+
+    Json(Health {
+        status: if files_api.failing { "degraded" } else { "ok" }.to_string(),
+        update: Update {
+            current_version: update_status.current_version.clone(),
+            latest_version: update_status.latest_version.clone(),
+            update_available: update_status.update_available,
+        },
+        state_sync: StateSync::from(sync_stats.inner().as_ref()),
+        monitor_loop: MonitorLoop::from(monitor_stats.inner().as_ref()),
+        runtime: Runtime::from(runtime_stats.inner().as_ref()),
+        process: Process::from(ProcessStats::current()),
+        files_api,
+        config_warnings: configuration::lint(config.inner()),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use std::net::IpAddr;
+    use std::sync::Arc;
+
+    use rocket::http::{ContentType, Status};
+    use rocket::log::LogLevel;
+    use rstest::*;
+
+    use super::*;
+    use crate::configuration::Configuration;
+    use crate::control::test::*;
+    use crate::dom::communication::SharedStateMutex;
+    use crate::dom::test::*;
+    use crate::dom::Dependencies;
+    use crate::env::PKG_VERSION;
+    use crate::web::server::test::*;
+
+    #[rstest]
+    fn test_web_api_can_get_health(
+        config: Configuration,
+        shared_state: Arc<SharedStateMutex>,
+        mocked_server_control: MockServerControl,
+        dependencies: Dependencies,
+        ip: IpAddr,
+        port: u16,
+        log_level: LogLevel,
+    ) {
+        // TESTING
+        let client = get_client(
+            &config,
+            shared_state,
+            mocked_server_control,
+            dependencies,
+            ip,
+            port,
+            log_level,
+        );
+
+        let response = client.get(get_api_endpoint("/health")).dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.content_type(), Some(ContentType::JSON));
+
+        let expected_health = Health {
+            status: "ok".to_string(),
+            update: Update {
+                current_version: PKG_VERSION.to_string(),
+                latest_version: None,
+                update_available: false,
+            },
+            state_sync: StateSync::from(&SyncStats::new()),
+            monitor_loop: MonitorLoop::from(&MonitorStats::new()),
+            runtime: Runtime::from(&RuntimeStats::new(1)),
+            process: Process::from(ProcessStats::default()),
+            files_api: FilesApi::from(&FilesApiStatus::new()),
+            config_warnings: configuration::lint(&config),
+        };
+        // the resource usage of the process keeps changing
+        let mut health = response.into_json::<Health>().unwrap();
+        health.process = Process::from(ProcessStats::default());
+        assert_eq!(health, expected_health);
+    }
+}
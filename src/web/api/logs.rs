@@ -0,0 +1,154 @@
+use std::sync::Arc;
+
+use rocket::serde::json::Json;
+use rocket::{get, FromFormField};
+use rocket_okapi::{openapi, JsonSchema};
+use serde::{Deserialize, Serialize};
+
+use crate::log_buffer::{LogBuffer, LogRecord};
+
+const DEFAULT_LIMIT: usize = 200;
+
+/// Minimum level of the returned log records.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, FromFormField, JsonSchema)]
+pub enum LogLevel {
+    #[field(value = "error")]
+    Error,
+    #[field(value = "warn")]
+    Warn,
+    #[field(value = "info")]
+    Info,
+    #[field(value = "debug")]
+    Debug,
+    #[field(value = "trace")]
+    Trace,
+}
+
+impl From<LogLevel> for log::Level {
+    fn from(level: LogLevel) -> Self {
+        match level {
+            LogLevel::Error => log::Level::Error,
+            LogLevel::Warn => log::Level::Warn,
+            LogLevel::Info => log::Level::Info,
+            LogLevel::Debug => log::Level::Debug,
+            LogLevel::Trace => log::Level::Trace,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LogEntry {
+    pub timestamp: String,
+    /// `error`, `warn`, `info`, `debug` or `trace`.
+    pub level: String,
+    /// Module which logged the record.
+    pub target: String,
+    pub message: String,
+}
+
+impl From<LogRecord> for LogEntry {
+    fn from(record: LogRecord) -> Self {
+        Self {
+            timestamp: record.timestamp.to_rfc3339(),
+            level: record.level.as_str().to_lowercase(),
+            target: record.target,
+            message: record.message,
+        }
+    }
+}
+
+/// Returns the most recent log records (oldest first) with at least the given level (defaults to
+/// `info`, which is also used for unknown levels), limited to the given number of records (defaults to 200).
+#[openapi(tag = "General")]
+#[get("/logs?<level>&<limit>")]
+pub fn get_logs(
+    level: Option<LogLevel>,
+    limit: Option<usize>,
+    log_buffer: &rocket::State<Arc<LogBuffer>>,
+) -> Json<Vec<LogEntry>> {
+    let level = level.unwrap_or(LogLevel::Info);
+    Json(
+        log_buffer
+            .recent(level.into(), limit.unwrap_or(DEFAULT_LIMIT))
+            .into_iter()
+            .map(LogEntry::from)
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use std::net::IpAddr;
+
+    use chrono::Utc;
+    use rocket::http::{ContentType, Status};
+    use rocket::log::LogLevel as RocketLogLevel;
+    use rstest::*;
+
+    use super::*;
+    use crate::configuration::Configuration;
+    use crate::control::test::*;
+    use crate::dom::communication::SharedStateMutex;
+    use crate::dom::test::*;
+    use crate::dom::Dependencies;
+    use crate::web::server::test::*;
+
+    #[rstest]
+    fn test_web_api_returns_recent_logs_filtered_by_level(
+        config: Configuration,
+        shared_state: Arc<SharedStateMutex>,
+        mocked_server_control: MockServerControl,
+        dependencies: Dependencies,
+        ip: IpAddr,
+        port: u16,
+        log_level: RocketLogLevel,
+    ) {
+        // SETUP
+        let client = get_client(
+            &config,
+            shared_state,
+            mocked_server_control,
+            dependencies,
+            ip,
+            port,
+            log_level,
+        );
+
+        let log_buffer = client.rocket().state::<Arc<LogBuffer>>().unwrap();
+        for (level, message) in [
+            (log::Level::Info, "monitoring 2 devices"),
+            (log::Level::Warn, "failed to ping NAS"),
+            (log::Level::Error, "NAS failed to shut down"),
+        ]
+        .iter()
+        {
+            log_buffer.push(LogRecord {
+                timestamp: Utc::now(),
+                level: *level,
+                target: "home_monitor_rs::monitor".to_string(),
+                message: message.to_string(),
+            });
+        }
+
+        // TESTING
+        let response = client.get(get_api_endpoint("/logs")).dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.content_type(), Some(ContentType::JSON));
+        assert_eq!(response.into_json::<Vec<LogEntry>>().unwrap().len(), 3);
+
+        let response = client
+            .get(get_api_endpoint("/logs?level=warn&limit=1"))
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        let entries = response.into_json::<Vec<LogEntry>>().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].level, "error");
+        assert_eq!(entries[0].message, "NAS failed to shut down");
+
+        // an unknown level falls back to the default level
+        let response = client.get(get_api_endpoint("/logs?level=loud")).dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.into_json::<Vec<LogEntry>>().unwrap().len(), 3);
+    }
+}
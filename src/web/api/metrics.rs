@@ -0,0 +1,131 @@
+use std::io::Cursor;
+use std::sync::Arc;
+
+use rocket::get;
+use rocket::response::Responder;
+use rocket::{http, response, Request, Response};
+use rocket_okapi::gen::OpenApiGenerator;
+use rocket_okapi::okapi::openapi3::Responses;
+use rocket_okapi::openapi;
+use rocket_okapi::response::OpenApiResponderInner;
+
+use crate::dom::communication::{Away, MonitorStats, RuntimeStats, SharedStateMutex, SyncStats};
+use crate::metrics::{ProcessStats, Registry};
+
+static OPENMETRICS_CONTENT_TYPE: (&str, &str) = ("application", "openmetrics-text");
+
+#[derive(Debug)]
+pub struct OpenMetrics(String);
+
+impl<'r, 'o: 'r> Responder<'r, 'o> for OpenMetrics {
+    fn respond_to(self, _: &Request) -> response::Result<'o> {
+        let content_type =
+            http::ContentType::new(OPENMETRICS_CONTENT_TYPE.0, OPENMETRICS_CONTENT_TYPE.1)
+                .with_params([("version", "1.0.0"), ("charset", "utf-8")]);
+
+        Response::build()
+            .header(content_type)
+            .status(http::Status::Ok)
+            .sized_body(self.0.len(), Cursor::new(self.0))
+            .ok()
+    }
+}
+
+impl OpenApiResponderInner for OpenMetrics {
+    fn responses(_: &mut OpenApiGenerator) -> rocket_okapi::Result<Responses> {
+        let mut responses = Responses::default();
+        responses
+            .responses
+            .entry("200".to_owned())
+            .or_insert_with(|| {
+                let response = rocket_okapi::okapi::openapi3::Response {
+                    description:
+                        "Counters and gauges in the [OpenMetrics](https://openmetrics.io/) text format."
+                            .to_owned(),
+                    ..Default::default()
+                };
+                response.into()
+            });
+        Ok(responses)
+    }
+}
+
+#[openapi(tag = "General")]
+#[get("/metrics")]
+pub fn get_metrics(
+    metrics: &rocket::State<Arc<Registry>>,
+    shared_state: &rocket::State<Arc<SharedStateMutex>>,
+    sync_stats: &rocket::State<Arc<SyncStats>>,
+    monitor_stats: &rocket::State<Arc<MonitorStats>>,
+    runtime_stats: &rocket::State<Arc<RuntimeStats>>,
+    away: &rocket::State<Arc<Away>>,
+) -> OpenMetrics {
+    let shared_state = shared_state.lock().unwrap();
+    OpenMetrics(metrics.encode(
+        shared_state.get_devices(),
+        sync_stats.inner(),
+        monitor_stats.inner(),
+        runtime_stats.inner(),
+        &ProcessStats::current(),
+        away.is_active(),
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use std::net::IpAddr;
+    use std::sync::Arc;
+
+    use rocket::http::Status;
+    use rocket::log::LogLevel;
+    use rstest::*;
+
+    use crate::configuration::Configuration;
+    use crate::control::test::*;
+    use crate::dom::communication::SharedStateMutex;
+    use crate::dom::test::*;
+    use crate::dom::Dependencies;
+    use crate::web::server::test::*;
+
+    #[rstest]
+    fn test_web_api_can_get_metrics(
+        config: Configuration,
+        shared_state: Arc<SharedStateMutex>,
+        mocked_server_control: MockServerControl,
+        dependencies: Dependencies,
+        ip: IpAddr,
+        port: u16,
+        log_level: LogLevel,
+    ) {
+        // TESTING
+        let client = get_client(
+            &config,
+            shared_state,
+            mocked_server_control,
+            dependencies,
+            ip,
+            port,
+            log_level,
+        );
+
+        let response = client.get(get_api_endpoint("/status")).dispatch();
+        assert_eq!(response.status(), Status::Ok);
+
+        let response = client.get(get_api_endpoint("/metrics")).dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(
+            response
+                .content_type()
+                .map(|content_type| content_type.media_type().sub().to_string()),
+            Some("openmetrics-text".to_string())
+        );
+
+        let body = response.into_string().unwrap();
+        assert!(body.contains(
+            "home_monitor_api_requests_total{method=\"GET\",endpoint=\"/api/v1/status\"} 1\n"
+        ));
+        assert!(body.contains("home_monitor_device_online{device=\"testserver\"} 0\n"));
+        assert!(body.ends_with("# EOF\n"));
+    }
+}
@@ -1,24 +1,139 @@
+mod action;
+mod author;
+mod away;
 mod config;
+mod device;
+mod device_attributes;
+mod device_diagnostics;
+mod device_events;
+mod device_exists_error;
+mod device_ignore;
+mod device_notes;
+mod diagnostics;
 mod error;
+mod events;
+mod files_api_error;
+mod files_api_status;
+mod group;
+mod groups;
+mod health;
 mod internal_server_error;
+mod logs;
+mod metrics;
+mod openapi;
+mod persons;
+mod probes;
+mod profile;
+mod request_counter;
+mod request_id;
+mod request_logger;
+mod rooms;
 mod server;
 mod status;
+mod store;
+mod store_error;
+mod summary;
+mod unknown_action_error;
+mod unknown_profile_error;
+mod version;
 
+use action::ActionAccepted;
+pub use action::Actions;
+use author::Author;
+use device_exists_error::DeviceExistsError;
 use error::Error;
+use files_api_error::FilesApiError;
+pub use files_api_status::FilesApiStatus;
 use internal_server_error::InternalServerError;
+pub use openapi::OpenApiSpec;
+pub use request_counter::RequestCounter;
+pub use request_id::RequestId;
+pub use request_logger::RequestLogger;
+pub use server::{Blackouts, ShutdownConfirmations};
+use store_error::StoreError;
+use unknown_action_error::UnknownActionError;
+use unknown_profile_error::UnknownProfileError;
+pub use version::ConfigPath;
 
+/// Invokes the given macro of `rocket_okapi` with all routes of the web API.
+macro_rules! api_routes {
+    ($macro:ident) => {
+        rocket_okapi::$macro![
+            config::get_config,
+            version::get_version,
+            summary::get_summary,
+            health::get_health,
+            probes::get_livez,
+            probes::get_readyz,
+            status::get_status,
+            rooms::get_rooms,
+            persons::get_persons,
+            profile::get_profile,
+            profile::put_profile,
+            away::get_away,
+            away::put_away,
+            logs::get_logs,
+            groups::get_groups,
+            device::post_device,
+            device_attributes::patch_device_attributes,
+            device_diagnostics::get_device_diagnostics,
+            device_events::get_device_events,
+            device_ignore::put_device_ignore,
+            device_ignore::delete_device_ignore,
+            device_notes::get_device_notes,
+            device_notes::post_device_note,
+            events::get_events,
+            events::get_events_schema,
+            diagnostics::post_diagnostics_capture,
+            metrics::get_metrics,
+            server::get_status,
+            server::get_always_off,
+            server::post_always_off,
+            server::delete_always_off,
+            server::get_always_on,
+            server::post_always_on,
+            server::delete_always_on,
+            server::delete_change_timeout,
+            server::get_control_disabled,
+            server::delete_control_disabled,
+            server::get_schedule_preview,
+            server::put_wakeup,
+            server::put_shutdown,
+            server::post_test_connection,
+            server::post_test_wakeup,
+            action::get_action,
+            group::get_group_always_off,
+            group::post_group_always_off,
+            group::delete_group_always_off,
+            group::get_group_always_on,
+            group::post_group_always_on,
+            group::delete_group_always_on,
+            store::get_store_inventory,
+            store::put_store_inventory,
+            store::put_store_device,
+            store::delete_store_device,
+            store::put_store_dependencies,
+            store::get_config_revisions,
+            store::post_config_rollback,
+        ]
+    };
+}
+
+/// Returns the routes of the web API without generating their OpenAPI description which is only
+/// generated once it is requested (see [`OpenApiSpec`]).
 pub fn get_routes() -> Vec<rocket::Route> {
-    rocket_okapi::openapi_get_routes![
-        config::get_config,
-        status::get_status,
-        server::get_status,
-        server::get_always_off,
-        server::post_always_off,
-        server::delete_always_off,
-        server::get_always_on,
-        server::post_always_on,
-        server::delete_always_on,
-        server::put_wakeup,
-        server::put_shutdown,
-    ]
+    let settings = rocket_okapi::settings::OpenApiSettings::new();
+    let mut routes = api_routes!(openapi_routes)(None, &settings);
+
+    // serve the completed OpenAPI description instead of the generated one
+    routes.extend(rocket::routes![openapi::get_openapi]);
+
+    routes
+}
+
+pub fn get_spec(api_path: &str) -> rocket_okapi::okapi::openapi3::OpenApi {
+    let settings = rocket_okapi::settings::OpenApiSettings::new();
+    let spec = api_routes!(openapi_spec)(&settings);
+
+    openapi::complete_spec(spec, api_path)
 }
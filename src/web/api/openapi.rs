@@ -0,0 +1,276 @@
+use std::io::{Cursor, Write};
+use std::sync::{Arc, OnceLock};
+use std::time::Instant;
+
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use log::debug;
+use rocket::get;
+use rocket::request::{FromRequest, Outcome};
+use rocket::response::Responder;
+use rocket::{http, response, Request, Response};
+use rocket_okapi::okapi::openapi3::{
+    Components, Info, OpenApi, SecurityRequirement, SecurityScheme, SecuritySchemeData, Server,
+};
+use rocket_okapi::okapi::Map;
+
+use crate::env::{PKG_DESCRIPTION, PKG_NAME, PKG_VERSION};
+
+static BEARER_AUTH: &str = "bearerAuth";
+
+/// Adds the metadata which can't be derived from the routes to the generated OpenAPI description.
+pub fn complete_spec(mut spec: OpenApi, api_path: &str) -> OpenApi {
+    spec.info = Info {
+        title: PKG_NAME.to_string(),
+        description: Some(PKG_DESCRIPTION.to_string()),
+        version: PKG_VERSION.to_string(),
+        ..Default::default()
+    };
+    spec.servers = vec![Server {
+        url: api_path.to_string(),
+        ..Default::default()
+    }];
+
+    // authentication is optional and only required by reverse proxies in front of the API
+    let components = spec.components.get_or_insert_with(Components::default);
+    components.security_schemes.insert(
+        BEARER_AUTH.to_string(),
+        SecurityScheme {
+            description: Some("Bearer token checked by an optional reverse proxy.".to_string()),
+            data: SecuritySchemeData::Http {
+                scheme: "bearer".to_string(),
+                bearer_format: None,
+            },
+            extensions: Map::new(),
+        }
+        .into(),
+    );
+    let mut bearer_auth = SecurityRequirement::new();
+    bearer_auth.insert(BEARER_AUTH.to_string(), Vec::new());
+    spec.security = vec![SecurityRequirement::new(), bearer_auth];
+
+    spec
+}
+
+/// The serialized OpenAPI description in plain and gzip compressed form.
+struct SerializedSpec {
+    json: String,
+    gzip: Vec<u8>,
+}
+
+impl SerializedSpec {
+    fn new(spec: &OpenApi) -> Self {
+        let json = serde_json::to_string(spec).expect("failed to serialize OpenAPI description");
+
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder
+            .write_all(json.as_bytes())
+            .and_then(|_| encoder.finish())
+            .map(|gzip| Self { json, gzip })
+            .expect("failed to compress OpenAPI description")
+    }
+}
+
+/// The OpenAPI description of the web API which is only generated (and cached) once it is used
+/// because generating it noticeably delays the startup of the web API on slow hardware.
+pub struct OpenApiSpec {
+    api_path: String,
+    serialized: OnceLock<SerializedSpec>,
+}
+
+impl OpenApiSpec {
+    pub fn new(api_path: &str) -> Self {
+        Self {
+            api_path: api_path.to_string(),
+            serialized: OnceLock::new(),
+        }
+    }
+
+    /// Generates the OpenAPI description unless it has already been generated.
+    pub fn prepare(&self) {
+        self.serialized();
+    }
+
+    fn serialized(&self) -> &SerializedSpec {
+        self.serialized.get_or_init(|| {
+            let start = Instant::now();
+            let serialized = SerializedSpec::new(&super::get_spec(&self.api_path));
+            debug!(
+                "generated the OpenAPI description in {}ms",
+                start.elapsed().as_millis()
+            );
+
+            serialized
+        })
+    }
+}
+
+pub struct AcceptsGzip(bool);
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for AcceptsGzip {
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let accepts_gzip = request
+            .headers()
+            .get("Accept-Encoding")
+            .flat_map(|value| value.split(','))
+            .any(|encoding| encoding.trim().starts_with("gzip"));
+
+        Outcome::Success(Self(accepts_gzip))
+    }
+}
+
+pub struct OpenApiResponse<'a> {
+    spec: &'a SerializedSpec,
+    gzip: bool,
+}
+
+impl<'r, 'o: 'r> Responder<'r, 'o> for OpenApiResponse<'o> {
+    fn respond_to(self, _: &Request) -> response::Result<'o> {
+        let mut response = Response::build();
+        response
+            .header(http::ContentType::JSON)
+            .raw_header("Vary", "Accept-Encoding")
+            .status(http::Status::Ok);
+
+        if self.gzip {
+            response
+                .raw_header("Content-Encoding", "gzip")
+                .sized_body(self.spec.gzip.len(), Cursor::new(&self.spec.gzip));
+        } else {
+            response.sized_body(self.spec.json.len(), Cursor::new(&self.spec.json));
+        }
+
+        response.ok()
+    }
+}
+
+#[get("/openapi.json")]
+pub fn get_openapi<'a>(
+    spec: &'a rocket::State<Arc<OpenApiSpec>>,
+    accepts_gzip: AcceptsGzip,
+) -> OpenApiResponse<'a> {
+    OpenApiResponse {
+        spec: spec.inner().serialized(),
+        gzip: accepts_gzip.0,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::Read;
+    use std::net::IpAddr;
+    use std::path::PathBuf;
+    use std::sync::Arc;
+
+    use flate2::read::GzDecoder;
+    use rocket::http::{Header, Status};
+    use rocket::log::LogLevel;
+    use rstest::*;
+
+    use super::*;
+    use crate::configuration::Configuration;
+    use crate::control::test::*;
+    use crate::dom::communication::SharedStateMutex;
+    use crate::dom::test::*;
+    use crate::dom::Dependencies;
+    use crate::web::api::get_spec;
+    use crate::web::server::test::*;
+
+    static OPENAPI_PATH: &str = "/openapi.json";
+
+    fn snapshot_path() -> PathBuf {
+        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("openapi.json");
+        path
+    }
+
+    #[rstest]
+    fn test_openapi_spec_matches_snapshot() {
+        let spec = get_spec("/api/v1");
+        let json = serde_json::to_string_pretty(&spec).unwrap() + "\n";
+
+        // run with UPDATE_OPENAPI_SNAPSHOT=1 to update the snapshot after changing the API
+        if std::env::var_os("UPDATE_OPENAPI_SNAPSHOT").is_some() {
+            std::fs::write(snapshot_path(), &json).unwrap();
+        }
+
+        let snapshot = std::fs::read_to_string(snapshot_path()).unwrap();
+        assert!(
+            snapshot == json,
+            "the OpenAPI description has changed, run the tests with UPDATE_OPENAPI_SNAPSHOT=1 \
+            and commit the updated openapi.json"
+        );
+    }
+
+    #[rstest]
+    fn test_openapi_spec_is_only_generated_once_used() {
+        let spec = OpenApiSpec::new("/api/v1");
+        assert!(spec.serialized.get().is_none());
+
+        spec.prepare();
+        let serialized = spec.serialized.get().unwrap();
+        assert_eq!(
+            serialized.json,
+            serde_json::to_string(&get_spec("/api/v1")).unwrap()
+        );
+    }
+
+    #[rstest]
+    #[case(None, false)]
+    #[case(Some("gzip, deflate, br"), true)]
+    #[case(Some("deflate"), false)]
+    fn test_web_api_serves_openapi_spec(
+        config: Configuration,
+        shared_state: Arc<SharedStateMutex>,
+        mocked_server_control: MockServerControl,
+        dependencies: Dependencies,
+        ip: IpAddr,
+        port: u16,
+        log_level: LogLevel,
+        #[case] accept_encoding: Option<&'static str>,
+        #[case] gzip: bool,
+    ) {
+        // SETUP
+        let client = get_client(
+            &config,
+            shared_state,
+            mocked_server_control,
+            dependencies,
+            ip,
+            port,
+            log_level,
+        );
+
+        // TESTING
+        let mut request = client.get(get_api_endpoint(OPENAPI_PATH));
+        if let Some(accept_encoding) = accept_encoding {
+            request = request.header(Header::new("Accept-Encoding", accept_encoding));
+        }
+        let response = request.dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(
+            response.headers().get_one("Content-Encoding"),
+            if gzip { Some("gzip") } else { None }
+        );
+
+        let body = response.into_bytes().unwrap();
+        let json = if gzip {
+            let mut json = String::new();
+            GzDecoder::new(body.as_slice())
+                .read_to_string(&mut json)
+                .unwrap();
+            json
+        } else {
+            String::from_utf8(body).unwrap()
+        };
+
+        let spec: OpenApi = serde_json::from_str(&json).unwrap();
+        assert_eq!(spec.info.version, PKG_VERSION);
+        assert_eq!(spec.servers[0].url, "/api/v1");
+        assert!(spec.paths.contains_key("/status"));
+    }
+}
@@ -0,0 +1,134 @@
+use std::sync::Arc;
+
+use rocket::get;
+use rocket::serde::json::Json;
+use rocket_okapi::{openapi, JsonSchema};
+use serde::{Deserialize, Serialize};
+
+use crate::configuration::Configuration;
+use crate::dom::communication::SharedStateMutex;
+use crate::dom::DeviceId;
+
+#[derive(Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Person {
+    id: String,
+    name: String,
+    is_home: bool,
+    devices: Vec<String>,
+}
+
+#[derive(Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Persons {
+    anyone_home: bool,
+    persons: Vec<Person>,
+}
+
+impl Persons {
+    pub fn new(persons: Vec<Person>) -> Self {
+        Self {
+            anyone_home: persons.iter().any(|person| person.is_home),
+            persons,
+        }
+    }
+}
+
+#[openapi(tag = "General")]
+#[get("/persons")]
+pub fn get_persons(
+    config: &rocket::State<Configuration>,
+    shared_state: &rocket::State<Arc<SharedStateMutex>>,
+) -> Json<Persons> {
+    // get the devices from the shared state
+    let shared_state = shared_state.lock().unwrap();
+    let devices = shared_state.get_devices();
+
+    let mut persons: Vec<Person> = config
+        .persons
+        .iter()
+        .map(|(person_id, person)| {
+            // a person is home if any of its devices is online
+            let is_home = person.devices.iter().any(|person_device_id| {
+                let person_device_id = DeviceId::from(person_device_id);
+                devices
+                    .iter()
+                    .any(|device| *device.id() == person_device_id && device.is_online())
+            });
+
+            Person {
+                id: person_id.to_string(),
+                name: person.name.clone(),
+                is_home,
+                devices: person.devices.iter().map(|id| id.to_string()).collect(),
+            }
+        })
+        .collect();
+    persons.sort_by(|a, b| a.id.cmp(&b.id));
+
+    Json(Persons::new(persons))
+}
+
+#[cfg(test)]
+mod test {
+    use std::net::IpAddr;
+    use std::sync::Arc;
+
+    use rocket::http::{ContentType, Status};
+    use rocket::log::LogLevel;
+    use rstest::*;
+
+    use super::*;
+    use crate::configuration;
+    use crate::configuration::Configuration;
+    use crate::control::test::*;
+    use crate::dom::communication::SharedStateMutex;
+    use crate::dom::device::test::*;
+    use crate::dom::test::*;
+    use crate::dom::Dependencies;
+    use crate::web::server::test::*;
+
+    #[rstest]
+    fn test_web_api_can_get_persons(
+        mut config: Configuration,
+        shared_state: Arc<SharedStateMutex>,
+        mocked_server_control: MockServerControl,
+        dependencies: Dependencies,
+        ip: IpAddr,
+        port: u16,
+        log_level: LogLevel,
+    ) {
+        // SETUP
+        config.persons.insert(
+            "dad".parse().unwrap(),
+            configuration::Person {
+                name: "Dad".to_string(),
+                devices: vec![MACHINE_ID.parse().unwrap()],
+            },
+        );
+
+        // TESTING
+        let client = get_client(
+            &config,
+            shared_state,
+            mocked_server_control,
+            dependencies,
+            ip,
+            port,
+            log_level,
+        );
+
+        let response = client.get(get_api_endpoint("/persons")).dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.content_type(), Some(ContentType::JSON));
+
+        let expected_persons = Persons::new(vec![Person {
+            id: "dad".to_string(),
+            name: "Dad".to_string(),
+            is_home: false,
+            devices: vec![MACHINE_ID.to_string()],
+        }]);
+        assert_eq!(response.into_json::<Persons>(), Some(expected_persons));
+    }
+}
@@ -0,0 +1,172 @@
+use std::sync::Arc;
+
+use rocket::get;
+use rocket::http::Status;
+use rocket::serde::json::Json;
+use rocket_okapi::{openapi, JsonSchema};
+use serde::{Deserialize, Serialize};
+
+use crate::dom::communication::Readiness;
+
+#[derive(Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Check {
+    name: String,
+    ok: bool,
+}
+
+impl Check {
+    fn new(name: &str, ok: bool) -> Self {
+        Self {
+            name: name.to_string(),
+            ok,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Probe {
+    ok: bool,
+    checks: Vec<Check>,
+}
+
+impl Probe {
+    fn from_checks(checks: Vec<Check>) -> (Status, Json<Probe>) {
+        let ok = checks.iter().all(|check| check.ok);
+        let status = if ok {
+            Status::Ok
+        } else {
+            Status::ServiceUnavailable
+        };
+
+        (status, Json(Probe { ok, checks }))
+    }
+}
+
+#[openapi(tag = "General")]
+#[get("/livez")]
+pub fn get_livez(readiness: &rocket::State<Arc<Readiness>>) -> (Status, Json<Probe>) {
+    // the runtime is responsive if this handler runs and the monitor keeps running
+    Probe::from_checks(vec![Check::new("monitor", !readiness.is_monitor_stalled())])
+}
+
+#[openapi(tag = "General")]
+#[get("/readyz")]
+pub fn get_readyz(readiness: &rocket::State<Arc<Readiness>>) -> (Status, Json<Probe>) {
+    Probe::from_checks(vec![
+        Check::new("config", true),
+        Check::new("monitor", readiness.is_monitor_started()),
+        Check::new("pinger", readiness.is_pinger_running()),
+        Check::new("interface", readiness.is_interface_present()),
+    ])
+}
+
+#[cfg(test)]
+mod test {
+    use std::net::IpAddr;
+    use std::sync::Arc;
+
+    use rocket::http::Status;
+    use rocket::log::LogLevel;
+    use rstest::*;
+
+    use super::*;
+    use crate::configuration::Configuration;
+    use crate::control::test::*;
+    use crate::dom::communication::SharedStateMutex;
+    use crate::dom::test::*;
+    use crate::dom::Dependencies;
+    use crate::web::server::test::*;
+
+    #[rstest]
+    fn test_web_api_is_alive_before_monitor_started(
+        config: Configuration,
+        shared_state: Arc<SharedStateMutex>,
+        mocked_server_control: MockServerControl,
+        dependencies: Dependencies,
+        ip: IpAddr,
+        port: u16,
+        log_level: LogLevel,
+    ) {
+        // TESTING
+        let client = get_client(
+            &config,
+            shared_state,
+            mocked_server_control,
+            dependencies,
+            ip,
+            port,
+            log_level,
+        );
+
+        let response = client.get(get_api_endpoint("/livez")).dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+    }
+
+    #[rstest]
+    fn test_web_api_is_not_ready_before_monitor_started(
+        config: Configuration,
+        shared_state: Arc<SharedStateMutex>,
+        mocked_server_control: MockServerControl,
+        dependencies: Dependencies,
+        ip: IpAddr,
+        port: u16,
+        log_level: LogLevel,
+    ) {
+        // TESTING
+        let client = get_client(
+            &config,
+            shared_state,
+            mocked_server_control,
+            dependencies,
+            ip,
+            port,
+            log_level,
+        );
+
+        let response = client.get(get_api_endpoint("/readyz")).dispatch();
+
+        assert_eq!(response.status(), Status::ServiceUnavailable);
+
+        let probe = response.into_json::<Probe>().unwrap();
+        assert!(!probe.ok);
+        assert!(probe
+            .checks
+            .iter()
+            .any(|check| check.name == "monitor" && !check.ok));
+    }
+
+    #[rstest]
+    fn test_web_api_is_ready_once_monitor_and_pinger_running(
+        config: Configuration,
+        shared_state: Arc<SharedStateMutex>,
+        mocked_server_control: MockServerControl,
+        dependencies: Dependencies,
+        ip: IpAddr,
+        port: u16,
+        log_level: LogLevel,
+    ) {
+        // SETUP
+        let readiness = Arc::new(Readiness::new("lo"));
+        readiness.heartbeat();
+        readiness.set_pinger_running();
+
+        // TESTING
+        let client = get_client_with_readiness(
+            &config,
+            shared_state,
+            mocked_server_control,
+            dependencies,
+            readiness,
+            ip,
+            port,
+            log_level,
+        );
+
+        let response = client.get(get_api_endpoint("/readyz")).dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+    }
+}
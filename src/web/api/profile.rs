@@ -0,0 +1,171 @@
+use std::result::Result;
+use std::sync::Arc;
+
+use log::info;
+use rocket::serde::json::Json;
+use rocket::{get, put};
+use rocket_okapi::{openapi, JsonSchema};
+use serde::{Deserialize, Serialize};
+
+use crate::dom::communication::Profiles;
+use crate::web::api::UnknownProfileError;
+
+#[derive(Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileStatus {
+    /// Active profile (missing if the configured dependencies are active).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    profile: Option<String>,
+    profiles: Vec<String>,
+}
+
+impl From<&Profiles> for ProfileStatus {
+    fn from(profiles: &Profiles) -> Self {
+        Self {
+            profile: profiles.active(),
+            profiles: profiles.names(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileSelection {
+    /// Profile to activate (the configured dependencies if missing).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    profile: Option<String>,
+}
+
+/// Returns the active and all configured profiles.
+#[openapi(tag = "General")]
+#[get("/profile")]
+pub fn get_profile(profiles: &rocket::State<Arc<Profiles>>) -> Json<ProfileStatus> {
+    Json(ProfileStatus::from(profiles.inner().as_ref()))
+}
+
+/// Activates the given profile (or the configured dependencies) replacing the dependencies of all
+/// servers.
+#[openapi(tag = "General")]
+#[put("/profile", data = "<selection>")]
+pub fn put_profile(
+    selection: Json<ProfileSelection>,
+    profiles: &rocket::State<Arc<Profiles>>,
+) -> Result<Json<ProfileStatus>, UnknownProfileError> {
+    let profile = selection.into_inner().profile;
+    if !profiles.activate(profile.as_deref()) {
+        return Err(UnknownProfileError::from(profile.unwrap_or_default()));
+    }
+
+    match &profile {
+        Some(profile) => info!("profile {} activated through the web API", profile),
+        None => info!("configured dependencies activated through the web API"),
+    }
+    Ok(Json(ProfileStatus::from(profiles.inner().as_ref())))
+}
+
+#[cfg(test)]
+mod test {
+    use std::net::IpAddr;
+
+    use rocket::http::{ContentType, Status};
+    use rocket::log::LogLevel;
+    use rstest::*;
+    use serde_json::json;
+
+    use super::*;
+    use crate::configuration::{self, Configuration};
+    use crate::control::test::*;
+    use crate::dom::communication::SharedStateMutex;
+    use crate::dom::test::*;
+    use crate::dom::Dependencies;
+    use crate::web::server::test::*;
+
+    #[rstest]
+    fn test_web_api_can_switch_profile(
+        mut config: Configuration,
+        shared_state: Arc<SharedStateMutex>,
+        mocked_server_control: MockServerControl,
+        dependencies: Dependencies,
+        ip: IpAddr,
+        port: u16,
+        log_level: LogLevel,
+    ) {
+        // SETUP
+        config
+            .profiles
+            .insert("vacation".to_string(), configuration::Profile::default());
+
+        let client = get_client(
+            &config,
+            shared_state,
+            mocked_server_control,
+            dependencies,
+            ip,
+            port,
+            log_level,
+        );
+
+        // TESTING
+        let response = client.get(get_api_endpoint("/profile")).dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.content_type(), Some(ContentType::JSON));
+        assert_eq!(
+            response.into_json::<ProfileStatus>(),
+            Some(ProfileStatus {
+                profile: None,
+                profiles: vec!["vacation".to_string()],
+            })
+        );
+
+        let response = client
+            .put(get_api_endpoint("/profile"))
+            .json(&json!({ "profile": "vacation" }))
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(
+            response.into_json::<ProfileStatus>().unwrap().profile,
+            Some("vacation".to_string())
+        );
+
+        let profiles = client.rocket().state::<Arc<Profiles>>().unwrap();
+        assert_eq!(profiles.active(), Some("vacation".to_string()));
+        assert!(profiles.take_change().is_some());
+
+        // switching back to the configured dependencies
+        let response = client
+            .put(get_api_endpoint("/profile"))
+            .json(&json!({}))
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(profiles.active(), None);
+    }
+
+    #[rstest]
+    fn test_web_api_cannot_switch_to_unknown_profile(
+        config: Configuration,
+        shared_state: Arc<SharedStateMutex>,
+        mocked_server_control: MockServerControl,
+        dependencies: Dependencies,
+        ip: IpAddr,
+        port: u16,
+        log_level: LogLevel,
+    ) {
+        // SETUP
+        let client = get_client(
+            &config,
+            shared_state,
+            mocked_server_control,
+            dependencies,
+            ip,
+            port,
+            log_level,
+        );
+
+        // TESTING
+        let response = client
+            .put(get_api_endpoint("/profile"))
+            .json(&json!({ "profile": "party" }))
+            .dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+    }
+}
@@ -0,0 +1,46 @@
+use std::sync::Arc;
+
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::{Request, Response};
+
+use crate::metrics::{Counter, Registry};
+
+pub struct RequestCounter {
+    metrics: Arc<Registry>,
+    api_path: String,
+}
+
+impl RequestCounter {
+    pub fn new(metrics: Arc<Registry>, api_path: &str) -> Self {
+        Self {
+            metrics,
+            api_path: api_path.to_string(),
+        }
+    }
+}
+
+#[rocket::async_trait]
+impl Fairing for RequestCounter {
+    fn info(&self) -> Info {
+        Info {
+            name: "API request counter",
+            kind: Kind::Response,
+        }
+    }
+
+    async fn on_response<'r>(&self, request: &'r Request<'_>, _: &mut Response<'r>) {
+        // only count requests to known web API endpoints
+        let route = match request.route() {
+            Some(route) if route.uri.base().starts_with(&self.api_path) => route,
+            _ => return,
+        };
+
+        self.metrics.increment(
+            Counter::ApiRequests,
+            &[
+                ("method", request.method().as_str()),
+                ("endpoint", route.uri.path()),
+            ],
+        );
+    }
+}
@@ -0,0 +1,60 @@
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::http::Header;
+use rocket::request::{FromRequest, Outcome};
+use rocket::{Request, Response};
+use rocket_okapi::gen::OpenApiGenerator;
+use rocket_okapi::request::{OpenApiFromRequest, RequestHeaderInput};
+
+use crate::dom::TraceContext;
+
+static REQUEST_ID_HEADER: &str = "X-Request-Id";
+
+pub fn get_trace_context<'r>(request: &'r Request<'_>) -> &'r TraceContext {
+    // re-use a trace ID provided by the client otherwise generate a new one
+    request.local_cache(|| match request.headers().get_one(REQUEST_ID_HEADER) {
+        Some(id) if !id.is_empty() => TraceContext::from(id),
+        _ => TraceContext::new(),
+    })
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for TraceContext {
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        Outcome::Success(get_trace_context(request).clone())
+    }
+}
+
+impl<'r> OpenApiFromRequest<'r> for TraceContext {
+    fn from_request_input(
+        _gen: &mut OpenApiGenerator,
+        _name: String,
+        _required: bool,
+    ) -> rocket_okapi::Result<RequestHeaderInput> {
+        Ok(RequestHeaderInput::None)
+    }
+}
+
+pub struct RequestId {}
+
+impl RequestId {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+#[rocket::async_trait]
+impl Fairing for RequestId {
+    fn info(&self) -> Info {
+        Info {
+            name: "Request ID",
+            kind: Kind::Response,
+        }
+    }
+
+    async fn on_response<'r>(&self, request: &'r Request<'_>, response: &mut Response<'r>) {
+        let context = get_trace_context(request);
+        response.set_header(Header::new(REQUEST_ID_HEADER, context.id().to_string()));
+    }
+}
@@ -0,0 +1,152 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Instant;
+
+use log::info;
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::http::{Method, Status};
+use rocket::{Request, Response};
+
+use super::request_id::get_trace_context;
+use crate::configuration::RequestLog;
+
+struct RequestStart(Instant);
+
+/// Derives a short, non-reversible identifier from a bearer token so requests can be attributed
+/// to a client without logging the token itself.
+pub fn get_token_id(request: &Request<'_>) -> Option<String> {
+    let token = request
+        .headers()
+        .get_one("Authorization")?
+        .strip_prefix("Bearer ")?;
+
+    let mut hasher = DefaultHasher::new();
+    token.hash(&mut hasher);
+    Some(format!("{:08x}", hasher.finish() as u32))
+}
+
+pub struct RequestLogger {
+    config: RequestLog,
+    requests: AtomicU64,
+}
+
+impl RequestLogger {
+    pub fn new(config: RequestLog) -> Self {
+        Self {
+            config,
+            requests: AtomicU64::new(0),
+        }
+    }
+
+    fn should_log(&self, method: Method, status: Status) -> bool {
+        if !self.config.enabled {
+            return false;
+        }
+
+        // always log requests changing the state and failed requests
+        if method != Method::Get || status.code >= 400 {
+            return true;
+        }
+
+        // only log every n-th of all other requests
+        let sample_every = self.config.sample_every.max(1);
+        let sample = self.requests.fetch_add(1, Ordering::Relaxed) % sample_every;
+        sample == 0
+    }
+}
+
+#[rocket::async_trait]
+impl Fairing for RequestLogger {
+    fn info(&self) -> Info {
+        Info {
+            name: "API request logger",
+            kind: Kind::Request | Kind::Response,
+        }
+    }
+
+    async fn on_request(&self, request: &mut Request<'_>, _: &mut rocket::Data<'_>) {
+        request.local_cache(|| RequestStart(Instant::now()));
+    }
+
+    async fn on_response<'r>(&self, request: &'r Request<'_>, response: &mut Response<'r>) {
+        if !self.should_log(request.method(), response.status()) {
+            return;
+        }
+
+        let latency = request
+            .local_cache(|| RequestStart(Instant::now()))
+            .0
+            .elapsed();
+        let client_ip = request
+            .client_ip()
+            .map(|ip| ip.to_string())
+            .unwrap_or_else(|| "unknown".to_string());
+
+        info!(
+            "[{}] {} {} {} {}ms from {} (token {})",
+            get_trace_context(request),
+            request.method(),
+            request.uri(),
+            response.status().code,
+            latency.as_millis(),
+            client_ip,
+            get_token_id(request).unwrap_or_else(|| "none".to_string())
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rstest::*;
+
+    use super::*;
+
+    fn request_logger(enabled: bool, sample_every: u64) -> RequestLogger {
+        RequestLogger::new(RequestLog {
+            enabled,
+            sample_every,
+        })
+    }
+
+    #[rstest]
+    fn test_request_logger_logs_nothing_if_disabled() {
+        let logger = request_logger(false, 1);
+
+        assert!(!logger.should_log(Method::Get, Status::Ok));
+        assert!(!logger.should_log(Method::Put, Status::Ok));
+        assert!(!logger.should_log(Method::Get, Status::InternalServerError));
+    }
+
+    #[rstest]
+    #[case(0)]
+    #[case(1)]
+    fn test_request_logger_logs_all_requests_without_sampling(#[case] sample_every: u64) {
+        let logger = request_logger(true, sample_every);
+
+        assert!((0..5).all(|_| logger.should_log(Method::Get, Status::Ok)));
+    }
+
+    #[rstest]
+    fn test_request_logger_samples_successful_get_requests() {
+        let logger = request_logger(true, 3);
+
+        let logged: Vec<bool> = (0..6)
+            .map(|_| logger.should_log(Method::Get, Status::Ok))
+            .collect();
+        assert_eq!(logged, vec![true, false, false, true, false, false]);
+    }
+
+    #[rstest]
+    fn test_request_logger_always_logs_actions_and_failures() {
+        let logger = request_logger(true, 100);
+
+        // the first request is always sampled
+        assert!(logger.should_log(Method::Get, Status::Ok));
+
+        assert!(!logger.should_log(Method::Get, Status::Ok));
+        assert!(logger.should_log(Method::Put, Status::Ok));
+        assert!(logger.should_log(Method::Delete, Status::Ok));
+        assert!(logger.should_log(Method::Get, Status::NotFound));
+    }
+}
@@ -0,0 +1,135 @@
+use std::sync::Arc;
+
+use rocket::get;
+use rocket::serde::json::Json;
+use rocket_okapi::{openapi, JsonSchema};
+use serde::{Deserialize, Serialize};
+
+use crate::configuration::{self, Configuration};
+use crate::dom::communication::SharedStateMutex;
+use crate::dom::DeviceId;
+
+#[derive(Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Room {
+    id: String,
+    name: String,
+    is_online: bool,
+    devices: Vec<String>,
+}
+
+#[derive(Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Rooms {
+    rooms: Vec<Room>,
+}
+
+impl Rooms {
+    pub fn new(rooms: Vec<Room>) -> Self {
+        Self { rooms }
+    }
+}
+
+#[openapi(tag = "General")]
+#[get("/rooms")]
+pub fn get_rooms(
+    config: &rocket::State<Configuration>,
+    shared_state: &rocket::State<Arc<SharedStateMutex>>,
+) -> Json<Rooms> {
+    // get the devices from the shared state
+    let shared_state = shared_state.lock().unwrap();
+    let devices = shared_state.get_devices();
+
+    let mut rooms: Vec<Room> = config
+        .rooms
+        .iter()
+        .map(|(room_id, room)| {
+            let room_devices = configuration::get_room_devices(&config.devices, room_id);
+
+            // a room is online if any of its devices is online
+            let is_online = room_devices.iter().any(|room_device_id| {
+                let room_device_id = DeviceId::from(room_device_id);
+                devices
+                    .iter()
+                    .any(|device| *device.id() == room_device_id && device.is_online())
+            });
+
+            Room {
+                id: room_id.to_string(),
+                name: room.name.clone(),
+                is_online,
+                devices: room_devices.iter().map(|id| id.to_string()).collect(),
+            }
+        })
+        .collect();
+    rooms.sort_by(|a, b| a.id.cmp(&b.id));
+
+    Json(Rooms::new(rooms))
+}
+
+#[cfg(test)]
+mod test {
+    use std::net::IpAddr;
+    use std::sync::Arc;
+
+    use rocket::http::{ContentType, Status};
+    use rocket::log::LogLevel;
+    use rstest::*;
+
+    use super::*;
+    use crate::configuration::Configuration;
+    use crate::control::test::*;
+    use crate::dom::communication::SharedStateMutex;
+    use crate::dom::device::test::*;
+    use crate::dom::test::*;
+    use crate::dom::Dependencies;
+    use crate::web::server::test::*;
+
+    #[rstest]
+    fn test_web_api_can_get_rooms(
+        mut config: Configuration,
+        shared_state: Arc<SharedStateMutex>,
+        mocked_server_control: MockServerControl,
+        dependencies: Dependencies,
+        ip: IpAddr,
+        port: u16,
+        log_level: LogLevel,
+    ) {
+        // SETUP
+        let room_id: configuration::RoomId = "livingroom".parse().unwrap();
+        config.rooms.insert(
+            room_id.clone(),
+            configuration::Room {
+                name: "Living Room".to_string(),
+            },
+        );
+        match config.devices.get_mut(&MACHINE_ID.parse().unwrap()) {
+            Some(configuration::Device::Machine(machine)) => machine.room = Some(room_id),
+            _ => panic!("missing machine in configuration"),
+        };
+
+        // TESTING
+        let client = get_client(
+            &config,
+            shared_state,
+            mocked_server_control,
+            dependencies,
+            ip,
+            port,
+            log_level,
+        );
+
+        let response = client.get(get_api_endpoint("/rooms")).dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.content_type(), Some(ContentType::JSON));
+
+        let expected_rooms = Rooms::new(vec![Room {
+            id: "livingroom".to_string(),
+            name: "Living Room".to_string(),
+            is_online: false,
+            devices: vec![MACHINE_ID.to_string()],
+        }]);
+        assert_eq!(response.into_json::<Rooms>(), Some(expected_rooms));
+    }
+}
@@ -0,0 +1,62 @@
+use std::fmt;
+use std::io::Cursor;
+
+use rocket::response::Responder;
+use rocket::{http, response, Request, Response};
+use rocket_okapi::gen::OpenApiGenerator;
+use rocket_okapi::okapi::openapi3::Responses;
+use rocket_okapi::response::OpenApiResponderInner;
+
+use crate::networking::ActionInProgress;
+
+#[derive(Debug)]
+pub struct ActionInProgressError(ActionInProgress);
+
+impl std::error::Error for ActionInProgressError {}
+
+impl From<ActionInProgress> for ActionInProgressError {
+    fn from(in_progress: ActionInProgress) -> Self {
+        Self(in_progress)
+    }
+}
+
+impl fmt::Display for ActionInProgressError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[ActionInProgressError] {}", self.0)
+    }
+}
+
+impl<'r, 'o: 'r> Responder<'r, 'o> for ActionInProgressError {
+    fn respond_to(self, _: &Request) -> response::Result<'o> {
+        let error_msg = self.to_string();
+        Response::build()
+            .header(http::ContentType::Plain)
+            .status(http::Status::Conflict)
+            .sized_body(error_msg.len(), Cursor::new(error_msg))
+            .ok()
+    }
+}
+
+impl OpenApiResponderInner for ActionInProgressError {
+    fn responses(_: &mut OpenApiGenerator) -> rocket_okapi::Result<Responses> {
+        let mut responses = Responses::default();
+        add_409_error(&mut responses);
+        Ok(responses)
+    }
+}
+
+fn add_409_error(responses: &mut Responses) {
+    responses
+        .responses
+        .entry("409".to_owned())
+        .or_insert_with(|| {
+            let response = rocket_okapi::okapi::openapi3::Response {
+                description: "\
+                    [409 Conflict](https://developer.mozilla.org/en-US/docs/Web/HTTP/Status/409)\n\n\
+                    This response is given when the same wakeup / shutdown of the server is already in progress or has just succeeded.\
+                    ".to_owned(),
+                ..Default::default()
+            };
+            response.into()
+        });
+}
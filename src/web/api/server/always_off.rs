@@ -12,7 +12,7 @@ use crate::web::api::server::UnknownDeviceError;
 
 #[derive(Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
 pub struct AlwaysOffResponse {
-    always_off: bool,
+    pub always_off: bool,
 }
 
 #[openapi(tag = "Server")]
@@ -32,13 +32,12 @@ pub fn get_always_off(
 pub fn post_always_off(
     server: String,
     state: &rocket::State<Vec<ServerControl>>,
+    files_api: &rocket::State<api::FilesApiStatus>,
 ) -> Result<Json<AlwaysOffResponse>, api::Error> {
     let control = get_server_control(state.inner(), server)?;
 
-    match control.always_off.set_always_off() {
-        Ok(_) => Ok(Json(AlwaysOffResponse { always_off: true })),
-        Err(e) => Err(api::Error::from(api::InternalServerError::from(e))),
-    }
+    files_api.record(control.always_off.set_always_off())?;
+    Ok(Json(AlwaysOffResponse { always_off: true }))
 }
 
 #[openapi(tag = "Server")]
@@ -46,13 +45,12 @@ pub fn post_always_off(
 pub fn delete_always_off(
     server: String,
     state: &rocket::State<Vec<ServerControl>>,
+    files_api: &rocket::State<api::FilesApiStatus>,
 ) -> Result<Json<AlwaysOffResponse>, api::Error> {
     let control = get_server_control(state.inner(), server)?;
 
-    match control.always_off.reset_always_off() {
-        Ok(_) => Ok(Json(AlwaysOffResponse { always_off: false })),
-        Err(e) => Err(api::Error::from(api::InternalServerError::from(e))),
-    }
+    files_api.record(control.always_off.reset_always_off())?;
+    Ok(Json(AlwaysOffResponse { always_off: false }))
 }
 
 #[cfg(test)]
@@ -12,7 +12,7 @@ use crate::web::api::server::UnknownDeviceError;
 
 #[derive(Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
 pub struct AlwaysOnResponse {
-    always_on: bool,
+    pub always_on: bool,
 }
 
 #[openapi(tag = "Server")]
@@ -32,13 +32,12 @@ pub fn get_always_on(
 pub fn post_always_on(
     server: String,
     state: &rocket::State<Vec<ServerControl>>,
+    files_api: &rocket::State<api::FilesApiStatus>,
 ) -> Result<Json<AlwaysOnResponse>, api::Error> {
     let control = get_server_control(state.inner(), server)?;
 
-    match control.always_on.set_always_on() {
-        Ok(_) => Ok(Json(AlwaysOnResponse { always_on: true })),
-        Err(e) => Err(api::Error::from(api::InternalServerError::from(e))),
-    }
+    files_api.record(control.always_on.set_always_on())?;
+    Ok(Json(AlwaysOnResponse { always_on: true }))
 }
 
 #[openapi(tag = "Server")]
@@ -46,13 +45,12 @@ pub fn post_always_on(
 pub fn delete_always_on(
     server: String,
     state: &rocket::State<Vec<ServerControl>>,
+    files_api: &rocket::State<api::FilesApiStatus>,
 ) -> Result<Json<AlwaysOnResponse>, api::Error> {
     let control = get_server_control(state.inner(), server)?;
 
-    match control.always_on.reset_always_on() {
-        Ok(_) => Ok(Json(AlwaysOnResponse { always_on: false })),
-        Err(e) => Err(api::Error::from(api::InternalServerError::from(e))),
-    }
+    files_api.record(control.always_on.reset_always_on())?;
+    Ok(Json(AlwaysOnResponse { always_on: false }))
 }
 
 #[cfg(test)]
@@ -225,6 +223,18 @@ mod test {
             .dispatch();
 
         assert_eq!(response.status(), Status::InternalServerError);
+        assert_eq!(response.content_type(), Some(ContentType::JSON));
+
+        let error = response.into_json::<serde_json::Value>().unwrap();
+        assert_eq!(error["code"], "permissionDenied");
+        assert!(error["hint"].is_string());
+
+        // the failure is reported as part of the health
+        let response = client.get(get_api_endpoint("/health")).dispatch();
+        let health = response.into_json::<serde_json::Value>().unwrap();
+        assert_eq!(health["status"], "degraded");
+        assert_eq!(health["filesApi"]["consecutiveFailures"], 1);
+        assert_eq!(health["filesApi"]["lastError"]["code"], "permissionDenied");
     }
 
     #[rstest]
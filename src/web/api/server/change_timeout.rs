@@ -0,0 +1,106 @@
+use std::result::Result;
+
+use rocket::delete;
+use rocket::serde::json::Json;
+use rocket_okapi::{openapi, JsonSchema};
+use serde::{Deserialize, Serialize};
+
+use super::get_server_control;
+use crate::control::ServerControl;
+use crate::dom::communication::ChangeTimeoutResetSender;
+use crate::web::api;
+
+#[derive(Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeTimeoutResponse {
+    pub change_timeout: u64,
+}
+
+/// Ends the timeout after the last wakeup / shutdown of the server so that the monitor
+/// re-evaluates whether to wake it up or shut it down right away.
+#[openapi(tag = "Server")]
+#[delete("/server/<server>/change-timeout")]
+pub fn delete_change_timeout(
+    server: String,
+    state: &rocket::State<Vec<ServerControl>>,
+    change_timeout_resets: &rocket::State<ChangeTimeoutResetSender>,
+) -> Result<Json<ChangeTimeoutResponse>, api::Error> {
+    let control = get_server_control(state.inner(), server)?;
+
+    match change_timeout_resets.send(control.server.machine.id.clone()) {
+        Ok(_) => Ok(Json(ChangeTimeoutResponse { change_timeout: 0 })),
+        Err(e) => Err(api::Error::from(api::InternalServerError::from(
+            anyhow::Error::from(e),
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::net::IpAddr;
+    use std::sync::Arc;
+
+    use rocket::http::{ContentType, Status};
+    use rocket::log::LogLevel;
+    use rstest::*;
+
+    use super::*;
+    use crate::configuration::Configuration;
+    use crate::control::test::*;
+    use crate::dom::communication::{change_timeout_reset_channel, SharedStateMutex};
+    use crate::dom::device::test::*;
+    use crate::dom::test::*;
+    use crate::dom::{Dependencies, DeviceId};
+    use crate::web::api::server::test::*;
+    use crate::web::server::test::*;
+
+    #[rstest]
+    fn test_web_api_can_delete_change_timeout(
+        config: Configuration,
+        shared_state: Arc<SharedStateMutex>,
+        mocked_server_control: MockServerControl,
+        dependencies: Dependencies,
+        server_id: DeviceId,
+        ip: IpAddr,
+        port: u16,
+        log_level: LogLevel,
+    ) {
+        // SETUP
+        let (change_timeout_resets, mut change_timeout_reset_receiver) =
+            change_timeout_reset_channel();
+
+        // TESTING
+        let client = get_client_with_change_timeout_resets(
+            &config,
+            shared_state,
+            mocked_server_control,
+            dependencies,
+            change_timeout_resets,
+            ip,
+            port,
+            log_level,
+        );
+
+        let response = client
+            .delete(get_server_api_endpoint("/change-timeout", &server_id))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.content_type(), Some(ContentType::JSON));
+        assert_eq!(
+            response.into_json::<ChangeTimeoutResponse>().unwrap(),
+            ChangeTimeoutResponse { change_timeout: 0 }
+        );
+
+        assert_eq!(change_timeout_reset_receiver.try_recv().unwrap(), server_id);
+
+        // unknown servers can't be reset
+        let response = client
+            .delete(get_server_api_endpoint(
+                "/change-timeout",
+                &"unknown".parse().unwrap(),
+            ))
+            .dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+    }
+}
@@ -0,0 +1,147 @@
+use std::result::Result;
+
+use rocket::serde::json::Json;
+use rocket::{delete, get};
+use rocket_okapi::{openapi, JsonSchema};
+use serde::{Deserialize, Serialize};
+
+use super::get_server_control;
+use crate::control::ServerControl;
+use crate::web::api::server::UnknownDeviceError;
+
+#[derive(Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ControlDisabledResponse {
+    pub control_disabled: bool,
+}
+
+/// Returns whether the server is only monitored because it failed to shut down too many times in
+/// a row.
+#[openapi(tag = "Server")]
+#[get("/server/<server>/control_disabled")]
+pub fn get_control_disabled(
+    server: String,
+    state: &rocket::State<Vec<ServerControl>>,
+) -> Result<Json<ControlDisabledResponse>, UnknownDeviceError> {
+    let control = get_server_control(state.inner(), server)?;
+    Ok(Json(ControlDisabledResponse {
+        control_disabled: control.control_switch.is_disabled(),
+    }))
+}
+
+/// Re-enables the automatic wakeups / shutdowns of the server after its control has been disabled.
+#[openapi(tag = "Server")]
+#[delete("/server/<server>/control_disabled")]
+pub fn delete_control_disabled(
+    server: String,
+    state: &rocket::State<Vec<ServerControl>>,
+) -> Result<Json<ControlDisabledResponse>, UnknownDeviceError> {
+    let control = get_server_control(state.inner(), server)?;
+    control.control_switch.enable();
+    Ok(Json(ControlDisabledResponse {
+        control_disabled: false,
+    }))
+}
+
+#[cfg(test)]
+mod test {
+    use std::net::IpAddr;
+    use std::sync::Arc;
+
+    use rocket::http::{ContentType, Status};
+    use rocket::log::LogLevel;
+    use rstest::*;
+
+    use super::*;
+    use crate::configuration::Configuration;
+    use crate::control::test::*;
+    use crate::dom::communication::SharedStateMutex;
+    use crate::dom::device::test::*;
+    use crate::dom::test::*;
+    use crate::dom::{Dependencies, DeviceId};
+    use crate::web::api::server::test::*;
+    use crate::web::server::test::*;
+
+    #[rstest]
+    fn test_web_api_can_reenable_disabled_control(
+        config: Configuration,
+        shared_state: Arc<SharedStateMutex>,
+        mocked_server_control: MockServerControl,
+        dependencies: Dependencies,
+        ip: IpAddr,
+        port: u16,
+        log_level: LogLevel,
+        server_id: DeviceId,
+    ) {
+        // SETUP
+        let client = get_client(
+            &config,
+            shared_state,
+            mocked_server_control,
+            dependencies,
+            ip,
+            port,
+            log_level,
+        );
+        let control_switch = client.rocket().state::<Vec<ServerControl>>().unwrap()[0]
+            .control_switch
+            .clone();
+        control_switch.disable();
+
+        // TESTING
+        let response = client
+            .get(get_server_api_endpoint("/control_disabled", &server_id))
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.content_type(), Some(ContentType::JSON));
+        assert_eq!(
+            response.into_json::<ControlDisabledResponse>(),
+            Some(ControlDisabledResponse {
+                control_disabled: true
+            })
+        );
+
+        let response = client
+            .delete(get_server_api_endpoint("/control_disabled", &server_id))
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(
+            response.into_json::<ControlDisabledResponse>(),
+            Some(ControlDisabledResponse {
+                control_disabled: false
+            })
+        );
+        assert!(!control_switch.is_disabled());
+    }
+
+    #[rstest]
+    fn test_web_api_cannot_reenable_control_of_invalid_server(
+        config: Configuration,
+        shared_state: Arc<SharedStateMutex>,
+        mocked_server_control: MockServerControl,
+        dependencies: Dependencies,
+        ip: IpAddr,
+        port: u16,
+        log_level: LogLevel,
+    ) {
+        // TESTING
+        let client = get_client(
+            &config,
+            shared_state,
+            mocked_server_control,
+            dependencies,
+            ip,
+            port,
+            log_level,
+        );
+
+        let response = client
+            .delete(get_server_api_endpoint(
+                "/control_disabled",
+                &"invalidserverid".parse().unwrap(),
+            ))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::NotFound);
+    }
+}
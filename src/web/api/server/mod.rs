@@ -1,14 +1,30 @@
+mod action_in_progress_error;
 mod always_off;
 mod always_on;
+mod authentication_error;
+mod change_timeout;
+mod control_disabled;
+mod schedule_preview;
 mod shutdown;
 mod status;
+mod test_connection;
+mod test_wakeup;
+mod unconfirmed_shutdown_error;
 mod unknown_device_error;
 mod wakeup;
 
+pub use action_in_progress_error::ActionInProgressError;
 pub use always_off::*;
 pub use always_on::*;
+pub use authentication_error::AuthenticationError;
+pub use change_timeout::*;
+pub use control_disabled::*;
+pub use schedule_preview::*;
 pub use shutdown::*;
 pub use status::*;
+pub use test_connection::*;
+pub use test_wakeup::*;
+pub use unconfirmed_shutdown_error::UnconfirmedShutdownError;
 pub use unknown_device_error::UnknownDeviceError;
 pub use wakeup::*;
 
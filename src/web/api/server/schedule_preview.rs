@@ -0,0 +1,196 @@
+use std::collections::HashMap;
+use std::result::Result;
+use std::sync::Arc;
+
+use chrono::{Duration, Local, Timelike};
+use rocket::get;
+use rocket::serde::json::Json;
+use rocket_okapi::{openapi, JsonSchema};
+use serde::{Deserialize, Serialize};
+
+use super::get_server_control;
+use crate::control::ServerControl;
+use crate::dom::communication::Profiles;
+use crate::dom::{self, DeviceId, PlannedAction, Schedule};
+use crate::web::api;
+
+const DEFAULT_HOURS: u32 = 24;
+const MAX_HOURS: u32 = 168;
+
+/// Schedules (e.g. calendars) during which the servers are never shut down automatically.
+pub struct Blackouts(pub HashMap<DeviceId, Vec<Schedule>>);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum ScheduleAction {
+    Wakeup,
+    Shutdown,
+    BlackoutStart,
+    BlackoutEnd,
+}
+
+impl From<PlannedAction> for ScheduleAction {
+    fn from(action: PlannedAction) -> Self {
+        match action {
+            PlannedAction::Wakeup => Self::Wakeup,
+            PlannedAction::Shutdown => Self::Shutdown,
+            PlannedAction::BlackoutStart => Self::BlackoutStart,
+            PlannedAction::BlackoutEnd => Self::BlackoutEnd,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleEvent {
+    pub time: String,
+    pub action: ScheduleAction,
+    /// Schedules which started (wakeup / blackout start) or ended (shutdown / blackout end).
+    pub schedules: Vec<String>,
+}
+
+#[derive(Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SchedulePreview {
+    pub from: String,
+    pub until: String,
+    /// Schedules of the active profile the server depends on.
+    pub schedules: Vec<String>,
+    pub blackouts: Vec<String>,
+    pub events: Vec<ScheduleEvent>,
+}
+
+/// Returns the wakeups, shutdowns and blackouts of the server planned by its schedules within the
+/// given number of hours (defaults to 24, at most 168). The server is only shut down if none of
+/// its other dependencies is online and not during a blackout.
+#[openapi(tag = "Server")]
+#[get("/server/<server>/schedule/preview?<hours>")]
+pub fn get_schedule_preview(
+    server: String,
+    hours: Option<u32>,
+    state: &rocket::State<Vec<ServerControl>>,
+    profiles: &rocket::State<Arc<Profiles>>,
+    blackouts: &rocket::State<Blackouts>,
+) -> Result<Json<SchedulePreview>, api::Error> {
+    let control = get_server_control(state.inner(), server)?;
+    let server_id = &control.server.machine.id;
+
+    let schedules = profiles.schedules(server_id);
+    let blackouts = blackouts.0.get(server_id).cloned().unwrap_or_default();
+    let ids = |schedules: &[Schedule]| -> Vec<String> {
+        schedules
+            .iter()
+            .map(|schedule| schedule.id().to_string())
+            .collect()
+    };
+
+    // the schedules are evaluated at full minutes
+    let now = Local::now();
+    let from = now
+        .with_second(0)
+        .and_then(|now| now.with_nanosecond(0))
+        .unwrap_or(now);
+    let until = from + Duration::hours(hours.unwrap_or(DEFAULT_HOURS).min(MAX_HOURS) as i64);
+    let events = dom::plan(&schedules, &blackouts, &from, &until)
+        .into_iter()
+        .map(|change| ScheduleEvent {
+            time: change.time.to_rfc3339(),
+            action: change.action.into(),
+            schedules: change
+                .schedules
+                .iter()
+                .map(|schedule| schedule.to_string())
+                .collect(),
+        })
+        .collect();
+
+    Ok(Json(SchedulePreview {
+        from: from.to_rfc3339(),
+        until: until.to_rfc3339(),
+        schedules: ids(&schedules),
+        blackouts: ids(&blackouts),
+        events,
+    }))
+}
+
+#[cfg(test)]
+mod test {
+    use std::net::IpAddr;
+
+    use chrono::DateTime;
+    use rocket::http::{ContentType, Status};
+    use rocket::log::LogLevel;
+    use rstest::*;
+
+    use super::*;
+    use crate::configuration::Configuration;
+    use crate::control::test::*;
+    use crate::dom::communication::SharedStateMutex;
+    use crate::dom::device::test::*;
+    use crate::dom::test::*;
+    use crate::dom::Dependencies;
+    use crate::web::api::server::test::*;
+    use crate::web::server::test::*;
+
+    #[rstest]
+    fn test_web_api_can_get_schedule_preview(
+        config: Configuration,
+        shared_state: Arc<SharedStateMutex>,
+        mocked_server_control: MockServerControl,
+        dependencies: Dependencies,
+        server_id: DeviceId,
+        ip: IpAddr,
+        port: u16,
+        log_level: LogLevel,
+    ) {
+        // TESTING
+        let client = get_client(
+            &config,
+            shared_state,
+            mocked_server_control,
+            dependencies,
+            ip,
+            port,
+            log_level,
+        );
+
+        let response = client
+            .get(get_server_api_endpoint(
+                "/schedule/preview?hours=48",
+                &server_id,
+            ))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.content_type(), Some(ContentType::JSON));
+
+        // the server doesn't depend on any schedules
+        let preview = response.into_json::<SchedulePreview>().unwrap();
+        let from = DateTime::parse_from_rfc3339(&preview.from).unwrap();
+        let until = DateTime::parse_from_rfc3339(&preview.until).unwrap();
+        assert_eq!(until - from, Duration::hours(48));
+        assert!(preview.schedules.is_empty());
+        assert!(preview.events.is_empty());
+
+        // the preview is limited to a week
+        let response = client
+            .get(get_server_api_endpoint(
+                "/schedule/preview?hours=1000",
+                &server_id,
+            ))
+            .dispatch();
+        let preview = response.into_json::<SchedulePreview>().unwrap();
+        let from = DateTime::parse_from_rfc3339(&preview.from).unwrap();
+        let until = DateTime::parse_from_rfc3339(&preview.until).unwrap();
+        assert_eq!(until - from, Duration::hours(MAX_HOURS as i64));
+
+        // unknown servers don't have a schedule
+        let response = client
+            .get(get_server_api_endpoint(
+                "/schedule/preview",
+                &"unknown".parse().unwrap(),
+            ))
+            .dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+    }
+}
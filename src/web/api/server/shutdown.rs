@@ -1,26 +1,202 @@
+use std::collections::HashMap;
 use std::result::Result;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
-use rocket::put;
-use rocket_okapi::openapi;
+use log::{error, info};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use rocket::response::status::Accepted;
+use rocket::response::Responder;
+use rocket::serde::json::Json;
+use rocket::{put, response, Request};
+use rocket_okapi::gen::OpenApiGenerator;
+use rocket_okapi::okapi::openapi3::Responses;
+use rocket_okapi::response::OpenApiResponderInner;
+use rocket_okapi::{openapi, JsonSchema};
+use serde::{Deserialize, Serialize};
+use subtle::ConstantTimeEq;
+use tokio_util::sync::CancellationToken;
 
 use super::get_server_control;
-use crate::control::ServerControl;
-use crate::web::api;
+use crate::configuration::{self, Configuration};
+use crate::control::{ServerControl, ACTION_TIMEOUT};
+use crate::dom::{ActionContext, DeviceId, TraceContext, TransitionCause};
+use crate::networking::Action;
+use crate::utils::Instant;
+use crate::web::api::server::{
+    ActionInProgressError, AuthenticationError, UnconfirmedShutdownError,
+};
+use crate::web::api::{self, ActionAccepted, Actions};
+
+/// Pending shutdowns which still have to be confirmed.
+pub struct ShutdownConfirmations {
+    pending: Mutex<HashMap<DeviceId, (String, Instant)>>,
+}
+
+impl ShutdownConfirmations {
+    pub fn new() -> Self {
+        Self {
+            pending: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns a new token which has to be used to confirm the shutdown of the given server
+    /// within the given duration.
+    pub fn request(&self, server_id: &DeviceId, validity: Duration) -> String {
+        let token = format!("{:016x}", OsRng.next_u64());
+        self.pending.lock().unwrap().insert(
+            server_id.clone(),
+            (token.clone(), Instant::now() + validity),
+        );
+
+        token
+    }
+
+    /// Checks (and consumes) the token confirming the shutdown of the given server.
+    pub fn confirm(&self, server_id: &DeviceId, token: &str) -> bool {
+        let mut pending = self.pending.lock().unwrap();
+        match pending.get(server_id) {
+            // don't leak how much of the token is correct through the time of the comparison
+            Some((expected_token, expiry))
+                if bool::from(expected_token.as_bytes().ct_eq(token.as_bytes())) =>
+            {
+                let confirmed = Instant::now() <= *expiry;
+                pending.remove(server_id);
+                confirmed
+            }
+            _ => false,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ShutdownConfirmation {
+    pub confirmation_token: String,
+    pub expires_in: u64,
+}
+
+pub enum ShutdownResponse {
+    ShutDown(ActionAccepted),
+    ConfirmationRequired(ShutdownConfirmation),
+}
+
+impl<'r, 'o: 'r> Responder<'r, 'o> for ShutdownResponse {
+    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'o> {
+        match self {
+            Self::ShutDown(accepted) => Accepted(Some(Json(accepted))).respond_to(req),
+            Self::ConfirmationRequired(confirmation) => {
+                Accepted(Some(Json(confirmation))).respond_to(req)
+            }
+        }
+    }
+}
+
+impl OpenApiResponderInner for ShutdownResponse {
+    fn responses(gen: &mut OpenApiGenerator) -> rocket_okapi::Result<Responses> {
+        rocket_okapi::util::produce_any_responses(
+            Accepted::<Json<ActionAccepted>>::responses(gen)?,
+            Accepted::<Json<ShutdownConfirmation>>::responses(gen)?,
+        )
+    }
+}
+
+fn get_shutdown_confirmation(config: &Configuration, server_id: &DeviceId) -> Option<u64> {
+    let server_id: configuration::DeviceId = server_id.to_string().parse().unwrap();
+    match config.devices.get(&server_id) {
+        Some(configuration::Device::Server(server)) => server.shutdown_confirmation,
+        _ => None,
+    }
+}
 
 #[openapi(tag = "Server")]
-#[put("/server/<server>/shutdown")]
-pub fn put_shutdown(
+#[put("/server/<server>/shutdown?<confirm>")]
+pub async fn put_shutdown(
     server: String,
+    confirm: Option<String>,
     state: &rocket::State<Vec<ServerControl>>,
-) -> Result<(), api::Error> {
-    let control = get_server_control(state.inner(), server)?;
+    config: &rocket::State<Configuration>,
+    confirmations: &rocket::State<ShutdownConfirmations>,
+    actions: &rocket::State<Arc<Actions>>,
+    trace: TraceContext,
+) -> Result<ShutdownResponse, api::Error> {
+    let control = get_server_control(state.inner(), server)?.clone();
+    let server_id = &control.server.machine.id;
+
+    // shutting down some servers has to be confirmed with a token
+    if let Some(validity) = get_shutdown_confirmation(config.inner(), server_id) {
+        match confirm {
+            None => {
+                info!(
+                    "[{}] shutdown of {} has to be confirmed within {}s",
+                    trace, control.server.machine.name, validity
+                );
+                let confirmation_token =
+                    confirmations.request(server_id, Duration::from_secs(validity));
+                return Ok(ShutdownResponse::ConfirmationRequired(
+                    ShutdownConfirmation {
+                        confirmation_token,
+                        expires_in: validity,
+                    },
+                ));
+            }
+            Some(token) => {
+                if !confirmations.confirm(server_id, &token) {
+                    return Err(api::Error::from(UnconfirmedShutdownError::from(
+                        server_id.clone(),
+                    )));
+                }
+            }
+        }
+    }
 
-    match control.shutdown.shutdown() {
-        Ok(_) => Ok(()),
-        Err(e) => Err(api::Error::from(api::InternalServerError::from(
-            anyhow::Error::from(e),
-        ))),
+    // don't try the credentials again which the server already rejected
+    if control.invalid_credentials.are_invalid() {
+        return Err(api::Error::from(AuthenticationError::from(
+            server_id.clone(),
+        )));
     }
+
+    let action_id = actions
+        .start(server_id, &control.server.machine.name, Action::Shutdown)
+        .map_err(|e| api::Error::from(ActionInProgressError::from(e)))?;
+    let context = ActionContext::new(trace, CancellationToken::new(), ACTION_TIMEOUT);
+
+    // don't keep the client waiting until the server has been shut down
+    let actions = actions.inner().clone();
+    let id = action_id.clone();
+    tokio::spawn(async move {
+        let result = match control.shutdown.shutdown(&context).await {
+            Ok(report) => {
+                info!(
+                    "[{}] shut down {}: {}",
+                    context, control.server.machine.name, report
+                );
+                control
+                    .expected_transition
+                    .expect(false, TransitionCause::Api);
+                Ok(report.to_string())
+            }
+            Err(e) if e.in_progress().is_some() => {
+                info!(
+                    "[{}] not shutting down {}: {}",
+                    context, control.server.machine.name, e
+                );
+                Err(e.to_string())
+            }
+            Err(e) => {
+                error!(
+                    "[{}] failed to shut down {}: {}",
+                    context, control.server.machine.name, e
+                );
+                Err(e.to_string())
+            }
+        };
+        actions.finish(&id, result);
+    });
+
+    Ok(ShutdownResponse::ShutDown(ActionAccepted { action_id }))
 }
 
 #[cfg(test)]
@@ -32,13 +208,16 @@ mod test {
     use rocket::log::LogLevel;
     use rstest::*;
 
-    use crate::configuration::Configuration;
+    use super::*;
     use crate::control::test::*;
     use crate::dom::communication::SharedStateMutex;
     use crate::dom::device::test::*;
     use crate::dom::test::*;
-    use crate::dom::{Dependencies, DeviceId};
+    use crate::dom::Dependencies;
+    use crate::networking::ActionReport;
     use crate::networking::ShutdownError;
+    use crate::web::api::action::test::*;
+    use crate::web::api::action::ActionState;
     use crate::web::api::server::test::*;
     use crate::web::server::test::*;
 
@@ -58,7 +237,7 @@ mod test {
             .shutdown
             .expect_shutdown()
             .once()
-            .return_once(|| Ok(()));
+            .return_once(|_| Ok(ActionReport::default()));
 
         // TESTING
         let client = get_client(
@@ -75,11 +254,13 @@ mod test {
             .put(get_server_api_endpoint("/shutdown", &server_id))
             .dispatch();
 
-        assert_eq!(response.status(), Status::Ok);
+        let status = wait_for_action(&client, response);
+        assert_eq!(status.state, ActionState::Succeeded);
+        assert_eq!(status.action, "shutdown");
     }
 
     #[rstest]
-    fn test_web_api_returns_internal_server_error_if_shutdown_server_fails(
+    fn test_web_api_reports_failed_shutdown(
         config: Configuration,
         shared_state: Arc<SharedStateMutex>,
         mut mocked_server_control: MockServerControl,
@@ -94,7 +275,7 @@ mod test {
             .shutdown
             .expect_shutdown()
             .once()
-            .return_once(|| Err(ShutdownError::new("".to_string())));
+            .return_once(|_| Err(ShutdownError::new("unreachable".to_string())));
 
         // TESTING
         let client = get_client(
@@ -111,7 +292,9 @@ mod test {
             .put(get_server_api_endpoint("/shutdown", &server_id))
             .dispatch();
 
-        assert_eq!(response.status(), Status::InternalServerError);
+        let status = wait_for_action(&client, response);
+        assert_eq!(status.state, ActionState::Failed);
+        assert!(status.result.unwrap().contains("unreachable"));
     }
 
     #[rstest]
@@ -144,4 +327,173 @@ mod test {
 
         assert_eq!(response.status(), Status::NotFound);
     }
+
+    #[rstest]
+    fn test_web_api_cannot_shutdown_server_with_invalid_credentials(
+        config: Configuration,
+        shared_state: Arc<SharedStateMutex>,
+        mut mocked_server_control: MockServerControl,
+        dependencies: Dependencies,
+        ip: IpAddr,
+        port: u16,
+        log_level: LogLevel,
+        server_id: DeviceId,
+    ) {
+        // EXPECTATIONS
+        mocked_server_control.shutdown.expect_shutdown().never();
+
+        // TESTING
+        let client = get_client(
+            &config,
+            shared_state,
+            mocked_server_control,
+            dependencies,
+            ip,
+            port,
+            log_level,
+        );
+
+        let server_controls = client.rocket().state::<Vec<ServerControl>>().unwrap();
+        assert!(server_controls[0].invalid_credentials.invalidate());
+
+        let response = client
+            .put(get_server_api_endpoint("/shutdown", &server_id))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Conflict);
+        assert!(response
+            .into_string()
+            .unwrap()
+            .starts_with("[AuthenticationError]"));
+    }
+
+    fn require_shutdown_confirmation(config: &mut Configuration, server_id: &DeviceId) {
+        let server_id: configuration::DeviceId = server_id.to_string().parse().unwrap();
+        match config.devices.get_mut(&server_id) {
+            Some(configuration::Device::Server(server)) => server.shutdown_confirmation = Some(60),
+            _ => panic!("{} is not a configured server", server_id),
+        }
+    }
+
+    #[rstest]
+    fn test_shutdown_confirmations_accept_valid_token_once(server_id: DeviceId) {
+        let confirmations = ShutdownConfirmations::new();
+        let token = confirmations.request(&server_id, Duration::from_secs(60));
+
+        assert!(!confirmations.confirm(&server_id, "invalid"));
+        assert!(confirmations.confirm(&server_id, &token));
+        assert!(!confirmations.confirm(&server_id, &token));
+    }
+
+    #[rstest]
+    fn test_shutdown_confirmations_reject_expired_token(server_id: DeviceId) {
+        let confirmations = ShutdownConfirmations::new();
+        let token = confirmations.request(&server_id, Duration::from_secs(60));
+
+        let expired = Instant::now() - Duration::from_secs(1);
+        confirmations
+            .pending
+            .lock()
+            .unwrap()
+            .get_mut(&server_id)
+            .unwrap()
+            .1 = expired;
+
+        assert!(!confirmations.confirm(&server_id, &token));
+    }
+
+    #[rstest]
+    fn test_web_api_requires_confirmation_to_shutdown_server(
+        mut config: Configuration,
+        shared_state: Arc<SharedStateMutex>,
+        mut mocked_server_control: MockServerControl,
+        dependencies: Dependencies,
+        ip: IpAddr,
+        port: u16,
+        log_level: LogLevel,
+        server_id: DeviceId,
+    ) {
+        // SETUP
+        require_shutdown_confirmation(&mut config, &server_id);
+
+        // EXPECTATIONS
+        mocked_server_control
+            .shutdown
+            .expect_shutdown()
+            .once()
+            .return_once(|_| Ok(ActionReport::default()));
+
+        // TESTING
+        let client = get_client(
+            &config,
+            shared_state,
+            mocked_server_control,
+            dependencies,
+            ip,
+            port,
+            log_level,
+        );
+
+        // the first request only returns the confirmation token
+        let response = client
+            .put(get_server_api_endpoint("/shutdown", &server_id))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Accepted);
+        let confirmation = response.into_json::<ShutdownConfirmation>().unwrap();
+        assert_eq!(confirmation.expires_in, 60);
+
+        let response = client
+            .put(get_server_api_endpoint(
+                &format!("/shutdown?confirm={}", confirmation.confirmation_token),
+                &server_id,
+            ))
+            .dispatch();
+
+        let status = wait_for_action(&client, response);
+        assert_eq!(status.state, ActionState::Succeeded);
+    }
+
+    #[rstest]
+    fn test_web_api_cannot_shutdown_server_with_invalid_confirmation(
+        mut config: Configuration,
+        shared_state: Arc<SharedStateMutex>,
+        mut mocked_server_control: MockServerControl,
+        dependencies: Dependencies,
+        ip: IpAddr,
+        port: u16,
+        log_level: LogLevel,
+        server_id: DeviceId,
+    ) {
+        // SETUP
+        require_shutdown_confirmation(&mut config, &server_id);
+
+        // EXPECTATIONS
+        mocked_server_control.shutdown.expect_shutdown().never();
+
+        // TESTING
+        let client = get_client(
+            &config,
+            shared_state,
+            mocked_server_control,
+            dependencies,
+            ip,
+            port,
+            log_level,
+        );
+
+        let response = client
+            .put(get_server_api_endpoint("/shutdown", &server_id))
+            .dispatch();
+        assert_eq!(response.status(), Status::Accepted);
+
+        let response = client
+            .put(get_server_api_endpoint(
+                "/shutdown?confirm=invalid",
+                &server_id,
+            ))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Forbidden);
+    }
 }
@@ -7,7 +7,7 @@ use serde::{Deserialize, Serialize};
 
 use super::get_device;
 use crate::dom::communication::SharedStateMutex;
-use crate::dom::Dependencies;
+use crate::dom::{self, Dependencies};
 use crate::web::api::server::UnknownDeviceError;
 use crate::web::serialization::Device;
 
@@ -39,14 +39,25 @@ pub fn get_status(
     // try to find the server
     let server = get_device(devices, &server_id)?;
     // and map it to a serializable device
-    let status_server = Device::from(server);
+    let status_server = Device::from(server)
+        .with_fingerprint(shared_state.get_fingerprint(&server_id))
+        .with_attributes(shared_state.get_attributes(&server_id));
+    let last_triggers = match server {
+        dom::Device::Server(server) => Some(&server.last_triggers),
+        dom::Device::Machine(_) => None,
+    };
 
     // get the device IDs of the dependencies
     let dependency_device_ids = dependencies.get(&server_id).unwrap();
     // and map them to the actual device (with status)
     let status_devices = dependency_device_ids
         .iter()
-        .map(|device_id| Device::from(get_device(devices, device_id).unwrap()))
+        .map(|device_id| {
+            Device::from(get_device(devices, device_id).unwrap())
+                .with_fingerprint(shared_state.get_fingerprint(device_id))
+                .with_attributes(shared_state.get_attributes(device_id))
+                .with_last_trigger(last_triggers.and_then(|triggers| triggers.get(device_id)))
+        })
         .collect();
 
     // create the status response from the devices
@@ -107,6 +118,52 @@ mod test {
         assert_eq!(response.into_json::<super::Status>(), Some(expected_status));
     }
 
+    #[rstest]
+    fn test_web_api_returns_last_trigger_of_dependencies(
+        config: Configuration,
+        shared_state: Arc<SharedStateMutex>,
+        mocked_server_control: MockServerControl,
+        dependencies: Dependencies,
+        ip: IpAddr,
+        port: u16,
+        log_level: LogLevel,
+        server_id: DeviceId,
+        mut server: Server,
+        machine: Machine,
+    ) {
+        // SETUP
+        let last_trigger = chrono::Utc::now();
+        server.record_trigger(&machine.id, last_trigger);
+        shared_state
+            .lock()
+            .unwrap()
+            .update_device(&crate::dom::Device::Server(server));
+
+        // TESTING
+        let client = get_client(
+            &config,
+            shared_state,
+            mocked_server_control,
+            dependencies,
+            ip,
+            port,
+            log_level,
+        );
+
+        let response = client
+            .get(get_server_api_endpoint("/status", &server_id))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+        let status = response.into_json::<super::Status>().unwrap();
+        assert_eq!(status.devices.len(), 1);
+        assert_eq!(
+            status.devices[0].last_trigger,
+            Some(last_trigger.to_string())
+        );
+        assert!(status.server.last_trigger.is_none());
+    }
+
     #[rstest]
     fn test_web_api_cannot_get_invalid_server_status(
         config: Configuration,
@@ -0,0 +1,213 @@
+use std::result::Result;
+
+use log::{error, info};
+use rocket::post;
+use rocket::serde::json::Json;
+use rocket_okapi::{openapi, JsonSchema};
+use serde::{Deserialize, Serialize};
+use tokio_util::sync::CancellationToken;
+
+use super::get_server_control;
+use crate::control::{ServerControl, ACTION_TIMEOUT};
+use crate::dom::{ActionContext, TraceContext};
+use crate::networking;
+use crate::web::api;
+
+#[derive(Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionTest {
+    /// The server accepts TCP connections on its SSH port.
+    pub reachable: bool,
+    /// The SSH handshake succeeded.
+    pub handshake: bool,
+    /// The configured credentials have been accepted.
+    pub authenticated: bool,
+    /// The user may run commands through sudo without a password.
+    pub sudo: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl From<networking::ConnectionTest> for ConnectionTest {
+    fn from(test: networking::ConnectionTest) -> Self {
+        Self {
+            reachable: test.reachable,
+            handshake: test.handshake,
+            authenticated: test.authenticated,
+            sudo: test.sudo,
+            error: test.error,
+        }
+    }
+}
+
+#[openapi(tag = "Server")]
+#[post("/server/<server>/test-connection")]
+pub async fn post_test_connection(
+    server: String,
+    state: &rocket::State<Vec<ServerControl>>,
+    trace: TraceContext,
+) -> Result<Json<ConnectionTest>, api::Error> {
+    let control = get_server_control(state.inner(), server)?;
+    let context = ActionContext::new(trace, CancellationToken::new(), ACTION_TIMEOUT);
+
+    match control.shutdown.test_connection(&context).await {
+        Ok(test) => {
+            info!(
+                "[{}] tested the connection to {}: {:?}",
+                context, control.server.machine.name, test
+            );
+            Ok(Json(ConnectionTest::from(test)))
+        }
+        Err(e) => {
+            error!(
+                "[{}] failed to test the connection to {}: {}",
+                context, control.server.machine.name, e
+            );
+            Err(api::Error::from(api::InternalServerError::from(e)))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::net::IpAddr;
+    use std::sync::Arc;
+
+    use anyhow::anyhow;
+    use rocket::http::{ContentType, Status};
+    use rocket::log::LogLevel;
+    use rstest::*;
+
+    use super::*;
+    use crate::configuration::Configuration;
+    use crate::control::test::*;
+    use crate::dom::communication::SharedStateMutex;
+    use crate::dom::device::test::*;
+    use crate::dom::test::*;
+    use crate::dom::{Dependencies, DeviceId};
+    use crate::web::api::server::test::*;
+    use crate::web::server::test::*;
+
+    #[rstest]
+    fn test_web_api_can_test_server_connection(
+        config: Configuration,
+        shared_state: Arc<SharedStateMutex>,
+        mut mocked_server_control: MockServerControl,
+        dependencies: Dependencies,
+        ip: IpAddr,
+        port: u16,
+        log_level: LogLevel,
+        server_id: DeviceId,
+    ) {
+        // EXPECTATIONS
+        mocked_server_control.shutdown.expect_shutdown().never();
+        mocked_server_control
+            .shutdown
+            .expect_test_connection()
+            .once()
+            .return_once(|_| {
+                Ok(networking::ConnectionTest {
+                    reachable: true,
+                    handshake: true,
+                    authenticated: false,
+                    sudo: false,
+                    error: Some("authentication failed".to_string()),
+                })
+            });
+
+        // TESTING
+        let client = get_client(
+            &config,
+            shared_state,
+            mocked_server_control,
+            dependencies,
+            ip,
+            port,
+            log_level,
+        );
+
+        let response = client
+            .post(get_server_api_endpoint("/test-connection", &server_id))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.content_type(), Some(ContentType::JSON));
+        assert_eq!(
+            response.into_json::<ConnectionTest>().unwrap(),
+            ConnectionTest {
+                reachable: true,
+                handshake: true,
+                authenticated: false,
+                sudo: false,
+                error: Some("authentication failed".to_string()),
+            }
+        );
+    }
+
+    #[rstest]
+    fn test_web_api_returns_internal_server_error_if_connection_cant_be_tested(
+        config: Configuration,
+        shared_state: Arc<SharedStateMutex>,
+        mut mocked_server_control: MockServerControl,
+        dependencies: Dependencies,
+        ip: IpAddr,
+        port: u16,
+        log_level: LogLevel,
+        server_id: DeviceId,
+    ) {
+        // EXPECTATIONS
+        mocked_server_control
+            .shutdown
+            .expect_test_connection()
+            .once()
+            .return_once(|_| Err(anyhow!("action exceeded its deadline")));
+
+        // TESTING
+        let client = get_client(
+            &config,
+            shared_state,
+            mocked_server_control,
+            dependencies,
+            ip,
+            port,
+            log_level,
+        );
+
+        let response = client
+            .post(get_server_api_endpoint("/test-connection", &server_id))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::InternalServerError);
+    }
+
+    #[rstest]
+    fn test_web_api_cannot_test_connection_of_invalid_server(
+        config: Configuration,
+        shared_state: Arc<SharedStateMutex>,
+        mocked_server_control: MockServerControl,
+        dependencies: Dependencies,
+        ip: IpAddr,
+        port: u16,
+        log_level: LogLevel,
+    ) {
+        // TESTING
+        let client = get_client(
+            &config,
+            shared_state,
+            mocked_server_control,
+            dependencies,
+            ip,
+            port,
+            log_level,
+        );
+
+        let response = client
+            .post(get_server_api_endpoint(
+                "/test-connection",
+                &"invalidserver".parse().unwrap(),
+            ))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::NotFound);
+    }
+}
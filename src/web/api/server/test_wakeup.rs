@@ -0,0 +1,221 @@
+use std::result::Result;
+use std::time::{Duration, Instant};
+
+use log::{error, info};
+use rocket::post;
+use rocket::serde::json::Json;
+use rocket_okapi::{openapi, JsonSchema};
+use serde::{Deserialize, Serialize};
+use tokio_util::sync::CancellationToken;
+
+use super::get_server_control;
+use crate::control::{Factory, ServerControl, ACTION_TIMEOUT};
+use crate::dom::{ActionContext, TraceContext};
+use crate::networking::PortChecker;
+use crate::web::api;
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WakeupTest {
+    /// The server was already reachable before it has been woken up.
+    pub already_online: bool,
+    /// The server became reachable within the timeout.
+    pub reachable: bool,
+    /// Number of seconds until the server became reachable or the timeout expired.
+    pub seconds: u64,
+}
+
+async fn is_reachable(checker: &std::sync::Arc<dyn PortChecker>) -> bool {
+    let checker = checker.clone();
+    tokio::task::spawn_blocking(move || checker.check())
+        .await
+        .unwrap_or(false)
+}
+
+#[openapi(tag = "Server")]
+#[post("/server/<server>/test-wakeup?<timeout>")]
+pub async fn post_test_wakeup(
+    server: String,
+    timeout: Option<u64>,
+    state: &rocket::State<Vec<ServerControl>>,
+    trace: TraceContext,
+) -> Result<Json<WakeupTest>, api::Error> {
+    let control = get_server_control(state.inner(), server)?;
+    // by default wait as long as the server may be unreachable while being online
+    let timeout = Duration::from_secs(timeout.unwrap_or(control.server.machine.last_seen_timeout));
+    let checker = Factory::create_online_checker(&control.server);
+
+    // a server which is already online doesn't tell anything about its Wake-on-LAN settings
+    let already_online = is_reachable(&checker).await;
+
+    let context = ActionContext::new(trace, CancellationToken::new(), ACTION_TIMEOUT);
+    if let Err(e) = control.wakeup.wakeup(&context).await {
+        error!(
+            "[{}] failed to wake up {}: {}",
+            context, control.server.machine.name, e
+        );
+        return Err(api::Error::from(api::InternalServerError::from(e)));
+    }
+
+    let start = Instant::now();
+    let reachable = loop {
+        if is_reachable(&checker).await {
+            break true;
+        }
+        if start.elapsed() >= timeout {
+            break false;
+        }
+
+        tokio::time::sleep(CHECK_INTERVAL).await;
+    };
+    let seconds = start.elapsed().as_secs();
+
+    info!(
+        "[{}] {} is {}reachable {} seconds after waking it up",
+        context,
+        control.server.machine.name,
+        if reachable { "" } else { "not " },
+        seconds
+    );
+
+    Ok(Json(WakeupTest {
+        already_online,
+        reachable,
+        seconds,
+    }))
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::{Error, ErrorKind};
+    use std::net::{IpAddr, TcpListener};
+    use std::sync::Arc;
+
+    use rocket::http::Status;
+    use rocket::log::LogLevel;
+    use rstest::*;
+
+    use super::*;
+    use crate::configuration::Configuration;
+    use crate::control::test::*;
+    use crate::dom::communication::SharedStateMutex;
+    use crate::dom::device::test::*;
+    use crate::dom::device::SshPort;
+    use crate::dom::test::*;
+    use crate::dom::{Dependencies, DeviceId};
+    use crate::networking::ActionReport;
+    use crate::web::api::server::test::*;
+    use crate::web::server::test::*;
+
+    fn local_server(mocked_server_control: &mut MockServerControl, port: u16) {
+        mocked_server_control.server.machine.ip = "127.0.0.1".parse().unwrap();
+        mocked_server_control.server.ssh.port = SshPort(port);
+    }
+
+    #[rstest]
+    #[case(true)]
+    #[case(false)]
+    fn test_web_api_can_test_wakeup_of_server(
+        config: Configuration,
+        shared_state: Arc<SharedStateMutex>,
+        mut mocked_server_control: MockServerControl,
+        dependencies: Dependencies,
+        ip: IpAddr,
+        port: u16,
+        log_level: LogLevel,
+        server_id: DeviceId,
+        #[case] reachable: bool,
+    ) {
+        // SETUP
+        let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        let ssh_port = listener.local_addr().unwrap().port();
+        if !reachable {
+            drop(listener);
+        }
+        local_server(&mut mocked_server_control, ssh_port);
+
+        // EXPECTATIONS
+        mocked_server_control
+            .wakeup
+            .expect_wakeup()
+            .once()
+            .return_once(|_| Ok(ActionReport::default()));
+
+        // TESTING
+        let client = get_client(
+            &config,
+            shared_state,
+            mocked_server_control,
+            dependencies,
+            ip,
+            port,
+            log_level,
+        );
+
+        let response = client
+            .post(get_server_api_endpoint(
+                "/test-wakeup?timeout=0",
+                &server_id,
+            ))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(
+            response.into_json::<WakeupTest>().unwrap(),
+            WakeupTest {
+                already_online: reachable,
+                reachable,
+                seconds: 0,
+            }
+        );
+    }
+
+    #[rstest]
+    fn test_web_api_returns_internal_server_error_if_test_wakeup_fails(
+        config: Configuration,
+        shared_state: Arc<SharedStateMutex>,
+        mut mocked_server_control: MockServerControl,
+        dependencies: Dependencies,
+        ip: IpAddr,
+        port: u16,
+        log_level: LogLevel,
+        server_id: DeviceId,
+    ) {
+        // SETUP
+        let closed_port = TcpListener::bind(("127.0.0.1", 0))
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        local_server(&mut mocked_server_control, closed_port);
+
+        // EXPECTATIONS
+        mocked_server_control
+            .wakeup
+            .expect_wakeup()
+            .once()
+            .return_once(|_| Err(anyhow::Error::from(Error::new(ErrorKind::AddrInUse, ""))));
+
+        // TESTING
+        let client = get_client(
+            &config,
+            shared_state,
+            mocked_server_control,
+            dependencies,
+            ip,
+            port,
+            log_level,
+        );
+
+        let response = client
+            .post(get_server_api_endpoint(
+                "/test-wakeup?timeout=0",
+                &server_id,
+            ))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::InternalServerError);
+    }
+}
@@ -0,0 +1,66 @@
+use std::fmt;
+use std::io::Cursor;
+
+use rocket::response::Responder;
+use rocket::{http, response, Request, Response};
+use rocket_okapi::gen::OpenApiGenerator;
+use rocket_okapi::okapi::openapi3::Responses;
+use rocket_okapi::response::OpenApiResponderInner;
+
+use crate::dom::DeviceId;
+
+#[derive(Debug)]
+pub struct UnconfirmedShutdownError(DeviceId);
+
+impl std::error::Error for UnconfirmedShutdownError {}
+
+impl From<DeviceId> for UnconfirmedShutdownError {
+    fn from(device_id: DeviceId) -> Self {
+        Self(device_id)
+    }
+}
+
+impl fmt::Display for UnconfirmedShutdownError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "[UnconfirmedShutdownError] invalid or expired confirmation token for {}",
+            self.0
+        )
+    }
+}
+
+impl<'r, 'o: 'r> Responder<'r, 'o> for UnconfirmedShutdownError {
+    fn respond_to(self, _: &Request) -> response::Result<'o> {
+        let error_msg = self.to_string();
+        Response::build()
+            .header(http::ContentType::Plain)
+            .status(http::Status::Forbidden)
+            .sized_body(error_msg.len(), Cursor::new(error_msg))
+            .ok()
+    }
+}
+
+impl OpenApiResponderInner for UnconfirmedShutdownError {
+    fn responses(_: &mut OpenApiGenerator) -> rocket_okapi::Result<Responses> {
+        let mut responses = Responses::default();
+        add_403_error(&mut responses);
+        Ok(responses)
+    }
+}
+
+fn add_403_error(responses: &mut Responses) {
+    responses
+        .responses
+        .entry("403".to_owned())
+        .or_insert_with(|| {
+            let response = rocket_okapi::okapi::openapi3::Response {
+                description: "\
+                    [403 Forbidden](https://developer.mozilla.org/en-US/docs/Web/HTTP/Status/403)\n\n\
+                    This response is given when a shutdown is confirmed with an invalid or expired token.\
+                    ".to_owned(),
+                ..Default::default()
+            };
+            response.into()
+        });
+}
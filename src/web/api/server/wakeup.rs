@@ -1,24 +1,71 @@
 use std::result::Result;
+use std::sync::Arc;
 
+use log::{error, info};
 use rocket::put;
+use rocket::response::status::Accepted;
+use rocket::serde::json::Json;
 use rocket_okapi::openapi;
+use tokio_util::sync::CancellationToken;
 
-use super::get_server_control;
-use crate::control::ServerControl;
-use crate::web::api;
+use super::{get_server_control, ActionInProgressError};
+use crate::control::{ServerControl, ACTION_TIMEOUT};
+use crate::dom::{ActionContext, TraceContext, TransitionCause};
+use crate::networking::{Action, ActionInProgress};
+use crate::web::api::{self, ActionAccepted, Actions};
 
 #[openapi(tag = "Server")]
 #[put("/server/<server>/wakeup")]
-pub fn put_wakeup(
+pub async fn put_wakeup(
     server: String,
     state: &rocket::State<Vec<ServerControl>>,
-) -> Result<(), api::Error> {
-    let control = get_server_control(state.inner(), server)?;
+    actions: &rocket::State<Arc<Actions>>,
+    trace: TraceContext,
+) -> Result<Accepted<Json<ActionAccepted>>, api::Error> {
+    let control = get_server_control(state.inner(), server)?.clone();
+    let action_id = actions
+        .start(
+            &control.server.machine.id,
+            &control.server.machine.name,
+            Action::Wakeup,
+        )
+        .map_err(|e| api::Error::from(ActionInProgressError::from(e)))?;
+    let context = ActionContext::new(trace, CancellationToken::new(), ACTION_TIMEOUT);
 
-    match control.wakeup.wakeup() {
-        Ok(_) => Ok(()),
-        Err(e) => Err(api::Error::from(api::InternalServerError::from(e))),
-    }
+    // don't keep the client waiting until the server has been woken up
+    let actions = actions.inner().clone();
+    let id = action_id.clone();
+    tokio::spawn(async move {
+        let result = match control.wakeup.wakeup(&context).await {
+            Ok(report) => {
+                info!(
+                    "[{}] woke up {}: {}",
+                    context, control.server.machine.name, report
+                );
+                control
+                    .expected_transition
+                    .expect(true, TransitionCause::Api);
+                Ok(report.to_string())
+            }
+            Err(e) if e.downcast_ref::<ActionInProgress>().is_some() => {
+                info!(
+                    "[{}] not waking up {}: {}",
+                    context, control.server.machine.name, e
+                );
+                Err(e.to_string())
+            }
+            Err(e) => {
+                error!(
+                    "[{}] failed to wake up {}: {}",
+                    context, control.server.machine.name, e
+                );
+                Err(e.to_string())
+            }
+        };
+        actions.finish(&id, result);
+    });
+
+    Ok(Accepted(Some(Json(ActionAccepted { action_id }))))
 }
 
 #[cfg(test)]
@@ -28,7 +75,7 @@ mod test {
     use std::sync::Arc;
 
     use anyhow;
-    use rocket::http::Status;
+    use rocket::http::{Header, Status};
     use rocket::log::LogLevel;
     use rstest::*;
 
@@ -38,6 +85,9 @@ mod test {
     use crate::dom::device::test::*;
     use crate::dom::test::*;
     use crate::dom::{Dependencies, DeviceId};
+    use crate::networking::{Action, ActionInProgress, ActionReport};
+    use crate::web::api::action::test::*;
+    use crate::web::api::action::ActionState;
     use crate::web::api::server::test::*;
     use crate::web::server::test::*;
 
@@ -57,7 +107,46 @@ mod test {
             .wakeup
             .expect_wakeup()
             .once()
-            .return_once(|| Ok(()));
+            .return_once(|_| Ok(ActionReport::default()));
+
+        // TESTING
+        let client = get_client(
+            &config,
+            shared_state,
+            mocked_server_control,
+            dependencies,
+            ip,
+            port,
+            log_level,
+        );
+
+        let response = client
+            .put(get_server_api_endpoint("/wakeup", &server_id))
+            .dispatch();
+
+        let status = wait_for_action(&client, response);
+        assert_eq!(status.state, ActionState::Succeeded);
+        assert_eq!(status.action, "wakeup");
+        assert_eq!(status.server, server_id.to_string());
+    }
+
+    #[rstest]
+    fn test_web_api_reports_failed_wakeup(
+        config: Configuration,
+        shared_state: Arc<SharedStateMutex>,
+        mut mocked_server_control: MockServerControl,
+        dependencies: Dependencies,
+        ip: IpAddr,
+        port: u16,
+        log_level: LogLevel,
+        server_id: DeviceId,
+    ) {
+        // EXPECTATIONS
+        mocked_server_control
+            .wakeup
+            .expect_wakeup()
+            .once()
+            .return_once(|_| Err(anyhow::Error::from(Error::new(ErrorKind::AddrInUse, ""))));
 
         // TESTING
         let client = get_client(
@@ -74,11 +163,12 @@ mod test {
             .put(get_server_api_endpoint("/wakeup", &server_id))
             .dispatch();
 
-        assert_eq!(response.status(), Status::Ok);
+        let status = wait_for_action(&client, response);
+        assert_eq!(status.state, ActionState::Failed);
     }
 
     #[rstest]
-    fn test_web_api_returns_internal_server_error_if_wakeup_server_fails(
+    fn test_web_api_reports_wakeup_already_in_progress(
         config: Configuration,
         shared_state: Arc<SharedStateMutex>,
         mut mocked_server_control: MockServerControl,
@@ -93,7 +183,12 @@ mod test {
             .wakeup
             .expect_wakeup()
             .once()
-            .return_once(|| Err(anyhow::Error::from(Error::new(ErrorKind::AddrInUse, ""))));
+            .return_once(|_| {
+                Err(anyhow::Error::from(ActionInProgress {
+                    action: Action::Wakeup,
+                    server: SERVER_NAME.to_string(),
+                }))
+            });
 
         // TESTING
         let client = get_client(
@@ -110,7 +205,51 @@ mod test {
             .put(get_server_api_endpoint("/wakeup", &server_id))
             .dispatch();
 
-        assert_eq!(response.status(), Status::InternalServerError);
+        let status = wait_for_action(&client, response);
+        assert_eq!(status.state, ActionState::Failed);
+        assert!(status.result.unwrap().contains("already in progress"));
+    }
+
+    #[rstest]
+    fn test_web_api_passes_request_id_to_wakeup_server(
+        config: Configuration,
+        shared_state: Arc<SharedStateMutex>,
+        mut mocked_server_control: MockServerControl,
+        dependencies: Dependencies,
+        ip: IpAddr,
+        port: u16,
+        log_level: LogLevel,
+        server_id: DeviceId,
+    ) {
+        // EXPECTATIONS
+        mocked_server_control
+            .wakeup
+            .expect_wakeup()
+            .withf(|context| context.trace.id() == "my-request")
+            .once()
+            .return_once(|_| Err(anyhow::Error::from(Error::new(ErrorKind::AddrInUse, ""))));
+
+        // TESTING
+        let client = get_client(
+            &config,
+            shared_state,
+            mocked_server_control,
+            dependencies,
+            ip,
+            port,
+            log_level,
+        );
+
+        let response = client
+            .put(get_server_api_endpoint("/wakeup", &server_id))
+            .header(Header::new("X-Request-Id", "my-request"))
+            .dispatch();
+
+        assert_eq!(
+            response.headers().get_one("X-Request-Id"),
+            Some("my-request")
+        );
+        wait_for_action(&client, response);
     }
 
     #[rstest]
@@ -6,32 +6,68 @@ use rocket::serde::json::Json;
 use rocket_okapi::{openapi, JsonSchema};
 use serde::{Deserialize, Serialize};
 
-use crate::dom::communication::SharedStateMutex;
+use crate::dom::communication::{Away, Profiles, SharedStateMutex};
 use crate::web::serialization::Device;
 
 #[derive(Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Status {
+    /// Active profile (missing if the configured dependencies are active).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    profile: Option<String>,
+    /// Whether the away mode is active.
+    away: bool,
     devices: Vec<Device>,
 }
 
 impl Status {
     pub fn new(devices: Vec<Device>) -> Self {
-        Self { devices }
+        Self {
+            profile: None,
+            away: false,
+            devices,
+        }
+    }
+
+    pub fn with_profile(mut self, profile: Option<String>) -> Self {
+        self.profile = profile;
+        self
+    }
+
+    pub fn with_away(mut self, away: bool) -> Self {
+        self.away = away;
+        self
     }
 }
 
 #[openapi(tag = "General")]
 #[get("/status")]
-pub fn get_status(state: &rocket::State<Arc<SharedStateMutex>>) -> Json<Status> {
+pub fn get_status(
+    state: &rocket::State<Arc<SharedStateMutex>>,
+    profiles: &rocket::State<Arc<Profiles>>,
+    away: &rocket::State<Arc<Away>>,
+) -> Json<Status> {
     // get the devices from the shared state
     let shared_state = state.lock().unwrap();
     let devices = shared_state.get_devices();
 
-    let status_devices = devices.iter().map(Device::from).collect();
+    let status_devices = devices
+        .iter()
+        .map(|device| {
+            Device::from(device)
+                .with_fingerprint(shared_state.get_fingerprint(device.id()))
+                .with_observed_mac(shared_state.get_mac_mismatch(device.id()))
+                .with_attributes(shared_state.get_attributes(device.id()))
+                .with_notes(shared_state.get_notes(device.id()))
+        })
+        .collect();
 
     // create the status response from the devices
-    Json(Status::new(status_devices))
+    Json(
+        Status::new(status_devices)
+            .with_profile(profiles.active())
+            .with_away(away.is_active()),
+    )
 }
 
 #[cfg(test)]
@@ -46,8 +82,9 @@ mod test {
     use crate::configuration::Configuration;
     use crate::control::test::*;
     use crate::dom::communication::SharedStateMutex;
+    use crate::dom::device::test::*;
     use crate::dom::test::*;
-    use crate::dom::Dependencies;
+    use crate::dom::{Dependencies, DeviceId, Fingerprint};
     use crate::web::serialization;
     use crate::web::server::test::*;
 
@@ -81,4 +118,48 @@ mod test {
         let expected_status = super::Status::new(serialization_devices);
         assert_eq!(response.into_json::<super::Status>(), Some(expected_status));
     }
+
+    #[rstest]
+    fn test_web_api_status_contains_fingerprint(
+        config: Configuration,
+        shared_state: Arc<SharedStateMutex>,
+        mocked_server_control: MockServerControl,
+        dependencies: Dependencies,
+        ip: IpAddr,
+        port: u16,
+        log_level: LogLevel,
+        server_id: DeviceId,
+    ) {
+        // SETUP
+        let fingerprint = Fingerprint::infer(Some(64), vec![22, 443], Vec::new());
+        shared_state
+            .lock()
+            .unwrap()
+            .set_fingerprint(&server_id, fingerprint.clone());
+
+        // TESTING
+        let client = get_client(
+            &config,
+            shared_state,
+            mocked_server_control,
+            dependencies,
+            ip,
+            port,
+            log_level,
+        );
+
+        let response = client.get(get_api_endpoint("/status")).dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+        let status = response.into_json::<super::Status>().unwrap();
+        let fingerprints: Vec<_> = status
+            .devices
+            .into_iter()
+            .map(|device| device.fingerprint)
+            .collect();
+        assert_eq!(
+            fingerprints,
+            vec![None, Some(serialization::Fingerprint::from(&fingerprint))]
+        );
+    }
 }
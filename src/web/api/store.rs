@@ -0,0 +1,459 @@
+use std::result::Result;
+use std::sync::Arc;
+
+use rocket::serde::json::Json;
+use rocket::{delete, get, post, put};
+use rocket_okapi::openapi;
+
+use crate::configuration::{self, Configuration};
+use crate::dom;
+use crate::store::{DeviceStore, Inventory, Revision};
+use crate::web::api::server::UnknownDeviceError;
+use crate::web::api::{Author, InternalServerError, StoreError};
+
+fn get_store(store: &Option<Arc<DeviceStore>>) -> Result<Arc<DeviceStore>, StoreError> {
+    store.clone().ok_or(StoreError::Disabled)
+}
+
+/// Runs the given access to the (blocking) SQLite database on the blocking thread pool instead of
+/// stalling the executor of the web API.
+async fn access_store<T, F>(store: &Option<Arc<DeviceStore>>, access: F) -> Result<T, StoreError>
+where
+    T: Send + 'static,
+    F: FnOnce(&DeviceStore) -> Result<T, StoreError> + Send + 'static,
+{
+    let store = get_store(store)?;
+    match tokio::task::spawn_blocking(move || access(&store)).await {
+        Ok(result) => result,
+        Err(e) => Err(StoreError::Internal(InternalServerError::from(
+            anyhow::Error::from(e),
+        ))),
+    }
+}
+
+async fn update_store<F>(
+    store: &Option<Arc<DeviceStore>>,
+    config: &Configuration,
+    reason: String,
+    author: Author,
+    modify: F,
+) -> Result<Json<Inventory>, StoreError>
+where
+    F: FnOnce(&mut Inventory) -> anyhow::Result<()> + Send + 'static,
+{
+    let config = config.clone();
+    let inventory = access_store(store, move |store| {
+        let inventory = store.update(&reason, author.as_deref(), |inventory| {
+            modify(inventory)?;
+            inventory.check(&config)?;
+            Ok(())
+        })?;
+        Ok(inventory)
+    })
+    .await?;
+
+    Ok(Json(inventory))
+}
+
+#[openapi(tag = "Store")]
+#[get("/store")]
+pub async fn get_store_inventory(
+    store: &rocket::State<Option<Arc<DeviceStore>>>,
+) -> Result<Json<Inventory>, StoreError> {
+    let inventory = access_store(store.inner(), |store| Ok(store.load()?)).await?;
+    Ok(Json(inventory))
+}
+
+#[openapi(tag = "Store")]
+#[put("/store", data = "<inventory>")]
+pub async fn put_store_inventory(
+    inventory: Json<Inventory>,
+    author: Author,
+    config: &rocket::State<Configuration>,
+    store: &rocket::State<Option<Arc<DeviceStore>>>,
+) -> Result<Json<Inventory>, StoreError> {
+    let reason = "replaced all devices and dependencies".to_string();
+    update_store(store.inner(), config.inner(), reason, author, |stored| {
+        *stored = inventory.into_inner();
+        Ok(())
+    })
+    .await
+}
+
+#[openapi(tag = "Store")]
+#[put("/store/device/<device>", data = "<definition>")]
+pub async fn put_store_device(
+    device: String,
+    definition: Json<configuration::Device>,
+    author: Author,
+    config: &rocket::State<Configuration>,
+    store: &rocket::State<Option<Arc<DeviceStore>>>,
+) -> Result<Json<Inventory>, StoreError> {
+    let reason = format!("updated device {}", device);
+    let device_id: configuration::DeviceId = device.parse().unwrap();
+    update_store(store.inner(), config.inner(), reason, author, |inventory| {
+        inventory.devices.insert(device_id, definition.into_inner());
+        Ok(())
+    })
+    .await
+}
+
+#[openapi(tag = "Store")]
+#[delete("/store/device/<device>")]
+pub async fn delete_store_device(
+    device: String,
+    author: Author,
+    config: &rocket::State<Configuration>,
+    store: &rocket::State<Option<Arc<DeviceStore>>>,
+) -> Result<Json<Inventory>, StoreError> {
+    let reason = format!("deleted device {}", device);
+    let device_id: configuration::DeviceId = device.parse().unwrap();
+    update_store(
+        store.inner(),
+        config.inner(),
+        reason,
+        author,
+        move |inventory| {
+            if inventory.devices.remove(&device_id).is_none() {
+                return Err(UnknownDeviceError::from(dom::DeviceId::from(&device_id)).into());
+            }
+
+            // drop the device from all dependencies and the dependencies left without any device
+            inventory.dependencies.0.remove(&device_id);
+            for deps in inventory.dependencies.0.values_mut() {
+                deps.retain(|dep| *dep != device_id);
+            }
+            inventory.dependencies.0.retain(|_, deps| !deps.is_empty());
+
+            Ok(())
+        },
+    )
+    .await
+}
+
+#[openapi(tag = "Store")]
+#[put("/store/dependencies/<server>", data = "<dependencies>")]
+pub async fn put_store_dependencies(
+    server: String,
+    dependencies: Json<Vec<String>>,
+    author: Author,
+    config: &rocket::State<Configuration>,
+    store: &rocket::State<Option<Arc<DeviceStore>>>,
+) -> Result<Json<Inventory>, StoreError> {
+    let reason = format!("updated dependencies of {}", server);
+    let server_id: configuration::DeviceId = server.parse().unwrap();
+    let dependencies: Vec<configuration::DeviceId> = dependencies
+        .into_inner()
+        .iter()
+        .map(|device| device.parse().unwrap())
+        .collect();
+
+    update_store(store.inner(), config.inner(), reason, author, |inventory| {
+        // an empty list of dependencies removes all dependencies of the server
+        if dependencies.is_empty() {
+            inventory.dependencies.0.remove(&server_id);
+        } else {
+            inventory.dependencies.0.insert(server_id, dependencies);
+        }
+        Ok(())
+    })
+    .await
+}
+
+#[openapi(tag = "Store")]
+#[get("/config/revisions")]
+pub async fn get_config_revisions(
+    store: &rocket::State<Option<Arc<DeviceStore>>>,
+) -> Result<Json<Vec<Revision>>, StoreError> {
+    let revisions = access_store(store.inner(), |store| Ok(store.revisions()?)).await?;
+    Ok(Json(revisions))
+}
+
+#[openapi(tag = "Store")]
+#[post("/config/revisions/<revision>/rollback")]
+pub async fn post_config_rollback(
+    revision: i64,
+    author: Author,
+    config: &rocket::State<Configuration>,
+    store: &rocket::State<Option<Arc<DeviceStore>>>,
+) -> Result<Json<Inventory>, StoreError> {
+    let stored = access_store(store.inner(), move |store| {
+        store
+            .revision(revision)?
+            .ok_or(StoreError::UnknownRevision(revision))
+    })
+    .await?;
+
+    let reason = format!("rollback to revision {}", revision);
+    update_store(store.inner(), config.inner(), reason, author, |inventory| {
+        *inventory = stored;
+        Ok(())
+    })
+    .await
+}
+
+#[cfg(test)]
+mod test {
+    use std::net::IpAddr;
+    use std::sync::Arc;
+
+    use rocket::http::{ContentType, Header, Status};
+    use rocket::log::LogLevel;
+    use rstest::*;
+    use serde_json::json;
+
+    use super::*;
+    use crate::control::test::*;
+    use crate::dom::communication::SharedStateMutex;
+    use crate::dom::device::test::*;
+    use crate::dom::test::*;
+    use crate::dom::{Dependencies, DeviceId};
+    use crate::web::server::test::*;
+
+    #[fixture]
+    fn store(config: Configuration) -> Arc<DeviceStore> {
+        let store = DeviceStore::open_in_memory().unwrap();
+        store.load_into(&mut config.clone()).unwrap();
+        Arc::new(store)
+    }
+
+    #[rstest]
+    fn test_web_api_store_fails_without_store(
+        config: Configuration,
+        shared_state: Arc<SharedStateMutex>,
+        mocked_server_control: MockServerControl,
+        dependencies: Dependencies,
+        ip: IpAddr,
+        port: u16,
+        log_level: LogLevel,
+    ) {
+        // TESTING
+        let client = get_client(
+            &config,
+            shared_state,
+            mocked_server_control,
+            dependencies,
+            ip,
+            port,
+            log_level,
+        );
+
+        let response = client.get(get_api_endpoint("/store")).dispatch();
+
+        assert_eq!(response.status(), Status::NotFound);
+    }
+
+    #[rstest]
+    fn test_web_api_can_get_store(
+        config: Configuration,
+        shared_state: Arc<SharedStateMutex>,
+        mocked_server_control: MockServerControl,
+        dependencies: Dependencies,
+        store: Arc<DeviceStore>,
+        ip: IpAddr,
+        port: u16,
+        log_level: LogLevel,
+    ) {
+        // TESTING
+        let client = get_client_with_store(
+            &config,
+            shared_state,
+            mocked_server_control,
+            dependencies,
+            store,
+            ip,
+            port,
+            log_level,
+        );
+
+        let response = client.get(get_api_endpoint("/store")).dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.content_type(), Some(ContentType::JSON));
+
+        let inventory = response.into_json::<Inventory>().unwrap();
+        assert_eq!(inventory.devices.len(), config.devices.len());
+        assert_eq!(inventory.dependencies, config.dependencies);
+    }
+
+    #[rstest]
+    fn test_web_api_can_put_store_device(
+        config: Configuration,
+        shared_state: Arc<SharedStateMutex>,
+        mocked_server_control: MockServerControl,
+        dependencies: Dependencies,
+        store: Arc<DeviceStore>,
+        ip: IpAddr,
+        port: u16,
+        log_level: LogLevel,
+    ) {
+        // TESTING
+        let client = get_client_with_store(
+            &config,
+            shared_state,
+            mocked_server_control,
+            dependencies,
+            store.clone(),
+            ip,
+            port,
+            log_level,
+        );
+
+        let response = client
+            .put(get_api_endpoint("/store/device/phone"))
+            .json(&json!({
+                "name": "Phone",
+                "ip": "10.0.0.3",
+                "timeout": 300
+            }))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+
+        let phone_id: configuration::DeviceId = "phone".parse().unwrap();
+        let inventory = store.load().unwrap();
+        assert_eq!(inventory.devices.len(), config.devices.len() + 1);
+        match inventory.devices.get(&phone_id) {
+            Some(configuration::Device::Machine(machine)) => assert_eq!(machine.name, "Phone"),
+            _ => panic!("phone is not a stored machine"),
+        }
+    }
+
+    #[rstest]
+    fn test_web_api_can_delete_store_device(
+        config: Configuration,
+        shared_state: Arc<SharedStateMutex>,
+        mocked_server_control: MockServerControl,
+        dependencies: Dependencies,
+        store: Arc<DeviceStore>,
+        ip: IpAddr,
+        port: u16,
+        log_level: LogLevel,
+        machine_id: DeviceId,
+    ) {
+        // TESTING
+        let client = get_client_with_store(
+            &config,
+            shared_state,
+            mocked_server_control,
+            dependencies,
+            store.clone(),
+            ip,
+            port,
+            log_level,
+        );
+
+        let endpoint = get_api_endpoint(&format!("/store/device/{}", machine_id));
+        let response = client.delete(&endpoint).dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+
+        // the server only depended on the deleted machine
+        let inventory = store.load().unwrap();
+        assert!(!inventory
+            .devices
+            .contains_key(&machine_id.to_string().parse().unwrap()));
+        assert!(inventory.dependencies.0.is_empty());
+
+        let response = client.delete(&endpoint).dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+    }
+
+    #[rstest]
+    fn test_web_api_rejects_invalid_store_dependencies(
+        config: Configuration,
+        shared_state: Arc<SharedStateMutex>,
+        mocked_server_control: MockServerControl,
+        dependencies: Dependencies,
+        store: Arc<DeviceStore>,
+        ip: IpAddr,
+        port: u16,
+        log_level: LogLevel,
+        server_id: DeviceId,
+    ) {
+        // TESTING
+        let client = get_client_with_store(
+            &config,
+            shared_state,
+            mocked_server_control,
+            dependencies,
+            store.clone(),
+            ip,
+            port,
+            log_level,
+        );
+
+        let response = client
+            .put(get_api_endpoint(&format!(
+                "/store/dependencies/{}",
+                server_id
+            )))
+            .json(&json!(["unknown"]))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::UnprocessableEntity);
+
+        // the stored dependencies haven't changed
+        assert_eq!(store.load().unwrap().dependencies, config.dependencies);
+    }
+
+    #[rstest]
+    fn test_web_api_can_rollback_config_revision(
+        config: Configuration,
+        shared_state: Arc<SharedStateMutex>,
+        mocked_server_control: MockServerControl,
+        dependencies: Dependencies,
+        store: Arc<DeviceStore>,
+        ip: IpAddr,
+        port: u16,
+        log_level: LogLevel,
+        machine_id: DeviceId,
+    ) {
+        // TESTING
+        let client = get_client_with_store(
+            &config,
+            shared_state,
+            mocked_server_control,
+            dependencies,
+            store.clone(),
+            ip,
+            port,
+            log_level,
+        );
+        let seeded = store.load().unwrap();
+
+        let response = client
+            .delete(get_api_endpoint(&format!("/store/device/{}", machine_id)))
+            .header(Header::new("Authorization", "Bearer secret"))
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+
+        let response = client.get(get_api_endpoint("/config/revisions")).dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.content_type(), Some(ContentType::JSON));
+
+        let revisions = response.into_json::<Vec<Revision>>().unwrap();
+        assert_eq!(revisions.len(), 2);
+        assert_eq!(
+            revisions[0].reason,
+            format!("deleted device {}", machine_id)
+        );
+        assert!(revisions[0].author.is_some());
+        assert_ne!(revisions[0].author.as_deref(), Some("secret"));
+        assert_eq!(
+            revisions[0].changes.removed,
+            vec![machine_id.to_string().parse().unwrap()]
+        );
+
+        let response = client
+            .post(get_api_endpoint("/config/revisions/1/rollback"))
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(store.load().unwrap(), seeded);
+
+        let response = client
+            .post(get_api_endpoint("/config/revisions/42/rollback"))
+            .dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+    }
+}
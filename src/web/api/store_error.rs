@@ -0,0 +1,127 @@
+use std::fmt;
+use std::io::Cursor;
+
+use rocket::response::Responder;
+use rocket::{http, response, Request, Response};
+use rocket_okapi::gen::OpenApiGenerator;
+use rocket_okapi::okapi::openapi3::Responses;
+use rocket_okapi::response::OpenApiResponderInner;
+
+use crate::configuration::DependencyError;
+use crate::web::api::server::UnknownDeviceError;
+use crate::web::api::InternalServerError;
+
+#[derive(Debug)]
+pub enum StoreError {
+    Disabled,
+    UnknownRevision(i64),
+    UnknownDevice(UnknownDeviceError),
+    Invalid(DependencyError),
+    Internal(InternalServerError),
+}
+
+impl std::error::Error for StoreError {}
+
+impl From<DependencyError> for StoreError {
+    fn from(error: DependencyError) -> Self {
+        Self::Invalid(error)
+    }
+}
+
+impl From<UnknownDeviceError> for StoreError {
+    fn from(error: UnknownDeviceError) -> Self {
+        Self::UnknownDevice(error)
+    }
+}
+
+impl From<anyhow::Error> for StoreError {
+    fn from(error: anyhow::Error) -> Self {
+        // keep the errors of the modifications apart from the errors of the database
+        let error = match error.downcast::<DependencyError>() {
+            Ok(error) => return Self::Invalid(error),
+            Err(error) => error,
+        };
+        match error.downcast::<UnknownDeviceError>() {
+            Ok(error) => Self::UnknownDevice(error),
+            Err(error) => Self::Internal(InternalServerError::from(error)),
+        }
+    }
+}
+
+impl fmt::Display for StoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Disabled => write!(f, "[StoreError] no device store configured"),
+            Self::UnknownRevision(revision) => {
+                write!(f, "[StoreError] unknown revision {}", revision)
+            }
+            Self::UnknownDevice(error) => error.fmt(f),
+            Self::Invalid(error) => write!(f, "[StoreError] {}", error),
+            Self::Internal(error) => error.fmt(f),
+        }
+    }
+}
+
+impl<'r, 'o: 'r> Responder<'r, 'o> for StoreError {
+    fn respond_to(self, req: &Request) -> response::Result<'o> {
+        let status = match self {
+            Self::Disabled | Self::UnknownRevision(_) => http::Status::NotFound,
+            Self::UnknownDevice(error) => return error.respond_to(req),
+            Self::Invalid(_) => http::Status::UnprocessableEntity,
+            Self::Internal(error) => return error.respond_to(req),
+        };
+        let error_msg = self.to_string();
+        Response::build()
+            .header(http::ContentType::Plain)
+            .status(status)
+            .sized_body(error_msg.len(), Cursor::new(error_msg))
+            .ok()
+    }
+}
+
+impl OpenApiResponderInner for StoreError {
+    fn responses(gen: &mut OpenApiGenerator) -> rocket_okapi::Result<Responses> {
+        let mut responses = Responses::default();
+        add_404_error(&mut responses);
+        add_422_error(&mut responses);
+        {
+            let responses_internal_server_error = InternalServerError::responses(gen)?;
+            responses
+                .responses
+                .extend(responses_internal_server_error.responses);
+        }
+        Ok(responses)
+    }
+}
+
+fn add_404_error(responses: &mut Responses) {
+    responses
+        .responses
+        .entry("404".to_owned())
+        .or_insert_with(|| {
+            let response = rocket_okapi::okapi::openapi3::Response {
+                description: "\
+                    [404 Not Found](https://developer.mozilla.org/en-US/docs/Web/HTTP/Status/404)\n\n\
+                    This response is given when no device store is configured or when you change an unknown device or revision.\
+                    ".to_owned(),
+                ..Default::default()
+            };
+            response.into()
+        });
+}
+
+fn add_422_error(responses: &mut Responses) {
+    responses
+        .responses
+        .entry("422".to_owned())
+        .or_insert_with(|| {
+            let response = rocket_okapi::okapi::openapi3::Response {
+                description: "\
+                    [422 Unprocessable Entity](https://developer.mozilla.org/en-US/docs/Web/HTTP/Status/422)\n\n\
+                    This response is given when the changed devices or dependencies are inconsistent.\
+                    ".to_owned(),
+                ..Default::default()
+            };
+            response.into()
+        });
+}
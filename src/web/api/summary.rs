@@ -0,0 +1,158 @@
+use rocket::get;
+use rocket::serde::json::Json;
+use rocket_okapi::{openapi, JsonSchema};
+use serde::{Deserialize, Serialize};
+
+use super::ConfigPath;
+use crate::configuration::{Configuration, Device, PingMethod};
+use crate::env::PKG_VERSION;
+
+#[derive(Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Summary {
+    version: String,
+    config_file: String,
+    interface: String,
+    /// Interval (in seconds) between two pings of every device.
+    ping_interval: u64,
+    /// Seconds after which a device is considered offline.
+    ping_timeout: u64,
+    ping_method: PingMethod,
+    devices: usize,
+    servers: usize,
+    profile: Option<String>,
+    away: bool,
+    /// Optional features which are enabled by the configuration (e.g. `dns` or `store`).
+    features: Vec<String>,
+    /// Authentication of the API documentation (`none` or `basic`).
+    docs_auth: String,
+}
+
+impl Summary {
+    pub fn new(config: &Configuration, config_path: &ConfigPath) -> Self {
+        Self {
+            version: PKG_VERSION.to_string(),
+            config_file: config_path.0.display().to_string(),
+            interface: config.network.interface.clone(),
+            ping_interval: config.network.ping.interval,
+            ping_timeout: config.network.ping.timeout,
+            ping_method: config.network.ping.method,
+            devices: config.devices.len(),
+            servers: config
+                .devices
+                .values()
+                .filter(|device| matches!(device, Device::Server(_)))
+                .count(),
+            profile: config.profile.clone(),
+            away: config.away,
+            features: features(config),
+            docs_auth: match &config.api.web.docs.auth {
+                Some(_) => "basic".to_string(),
+                None => "none".to_string(),
+            },
+        }
+    }
+}
+
+/// Names of the optional features enabled by the given configuration.
+fn features(config: &Configuration) -> Vec<String> {
+    let api = &config.api;
+    [
+        ("docs", api.web.docs.enabled),
+        ("files", api.files.is_some()),
+        ("beacon", api.beacon.port != 0),
+        ("dns", api.dns.port != 0),
+        ("wakeOnLan", api.wake_on_lan.port != 0),
+        ("schedules", !config.schedules.is_empty()),
+        ("profiles", !config.profiles.is_empty()),
+        ("proxy", config.proxy.is_some()),
+        ("notifications", !config.notifications.webhooks.is_empty()),
+        ("hooks", !config.hooks.is_empty()),
+        ("updates", config.updates.check),
+        ("fingerprinting", config.fingerprinting.enabled),
+        ("presence", !config.presence.is_empty()),
+        ("store", config.store.is_some()),
+        ("failover", config.failover.is_some()),
+        ("recovery", config.recovery.is_some()),
+    ]
+    .iter()
+    .filter(|(_, enabled)| *enabled)
+    .map(|(feature, _)| feature.to_string())
+    .collect()
+}
+
+/// Returns a summary of the effective configuration (after applying defaults and command line
+/// overrides) e.g. for support requests.
+#[openapi(tag = "General")]
+#[get("/summary")]
+pub fn get_summary(
+    config: &rocket::State<Configuration>,
+    config_path: &rocket::State<ConfigPath>,
+) -> Json<Summary> {
+    Json(Summary::new(config.inner(), config_path.inner()))
+}
+
+#[cfg(test)]
+mod test {
+    use std::net::IpAddr;
+    use std::sync::Arc;
+
+    use rocket::http::{ContentType, Status};
+    use rocket::log::LogLevel;
+    use rstest::*;
+
+    use super::*;
+    use crate::configuration::{self, BasicAuth, Store};
+    use crate::control::test::*;
+    use crate::dom::communication::SharedStateMutex;
+    use crate::dom::test::*;
+    use crate::dom::Dependencies;
+    use crate::web::server::test::*;
+
+    #[rstest]
+    fn test_web_api_can_get_summary(
+        mut config: Configuration,
+        shared_state: Arc<SharedStateMutex>,
+        mocked_server_control: MockServerControl,
+        dependencies: Dependencies,
+        ip: IpAddr,
+        port: u16,
+        log_level: LogLevel,
+    ) {
+        // SETUP
+        config.store = Some(Store {
+            path: "/var/lib/home-monitor-rs/home-monitor-rs.db".into(),
+        });
+        config.api.web.docs.auth = Some(BasicAuth {
+            username: "admin".to_string(),
+            password: "secret".into(),
+        });
+
+        // TESTING
+        let client = get_client(
+            &config,
+            shared_state,
+            mocked_server_control,
+            dependencies,
+            ip,
+            port,
+            log_level,
+        );
+
+        let response = client.get(get_api_endpoint("/summary")).dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.content_type(), Some(ContentType::JSON));
+
+        let summary = response.into_json::<Summary>().unwrap();
+        assert_eq!(summary.version, PKG_VERSION);
+        assert_eq!(summary.config_file, configuration::LOCATION);
+        assert_eq!(summary.interface, config.network.interface);
+        assert_eq!(summary.ping_interval, config.network.ping.interval);
+        assert_eq!(summary.devices, config.devices.len());
+        assert!(summary.servers > 0 && summary.servers < summary.devices);
+        assert!(summary.features.contains(&"store".to_string()));
+        assert!(!summary.features.contains(&"failover".to_string()));
+        assert_eq!(summary.docs_auth, "basic");
+    }
+}
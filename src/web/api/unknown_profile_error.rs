@@ -0,0 +1,60 @@
+use std::fmt;
+use std::io::Cursor;
+
+use rocket::response::Responder;
+use rocket::{http, response, Request, Response};
+use rocket_okapi::gen::OpenApiGenerator;
+use rocket_okapi::okapi::openapi3::Responses;
+use rocket_okapi::response::OpenApiResponderInner;
+
+#[derive(Debug)]
+pub struct UnknownProfileError(String);
+
+impl std::error::Error for UnknownProfileError {}
+
+impl From<String> for UnknownProfileError {
+    fn from(profile: String) -> Self {
+        Self(profile)
+    }
+}
+
+impl fmt::Display for UnknownProfileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[UnknownProfileError] {}", self.0)
+    }
+}
+
+impl<'r, 'o: 'r> Responder<'r, 'o> for UnknownProfileError {
+    fn respond_to(self, _: &Request) -> response::Result<'o> {
+        let error_msg = self.to_string();
+        Response::build()
+            .header(http::ContentType::Plain)
+            .status(http::Status::NotFound)
+            .sized_body(error_msg.len(), Cursor::new(error_msg))
+            .ok()
+    }
+}
+
+impl OpenApiResponderInner for UnknownProfileError {
+    fn responses(_: &mut OpenApiGenerator) -> rocket_okapi::Result<Responses> {
+        let mut responses = Responses::default();
+        add_404_error(&mut responses);
+        Ok(responses)
+    }
+}
+
+fn add_404_error(responses: &mut Responses) {
+    responses
+        .responses
+        .entry("404".to_owned())
+        .or_insert_with(|| {
+            let response = rocket_okapi::okapi::openapi3::Response {
+                description: "\
+                    [404 Not Found](https://developer.mozilla.org/en-US/docs/Web/HTTP/Status/404)\n\n\
+                    This response is given when the profile is not configured.\
+                    ".to_owned(),
+                ..Default::default()
+            };
+            response.into()
+        });
+}
@@ -0,0 +1,97 @@
+use std::path::PathBuf;
+
+use chrono::{TimeZone, Utc};
+use rocket::get;
+use rocket::serde::json::Json;
+use rocket_okapi::{openapi, JsonSchema};
+use serde::{Deserialize, Serialize};
+
+use crate::env::{BUILD_FEATURES, BUILD_GIT_COMMIT, BUILD_TIMESTAMP, PKG_NAME, PKG_VERSION};
+
+pub struct ConfigPath(pub PathBuf);
+
+#[derive(Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Version {
+    name: String,
+    version: String,
+    git_commit: String,
+    build_timestamp: Option<String>,
+    features: Vec<String>,
+    config_file: String,
+}
+
+#[openapi(tag = "General")]
+#[get("/version")]
+pub fn get_version(config_path: &rocket::State<ConfigPath>) -> Json<Version> {
+    let build_timestamp = BUILD_TIMESTAMP
+        .parse::<i64>()
+        .ok()
+        .and_then(|timestamp| Utc.timestamp_opt(timestamp, 0).single())
+        .map(|timestamp| timestamp.to_rfc3339());
+
+    Json(Version {
+        name: PKG_NAME.to_string(),
+        version: PKG_VERSION.to_string(),
+        git_commit: BUILD_GIT_COMMIT.to_string(),
+        build_timestamp,
+        features: BUILD_FEATURES
+            .split(',')
+            .filter(|feature| !feature.is_empty())
+            .map(str::to_string)
+            .collect(),
+        config_file: config_path.0.display().to_string(),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use std::net::IpAddr;
+    use std::sync::Arc;
+
+    use rocket::http::{ContentType, Status};
+    use rocket::log::LogLevel;
+    use rstest::*;
+
+    use super::*;
+    use crate::configuration::{self, Configuration};
+    use crate::control::test::*;
+    use crate::dom::communication::SharedStateMutex;
+    use crate::dom::test::*;
+    use crate::dom::Dependencies;
+    use crate::web::server::test::*;
+
+    #[rstest]
+    fn test_web_api_can_get_version(
+        config: Configuration,
+        shared_state: Arc<SharedStateMutex>,
+        mocked_server_control: MockServerControl,
+        dependencies: Dependencies,
+        ip: IpAddr,
+        port: u16,
+        log_level: LogLevel,
+    ) {
+        // TESTING
+        let client = get_client(
+            &config,
+            shared_state,
+            mocked_server_control,
+            dependencies,
+            ip,
+            port,
+            log_level,
+        );
+
+        let response = client.get(get_api_endpoint("/version")).dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.content_type(), Some(ContentType::JSON));
+
+        let version = response.into_json::<Version>().unwrap();
+        assert_eq!(version.name, PKG_NAME);
+        assert_eq!(version.version, PKG_VERSION);
+        assert_eq!(version.git_commit, BUILD_GIT_COMMIT);
+        assert!(version.build_timestamp.is_some());
+        assert_eq!(version.config_file, configuration::LOCATION);
+    }
+}
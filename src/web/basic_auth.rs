@@ -0,0 +1,61 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use rocket::http::{Header, Status};
+use rocket::response::Responder;
+use rocket::route::{Handler, Outcome};
+use rocket::{response, Data, Request, Response, Route};
+
+use crate::configuration;
+use crate::env::PKG_NAME;
+
+struct Unauthorized;
+
+impl<'r, 'o: 'r> Responder<'r, 'o> for Unauthorized {
+    fn respond_to(self, _: &Request) -> response::Result<'o> {
+        Response::build()
+            .status(Status::Unauthorized)
+            .header(Header::new(
+                "WWW-Authenticate",
+                format!("Basic realm=\"{PKG_NAME}\""),
+            ))
+            .ok()
+    }
+}
+
+/// Route handler which only forwards requests with the expected basic authentication.
+#[derive(Clone)]
+struct BasicAuthHandler {
+    handler: Box<dyn Handler>,
+    authorization: String,
+}
+
+#[rocket::async_trait]
+impl Handler for BasicAuthHandler {
+    async fn handle<'r>(&self, request: &'r Request<'_>, data: Data<'r>) -> Outcome<'r> {
+        match request.headers().get_one("Authorization") {
+            Some(authorization) if authorization == self.authorization => {
+                self.handler.handle(request, data).await
+            }
+            _ => Outcome::from(request, Unauthorized),
+        }
+    }
+}
+
+/// Protects all the given routes with the given basic authentication credentials.
+pub fn protect(routes: Vec<Route>, auth: &configuration::BasicAuth) -> Vec<Route> {
+    let authorization = format!(
+        "Basic {}",
+        STANDARD.encode(format!("{}:{}", auth.username, auth.password.expose()))
+    );
+
+    routes
+        .into_iter()
+        .map(|mut route| {
+            route.handler = Box::new(BasicAuthHandler {
+                handler: route.handler,
+                authorization: authorization.clone(),
+            });
+            route
+        })
+        .collect()
+}
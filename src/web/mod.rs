@@ -1,4 +1,5 @@
 pub mod api;
+mod basic_auth;
 pub mod serialization;
 pub mod server;
 mod shared_state_sync;
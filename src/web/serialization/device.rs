@@ -1,7 +1,9 @@
+use std::collections::BTreeMap;
 use std::convert::From;
 use std::net::IpAddr;
 use std::option::Option;
 
+use chrono::{DateTime, Utc};
 use macaddr::MacAddr8;
 use rocket_okapi::JsonSchema;
 use schemars::gen::SchemaGenerator;
@@ -11,6 +13,9 @@ use serde::{Deserialize, Serialize};
 use crate::dom;
 use crate::utils::MacAddr;
 
+/// Maximum number of notes of a device included in its status.
+const MAX_NOTES: usize = 5;
+
 impl JsonSchema for MacAddr {
     fn schema_name() -> String {
         "macaddr".to_owned()
@@ -30,6 +35,141 @@ impl JsonSchema for MacAddr {
     }
 }
 
+#[derive(Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Service {
+    pub name: String,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    pub is_online: bool,
+}
+
+impl From<&dom::device::Service> for Service {
+    fn from(service: &dom::device::Service) -> Self {
+        Self {
+            name: service.name.clone(),
+            port: match &service.check {
+                dom::device::ServiceCheck::Tcp(port) => Some(*port),
+                dom::device::ServiceCheck::Http(_) => None,
+            },
+            url: match &service.check {
+                dom::device::ServiceCheck::Tcp(_) => None,
+                dom::device::ServiceCheck::Http(http) => Some(http.url.clone()),
+            },
+            is_online: service.is_online,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ExternalDependency {
+    pub name: String,
+    pub url: String,
+    pub is_active: bool,
+}
+
+impl From<&dom::device::ExternalDependency> for ExternalDependency {
+    fn from(dependency: &dom::device::ExternalDependency) -> Self {
+        Self {
+            name: dependency.name.clone(),
+            url: match &dependency.check {
+                dom::device::ExternalCheck::Http(http) => http.url.clone(),
+                dom::device::ExternalCheck::Snmp(snmp) => snmp.to_string(),
+                dom::device::ExternalCheck::Sessions(sessions) => sessions.to_string(),
+            },
+            is_active: dependency.is_active,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Fingerprint {
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub os: Option<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub device_type: Option<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ttl: Option<u8>,
+    #[serde(default)]
+    pub open_ports: Vec<u16>,
+    #[serde(default)]
+    pub services: Vec<String>,
+}
+
+impl From<&dom::Fingerprint> for Fingerprint {
+    fn from(fingerprint: &dom::Fingerprint) -> Self {
+        Self {
+            os: fingerprint.os.map(|os| os.to_string()),
+            device_type: fingerprint
+                .device_type
+                .map(|device_type| device_type.to_string()),
+            ttl: fingerprint.ttl,
+            open_ports: fingerprint.open_ports.clone(),
+            services: fingerprint.services.clone(),
+        }
+    }
+}
+
+/// Change of the online state of a server with its probable cause (`monitor`, `schedule`, `api`
+/// or `external`).
+#[derive(Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Transition {
+    pub is_online: bool,
+    pub cause: String,
+    pub date: String,
+}
+
+impl From<&dom::Transition> for Transition {
+    fn from(transition: &dom::Transition) -> Self {
+        Self {
+            is_online: transition.is_online,
+            cause: transition.cause.to_string(),
+            date: transition.date.to_string(),
+        }
+    }
+}
+
+/// Note attached to a device through the web API.
+#[derive(Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Note {
+    pub date: String,
+    pub text: String,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub is_incident: bool,
+}
+
+impl From<&dom::Note> for Note {
+    fn from(note: &dom::Note) -> Self {
+        Self {
+            date: note.date.to_string(),
+            text: note.text.clone(),
+            is_incident: note.is_incident,
+        }
+    }
+}
+
+/// Daily on-time budget of a server (in seconds).
+#[derive(Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Budget {
+    pub max_daily_on_time: u64,
+    /// Time the server has been online today (updated once per minute).
+    pub on_time_today: u64,
+    pub is_exhausted: bool,
+}
+
 #[derive(Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Device {
@@ -42,12 +182,130 @@ pub struct Device {
     pub last_seen_timeout: u64,
     pub is_online: bool,
     pub last_seen: Option<String>,
+
+    pub consecutive_missed_pings: u64,
+    /// Probe which produced the last "online" verdict (`ping` or `arp`).
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub probe_method: Option<String>,
+    /// Whether the device being offline is currently ignored (e.g. during a maintenance window).
+    #[serde(default)]
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub is_ignored: bool,
+    /// Whether the device isn't on a subnet of the monitored interface (which rules out
+    /// Wake-on-LAN and ARP requests).
+    #[serde(default)]
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub is_off_subnet: bool,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub consecutive_failed_actions: Option<u64>,
+    /// Whether the server is only monitored because it failed to shut down too many times in a
+    /// row.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub is_control_disabled: bool,
+    /// Whether the server rejected the configured SSH credentials and isn't shut down anymore.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub has_invalid_credentials: bool,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub services: Vec<Service>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub external_dependencies: Vec<ExternalDependency>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unexpected_onlines: Option<u64>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_unexpected_online: Option<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub external_wakeups: Option<u64>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_external_wakeup: Option<String>,
+    /// Seconds until the server may be woken up / shut down again after its last wakeup /
+    /// shutdown.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub change_timeout: Option<u64>,
+    /// Seconds the wakeup of the server is deferred to stagger it with the wakeups of other
+    /// servers.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wakeup_deferred: Option<u64>,
+    /// Latest changes of the online state of the server (oldest first).
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub transitions: Vec<Transition>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fingerprint: Option<Fingerprint>,
+    /// MAC address found in the ARP table for the IP address of the server if it differs from the
+    /// configured one.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub observed_mac: Option<MacAddr>,
+    /// Last time the online state of the dependency influenced whether its server is woken up or
+    /// kept online (updated once per hour).
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_trigger: Option<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub budget: Option<Budget>,
+    /// Custom attributes set through the web API.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub attributes: BTreeMap<String, String>,
+    /// Latest notes attached through the web API (oldest first).
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub notes: Vec<Note>,
 }
 
 impl Device {
     pub fn default_mac() -> MacAddr {
         MacAddr::V8(MacAddr8::nil())
     }
+
+    /// Annotates the device with the operating system and type inferred by fingerprinting.
+    pub fn with_fingerprint(mut self, fingerprint: Option<&dom::Fingerprint>) -> Self {
+        self.fingerprint = fingerprint.map(Fingerprint::from);
+        self
+    }
+
+    pub fn with_observed_mac(mut self, observed_mac: Option<&MacAddr>) -> Self {
+        self.observed_mac = observed_mac.cloned();
+        self
+    }
+
+    pub fn with_attributes(mut self, attributes: Option<&dom::Attributes>) -> Self {
+        self.attributes = attributes.cloned().unwrap_or_default();
+        self
+    }
+
+    /// Annotates the device with its latest notes.
+    pub fn with_notes(mut self, notes: Option<&Vec<dom::Note>>) -> Self {
+        self.notes = notes
+            .map(|notes| {
+                notes
+                    .iter()
+                    .skip(notes.len().saturating_sub(MAX_NOTES))
+                    .map(Note::from)
+                    .collect()
+            })
+            .unwrap_or_default();
+        self
+    }
+
+    pub fn with_last_trigger(mut self, last_trigger: Option<&DateTime<Utc>>) -> Self {
+        self.last_trigger = last_trigger.map(|date| date.to_string());
+        self
+    }
 }
 
 impl From<dom::Machine> for Device {
@@ -65,6 +323,30 @@ impl From<&dom::Machine> for Device {
             last_seen_timeout: machine.last_seen_timeout,
             is_online: machine.is_online,
             last_seen: machine.last_seen_date.map(|date| date.to_string()),
+            consecutive_missed_pings: machine.consecutive_missed_pings,
+            probe_method: machine
+                .probe_method
+                .map(|probe_method| probe_method.to_string()),
+            is_ignored: machine.is_ignored,
+            is_off_subnet: machine.is_off_subnet,
+            consecutive_failed_actions: None,
+            is_control_disabled: false,
+            has_invalid_credentials: false,
+            services: Vec::new(),
+            external_dependencies: Vec::new(),
+            unexpected_onlines: None,
+            last_unexpected_online: None,
+            external_wakeups: None,
+            last_external_wakeup: None,
+            change_timeout: None,
+            wakeup_deferred: None,
+            transitions: Vec::new(),
+            fingerprint: None,
+            observed_mac: None,
+            last_trigger: None,
+            budget: None,
+            attributes: BTreeMap::new(),
+            notes: Vec::new(),
         }
     }
 }
@@ -79,6 +361,28 @@ impl From<&dom::Server> for Device {
     fn from(server: &dom::Server) -> Self {
         let mut device = Device::from(&server.machine);
         device.mac = server.mac;
+        device.consecutive_failed_actions = Some(server.consecutive_failed_actions);
+        device.is_control_disabled = server.is_control_disabled;
+        device.has_invalid_credentials = server.has_invalid_credentials;
+        device.services = server.services.iter().map(Service::from).collect();
+        device.external_dependencies = server
+            .external_dependencies
+            .iter()
+            .map(ExternalDependency::from)
+            .collect();
+        device.unexpected_onlines = Some(server.unexpected_onlines);
+        device.last_unexpected_online = server.last_unexpected_online.map(|date| date.to_string());
+        device.external_wakeups = Some(server.external_wakeups);
+        device.last_external_wakeup = server.last_external_wakeup.map(|date| date.to_string());
+        device.change_timeout = Some(server.remaining_change_timeout(Utc::now()).as_secs());
+        device.wakeup_deferred = Some(server.remaining_wakeup_deferral(Utc::now()).as_secs())
+            .filter(|remaining| *remaining > 0);
+        device.transitions = server.transitions.iter().map(Transition::from).collect();
+        device.budget = server.max_daily_on_time.map(|max_daily_on_time| Budget {
+            max_daily_on_time: max_daily_on_time.as_secs(),
+            on_time_today: server.on_time_today.as_secs(),
+            is_exhausted: server.is_budget_exhausted(),
+        });
 
         device
     }
@@ -1,3 +1,5 @@
 mod device;
 
-pub use device::Device;
+#[cfg(test)]
+pub use device::Fingerprint;
+pub use device::{Device, Note};
@@ -1,7 +1,12 @@
+use std::collections::HashMap;
 use std::net::IpAddr;
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Instant;
 
-use log::warn;
+use log::{debug, info, warn};
+use rocket::fairing::AdHoc;
+use rocket::Route;
 use rocket_okapi::rapidoc::{
     make_rapidoc, GeneralConfig, HideShowConfig, LayoutConfig, NavConfig, RapiDocConfig,
     RenderStyle, Theme, UiConfig,
@@ -9,27 +14,44 @@ use rocket_okapi::rapidoc::{
 use rocket_okapi::settings::UrlObject;
 use rocket_okapi::swagger_ui::{make_swagger_ui, SwaggerUIConfig};
 
-use super::api;
-use crate::configuration::Configuration;
-use crate::control::ServerControl;
-use crate::dom::communication::SharedStateMutex;
-use crate::dom::Dependencies;
+use super::{api, basic_auth};
+use crate::configuration::{self, Configuration};
+use crate::control::{GroupControl, ServerControl};
+use crate::dom::communication::{
+    Away, ChangeTimeoutResetSender, DeviceEvents, GuestSender, IgnoreWindows, MonitorStats,
+    Profiles, Readiness, RuntimeStats, SharedStateMutex, SyncStats,
+};
+use crate::dom::{Dependencies, DeviceId, Schedule};
 use crate::env::PKG_NAME;
+use crate::events::Events;
+use crate::log_buffer::LogBuffer;
+use crate::metrics::Registry;
+use crate::networking::{LatencyProber, PacketCapture};
+use crate::store::DeviceStore;
+use crate::updates::SharedUpdateStatus;
 
-static OPENAPI_SPEC: &str = "/api/v1/openapi.json";
+static API_PATH: &str = "/api/v1";
+static OPENAPI_SPEC: &str = "/openapi.json";
 
-fn swagger_ui() -> SwaggerUIConfig {
+fn swagger_ui(spec_url: &str) -> SwaggerUIConfig {
     SwaggerUIConfig {
-        url: OPENAPI_SPEC.to_string(),
+        url: spec_url.to_string(),
         urls: vec![],
         ..Default::default()
     }
 }
 
-fn rapidoc() -> RapiDocConfig {
+fn docs_routes(docs: &configuration::Docs, routes: impl Into<Vec<Route>>) -> Vec<Route> {
+    match &docs.auth {
+        Some(auth) => basic_auth::protect(routes.into(), auth),
+        None => routes.into(),
+    }
+}
+
+fn rapidoc(spec_url: &str) -> RapiDocConfig {
     RapiDocConfig {
         general: GeneralConfig {
-            spec_urls: vec![UrlObject::new(PKG_NAME, OPENAPI_SPEC)],
+            spec_urls: vec![UrlObject::new(PKG_NAME, spec_url)],
             sort_tags: true,
             ..Default::default()
         },
@@ -64,13 +86,35 @@ impl Server {
         name: &str,
         version: &str,
         config: Configuration,
+        config_path: PathBuf,
         shared_state: Arc<SharedStateMutex>,
         server_controls: Vec<ServerControl>,
+        group_controls: Vec<GroupControl>,
         dependencies: Dependencies,
+        guest_sender: GuestSender,
+        change_timeout_resets: ChangeTimeoutResetSender,
+        device_events: DeviceEvents,
+        action_events: Events,
+        metrics: Arc<Registry>,
+        update_status: SharedUpdateStatus,
+        readiness: Arc<Readiness>,
+        sync_stats: Arc<SyncStats>,
+        monitor_stats: Arc<MonitorStats>,
+        runtime_stats: Arc<RuntimeStats>,
+        ignore_windows: Arc<IgnoreWindows>,
+        profiles: Arc<Profiles>,
+        blackouts: HashMap<DeviceId, Vec<Schedule>>,
+        away: Arc<Away>,
+        log_buffer: Arc<LogBuffer>,
+        packet_capture: Arc<dyn PacketCapture>,
+        latency_prober: Arc<dyn LatencyProber>,
+        store: Option<Arc<DeviceStore>>,
         ip: IpAddr,
         port: u16,
         log_level: rocket::config::LogLevel,
     ) -> Self {
+        let start = Instant::now();
+
         // create a custom configuration for Rocket
         let mut rocket_config = rocket::Config {
             address: ip,
@@ -86,14 +130,78 @@ impl Server {
             Err(e) => warn!("failed to create custom identitiy for the web API: {}", e),
         };
 
-        let server = rocket::custom(&rocket_config)
-            .mount("/api/v1/", api::get_routes())
-            .mount("/docs/swagger/", make_swagger_ui(&swagger_ui()))
-            .mount("/docs/rapidoc/", make_rapidoc(&rapidoc()))
+        // all routes are hosted under the (optional) base path
+        let base_path = config.api.web.normalized_base_path();
+        let api_path = format!("{base_path}{API_PATH}");
+        let spec_url = format!("{api_path}{OPENAPI_SPEC}");
+
+        let routes = api::get_routes();
+        let spec = Arc::new(api::OpenApiSpec::new(&api_path));
+
+        let mut server = rocket::custom(&rocket_config)
+            .attach(api::RequestCounter::new(metrics.clone(), &api_path))
+            .attach(api::RequestId::new())
+            .attach(api::RequestLogger::new(config.api.web.request_log.clone()))
+            .mount(format!("{api_path}/"), routes);
+
+        // the interactive documentation can be disabled or protected
+        let docs = &config.api.web.docs;
+        if docs.enabled {
+            server = server
+                .mount(
+                    format!("{base_path}/docs/swagger/"),
+                    docs_routes(docs, make_swagger_ui(&swagger_ui(&spec_url))),
+                )
+                .mount(
+                    format!("{base_path}/docs/rapidoc/"),
+                    docs_routes(docs, make_rapidoc(&rapidoc(&spec_url))),
+                );
+        } else {
+            info!("interactive web API documentation is disabled");
+        }
+
+        let server = server
             .manage(config)
+            .manage(api::ConfigPath(config_path))
             .manage(shared_state)
             .manage(server_controls)
-            .manage(dependencies);
+            .manage(group_controls)
+            .manage(dependencies)
+            .manage(guest_sender)
+            .manage(change_timeout_resets)
+            .manage(device_events)
+            .manage(action_events)
+            .manage(metrics)
+            .manage(update_status)
+            .manage(readiness)
+            .manage(sync_stats)
+            .manage(monitor_stats)
+            .manage(runtime_stats)
+            .manage(ignore_windows)
+            .manage(profiles)
+            .manage(api::Blackouts(blackouts))
+            .manage(away)
+            .manage(log_buffer)
+            .manage(packet_capture)
+            .manage(latency_prober)
+            .manage(store)
+            .manage(api::ShutdownConfirmations::new())
+            .manage(Arc::new(api::Actions::new()))
+            .manage(api::FilesApiStatus::new())
+            .manage(spec.clone())
+            .attach(AdHoc::on_liftoff("Startup Timing", move |_| {
+                Box::pin(async move {
+                    info!(
+                        "web API is answering requests {}ms after its startup",
+                        start.elapsed().as_millis()
+                    );
+
+                    // generate the OpenAPI description in the background instead of delaying the
+                    // startup or the first request for it
+                    tokio::task::spawn_blocking(move || spec.prepare());
+                })
+            }));
+        debug!("prepared the web API in {}ms", start.elapsed().as_millis());
 
         Self { server }
     }
@@ -121,8 +229,10 @@ impl Server {
 
 #[cfg(test)]
 pub mod test {
+    use std::collections::HashMap;
     use std::sync::Mutex;
 
+    use rocket::http::{Header, Status};
     use rocket::local::blocking::Client;
     use rocket::log::LogLevel;
     use rstest::*;
@@ -130,8 +240,12 @@ pub mod test {
 
     use super::*;
     use crate::control::test::*;
+    use crate::dom::communication::Profile;
     use crate::dom::device::test::*;
+    use crate::dom::test::*;
     use crate::env::*;
+    use crate::log_buffer::LOG_BUFFER_CAPACITY;
+    use crate::networking::{MockLatencyProber, MockPacketCapture};
     use crate::web::serialization;
     use crate::{configuration, dom};
 
@@ -145,9 +259,10 @@ pub mod test {
         server: dom::Server,
         machine: dom::Machine,
     ) -> Vec<serialization::Device> {
+        // sorted by their ID like in the shared state
         vec![
-            serialization::Device::from(server),
             serialization::Device::from(machine),
+            serialization::Device::from(server),
         ]
     }
 
@@ -235,13 +350,287 @@ pub mod test {
         port: u16,
         log_level: LogLevel,
     ) -> Client {
+        let (guest_sender, _) = dom::communication::guest_channel();
+
+        get_client_with_guest_sender(
+            config,
+            shared_state,
+            mocked_server_control,
+            dependencies,
+            guest_sender,
+            ip,
+            port,
+            log_level,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn get_client_with_guest_sender(
+        config: &Configuration,
+        shared_state: Arc<SharedStateMutex>,
+        mocked_server_control: MockServerControl,
+        dependencies: Dependencies,
+        guest_sender: GuestSender,
+        ip: IpAddr,
+        port: u16,
+        log_level: LogLevel,
+    ) -> Client {
+        create_client(
+            config,
+            shared_state,
+            mocked_server_control,
+            dependencies,
+            guest_sender,
+            dom::communication::change_timeout_reset_channel().0,
+            dom::communication::device_events(),
+            Arc::new(Readiness::new(&config.network.interface)),
+            Arc::new(MockPacketCapture::new()),
+            Arc::new(MockLatencyProber::new()),
+            None,
+            ip,
+            port,
+            log_level,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn get_client_with_change_timeout_resets(
+        config: &Configuration,
+        shared_state: Arc<SharedStateMutex>,
+        mocked_server_control: MockServerControl,
+        dependencies: Dependencies,
+        change_timeout_resets: ChangeTimeoutResetSender,
+        ip: IpAddr,
+        port: u16,
+        log_level: LogLevel,
+    ) -> Client {
+        let (guest_sender, _) = dom::communication::guest_channel();
+
+        create_client(
+            config,
+            shared_state,
+            mocked_server_control,
+            dependencies,
+            guest_sender,
+            change_timeout_resets,
+            dom::communication::device_events(),
+            Arc::new(Readiness::new(&config.network.interface)),
+            Arc::new(MockPacketCapture::new()),
+            Arc::new(MockLatencyProber::new()),
+            None,
+            ip,
+            port,
+            log_level,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn get_client_with_readiness(
+        config: &Configuration,
+        shared_state: Arc<SharedStateMutex>,
+        mocked_server_control: MockServerControl,
+        dependencies: Dependencies,
+        readiness: Arc<Readiness>,
+        ip: IpAddr,
+        port: u16,
+        log_level: LogLevel,
+    ) -> Client {
+        let (guest_sender, _) = dom::communication::guest_channel();
+
+        create_client(
+            config,
+            shared_state,
+            mocked_server_control,
+            dependencies,
+            guest_sender,
+            dom::communication::change_timeout_reset_channel().0,
+            dom::communication::device_events(),
+            readiness,
+            Arc::new(MockPacketCapture::new()),
+            Arc::new(MockLatencyProber::new()),
+            None,
+            ip,
+            port,
+            log_level,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn get_client_with_packet_capture(
+        config: &Configuration,
+        shared_state: Arc<SharedStateMutex>,
+        mocked_server_control: MockServerControl,
+        dependencies: Dependencies,
+        packet_capture: MockPacketCapture,
+        ip: IpAddr,
+        port: u16,
+        log_level: LogLevel,
+    ) -> Client {
+        let (guest_sender, _) = dom::communication::guest_channel();
+
+        create_client(
+            config,
+            shared_state,
+            mocked_server_control,
+            dependencies,
+            guest_sender,
+            dom::communication::change_timeout_reset_channel().0,
+            dom::communication::device_events(),
+            Arc::new(Readiness::new(&config.network.interface)),
+            Arc::new(packet_capture),
+            Arc::new(MockLatencyProber::new()),
+            None,
+            ip,
+            port,
+            log_level,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn get_client_with_latency_prober(
+        config: &Configuration,
+        shared_state: Arc<SharedStateMutex>,
+        mocked_server_control: MockServerControl,
+        dependencies: Dependencies,
+        latency_prober: MockLatencyProber,
+        ip: IpAddr,
+        port: u16,
+        log_level: LogLevel,
+    ) -> Client {
+        let (guest_sender, _) = dom::communication::guest_channel();
+
+        create_client(
+            config,
+            shared_state,
+            mocked_server_control,
+            dependencies,
+            guest_sender,
+            dom::communication::change_timeout_reset_channel().0,
+            dom::communication::device_events(),
+            Arc::new(Readiness::new(&config.network.interface)),
+            Arc::new(MockPacketCapture::new()),
+            Arc::new(latency_prober),
+            None,
+            ip,
+            port,
+            log_level,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn get_client_with_device_events(
+        config: &Configuration,
+        shared_state: Arc<SharedStateMutex>,
+        mocked_server_control: MockServerControl,
+        dependencies: Dependencies,
+        device_events: DeviceEvents,
+        ip: IpAddr,
+        port: u16,
+        log_level: LogLevel,
+    ) -> Client {
+        let (guest_sender, _) = dom::communication::guest_channel();
+
+        create_client(
+            config,
+            shared_state,
+            mocked_server_control,
+            dependencies,
+            guest_sender,
+            dom::communication::change_timeout_reset_channel().0,
+            device_events,
+            Arc::new(Readiness::new(&config.network.interface)),
+            Arc::new(MockPacketCapture::new()),
+            Arc::new(MockLatencyProber::new()),
+            None,
+            ip,
+            port,
+            log_level,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn get_client_with_store(
+        config: &Configuration,
+        shared_state: Arc<SharedStateMutex>,
+        mocked_server_control: MockServerControl,
+        dependencies: Dependencies,
+        store: Arc<DeviceStore>,
+        ip: IpAddr,
+        port: u16,
+        log_level: LogLevel,
+    ) -> Client {
+        let (guest_sender, _) = dom::communication::guest_channel();
+
+        create_client(
+            config,
+            shared_state,
+            mocked_server_control,
+            dependencies,
+            guest_sender,
+            dom::communication::change_timeout_reset_channel().0,
+            dom::communication::device_events(),
+            Arc::new(Readiness::new(&config.network.interface)),
+            Arc::new(MockPacketCapture::new()),
+            Arc::new(MockLatencyProber::new()),
+            Some(store),
+            ip,
+            port,
+            log_level,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn create_client(
+        config: &Configuration,
+        shared_state: Arc<SharedStateMutex>,
+        mocked_server_control: MockServerControl,
+        dependencies: Dependencies,
+        guest_sender: GuestSender,
+        change_timeout_resets: ChangeTimeoutResetSender,
+        device_events: DeviceEvents,
+        readiness: Arc<Readiness>,
+        packet_capture: Arc<dyn PacketCapture>,
+        latency_prober: Arc<dyn LatencyProber>,
+        store: Option<Arc<DeviceStore>>,
+        ip: IpAddr,
+        port: u16,
+        log_level: LogLevel,
+    ) -> Client {
+        let group_controls = mocked_server_control.groups.clone();
         let server = Server::new(
             PKG_NAME,
             PKG_VERSION,
             config.clone(),
+            PathBuf::from(configuration::LOCATION),
             shared_state,
             vec![ServerControl::from(mocked_server_control)],
+            group_controls,
             dependencies,
+            guest_sender,
+            change_timeout_resets,
+            device_events,
+            crate::events::events(),
+            Arc::new(Registry::new()),
+            crate::updates::update_status(PKG_VERSION),
+            readiness,
+            Arc::new(SyncStats::new()),
+            Arc::new(MonitorStats::new()),
+            Arc::new(RuntimeStats::new(1)),
+            Arc::new(IgnoreWindows::new(HashMap::new())),
+            Arc::new(Profiles::new(
+                Profile::default(),
+                config
+                    .profiles
+                    .keys()
+                    .map(|name| (name.clone(), Profile::default()))
+                    .collect(),
+            )),
+            HashMap::new(),
+            Arc::new(Away::new(config.away)),
+            Arc::new(LogBuffer::new(LOG_BUFFER_CAPACITY)),
+            packet_capture,
+            latency_prober,
+            store,
             ip,
             port,
             log_level,
@@ -253,4 +642,107 @@ pub mod test {
     pub fn get_api_endpoint(endpoint: &str) -> String {
         format!("/api/v1{endpoint}")
     }
+
+    #[rstest]
+    fn test_web_api_can_be_hosted_under_base_path(
+        mut config: Configuration,
+        shared_state: Arc<SharedStateMutex>,
+        mocked_server_control: MockServerControl,
+        dependencies: Dependencies,
+        ip: IpAddr,
+        port: u16,
+        log_level: LogLevel,
+    ) {
+        // SETUP
+        config.api.web.base_path = "/home-monitor/".to_string();
+
+        let client = get_client(
+            &config,
+            shared_state,
+            mocked_server_control,
+            dependencies,
+            ip,
+            port,
+            log_level,
+        );
+
+        // TESTING
+        let response = client.get("/home-monitor/api/v1/status").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+
+        let response = client.get(get_api_endpoint("/status")).dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+
+        let response = client
+            .get("/home-monitor/docs/swagger/index.html")
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+
+        let response = client.get("/home-monitor/api/v1/openapi.json").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        let spec: serde_json::Value = response.into_json().unwrap();
+        assert_eq!(spec["servers"][0]["url"], "/home-monitor/api/v1");
+    }
+
+    #[rstest]
+    #[case(None, Status::Ok)]
+    #[case(Some("Basic YWRtaW46c2VjcmV0"), Status::Ok)]
+    #[case(Some("Basic YWRtaW46d3Jvbmc="), Status::Unauthorized)]
+    fn test_web_api_can_disable_or_protect_docs(
+        mut config: Configuration,
+        shared_state: Arc<SharedStateMutex>,
+        dependencies: Dependencies,
+        ip: IpAddr,
+        port: u16,
+        log_level: LogLevel,
+        #[case] authorization: Option<&'static str>,
+        #[case] expected_status: Status,
+    ) {
+        let client = |config: &Configuration| {
+            get_client(
+                config,
+                shared_state.clone(),
+                mocked_server_control(server()),
+                dependencies.clone(),
+                ip,
+                port,
+                log_level,
+            )
+        };
+        let get = |client: &Client, uri: &'static str| {
+            let mut request = client.get(uri);
+            if let Some(authorization) = authorization {
+                request = request.header(Header::new("Authorization", authorization));
+            }
+            request.dispatch().status()
+        };
+
+        // documentation without authentication
+        assert_eq!(
+            get(&client(&config), "/docs/swagger/index.html"),
+            Status::Ok
+        );
+
+        // documentation protected by basic authentication (admin:secret)
+        config.api.web.docs.auth = Some(configuration::BasicAuth {
+            username: "admin".to_string(),
+            password: "secret".into(),
+        });
+        let protected = client(&config);
+        let expected_status = match authorization {
+            None => Status::Unauthorized,
+            Some(_) => expected_status,
+        };
+        assert_eq!(get(&protected, "/docs/swagger/index.html"), expected_status);
+        assert_eq!(get(&protected, "/docs/rapidoc/index.html"), expected_status);
+        // the API itself isn't affected
+        assert_eq!(get(&protected, "/api/v1/status"), Status::Ok);
+
+        // disabled documentation
+        config.api.web.docs.enabled = false;
+        assert_eq!(
+            get(&client(&config), "/docs/swagger/index.html"),
+            Status::NotFound
+        );
+    }
 }
@@ -2,36 +2,59 @@ use std::sync::Arc;
 
 use log::debug;
 
-use crate::dom::communication::{MpscReceiver, SharedStateMutex};
+use crate::dom::communication::{
+    DeviceEvents, DeviceUpdate, MpscReceiver, SharedStateMutex, SyncStats,
+};
 
 pub struct SharedStateSync {
     shared_state: Arc<SharedStateMutex>,
     receiver: MpscReceiver,
+    events: DeviceEvents,
+    stats: Arc<SyncStats>,
 }
 
 impl SharedStateSync {
-    pub fn new(shared_state: Arc<SharedStateMutex>, receiver: MpscReceiver) -> Self {
+    pub fn new(
+        shared_state: Arc<SharedStateMutex>,
+        receiver: MpscReceiver,
+        events: DeviceEvents,
+        stats: Arc<SyncStats>,
+    ) -> Self {
         Self {
             shared_state,
             receiver,
+            events,
+            stats,
         }
     }
 
     pub async fn sync(&mut self) {
         loop {
-            match self.receiver.recv().await {
-                Some(updated_device) => {
+            let update = match self.receiver.recv().await {
+                Some(update) => update,
+                None => {
+                    debug!("stopping shared state sync because all senders were dropped");
+                    break;
+                }
+            };
+
+            match &update {
+                DeviceUpdate::Changed(updated_device) => {
                     debug!("updating {} in shared state", updated_device);
                     self.shared_state
                         .lock()
                         .unwrap()
                         .update_device(updated_device);
                 }
-                None => {
-                    debug!("stopping shared state sync because all senders were dropped");
-                    break;
+                DeviceUpdate::Removed(device_id) => {
+                    debug!("removing {} from shared state", device_id);
+                    self.shared_state.lock().unwrap().remove_device(device_id);
                 }
             }
+
+            // it's fine if nobody is interested in the update
+            let _ = self.events.send(update);
+            self.stats.synced(self.events.len());
         }
     }
 }